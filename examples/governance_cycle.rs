@@ -0,0 +1,162 @@
+//! Example: one governance cycle from idea to funded grant, on a simulated clock.
+//!
+//! Members commit and reveal their votes on a treasury proposal, the executed
+//! withdrawal becomes a grant paid out in monthly instalments, every grant
+//! transition lands in the hash-chained audit trail, and the cycle ends with
+//! the decision record and the health score.
+
+use fsm_governance_engine_lib::enums::CapabilityType;
+use fsm_governance_engine_lib::governance::GovernanceVotingType;
+use fsm_governance_engine_lib::grant::{BUCKET_SECONDS, unlock_schedule};
+use fsm_governance_engine_lib::health::{HealthInputs, score};
+use fsm_governance_engine_lib::links::InMemoryLinks;
+use fsm_governance_engine_lib::orchestration::vote_commitment;
+use fsm_governance_engine_lib::prelude::*;
+use fsm_governance_engine_lib::proposal::{TreasuryOperationData, TreasuryProposalType};
+use fsm_governance_engine_lib::reporting::decision_record;
+use fsm_governance_engine_lib::{AuditActor, GovernanceRound, RoundConfig, TickOutcome};
+
+const IDEA_ID: u64 = 11;
+const TREASURER: AuditActor = [0xAA; 32];
+
+fn main() -> Result<(), FsmError> {
+    let mut clock = SimClock::new(1_700_000_000)?;
+    let mut links = InMemoryLinks::new();
+
+    // Five voting members; member 1 also writes proposals
+    let members: Vec<Member<u8>> = (1..=5)
+        .map(|id| {
+            let mut capabilities = vec![CapabilityType::Vote];
+            if id == 1 {
+                capabilities.push(CapabilityType::Propose);
+            }
+            Member::new(id, capabilities)
+        })
+        .collect();
+    let author = members
+        .iter()
+        .find(|m| m.has_capability(CapabilityType::Propose))
+        .ok_or(FsmError::InsufficientMembers)?;
+
+    // The idea is reviewed and approved before it is promoted
+    let mut idea = IdeaStatus::Draft;
+    for next in [IdeaStatus::UnderReview, IdeaStatus::Approved] {
+        idea.validate_transition(next)?;
+        idea = next;
+    }
+    links.add_idea(IDEA_ID);
+    println!("idea {IDEA_ID}: {idea:?}");
+
+    let mut proposal = ProposalBuilder::new(
+        1,
+        "Fund the relay network".to_string(),
+        "Three months of relay hosting".to_string(),
+        "treasury".to_string(),
+        author.id,
+    )
+    .treasury_operation(TreasuryOperationData::new(
+        TreasuryProposalType::Withdrawal,
+        Some(900),
+        None,
+        None,
+        None,
+        None,
+        "Relay hosting".to_string(),
+    ))
+    .build(clock.now())?;
+    proposal.set_idea_link(&links, IDEA_ID)?;
+
+    // Voting: 24 hours, sealed for the first 12, quorum 60%
+    let params = GovernanceParams::new(60, 24, 0, false, clock.now())?;
+    let config = RoundConfig {
+        quorum_id: 1,
+        voting_id: 1,
+        voting_type: GovernanceVotingType::SimpleMajority,
+        voting_data_hash: [0; 32],
+        total_members: members.len() as u64,
+        min_quorum: 3,
+    };
+    let mut round = GovernanceRound::open(proposal, &params, config, clock.now())?;
+    let reveal_from = clock.now() + 12 * 3600;
+    round.require_commit_reveal(reveal_from)?;
+
+    let ballots: Vec<(u8, bool, u64, [u8; 32])> = members
+        .iter()
+        .filter(|m| m.is_in_good_standing() && m.id != 5)
+        .map(|m| (m.id, m.id != 4, 1, [m.id; 32]))
+        .collect();
+    for (voter, support, weight, salt) in &ballots {
+        let commitment = vote_commitment(1, voter, *support, *weight, salt)?;
+        round.commit_vote(
+            *voter,
+            commitment,
+            clock.advance(DurationSecs::from_secs(60))?,
+        )?;
+    }
+    clock.advance_to(reveal_from)?;
+    for (voter, support, weight, salt) in &ballots {
+        round.reveal_vote(*voter, *support, *weight, salt, clock.now())?;
+    }
+    println!(
+        "revealed {} votes, {} pending",
+        ballots.len(),
+        round.pending_reveals()
+    );
+
+    clock.advance_to(round.voting_ends_at()?)?;
+    if let TickOutcome::Closed { status, .. } = round.tick(clock.now())? {
+        println!("voting closed: {status:?}");
+    }
+    let finalized = round.finalize()?;
+    let mut proposal = finalized.proposal;
+    proposal.execute_with_time(clock.advance(DurationSecs::from_secs(3600))?)?;
+    links.add_proposal(proposal.id, proposal.status.clone());
+
+    // The executed withdrawal funds a grant paid in three monthly instalments
+    let mut grant = Grant::from_treasury_proposal(
+        1,
+        &proposal,
+        &links,
+        [1u8; 32],
+        GrantCategory::Development,
+        GrantType::Core,
+        GrantDisbursementType::Standard,
+        clock.now(),
+    )?;
+    let start = clock.now();
+    for month in 0..3 {
+        grant.schedule_payout(start + month * BUCKET_SECONDS, 300)?;
+    }
+    let mut trail = AuditTrail::new();
+    trail.record_grant(&mut grant, TREASURER, "approve", start, |g| g.approve())?;
+    trail.record_grant(&mut grant, TREASURER, "activate", start, |g| g.activate())?;
+    for month in unlock_schedule(std::slice::from_ref(&grant), start, 3) {
+        clock.advance_to(month.starts_at)?;
+        let amount = month.amount;
+        trail.record_grant(&mut grant, TREASURER, "disburse", clock.now(), |g| {
+            g.disburse(amount)
+        })?;
+        println!(
+            "month from {}: paid {amount}, grant {:?}",
+            month.starts_at, grant.status
+        );
+    }
+    trail.verify()?;
+    println!("audit chain head: {:02x?}", &trail.chain_head()[..8]);
+
+    let record = decision_record(&proposal, None, Some(&finalized.quorum), &trail)?;
+    println!("{}", record.render_markdown());
+
+    let mut inputs = HealthInputs::default();
+    inputs.add_proposal(&proposal);
+    inputs.add_grant(&grant, clock.now());
+    inputs.participation_bps = u64::from(finalized.quorum.current_percentage) * 100;
+    inputs.active_policies = 1;
+    inputs.total_policies = 1;
+    let health = score(inputs);
+    println!(
+        "health score: {} (drags: {:?})",
+        health.overall, health.top_drags
+    );
+    Ok(())
+}
@@ -1,5 +1,7 @@
 //! Example: governance proposal lifecycle with audit JSONL output.
 
+use fsm_governance_engine_lib::DurationSecs;
+use fsm_governance_engine_lib::proposal::treasury::{TreasuryOperationData, TreasuryProposalType};
 use fsm_governance_engine_lib::proposal::types::{Proposal, ProposalStatus};
 use serde::Serialize;
 
@@ -13,18 +15,26 @@ struct AuditEntry {
 }
 
 fn main() {
-    let mut proposal = Proposal::new_with_time(
+    let mut proposal = Proposal::builder(
         1,
         "Budget Allocation".to_string(),
         "Allocate funds for infra".to_string(),
         "treasury".to_string(),
         42u64,
-        0,
     )
+    .voting_duration(DurationSecs::from_secs(1))
+    .treasury_operation(TreasuryOperationData::new(
+        TreasuryProposalType::Withdrawal,
+        Some(10_000),
+        None,
+        None,
+        None,
+        None,
+        "Infra budget".to_string(),
+    ))
+    .build(0)
     .expect("proposal create");
 
-    proposal.voting_duration = 1;
-
     let mut audit = Vec::new();
 
     audit.push(AuditEntry {
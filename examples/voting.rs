@@ -12,6 +12,7 @@ fn main() {
         status: GovernanceVotingStatus::Open,
         created_at: 0,
         voting_data_hash: [0u8; 32],
+        voting_ends_at: None,
     };
 
     let data_hash = [9u8; 32];
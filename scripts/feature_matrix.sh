@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+# Check every target with no features, each feature alone, and the defaults.
+set -euo pipefail
+
+features=(serde borsh hash definitions governance-meta compat-v1 scxml tracing)
+
+cargo clippy -p fsm_governance_engine_lib --all-targets --no-default-features -- -D warnings
+for feature in "${features[@]}"; do
+  cargo clippy -p fsm_governance_engine_lib --all-targets --no-default-features \
+    --features "$feature" -- -D warnings
+done
+cargo clippy -p fsm_governance_engine_lib --all-targets -- -D warnings
+
+cargo test -p fsm_governance_engine_lib --no-default-features
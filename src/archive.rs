@@ -0,0 +1,201 @@
+//! Incremental archive sweep over large proposal sets.
+//!
+//! `sweep` archives expired proposals a batch at a time so a scheduler can
+//! spread the work across runs. Proposals are visited in id order starting
+//! after the `SweepCursor`; each run handles at most `budget` of them and
+//! returns the cursor to persist for the next run.
+//!
+//! A sweep only applies `Proposal::check_and_auto_archive`, which does nothing
+//! to a proposal that is already Archived or not yet due. Re-running from an
+//! older cursor after a crash therefore revisits proposals without applying
+//! anything twice.
+
+use crate::error::FsmError;
+use crate::proposal::Proposal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Where the next `sweep` resumes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SweepCursor {
+    /// Last id visited; `None` starts from the lowest id
+    pub after_id: Option<u64>,
+}
+
+impl SweepCursor {
+    /// Cursor before the first proposal
+    pub fn start() -> Self {
+        Self::default()
+    }
+}
+
+/// What one `sweep` run did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SweepReport {
+    /// Proposals visited, at most the budget
+    pub visited: usize,
+    /// Proposals archived by this run
+    pub archived: usize,
+    /// Proposals visited and left unchanged (not expired, not finished or already archived)
+    pub unchanged: usize,
+    /// Proposals whose archive step failed, ids ascending; the sweep moves past them
+    pub failed: Vec<(u64, FsmError)>,
+    /// No proposal remains after the returned cursor
+    pub complete: bool,
+}
+
+/// Archive expired proposals after `cursor`, at most `budget` of them (see the module docs)
+///
+/// Proposals need not be sorted; they are visited by ascending id. A failed
+/// proposal is reported and skipped, so one bad record does not stall the
+/// sweep. With a budget of 0 the cursor is returned unchanged.
+pub fn sweep<P>(
+    proposals: &mut [Proposal<P>],
+    cursor: SweepCursor,
+    budget: usize,
+    current_time: i64,
+) -> (SweepCursor, SweepReport) {
+    let mut pending: Vec<usize> = (0..proposals.len())
+        .filter(|&i| cursor.after_id.is_none_or(|after| proposals[i].id > after))
+        .collect();
+    pending.sort_by_key(|&i| proposals[i].id);
+
+    let mut report = SweepReport {
+        complete: pending.len() <= budget,
+        ..SweepReport::default()
+    };
+    let mut next = cursor;
+    for &index in pending.iter().take(budget) {
+        let proposal = &mut proposals[index];
+        report.visited += 1;
+        match proposal.check_and_auto_archive(current_time) {
+            Ok(true) => report.archived += 1,
+            Ok(false) => report.unchanged += 1,
+            Err(error) => report.failed.push((proposal.id, error)),
+        }
+        next.after_id = Some(proposal.id);
+    }
+    (next, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::ProposalStatus;
+
+    fn create_test_proposal(id: u64, expires_at: Option<i64>) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(1000)
+        .unwrap();
+        proposal.set_expiration(expires_at).unwrap();
+        proposal
+            .cancel_with_time("Withdrawn".to_string(), 1000)
+            .unwrap();
+        proposal
+    }
+
+    fn create_test_proposals() -> Vec<Proposal<u8>> {
+        // Out of id order; 4 has not expired, 2 stays Draft
+        let mut proposals = vec![
+            create_test_proposal(5, Some(2000)),
+            create_test_proposal(1, Some(2000)),
+            create_test_proposal(4, Some(9000)),
+            create_test_proposal(3, Some(2000)),
+            create_test_proposal(2, Some(2000)),
+        ];
+        proposals[4] = Proposal::builder(
+            2,
+            "Draft".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(1000)
+        .unwrap();
+        proposals
+    }
+
+    fn archived_ids(proposals: &[Proposal<u8>]) -> Vec<u64> {
+        let mut ids: Vec<u64> = proposals
+            .iter()
+            .filter(|p| p.status == ProposalStatus::Archived)
+            .map(|p| p.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_sweep_in_batches() {
+        let mut proposals = create_test_proposals();
+        let (cursor, report) = sweep(&mut proposals, SweepCursor::start(), 2, 3000);
+        assert_eq!(cursor.after_id, Some(2));
+        assert_eq!(
+            (report.visited, report.archived, report.unchanged),
+            (2, 1, 1)
+        );
+        assert!(!report.complete);
+        assert_eq!(archived_ids(&proposals), vec![1]);
+
+        let (cursor, report) = sweep(&mut proposals, cursor, 2, 3000);
+        assert_eq!(cursor.after_id, Some(4));
+        assert_eq!((report.archived, report.unchanged), (1, 1));
+        assert!(!report.complete);
+
+        let (cursor, report) = sweep(&mut proposals, cursor, 2, 3000);
+        assert_eq!(cursor.after_id, Some(5));
+        assert_eq!(report.visited, 1);
+        assert!(report.complete);
+        assert_eq!(archived_ids(&proposals), vec![1, 3, 5]);
+
+        let (last, report) = sweep(&mut proposals, cursor, 2, 3000);
+        assert_eq!(last, cursor);
+        assert_eq!(report.visited, 0);
+        assert!(report.complete);
+    }
+
+    #[test]
+    fn test_rerun_from_old_cursor_applies_nothing_twice() {
+        let mut proposals = create_test_proposals();
+        let (cursor, _) = sweep(&mut proposals, SweepCursor::start(), 3, 3000);
+        let after_first = proposals.clone();
+        // Crash before the cursor was persisted: the same batch runs again
+        let (again, report) = sweep(&mut proposals, SweepCursor::start(), 3, 3000);
+        assert_eq!(again, cursor);
+        assert_eq!((report.archived, report.unchanged), (0, 3));
+        assert_eq!(proposals, after_first);
+    }
+
+    #[test]
+    fn test_failures_are_reported_and_skipped() {
+        let mut proposals = create_test_proposals();
+        let (cursor, report) = sweep(&mut proposals, SweepCursor::start(), 0, 3000);
+        assert_eq!(cursor, SweepCursor::start());
+        assert!(!report.complete);
+
+        // Earlier than the cancellation: archiving fails with TimeRegression
+        proposals[1].expires_at = Some(500);
+        let (cursor, report) = sweep(&mut proposals, SweepCursor::start(), 1, 900);
+        assert_eq!(report.failed, vec![(1, FsmError::TimeRegression)]);
+        assert_eq!(cursor.after_id, Some(1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cursor_serde_roundtrip() {
+        let cursor = SweepCursor { after_id: Some(42) };
+        let json = serde_json::to_string(&cursor).unwrap();
+        assert_eq!(json, r#"{"after_id":42}"#);
+        assert_eq!(serde_json::from_str::<SweepCursor>(&json).unwrap(), cursor);
+    }
+}
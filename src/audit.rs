@@ -1,14 +1,45 @@
 //! Audit trail helpers for FSM transitions.
 //!
 //! Records every state change for grants and allows verification of the sequence.
+//! Entries, redaction and purge records are hash-chained in sequence order: each link
+//! covers the previous chain hash and the record's content, so edits made
+//! outside `record` and `redact` fail `verify`. An entry's content hash binds
+//! its structural fields to a digest of its free text, which is all redaction
+//! keeps of that text. `record`, `redact` and `record_purge` stamp each record with the next
+//! number of the trail's `SequenceGenerator`, which an `EventBuffer` may share;
+//! `verify` checks they strictly increase (see `sequence` for why gaps are allowed).
+//!
+//! `AuditTrail::subscribe` registers `AuditSubscriber`s that `record` notifies
+//! synchronously after each append, in subscription order. A subscriber that
+//! returns an error or panics cannot undo the append: the failure is kept in
+//! `subscriber_errors()` and the remaining subscribers are still notified.
 
+use crate::enums::EntityKind;
 use crate::error::FsmError;
+use crate::grant::Grant;
 use crate::grant::types::GrantStatus;
+use crate::retention::retention_expired;
+use crate::sequence::{SequenceGenerator, check_increasing};
+#[cfg(feature = "borsh")]
 use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+
+/// Identifier of whoever acted on the trail
+pub type AuditActor = [u8; 32];
+
+/// Replaces free-text fields of redacted entries
+pub const REDACTION_MARKER: &str = "[redacted]";
 
 /// Immutable audit entry representing one transition.
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AuditEntry {
     pub grant_id: u64,
     pub actor: [u8; 32],
@@ -17,6 +48,21 @@ pub struct AuditEntry {
     pub action: String,
     pub timestamp: i64,
     pub metadata: Option<String>,
+    /// `text_hash()` from before redaction; keeps the hash chain intact
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub redacted_text_hash: Option<[u8; 32]>,
+    /// `timestamp` was clamped to absorb clock skew (see `time_guard`)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub clamped: bool,
+    /// Assigned by `AuditTrail::record`; 0 until recorded
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sequence: u64,
 }
 
 impl AuditEntry {
@@ -38,34 +84,382 @@ impl AuditEntry {
             action: action.to_string(),
             timestamp,
             metadata,
+            redacted_text_hash: None,
+            clamped: false,
+            sequence: 0,
         }
     }
+
+    /// Whether `AuditTrail::redact` removed this entry's free text
+    pub fn is_redacted(&self) -> bool {
+        self.redacted_text_hash.is_some()
+    }
+
+    /// SHA-256 of the free text (`action`, `metadata`), or of the original text once redacted
+    pub fn text_hash(&self) -> [u8; 32] {
+        if let Some(original) = self.redacted_text_hash {
+            return original;
+        }
+        // Serializing plain fields into a Vec cannot fail
+        let bytes = borsh::to_vec(&(&self.action, &self.metadata)).unwrap_or_default();
+        Sha256::digest(bytes).into()
+    }
+
+    /// SHA-256 of the structural fields and `text_hash()`
+    ///
+    /// Redaction leaves it unchanged, and it still covers the grant, states,
+    /// timestamp and sequence of a redacted entry.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let content = (
+            self.grant_id,
+            self.actor,
+            self.from_state,
+            self.to_state,
+            self.timestamp,
+            self.clamped,
+            self.sequence,
+            self.text_hash(),
+        );
+        let bytes = borsh::to_vec(&content).unwrap_or_default();
+        Sha256::digest(bytes).into()
+    }
+}
+
+/// Kind of record a chain link covers, hashed into the link
+#[derive(Clone, Copy)]
+enum LinkKind {
+    Entry = 0,
+    Redaction = 1,
+    Purge = 2,
+}
+
+fn chain_hash(previous: &[u8; 32], kind: LinkKind, content_hash: [u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(previous);
+    hasher.update([kind as u8]);
+    hasher.update(content_hash);
+    hasher.finalize().into()
+}
+
+/// Record of an entry redacted by `AuditTrail::redact`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RedactionEntry {
+    /// Position of the redacted entry in `entries()`
+    pub index: usize,
+    pub redactor: AuditActor,
+    pub redacted_at: i64,
+    /// Assigned by `AuditTrail::redact`, from the same generator as entries
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sequence: u64,
+}
+
+impl RedactionEntry {
+    /// SHA-256 of the record, as linked into the trail's hash chain
+    pub fn record_hash(&self) -> [u8; 32] {
+        let content = (
+            self.index as u64,
+            self.redactor,
+            self.redacted_at,
+            self.sequence,
+        );
+        let bytes = borsh::to_vec(&content).unwrap_or_default();
+        Sha256::digest(bytes).into()
+    }
+}
+
+/// Audit record of a retention purge (see `retention::purge`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PurgeEntry {
+    pub entity_kind: EntityKind,
+    pub entity_id: u64,
+    /// SHA-256 of the entity's JSON form, captured before removal
+    pub content_hash: [u8; 32],
+    pub purged_at: i64,
+    /// When the entity was archived; the retention window starts here
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub archived_at: i64,
+    /// Retention period the purge was checked against
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub retention_seconds: i64,
+    /// Assigned by `AuditTrail::record_purge`, from the same generator as entries
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sequence: u64,
+}
+
+impl PurgeEntry {
+    /// SHA-256 of the record, as linked into the trail's hash chain
+    pub fn record_hash(&self) -> [u8; 32] {
+        let content = (
+            self.entity_kind,
+            self.entity_id,
+            self.content_hash,
+            self.purged_at,
+            self.archived_at,
+            self.retention_seconds,
+            self.sequence,
+        );
+        let bytes = borsh::to_vec(&content).unwrap_or_default();
+        Sha256::digest(bytes).into()
+    }
+}
+
+/// Entry recorded with an earlier timestamp than the entry before it for the same entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChronologyViolation {
+    pub entity_kind: EntityKind,
+    pub entity_id: u64,
+    /// Position of the offending entry in `entries()`
+    pub index: usize,
+    pub timestamp: i64,
+    /// Timestamp of the previous entry for the same entity
+    pub previous_timestamp: i64,
+}
+
+/// Receives each entry appended to an `AuditTrail` it subscribed to
+pub trait AuditSubscriber {
+    /// Called after `entry` was appended; an error is recorded, not propagated
+    fn on_entry(&mut self, entry: &AuditEntry) -> Result<(), String>;
+}
+
+/// Forwards entries to an `mpsc` channel, for consumers on another thread or task
+#[derive(Clone, Debug)]
+pub struct ChannelSubscriber {
+    sender: mpsc::Sender<AuditEntry>,
+}
+
+impl ChannelSubscriber {
+    /// Subscriber and the receiving end of its channel
+    pub fn new() -> (Self, mpsc::Receiver<AuditEntry>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self { sender }, receiver)
+    }
+}
+
+impl AuditSubscriber for ChannelSubscriber {
+    fn on_entry(&mut self, entry: &AuditEntry) -> Result<(), String> {
+        self.sender
+            .send(entry.clone())
+            .map_err(|_| "receiver dropped".to_string())
+    }
+}
+
+/// A subscriber that failed on an appended entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SubscriberError {
+    /// Position of the subscriber in subscription order
+    pub subscriber: usize,
+    /// Position of the entry in `entries()`
+    pub entry_index: usize,
+    /// The returned error, or the panic message
+    pub message: String,
+}
+
+/// Subscribers are not cloned: a cloned trail starts without any
+#[derive(Default)]
+struct Subscribers(Vec<Box<dyn AuditSubscriber>>);
+
+impl Clone for Subscribers {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for Subscribers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} subscribers", self.0.len())
+    }
 }
 
 /// In-memory audit trail for FSM transitions.
 #[derive(Default, Clone, Debug)]
 pub struct AuditTrail {
     entries: Vec<AuditEntry>,
+    /// Chain hash after each entry, redaction or purge record, in sequence order
+    chain: Vec<[u8; 32]>,
+    purges: Vec<PurgeEntry>,
+    redactions: Vec<RedactionEntry>,
+    subscribers: Subscribers,
+    subscriber_errors: Vec<SubscriberError>,
+    sequence: SequenceGenerator,
 }
 
 impl AuditTrail {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            chain: Vec::new(),
+            purges: Vec::new(),
+            redactions: Vec::new(),
+            subscribers: Subscribers::default(),
+            subscriber_errors: Vec::new(),
+            sequence: SequenceGenerator::new(),
+        }
+    }
+
+    /// Rebuild a trail persisted as its records and `chain_head()`
+    ///
+    /// Relinks entries, redaction and purge records in sequence order, then
+    /// checks the result with `verify` and against `chain_head`: a record
+    /// edited, dropped or added since the trail was saved fails, with
+    /// `InvalidState` unless `verify` reports otherwise. New records are
+    /// numbered above the highest saved sequence; `with_sequence` can swap in a
+    /// generator shared with other logs, which must not be behind it.
+    pub fn from_records(
+        entries: Vec<AuditEntry>,
+        redactions: Vec<RedactionEntry>,
+        purges: Vec<PurgeEntry>,
+        chain_head: [u8; 32],
+    ) -> Result<Self, FsmError> {
+        let mut trail = Self {
+            entries,
+            redactions,
+            purges,
+            ..Self::new()
+        };
+        let mut previous = [0u8; 32];
+        for (kind, content_hash) in trail.links()? {
+            previous = chain_hash(&previous, kind, content_hash);
+            trail.chain.push(previous);
+        }
+        trail.verify()?;
+        if trail.chain_head() != chain_head {
+            return Err(FsmError::InvalidState);
+        }
+        let max_seen = trail
+            .entries
+            .iter()
+            .map(|entry| entry.sequence)
+            .chain(trail.redactions.iter().map(|redaction| redaction.sequence))
+            .chain(trail.purges.iter().map(|purge| purge.sequence))
+            .max()
+            .unwrap_or(0);
+        trail.sequence = SequenceGenerator::resume_from(max_seen);
+        Ok(trail)
+    }
+
+    /// Number entries from `sequence`, e.g. `SequenceGenerator::resume_from` after a restart
+    ///
+    /// Pass clones of one generator to logs that need a single order across them.
+    pub fn with_sequence(mut self, sequence: SequenceGenerator) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Handle on the generator numbering the entries; persist it with the entries
+    pub fn sequence_generator(&self) -> SequenceGenerator {
+        self.sequence.clone()
+    }
+
+    /// Notify `subscriber` of every entry appended from now on; returns its position
+    pub fn subscribe(&mut self, subscriber: Box<dyn AuditSubscriber>) -> usize {
+        self.subscribers.0.push(subscriber);
+        self.subscribers.0.len() - 1
+    }
+
+    /// Failures of subscribers, in the order they happened
+    pub fn subscriber_errors(&self) -> &[SubscriberError] {
+        &self.subscriber_errors
+    }
+
+    fn notify(&mut self) {
+        let entry_index = self.entries.len() - 1;
+        let entry = &self.entries[entry_index];
+        for (subscriber, sink) in self.subscribers.0.iter_mut().enumerate() {
+            let message = match panic::catch_unwind(AssertUnwindSafe(|| sink.on_entry(entry))) {
+                Ok(Ok(())) => continue,
+                Ok(Err(message)) => message,
+                Err(payload) => payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "subscriber panicked".to_string()),
+            };
+            self.subscriber_errors.push(SubscriberError {
+                subscriber,
+                entry_index,
+                message,
+            });
         }
     }
 
     /// Append an audit entry after verifying the transition is permitted.
-    pub fn record(&mut self, entry: AuditEntry) -> Result<(), FsmError> {
+    ///
+    /// The entry's `sequence` is replaced by the trail's next one; `Overflow`
+    /// once the generator is exhausted. Subscribers are notified once the
+    /// entry is in place (see the module docs).
+    pub fn record(&mut self, mut entry: AuditEntry) -> Result<(), FsmError> {
         entry
             .from_state
             .validate_transition(entry.to_state)
             .map_err(|_| FsmError::InvalidStateTransition)?;
-        self.entries.push(entry);
+        entry.sequence = self.sequence.issue()?;
+        self.append(entry);
         Ok(())
     }
 
+    /// Append an entry already checked and stamped with a sequence issued by this trail's generator
+    pub(crate) fn append(&mut self, entry: AuditEntry) {
+        let hash = chain_hash(&self.chain_head(), LinkKind::Entry, entry.content_hash());
+        self.entries.push(entry);
+        self.chain.push(hash);
+        self.notify();
+    }
+
+    /// Run `op` on `grant` and record its status change, if any, as `action` by `actor`.
+    ///
+    /// `op` works on a copy that replaces `grant` only once the entry is
+    /// recorded, so a failed `op` or a refused entry leaves both untouched.
+    /// A `timestamp` behind the grant's `last_event_at` but within its clock
+    /// skew tolerance is recorded at the clamped time with `clamped` set, as
+    /// the grant's timed transitions record it.
+    pub fn record_grant<T>(
+        &mut self,
+        grant: &mut Grant,
+        actor: AuditActor,
+        action: &'static str,
+        timestamp: i64,
+        op: impl FnOnce(&mut Grant) -> Result<T, FsmError>,
+    ) -> Result<T, FsmError> {
+        let event_time = grant.event_time(timestamp).ok();
+        let mut next = grant.clone();
+        let output = op(&mut next)?;
+        if next.status != grant.status {
+            let mut entry = AuditEntry::new(
+                grant.id,
+                actor,
+                grant.status,
+                next.status,
+                action,
+                timestamp,
+                None,
+            );
+            if let Some(at) = event_time {
+                entry.timestamp = at.time;
+                entry.clamped = at.clamped;
+            }
+            self.record(entry)?;
+        }
+        *grant = next;
+        Ok(output)
+    }
+
+    /// Chain hash after the last entry, redaction or purge record; zeros for an empty trail.
+    pub fn chain_head(&self) -> [u8; 32] {
+        self.chain.last().copied().unwrap_or([0u8; 32])
+    }
+
     /// Verify that history only contains valid transitions and is monotonically increasing.
+    ///
+    /// Then checks the redaction records against the entries they redacted,
+    /// that sequences strictly increase, and recomputes the hash chain over
+    /// entries, redaction and purge records (`InvalidState` on any mismatch).
+    /// Redacted entries keep their content hash, so redaction does not break it.
     pub fn verify(&self) -> Result<(), FsmError> {
         for window in self.entries.windows(2) {
             let first = &window[0];
@@ -77,6 +471,79 @@ impl AuditTrail {
                 return Err(FsmError::InvalidStateTransition);
             }
         }
+        check_increasing(self.entries.iter().map(|entry| entry.sequence))?;
+        self.verify_redactions()?;
+        check_increasing(self.purges.iter().map(|purge| purge.sequence))?;
+        let links = self.links()?;
+        if self.chain.len() != links.len() {
+            return Err(FsmError::InvalidState);
+        }
+        let mut previous = [0u8; 32];
+        for ((kind, content_hash), stored) in links.into_iter().zip(&self.chain) {
+            previous = chain_hash(&previous, kind, content_hash);
+            if previous != *stored {
+                return Err(FsmError::InvalidState);
+            }
+        }
+        Ok(())
+    }
+
+    /// Kind and hash of every entry, redaction and purge record, in sequence order
+    ///
+    /// `InvalidState` if two records share a sequence.
+    fn links(&self) -> Result<Vec<(LinkKind, [u8; 32])>, FsmError> {
+        let mut links: Vec<(u64, LinkKind, [u8; 32])> = self
+            .entries
+            .iter()
+            .map(|entry| (entry.sequence, LinkKind::Entry, entry.content_hash()))
+            .chain(self.redactions.iter().map(|redaction| {
+                (
+                    redaction.sequence,
+                    LinkKind::Redaction,
+                    redaction.record_hash(),
+                )
+            }))
+            .chain(
+                self.purges
+                    .iter()
+                    .map(|purge| (purge.sequence, LinkKind::Purge, purge.record_hash())),
+            )
+            .collect();
+        links.sort_by_key(|(sequence, _, _)| *sequence);
+        check_increasing(links.iter().map(|(sequence, _, _)| *sequence))?;
+        Ok(links
+            .into_iter()
+            .map(|(_, kind, content_hash)| (kind, content_hash))
+            .collect())
+    }
+
+    /// Every redacted entry has exactly one later redaction record and only marker text
+    fn verify_redactions(&self) -> Result<(), FsmError> {
+        check_increasing(self.redactions.iter().map(|redaction| redaction.sequence))?;
+        let mut recorded = vec![false; self.entries.len()];
+        for redaction in &self.redactions {
+            let entry = self
+                .entries
+                .get(redaction.index)
+                .ok_or(FsmError::InvalidState)?;
+            if !entry.is_redacted()
+                || recorded[redaction.index]
+                || redaction.sequence <= entry.sequence
+            {
+                return Err(FsmError::InvalidState);
+            }
+            recorded[redaction.index] = true;
+        }
+        let is_marker = |text: &str| text == REDACTION_MARKER;
+        for (entry, recorded) in self.entries.iter().zip(recorded) {
+            if entry.is_redacted() != recorded
+                || (recorded
+                    && !(is_marker(&entry.action)
+                        && entry.metadata.as_deref().is_none_or(is_marker)))
+            {
+                return Err(FsmError::InvalidState);
+            }
+        }
         Ok(())
     }
 
@@ -84,6 +551,151 @@ impl AuditTrail {
     pub fn entries(&self) -> &[AuditEntry] {
         &self.entries
     }
+
+    /// Entries as JSON, one per line.
+    #[cfg(feature = "serde")]
+    pub fn to_json_lines(&self) -> Result<String, FsmError> {
+        let mut out = String::new();
+        for entry in &self.entries {
+            out.push_str(&serde_json::to_string(entry).map_err(|_| FsmError::InvalidInput)?);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+
+    /// Replace the free text (`action`, `metadata`) of entry `index` with `REDACTION_MARKER`.
+    ///
+    /// The original text is discarded; only its `text_hash()` is kept, so the
+    /// entry's content hash and `verify` are unaffected. A sequenced
+    /// `RedactionEntry` is appended to `redactions()` and linked into the hash
+    /// chain. `InvalidInput` for an unknown index, `InvalidState` if already
+    /// redacted, `Overflow` once the sequence generator is exhausted.
+    pub fn redact(
+        &mut self,
+        index: usize,
+        redactor: AuditActor,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let entry = self.entries.get(index).ok_or(FsmError::InvalidInput)?;
+        if entry.is_redacted() {
+            return Err(FsmError::InvalidState);
+        }
+        let redaction = RedactionEntry {
+            index,
+            redactor,
+            redacted_at: current_time,
+            sequence: self.sequence.issue()?,
+        };
+        let hash = chain_hash(
+            &self.chain_head(),
+            LinkKind::Redaction,
+            redaction.record_hash(),
+        );
+        let entry = &mut self.entries[index];
+        entry.redacted_text_hash = Some(entry.text_hash());
+        entry.action = REDACTION_MARKER.to_string();
+        if entry.metadata.is_some() {
+            entry.metadata = Some(REDACTION_MARKER.to_string());
+        }
+        self.redactions.push(redaction);
+        self.chain.push(hash);
+        Ok(())
+    }
+
+    /// Redaction records in the order they were written.
+    pub fn redactions(&self) -> &[RedactionEntry] {
+        &self.redactions
+    }
+
+    /// Transitions of one entity in recorded order.
+    ///
+    /// Transition entries only cover grants, so other kinds have no history.
+    pub(crate) fn entity_entries(
+        &self,
+        entity_kind: EntityKind,
+        entity_id: u64,
+    ) -> impl Iterator<Item = (usize, &AuditEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(move |(_, e)| entity_kind == EntityKind::Grant && e.grant_id == entity_id)
+    }
+
+    /// Status of an entity at `timestamp`, from the latest transition at or before it.
+    ///
+    /// A transition takes effect at its own timestamp; among entries with equal
+    /// timestamps the last recorded wins. `None` before the first transition.
+    pub fn status_at(
+        &self,
+        entity_kind: EntityKind,
+        entity_id: u64,
+        timestamp: i64,
+    ) -> Option<String> {
+        self.entity_entries(entity_kind, entity_id)
+            .filter(|(_, e)| e.timestamp <= timestamp)
+            .max_by_key(|(index, e)| (e.timestamp, *index))
+            .map(|(_, e)| format!("{:?}", e.to_state))
+    }
+
+    /// `(timestamp, from, to)` for every transition of an entity, oldest first.
+    ///
+    /// Entries with equal timestamps keep their recorded order.
+    pub fn timeline(&self, entity_kind: EntityKind, entity_id: u64) -> Vec<(i64, String, String)> {
+        let mut timeline: Vec<(i64, String, String)> = self
+            .entity_entries(entity_kind, entity_id)
+            .map(|(_, e)| {
+                (
+                    e.timestamp,
+                    format!("{:?}", e.from_state),
+                    format!("{:?}", e.to_state),
+                )
+            })
+            .collect();
+        timeline.sort_by_key(|(timestamp, _, _)| *timestamp);
+        timeline
+    }
+
+    /// Entries recorded out of chronological order for their entity, in recorded order.
+    pub fn validate_chronology(&self) -> Vec<ChronologyViolation> {
+        let mut last_seen: BTreeMap<u64, i64> = BTreeMap::new();
+        let mut violations = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let Some(previous) = last_seen.insert(entry.grant_id, entry.timestamp)
+                && entry.timestamp < previous
+            {
+                violations.push(ChronologyViolation {
+                    entity_kind: EntityKind::Grant,
+                    entity_id: entry.grant_id,
+                    index,
+                    timestamp: entry.timestamp,
+                    previous_timestamp: previous,
+                });
+            }
+        }
+        violations
+    }
+
+    /// Append a purge record and link it into the hash chain.
+    ///
+    /// The record's `sequence` is replaced by the trail's next one.
+    /// `InvalidInput` for a negative `retention_seconds`, `InvalidState` unless
+    /// `purged_at` is past `archived_at + retention_seconds`, `Overflow` if that
+    /// sum overflows or the generator is exhausted.
+    pub fn record_purge(&mut self, mut entry: PurgeEntry) -> Result<(), FsmError> {
+        if !retention_expired(entry.archived_at, entry.retention_seconds, entry.purged_at)? {
+            return Err(FsmError::InvalidState);
+        }
+        entry.sequence = self.sequence.issue()?;
+        let hash = chain_hash(&self.chain_head(), LinkKind::Purge, entry.record_hash());
+        self.purges.push(entry);
+        self.chain.push(hash);
+        Ok(())
+    }
+
+    /// Purge records in the order they were written.
+    pub fn purges(&self) -> &[PurgeEntry] {
+        &self.purges
+    }
 }
 
 #[cfg(test)]
@@ -138,6 +750,404 @@ mod tests {
         assert!(trail.verify().is_err());
     }
 
+    struct Recorder(
+        std::rc::Rc<std::cell::RefCell<Vec<(&'static str, GrantStatus)>>>,
+        &'static str,
+    );
+
+    impl AuditSubscriber for Recorder {
+        fn on_entry(&mut self, entry: &AuditEntry) -> Result<(), String> {
+            self.0.borrow_mut().push((self.1, entry.to_state));
+            Ok(())
+        }
+    }
+
+    struct Panicking;
+
+    impl AuditSubscriber for Panicking {
+        fn on_entry(&mut self, entry: &AuditEntry) -> Result<(), String> {
+            if entry.to_state == GrantStatus::Active {
+                panic!("subscriber failed");
+            }
+            Err("not ready".to_string())
+        }
+    }
+
+    #[test]
+    fn subscribers_notified_in_order() {
+        let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let mut trail = AuditTrail::new();
+        trail.subscribe(Box::new(Recorder(seen.clone(), "first")));
+        trail.subscribe(Box::new(Recorder(seen.clone(), "second")));
+        trail
+            .record(sample_entry(GrantStatus::Pending, GrantStatus::Approved))
+            .unwrap();
+        // Refused entries are not delivered
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Pending))
+            .unwrap_err();
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+        assert_eq!(
+            *seen.borrow(),
+            vec![
+                ("first", GrantStatus::Approved),
+                ("second", GrantStatus::Approved),
+                ("first", GrantStatus::Active),
+                ("second", GrantStatus::Active),
+            ]
+        );
+        assert!(trail.subscriber_errors().is_empty());
+    }
+
+    #[test]
+    fn failing_subscriber_does_not_lose_entry() {
+        let (channel, receiver) = ChannelSubscriber::new();
+        let mut trail = AuditTrail::new();
+        assert_eq!(trail.subscribe(Box::new(Panicking)), 0);
+        assert_eq!(trail.subscribe(Box::new(channel)), 1);
+        trail
+            .record(sample_entry(GrantStatus::Pending, GrantStatus::Approved))
+            .unwrap();
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+
+        assert_eq!(trail.entries().len(), 2);
+        assert!(trail.verify().is_ok());
+        assert_eq!(
+            trail.subscriber_errors(),
+            [
+                SubscriberError {
+                    subscriber: 0,
+                    entry_index: 0,
+                    message: "not ready".to_string(),
+                },
+                SubscriberError {
+                    subscriber: 0,
+                    entry_index: 1,
+                    message: "subscriber failed".to_string(),
+                },
+            ]
+        );
+        // The subscriber after the failing one still got both entries
+        let received: Vec<AuditEntry> = receiver.try_iter().collect();
+        assert_eq!(received, trail.entries());
+
+        drop(receiver);
+        trail
+            .record(sample_entry(GrantStatus::Active, GrantStatus::Completed))
+            .unwrap();
+        assert_eq!(
+            trail.subscriber_errors().last().unwrap().message,
+            "receiver dropped"
+        );
+        assert_eq!(trail.clone().subscribe(Box::new(Panicking)), 0);
+    }
+
+    fn redaction_trail() -> AuditTrail {
+        let mut trail = AuditTrail::new();
+        trail
+            .record(sample_entry(GrantStatus::Pending, GrantStatus::Approved))
+            .unwrap();
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+        trail
+    }
+
+    #[test]
+    fn verify_detects_tampering() {
+        let mut trail = redaction_trail();
+        trail.entries[0].metadata = Some("edited".to_string());
+        assert_eq!(trail.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn record_assigns_increasing_sequences() {
+        let mut trail = AuditTrail::new();
+        let mut entry = sample_entry(GrantStatus::Pending, GrantStatus::Approved);
+        entry.sequence = 42;
+        trail.record(entry).unwrap();
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+        let sequences: Vec<u64> = trail.entries().iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![1, 2]);
+        assert_eq!(trail.sequence_generator().last(), 2);
+        assert!(trail.verify().is_ok());
+
+        // Stored hashes cover the sequence, so a renumbered entry breaks the chain
+        let mut renumbered = trail.clone();
+        renumbered.entries[1].sequence = 3;
+        assert_eq!(renumbered.verify(), Err(FsmError::InvalidState));
+        let mut duplicate = trail.clone();
+        duplicate.entries[1].sequence = 1;
+        assert_eq!(duplicate.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn resumed_trail_continues_without_collisions() {
+        let mut before = AuditTrail::new();
+        before
+            .record(sample_entry(GrantStatus::Pending, GrantStatus::Approved))
+            .unwrap();
+        before
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+        // Persisted generator state may lag; the entries give the maximum
+        let max_seen = before.entries().iter().map(|e| e.sequence).max().unwrap();
+        let mut after = AuditTrail::new().with_sequence(SequenceGenerator::resume_from(max_seen));
+        after
+            .record(sample_entry(GrantStatus::Active, GrantStatus::Completed))
+            .unwrap();
+        assert!(after.verify().is_ok());
+        let sequences: Vec<u64> = before
+            .entries()
+            .iter()
+            .chain(after.entries())
+            .map(|e| e.sequence)
+            .collect();
+        assert_eq!(sequences, vec![1, 2, 3]);
+
+        let mut exhausted =
+            AuditTrail::new().with_sequence(SequenceGenerator::resume_from(u64::MAX));
+        assert_eq!(
+            exhausted.record(sample_entry(GrantStatus::Pending, GrantStatus::Approved)),
+            Err(FsmError::Overflow)
+        );
+        assert!(exhausted.entries().is_empty());
+    }
+
+    #[test]
+    fn reloaded_trail_resumes_without_collisions() {
+        let before = redaction_trail();
+        let mut after = AuditTrail::from_records(
+            before.entries().to_vec(),
+            Vec::new(),
+            Vec::new(),
+            before.chain_head(),
+        )
+        .unwrap();
+        after
+            .record(sample_entry(GrantStatus::Active, GrantStatus::Completed))
+            .unwrap();
+        after.redact(0, [1; 32], 2_000).unwrap();
+        assert!(after.verify().is_ok());
+        let sequences: Vec<u64> = after.entries().iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 3]);
+        assert_eq!(after.redactions()[0].sequence, 4);
+    }
+
+    #[test]
+    fn shared_generator_orders_entries_and_events() {
+        use crate::events::{EventBuffer, GovernanceEvent};
+
+        let sequence = SequenceGenerator::new();
+        let mut trail = AuditTrail::new().with_sequence(sequence.clone());
+        let mut events = EventBuffer::new().with_sequence(sequence.clone());
+        trail
+            .record(sample_entry(GrantStatus::Pending, GrantStatus::Approved))
+            .unwrap();
+        let event = GovernanceEvent::GrantStatusChanged {
+            grant_id: 1,
+            from: GrantStatus::Pending,
+            to: GrantStatus::Approved,
+            at: 1_000,
+        };
+        assert_eq!(events.push(event), Ok(2));
+        trail
+            .record(sample_entry(GrantStatus::Approved, GrantStatus::Active))
+            .unwrap();
+        trail.redact(0, [9u8; 32], 5_000).unwrap();
+
+        let entries: Vec<u64> = trail.entries().iter().map(|e| e.sequence).collect();
+        assert_eq!(entries, vec![1, 3]);
+        assert_eq!(trail.redactions()[0].sequence, 4);
+        assert_eq!(sequence.last(), 4);
+        assert!(
+            trail
+                .sequence_generator()
+                .shares_counter(&events.sequence_generator())
+        );
+        assert!(trail.verify().is_ok());
+    }
+
+    #[test]
+    fn redaction_keeps_chain_valid() {
+        let mut trail = redaction_trail();
+        let head = trail.chain_head();
+        trail.redact(0, [9u8; 32], 5_000).unwrap();
+
+        let entry = &trail.entries()[0];
+        assert!(entry.is_redacted());
+        assert_eq!(entry.action, REDACTION_MARKER);
+        assert_eq!(entry.metadata.as_deref(), Some(REDACTION_MARKER));
+        assert_ne!(trail.chain_head(), head);
+        assert!(trail.verify().is_ok());
+        assert_eq!(
+            trail.redactions(),
+            &[RedactionEntry {
+                index: 0,
+                redactor: [9u8; 32],
+                redacted_at: 5_000,
+                sequence: 3,
+            }]
+        );
+        // Transition data is kept for historical queries
+        assert_eq!(
+            trail.status_at(EntityKind::Grant, 1, 1_000),
+            Some("Active".to_string())
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn redacted_export_shows_marker() {
+        let mut trail = redaction_trail();
+        trail.redact(1, [9u8; 32], 5_000).unwrap();
+        let lines = trail.to_json_lines().unwrap();
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\"metadata\""));
+        assert!(!lines[0].contains(REDACTION_MARKER));
+        assert!(lines[1].contains(REDACTION_MARKER));
+        assert!(!lines[1].contains("\"metadata\":\"metadata\""));
+        assert!(!lines[1].contains("\"test\""));
+        let parsed: AuditEntry = serde_json::from_str(lines[1]).unwrap();
+        assert!(parsed.is_redacted());
+    }
+
+    #[test]
+    fn redact_validation() {
+        let mut trail = redaction_trail();
+        assert_eq!(
+            trail.redact(2, [9u8; 32], 5_000),
+            Err(FsmError::InvalidInput)
+        );
+        trail.redact(0, [9u8; 32], 5_000).unwrap();
+        assert_eq!(
+            trail.redact(0, [9u8; 32], 6_000),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(trail.redactions().len(), 1);
+        // Hiding a redaction by faking the original hash breaks the chain
+        trail.entries[0].redacted_text_hash = Some([0u8; 32]);
+        assert_eq!(trail.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn redacted_entries_stay_bound_to_the_chain() {
+        let mut trail = redaction_trail();
+        trail.redact(1, [9u8; 32], 5_000).unwrap();
+        assert!(trail.verify().is_ok());
+
+        let mut moved = trail.clone();
+        moved.entries[1].timestamp = 2_000;
+        assert_eq!(moved.verify(), Err(FsmError::InvalidState));
+        let mut regranted = trail.clone();
+        regranted.entries[1].grant_id = 2;
+        assert_eq!(regranted.verify(), Err(FsmError::InvalidState));
+        let mut rewritten = trail.clone();
+        rewritten.entries[1].action = "approve".to_string();
+        assert_eq!(rewritten.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn redaction_records_are_verified() {
+        let mut trail = redaction_trail();
+        trail.redact(0, [9u8; 32], 5_000).unwrap();
+
+        let mut dropped = trail.clone();
+        dropped.redactions.clear();
+        assert_eq!(dropped.verify(), Err(FsmError::InvalidState));
+        let mut reattributed = trail.clone();
+        reattributed.redactions[0].redactor = [8u8; 32];
+        assert_eq!(reattributed.verify(), Err(FsmError::InvalidState));
+        let mut retargeted = trail.clone();
+        retargeted.redactions[0].index = 1;
+        assert_eq!(retargeted.verify(), Err(FsmError::InvalidState));
+
+        // An unrecorded redaction is caught even with a matching text hash
+        let mut unrecorded = redaction_trail();
+        let entry = &mut unrecorded.entries[1];
+        entry.redacted_text_hash = Some(entry.text_hash());
+        entry.action = REDACTION_MARKER.to_string();
+        entry.metadata = Some(REDACTION_MARKER.to_string());
+        assert_eq!(unrecorded.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn purge_records_are_chained() {
+        let mut trail = redaction_trail();
+        let head = trail.chain_head();
+        trail
+            .record_purge(PurgeEntry {
+                entity_kind: EntityKind::Proposal,
+                entity_id: 4,
+                content_hash: [1; 32],
+                purged_at: 500,
+                archived_at: 100,
+                retention_seconds: 300,
+                sequence: 0,
+            })
+            .unwrap();
+        assert_eq!(trail.purges()[0].sequence, 3);
+        assert_ne!(trail.chain_head(), head);
+        assert!(trail.verify().is_ok());
+
+        let mut edited = trail.clone();
+        edited.purges[0].entity_id = 5;
+        assert_eq!(edited.verify(), Err(FsmError::InvalidState));
+        let mut dropped = trail.clone();
+        dropped.purges.clear();
+        assert_eq!(dropped.verify(), Err(FsmError::InvalidState));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reloaded_trail_detects_tampering() {
+        type Records = (Vec<AuditEntry>, Vec<RedactionEntry>, Vec<PurgeEntry>);
+        let mut trail = redaction_trail();
+        trail.redact(0, [1; 32], 2_000).unwrap();
+        trail
+            .record_purge(PurgeEntry {
+                entity_kind: EntityKind::Proposal,
+                entity_id: 4,
+                content_hash: [1; 32],
+                purged_at: 500,
+                archived_at: 100,
+                retention_seconds: 300,
+                sequence: 0,
+            })
+            .unwrap();
+        let saved =
+            serde_json::to_string(&(trail.entries(), trail.redactions(), trail.purges())).unwrap();
+        let head = trail.chain_head();
+        let reload = |json: &str| {
+            let (entries, redactions, purges): Records = serde_json::from_str(json).unwrap();
+            AuditTrail::from_records(entries, redactions, purges, head)
+        };
+
+        let reloaded = reload(&saved).unwrap();
+        assert!(reloaded.verify().is_ok());
+        assert_eq!(reloaded.chain_head(), head);
+        assert_eq!(reloaded.entries(), trail.entries());
+
+        let (mut entries, redactions, purges): Records = serde_json::from_str(&saved).unwrap();
+        entries[1].timestamp += 1;
+        let tampered = serde_json::to_string(&(entries, redactions, purges)).unwrap();
+        assert_eq!(reload(&tampered).err(), Some(FsmError::InvalidState));
+
+        // A consistent trail missing its last record no longer ends at the saved head
+        let (entries, redactions, _): Records = serde_json::from_str(&saved).unwrap();
+        let truncated =
+            serde_json::to_string(&(entries, redactions, Vec::<PurgeEntry>::new())).unwrap();
+        assert_eq!(reload(&truncated).err(), Some(FsmError::InvalidState));
+    }
+
+    #[cfg(feature = "serde")]
     #[test]
     fn serialize_deserialize_json() {
         let entry = sample_entry(GrantStatus::Pending, GrantStatus::Approved);
@@ -166,4 +1176,181 @@ mod tests {
         assert_eq!(trail.entries().len(), 2);
         assert!(trail.verify().is_ok());
     }
+
+    fn entry_at(grant_id: u64, from: GrantStatus, to: GrantStatus, timestamp: i64) -> AuditEntry {
+        AuditEntry::new(grant_id, [0u8; 32], from, to, "test", timestamp, None)
+    }
+
+    fn dense_trail() -> AuditTrail {
+        let mut trail = AuditTrail::new();
+        for entry in [
+            entry_at(1, GrantStatus::Pending, GrantStatus::Approved, 100),
+            entry_at(1, GrantStatus::Approved, GrantStatus::Active, 200),
+            entry_at(2, GrantStatus::Pending, GrantStatus::Rejected, 250),
+            entry_at(1, GrantStatus::Active, GrantStatus::Suspended, 300),
+            entry_at(1, GrantStatus::Suspended, GrantStatus::Active, 300),
+            entry_at(1, GrantStatus::Active, GrantStatus::Completed, 301),
+            entry_at(1, GrantStatus::Completed, GrantStatus::Archived, 400),
+        ] {
+            trail.record(entry).unwrap();
+        }
+        trail
+    }
+
+    #[test]
+    fn status_at_boundaries() {
+        let trail = dense_trail();
+        let at = |t| trail.status_at(EntityKind::Grant, 1, t);
+        assert_eq!(at(99), None);
+        assert_eq!(at(100).as_deref(), Some("Approved"));
+        assert_eq!(at(199).as_deref(), Some("Approved"));
+        assert_eq!(at(200).as_deref(), Some("Active"));
+        // Two transitions in the same second: the later record wins
+        assert_eq!(at(300).as_deref(), Some("Active"));
+        assert_eq!(at(301).as_deref(), Some("Completed"));
+        assert_eq!(at(400).as_deref(), Some("Archived"));
+        assert_eq!(at(i64::MAX).as_deref(), Some("Archived"));
+
+        assert_eq!(
+            trail.status_at(EntityKind::Grant, 2, 250).as_deref(),
+            Some("Rejected")
+        );
+        assert_eq!(trail.status_at(EntityKind::Proposal, 1, 400), None);
+    }
+
+    #[test]
+    fn timeline_is_chronological() {
+        let trail = dense_trail();
+        let timeline = trail.timeline(EntityKind::Grant, 1);
+        assert_eq!(timeline.len(), 6);
+        assert_eq!(
+            timeline[3],
+            (300, "Suspended".to_string(), "Active".to_string())
+        );
+        assert!(timeline.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(trail.timeline(EntityKind::Grant, 3).is_empty());
+    }
+
+    #[test]
+    fn out_of_order_entries_detected() {
+        let mut trail = dense_trail();
+        assert!(trail.validate_chronology().is_empty());
+
+        trail.entries.push(entry_at(
+            2,
+            GrantStatus::Rejected,
+            GrantStatus::Archived,
+            240,
+        ));
+        trail.entries.push(entry_at(
+            1,
+            GrantStatus::Archived,
+            GrantStatus::Archived,
+            500,
+        ));
+        assert_eq!(
+            trail.validate_chronology(),
+            vec![ChronologyViolation {
+                entity_kind: EntityKind::Grant,
+                entity_id: 2,
+                index: 7,
+                timestamp: 240,
+                previous_timestamp: 250,
+            }]
+        );
+        // Queries still use timestamps, not record order
+        assert_eq!(
+            trail.status_at(EntityKind::Grant, 2, 245).as_deref(),
+            Some("Archived")
+        );
+        assert_eq!(
+            trail.timeline(EntityKind::Grant, 2)[0],
+            (240, "Rejected".to_string(), "Archived".to_string())
+        );
+    }
+
+    #[test]
+    fn record_grant_follows_the_operation() {
+        use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+        let mut grant = Grant::new(
+            5,
+            1,
+            [0u8; 32],
+            GrantCategory::Development,
+            GrantType::Core,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap();
+        let mut trail = AuditTrail::new();
+        trail
+            .record_grant(&mut grant, [1u8; 32], "approve", 10, |g| g.approve())
+            .unwrap();
+        trail
+            .record_grant(&mut grant, [1u8; 32], "activate", 20, |g| g.activate())
+            .unwrap();
+        trail
+            .record_grant(&mut grant, [1u8; 32], "disburse", 30, |g| g.disburse(40))
+            .unwrap();
+        assert_eq!(grant.disbursed_amount, 40);
+        assert_eq!(
+            trail.timeline(EntityKind::Grant, 5),
+            vec![
+                (10, "Pending".to_string(), "Approved".to_string()),
+                (20, "Approved".to_string(), "Active".to_string()),
+            ]
+        );
+        let head = trail.chain_head();
+        let before = grant.clone();
+        assert_eq!(
+            trail.record_grant(&mut grant, [1u8; 32], "approve", 40, |g| g.approve()),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(grant, before);
+        assert_eq!(trail.chain_head(), head);
+        assert_eq!(trail.verify(), Ok(()));
+    }
+
+    #[test]
+    fn record_grant_marks_clamped_entries() {
+        use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+        let mut grant = Grant::new(
+            6,
+            1,
+            [0u8; 32],
+            GrantCategory::Development,
+            GrantType::Core,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap()
+        .with_clock_skew_tolerance(2);
+        let mut trail = AuditTrail::new();
+        trail
+            .record_grant(&mut grant, [1u8; 32], "approve", 100, |g| {
+                g.approve_with_time(100)
+            })
+            .unwrap();
+        let before = grant.clone();
+        assert_eq!(
+            trail.record_grant(&mut grant, [1u8; 32], "activate", 97, |g| {
+                g.activate_with_time(97)
+            }),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(grant, before);
+        trail
+            .record_grant(&mut grant, [1u8; 32], "activate", 98, |g| {
+                g.activate_with_time(98)
+            })
+            .unwrap();
+        let entries = trail.entries();
+        assert!(!entries[0].clamped);
+        assert_eq!((entries[1].timestamp, entries[1].clamped), (100, true));
+        assert_eq!(trail.verify(), Ok(()));
+    }
 }
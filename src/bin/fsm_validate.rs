@@ -3,6 +3,7 @@ use std::fs;
 use std::process;
 
 use fsm_governance_engine_lib::FsmDefinition;
+use fsm_governance_engine_lib::definition::DefinitionLimits;
 use serde_json::Value;
 
 fn main() {
@@ -51,6 +52,11 @@ fn main() {
         }
     };
 
+    if let Err(err) = DefinitionLimits::default().check_input(&raw) {
+        eprintln!("Validation failed: {}", err);
+        process::exit(1);
+    }
+
     let json_value: Value = match serde_json::from_str(&raw) {
         Ok(value) => value,
         Err(err) => {
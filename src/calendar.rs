@@ -0,0 +1,332 @@
+//! Governance calendar.
+//!
+//! Collects time-based deadlines across proposals and grants so operators can
+//! see what needs attention within a lookahead window.
+
+pub use crate::enums::EntityKind;
+use crate::grant::funding::accepts_funding_changes;
+use crate::grant::{Grant, GrantStatus};
+use crate::proposal::{Proposal, ProposalStatus};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Type of deadline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeadlineType {
+    /// Voting period of an Active proposal ends
+    VotingEnd,
+    /// Proposal `expires_at` (auto-archive time)
+    Expiration,
+    /// Treasury capability granted by the proposal expires
+    CapabilityExpiry,
+    /// Scheduled grant payout not yet covered by disbursements falls due
+    PayoutDue,
+    /// Dispute window of a Completed grant closes
+    DisputeWindowEnd,
+    /// Suspended grant reaches its `auto_resume_at`
+    AutoResume,
+    /// Suspended grant reaches its `auto_cancel_at`
+    AutoCancel,
+}
+
+/// One upcoming or overdue deadline
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Deadline {
+    pub entity_kind: EntityKind,
+    pub entity_id: u64,
+    pub deadline_type: DeadlineType,
+    pub at: i64,
+    /// `at` is already in the past
+    pub overdue: bool,
+}
+
+/// Deadlines due before `current_time + lookahead_seconds`, sorted ascending
+///
+/// Overdue deadlines are included with `overdue: true`. Ties on `at` are
+/// ordered by entity kind, id and deadline type, so the output does not depend
+/// on the order of `proposals` and `grants`.
+///
+/// Proposals contribute the end of voting (`voting_ends_at`) while Active and
+/// their `expires_at` while in a status `check_and_auto_archive` archives.
+/// Grants contribute their unpaid scheduled payouts while still open to
+/// funding changes, the end of a Completed grant's dispute window, and the
+/// suspension deadlines of a Suspended grant. Nothing is allocated per entity
+/// beyond the returned vector.
+pub fn upcoming<P>(
+    proposals: &[Proposal<P>],
+    grants: &[Grant],
+    lookahead_seconds: i64,
+    current_time: i64,
+) -> Vec<Deadline> {
+    let horizon = current_time.saturating_add(lookahead_seconds.max(0));
+    let mut deadlines = Vec::new();
+    let mut push = |entity_kind, entity_id, deadline_type, at: i64| {
+        if at <= horizon {
+            deadlines.push(Deadline {
+                entity_kind,
+                entity_id,
+                deadline_type,
+                at,
+                overdue: at < current_time,
+            });
+        }
+    };
+
+    for proposal in proposals {
+        if proposal.status == ProposalStatus::Active
+            && let Ok(voting_end) = proposal.voting_ends_at()
+        {
+            push(
+                EntityKind::Proposal,
+                proposal.id,
+                DeadlineType::VotingEnd,
+                voting_end,
+            );
+        }
+        if proposal.archives_on_expiry()
+            && let Some(expires_at) = proposal.expires_at
+        {
+            push(
+                EntityKind::Proposal,
+                proposal.id,
+                DeadlineType::Expiration,
+                expires_at,
+            );
+        }
+        if proposal.status == ProposalStatus::Executed
+            && let Some(expires_at) = proposal
+                .treasury_operation
+                .as_ref()
+                .and_then(|operation| operation.expires_at)
+        {
+            push(
+                EntityKind::Proposal,
+                proposal.id,
+                DeadlineType::CapabilityExpiry,
+                expires_at,
+            );
+        }
+    }
+
+    for grant in grants {
+        if accepts_funding_changes(grant.status) {
+            for payout in grant.unpaid_payouts() {
+                push(
+                    EntityKind::Grant,
+                    grant.id,
+                    DeadlineType::PayoutDue,
+                    payout.due_at,
+                );
+            }
+        }
+        if let Some(end) = grant.dispute_window_ends_at() {
+            push(
+                EntityKind::Grant,
+                grant.id,
+                DeadlineType::DisputeWindowEnd,
+                end,
+            );
+        }
+        if grant.status == GrantStatus::Suspended {
+            if let Some(at) = grant.auto_resume_at {
+                push(EntityKind::Grant, grant.id, DeadlineType::AutoResume, at);
+            }
+            if let Some(at) = grant.auto_cancel_at {
+                push(EntityKind::Grant, grant.id, DeadlineType::AutoCancel, at);
+            }
+        }
+    }
+
+    deadlines.sort_by_key(|d| (d.at, d.entity_kind, d.entity_id, d.deadline_type));
+    deadlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::governance_params::GovernanceParams;
+    use crate::grant::SuspensionWindow;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+    use crate::proposal::{TreasuryOperationData, TreasuryProposalType};
+    use crate::reason::SuspensionCode;
+
+    fn create_test_proposal(id: u64, voting_duration: i64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(voting_duration))
+        .build(0)
+        .unwrap()
+    }
+
+    fn create_test_grant(id: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1000,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upcoming_mixed_entities() {
+        let mut voting = create_test_proposal(1, 500);
+        voting.activate_with_time(1, 10, 100).unwrap(); // ends at 600
+
+        let mut overdue = create_test_proposal(2, 50);
+        overdue.activate_with_time(1, 10, 100).unwrap(); // ended at 150
+
+        let mut expiring = create_test_proposal(3, 100);
+        expiring.set_expiration(Some(700)).unwrap();
+        expiring
+            .cancel_with_time("dropped".to_string(), 50)
+            .unwrap();
+
+        // Drafts are not archived on expiry, so their expiration is not listed
+        let mut draft = create_test_proposal(7, 100);
+        draft.set_expiration(Some(500)).unwrap();
+
+        let mut far = create_test_proposal(4, 100);
+        far.set_expiration(Some(10_000)).unwrap();
+
+        let mut capability = Proposal::builder(
+            5,
+            "Grant capability".to_string(),
+            "Description".to_string(),
+            "treasury".to_string(),
+            1u8,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .treasury_operation(TreasuryOperationData::new(
+            TreasuryProposalType::GrantCapability,
+            None,
+            None,
+            Some(2),
+            Some("spend".to_string()),
+            Some(650),
+            "Grant spend".to_string(),
+        ))
+        .build(0)
+        .unwrap();
+        capability.activate_with_time(1, 10, 0).unwrap();
+        capability.pass_with_time(10).unwrap();
+        capability.execute_with_time(20).unwrap();
+
+        let mut archived = create_test_proposal(6, 100);
+        archived.set_expiration(Some(300)).unwrap();
+        archived
+            .cancel_with_time("dropped".to_string(), 50)
+            .unwrap();
+        archived.archive_with_time(60).unwrap();
+
+        let proposals = vec![voting, overdue, expiring, far, capability, archived, draft];
+        let grants = vec![create_test_grant(1), create_test_grant(2)];
+
+        let deadlines = upcoming(&proposals, &grants, 500, 400);
+        let summary: Vec<_> = deadlines
+            .iter()
+            .map(|d| (d.entity_id, d.deadline_type, d.at, d.overdue))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, DeadlineType::VotingEnd, 150, true),
+                (1, DeadlineType::VotingEnd, 600, false),
+                (5, DeadlineType::CapabilityExpiry, 650, false),
+                (3, DeadlineType::Expiration, 700, false),
+            ]
+        );
+        assert!(
+            deadlines
+                .iter()
+                .all(|d| d.entity_kind == EntityKind::Proposal)
+        );
+    }
+
+    #[test]
+    fn test_upcoming_grant_deadlines() {
+        let mut paying = create_test_grant(1);
+        paying.schedule_payout(300, 400).unwrap();
+        paying.schedule_payout(800, 600).unwrap();
+        paying.approve().unwrap();
+        paying.activate_with_time(10).unwrap();
+        paying.disburse_with_time(400, 200).unwrap();
+
+        let mut completed = create_test_grant(2).with_dispute_window(DurationSecs::from_secs(50));
+        completed.approve().unwrap();
+        completed.activate_with_time(10).unwrap();
+        completed.disburse_with_time(1000, 100).unwrap(); // window ends at 150
+
+        let params = GovernanceParams::new(50, 168, 30, true, 0).unwrap();
+        let mut suspended = create_test_grant(3);
+        suspended.approve().unwrap();
+        suspended.activate_with_time(10).unwrap();
+        suspended
+            .suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                SuspensionWindow {
+                    auto_resume_at: Some(700),
+                    auto_cancel_after: Some(DurationSecs::from_secs(500)),
+                },
+                &params,
+                300,
+            )
+            .unwrap(); // cancels at 800
+
+        let mut cancelled = create_test_grant(4);
+        cancelled.schedule_payout(500, 100).unwrap();
+        cancelled.approve().unwrap();
+        cancelled.activate_with_time(10).unwrap();
+        cancelled.cancel().unwrap();
+
+        let grants = vec![cancelled, suspended, completed, paying];
+        let deadlines = upcoming::<u8>(&[], &grants, 500, 400);
+        let summary: Vec<_> = deadlines
+            .iter()
+            .map(|d| (d.entity_id, d.deadline_type, d.at, d.overdue))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, DeadlineType::DisputeWindowEnd, 150, true),
+                (3, DeadlineType::AutoResume, 700, false),
+                (1, DeadlineType::PayoutDue, 800, false),
+                (3, DeadlineType::AutoCancel, 800, false),
+            ]
+        );
+        assert!(deadlines.iter().all(|d| d.entity_kind == EntityKind::Grant));
+    }
+
+    #[test]
+    fn test_upcoming_ties_are_deterministic() {
+        let mut first = create_test_proposal(9, 100);
+        first.set_expiration(Some(500)).unwrap();
+        first.cancel_with_time("dropped".to_string(), 10).unwrap();
+        let mut second = create_test_proposal(3, 100);
+        second.set_expiration(Some(500)).unwrap();
+        second.cancel_with_time("dropped".to_string(), 10).unwrap();
+
+        let deadlines = upcoming::<u8>(&[first, second], &[], 1000, 0);
+        let ids: Vec<_> = deadlines.iter().map(|d| d.entity_id).collect();
+        assert_eq!(ids, vec![3, 9]);
+    }
+
+    #[test]
+    fn test_upcoming_empty() {
+        assert!(upcoming::<u8>(&[], &[], 1000, 0).is_empty());
+    }
+}
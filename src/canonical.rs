@@ -0,0 +1,497 @@
+//! Canonical serialization for signing and hashing.
+//!
+//! `to_canonical_bytes` encodes any `Serialize` value as canonical JSON, so
+//! every service produces the same bytes for the same value regardless of
+//! struct field order or map iteration order:
+//!
+//! - UTF-8, no whitespace outside strings
+//! - object keys sorted by their UTF-8 bytes, each key once
+//! - strings escaped as `serde_json` does: `"` and `\` backslash-escaped,
+//!   `\b \f \n \r \t` for those controls, `\u00XX` (lowercase hex) for other
+//!   characters below U+0020, everything else verbatim
+//! - integers in plain decimal; non-integer numbers are refused
+//! - `true`, `false`, `null` as literals; a `None` field is written as an
+//!   explicit `null`, never dropped. So is any field a type leaves out of its
+//!   serde output with `skip_serializing_if`, whatever its value, so that
+//!   such fields stay in the signed bytes.
+//!
+//! `canonical_hash` is the SHA-256 of those bytes. Retention content hashes
+//! and decision records use it, and `hashing` verifies off-chain documents
+//! with it. `tests/canonical_golden` holds byte fixtures that must not change
+//! between releases except with a changelog entry.
+
+use crate::error::FsmError;
+use serde::Serialize;
+use serde::ser;
+use serde_json::value::Serializer as JsonSerializer;
+use serde_json::{Map, Value};
+#[cfg(feature = "hash")]
+use sha2::{Digest, Sha256};
+
+/// Canonical encoding of `value` (see the module docs)
+///
+/// `InvalidInput` if `value` cannot be represented as JSON (e.g. a map with
+/// non-string keys) or contains a non-integer number.
+pub fn to_canonical_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, FsmError> {
+    to_canonical_string(value).map(String::into_bytes)
+}
+
+/// SHA-256 of `to_canonical_bytes(value)`
+#[cfg(feature = "hash")]
+pub fn canonical_hash<T: Serialize>(value: &T) -> Result<[u8; 32], FsmError> {
+    Ok(Sha256::digest(to_canonical_bytes(value)?).into())
+}
+
+/// `to_canonical_bytes` as a string
+pub(crate) fn to_canonical_string<T: Serialize>(value: &T) -> Result<String, FsmError> {
+    let value = value
+        .serialize(ValueSerializer)
+        .map_err(|_| FsmError::InvalidInput)?;
+    let mut out = String::new();
+    write_canonical(&value, &mut out)?;
+    Ok(out)
+}
+
+/// `serde_json::to_value`, but fields skipped by `skip_serializing_if` become `null`
+///
+/// Derived `Serialize` impls report such fields through `skip_field`, which
+/// `serde_json` ignores.
+struct ValueSerializer;
+
+macro_rules! delegate_to_json {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Value, Self::Error> {
+                ser::Serializer::$method(JsonSerializer, v)
+            }
+        )*
+    };
+}
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = MapSerializer;
+    type SerializeStructVariant = VariantSerializer<MapSerializer>;
+
+    delegate_to_json!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_i128(i128),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_u128(u128),
+        serialize_f32(f32),
+        serialize_f64(f64),
+        serialize_char(char),
+        serialize_str(&str),
+        serialize_bytes(&[u8]),
+    );
+
+    fn serialize_none(self) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+        Ok(Value::Null)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Self::Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error> {
+        Ok(tagged(variant, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Self::Error> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SeqSerializer, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer, Self::Error> {
+        Ok(MapSerializer::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<MapSerializer, Self::Error> {
+        Ok(MapSerializer::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(VariantSerializer {
+            variant,
+            inner: MapSerializer::default(),
+        })
+    }
+}
+
+/// `{ variant: value }`, the externally tagged form of an enum variant
+fn tagged(variant: &str, value: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(variant.to_string(), value);
+    Value::Object(map)
+}
+
+struct SeqSerializer(Vec<Value>);
+
+impl SeqSerializer {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), serde_json::Error> {
+        self.0.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Array(self.0))
+    }
+}
+
+#[derive(Default)]
+struct MapSerializer {
+    map: Map<String, Value>,
+    next_key: Option<String>,
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    /// Keys follow `serde_json`: strings, or integers written as strings
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        let mut probe = ser::Serializer::serialize_map(JsonSerializer, Some(1))?;
+        probe.serialize_entry(key, &())?;
+        let Value::Object(probe) = probe.end()? else {
+            return Err(ser::Error::custom("map key did not serialize to a string"));
+        };
+        self.next_key = probe.into_iter().next().map(|(key, _)| key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ser::Error::custom("map value without a key"))?;
+        self.map.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Object(self.map))
+    }
+}
+
+impl ser::SerializeStruct for MapSerializer {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.map
+            .insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.map.insert(key.to_string(), Value::Null);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Object(self.map))
+    }
+}
+
+struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.inner.push(value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(tagged(self.variant, Value::Array(self.inner.0)))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<MapSerializer> {
+    type Ok = Value;
+    type Error = serde_json::Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        ser::SerializeStruct::skip_field(&mut self.inner, key)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(tagged(self.variant, Value::Object(self.inner.map)))
+    }
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), FsmError> {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            if !(n.is_i64() || n.is_u64()) {
+                return Err(FsmError::InvalidInput);
+            }
+            out.push_str(&n.to_string());
+        }
+        Value::String(s) => write_string(s, out)?,
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push('{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_string(key, out)?;
+                out.push(':');
+                write_canonical(item, out)?;
+            }
+            out.push('}');
+        }
+    }
+    Ok(())
+}
+
+fn write_string(s: &str, out: &mut String) -> Result<(), FsmError> {
+    out.push_str(&serde_json::to_string(s).map_err(|_| FsmError::InvalidInput)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[test]
+    fn test_field_and_map_order_do_not_matter() {
+        #[derive(Serialize)]
+        struct Forward {
+            alpha: u64,
+            beta: Option<u64>,
+        }
+        #[derive(Serialize)]
+        struct Reversed {
+            beta: Option<u64>,
+            alpha: u64,
+        }
+        let forward = to_canonical_bytes(&Forward {
+            alpha: 1,
+            beta: None,
+        })
+        .unwrap();
+        assert_eq!(forward, br#"{"alpha":1,"beta":null}"#);
+        assert_eq!(
+            to_canonical_bytes(&Reversed {
+                beta: None,
+                alpha: 1
+            })
+            .unwrap(),
+            forward
+        );
+        let map: HashMap<String, u8> = (0..32).map(|i| (format!("k{i}"), i)).collect();
+        assert_eq!(
+            to_canonical_bytes(&map).unwrap(),
+            to_canonical_bytes(&map.clone()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_skipped_fields_are_written_as_null() {
+        #[derive(Serialize)]
+        struct Inner {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            note: Option<String>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            tags: Vec<String>,
+        }
+        #[derive(Serialize)]
+        enum Outer {
+            Plain(Inner),
+            Named {
+                inner: Inner,
+                by_id: BTreeMap<u64, bool>,
+            },
+        }
+        let inner = || Inner {
+            note: None,
+            tags: Vec::new(),
+        };
+        assert_eq!(
+            to_canonical_bytes(&Outer::Plain(inner())).unwrap(),
+            br#"{"Plain":{"note":null,"tags":null}}"#
+        );
+        let named = Outer::Named {
+            inner: inner(),
+            by_id: BTreeMap::from([(7, true)]),
+        };
+        assert_eq!(
+            to_canonical_bytes(&named).unwrap(),
+            br#"{"Named":{"by_id":{"7":true},"inner":{"note":null,"tags":null}}}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&inner()).unwrap(),
+            "{}",
+            "plain serde output keeps skipping"
+        );
+    }
+
+    #[test]
+    fn test_integers_only() {
+        assert_eq!(
+            to_canonical_bytes(&json!([u64::MAX, i64::MIN])).unwrap(),
+            b"[18446744073709551615,-9223372036854775808]"
+        );
+        assert_eq!(
+            to_canonical_bytes(&json!({"a": 0.5})),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[cfg(feature = "hash")]
+    #[test]
+    fn test_hash_is_sha256_of_bytes() {
+        let value = json!({"b": [true, null], "a": "x"});
+        let bytes = to_canonical_bytes(&value).unwrap();
+        assert_eq!(
+            canonical_hash(&value).unwrap(),
+            <[u8; 32]>::from(Sha256::digest(&bytes))
+        );
+    }
+}
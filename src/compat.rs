@@ -0,0 +1,185 @@
+//! Compatibility shims for integrations written against the 0.1 API.
+//!
+//! Behind the `compat-v1` feature (on by default). The 0.1 release had a
+//! five-variant `FsmError` and plain integer ids, timestamps and durations;
+//! the error has since gained variants with fields and more breaking changes
+//! are planned. `FsmErrorV1` is the 0.1 error, with conversions both ways, and
+//! the deprecated wrappers here keep the 0.1 call shapes working on top of
+//! the current API.
+//!
+//! Deprecation plan: the wrappers are deprecated from 0.2.0 and will be
+//! removed, together with this module and the feature, in the first release
+//! after 0.2.x. `FsmErrorV1` and the aliases are not deprecated, so an
+//! integration can keep matching on the old error while it migrates.
+
+use crate::enums::IdeaStatus;
+use crate::error::{ErrorClass, FsmError};
+use crate::grant::{Grant, GrantStatus};
+use crate::proposal::{Proposal, ProposalStatus};
+use std::fmt;
+
+/// Proposal id as taken by the 0.1 API
+pub type ProposalId = u64;
+/// Grant id as taken by the 0.1 API
+pub type GrantId = u64;
+/// Idea id as taken by the 0.1 API
+pub type IdeaId = u64;
+/// Unix timestamp in seconds as taken by the 0.1 API
+pub type Timestamp = i64;
+/// Duration in seconds, before `DurationSecs`
+pub type Seconds = i64;
+
+/// The 0.1 `FsmError`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FsmErrorV1 {
+    InvalidStateTransition,
+    InvalidInput,
+    InsufficientMembers,
+    InvalidState,
+    Overflow,
+}
+
+impl From<FsmErrorV1> for FsmError {
+    fn from(error: FsmErrorV1) -> Self {
+        match error {
+            FsmErrorV1::InvalidStateTransition => FsmError::InvalidStateTransition,
+            FsmErrorV1::InvalidInput => FsmError::InvalidInput,
+            FsmErrorV1::InsufficientMembers => FsmError::InsufficientMembers,
+            FsmErrorV1::InvalidState => FsmError::InvalidState,
+            FsmErrorV1::Overflow => FsmError::Overflow,
+        }
+    }
+}
+
+/// Newer variants map by `ErrorClass`: input errors to `InvalidInput`,
+/// preconditions and conflicts to `InvalidState`
+impl From<FsmError> for FsmErrorV1 {
+    fn from(error: FsmError) -> Self {
+        match error {
+            FsmError::InsufficientMembers => FsmErrorV1::InsufficientMembers,
+            _ => match error.class() {
+                ErrorClass::Transition => FsmErrorV1::InvalidStateTransition,
+                ErrorClass::Input => FsmErrorV1::InvalidInput,
+                ErrorClass::Precondition | ErrorClass::Conflict => FsmErrorV1::InvalidState,
+                ErrorClass::Arithmetic => FsmErrorV1::Overflow,
+            },
+        }
+    }
+}
+
+impl std::error::Error for FsmErrorV1 {}
+
+impl fmt::Display for FsmErrorV1 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        FsmError::from(*self).fmt(f)
+    }
+}
+
+/// `ProposalStatus::validate_transition` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `ProposalStatus::validate_transition`")]
+pub fn validate_proposal_transition(
+    from: &ProposalStatus,
+    to: &ProposalStatus,
+) -> Result<(), FsmErrorV1> {
+    Ok(from.validate_transition(to)?)
+}
+
+/// `GrantStatus::validate_transition` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `GrantStatus::validate_transition`")]
+pub fn validate_grant_transition(from: GrantStatus, to: GrantStatus) -> Result<(), FsmErrorV1> {
+    Ok(from.validate_transition(to)?)
+}
+
+/// `IdeaStatus::validate_transition` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `IdeaStatus::validate_transition`")]
+pub fn validate_idea_transition(from: IdeaStatus, to: IdeaStatus) -> Result<(), FsmErrorV1> {
+    Ok(from.validate_transition(to)?)
+}
+
+/// `Proposal::new_with_time` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `Proposal::builder`")]
+pub fn new_proposal<P>(
+    id: ProposalId,
+    title: String,
+    description: String,
+    proposal_type: String,
+    author: P,
+    created_at: Timestamp,
+) -> Result<Proposal<P>, FsmErrorV1> {
+    Ok(Proposal::new_with_time(
+        id,
+        title,
+        description,
+        proposal_type,
+        author,
+        created_at,
+    )?)
+}
+
+/// `Proposal::activate_with_time` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `Proposal::activate_in`")]
+pub fn activate_proposal<P>(
+    proposal: &mut Proposal<P>,
+    min_quorum: u64,
+    total_members: u64,
+    current_time: Timestamp,
+) -> Result<(), FsmErrorV1> {
+    Ok(proposal.activate_with_time(min_quorum, total_members, current_time)?)
+}
+
+/// Voting period in seconds, as the 0.1 integer field held it
+#[deprecated(since = "0.2.0", note = "use `voting_duration.as_secs()`")]
+pub fn voting_duration_secs<P>(proposal: &Proposal<P>) -> Seconds {
+    proposal.voting_duration.as_secs()
+}
+
+/// `Grant::approve` with the 0.1 error
+#[deprecated(since = "0.2.0", note = "use `Grant::approve_with_time`")]
+pub fn approve_grant(grant: &mut Grant) -> Result<(), FsmErrorV1> {
+    Ok(grant.approve()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::EntityKind;
+
+    #[test]
+    fn test_v1_errors_roundtrip() {
+        for error in [
+            FsmErrorV1::InvalidStateTransition,
+            FsmErrorV1::InvalidInput,
+            FsmErrorV1::InsufficientMembers,
+            FsmErrorV1::InvalidState,
+            FsmErrorV1::Overflow,
+        ] {
+            assert_eq!(FsmErrorV1::from(FsmError::from(error)), error);
+            assert_eq!(error.to_string(), FsmError::from(error).to_string());
+        }
+    }
+
+    #[test]
+    fn test_newer_errors_map_by_class() {
+        let cases = [
+            (FsmError::TimeRegression, FsmErrorV1::InvalidInput),
+            (
+                FsmError::BrokenReference {
+                    entity: EntityKind::Proposal,
+                    id: 1,
+                },
+                FsmErrorV1::InvalidInput,
+            ),
+            (FsmError::ConflictDetected, FsmErrorV1::InvalidState),
+            (
+                FsmError::InsufficientSponsors {
+                    required: 3,
+                    actual: 1,
+                },
+                FsmErrorV1::InvalidState,
+            ),
+        ];
+        for (error, expected) in cases {
+            assert_eq!(FsmErrorV1::from(error), expected, "{error:?}");
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! Differential checks between a status enum and an `FsmDefinition`.
+//!
+//! The status enums hard-code their transitions in `next_states`, while
+//! deployments describe the same machines as JSON definitions. `differential`
+//! keeps the two honest: for every ordered pair of distinct states it compares
+//! the enum's `can_transition_to` with the presence of a single definition
+//! edge, then replays seeded pseudo-random walks through both and compares
+//! whether each walk is accepted. Guards are ignored; an edge counts as
+//! reachable whatever its guard says.
+//!
+//! Same-state moves are no-ops the enums always accept, so they are accepted
+//! on the definition side too rather than requiring self edges.
+//!
+//! States are matched by name: the enum side uses the variant's `Debug`
+//! output, which is also its serde name.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::definition::FsmDefinition;
+
+/// A status enum's states and its transition predicate
+pub struct EnumTable<'a, S, F> {
+    states: &'a [S],
+    can_transition: F,
+}
+
+impl<'a, S, F> EnumTable<'a, S, F>
+where
+    S: fmt::Debug,
+    F: Fn(&S, &S) -> bool,
+{
+    /// Table over `states` (usually `VARIANTS`) with `can_transition(from, to)`
+    pub fn new(states: &'a [S], can_transition: F) -> Self {
+        Self {
+            states,
+            can_transition,
+        }
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.states.iter().map(|s| format!("{s:?}")).collect()
+    }
+}
+
+/// Random walk settings for `differential`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WalkSamples {
+    /// Number of walks
+    pub walks: usize,
+    /// Steps per walk
+    pub steps: usize,
+    /// Seed; the same seed replays the same walks
+    pub seed: u64,
+}
+
+impl Default for WalkSamples {
+    fn default() -> Self {
+        Self {
+            walks: 256,
+            steps: 12,
+            seed: 0x5eed,
+        }
+    }
+}
+
+/// One disagreement found by `differential`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Mismatch {
+    /// Enum state the definition does not declare
+    MissingState { state: String },
+    /// Definition state the enum does not have
+    ExtraState { state: String },
+    /// `can_transition_to` and the definition's edges disagree on `from -> to`
+    Edge {
+        from: String,
+        to: String,
+        enum_allows: bool,
+        definition_allows: bool,
+    },
+    /// The enum and the definition disagree on accepting `path`
+    Walk {
+        path: Vec<String>,
+        enum_accepts: bool,
+        definition_accepts: bool,
+    },
+}
+
+/// Result of `differential`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// Ordered pairs compared
+    pub pairs_checked: usize,
+    /// Walks replayed
+    pub walks_checked: usize,
+    /// State mismatches first, then edges in state order, then the first failing walk
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ConformanceReport {
+    /// True when the enum and the definition agree everywhere
+    pub fn is_ok(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compare `table` with `definition` (see the module docs)
+///
+/// Edges are only compared between states both sides declare. Walks step to a
+/// state either side allows most of the time, and to an arbitrary state
+/// otherwise, so both accepted and rejected walks are exercised. Only the
+/// first disagreeing walk is reported.
+pub fn differential<S, F>(
+    table: &EnumTable<'_, S, F>,
+    definition: &FsmDefinition,
+    samples: WalkSamples,
+) -> ConformanceReport
+where
+    S: fmt::Debug,
+    F: Fn(&S, &S) -> bool,
+{
+    let names = table.names();
+    let enum_states: BTreeSet<&str> = names.iter().map(String::as_str).collect();
+    let definition_states: BTreeSet<&str> = definition.states.iter().map(String::as_str).collect();
+    let edges: BTreeSet<(&str, &str)> = definition
+        .transitions
+        .iter()
+        .map(|t| (t.from.as_str(), t.to.as_str()))
+        .collect();
+    let definition_allows =
+        |from: usize, to: usize| from == to || edges.contains(&(&names[from], &names[to]));
+    let enum_allows =
+        |from: usize, to: usize| (table.can_transition)(&table.states[from], &table.states[to]);
+
+    let mut report = ConformanceReport::default();
+    for state in enum_states.difference(&definition_states) {
+        report.mismatches.push(Mismatch::MissingState {
+            state: state.to_string(),
+        });
+    }
+    for state in definition_states.difference(&enum_states) {
+        report.mismatches.push(Mismatch::ExtraState {
+            state: state.to_string(),
+        });
+    }
+
+    // Indices of enum states the definition also declares
+    let shared: Vec<usize> = (0..names.len())
+        .filter(|&i| definition_states.contains(names[i].as_str()))
+        .collect();
+    for &from in &shared {
+        for &to in &shared {
+            if from == to {
+                continue;
+            }
+            report.pairs_checked += 1;
+            let (by_enum, by_definition) = (enum_allows(from, to), definition_allows(from, to));
+            if by_enum != by_definition {
+                report.mismatches.push(Mismatch::Edge {
+                    from: names[from].clone(),
+                    to: names[to].clone(),
+                    enum_allows: by_enum,
+                    definition_allows: by_definition,
+                });
+            }
+        }
+    }
+
+    if shared.is_empty() {
+        return report;
+    }
+    let mut rng = SplitMix64(samples.seed);
+    for _ in 0..samples.walks {
+        report.walks_checked += 1;
+        let mut path = vec![shared[rng.below(shared.len())]];
+        let (mut enum_accepts, mut definition_accepts) = (true, true);
+        for _ in 0..samples.steps {
+            let from = path[path.len() - 1];
+            let allowed: Vec<usize> = shared
+                .iter()
+                .copied()
+                .filter(|&to| enum_allows(from, to) || definition_allows(from, to))
+                .collect();
+            let to = if rng.below(4) == 0 {
+                shared[rng.below(shared.len())]
+            } else {
+                allowed[rng.below(allowed.len())]
+            };
+            enum_accepts &= enum_allows(from, to);
+            definition_accepts &= definition_allows(from, to);
+            path.push(to);
+        }
+        if enum_accepts != definition_accepts {
+            report.mismatches.push(Mismatch::Walk {
+                path: path.iter().map(|&i| names[i].clone()).collect(),
+                enum_accepts,
+                definition_accepts,
+            });
+            break;
+        }
+    }
+    report
+}
+
+/// Small deterministic generator so walks replay from a seed without a `rand` dependency
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::GrantStatus;
+
+    fn grant_definition(drop_edge: Option<(&str, &str)>) -> FsmDefinition {
+        let mut definition = FsmDefinition {
+            name: Some("grant".to_string()),
+            states: GrantStatus::VARIANTS
+                .iter()
+                .map(|s| format!("{s:?}"))
+                .collect(),
+            transitions: Vec::new(),
+            defaults: None,
+            invariants: Vec::new(),
+        };
+        for from in GrantStatus::VARIANTS {
+            for to in from.next_states() {
+                let (from, to) = (format!("{from:?}"), format!("{to:?}"));
+                if drop_edge == Some((from.as_str(), to.as_str())) {
+                    continue;
+                }
+                definition
+                    .transitions
+                    .push(crate::definition::FsmTransition {
+                        action: format!("{from}_to_{to}"),
+                        from,
+                        to,
+                        guard: None,
+                        metadata: None,
+                    });
+            }
+        }
+        definition
+    }
+
+    #[test]
+    fn matching_definition_conforms() {
+        let table = EnumTable::new(GrantStatus::VARIANTS, |a: &GrantStatus, b| {
+            a.can_transition_to(*b)
+        });
+        let report = differential(&table, &grant_definition(None), WalkSamples::default());
+        assert!(report.is_ok(), "{report:?}");
+        let n = GrantStatus::VARIANTS.len();
+        assert_eq!(report.pairs_checked, n * (n - 1));
+        assert_eq!(report.walks_checked, 256);
+    }
+
+    #[test]
+    fn missing_edge_and_state_are_reported() {
+        let table = EnumTable::new(GrantStatus::VARIANTS, |a: &GrantStatus, b| {
+            a.can_transition_to(*b)
+        });
+        let mut definition = grant_definition(Some(("Suspended", "Active")));
+        definition.states.push("Frozen".to_string());
+        let report = differential(&table, &definition, WalkSamples::default());
+        assert_eq!(
+            report.mismatches[..2],
+            [
+                Mismatch::ExtraState {
+                    state: "Frozen".to_string()
+                },
+                Mismatch::Edge {
+                    from: "Suspended".to_string(),
+                    to: "Active".to_string(),
+                    enum_allows: true,
+                    definition_allows: false,
+                },
+            ]
+        );
+        assert!(matches!(
+            report.mismatches.last(),
+            Some(Mismatch::Walk {
+                enum_accepts: true,
+                definition_accepts: false,
+                ..
+            })
+        ));
+    }
+}
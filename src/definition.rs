@@ -1,13 +1,18 @@
 //! Declarative FSM definitions for validation-only workflows.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
+pub use crate::error::DefinitionLimit;
 use crate::error::FsmError;
+use crate::telemetry::OperationSpan;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct FsmDefinition {
+    /// Machine name; must be unique across definitions passed to `validate_many`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub states: Vec<String>,
     pub transitions: Vec<FsmTransition>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -53,17 +58,181 @@ pub struct FsmInvariant {
     pub description: Option<String>,
 }
 
+macro_rules! invariant_kinds {
+    ($($variant:ident => $name:literal),* $(,)?) => {
+        /// Invariant kinds understood by `validate_invariants`
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum InvariantKind {
+            $($variant,)*
+        }
+
+        impl InvariantKind {
+            /// Every supported kind; `meta::capabilities` reports this list
+            pub const ALL: &'static [InvariantKind] = &[$(InvariantKind::$variant),*];
+
+            /// Name used in `FsmInvariant::kind`
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(InvariantKind::$variant => $name,)*
+                }
+            }
+        }
+    };
+}
+
+invariant_kinds! {
+    TerminalStates => "terminal_states",
+    RequiredTransitions => "required_transitions",
+    ForbiddenTransitions => "forbidden_transitions",
+    ForbiddenCycles => "forbidden_cycles",
+    SelfTransitionsRequired => "self_transitions_required",
+}
+
+impl InvariantKind {
+    /// Kind named `name`, if supported
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.as_str() == name)
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct FsmTransitionRef {
     pub from: String,
     pub to: String,
 }
 
+/// Size limits checked before any semantic rule
+///
+/// Names are state names, the machine name, transition `from`/`to`/`action`,
+/// roles, the initial state and everything an invariant names; their length
+/// is counted in characters. Guards and descriptions are only bounded by
+/// `max_input_bytes`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DefinitionLimits {
+    pub max_states: usize,
+    pub max_transitions: usize,
+    pub max_name_length: usize,
+    pub max_invariants: usize,
+    /// Size of the JSON (or SCXML) text, checked before parsing
+    pub max_input_bytes: usize,
+}
+
+impl Default for DefinitionLimits {
+    fn default() -> Self {
+        Self {
+            max_states: 10_000,
+            max_transitions: 100_000,
+            max_name_length: 128,
+            max_invariants: 1_000,
+            max_input_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+impl DefinitionLimits {
+    /// `DefinitionTooLarge(InputBytes)` if `input` is longer than `max_input_bytes`
+    pub fn check_input(&self, input: &str) -> Result<(), FsmError> {
+        if input.len() > self.max_input_bytes {
+            return Err(FsmError::DefinitionTooLarge(DefinitionLimit::InputBytes));
+        }
+        Ok(())
+    }
+}
+
 impl FsmDefinition {
+    /// `validate_with_limits` with the default limits
     pub fn validate(&self) -> Result<(), FsmError> {
-        self.validate_structure()?;
-        self.validate_invariants()?;
-        Ok(())
+        self.validate_with_limits(&DefinitionLimits::default())
+    }
+
+    /// Check `limits`, then the structure, then the invariants
+    pub fn validate_with_limits(&self, limits: &DefinitionLimits) -> Result<(), FsmError> {
+        let span = OperationSpan::definition_validate(self.states.len(), self.transitions.len());
+        let result = self
+            .validate_limits(limits)
+            .and_then(|()| self.validate_structure())
+            .and_then(|()| self.validate_invariants());
+        span.finish(None, &result);
+        result
+    }
+
+    /// `DefinitionTooLarge` naming the first limit exceeded
+    pub fn validate_limits(&self, limits: &DefinitionLimits) -> Result<(), FsmError> {
+        match self.exceeded_limit(limits) {
+            Some(limit) => Err(FsmError::DefinitionTooLarge(limit)),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn exceeded_limit(&self, limits: &DefinitionLimits) -> Option<DefinitionLimit> {
+        if self.states.len() > limits.max_states {
+            Some(DefinitionLimit::States)
+        } else if self.transitions.len() > limits.max_transitions {
+            Some(DefinitionLimit::Transitions)
+        } else if self.invariants.len() > limits.max_invariants {
+            Some(DefinitionLimit::Invariants)
+        } else if self
+            .names()
+            .any(|name| name.chars().count() > limits.max_name_length)
+        {
+            Some(DefinitionLimit::NameLength)
+        } else {
+            None
+        }
+    }
+
+    /// Parse JSON within the default limits
+    ///
+    /// `DefinitionTooLarge` if the text or the parsed definition exceeds a
+    /// limit, `InvalidInput` if it is not a definition document. Semantic rules
+    /// are left to `validate`.
+    pub fn from_json_str(json: &str) -> Result<Self, FsmError> {
+        Self::from_json_str_with_limits(json, &DefinitionLimits::default())
+    }
+
+    /// `from_json_str` with explicit limits
+    pub fn from_json_str_with_limits(
+        json: &str,
+        limits: &DefinitionLimits,
+    ) -> Result<Self, FsmError> {
+        limits.check_input(json)?;
+        let definition: Self = serde_json::from_str(json).map_err(|_| FsmError::InvalidInput)?;
+        definition.validate_limits(limits)?;
+        Ok(definition)
+    }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        let transitions = self.transitions.iter().flat_map(|transition| {
+            [&transition.from, &transition.to, &transition.action]
+                .into_iter()
+                .chain(
+                    transition
+                        .metadata
+                        .iter()
+                        .flat_map(|metadata| metadata.roles.iter()),
+                )
+        });
+        let invariants = self.invariants.iter().flat_map(|invariant| {
+            std::iter::once(&invariant.kind)
+                .chain(invariant.states.iter())
+                .chain(
+                    invariant
+                        .transitions
+                        .iter()
+                        .flat_map(|reference| [&reference.from, &reference.to]),
+                )
+        });
+        self.name
+            .iter()
+            .chain(self.states.iter())
+            .chain(
+                self.defaults
+                    .iter()
+                    .filter_map(|defaults| defaults.initial_state.as_ref()),
+            )
+            .chain(transitions)
+            .chain(invariants)
+            .map(String::as_str)
     }
 
     pub fn validate_structure(&self) -> Result<(), FsmError> {
@@ -71,7 +240,7 @@ impl FsmDefinition {
             return Err(FsmError::InvalidInput);
         }
 
-        let state_set: HashSet<&str> = self.states.iter().map(|s| s.as_str()).collect();
+        let state_set: BTreeSet<&str> = self.states.iter().map(|s| s.as_str()).collect();
 
         for transition in &self.transitions {
             if transition.from.trim().is_empty()
@@ -103,14 +272,13 @@ impl FsmDefinition {
             return Ok(());
         }
 
-        let transition_set: HashSet<(&str, &str)> = self
+        let transition_set: BTreeSet<(&str, &str)> = self
             .transitions
             .iter()
             .map(|t| (t.from.as_str(), t.to.as_str()))
             .collect();
 
-        let mut adjacency: std::collections::HashMap<&str, Vec<&str>> =
-            std::collections::HashMap::new();
+        let mut adjacency: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
         for transition in &self.transitions {
             adjacency
                 .entry(transition.from.as_str())
@@ -119,8 +287,11 @@ impl FsmDefinition {
         }
 
         for invariant in &self.invariants {
-            match invariant.kind.as_str() {
-                "terminal_states" => {
+            let Some(kind) = InvariantKind::from_name(&invariant.kind) else {
+                return Err(FsmError::InvalidInput);
+            };
+            match kind {
+                InvariantKind::TerminalStates => {
                     for state in &invariant.states {
                         if let Some(outbound) = adjacency.get(state.as_str())
                             && !outbound.is_empty()
@@ -129,7 +300,7 @@ impl FsmDefinition {
                         }
                     }
                 }
-                "required_transitions" => {
+                InvariantKind::RequiredTransitions => {
                     for transition in &invariant.transitions {
                         if !transition_set
                             .contains(&(transition.from.as_str(), transition.to.as_str()))
@@ -138,7 +309,7 @@ impl FsmDefinition {
                         }
                     }
                 }
-                "forbidden_transitions" => {
+                InvariantKind::ForbiddenTransitions => {
                     for transition in &invariant.transitions {
                         if transition_set
                             .contains(&(transition.from.as_str(), transition.to.as_str()))
@@ -147,14 +318,14 @@ impl FsmDefinition {
                         }
                     }
                 }
-                "forbidden_cycles" => {
+                InvariantKind::ForbiddenCycles => {
                     for state in &invariant.states {
                         if has_cycle_from(state.as_str(), &adjacency) {
                             return Err(FsmError::InvalidInput);
                         }
                     }
                 }
-                "self_transitions_required" => {
+                InvariantKind::SelfTransitionsRequired => {
                     let states: Vec<&str> = if invariant.states.is_empty() {
                         self.states.iter().map(|s| s.as_str()).collect()
                     } else {
@@ -167,7 +338,6 @@ impl FsmDefinition {
                         }
                     }
                 }
-                _ => return Err(FsmError::InvalidInput),
             }
         }
 
@@ -175,8 +345,136 @@ impl FsmDefinition {
     }
 }
 
-fn has_cycle_from(start: &str, adjacency: &std::collections::HashMap<&str, Vec<&str>>) -> bool {
-    let mut visited: HashSet<&str> = HashSet::new();
+/// Why a definition failed in `validate_many`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum DefinitionFailure {
+    /// The input is not a valid definition document
+    Parse { message: String },
+    /// `validate_structure` failed
+    Structure { error: FsmError, message: String },
+    /// `validate_invariants` failed
+    Invariants { error: FsmError, message: String },
+    /// The input or the parsed definition exceeds the default `DefinitionLimits`
+    TooLarge { error: FsmError, message: String },
+    /// Another input already declares this machine name
+    DuplicateName { name: String, first: String },
+}
+
+/// Outcome for one input of `validate_many`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DefinitionResult {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<DefinitionFailure>,
+}
+
+impl DefinitionResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Results of `validate_many`, in input order
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub results: Vec<DefinitionResult>,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Parse and validate `(name, json)` pairs
+///
+/// Every input is checked; a failure does not stop the others. A definition
+/// whose `name` was already declared by an earlier input fails with
+/// `DuplicateName`, which takes precedence over its own validation errors.
+pub fn validate_many(inputs: &[(String, &str)]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let mut declared: BTreeMap<String, String> = BTreeMap::new();
+
+    let limits = DefinitionLimits::default();
+
+    for (name, json) in inputs {
+        let parsed = match limits.check_input(json) {
+            Ok(()) => serde_json::from_str::<FsmDefinition>(json).map_err(|err| {
+                DefinitionFailure::Parse {
+                    message: err.to_string(),
+                }
+            }),
+            Err(error) => Err(DefinitionFailure::TooLarge {
+                error,
+                message: error.to_string(),
+            }),
+        };
+        let (machine_name, failure) = match parsed {
+            Err(failure) => (None, Some(failure)),
+            Ok(definition) => {
+                let duplicate =
+                    definition
+                        .name
+                        .as_ref()
+                        .and_then(|machine| match declared.get(machine) {
+                            Some(first) => Some(DefinitionFailure::DuplicateName {
+                                name: machine.clone(),
+                                first: first.clone(),
+                            }),
+                            None => {
+                                declared.insert(machine.clone(), name.clone());
+                                None
+                            }
+                        });
+                let failure = duplicate.or_else(|| definition.validation_failure(&limits));
+                (definition.name, failure)
+            }
+        };
+        if failure.is_none() {
+            report.passed += 1;
+        } else {
+            report.failed += 1;
+        }
+        report.results.push(DefinitionResult {
+            name: name.clone(),
+            machine_name,
+            failure,
+        });
+    }
+    report
+}
+
+impl FsmDefinition {
+    fn validation_failure(&self, limits: &DefinitionLimits) -> Option<DefinitionFailure> {
+        if let Err(error) = self.validate_limits(limits) {
+            return Some(DefinitionFailure::TooLarge {
+                error,
+                message: error.to_string(),
+            });
+        }
+        if let Err(error) = self.validate_structure() {
+            return Some(DefinitionFailure::Structure {
+                error,
+                message: error.to_string(),
+            });
+        }
+        if let Err(error) = self.validate_invariants() {
+            return Some(DefinitionFailure::Invariants {
+                error,
+                message: error.to_string(),
+            });
+        }
+        None
+    }
+}
+
+fn has_cycle_from(start: &str, adjacency: &BTreeMap<&str, Vec<&str>>) -> bool {
+    let mut visited: BTreeSet<&str> = BTreeSet::new();
     visited.insert(start);
 
     if let Some(neighbors) = adjacency.get(start) {
@@ -196,8 +494,8 @@ fn has_cycle_from(start: &str, adjacency: &std::collections::HashMap<&str, Vec<&
 fn dfs_reaches_start<'a>(
     current: &'a str,
     start: &'a str,
-    adjacency: &std::collections::HashMap<&'a str, Vec<&'a str>>,
-    visited: &mut HashSet<&'a str>,
+    adjacency: &BTreeMap<&'a str, Vec<&'a str>>,
+    visited: &mut BTreeSet<&'a str>,
 ) -> bool {
     if let Some(neighbors) = adjacency.get(current) {
         for neighbor in neighbors {
@@ -219,6 +517,7 @@ mod tests {
     #[test]
     fn test_validate_structure_success() {
         let definition = FsmDefinition {
+            name: None,
             states: vec!["Draft".into(), "Review".into(), "Approved".into()],
             transitions: vec![FsmTransition {
                 from: "Draft".into(),
@@ -239,6 +538,7 @@ mod tests {
     #[test]
     fn test_validate_structure_unknown_state() {
         let definition = FsmDefinition {
+            name: None,
             states: vec!["Draft".into(), "Review".into()],
             transitions: vec![FsmTransition {
                 from: "Draft".into(),
@@ -257,6 +557,7 @@ mod tests {
     #[test]
     fn test_validate_invariants_terminal_state() {
         let definition = FsmDefinition {
+            name: None,
             states: vec!["Draft".into(), "Archived".into()],
             transitions: vec![FsmTransition {
                 from: "Draft".into(),
@@ -280,6 +581,7 @@ mod tests {
     #[test]
     fn test_validate_invariants_terminal_state_violation() {
         let definition = FsmDefinition {
+            name: None,
             states: vec!["Draft".into(), "Archived".into()],
             transitions: vec![FsmTransition {
                 from: "Archived".into(),
@@ -302,4 +604,231 @@ mod tests {
             Err(FsmError::InvalidInput)
         );
     }
+
+    const VALID: &str = r#"{"name":"approval","states":["Draft","Review"],"transitions":[{"from":"Draft","to":"Review","action":"submit"}]}"#;
+
+    #[test]
+    fn test_validate_many_collects_per_file_results() {
+        let unknown_state =
+            r#"{"states":["A"],"transitions":[{"from":"A","to":"B","action":"go"}]}"#;
+        let bad_invariant = r#"{"states":["A"],"transitions":[{"from":"A","to":"A","action":"go"}],"invariants":[{"kind":"terminal_states","states":["A"]}]}"#;
+        let inputs = vec![
+            ("approval.json".to_string(), VALID),
+            ("broken.json".to_string(), "{not json"),
+            ("unknown_state.json".to_string(), unknown_state),
+            ("bad_invariant.json".to_string(), bad_invariant),
+        ];
+        let report = validate_many(&inputs);
+
+        assert_eq!((report.passed, report.failed), (1, 3));
+        assert!(!report.is_ok());
+        assert!(report.results[0].passed());
+        assert_eq!(report.results[0].machine_name.as_deref(), Some("approval"));
+        assert!(matches!(
+            report.results[1].failure,
+            Some(DefinitionFailure::Parse { .. })
+        ));
+        assert_eq!(
+            report.results[2].failure,
+            Some(DefinitionFailure::Structure {
+                error: FsmError::InvalidInput,
+                message: "Invalid input provided".to_string(),
+            })
+        );
+        assert!(matches!(
+            report.results[3].failure,
+            Some(DefinitionFailure::Invariants { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_many_rejects_duplicate_names() {
+        let unnamed = r#"{"states":["A"],"transitions":[{"from":"A","to":"A","action":"go"}]}"#;
+        let inputs = vec![
+            ("a.json".to_string(), VALID),
+            ("b.json".to_string(), unnamed),
+            ("c.json".to_string(), unnamed),
+            ("d.json".to_string(), VALID),
+        ];
+        let report = validate_many(&inputs);
+
+        assert_eq!((report.passed, report.failed), (3, 1));
+        assert_eq!(
+            report.results[3].failure,
+            Some(DefinitionFailure::DuplicateName {
+                name: "approval".to_string(),
+                first: "a.json".to_string(),
+            })
+        );
+    }
+
+    fn sized_definition(states: usize, name_length: usize) -> FsmDefinition {
+        let mut states: Vec<String> = (0..states).map(|i| format!("S{i}")).collect();
+        states[0] = "A".repeat(name_length);
+        FsmDefinition {
+            name: None,
+            transitions: vec![FsmTransition {
+                from: states[0].clone(),
+                to: states[0].clone(),
+                action: "go".into(),
+                guard: None,
+                metadata: None,
+            }],
+            states,
+            defaults: None,
+            invariants: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_default_limits_at_and_over() {
+        let limits = DefinitionLimits::default();
+        assert!(sized_definition(10_000, 128).validate().is_ok());
+        assert_eq!(
+            sized_definition(10_001, 1).validate(),
+            Err(FsmError::DefinitionTooLarge(DefinitionLimit::States))
+        );
+        assert_eq!(
+            sized_definition(1, 129).validate(),
+            Err(FsmError::DefinitionTooLarge(DefinitionLimit::NameLength))
+        );
+        // Characters, not bytes
+        let mut definition = sized_definition(1, 1);
+        definition.name = Some("é".repeat(128));
+        assert_eq!(definition.validate_limits(&limits), Ok(()));
+    }
+
+    #[test]
+    fn test_each_limit_at_and_over() {
+        let limits = DefinitionLimits {
+            max_states: 3,
+            max_transitions: 2,
+            max_name_length: 15,
+            max_invariants: 1,
+            max_input_bytes: 1024,
+        };
+        let at_limit = FsmDefinition {
+            name: Some("machine1".into()),
+            states: vec!["A".into(), "B".into(), "C".into()],
+            transitions: vec![
+                FsmTransition {
+                    from: "A".into(),
+                    to: "B".into(),
+                    action: "go".into(),
+                    guard: None,
+                    metadata: Some(FsmTransitionMetadata {
+                        description: Some("descriptions are not names".into()),
+                        roles: vec!["operator".into()],
+                    }),
+                },
+                FsmTransition {
+                    from: "B".into(),
+                    to: "C".into(),
+                    action: "go".into(),
+                    guard: None,
+                    metadata: None,
+                },
+            ],
+            defaults: None,
+            invariants: vec![FsmInvariant {
+                kind: "terminal_states".into(),
+                states: vec!["C".into()],
+                transitions: Vec::new(),
+                description: None,
+            }],
+        };
+        assert_eq!(at_limit.validate_with_limits(&limits), Ok(()));
+
+        let mut over = at_limit.clone();
+        over.states.push("D".into());
+        let mut transitions = at_limit.clone();
+        transitions
+            .transitions
+            .push(transitions.transitions[1].clone());
+        let mut invariants = at_limit.clone();
+        invariants.invariants.push(invariants.invariants[0].clone());
+        let mut role = at_limit.clone();
+        role.transitions[0].metadata.as_mut().unwrap().roles[0] = "operators_review".into();
+        let mut initial = at_limit.clone();
+        initial.defaults = Some(FsmDefaults {
+            initial_state: Some("0123456789abcdef".into()),
+        });
+        for (definition, limit) in [
+            (over, DefinitionLimit::States),
+            (transitions, DefinitionLimit::Transitions),
+            (invariants, DefinitionLimit::Invariants),
+            (role, DefinitionLimit::NameLength),
+            (initial, DefinitionLimit::NameLength),
+        ] {
+            assert_eq!(
+                definition.validate_with_limits(&limits),
+                Err(FsmError::DefinitionTooLarge(limit))
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_json_str_enforces_limits() {
+        let limits = DefinitionLimits {
+            max_input_bytes: VALID.len(),
+            ..DefinitionLimits::default()
+        };
+        let definition = FsmDefinition::from_json_str_with_limits(VALID, &limits).unwrap();
+        assert_eq!(definition.name.as_deref(), Some("approval"));
+        let padded = format!("{VALID} ");
+        assert_eq!(
+            FsmDefinition::from_json_str_with_limits(&padded, &limits),
+            Err(FsmError::DefinitionTooLarge(DefinitionLimit::InputBytes))
+        );
+        let long_state = VALID.replace("Review", &"R".repeat(129));
+        assert_eq!(
+            FsmDefinition::from_json_str(&long_state),
+            Err(FsmError::DefinitionTooLarge(DefinitionLimit::NameLength))
+        );
+        assert_eq!(
+            FsmDefinition::from_json_str("{not json"),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_validate_many_reports_too_large() {
+        let long_name = VALID.replace("approval", &"a".repeat(129));
+        let report = validate_many(&[("long.json".to_string(), long_name.as_str())]);
+        assert_eq!(
+            report.results[0].failure,
+            Some(DefinitionFailure::TooLarge {
+                error: FsmError::DefinitionTooLarge(DefinitionLimit::NameLength),
+                message: "Definition exceeds the NameLength limit".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_validation_report_serializes() {
+        let report = validate_many(&[("x.json".to_string(), "[]")]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["results"][0]["failure"]["stage"], "parse");
+        let decoded: ValidationReport = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, report);
+    }
+
+    #[test]
+    fn test_every_listed_invariant_kind_is_checked() {
+        let with_kind = |kind: &str| {
+            let json = format!(
+                r#"{{"states":["A"],"transitions":[{{"from":"A","to":"A","action":"stay"}}],"invariants":[{{"kind":"{kind}"}}]}}"#
+            );
+            FsmDefinition::from_json_str(&json)
+                .unwrap()
+                .validate_invariants()
+        };
+        for kind in InvariantKind::ALL {
+            assert_eq!(InvariantKind::from_name(kind.as_str()), Some(*kind));
+            assert_eq!(with_kind(kind.as_str()), Ok(()), "{kind:?}");
+        }
+        assert_eq!(InvariantKind::from_name("acyclic"), None);
+        assert_eq!(with_kind("acyclic"), Err(FsmError::InvalidInput));
+    }
 }
@@ -0,0 +1,174 @@
+//! Durations in seconds.
+//!
+//! `DurationSecs` carries the unit in the type, so a count of hours cannot be
+//! stored where seconds are expected. It serializes as a plain integer number
+//! of seconds (serde and borsh), the same encoding as the raw `i64` fields it
+//! replaces.
+
+use crate::error::FsmError;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+const SECS_PER_HOUR: i64 = 3600;
+const SECS_PER_DAY: i64 = 24 * SECS_PER_HOUR;
+
+/// A signed duration in whole seconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct DurationSecs(i64);
+
+impl DurationSecs {
+    pub const ZERO: Self = Self(0);
+    pub const MAX: Self = Self(i64::MAX);
+
+    pub const fn from_secs(secs: i64) -> Self {
+        Self(secs)
+    }
+
+    /// `None` if the result does not fit in `i64` seconds
+    pub const fn from_hours(hours: u64) -> Option<Self> {
+        Self::from_units(hours, SECS_PER_HOUR)
+    }
+
+    /// `None` if the result does not fit in `i64` seconds
+    pub const fn from_days(days: u64) -> Option<Self> {
+        Self::from_units(days, SECS_PER_DAY)
+    }
+
+    const fn from_units(count: u64, secs_per_unit: i64) -> Option<Self> {
+        if count > (i64::MAX / secs_per_unit) as u64 {
+            return None;
+        }
+        Some(Self(count as i64 * secs_per_unit))
+    }
+
+    pub const fn as_secs(self) -> i64 {
+        self.0
+    }
+
+    /// Whole hours, rounded toward zero
+    pub const fn as_hours(self) -> i64 {
+        self.0 / SECS_PER_HOUR
+    }
+
+    pub const fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub const fn checked_add(self, other: Self) -> Option<Self> {
+        match self.0.checked_add(other.0) {
+            Some(secs) => Some(Self(secs)),
+            None => None,
+        }
+    }
+
+    /// `timestamp` plus this duration; `None` on overflow
+    pub const fn after(self, timestamp: i64) -> Option<i64> {
+        timestamp.checked_add(self.0)
+    }
+}
+
+impl fmt::Display for DurationSecs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}s", self.0)
+    }
+}
+
+/// `Overflow` if the duration exceeds `i64::MAX` seconds; sub-second parts are dropped
+impl TryFrom<Duration> for DurationSecs {
+    type Error = FsmError;
+
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        i64::try_from(duration.as_secs())
+            .map(Self)
+            .map_err(|_| FsmError::Overflow)
+    }
+}
+
+/// `InvalidInput` for a negative duration
+impl TryFrom<DurationSecs> for Duration {
+    type Error = FsmError;
+
+    fn try_from(duration: DurationSecs) -> Result<Self, Self::Error> {
+        u64::try_from(duration.0)
+            .map(Duration::from_secs)
+            .map_err(|_| FsmError::InvalidInput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_constructors() {
+        assert_eq!(DurationSecs::from_hours(168).unwrap().as_secs(), 604_800);
+        assert_eq!(DurationSecs::from_days(7), DurationSecs::from_hours(168));
+        assert_eq!(DurationSecs::from_secs(7_199).as_hours(), 1);
+        assert_eq!(DurationSecs::from_hours(u64::MAX), None);
+        assert_eq!(DurationSecs::from_days(u64::MAX / 2), None);
+        let max_hours = (i64::MAX / 3600) as u64;
+        assert!(DurationSecs::from_hours(max_hours).is_some());
+        assert_eq!(DurationSecs::from_hours(max_hours + 1), None);
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let day = DurationSecs::from_days(1).unwrap();
+        assert_eq!(day.after(100), Some(86_500));
+        assert_eq!(DurationSecs::MAX.after(1), None);
+        assert_eq!(
+            day.checked_add(day),
+            Some(DurationSecs::from_days(2).unwrap())
+        );
+        assert_eq!(DurationSecs::MAX.checked_add(day), None);
+        assert!(DurationSecs::from_secs(-1).is_negative());
+        assert_eq!(day.to_string(), "86400s");
+    }
+
+    #[test]
+    fn test_std_duration_conversions() {
+        assert_eq!(
+            DurationSecs::try_from(Duration::from_millis(90_500)),
+            Ok(DurationSecs::from_secs(90))
+        );
+        assert_eq!(
+            DurationSecs::try_from(Duration::from_secs(u64::MAX)),
+            Err(FsmError::Overflow)
+        );
+        assert_eq!(
+            Duration::try_from(DurationSecs::from_secs(5)),
+            Ok(Duration::from_secs(5))
+        );
+        assert_eq!(
+            Duration::try_from(DurationSecs::from_secs(-5)),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializes_as_plain_seconds() {
+        let duration = DurationSecs::from_hours(2).unwrap();
+        assert_eq!(serde_json::to_string(&duration).unwrap(), "7200");
+        assert_eq!(
+            serde_json::from_str::<DurationSecs>("-3").unwrap(),
+            DurationSecs::from_secs(-3)
+        );
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh_matches_raw_i64() {
+        let duration = DurationSecs::from_secs(604_800);
+        assert_eq!(
+            borsh::to_vec(&duration).unwrap(),
+            borsh::to_vec(&604_800i64).unwrap()
+        );
+    }
+}
@@ -1,21 +1,27 @@
 //! Enum definitions for Core program state
 
+#[cfg(feature = "borsh")]
 use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// Kind of entity a deadline, audit record or reference belongs to
+///
+/// Re-exported as `calendar::EntityKind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EntityKind {
+    Proposal,
+    Grant,
+    /// Only named by `FsmError::BrokenReference`; ideas have no deadlines
+    Idea,
+}
+
 /// Idea status enum
-#[derive(
-    BorshSerialize,
-    BorshDeserialize,
-    Clone,
-    Copy,
-    Debug,
-    Default,
-    PartialEq,
-    Eq,
-    Serialize,
-    Deserialize,
-)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IdeaStatus {
     #[default]
     Draft, // Draft
@@ -33,6 +39,19 @@ pub enum IdeaStatus {
     Expired,           // Expired
 }
 
+/// Which Idea FSM a deployment runs
+///
+/// `StrictTerminal` drops the edges into Resubmitted, so Rejected leads only
+/// to Archived and Archived is final.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IdeaFsmProfile {
+    #[default]
+    Standard,
+    StrictTerminal,
+}
+
 /// Member action enum
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum MemberAction {
@@ -55,6 +74,7 @@ pub enum DelegationType {
 
 /// Capability type enum
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CapabilityType {
     Withdraw,
     Deposit,
@@ -1,9 +1,27 @@
 //! Defines the custom error type for the FSM Governance Engine.
+//!
+//! Lifecycle methods report the same kind of problem with the same variant:
+//!
+//! | Problem | Variant | Example |
+//! |---|---|---|
+//! | The requested status change is not an edge of the FSM | `InvalidStateTransition` | archiving a Draft proposal |
+//! | The change or operation is allowed in principle but a precondition does not hold | `InvalidState` (or a specific variant such as `InsufficientMembers`) | passing before voting ends, voting on a closed proposal |
+//! | An argument is malformed or out of range | `InvalidInput` | a zero id, an unknown ticket |
+//!
+//! `FsmError::class` and `FsmError::CLASSES` expose the grouping for API
+//! layers that map errors to user messages. `Display` is English only; for
+//! localized messages, `FsmError::message_key` gives a stable catalog key and
+//! `FsmError::params` the values to substitute into the translated text.
 
+use crate::enums::{CapabilityType, EntityKind};
+use crate::reason_policy::ReasonTransition;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Custom error for FSM state transitions.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FsmError {
     /// The attempted state transition is invalid.
     InvalidStateTransition,
@@ -11,10 +29,279 @@ pub enum FsmError {
     InvalidInput,
     /// Insufficient quorum/members for the requested operation.
     InsufficientMembers,
-    /// The requested transition cannot be performed because the system is in the wrong state.
+    /// The operation is allowed in principle but a precondition does not hold
+    /// (voting still open, timelock running, entity not accepting the change).
     InvalidState,
     /// Number overflow detected while computing values.
     Overflow,
+    /// An idempotency key was reused with different arguments (see `idempotency`).
+    IdempotencyKeyReused,
+    /// The entity changed since the caller read it (revision mismatch); re-read and retry.
+    ConflictDetected,
+    /// The member has a conflict of interest with the entity (e.g. reviewing their own proposal).
+    ConflictOfInterest,
+    /// The supplied timestamp is earlier than the entity's last recorded event.
+    TimeRegression,
+    /// The value was updated too recently; retry after the minimum interval.
+    TooFrequentUpdate,
+    /// The account is already initialized and cannot be initialized again.
+    AlreadyInitialized,
+    /// A link names an entity that does not exist (see `links`).
+    BrokenReference { entity: EntityKind, id: u64 },
+    /// A definition exceeds one of its `DefinitionLimits`.
+    DefinitionTooLarge(DefinitionLimit),
+    /// A signed message was replayed: its nonce is not above the last one accepted.
+    ReplayDetected,
+    /// The voter has no power in the proposal's `PowerSnapshot`.
+    NotInSnapshot,
+    /// The vote weighs less than the proposal's `min_vote_weight`, or nothing once normalized.
+    BelowMinimumWeight,
+    /// The transition needs a non-blank reason under the entity's `ReasonPolicy`.
+    ReasonRequired(ReasonTransition),
+    /// The transition must not carry a reason under the entity's `ReasonPolicy`.
+    ReasonNotAllowed(ReasonTransition),
+    /// A requested window is longer than `GovernanceParams` permit; carries the maximum.
+    ExceedsPolicyLimit { max_hours: u64 },
+    /// Fewer sponsors than the activation gate requires; both counts include the author.
+    InsufficientSponsors { required: u32, actual: u32 },
+    /// The operation needs a capability that neither the actor nor its backers hold.
+    CapabilityRequired { capability: CapabilityType },
+}
+
+/// Which `definition::DefinitionLimits` field a definition exceeded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DefinitionLimit {
+    /// `max_states`
+    States,
+    /// `max_transitions`
+    Transitions,
+    /// `max_name_length`
+    NameLength,
+    /// `max_invariants`
+    Invariants,
+    /// `max_input_bytes`
+    InputBytes,
+}
+
+/// What kind of problem an `FsmError` reports (see the module docs)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ErrorClass {
+    /// The requested status change is not an edge of the FSM
+    Transition,
+    /// Allowed in principle, but not in the entity's current situation
+    Precondition,
+    /// A malformed or out-of-range argument
+    Input,
+    /// Another writer, earlier initialization or earlier message got there first
+    Conflict,
+    /// A computed value does not fit its type
+    Arithmetic,
+}
+
+impl FsmError {
+    /// Every `code` with its `class`, in declaration order
+    pub const CLASSES: &'static [(&'static str, ErrorClass)] = &[
+        ("InvalidStateTransition", ErrorClass::Transition),
+        ("InvalidInput", ErrorClass::Input),
+        ("InsufficientMembers", ErrorClass::Precondition),
+        ("InvalidState", ErrorClass::Precondition),
+        ("Overflow", ErrorClass::Arithmetic),
+        ("IdempotencyKeyReused", ErrorClass::Conflict),
+        ("ConflictDetected", ErrorClass::Conflict),
+        ("ConflictOfInterest", ErrorClass::Precondition),
+        ("TimeRegression", ErrorClass::Input),
+        ("TooFrequentUpdate", ErrorClass::Precondition),
+        ("AlreadyInitialized", ErrorClass::Conflict),
+        ("BrokenReference", ErrorClass::Input),
+        ("DefinitionTooLarge", ErrorClass::Input),
+        ("ReplayDetected", ErrorClass::Conflict),
+        ("NotInSnapshot", ErrorClass::Input),
+        ("BelowMinimumWeight", ErrorClass::Input),
+        ("ReasonRequired", ErrorClass::Input),
+        ("ReasonNotAllowed", ErrorClass::Input),
+        ("ExceedsPolicyLimit", ErrorClass::Input),
+        ("InsufficientSponsors", ErrorClass::Precondition),
+        ("CapabilityRequired", ErrorClass::Precondition),
+    ];
+
+    /// Every `code`, in declaration order
+    pub const CODES: &'static [&'static str] = &[
+        "InvalidStateTransition",
+        "InvalidInput",
+        "InsufficientMembers",
+        "InvalidState",
+        "Overflow",
+        "IdempotencyKeyReused",
+        "ConflictDetected",
+        "ConflictOfInterest",
+        "TimeRegression",
+        "TooFrequentUpdate",
+        "AlreadyInitialized",
+        "BrokenReference",
+        "DefinitionTooLarge",
+        "ReplayDetected",
+        "NotInSnapshot",
+        "BelowMinimumWeight",
+        "ReasonRequired",
+        "ReasonNotAllowed",
+        "ExceedsPolicyLimit",
+        "InsufficientSponsors",
+        "CapabilityRequired",
+    ];
+
+    /// Every `message_key`, in declaration order
+    pub const MESSAGE_KEYS: &'static [&'static str] = &[
+        "error.invalid_transition",
+        "error.invalid_input",
+        "error.quorum_not_met",
+        "error.invalid_state",
+        "error.overflow",
+        "error.idempotency_key_reused",
+        "error.conflict_detected",
+        "error.conflict_of_interest",
+        "error.time_regression",
+        "error.too_frequent_update",
+        "error.already_initialized",
+        "error.broken_reference",
+        "error.definition_too_large",
+        "error.replay_detected",
+        "error.not_in_snapshot",
+        "error.below_minimum_weight",
+        "error.reason_required",
+        "error.reason_not_allowed",
+        "error.exceeds_policy_limit",
+        "error.insufficient_sponsors",
+        "error.capability_required",
+    ];
+
+    /// Stable code of the variant, the same name serde uses as its tag
+    pub fn code(&self) -> &'static str {
+        match self {
+            FsmError::InvalidStateTransition => "InvalidStateTransition",
+            FsmError::InvalidInput => "InvalidInput",
+            FsmError::InsufficientMembers => "InsufficientMembers",
+            FsmError::InvalidState => "InvalidState",
+            FsmError::Overflow => "Overflow",
+            FsmError::IdempotencyKeyReused => "IdempotencyKeyReused",
+            FsmError::ConflictDetected => "ConflictDetected",
+            FsmError::ConflictOfInterest => "ConflictOfInterest",
+            FsmError::TimeRegression => "TimeRegression",
+            FsmError::TooFrequentUpdate => "TooFrequentUpdate",
+            FsmError::AlreadyInitialized => "AlreadyInitialized",
+            FsmError::BrokenReference { .. } => "BrokenReference",
+            FsmError::DefinitionTooLarge(_) => "DefinitionTooLarge",
+            FsmError::ReplayDetected => "ReplayDetected",
+            FsmError::NotInSnapshot => "NotInSnapshot",
+            FsmError::BelowMinimumWeight => "BelowMinimumWeight",
+            FsmError::ReasonRequired(_) => "ReasonRequired",
+            FsmError::ReasonNotAllowed(_) => "ReasonNotAllowed",
+            FsmError::ExceedsPolicyLimit { .. } => "ExceedsPolicyLimit",
+            FsmError::InsufficientSponsors { .. } => "InsufficientSponsors",
+            FsmError::CapabilityRequired { .. } => "CapabilityRequired",
+        }
+    }
+
+    /// Stable message catalog key of the variant, listed in `MESSAGE_KEYS`
+    ///
+    /// Keys never change once released; a renamed variant keeps its key.
+    pub fn message_key(&self) -> &'static str {
+        match self {
+            FsmError::InvalidStateTransition => "error.invalid_transition",
+            FsmError::InvalidInput => "error.invalid_input",
+            FsmError::InsufficientMembers => "error.quorum_not_met",
+            FsmError::InvalidState => "error.invalid_state",
+            FsmError::Overflow => "error.overflow",
+            FsmError::IdempotencyKeyReused => "error.idempotency_key_reused",
+            FsmError::ConflictDetected => "error.conflict_detected",
+            FsmError::ConflictOfInterest => "error.conflict_of_interest",
+            FsmError::TimeRegression => "error.time_regression",
+            FsmError::TooFrequentUpdate => "error.too_frequent_update",
+            FsmError::AlreadyInitialized => "error.already_initialized",
+            FsmError::BrokenReference { .. } => "error.broken_reference",
+            FsmError::DefinitionTooLarge(_) => "error.definition_too_large",
+            FsmError::ReplayDetected => "error.replay_detected",
+            FsmError::NotInSnapshot => "error.not_in_snapshot",
+            FsmError::BelowMinimumWeight => "error.below_minimum_weight",
+            FsmError::ReasonRequired(_) => "error.reason_required",
+            FsmError::ReasonNotAllowed(_) => "error.reason_not_allowed",
+            FsmError::ExceedsPolicyLimit { .. } => "error.exceeds_policy_limit",
+            FsmError::InsufficientSponsors { .. } => "error.insufficient_sponsors",
+            FsmError::CapabilityRequired { .. } => "error.capability_required",
+        }
+    }
+
+    /// Values to substitute into the message of `message_key`, by name
+    ///
+    /// Entity kinds, limits and statuses are given by their variant names;
+    /// variants without fields have no parameters.
+    pub fn params(&self) -> Vec<(&'static str, String)> {
+        match self {
+            FsmError::BrokenReference { entity, id } => {
+                vec![("entity", format!("{entity:?}")), ("id", id.to_string())]
+            }
+            FsmError::DefinitionTooLarge(limit) => vec![("limit", format!("{limit:?}"))],
+            FsmError::ReasonRequired(transition) | FsmError::ReasonNotAllowed(transition) => {
+                vec![
+                    ("entity", format!("{:?}", transition.entity)),
+                    ("from", transition.name(transition.from)),
+                    ("to", transition.name(transition.to)),
+                ]
+            }
+            FsmError::ExceedsPolicyLimit { max_hours } => {
+                vec![("max_hours", max_hours.to_string())]
+            }
+            FsmError::InsufficientSponsors { required, actual } => vec![
+                ("required", required.to_string()),
+                ("actual", actual.to_string()),
+            ],
+            FsmError::CapabilityRequired { capability } => {
+                vec![("capability", format!("{capability:?}"))]
+            }
+            FsmError::InvalidStateTransition
+            | FsmError::InvalidInput
+            | FsmError::InsufficientMembers
+            | FsmError::InvalidState
+            | FsmError::Overflow
+            | FsmError::IdempotencyKeyReused
+            | FsmError::ConflictDetected
+            | FsmError::ConflictOfInterest
+            | FsmError::TimeRegression
+            | FsmError::TooFrequentUpdate
+            | FsmError::AlreadyInitialized
+            | FsmError::ReplayDetected
+            | FsmError::NotInSnapshot
+            | FsmError::BelowMinimumWeight => Vec::new(),
+        }
+    }
+
+    /// Kind of problem, as listed in `CLASSES`
+    pub fn class(&self) -> ErrorClass {
+        match self {
+            FsmError::InvalidStateTransition => ErrorClass::Transition,
+            FsmError::InvalidInput
+            | FsmError::TimeRegression
+            | FsmError::BrokenReference { .. }
+            | FsmError::DefinitionTooLarge(_)
+            | FsmError::NotInSnapshot
+            | FsmError::BelowMinimumWeight
+            | FsmError::ReasonRequired(_)
+            | FsmError::ReasonNotAllowed(_)
+            | FsmError::ExceedsPolicyLimit { .. } => ErrorClass::Input,
+            FsmError::InsufficientMembers
+            | FsmError::InvalidState
+            | FsmError::ConflictOfInterest
+            | FsmError::TooFrequentUpdate
+            | FsmError::InsufficientSponsors { .. }
+            | FsmError::CapabilityRequired { .. } => ErrorClass::Precondition,
+            FsmError::IdempotencyKeyReused
+            | FsmError::ConflictDetected
+            | FsmError::AlreadyInitialized
+            | FsmError::ReplayDetected => ErrorClass::Conflict,
+            FsmError::Overflow => ErrorClass::Arithmetic,
+        }
+    }
 }
 
 // Implement standard `Error` trait.
@@ -29,6 +316,40 @@ impl fmt::Display for FsmError {
             FsmError::InsufficientMembers => write!(f, "Not enough members for quorum"),
             FsmError::InvalidState => write!(f, "Invalid state for requested operation"),
             FsmError::Overflow => write!(f, "Arithmetic overflow detected"),
+            FsmError::IdempotencyKeyReused => {
+                write!(f, "Idempotency key already used with other arguments")
+            }
+            FsmError::ConflictDetected => write!(f, "Concurrent modification detected"),
+            FsmError::ConflictOfInterest => write!(f, "Conflict of interest"),
+            FsmError::TimeRegression => write!(f, "Timestamp precedes the last recorded event"),
+            FsmError::TooFrequentUpdate => write!(f, "Updated too recently"),
+            FsmError::AlreadyInitialized => write!(f, "Already initialized"),
+            FsmError::BrokenReference { entity, id } => {
+                write!(f, "Broken reference: {entity:?} {id} does not exist")
+            }
+            FsmError::DefinitionTooLarge(limit) => {
+                write!(f, "Definition exceeds the {limit:?} limit")
+            }
+            FsmError::ReplayDetected => write!(f, "Replayed message: nonce already used"),
+            FsmError::NotInSnapshot => write!(f, "Voter is not in the voting power snapshot"),
+            FsmError::BelowMinimumWeight => {
+                write!(f, "Vote weight is below the proposal's minimum")
+            }
+            FsmError::ReasonRequired(transition) => {
+                write!(f, "A reason is required for the {transition} transition")
+            }
+            FsmError::ReasonNotAllowed(transition) => {
+                write!(f, "No reason may be given for the {transition} transition")
+            }
+            FsmError::ExceedsPolicyLimit { max_hours } => {
+                write!(f, "Exceeds the policy limit of {max_hours} hours")
+            }
+            FsmError::InsufficientSponsors { required, actual } => {
+                write!(f, "Needs {required} sponsors, has {actual}")
+            }
+            FsmError::CapabilityRequired { capability } => {
+                write!(f, "Requires the {capability:?} capability")
+            }
         }
     }
 }
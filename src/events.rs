@@ -0,0 +1,364 @@
+//! Governance events derived from state changes.
+//!
+//! `EventBuffer::record_proposal` and `EventBuffer::record_grant` run a
+//! lifecycle operation and append one event per observable change, so a
+//! notifier can drain the buffer instead of polling storage.
+//!
+//! Each appended event gets the next number of the buffer's
+//! `SequenceGenerator`; share the generator with an `AuditTrail` to order
+//! events and audit records together. `drain_sequenced` hands the numbers to
+//! consumers that need an order surviving restarts; seed a new buffer with
+//! `SequenceGenerator::resume_from` the highest number they have seen.
+
+use crate::error::FsmError;
+use crate::grant::{Grant, GrantStatus};
+use crate::proposal::{Proposal, ProposalStatus};
+use crate::sequence::SequenceGenerator;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Observable governance state change
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GovernanceEvent {
+    /// Proposal moved between statuses
+    ProposalStatusChanged {
+        proposal_id: u64,
+        from: ProposalStatus,
+        to: ProposalStatus,
+        at: i64,
+    },
+    /// Votes of a superseded proposal were copied into its successor
+    ProposalVotesMerged {
+        proposal_id: u64,
+        from_proposal_id: u64,
+        votes_copied: u64,
+        at: i64,
+    },
+    /// Grant moved between statuses
+    GrantStatusChanged {
+        grant_id: u64,
+        from: GrantStatus,
+        to: GrantStatus,
+        at: i64,
+    },
+    /// Funds were disbursed from a grant
+    GrantDisbursed {
+        grant_id: u64,
+        amount: u64,
+        disbursed_before: u64,
+        disbursed_after: u64,
+        at: i64,
+    },
+}
+
+/// An event with the sequence number it was appended under
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    pub event: GovernanceEvent,
+}
+
+/// Buffer of events in insertion order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventBuffer {
+    events: VecDeque<SequencedEvent>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    sequence: SequenceGenerator,
+}
+
+impl EventBuffer {
+    /// Create an empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number events from `sequence`, e.g. `SequenceGenerator::resume_from` after a restart
+    ///
+    /// Pass clones of one generator to logs that need a single order across them.
+    pub fn with_sequence(mut self, sequence: SequenceGenerator) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Handle on the generator numbering the events; persist it with the drained events
+    pub fn sequence_generator(&self) -> SequenceGenerator {
+        self.sequence.clone()
+    }
+
+    /// Append an event and return its sequence; `Overflow` once the generator is exhausted
+    pub fn push(&mut self, event: GovernanceEvent) -> Result<u64, FsmError> {
+        let sequence = self.sequence.issue()?;
+        self.events.push_back(SequencedEvent { sequence, event });
+        Ok(sequence)
+    }
+
+    /// Number of buffered events
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Buffered events, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &GovernanceEvent> {
+        self.events.iter().map(|sequenced| &sequenced.event)
+    }
+
+    /// Buffered events with their sequences, oldest first
+    pub fn iter_sequenced(&self) -> impl Iterator<Item = &SequencedEvent> {
+        self.events.iter()
+    }
+
+    /// Remove and return all events, oldest first
+    pub fn drain(&mut self) -> Vec<GovernanceEvent> {
+        self.events
+            .drain(..)
+            .map(|sequenced| sequenced.event)
+            .collect()
+    }
+
+    /// Remove and return all events with their sequences, oldest first
+    pub fn drain_sequenced(&mut self) -> Vec<SequencedEvent> {
+        self.events.drain(..).collect()
+    }
+
+    /// Run `op` on `proposal` and record a status change event if it applied one
+    pub fn record_proposal<P, T>(
+        &mut self,
+        proposal: &mut Proposal<P>,
+        at: i64,
+        op: impl FnOnce(&mut Proposal<P>) -> Result<T, FsmError>,
+    ) -> Result<T, FsmError> {
+        let from = proposal.status.clone();
+        let output = op(proposal)?;
+        if proposal.status != from {
+            self.push(GovernanceEvent::ProposalStatusChanged {
+                proposal_id: proposal.id,
+                from,
+                to: proposal.status.clone(),
+                at,
+            })?;
+        }
+        Ok(output)
+    }
+
+    /// Run `op` on `grant` and record disbursement and status change events
+    ///
+    /// A disbursement that completes the grant yields `GrantDisbursed` followed
+    /// by `GrantStatusChanged`.
+    pub fn record_grant<T>(
+        &mut self,
+        grant: &mut Grant,
+        at: i64,
+        op: impl FnOnce(&mut Grant) -> Result<T, FsmError>,
+    ) -> Result<T, FsmError> {
+        let from = grant.status;
+        let disbursed_before = grant.disbursed_amount;
+        let output = op(grant)?;
+        if grant.disbursed_amount > disbursed_before {
+            self.push(GovernanceEvent::GrantDisbursed {
+                grant_id: grant.id,
+                amount: grant.disbursed_amount - disbursed_before,
+                disbursed_before,
+                disbursed_after: grant.disbursed_amount,
+                at,
+            })?;
+        }
+        if grant.status != from {
+            self.push(GovernanceEvent::GrantStatusChanged {
+                grant_id: grant.id,
+                from,
+                to: grant.status,
+                at,
+            })?;
+        }
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+
+    fn status_changed(from: ProposalStatus, to: ProposalStatus, at: i64) -> GovernanceEvent {
+        GovernanceEvent::ProposalStatusChanged {
+            proposal_id: 1,
+            from,
+            to,
+            at,
+        }
+    }
+
+    #[test]
+    fn test_full_proposal_lifecycle_events() {
+        let mut buffer = EventBuffer::new();
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+
+        buffer
+            .record_proposal(&mut proposal, 0, |p| p.activate_with_time(1, 10, 0))
+            .unwrap();
+        buffer
+            .record_proposal(&mut proposal, 100, |p| p.pass_with_time(100))
+            .unwrap();
+        let ticket = buffer
+            .record_proposal(&mut proposal, 110, |p| p.prepare_execute(110))
+            .unwrap();
+        buffer
+            .record_proposal(&mut proposal, 120, |p| p.commit_execute(&ticket, 120))
+            .unwrap();
+        // Failed operations leave no event
+        assert!(
+            buffer
+                .record_proposal(&mut proposal, 125, |p| p.execute_with_time(125))
+                .is_err()
+        );
+        buffer
+            .record_proposal(&mut proposal, 130, |p| p.archive_with_time(130))
+            .unwrap();
+
+        use ProposalStatus::*;
+        assert_eq!(
+            buffer.drain(),
+            vec![
+                status_changed(Draft, Active, 0),
+                status_changed(Active, Passed, 100),
+                status_changed(Passed, Executing, 110),
+                status_changed(Executing, Executed, 120),
+                status_changed(Executed, Archived, 130),
+            ]
+        );
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_grant_disbursement_events() {
+        let mut buffer = EventBuffer::new();
+        let mut grant = Grant::new(
+            5,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1000,
+            0,
+            0,
+        )
+        .unwrap();
+
+        buffer.record_grant(&mut grant, 1, |g| g.approve()).unwrap();
+        buffer
+            .record_grant(&mut grant, 2, |g| g.activate())
+            .unwrap();
+        buffer
+            .record_grant(&mut grant, 3, |g| g.disburse(400))
+            .unwrap();
+        buffer
+            .record_grant(&mut grant, 4, |g| g.disburse(600))
+            .unwrap();
+
+        assert_eq!(
+            buffer.drain(),
+            vec![
+                GovernanceEvent::GrantStatusChanged {
+                    grant_id: 5,
+                    from: GrantStatus::Pending,
+                    to: GrantStatus::Approved,
+                    at: 1,
+                },
+                GovernanceEvent::GrantStatusChanged {
+                    grant_id: 5,
+                    from: GrantStatus::Approved,
+                    to: GrantStatus::Active,
+                    at: 2,
+                },
+                GovernanceEvent::GrantDisbursed {
+                    grant_id: 5,
+                    amount: 400,
+                    disbursed_before: 0,
+                    disbursed_after: 400,
+                    at: 3,
+                },
+                GovernanceEvent::GrantDisbursed {
+                    grant_id: 5,
+                    amount: 600,
+                    disbursed_before: 400,
+                    disbursed_after: 1000,
+                    at: 4,
+                },
+                GovernanceEvent::GrantStatusChanged {
+                    grant_id: 5,
+                    from: GrantStatus::Active,
+                    to: GrantStatus::Completed,
+                    at: 4,
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_event_serde_roundtrip() {
+        let mut buffer = EventBuffer::new();
+        buffer
+            .push(status_changed(
+                ProposalStatus::Draft,
+                ProposalStatus::Active,
+                10,
+            ))
+            .unwrap();
+        let json = serde_json::to_string(&buffer).unwrap();
+        let restored: EventBuffer = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, buffer);
+    }
+
+    #[test]
+    fn test_sequences_survive_restart() {
+        let mut buffer = EventBuffer::new();
+        let first = status_changed(ProposalStatus::Draft, ProposalStatus::Active, 10);
+        let second = status_changed(ProposalStatus::Active, ProposalStatus::Passed, 20);
+        assert_eq!(buffer.push(first.clone()), Ok(1));
+        assert_eq!(buffer.push(second.clone()), Ok(2));
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), vec![&first, &second]);
+        let delivered = buffer.drain_sequenced();
+        assert_eq!(
+            delivered,
+            vec![
+                SequencedEvent {
+                    sequence: 1,
+                    event: first.clone(),
+                },
+                SequencedEvent {
+                    sequence: 2,
+                    event: second,
+                },
+            ]
+        );
+        // Draining keeps numbering where it was
+        assert_eq!(buffer.push(first.clone()), Ok(3));
+
+        let max_seen = 3;
+        let mut restarted =
+            EventBuffer::new().with_sequence(SequenceGenerator::resume_from(max_seen));
+        assert_eq!(restarted.push(first), Ok(4));
+        assert_eq!(restarted.sequence_generator().last(), 4);
+    }
+}
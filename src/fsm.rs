@@ -1,11 +1,18 @@
 //! Finite State Machine (FSM) for Idea and Grant state transitions
 //!
-//! This module provides formal validation of state transitions for ideas and grants.
+//! This module provides formal validation of state transitions for ideas, grants and proposals.
 //! Each state defines which states it can transition to, ensuring valid state changes.
 
-use crate::enums::IdeaStatus;
+use crate::enums::{IdeaFsmProfile, IdeaStatus};
 use crate::error::FsmError;
+#[cfg(feature = "governance-meta")]
+use crate::governance::security_board::SecurityBoardDecisionStatus;
+#[cfg(feature = "governance-meta")]
+use crate::governance::security_committees::CommitteeDecisionStatus;
 use crate::grant::types::GrantStatus;
+use crate::metrics;
+use crate::proposal::ProposalStatus;
+use crate::proposal::amendment::AmendmentStatus;
 
 /// FSM implementation for IdeaStatus
 impl IdeaStatus {
@@ -29,19 +36,44 @@ impl IdeaStatus {
         }
     }
 
+    /// Valid next states under `profile`; `Standard` is `next_states`
+    pub fn next_states_for(&self, profile: IdeaFsmProfile) -> &'static [IdeaStatus] {
+        use IdeaStatus::*;
+        match (profile, self) {
+            (IdeaFsmProfile::StrictTerminal, Rejected) => &[Archived],
+            (IdeaFsmProfile::StrictTerminal, Archived) => &[],
+            _ => self.next_states(),
+        }
+    }
+
     /// Check if transition from current state to target state is valid
     pub fn can_transition_to(&self, target: IdeaStatus) -> bool {
+        self.can_transition_to_in(IdeaFsmProfile::Standard, target)
+    }
+
+    /// `can_transition_to` under `profile`
+    pub fn can_transition_to_in(&self, profile: IdeaFsmProfile, target: IdeaStatus) -> bool {
         // Same state is always valid (no-op)
         if *self == target {
             return true;
         }
 
-        self.next_states().contains(&target)
+        self.next_states_for(profile).contains(&target)
     }
 
     /// Validate transition and return error if invalid
     pub fn validate_transition(&self, target: IdeaStatus) -> Result<(), FsmError> {
-        if !self.can_transition_to(target) {
+        self.validate_transition_in(IdeaFsmProfile::Standard, target)
+    }
+
+    /// `validate_transition` under `profile`
+    pub fn validate_transition_in(
+        &self,
+        profile: IdeaFsmProfile,
+        target: IdeaStatus,
+    ) -> Result<(), FsmError> {
+        if !self.can_transition_to_in(profile, target) {
+            metrics::record_validation_failure("idea", self, &target);
             return Err(FsmError::InvalidStateTransition);
         }
         Ok(())
@@ -58,7 +90,7 @@ impl GrantStatus {
             Approved => &[Active, Suspended],
             Active => &[Completed, Cancelled, Suspended, Expired],
             Suspended => &[Active, Cancelled],
-            Completed => &[Archived],
+            Completed => &[Archived, Suspended],
             Cancelled => &[Archived],
             Rejected => &[Archived],
             Expired => &[Archived],
@@ -79,6 +111,170 @@ impl GrantStatus {
     /// Validate transition and return error if invalid
     pub fn validate_transition(&self, target: GrantStatus) -> Result<(), FsmError> {
         if !self.can_transition_to(target) {
+            metrics::record_validation_failure("grant", self, &target);
+            return Err(FsmError::InvalidStateTransition);
+        }
+        Ok(())
+    }
+}
+
+/// FSM implementation for ProposalStatus
+///
+/// Mirrors the transitions applied by the proposal lifecycle methods.
+impl ProposalStatus {
+    /// Get all valid next states from current state
+    pub fn next_states(&self) -> &'static [ProposalStatus] {
+        use ProposalStatus::*;
+        match self {
+            Draft => &[Active, Cancelled],
+            Active => &[Passed, Rejected, Tied, QuorumFailed, Cancelled],
+            Passed => &[Executing, Executed, Cancelled],
+            Executing => &[Executed, Passed],
+            Rejected => &[Archived],
+            Executed => &[Archived],
+            Cancelled => &[Archived],
+            QuorumFailed => &[Archived],
+            Tied => &[],
+            Archived => &[], // Terminal state
+        }
+    }
+
+    /// Check if transition from current state to target state is valid
+    pub fn can_transition_to(&self, target: &ProposalStatus) -> bool {
+        // Same state is always valid (no-op)
+        if self == target {
+            return true;
+        }
+
+        self.next_states().contains(target)
+    }
+
+    /// Validate transition and return error if invalid
+    pub fn validate_transition(&self, target: &ProposalStatus) -> Result<(), FsmError> {
+        if !self.can_transition_to(target) {
+            metrics::record_validation_failure("proposal", self, target);
+            return Err(FsmError::InvalidStateTransition);
+        }
+        Ok(())
+    }
+}
+
+/// FSM implementation for AmendmentStatus
+///
+/// Accepted, Rejected and Withdrawn are final.
+impl AmendmentStatus {
+    /// Get all valid next states from current state
+    pub fn next_states(&self) -> &'static [AmendmentStatus] {
+        use AmendmentStatus::*;
+        match self {
+            Proposed => &[Voting, Withdrawn],
+            Voting => &[Accepted, Rejected, Withdrawn],
+            Accepted | Rejected | Withdrawn => &[], // Terminal states
+        }
+    }
+
+    /// Whether no further transitions are possible
+    pub fn is_terminal(&self) -> bool {
+        self.next_states().is_empty()
+    }
+
+    /// Check if transition from current state to target state is valid
+    pub fn can_transition_to(&self, target: AmendmentStatus) -> bool {
+        // Same state is always valid (no-op)
+        if *self == target {
+            return true;
+        }
+
+        self.next_states().contains(&target)
+    }
+
+    /// Validate transition and return error if invalid
+    pub fn validate_transition(&self, target: AmendmentStatus) -> Result<(), FsmError> {
+        if !self.can_transition_to(target) {
+            metrics::record_validation_failure("proposal_amendment", self, &target);
+            return Err(FsmError::InvalidStateTransition);
+        }
+        Ok(())
+    }
+}
+
+/// FSM implementation for SecurityBoardDecisionStatus
+///
+/// Deferred decisions return to Pending; Approved and Rejected are final.
+#[cfg(feature = "governance-meta")]
+impl SecurityBoardDecisionStatus {
+    /// Get all valid next states from current state
+    pub fn next_states(&self) -> &'static [SecurityBoardDecisionStatus] {
+        use SecurityBoardDecisionStatus::*;
+        match self {
+            Pending => &[Approved, Rejected, Deferred],
+            Deferred => &[Pending],
+            Approved => &[], // Terminal state
+            Rejected => &[], // Terminal state
+        }
+    }
+
+    /// Whether no further transitions are possible
+    pub fn is_terminal(&self) -> bool {
+        self.next_states().is_empty()
+    }
+
+    /// Check if transition from current state to target state is valid
+    pub fn can_transition_to(&self, target: SecurityBoardDecisionStatus) -> bool {
+        // Same state is always valid (no-op)
+        if *self == target {
+            return true;
+        }
+
+        self.next_states().contains(&target)
+    }
+
+    /// Validate transition and return error if invalid
+    pub fn validate_transition(&self, target: SecurityBoardDecisionStatus) -> Result<(), FsmError> {
+        if !self.can_transition_to(target) {
+            metrics::record_validation_failure("security_board_decision", self, &target);
+            return Err(FsmError::InvalidStateTransition);
+        }
+        Ok(())
+    }
+}
+
+/// FSM implementation for CommitteeDecisionStatus
+///
+/// Escalated decisions are settled by the security board; Approved and
+/// Rejected are final.
+#[cfg(feature = "governance-meta")]
+impl CommitteeDecisionStatus {
+    /// Get all valid next states from current state
+    pub fn next_states(&self) -> &'static [CommitteeDecisionStatus] {
+        use CommitteeDecisionStatus::*;
+        match self {
+            Pending => &[Approved, Rejected, Escalated],
+            Escalated => &[Approved, Rejected],
+            Approved => &[], // Terminal state
+            Rejected => &[], // Terminal state
+        }
+    }
+
+    /// Whether no further transitions are possible
+    pub fn is_terminal(&self) -> bool {
+        self.next_states().is_empty()
+    }
+
+    /// Check if transition from current state to target state is valid
+    pub fn can_transition_to(&self, target: CommitteeDecisionStatus) -> bool {
+        // Same state is always valid (no-op)
+        if *self == target {
+            return true;
+        }
+
+        self.next_states().contains(&target)
+    }
+
+    /// Validate transition and return error if invalid
+    pub fn validate_transition(&self, target: CommitteeDecisionStatus) -> Result<(), FsmError> {
+        if !self.can_transition_to(target) {
+            metrics::record_validation_failure("committee_decision", self, &target);
             return Err(FsmError::InvalidStateTransition);
         }
         Ok(())
@@ -231,6 +427,53 @@ mod tests {
         assert_eq!(next.len(), 1);
     }
 
+    #[test]
+    fn test_idea_fsm_profiles() {
+        use IdeaFsmProfile::*;
+        let all_statuses = [
+            IdeaStatus::Draft,
+            IdeaStatus::UnderReview,
+            IdeaStatus::Voting,
+            IdeaStatus::Approved,
+            IdeaStatus::Rejected,
+            IdeaStatus::InProgress,
+            IdeaStatus::Paused,
+            IdeaStatus::Completed,
+            IdeaStatus::Executed,
+            IdeaStatus::Commercialization,
+            IdeaStatus::Archived,
+            IdeaStatus::Resubmitted,
+            IdeaStatus::Expired,
+        ];
+        let into_resubmitted = |profile| {
+            all_statuses
+                .iter()
+                .filter(|status| {
+                    **status != IdeaStatus::Resubmitted
+                        && status.can_transition_to_in(profile, IdeaStatus::Resubmitted)
+                })
+                .count()
+        };
+        assert_eq!(into_resubmitted(Standard), 2);
+        assert_eq!(into_resubmitted(StrictTerminal), 0);
+        assert!(
+            IdeaStatus::Archived
+                .next_states_for(StrictTerminal)
+                .is_empty()
+        );
+        assert_eq!(
+            IdeaStatus::Rejected.next_states_for(StrictTerminal),
+            &[IdeaStatus::Archived]
+        );
+        assert_eq!(
+            IdeaStatus::Archived.validate_transition_in(StrictTerminal, IdeaStatus::Resubmitted),
+            Err(FsmError::InvalidStateTransition)
+        );
+        for status in all_statuses {
+            assert_eq!(status.next_states_for(Standard), status.next_states());
+        }
+    }
+
     #[test]
     fn test_idea_fsm_commercialization_transitions() {
         let next = IdeaStatus::Commercialization.next_states();
@@ -268,7 +511,9 @@ mod tests {
     fn test_grant_fsm_completed_transitions() {
         let next = GrantStatus::Completed.next_states();
         assert!(next.contains(&GrantStatus::Archived));
-        assert_eq!(next.len(), 1);
+        // Upheld disputes suspend for clawback
+        assert!(next.contains(&GrantStatus::Suspended));
+        assert_eq!(next.len(), 2);
     }
 
     #[test]
@@ -760,4 +1005,112 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_proposal_fsm_transitions() {
+        assert!(ProposalStatus::Draft.can_transition_to(&ProposalStatus::Active));
+        assert!(ProposalStatus::Passed.can_transition_to(&ProposalStatus::Executing));
+        assert!(ProposalStatus::Executing.can_transition_to(&ProposalStatus::Executed));
+        assert!(ProposalStatus::Executing.can_transition_to(&ProposalStatus::Passed));
+        assert!(!ProposalStatus::Executing.can_transition_to(&ProposalStatus::Cancelled));
+        assert!(ProposalStatus::Passed.can_transition_to(&ProposalStatus::Cancelled));
+        assert!(!ProposalStatus::Draft.can_transition_to(&ProposalStatus::Executed));
+        assert!(ProposalStatus::Archived.next_states().is_empty());
+        assert!(ProposalStatus::Active.can_transition_to(&ProposalStatus::QuorumFailed));
+        assert_eq!(
+            ProposalStatus::QuorumFailed.next_states(),
+            &[ProposalStatus::Archived]
+        );
+    }
+
+    #[test]
+    fn test_proposal_fsm_validate_transition() {
+        assert!(
+            ProposalStatus::Active
+                .validate_transition(&ProposalStatus::Passed)
+                .is_ok()
+        );
+        assert_eq!(
+            ProposalStatus::Active.validate_transition(&ProposalStatus::Executing),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn test_amendment_fsm_all_transitions() {
+        use AmendmentStatus::*;
+        let all = [Proposed, Voting, Accepted, Rejected, Withdrawn];
+        let allowed = [
+            (Proposed, Voting),
+            (Proposed, Withdrawn),
+            (Voting, Accepted),
+            (Voting, Rejected),
+            (Voting, Withdrawn),
+        ];
+        for from in all {
+            for to in all {
+                let expected = from == to || allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), expected, "{from:?} -> {to:?}");
+                assert_eq!(from.validate_transition(to).is_ok(), expected);
+            }
+            assert_eq!(from.is_terminal(), !matches!(from, Proposed | Voting));
+        }
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_security_board_decision_fsm_all_transitions() {
+        use SecurityBoardDecisionStatus::*;
+        let all = [Pending, Approved, Rejected, Deferred];
+        let allowed = [
+            (Pending, Approved),
+            (Pending, Rejected),
+            (Pending, Deferred),
+            (Deferred, Pending),
+        ];
+        for from in all {
+            for to in all {
+                let expected = from == to || allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), expected, "{from:?} -> {to:?}");
+                assert_eq!(from.validate_transition(to).is_ok(), expected);
+            }
+        }
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_committee_decision_fsm_all_transitions() {
+        use CommitteeDecisionStatus::*;
+        let all = [Pending, Approved, Rejected, Escalated];
+        let allowed = [
+            (Pending, Approved),
+            (Pending, Rejected),
+            (Pending, Escalated),
+            (Escalated, Approved),
+            (Escalated, Rejected),
+        ];
+        for from in all {
+            for to in all {
+                let expected = from == to || allowed.contains(&(from, to));
+                assert_eq!(from.can_transition_to(to), expected, "{from:?} -> {to:?}");
+                assert_eq!(from.validate_transition(to).is_ok(), expected);
+            }
+            assert_eq!(from.is_terminal(), matches!(from, Approved | Rejected));
+        }
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_security_board_decision_fsm_terminal_states() {
+        assert!(SecurityBoardDecisionStatus::Approved.is_terminal());
+        assert!(SecurityBoardDecisionStatus::Rejected.is_terminal());
+        assert!(!SecurityBoardDecisionStatus::Pending.is_terminal());
+        assert!(!SecurityBoardDecisionStatus::Deferred.is_terminal());
+        assert_eq!(
+            SecurityBoardDecisionStatus::Rejected
+                .validate_transition(SecurityBoardDecisionStatus::Pending)
+                .unwrap_err(),
+            FsmError::InvalidStateTransition
+        );
+    }
 }
@@ -8,9 +8,10 @@
 use crate::error::FsmError;
 
 /// Analytics type
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceAnalyticsType {
     /// Participation analytics
+    #[default]
     Participation,
     /// Voting analytics
     Voting,
@@ -21,9 +22,10 @@ pub enum GovernanceAnalyticsType {
 }
 
 /// Analytics status
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceAnalyticsStatus {
     /// Analytics active
+    #[default]
     Active,
     /// Analytics paused
     Paused,
@@ -32,7 +34,7 @@ pub enum GovernanceAnalyticsStatus {
 }
 
 /// Governance analytics metadata (on-chain)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct GovernanceAnalyticsMetadata {
     /// Analytics ID
     pub analytics_id: u64,
@@ -48,10 +50,38 @@ pub struct GovernanceAnalyticsMetadata {
     pub analytics_config_hash: [u8; 32],
 }
 
+impl GovernanceAnalyticsMetadata {
+    /// Create initialized governance analytics metadata
+    pub fn initialize(
+        analytics_id: u64,
+        governance_id: u64,
+        analytics_type: GovernanceAnalyticsType,
+        analytics_config_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if analytics_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            analytics_id,
+            governance_id,
+            analytics_type,
+            status: GovernanceAnalyticsStatus::Active,
+            created_at: current_time,
+            analytics_config_hash,
+        })
+    }
+}
+
 /// On-chain functions
 pub mod onchain {
     use super::*;
+    use crate::governance::ensure_uninitialized;
 
+    /// Initialize in place (zero-copy accounts); see `GovernanceAnalyticsMetadata::initialize`
+    ///
+    /// `AlreadyInitialized` if `analytics` already has a non-zero `analytics_id`; use
+    /// `reinitialize_governance_analytics` to overwrite it deliberately.
     pub fn initialize_governance_analytics(
         analytics: &mut GovernanceAnalyticsMetadata,
         analytics_id: u64,
@@ -60,15 +90,33 @@ pub mod onchain {
         analytics_config_hash: [u8; 32],
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if analytics_id == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-        analytics.analytics_id = analytics_id;
-        analytics.governance_id = governance_id;
-        analytics.analytics_type = analytics_type;
-        analytics.status = GovernanceAnalyticsStatus::Active;
-        analytics.created_at = current_time;
-        analytics.analytics_config_hash = analytics_config_hash;
+        ensure_uninitialized(analytics.analytics_id)?;
+        reinitialize_governance_analytics(
+            analytics,
+            analytics_id,
+            governance_id,
+            analytics_type,
+            analytics_config_hash,
+            current_time,
+        )
+    }
+
+    /// `initialize_governance_analytics` without the already-initialized check; overwrites `analytics`
+    pub fn reinitialize_governance_analytics(
+        analytics: &mut GovernanceAnalyticsMetadata,
+        analytics_id: u64,
+        governance_id: u64,
+        analytics_type: GovernanceAnalyticsType,
+        analytics_config_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *analytics = GovernanceAnalyticsMetadata::initialize(
+            analytics_id,
+            governance_id,
+            analytics_type,
+            analytics_config_hash,
+            current_time,
+        )?;
         Ok(())
     }
 }
@@ -148,26 +196,15 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_analytics() {
-        let mut analytics = GovernanceAnalyticsMetadata {
-            analytics_id: 0,
-            governance_id: 0,
-            analytics_type: GovernanceAnalyticsType::Participation,
-            status: GovernanceAnalyticsStatus::Active,
-            created_at: 0,
-            analytics_config_hash: [0u8; 32],
-        };
-
         let config_hash = [1u8; 32];
-        let result = onchain::initialize_governance_analytics(
-            &mut analytics,
+        let analytics = GovernanceAnalyticsMetadata::initialize(
             100,
             200,
             GovernanceAnalyticsType::Voting,
             config_hash,
             5000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_id, 100);
         assert_eq!(analytics.governance_id, 200);
         assert_eq!(analytics.analytics_type, GovernanceAnalyticsType::Voting);
@@ -178,11 +215,9 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_analytics_invalid_id() {
-        let mut analytics = create_test_analytics();
         let config_hash = [1u8; 32];
 
-        let result = onchain::initialize_governance_analytics(
-            &mut analytics,
+        let result = GovernanceAnalyticsMetadata::initialize(
             0, // Invalid: analytics_id must be > 0
             200,
             GovernanceAnalyticsType::Voting,
@@ -203,61 +238,38 @@ mod tests {
         ];
 
         for analytics_type in types {
-            let mut analytics = GovernanceAnalyticsMetadata {
-                analytics_id: 0,
-                governance_id: 0,
-                analytics_type: GovernanceAnalyticsType::Participation,
-                status: GovernanceAnalyticsStatus::Active,
-                created_at: 0,
-                analytics_config_hash: [0u8; 32],
-            };
-
-            let result = onchain::initialize_governance_analytics(
-                &mut analytics,
-                1,
-                1,
-                analytics_type,
-                [0u8; 32],
-                1000,
-            );
-
-            assert!(result.is_ok());
+            let analytics =
+                GovernanceAnalyticsMetadata::initialize(1, 1, analytics_type, [0u8; 32], 1000)
+                    .unwrap();
             assert_eq!(analytics.analytics_type, analytics_type);
         }
     }
 
     #[test]
     fn test_governance_analytics_config_hash() {
-        let mut analytics = create_test_analytics();
         let custom_hash = [42u8; 32];
 
-        let result = onchain::initialize_governance_analytics(
-            &mut analytics,
+        let analytics = GovernanceAnalyticsMetadata::initialize(
             1,
             1,
             GovernanceAnalyticsType::Custom,
             custom_hash,
             2000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_config_hash, custom_hash);
     }
 
     #[test]
     fn test_governance_analytics_timestamp() {
-        let mut analytics = create_test_analytics();
-
-        let result = onchain::initialize_governance_analytics(
-            &mut analytics,
+        let analytics = GovernanceAnalyticsMetadata::initialize(
             1,
             1,
             GovernanceAnalyticsType::Proposal,
             [0u8; 32],
             9999,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.created_at, 9999);
     }
 
@@ -266,7 +278,7 @@ mod tests {
         let mut analytics = create_test_analytics();
         analytics.status = GovernanceAnalyticsStatus::Paused;
 
-        let result = onchain::initialize_governance_analytics(
+        let result = onchain::reinitialize_governance_analytics(
             &mut analytics,
             1,
             1,
@@ -345,18 +357,14 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_analytics_large_ids() {
-        let mut analytics = create_test_analytics();
-
-        let result = onchain::initialize_governance_analytics(
-            &mut analytics,
+        let analytics = GovernanceAnalyticsMetadata::initialize(
             u64::MAX,
             u64::MAX,
             GovernanceAnalyticsType::Custom,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_id, u64::MAX);
         assert_eq!(analytics.governance_id, u64::MAX);
     }
@@ -373,7 +381,7 @@ mod tests {
         };
 
         let new_hash = [2u8; 32];
-        let result = onchain::initialize_governance_analytics(
+        let result = onchain::reinitialize_governance_analytics(
             &mut analytics,
             1,
             2,
@@ -426,4 +434,31 @@ mod tests {
         assert_eq!(result1, Vec::<u8>::new());
         assert_eq!(result2, Vec::<u8>::new());
     }
+
+    #[test]
+    fn test_initialize_governance_analytics_rejects_initialized() {
+        let mut analytics = create_test_analytics();
+        assert_eq!(
+            onchain::initialize_governance_analytics(
+                &mut analytics,
+                2,
+                2,
+                GovernanceAnalyticsType::Voting,
+                [0u8; 32],
+                2000
+            ),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(analytics, create_test_analytics());
+        onchain::reinitialize_governance_analytics(
+            &mut analytics,
+            2,
+            2,
+            GovernanceAnalyticsType::Voting,
+            [0u8; 32],
+            2000,
+        )
+        .unwrap();
+        assert_eq!(analytics.created_at, 2000);
+    }
 }
@@ -0,0 +1,361 @@
+//! Weighted security board voting
+//!
+//! A `BoardDecisionRecord` collects the ballots of board members on one
+//! `SecurityBoardDecisionMetadata` and settles it. Each ballot is weighted by
+//! the member's role under a `RoleWeightPolicy` (by default the Chairperson
+//! counts twice and Advisors not at all). Abstentions are recorded but not
+//! counted, and the threshold applies to the approve and reject weight only.
+//! Zero-weight ballots, such as an Advisor's, stay in the record as advisory.
+//!
+//! The weight policy is fixed once the first ballot is cast.
+
+use super::security_board::{
+    SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus, SecurityBoardMemberMetadata,
+    SecurityBoardMemberRole,
+};
+use crate::error::FsmError;
+use crate::metrics;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Ballot weight of each board role
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoleWeightPolicy {
+    pub chairperson: u64,
+    pub member: u64,
+    pub advisor: u64,
+}
+
+impl Default for RoleWeightPolicy {
+    /// Chairperson 2, Member 1, Advisor 0 (advisory only)
+    fn default() -> Self {
+        Self {
+            chairperson: 2,
+            member: 1,
+            advisor: 0,
+        }
+    }
+}
+
+impl RoleWeightPolicy {
+    pub fn weight(&self, role: SecurityBoardMemberRole) -> u64 {
+        match role {
+            SecurityBoardMemberRole::Chairperson => self.chairperson,
+            SecurityBoardMemberRole::Member => self.member,
+            SecurityBoardMemberRole::Advisor => self.advisor,
+        }
+    }
+
+    /// `InvalidInput` if every role weighs 0
+    pub fn validate(&self) -> Result<(), FsmError> {
+        if self.chairperson == 0 && self.member == 0 && self.advisor == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+}
+
+/// A board member's choice
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BoardVote {
+    Approve,
+    Reject,
+    /// Recorded, not counted
+    Abstain,
+}
+
+/// One member's ballot as recorded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoardBallot {
+    pub member_id: u64,
+    /// Role when the ballot was cast
+    pub role: SecurityBoardMemberRole,
+    pub vote: BoardVote,
+    /// Weight under the record's policy; 0 for an advisory ballot
+    pub weight: u64,
+    pub cast_at: i64,
+}
+
+/// Weighted totals of a record's ballots
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BoardTally {
+    pub approve: u64,
+    pub reject: u64,
+    /// Abstaining ballots
+    pub abstentions: usize,
+    /// Zero-weight ballots, whatever their vote
+    pub advisory: usize,
+}
+
+/// Ballots on one board decision (see the module docs)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoardDecisionRecord<P> {
+    pub decision: SecurityBoardDecisionMetadata<P>,
+    /// Approve weight must exceed this percentage of approve + reject weight
+    pub threshold_percent: u8,
+    weights: RoleWeightPolicy,
+    ballots: Vec<BoardBallot>,
+}
+
+impl<P> BoardDecisionRecord<P> {
+    /// Collect ballots on `decision` under the default `RoleWeightPolicy`
+    ///
+    /// `InvalidInput` if `threshold_percent` exceeds 100.
+    pub fn new(
+        decision: SecurityBoardDecisionMetadata<P>,
+        threshold_percent: u8,
+    ) -> Result<Self, FsmError> {
+        if threshold_percent > 100 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            decision,
+            threshold_percent,
+            weights: RoleWeightPolicy::default(),
+            ballots: Vec::new(),
+        })
+    }
+
+    pub fn weight_policy(&self) -> RoleWeightPolicy {
+        self.weights
+    }
+
+    /// Replace the weight policy
+    ///
+    /// `InvalidState` once a ballot has been cast; `InvalidInput` if the
+    /// policy is invalid.
+    pub fn set_weight_policy(&mut self, policy: RoleWeightPolicy) -> Result<(), FsmError> {
+        if !self.ballots.is_empty() {
+            return Err(FsmError::InvalidState);
+        }
+        policy.validate()?;
+        self.weights = policy;
+        Ok(())
+    }
+
+    /// Ballots in casting order, advisory ones included
+    pub fn ballots(&self) -> &[BoardBallot] {
+        &self.ballots
+    }
+
+    /// Record `member`'s ballot and count the decision as one they took part in
+    ///
+    /// `InvalidInput` for an uninitialized member (id 0); `InvalidState` if
+    /// the decision is not Pending or the member already voted.
+    pub fn cast<Q>(
+        &mut self,
+        member: &mut SecurityBoardMemberMetadata<Q>,
+        vote: BoardVote,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let result = self.try_cast(member, vote, current_time);
+        metrics::record_vote("security_board_decision", "cast", &result);
+        result
+    }
+
+    fn try_cast<Q>(
+        &mut self,
+        member: &mut SecurityBoardMemberMetadata<Q>,
+        vote: BoardVote,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if member.member_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.decision.status != SecurityBoardDecisionStatus::Pending
+            || self
+                .ballots
+                .iter()
+                .any(|ballot| ballot.member_id == member.member_id)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        self.ballots.push(BoardBallot {
+            member_id: member.member_id,
+            role: member.role,
+            vote,
+            weight: self.weights.weight(member.role),
+            cast_at: current_time,
+        });
+        member.decisions_participated = member.decisions_participated.saturating_add(1);
+        member.last_active_at = member.last_active_at.max(current_time);
+        Ok(())
+    }
+
+    /// Weighted approve and reject totals; `Overflow` past `u64::MAX`
+    pub fn tally(&self) -> Result<BoardTally, FsmError> {
+        let mut tally = BoardTally::default();
+        for ballot in &self.ballots {
+            if ballot.weight == 0 {
+                tally.advisory += 1;
+            }
+            let total = match ballot.vote {
+                BoardVote::Approve => &mut tally.approve,
+                BoardVote::Reject => &mut tally.reject,
+                BoardVote::Abstain => {
+                    tally.abstentions += 1;
+                    continue;
+                }
+            };
+            *total = total.checked_add(ballot.weight).ok_or(FsmError::Overflow)?;
+        }
+        Ok(tally)
+    }
+
+    /// Approve the decision if approve weight is more than `threshold_percent`
+    /// of approve + reject weight, and reject it otherwise
+    ///
+    /// With no counted weight the decision is rejected. Fails like
+    /// `SecurityBoardDecisionMetadata::approve` if the decision is not Pending.
+    pub fn finalize(&mut self, current_time: i64) -> Result<SecurityBoardDecisionStatus, FsmError> {
+        let tally = self.tally()?;
+        let decided = u128::from(tally.approve) + u128::from(tally.reject);
+        if decided > 0
+            && u128::from(tally.approve) * 100 > u128::from(self.threshold_percent) * decided
+        {
+            self.decision.approve(current_time)?;
+        } else {
+            self.decision.reject(current_time)?;
+        }
+        Ok(self.decision.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_member(
+        member_id: u64,
+        role: SecurityBoardMemberRole,
+    ) -> SecurityBoardMemberMetadata<u8> {
+        SecurityBoardMemberMetadata::initialize(member_id, member_id as u8, role, 1000).unwrap()
+    }
+
+    fn create_test_record() -> BoardDecisionRecord<u8> {
+        let decision =
+            SecurityBoardDecisionMetadata::initialize(1, Some(100), [0u8; 32], 1000).unwrap();
+        BoardDecisionRecord::new(decision, 50).unwrap()
+    }
+
+    /// Chair approves, a member rejects, another abstains, an advisor rejects
+    fn cast_board(record: &mut BoardDecisionRecord<u8>) {
+        let ballots = [
+            (1, SecurityBoardMemberRole::Chairperson, BoardVote::Approve),
+            (2, SecurityBoardMemberRole::Member, BoardVote::Reject),
+            (3, SecurityBoardMemberRole::Member, BoardVote::Abstain),
+            (4, SecurityBoardMemberRole::Advisor, BoardVote::Reject),
+        ];
+        for (id, role, vote) in ballots {
+            record
+                .cast(&mut create_test_member(id, role), vote, 1100)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_chair_multiplier_decides_the_outcome() {
+        let mut weighted = create_test_record();
+        cast_board(&mut weighted);
+        assert_eq!(
+            weighted.tally(),
+            Ok(BoardTally {
+                approve: 2,
+                reject: 1,
+                abstentions: 1,
+                advisory: 1,
+            })
+        );
+        assert_eq!(
+            weighted.finalize(1200),
+            Ok(SecurityBoardDecisionStatus::Approved)
+        );
+        assert_eq!(weighted.decision.decided_at, Some(1200));
+
+        let mut flat = create_test_record();
+        flat.set_weight_policy(RoleWeightPolicy {
+            chairperson: 1,
+            ..RoleWeightPolicy::default()
+        })
+        .unwrap();
+        cast_board(&mut flat);
+        assert_eq!(
+            (flat.tally().unwrap().approve, flat.tally().unwrap().reject),
+            (1, 1)
+        );
+        assert_eq!(
+            flat.finalize(1200),
+            Ok(SecurityBoardDecisionStatus::Rejected)
+        );
+    }
+
+    #[test]
+    fn test_advisory_ballots_are_recorded_not_counted() {
+        let mut record = create_test_record();
+        let mut advisor = create_test_member(4, SecurityBoardMemberRole::Advisor);
+        record.cast(&mut advisor, BoardVote::Approve, 1100).unwrap();
+        assert_eq!(advisor.decisions_participated, 1);
+        assert_eq!(advisor.last_active_at, 1100);
+        assert_eq!(
+            record.ballots(),
+            &[BoardBallot {
+                member_id: 4,
+                role: SecurityBoardMemberRole::Advisor,
+                vote: BoardVote::Approve,
+                weight: 0,
+                cast_at: 1100,
+            }]
+        );
+        assert_eq!(record.tally().unwrap().approve, 0);
+        // Nothing counted: rejected
+        assert_eq!(
+            record.finalize(1200),
+            Ok(SecurityBoardDecisionStatus::Rejected)
+        );
+    }
+
+    #[test]
+    fn test_weight_policy_is_fixed_after_first_ballot() {
+        let mut record = create_test_record();
+        assert_eq!(
+            record.set_weight_policy(RoleWeightPolicy {
+                chairperson: 0,
+                member: 0,
+                advisor: 0,
+            }),
+            Err(FsmError::InvalidInput)
+        );
+        let mut member = create_test_member(2, SecurityBoardMemberRole::Member);
+        record.cast(&mut member, BoardVote::Approve, 1100).unwrap();
+        assert_eq!(
+            record.set_weight_policy(RoleWeightPolicy::default()),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            record.cast(&mut member, BoardVote::Reject, 1110),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            record.cast(
+                &mut SecurityBoardMemberMetadata::<u8>::default(),
+                BoardVote::Approve,
+                1110
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        record.finalize(1200).unwrap();
+        let mut late = create_test_member(3, SecurityBoardMemberRole::Member);
+        assert_eq!(
+            record.cast(&mut late, BoardVote::Approve, 1300),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            BoardDecisionRecord::new(record.decision.clone(), 101),
+            Err(FsmError::InvalidInput)
+        );
+    }
+}
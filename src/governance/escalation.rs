@@ -0,0 +1,249 @@
+//! Committee decision escalation module
+//!
+//! Hands a committee decision to the security board
+//!
+//! `escalate` opens a Pending board decision for the same proposal and marks the
+//! committee decision Escalated; once the board decides, `resolve_escalation`
+//! copies its outcome back to the committee decision.
+
+use crate::error::FsmError;
+use crate::governance::security_board::{
+    SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus,
+};
+use crate::governance::security_committees::{CommitteeDecisionMetadata, CommitteeDecisionStatus};
+
+/// Maximum length of an escalation reason
+pub const MAX_ESCALATION_REASON_LEN: usize = 200;
+
+/// Link between an escalated committee decision and its board decision
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Escalation {
+    /// Committee that escalated
+    pub from_committee_id: u64,
+    /// Escalated committee decision
+    pub decision_id: u64,
+    /// Board decision opened for it
+    pub board_decision_id: u64,
+    /// Why it was escalated
+    pub reason: String,
+    /// Escalated at
+    pub escalated_at: i64,
+    /// Board decision that settled it, once resolved
+    pub resolved_by_board_decision: Option<u64>,
+}
+
+impl Escalation {
+    /// Whether the board outcome has been synced back
+    pub fn is_resolved(&self) -> bool {
+        self.resolved_by_board_decision.is_some()
+    }
+}
+
+/// Escalate a Pending committee decision to the security board
+///
+/// Returns the escalation record and a Pending board decision
+/// `board_decision_id` for the same proposal and decision data hash.
+/// `InvalidInput` for an empty or too long reason or a zero board decision id;
+/// `InvalidStateTransition` if the committee decision is already Escalated or
+/// final. Nothing is changed on error.
+pub fn escalate<P>(
+    committee_decision: &mut CommitteeDecisionMetadata,
+    board_decision_id: u64,
+    reason: String,
+    current_time: i64,
+) -> Result<(Escalation, SecurityBoardDecisionMetadata<P>), FsmError> {
+    if reason.is_empty() || reason.len() > MAX_ESCALATION_REASON_LEN {
+        return Err(FsmError::InvalidInput);
+    }
+    let board_decision = SecurityBoardDecisionMetadata::initialize(
+        board_decision_id,
+        committee_decision.proposal_id,
+        committee_decision.decision_data_hash,
+        current_time,
+    )?;
+    committee_decision.transition(CommitteeDecisionStatus::Escalated, None)?;
+
+    let escalation = Escalation {
+        from_committee_id: committee_decision.committee_id,
+        decision_id: committee_decision.decision_id,
+        board_decision_id,
+        reason,
+        escalated_at: current_time,
+        resolved_by_board_decision: None,
+    };
+    Ok((escalation, board_decision))
+}
+
+/// Sync a finalized board decision back to the escalated committee decision
+///
+/// The committee decision takes the board's outcome and decision time.
+/// `InvalidInput` if either decision is not the one linked by `escalation`;
+/// `InvalidState` while the board decision is Pending or Deferred;
+/// `InvalidStateTransition` if the escalation is already resolved.
+pub fn resolve_escalation<P>(
+    escalation: &mut Escalation,
+    committee_decision: &mut CommitteeDecisionMetadata,
+    board_decision: &SecurityBoardDecisionMetadata<P>,
+) -> Result<(), FsmError> {
+    if escalation.is_resolved() {
+        return Err(FsmError::InvalidStateTransition);
+    }
+    if committee_decision.decision_id != escalation.decision_id
+        || committee_decision.committee_id != escalation.from_committee_id
+        || board_decision.decision_id != escalation.board_decision_id
+    {
+        return Err(FsmError::InvalidInput);
+    }
+    let outcome = match board_decision.status {
+        SecurityBoardDecisionStatus::Approved => CommitteeDecisionStatus::Approved,
+        SecurityBoardDecisionStatus::Rejected => CommitteeDecisionStatus::Rejected,
+        SecurityBoardDecisionStatus::Pending | SecurityBoardDecisionStatus::Deferred => {
+            return Err(FsmError::InvalidState);
+        }
+    };
+    committee_decision.transition(outcome, board_decision.decided_at)?;
+    escalation.resolved_by_board_decision = Some(board_decision.decision_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_decision() -> CommitteeDecisionMetadata {
+        CommitteeDecisionMetadata::initialize(5, 2, Some(42), [7; 32], 1000).unwrap()
+    }
+
+    fn escalated() -> (
+        CommitteeDecisionMetadata,
+        Escalation,
+        SecurityBoardDecisionMetadata<u8>,
+    ) {
+        let mut decision = pending_decision();
+        let (escalation, board) = escalate(
+            &mut decision,
+            90,
+            "Cross-committee impact".to_string(),
+            1100,
+        )
+        .unwrap();
+        (decision, escalation, board)
+    }
+
+    #[test]
+    fn test_escalate_links_board_decision() {
+        let (decision, escalation, board) = escalated();
+        assert_eq!(decision.status, CommitteeDecisionStatus::Escalated);
+        assert_eq!(
+            escalation,
+            Escalation {
+                from_committee_id: 2,
+                decision_id: 5,
+                board_decision_id: 90,
+                reason: "Cross-committee impact".to_string(),
+                escalated_at: 1100,
+                resolved_by_board_decision: None,
+            }
+        );
+        assert_eq!(board.decision_id, 90);
+        assert_eq!(board.status, SecurityBoardDecisionStatus::Pending);
+        assert_eq!(board.proposal_id, Some(42));
+        assert_eq!(board.decision_data_hash, [7; 32]);
+        assert_eq!(board.created_at, 1100);
+    }
+
+    #[test]
+    fn test_board_rejection_round_trip() {
+        let (mut decision, mut escalation, mut board) = escalated();
+        assert_eq!(
+            resolve_escalation(&mut escalation, &mut decision, &board),
+            Err(FsmError::InvalidState)
+        );
+        board.defer().unwrap();
+        assert_eq!(
+            resolve_escalation(&mut escalation, &mut decision, &board),
+            Err(FsmError::InvalidState)
+        );
+        board.reopen().unwrap();
+        board.reject(1300).unwrap();
+
+        resolve_escalation(&mut escalation, &mut decision, &board).unwrap();
+        assert_eq!(decision.status, CommitteeDecisionStatus::Rejected);
+        assert_eq!(decision.decided_at, Some(1300));
+        assert_eq!(escalation.resolved_by_board_decision, Some(90));
+        assert_eq!(
+            resolve_escalation(&mut escalation, &mut decision, &board),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn test_board_approval_round_trip() {
+        let (mut decision, mut escalation, mut board) = escalated();
+        board.approve(1250).unwrap();
+        resolve_escalation(&mut escalation, &mut decision, &board).unwrap();
+        assert_eq!(decision.status, CommitteeDecisionStatus::Approved);
+        assert_eq!(decision.decided_at, Some(1250));
+        assert!(escalation.is_resolved());
+    }
+
+    #[test]
+    fn test_escalate_rejects_escalated_or_final() {
+        let (mut decision, _, _) = escalated();
+        assert_eq!(
+            escalate::<u8>(&mut decision, 91, "Again".to_string(), 1200).map(|_| ()),
+            Err(FsmError::InvalidStateTransition)
+        );
+
+        let mut decision = pending_decision();
+        decision.approve(1050).unwrap();
+        let before = decision.clone();
+        assert_eq!(
+            escalate::<u8>(&mut decision, 91, "Late".to_string(), 1200).map(|_| ()),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(decision, before);
+    }
+
+    #[test]
+    fn test_escalate_validates_input() {
+        let mut decision = pending_decision();
+        for (board_id, reason) in [
+            (90, String::new()),
+            (90, "a".repeat(MAX_ESCALATION_REASON_LEN + 1)),
+            (0, "Reason".to_string()),
+        ] {
+            assert_eq!(
+                escalate::<u8>(&mut decision, board_id, reason, 1100).map(|_| ()),
+                Err(FsmError::InvalidInput)
+            );
+        }
+        assert_eq!(decision, pending_decision());
+    }
+
+    #[test]
+    fn test_resolve_rejects_unlinked_decisions() {
+        let (mut decision, mut escalation, mut board) = escalated();
+        board.approve(1250).unwrap();
+        let other_board = SecurityBoardDecisionMetadata::<u8>::new(
+            91,
+            Some(42),
+            SecurityBoardDecisionStatus::Approved,
+            1100,
+            Some(1250),
+            [7; 32],
+        );
+        assert_eq!(
+            resolve_escalation(&mut escalation, &mut decision, &other_board),
+            Err(FsmError::InvalidInput)
+        );
+        let mut other_decision = pending_decision();
+        other_decision.decision_id = 6;
+        assert_eq!(
+            resolve_escalation(&mut escalation, &mut other_decision, &board),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(decision.status, CommitteeDecisionStatus::Escalated);
+        assert!(!escalation.is_resolved());
+    }
+}
@@ -4,9 +4,11 @@
 //! - On-chain: Metadata, policies, committees, security board
 //! - Off-chain: Advanced analytics, optimization, recommendations
 //!
-//! Includes: analytics, voting, participation
+//! Includes: analytics, voting, participation, committee escalation, weighted board voting
 
 pub mod analytics;
+pub mod board_voting;
+pub mod escalation;
 pub mod participation;
 pub mod proposal_lifecycle;
 pub mod quorum;
@@ -16,31 +18,62 @@ pub mod security_excellence;
 pub mod security_policies;
 pub mod voting;
 
+use crate::error::FsmError;
+
+/// `AlreadyInitialized` unless `id` is still zero (an unused account)
+///
+/// Every `onchain::initialize_*` function checks its target with this so that
+/// re-initializing a live account cannot wipe its history; the matching
+/// `reinitialize_*` function skips it.
+pub(crate) fn ensure_uninitialized(id: u64) -> Result<(), FsmError> {
+    if id != 0 {
+        return Err(FsmError::AlreadyInitialized);
+    }
+    Ok(())
+}
+
 // Re-exports (specific to avoid ambiguous glob re-exports)
 pub use analytics::{
     GovernanceAnalyticsMetadata, GovernanceAnalyticsStatus, GovernanceAnalyticsType,
     onchain::initialize_governance_analytics,
 };
+pub use board_voting::{BoardBallot, BoardDecisionRecord, BoardTally, BoardVote, RoleWeightPolicy};
+pub use escalation::{Escalation, escalate, resolve_escalation};
 pub use participation::{
     GovernanceParticipationMetadata, GovernanceParticipationStatus, GovernanceParticipationType,
     onchain::initialize_governance_participation,
 };
 pub use proposal_lifecycle::{
     ProposalLifecycleMetadata, ProposalLifecycleStage, onchain as proposal_lifecycle_onchain,
+    onchain::initialize_lifecycle,
+};
+pub use quorum::{
+    CapabilityWeights, QuorumCalculationMethod, QuorumMetadata, WeightedParticipation,
+    active_member_count, onchain as quorum_onchain,
+    onchain::{
+        initialize_quorum_metadata, update_quorum_from_counts, update_quorum_percentage,
+        update_required_percentage,
+    },
+    weighted_participation, weighted_participation_checked, weighted_participation_with,
 };
-pub use quorum::{QuorumCalculationMethod, QuorumMetadata, onchain as quorum_onchain};
 pub use security_board::{
     SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus, SecurityBoardMemberMetadata,
     SecurityBoardMemberRole, onchain as security_board_onchain,
+    onchain::{initialize_board_member, initialize_decision},
 };
 pub use security_committees::{
-    CommitteeMemberRole, SecurityCommitteeMetadata, onchain as security_committees_onchain,
+    CommitteeDecisionMetadata, CommitteeDecisionStatus, CommitteeMemberRole,
+    SecurityCommitteeMetadata, onchain as security_committees_onchain,
+    onchain::initialize_committee,
+};
+pub use security_excellence::{
+    SecurityExcellenceMetadata, onchain as security_excellence_onchain, onchain::initialize_score,
 };
-pub use security_excellence::{SecurityExcellenceMetadata, onchain as security_excellence_onchain};
 pub use security_policies::{
     SecurityPolicyMetadata, SecurityPolicyStatus, onchain as security_policies_onchain,
+    onchain::initialize_policy,
 };
 pub use voting::{
     GovernanceVotingMetadata, GovernanceVotingStatus, GovernanceVotingType,
-    onchain::initialize_governance_voting,
+    onchain::{initialize_governance_voting, initialize_governance_voting_for},
 };
@@ -8,9 +8,10 @@
 use crate::error::FsmError;
 
 /// Participation type
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceParticipationType {
     /// Voting participation
+    #[default]
     Voting,
     /// Proposal participation
     Proposal,
@@ -21,9 +22,10 @@ pub enum GovernanceParticipationType {
 }
 
 /// Participation status
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceParticipationStatus {
     /// Participation active
+    #[default]
     Active,
     /// Participation paused
     Paused,
@@ -32,7 +34,7 @@ pub enum GovernanceParticipationStatus {
 }
 
 /// Governance participation metadata (on-chain)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct GovernanceParticipationMetadata {
     /// Participation ID
     pub participation_id: u64,
@@ -46,12 +48,48 @@ pub struct GovernanceParticipationMetadata {
     pub created_at: i64,
     /// Participation config hash
     pub participation_config_hash: [u8; 32],
+    /// Latest recorded activity of the member (see `quorum::active_member_count`)
+    pub last_activity_at: i64,
+}
+
+impl GovernanceParticipationMetadata {
+    /// Create initialized governance participation metadata
+    pub fn initialize(
+        participation_id: u64,
+        member_id: u64,
+        participation_type: GovernanceParticipationType,
+        participation_config_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if participation_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            participation_id,
+            member_id,
+            participation_type,
+            status: GovernanceParticipationStatus::Active,
+            created_at: current_time,
+            participation_config_hash,
+            last_activity_at: current_time,
+        })
+    }
+
+    /// Record activity of the member at `current_time`; earlier times are ignored
+    pub fn record_activity(&mut self, current_time: i64) {
+        self.last_activity_at = self.last_activity_at.max(current_time);
+    }
 }
 
 /// On-chain functions
 pub mod onchain {
     use super::*;
+    use crate::governance::ensure_uninitialized;
 
+    /// Initialize in place (zero-copy accounts); see `GovernanceParticipationMetadata::initialize`
+    ///
+    /// `AlreadyInitialized` if `participation` already has a non-zero `participation_id`; use
+    /// `reinitialize_governance_participation` to overwrite it deliberately.
     pub fn initialize_governance_participation(
         participation: &mut GovernanceParticipationMetadata,
         participation_id: u64,
@@ -60,15 +98,33 @@ pub mod onchain {
         participation_config_hash: [u8; 32],
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if participation_id == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-        participation.participation_id = participation_id;
-        participation.member_id = member_id;
-        participation.participation_type = participation_type;
-        participation.status = GovernanceParticipationStatus::Active;
-        participation.created_at = current_time;
-        participation.participation_config_hash = participation_config_hash;
+        ensure_uninitialized(participation.participation_id)?;
+        reinitialize_governance_participation(
+            participation,
+            participation_id,
+            member_id,
+            participation_type,
+            participation_config_hash,
+            current_time,
+        )
+    }
+
+    /// `initialize_governance_participation` without the already-initialized check; overwrites `participation`
+    pub fn reinitialize_governance_participation(
+        participation: &mut GovernanceParticipationMetadata,
+        participation_id: u64,
+        member_id: u64,
+        participation_type: GovernanceParticipationType,
+        participation_config_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *participation = GovernanceParticipationMetadata::initialize(
+            participation_id,
+            member_id,
+            participation_type,
+            participation_config_hash,
+            current_time,
+        )?;
         Ok(())
     }
 }
@@ -94,6 +150,7 @@ mod tests {
             status: GovernanceParticipationStatus::Active,
             created_at: 1000,
             participation_config_hash: [0u8; 32],
+            last_activity_at: 1000,
         }
     }
 
@@ -147,27 +204,24 @@ mod tests {
     }
 
     #[test]
-    fn test_initialize_governance_participation() {
-        let mut participation = GovernanceParticipationMetadata {
-            participation_id: 0,
-            member_id: 0,
-            participation_type: GovernanceParticipationType::Voting,
-            status: GovernanceParticipationStatus::Active,
-            created_at: 0,
-            participation_config_hash: [0u8; 32],
-        };
+    fn test_record_activity_only_moves_forward() {
+        let mut participation = create_test_participation();
+        participation.record_activity(2000);
+        participation.record_activity(1500);
+        assert_eq!(participation.last_activity_at, 2000);
+    }
 
+    #[test]
+    fn test_initialize_governance_participation() {
         let config_hash = [2u8; 32];
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let participation = GovernanceParticipationMetadata::initialize(
             200,
             300,
             GovernanceParticipationType::Proposal,
             config_hash,
             6000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(participation.participation_id, 200);
         assert_eq!(participation.member_id, 300);
         assert_eq!(
@@ -176,16 +230,15 @@ mod tests {
         );
         assert_eq!(participation.status, GovernanceParticipationStatus::Active);
         assert_eq!(participation.created_at, 6000);
+        assert_eq!(participation.last_activity_at, 6000);
         assert_eq!(participation.participation_config_hash, config_hash);
     }
 
     #[test]
     fn test_initialize_governance_participation_invalid_id() {
-        let mut participation = create_test_participation();
         let config_hash = [1u8; 32];
 
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let result = GovernanceParticipationMetadata::initialize(
             0, // Invalid: participation_id must be > 0
             300,
             GovernanceParticipationType::Discussion,
@@ -206,61 +259,43 @@ mod tests {
         ];
 
         for participation_type in types {
-            let mut participation = GovernanceParticipationMetadata {
-                participation_id: 0,
-                member_id: 0,
-                participation_type: GovernanceParticipationType::Voting,
-                status: GovernanceParticipationStatus::Active,
-                created_at: 0,
-                participation_config_hash: [0u8; 32],
-            };
-
-            let result = onchain::initialize_governance_participation(
-                &mut participation,
+            let participation = GovernanceParticipationMetadata::initialize(
                 1,
                 1,
                 participation_type,
                 [0u8; 32],
                 1000,
-            );
-
-            assert!(result.is_ok());
+            )
+            .unwrap();
             assert_eq!(participation.participation_type, participation_type);
         }
     }
 
     #[test]
     fn test_governance_participation_member_id() {
-        let mut participation = create_test_participation();
-
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let participation = GovernanceParticipationMetadata::initialize(
             1,
             9999,
             GovernanceParticipationType::Voting,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(participation.member_id, 9999);
     }
 
     #[test]
     fn test_governance_participation_config_hash() {
-        let mut participation = create_test_participation();
         let custom_hash = [99u8; 32];
 
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let participation = GovernanceParticipationMetadata::initialize(
             1,
             1,
             GovernanceParticipationType::Custom,
             custom_hash,
             3000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(participation.participation_config_hash, custom_hash);
     }
 
@@ -269,7 +304,7 @@ mod tests {
         let mut participation = create_test_participation();
         participation.status = GovernanceParticipationStatus::Disabled;
 
-        let result = onchain::initialize_governance_participation(
+        let result = onchain::reinitialize_governance_participation(
             &mut participation,
             1,
             1,
@@ -285,18 +320,14 @@ mod tests {
 
     #[test]
     fn test_governance_participation_timestamp() {
-        let mut participation = create_test_participation();
-
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let participation = GovernanceParticipationMetadata::initialize(
             1,
             1,
             GovernanceParticipationType::Discussion,
             [0u8; 32],
             7777,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(participation.created_at, 7777);
     }
 
@@ -365,18 +396,14 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_participation_large_ids() {
-        let mut participation = create_test_participation();
-
-        let result = onchain::initialize_governance_participation(
-            &mut participation,
+        let participation = GovernanceParticipationMetadata::initialize(
             u64::MAX,
             u64::MAX,
             GovernanceParticipationType::Custom,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(participation.participation_id, u64::MAX);
         assert_eq!(participation.member_id, u64::MAX);
     }
@@ -390,10 +417,11 @@ mod tests {
             status: GovernanceParticipationStatus::Disabled,
             created_at: 1000,
             participation_config_hash: [1u8; 32],
+            last_activity_at: 1500,
         };
 
         let new_hash = [2u8; 32];
-        let result = onchain::initialize_governance_participation(
+        let result = onchain::reinitialize_governance_participation(
             &mut participation,
             1,
             2,
@@ -424,6 +452,7 @@ mod tests {
             status: GovernanceParticipationStatus::Paused,
             created_at: 5000,
             participation_config_hash: [42u8; 32],
+            last_activity_at: 5000,
         };
 
         assert_eq!(participation.participation_id, 123);
@@ -452,4 +481,31 @@ mod tests {
         assert_eq!(result1, Vec::<u8>::new());
         assert_eq!(result2, Vec::<u8>::new());
     }
+
+    #[test]
+    fn test_initialize_governance_participation_rejects_initialized() {
+        let mut participation = create_test_participation();
+        assert_eq!(
+            onchain::initialize_governance_participation(
+                &mut participation,
+                2,
+                2,
+                GovernanceParticipationType::Voting,
+                [0u8; 32],
+                2000
+            ),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(participation, create_test_participation());
+        onchain::reinitialize_governance_participation(
+            &mut participation,
+            2,
+            2,
+            GovernanceParticipationType::Voting,
+            [0u8; 32],
+            2000,
+        )
+        .unwrap();
+        assert_eq!(participation.created_at, 2000);
+    }
 }
@@ -8,9 +8,10 @@
 use crate::error::FsmError;
 
 /// Proposal lifecycle stage
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ProposalLifecycleStage {
     /// Draft stage
+    #[default]
     Draft,
     /// Review stage
     Review,
@@ -25,7 +26,7 @@ pub enum ProposalLifecycleStage {
 /// Proposal lifecycle metadata (on-chain)
 ///
 /// Stores proposal lifecycle information
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ProposalLifecycleMetadata {
     /// Lifecycle ID
     pub lifecycle_id: u64,
@@ -39,27 +40,55 @@ pub struct ProposalLifecycleMetadata {
     pub updated_at: i64,
 }
 
+impl ProposalLifecycleMetadata {
+    /// Create initialized proposal lifecycle metadata (starts at Draft)
+    pub fn initialize(
+        lifecycle_id: u64,
+        proposal_id: u64,
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if lifecycle_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+
+        Ok(Self {
+            lifecycle_id,
+            proposal_id,
+            current_stage: ProposalLifecycleStage::Draft,
+            created_at: current_time,
+            updated_at: current_time,
+        })
+    }
+}
+
 /// On-chain functions for proposal lifecycle
 pub mod onchain {
     use super::*;
+    use crate::governance::ensure_uninitialized;
 
-    /// Initialize proposal lifecycle
+    /// Initialize proposal lifecycle in place (zero-copy accounts)
+    ///
+    /// `AlreadyInitialized` if `lifecycle` already has a non-zero `lifecycle_id`; use
+    /// `reinitialize_lifecycle` to overwrite it deliberately.
     pub fn initialize_lifecycle(
         lifecycle: &mut ProposalLifecycleMetadata,
         lifecycle_id: u64,
         proposal_id: u64,
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if lifecycle_id == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-
-        lifecycle.lifecycle_id = lifecycle_id;
-        lifecycle.proposal_id = proposal_id;
-        lifecycle.current_stage = ProposalLifecycleStage::Draft;
-        lifecycle.created_at = current_time;
-        lifecycle.updated_at = current_time;
+        ensure_uninitialized(lifecycle.lifecycle_id)?;
+        reinitialize_lifecycle(lifecycle, lifecycle_id, proposal_id, current_time)
+    }
 
+    /// `initialize_lifecycle` without the already-initialized check; overwrites `lifecycle`
+    pub fn reinitialize_lifecycle(
+        lifecycle: &mut ProposalLifecycleMetadata,
+        lifecycle_id: u64,
+        proposal_id: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *lifecycle =
+            ProposalLifecycleMetadata::initialize(lifecycle_id, proposal_id, current_time)?;
         Ok(())
     }
 }
@@ -122,17 +151,7 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle() {
-        let mut lifecycle = ProposalLifecycleMetadata {
-            lifecycle_id: 0,
-            proposal_id: 0,
-            current_stage: ProposalLifecycleStage::Draft,
-            created_at: 0,
-            updated_at: 0,
-        };
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 500, 600, 8000);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(500, 600, 8000).unwrap();
         assert_eq!(lifecycle.lifecycle_id, 500);
         assert_eq!(lifecycle.proposal_id, 600);
         assert_eq!(lifecycle.current_stage, ProposalLifecycleStage::Draft);
@@ -142,13 +161,9 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle_invalid_id() {
-        let mut lifecycle = create_test_lifecycle();
-
-        let result = onchain::initialize_lifecycle(
-            &mut lifecycle,
+        let result = ProposalLifecycleMetadata::initialize(
             0, // Invalid: lifecycle_id must be > 0
-            600,
-            8000,
+            600, 8000,
         );
 
         assert_eq!(result.unwrap_err(), FsmError::InvalidInput);
@@ -159,7 +174,7 @@ mod tests {
         let mut lifecycle = create_test_lifecycle();
         lifecycle.current_stage = ProposalLifecycleStage::Completed;
 
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 1, 1000);
+        let result = onchain::reinitialize_lifecycle(&mut lifecycle, 1, 1, 1000);
 
         assert!(result.is_ok());
         // Should always start at Draft stage
@@ -168,11 +183,7 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle_timestamps() {
-        let mut lifecycle = create_test_lifecycle();
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 1, 12345);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(1, 1, 12345).unwrap();
         // Both created_at and updated_at should be set to current_time
         assert_eq!(lifecycle.created_at, 12345);
         assert_eq!(lifecycle.updated_at, 12345);
@@ -180,11 +191,7 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle_proposal_id() {
-        let mut lifecycle = create_test_lifecycle();
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 99999, 1000);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(1, 99999, 1000).unwrap();
         assert_eq!(lifecycle.proposal_id, 99999);
     }
 
@@ -235,28 +242,14 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle_large_ids() {
-        let mut lifecycle = create_test_lifecycle();
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, u64::MAX, u64::MAX, 1000);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(u64::MAX, u64::MAX, 1000).unwrap();
         assert_eq!(lifecycle.lifecycle_id, u64::MAX);
         assert_eq!(lifecycle.proposal_id, u64::MAX);
     }
 
     #[test]
     fn test_initialize_lifecycle_stage_always_draft() {
-        let mut lifecycle = ProposalLifecycleMetadata {
-            lifecycle_id: 0,
-            proposal_id: 0,
-            current_stage: ProposalLifecycleStage::Completed, // Will be reset
-            created_at: 0,
-            updated_at: 0,
-        };
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 1, 1000);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(1, 1, 1000).unwrap();
         assert_eq!(lifecycle.current_stage, ProposalLifecycleStage::Draft);
     }
 
@@ -308,11 +301,7 @@ mod tests {
 
     #[test]
     fn test_initialize_lifecycle_timestamps_same() {
-        let mut lifecycle = create_test_lifecycle();
-
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 1, 7777);
-
-        assert!(result.is_ok());
+        let lifecycle = ProposalLifecycleMetadata::initialize(1, 1, 7777).unwrap();
         // Both timestamps should be the same
         assert_eq!(lifecycle.created_at, lifecycle.updated_at);
         assert_eq!(lifecycle.created_at, 7777);
@@ -325,7 +314,7 @@ mod tests {
         let proposal_ids = vec![1u64, 100u64, 1000u64, u64::MAX];
 
         for proposal_id in proposal_ids {
-            let result = onchain::initialize_lifecycle(&mut lifecycle, 1, proposal_id, 1000);
+            let result = onchain::reinitialize_lifecycle(&mut lifecycle, 1, proposal_id, 1000);
 
             assert!(result.is_ok());
             assert_eq!(lifecycle.proposal_id, proposal_id);
@@ -356,7 +345,7 @@ mod tests {
         let mut lifecycle = create_test_lifecycle();
 
         // proposal_id can be 0 (not validated)
-        let result = onchain::initialize_lifecycle(&mut lifecycle, 1, 0, 1000);
+        let result = onchain::reinitialize_lifecycle(&mut lifecycle, 1, 0, 1000);
 
         assert!(result.is_ok());
         assert_eq!(lifecycle.proposal_id, 0);
@@ -377,4 +366,16 @@ mod tests {
         assert!(!result1);
         assert!(!result2);
     }
+
+    #[test]
+    fn test_initialize_lifecycle_rejects_initialized() {
+        let mut lifecycle = create_test_lifecycle();
+        assert_eq!(
+            onchain::initialize_lifecycle(&mut lifecycle, 2, 2, 2000),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(lifecycle, create_test_lifecycle());
+        onchain::reinitialize_lifecycle(&mut lifecycle, 2, 2, 2000).unwrap();
+        assert_eq!(lifecycle.created_at, 2000);
+    }
 }
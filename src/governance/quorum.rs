@@ -5,12 +5,22 @@
 //! On-chain: Quorum requirements, calculations
 //! Off-chain: Quorum optimization, analytics
 
+use crate::enums::{CapabilityType, MemberStatus};
 use crate::error::FsmError;
+use crate::governance::participation::GovernanceParticipationMetadata;
+use crate::math::ArithmeticPolicy;
+use crate::member::Member;
+use crate::proposal::TallyOverflow;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// Quorum calculation method
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum QuorumCalculationMethod {
     /// Fixed percentage
+    #[default]
     FixedPercentage,
     /// Dynamic based on activity
     Dynamic,
@@ -21,7 +31,7 @@ pub enum QuorumCalculationMethod {
 /// Quorum metadata (on-chain)
 ///
 /// Stores quorum requirements and calculations
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct QuorumMetadata {
     /// Quorum ID
     pub quorum_id: u64,
@@ -41,19 +51,15 @@ pub struct QuorumMetadata {
     pub updated_at: i64,
 }
 
-/// On-chain functions for quorum
-pub mod onchain {
-    use super::*;
-
-    /// Initialize quorum metadata
-    pub fn initialize_quorum_metadata(
-        quorum: &mut QuorumMetadata,
+impl QuorumMetadata {
+    /// Create initialized quorum metadata
+    pub fn initialize(
         quorum_id: u64,
         proposal_id: Option<u64>,
         required_percentage: u8,
         calculation_method: QuorumCalculationMethod,
         current_time: i64,
-    ) -> Result<(), FsmError> {
+    ) -> Result<Self, FsmError> {
         if quorum_id == 0 {
             return Err(FsmError::InvalidInput);
         }
@@ -61,15 +67,200 @@ pub mod onchain {
             return Err(FsmError::InvalidInput);
         }
 
-        quorum.quorum_id = quorum_id;
-        quorum.proposal_id = proposal_id;
-        quorum.required_percentage = required_percentage;
-        quorum.calculation_method = calculation_method;
-        quorum.current_percentage = 0;
-        quorum.quorum_reached = false;
-        quorum.created_at = current_time;
-        quorum.updated_at = current_time;
+        Ok(Self {
+            quorum_id,
+            proposal_id,
+            required_percentage,
+            calculation_method,
+            current_percentage: 0,
+            quorum_reached: false,
+            created_at: current_time,
+            updated_at: current_time,
+        })
+    }
+}
+
+/// Quorum weight per capability
+///
+/// A member weighs as much as the heaviest capability it holds; members
+/// holding none of the weighted capabilities count 0. The default counts
+/// Vote holders only, each as 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapabilityWeights {
+    pub vote: u64,
+    pub manage: u64,
+    pub propose: u64,
+    pub deposit: u64,
+    pub withdraw: u64,
+}
+
+impl Default for CapabilityWeights {
+    fn default() -> Self {
+        Self {
+            vote: 1,
+            manage: 0,
+            propose: 0,
+            deposit: 0,
+            withdraw: 0,
+        }
+    }
+}
+
+impl CapabilityWeights {
+    pub fn weight(&self, capability: CapabilityType) -> u64 {
+        match capability {
+            CapabilityType::Vote => self.vote,
+            CapabilityType::Manage => self.manage,
+            CapabilityType::Propose => self.propose,
+            CapabilityType::Deposit => self.deposit,
+            CapabilityType::Withdraw => self.withdraw,
+        }
+    }
+
+    /// Weight of `member`, ignoring its status
+    pub fn member_weight<P>(&self, member: &Member<P>) -> u64 {
+        member
+            .capabilities
+            .iter()
+            .map(|capability| self.weight(*capability))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Weighted voter mass and weighted eligible mass, as `(current, total)`
+///
+/// Suspended and Banned members are left out of both. Voters that are not in
+/// `members` count nothing, and each member counts once however often it
+/// appears in `voters`. Sums saturate at `u64::MAX`; see
+/// `weighted_participation_with` to fail instead. Pass the result to
+/// `onchain::update_quorum_from_counts`.
+pub fn weighted_participation<P: Ord>(
+    members: &[Member<P>],
+    voters: &[P],
+    weights: &CapabilityWeights,
+) -> (u64, u64) {
+    // Clamping never fails
+    weighted_participation_with(members, voters, weights, TallyOverflow::Clamp).unwrap_or_default()
+}
 
+/// `weighted_participation` with sums added under `overflow`
+pub fn weighted_participation_with<P: Ord>(
+    members: &[Member<P>],
+    voters: &[P],
+    weights: &CapabilityWeights,
+    overflow: TallyOverflow,
+) -> Result<(u64, u64), FsmError> {
+    let participation = weighted_participation_checked(members, voters, weights, overflow.into())?;
+    Ok((participation.current, participation.total))
+}
+
+/// Weighted participation computed under an `ArithmeticPolicy`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WeightedParticipation {
+    pub current: u64,
+    pub total: u64,
+    /// A sum was clamped at `u64::MAX` under `ArithmeticPolicy::Saturating`
+    pub degraded: bool,
+}
+
+/// `weighted_participation` under `policy`, reporting whether a sum saturated
+pub fn weighted_participation_checked<P: Ord>(
+    members: &[Member<P>],
+    voters: &[P],
+    weights: &CapabilityWeights,
+    policy: ArithmeticPolicy,
+) -> Result<WeightedParticipation, FsmError> {
+    let voters: BTreeSet<&P> = voters.iter().collect();
+    let mut participation = WeightedParticipation::default();
+    for member in members.iter().filter(|member| member.is_in_good_standing()) {
+        let weight = weights.member_weight(member);
+        if voters.contains(&member.id) {
+            let current = policy.add(participation.current, weight)?;
+            participation.current = current.value;
+            participation.degraded |= current.degraded;
+        }
+        let total = policy.add(participation.total, weight)?;
+        participation.total = total.value;
+        participation.degraded |= total.degraded;
+    }
+    Ok(participation)
+}
+
+/// Members still active enough to count toward quorum
+///
+/// A member counts if its status is Active and its latest
+/// `last_activity_at` across its `participation` records (matched by
+/// `member_id`) is at least `current_time - inactivity_cutoff_seconds`; a
+/// member active exactly at the cutoff still counts. Members without a
+/// participation record have no recorded activity and do not count. Use the
+/// result as `ActivationContext::active_members`.
+pub fn active_member_count<P: Copy + Into<u64>>(
+    members: &[Member<P>],
+    participation: &[GovernanceParticipationMetadata],
+    inactivity_cutoff_seconds: u64,
+    current_time: i64,
+) -> u64 {
+    let cutoff = i64::try_from(inactivity_cutoff_seconds)
+        .ok()
+        .and_then(|seconds| current_time.checked_sub(seconds))
+        .unwrap_or(i64::MIN);
+    let active: BTreeSet<u64> = participation
+        .iter()
+        .filter(|record| record.last_activity_at >= cutoff)
+        .map(|record| record.member_id)
+        .collect();
+    members
+        .iter()
+        .filter(|member| member.status == MemberStatus::Active)
+        .filter(|member| active.contains(&member.id.into()))
+        .count() as u64
+}
+
+/// On-chain functions for quorum
+pub mod onchain {
+    use super::*;
+    use crate::governance::ensure_uninitialized;
+
+    /// Initialize quorum metadata in place (zero-copy accounts)
+    ///
+    /// `AlreadyInitialized` if `quorum` already has a non-zero `quorum_id`;
+    /// change the requirement with `update_required_percentage` instead.
+    pub fn initialize_quorum_metadata(
+        quorum: &mut QuorumMetadata,
+        quorum_id: u64,
+        proposal_id: Option<u64>,
+        required_percentage: u8,
+        calculation_method: QuorumCalculationMethod,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ensure_uninitialized(quorum.quorum_id)?;
+        reinitialize_quorum_metadata(
+            quorum,
+            quorum_id,
+            proposal_id,
+            required_percentage,
+            calculation_method,
+            current_time,
+        )
+    }
+
+    /// `initialize_quorum_metadata` without the already-initialized check; overwrites `quorum`
+    pub fn reinitialize_quorum_metadata(
+        quorum: &mut QuorumMetadata,
+        quorum_id: u64,
+        proposal_id: Option<u64>,
+        required_percentage: u8,
+        calculation_method: QuorumCalculationMethod,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *quorum = QuorumMetadata::initialize(
+            quorum_id,
+            proposal_id,
+            required_percentage,
+            calculation_method,
+            current_time,
+        )?;
         Ok(())
     }
 
@@ -89,6 +280,58 @@ pub mod onchain {
 
         Ok(())
     }
+
+    /// Change the required percentage by at most `max_delta` points, at least
+    /// `min_interval` seconds after the last update (`updated_at`)
+    ///
+    /// `quorum_reached` is recomputed against the new requirement. Errors:
+    /// `InvalidState` for an uninitialized quorum, `InvalidInput` above 100 or
+    /// beyond `max_delta`, `TimeRegression` before `updated_at`,
+    /// `TooFrequentUpdate` within `min_interval`.
+    pub fn update_required_percentage(
+        quorum: &mut QuorumMetadata,
+        new_required: u8,
+        max_delta: u8,
+        min_interval: i64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if quorum.quorum_id == 0 {
+            return Err(FsmError::InvalidState);
+        }
+        if new_required > 100 || new_required.abs_diff(quorum.required_percentage) > max_delta {
+            return Err(FsmError::InvalidInput);
+        }
+        let elapsed = i128::from(current_time) - i128::from(quorum.updated_at);
+        if elapsed < 0 {
+            return Err(FsmError::TimeRegression);
+        }
+        if elapsed < i128::from(min_interval) {
+            return Err(FsmError::TooFrequentUpdate);
+        }
+
+        quorum.required_percentage = new_required;
+        quorum.quorum_reached = quorum.current_percentage >= new_required;
+        quorum.updated_at = current_time;
+
+        Ok(())
+    }
+
+    /// Update quorum from a participating count out of an eligible count
+    ///
+    /// The percentage is rounded down. `InvalidInput` if `total` is zero or
+    /// `current` exceeds it.
+    pub fn update_quorum_from_counts(
+        quorum: &mut QuorumMetadata,
+        current: u64,
+        total: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if total == 0 || current > total {
+            return Err(FsmError::InvalidInput);
+        }
+        let percentage = (current as u128 * 100 / total as u128) as u8;
+        update_quorum_percentage(quorum, percentage, current_time)
+    }
 }
 
 /// Off-chain functions for quorum
@@ -132,6 +375,182 @@ mod tests {
         }
     }
 
+    fn create_test_members() -> Vec<Member<u8>> {
+        vec![
+            Member::new(1, vec![CapabilityType::Vote, CapabilityType::Manage]),
+            Member::new(2, vec![CapabilityType::Vote]),
+            Member::new(3, vec![CapabilityType::Vote]),
+            Member::new(4, vec![CapabilityType::Vote]),
+            Member::new(5, vec![CapabilityType::Propose]),
+        ]
+    }
+
+    #[test]
+    fn test_weighted_participation() {
+        let members = create_test_members();
+        let weights = CapabilityWeights {
+            manage: 3,
+            ..CapabilityWeights::default()
+        };
+        // Member 5 holds no weighted capability; 9 is not a member; 2 voted twice
+        assert_eq!(
+            weighted_participation(&members, &[1, 2, 2, 5, 9], &weights),
+            (4, 6)
+        );
+        assert_eq!(
+            weighted_participation(&members, &[2], &CapabilityWeights::default()),
+            (1, 4)
+        );
+        assert_eq!(weighted_participation::<u8>(&[], &[1], &weights), (0, 0));
+    }
+
+    fn create_test_activity(
+        member_id: u64,
+        last_activity_at: i64,
+    ) -> GovernanceParticipationMetadata {
+        GovernanceParticipationMetadata {
+            participation_id: member_id,
+            member_id,
+            last_activity_at,
+            ..GovernanceParticipationMetadata::default()
+        }
+    }
+
+    #[test]
+    fn test_active_member_count_cutoff_boundary() {
+        const YEAR: u64 = 365 * 24 * 3600;
+        let now = 2 * YEAR as i64;
+        let mut members = create_test_members();
+        members[3].status = MemberStatus::Suspended;
+        let participation = vec![
+            // Exactly at the cutoff: still active
+            create_test_activity(1, now - YEAR as i64),
+            // One second past the cutoff
+            create_test_activity(2, now - YEAR as i64 - 1),
+            // An older record does not hide a recent one
+            create_test_activity(3, 0),
+            create_test_activity(3, now),
+            // Suspended, however recent
+            create_test_activity(4, now),
+            // Not a member
+            create_test_activity(9, now),
+        ];
+        // Member 5 has no participation record
+        assert_eq!(active_member_count(&members, &participation, YEAR, now), 2);
+        assert_eq!(
+            active_member_count(&members, &participation, YEAR + 1, now),
+            3
+        );
+        assert_eq!(
+            active_member_count(&members, &participation, u64::MAX, now),
+            3
+        );
+    }
+
+    #[test]
+    fn test_weighted_participation_saturates() {
+        let members = create_test_members();
+        let weights = CapabilityWeights {
+            vote: u64::MAX,
+            ..CapabilityWeights::default()
+        };
+        assert_eq!(
+            weighted_participation(&members, &[1, 2], &weights),
+            (u64::MAX, u64::MAX)
+        );
+        assert_eq!(
+            weighted_participation_with(&members, &[1, 2], &weights, TallyOverflow::Reject),
+            Err(FsmError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_weighted_participation_policy_at_boundary() {
+        let members = create_test_members();
+        // Four voting members of weight MAX / 4 sum to just under u64::MAX
+        let fits = CapabilityWeights {
+            vote: u64::MAX / 4,
+            ..CapabilityWeights::default()
+        };
+        for policy in [ArithmeticPolicy::Strict, ArithmeticPolicy::Saturating] {
+            assert_eq!(
+                weighted_participation_checked(&members, &[2], &fits, policy),
+                Ok(WeightedParticipation {
+                    current: u64::MAX / 4,
+                    total: u64::MAX / 4 * 4,
+                    degraded: false,
+                })
+            );
+        }
+        let overflows = CapabilityWeights {
+            vote: u64::MAX / 4 + 1,
+            ..fits
+        };
+        assert_eq!(
+            weighted_participation_checked(&members, &[2], &overflows, ArithmeticPolicy::Strict),
+            Err(FsmError::Overflow)
+        );
+        assert_eq!(
+            weighted_participation_checked(
+                &members,
+                &[2],
+                &overflows,
+                ArithmeticPolicy::Saturating
+            ),
+            Ok(WeightedParticipation {
+                current: u64::MAX / 4 + 1,
+                total: u64::MAX,
+                degraded: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_excluding_suspended_members_flips_quorum() {
+        let mut members = create_test_members();
+        let weights = CapabilityWeights::default();
+        let voters = [1, 2];
+        let mut quorum = create_test_quorum();
+
+        let (current, total) = weighted_participation(&members, &voters, &weights);
+        onchain::update_quorum_from_counts(&mut quorum, current, total, 2000).unwrap();
+        assert_eq!(quorum.current_percentage, 50);
+        assert!(quorum.quorum_reached);
+
+        quorum.required_percentage = 60;
+        onchain::update_quorum_from_counts(&mut quorum, current, total, 3000).unwrap();
+        assert!(!quorum.quorum_reached);
+
+        members[2].status = crate::enums::MemberStatus::Suspended;
+        let (current, total) = weighted_participation(&members, &voters, &weights);
+        assert_eq!((current, total), (2, 3));
+        onchain::update_quorum_from_counts(&mut quorum, current, total, 4000).unwrap();
+        assert_eq!(quorum.current_percentage, 66);
+        assert!(quorum.quorum_reached);
+
+        // A banned voter leaves both sides
+        members[1].status = crate::enums::MemberStatus::Banned;
+        assert_eq!(weighted_participation(&members, &voters, &weights), (1, 2));
+    }
+
+    #[test]
+    fn test_update_quorum_from_counts_validation() {
+        let mut quorum = create_test_quorum();
+        assert_eq!(
+            onchain::update_quorum_from_counts(&mut quorum, 0, 0, 2000),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            onchain::update_quorum_from_counts(&mut quorum, 3, 2, 2000),
+            Err(FsmError::InvalidInput)
+        );
+        onchain::update_quorum_from_counts(&mut quorum, u64::MAX, u64::MAX, 2000).unwrap();
+        assert_eq!(quorum.current_percentage, 100);
+        onchain::update_quorum_from_counts(&mut quorum, u64::MAX / 2, u64::MAX, 3000).unwrap();
+        assert_eq!(quorum.current_percentage, 49);
+        assert!(!quorum.quorum_reached);
+    }
+
     #[test]
     fn test_quorum_calculation_method_variants() {
         assert_eq!(
@@ -166,27 +585,10 @@ mod tests {
 
     #[test]
     fn test_initialize_quorum_metadata() {
-        let mut quorum = QuorumMetadata {
-            quorum_id: 0,
-            proposal_id: None,
-            required_percentage: 0,
-            calculation_method: QuorumCalculationMethod::FixedPercentage,
-            current_percentage: 0,
-            quorum_reached: false,
-            created_at: 0,
-            updated_at: 0,
-        };
+        let quorum =
+            QuorumMetadata::initialize(200, Some(300), 75, QuorumCalculationMethod::Dynamic, 7000)
+                .unwrap();
 
-        let result = onchain::initialize_quorum_metadata(
-            &mut quorum,
-            200,
-            Some(300),
-            75,
-            QuorumCalculationMethod::Dynamic,
-            7000,
-        );
-
-        assert!(result.is_ok());
         assert_eq!(quorum.quorum_id, 200);
         assert_eq!(quorum.proposal_id, Some(300));
         assert_eq!(quorum.required_percentage, 75);
@@ -199,10 +601,7 @@ mod tests {
 
     #[test]
     fn test_initialize_quorum_metadata_invalid_id() {
-        let mut quorum = create_test_quorum();
-
-        let result = onchain::initialize_quorum_metadata(
-            &mut quorum,
+        let result = QuorumMetadata::initialize(
             0, // Invalid: quorum_id must be > 0
             Some(300),
             75,
@@ -215,10 +614,7 @@ mod tests {
 
     #[test]
     fn test_initialize_quorum_metadata_invalid_percentage() {
-        let mut quorum = create_test_quorum();
-
-        let result = onchain::initialize_quorum_metadata(
-            &mut quorum,
+        let result = QuorumMetadata::initialize(
             1,
             Some(300),
             101, // Invalid: required_percentage must be <= 100
@@ -231,18 +627,14 @@ mod tests {
 
     #[test]
     fn test_initialize_quorum_metadata_without_proposal_id() {
-        let mut quorum = create_test_quorum();
-
-        let result = onchain::initialize_quorum_metadata(
-            &mut quorum,
+        let quorum = QuorumMetadata::initialize(
             1,
             None, // No specific proposal
             60,
             QuorumCalculationMethod::Weighted,
             5000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(quorum.proposal_id, None);
     }
 
@@ -306,11 +698,7 @@ mod tests {
         ];
 
         for method in methods {
-            let mut quorum = create_test_quorum();
-            let result =
-                onchain::initialize_quorum_metadata(&mut quorum, 1, None, 50, method, 1000);
-
-            assert!(result.is_ok());
+            let quorum = QuorumMetadata::initialize(1, None, 50, method, 1000).unwrap();
             assert_eq!(quorum.calculation_method, method);
         }
     }
@@ -382,35 +770,28 @@ mod tests {
         let percentages = vec![0u8, 1u8, 50u8, 99u8, 100u8];
 
         for percentage in percentages {
-            let mut quorum = create_test_quorum();
-            let result = onchain::initialize_quorum_metadata(
-                &mut quorum,
+            let quorum = QuorumMetadata::initialize(
                 1,
                 None,
                 percentage,
                 QuorumCalculationMethod::FixedPercentage,
                 1000,
-            );
-
-            assert!(result.is_ok());
+            )
+            .unwrap();
             assert_eq!(quorum.required_percentage, percentage);
         }
     }
 
     #[test]
     fn test_initialize_quorum_metadata_large_quorum_id() {
-        let mut quorum = create_test_quorum();
-
-        let result = onchain::initialize_quorum_metadata(
-            &mut quorum,
+        let quorum = QuorumMetadata::initialize(
             u64::MAX,
             None,
             50,
             QuorumCalculationMethod::FixedPercentage,
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(quorum.quorum_id, u64::MAX);
     }
 
@@ -492,14 +873,9 @@ mod tests {
     #[test]
     fn test_initialize_quorum_metadata_always_starts_at_zero() {
         let mut quorum = QuorumMetadata {
-            quorum_id: 0,
-            proposal_id: None,
-            required_percentage: 0,
-            calculation_method: QuorumCalculationMethod::FixedPercentage,
             current_percentage: 100, // Will be reset
             quorum_reached: true,    // Will be reset
-            created_at: 0,
-            updated_at: 0,
+            ..Default::default()
         };
 
         let result = onchain::initialize_quorum_metadata(
@@ -516,6 +892,95 @@ mod tests {
         assert!(!quorum.quorum_reached);
     }
 
+    #[test]
+    fn test_quorum_metadata_default_is_uninitialized() {
+        let quorum = QuorumMetadata::default();
+        assert_eq!(quorum.quorum_id, 0);
+        assert_eq!(quorum.proposal_id, None);
+        assert_eq!(
+            quorum.calculation_method,
+            QuorumCalculationMethod::FixedPercentage
+        );
+        assert!(!quorum.quorum_reached);
+    }
+
+    #[test]
+    fn test_initialize_quorum_metadata_in_place_matches_constructor() {
+        let mut quorum = QuorumMetadata::default();
+        onchain::initialize_quorum_metadata(
+            &mut quorum,
+            7,
+            Some(8),
+            60,
+            QuorumCalculationMethod::Weighted,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            quorum,
+            QuorumMetadata::initialize(7, Some(8), 60, QuorumCalculationMethod::Weighted, 1000)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reinitialize_quorum_rejected() {
+        let mut quorum = create_test_quorum();
+        assert_eq!(
+            onchain::initialize_quorum_metadata(
+                &mut quorum,
+                2,
+                None,
+                90,
+                QuorumCalculationMethod::FixedPercentage,
+                2000,
+            ),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(quorum, create_test_quorum());
+    }
+
+    #[test]
+    fn test_update_required_percentage_bounded() {
+        let mut quorum = create_test_quorum();
+        quorum.current_percentage = 55;
+        quorum.quorum_reached = true;
+        assert_eq!(
+            onchain::update_required_percentage(&mut quorum, 61, 10, 100, 2000),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            onchain::update_required_percentage(&mut quorum, 101, 100, 100, 2000),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            onchain::update_required_percentage(&mut quorum, 60, 10, 100, 1099),
+            Err(FsmError::TooFrequentUpdate)
+        );
+        assert_eq!(
+            onchain::update_required_percentage(&mut quorum, 60, 10, 100, 999),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(quorum.required_percentage, 50);
+
+        onchain::update_required_percentage(&mut quorum, 60, 10, 100, 1100).unwrap();
+        assert_eq!(quorum.required_percentage, 60);
+        assert!(!quorum.quorum_reached);
+        assert_eq!(quorum.updated_at, 1100);
+        assert_eq!(
+            onchain::update_required_percentage(&mut quorum, 55, 10, 100, 1150),
+            Err(FsmError::TooFrequentUpdate)
+        );
+        onchain::update_required_percentage(&mut quorum, 55, 10, 100, 1200).unwrap();
+        assert!(quorum.quorum_reached);
+
+        let mut uninitialized = QuorumMetadata::default();
+        assert_eq!(
+            onchain::update_required_percentage(&mut uninitialized, 10, 10, 0, 0),
+            Err(FsmError::InvalidState)
+        );
+    }
+
     #[test]
     fn test_update_quorum_percentage_timestamp_update() {
         let mut quorum = create_test_quorum();
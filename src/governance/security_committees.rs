@@ -6,20 +6,39 @@
 //! Off-chain: Actual committee coordination, analysis
 
 use crate::error::FsmError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Committee member role
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum CommitteeMemberRole {
     /// Chairperson
     Chairperson,
     /// Member
+    #[default]
     Member,
 }
 
+/// Committee decision status
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CommitteeDecisionStatus {
+    /// Decision pending
+    #[default]
+    Pending,
+    /// Decision approved
+    Approved,
+    /// Decision rejected
+    Rejected,
+    /// Handed to the security board; its outcome is synced back by
+    /// `escalation::resolve_escalation`
+    Escalated,
+}
+
 /// Security committee metadata (on-chain)
 ///
 /// Stores metadata for security committees
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SecurityCommitteeMetadata {
     /// Committee ID
     pub committee_id: u64,
@@ -31,17 +50,13 @@ pub struct SecurityCommitteeMetadata {
     pub updated_at: i64,
 }
 
-/// On-chain functions for security committees
-pub mod onchain {
-    use super::*;
-
-    /// Initialize security committee
-    pub fn initialize_committee(
-        committee: &mut SecurityCommitteeMetadata,
+impl SecurityCommitteeMetadata {
+    /// Create initialized security committee metadata
+    pub fn initialize(
         committee_id: u64,
         name: String,
         current_time: i64,
-    ) -> Result<(), FsmError> {
+    ) -> Result<Self, FsmError> {
         if committee_id == 0 {
             return Err(FsmError::InvalidInput);
         }
@@ -52,11 +67,113 @@ pub mod onchain {
             return Err(FsmError::InvalidInput);
         }
 
-        committee.committee_id = committee_id;
-        committee.name = name;
-        committee.created_at = current_time;
-        committee.updated_at = current_time;
+        Ok(Self {
+            committee_id,
+            name,
+            created_at: current_time,
+            updated_at: current_time,
+        })
+    }
+}
+
+/// Committee decision metadata (on-chain)
+///
+/// Stores metadata for decisions taken at committee level
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitteeDecisionMetadata {
+    /// Decision ID
+    pub decision_id: u64,
+    /// Deciding committee
+    pub committee_id: u64,
+    /// Proposal ID (if related)
+    pub proposal_id: Option<u64>,
+    /// Status
+    pub status: CommitteeDecisionStatus,
+    /// Created at
+    pub created_at: i64,
+    /// Decided at
+    pub decided_at: Option<i64>,
+    /// Decision data hash
+    pub decision_data_hash: [u8; 32],
+}
+
+impl CommitteeDecisionMetadata {
+    /// Create initialized committee decision metadata (starts Pending)
+    pub fn initialize(
+        decision_id: u64,
+        committee_id: u64,
+        proposal_id: Option<u64>,
+        decision_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if decision_id == 0 || committee_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+
+        Ok(Self {
+            decision_id,
+            committee_id,
+            proposal_id,
+            status: CommitteeDecisionStatus::Pending,
+            created_at: current_time,
+            decided_at: None,
+            decision_data_hash,
+        })
+    }
+
+    /// Approve a Pending decision
+    pub fn approve(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(CommitteeDecisionStatus::Approved, Some(current_time))
+    }
+
+    /// Reject a Pending decision
+    pub fn reject(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(CommitteeDecisionStatus::Rejected, Some(current_time))
+    }
+
+    pub(crate) fn transition(
+        &mut self,
+        target: CommitteeDecisionStatus,
+        decided_at: Option<i64>,
+    ) -> Result<(), FsmError> {
+        // Staying in the same state is not a decision
+        if self.status == target {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        self.status.validate_transition(target)?;
+        self.status = target;
+        self.decided_at = decided_at;
+        Ok(())
+    }
+}
+
+/// On-chain functions for security committees
+pub mod onchain {
+    use super::*;
+    use crate::governance::ensure_uninitialized;
+
+    /// Initialize security committee in place (zero-copy accounts)
+    ///
+    /// `AlreadyInitialized` if `committee` already has a non-zero `committee_id`; use
+    /// `reinitialize_committee` to overwrite it deliberately.
+    pub fn initialize_committee(
+        committee: &mut SecurityCommitteeMetadata,
+        committee_id: u64,
+        name: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ensure_uninitialized(committee.committee_id)?;
+        reinitialize_committee(committee, committee_id, name, current_time)
+    }
 
+    /// `initialize_committee` without the already-initialized check; overwrites `committee`
+    pub fn reinitialize_committee(
+        committee: &mut SecurityCommitteeMetadata,
+        committee_id: u64,
+        name: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *committee = SecurityCommitteeMetadata::initialize(committee_id, name, current_time)?;
         Ok(())
     }
 }
@@ -105,21 +222,12 @@ mod tests {
 
     #[test]
     fn test_initialize_committee() {
-        let mut committee = SecurityCommitteeMetadata {
-            committee_id: 0,
-            name: String::new(),
-            created_at: 0,
-            updated_at: 0,
-        };
-
-        let result = onchain::initialize_committee(
-            &mut committee,
+        let committee = SecurityCommitteeMetadata::initialize(
             700,
             "Security Review Committee".to_string(),
             12000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(committee.committee_id, 700);
         assert_eq!(committee.name, "Security Review Committee");
         assert_eq!(committee.created_at, 12000);
@@ -128,10 +236,7 @@ mod tests {
 
     #[test]
     fn test_initialize_committee_invalid_id() {
-        let mut committee = create_test_committee();
-
-        let result = onchain::initialize_committee(
-            &mut committee,
+        let result = SecurityCommitteeMetadata::initialize(
             0, // Invalid: committee_id must be > 0
             "Committee Name".to_string(),
             1000,
@@ -142,10 +247,7 @@ mod tests {
 
     #[test]
     fn test_initialize_committee_empty_name() {
-        let mut committee = create_test_committee();
-
-        let result = onchain::initialize_committee(
-            &mut committee,
+        let result = SecurityCommitteeMetadata::initialize(
             1,
             String::new(), // Invalid: name must not be empty
             1000,
@@ -156,33 +258,25 @@ mod tests {
 
     #[test]
     fn test_initialize_committee_name_too_long() {
-        let mut committee = create_test_committee();
         let long_name = "a".repeat(101); // 101 chars, max is 100
 
-        let result = onchain::initialize_committee(&mut committee, 1, long_name, 1000);
+        let result = SecurityCommitteeMetadata::initialize(1, long_name, 1000);
 
         assert_eq!(result.unwrap_err(), FsmError::InvalidInput);
     }
 
     #[test]
     fn test_initialize_committee_name_max_length() {
-        let mut committee = create_test_committee();
         let max_name = "a".repeat(100); // Exactly 100 chars
 
-        let result = onchain::initialize_committee(&mut committee, 1, max_name.clone(), 1000);
-
-        assert!(result.is_ok());
+        let committee = SecurityCommitteeMetadata::initialize(1, max_name.clone(), 1000).unwrap();
         assert_eq!(committee.name.len(), 100);
     }
 
     #[test]
     fn test_initialize_committee_timestamps() {
-        let mut committee = create_test_committee();
-
-        let result =
-            onchain::initialize_committee(&mut committee, 1, "Committee Name".to_string(), 23456);
-
-        assert!(result.is_ok());
+        let committee =
+            SecurityCommitteeMetadata::initialize(1, "Committee Name".to_string(), 23456).unwrap();
         // Both created_at and updated_at should be set to current_time
         assert_eq!(committee.created_at, 23456);
         assert_eq!(committee.updated_at, 23456);
@@ -209,10 +303,7 @@ mod tests {
         ];
 
         for name in names {
-            let mut committee = create_test_committee();
-            let result = onchain::initialize_committee(&mut committee, 1, name.clone(), 1000);
-
-            assert!(result.is_ok());
+            let committee = SecurityCommitteeMetadata::initialize(1, name.clone(), 1000).unwrap();
             assert_eq!(committee.name, name);
         }
     }
@@ -253,12 +344,8 @@ mod tests {
 
     #[test]
     fn test_initialize_committee_large_committee_id() {
-        let mut committee = create_test_committee();
-
-        let result =
-            onchain::initialize_committee(&mut committee, u64::MAX, "Committee".to_string(), 1000);
-
-        assert!(result.is_ok());
+        let committee =
+            SecurityCommitteeMetadata::initialize(u64::MAX, "Committee".to_string(), 1000).unwrap();
         assert_eq!(committee.committee_id, u64::MAX);
     }
 
@@ -271,7 +358,8 @@ mod tests {
             updated_at: 2000,
         };
 
-        let result = onchain::initialize_committee(&mut committee, 1, "New Name".to_string(), 3000);
+        let result =
+            onchain::reinitialize_committee(&mut committee, 1, "New Name".to_string(), 3000);
 
         assert!(result.is_ok());
         // All fields should be updated
@@ -311,4 +399,32 @@ mod tests {
         assert!(!result1);
         assert!(!result2);
     }
+
+    #[test]
+    fn test_committee_decision_lifecycle() {
+        let mut decision =
+            CommitteeDecisionMetadata::initialize(5, 1, Some(9), [1; 32], 1000).unwrap();
+        assert_eq!(decision.status, CommitteeDecisionStatus::Pending);
+        assert_eq!(decision.decided_at, None);
+        decision.approve(1100).unwrap();
+        assert_eq!(decision.status, CommitteeDecisionStatus::Approved);
+        assert_eq!(decision.decided_at, Some(1100));
+        assert_eq!(decision.reject(1200), Err(FsmError::InvalidStateTransition));
+        assert_eq!(
+            CommitteeDecisionMetadata::initialize(5, 0, None, [0; 32], 1000),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_initialize_committee_rejects_initialized() {
+        let mut committee = create_test_committee();
+        assert_eq!(
+            onchain::initialize_committee(&mut committee, 2, "Other".to_string(), 2000),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(committee, create_test_committee());
+        onchain::reinitialize_committee(&mut committee, 2, "Other".to_string(), 2000).unwrap();
+        assert_eq!(committee.created_at, 2000);
+    }
 }
@@ -10,7 +10,7 @@ use crate::error::FsmError;
 /// Security excellence score metadata (on-chain)
 ///
 /// Stores security excellence metrics
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SecurityExcellenceMetadata {
     /// Score ID
     pub score_id: u64,
@@ -24,18 +24,14 @@ pub struct SecurityExcellenceMetadata {
     pub score_data_hash: [u8; 32],
 }
 
-/// On-chain functions for security excellence
-pub mod onchain {
-    use super::*;
-
-    /// Initialize security excellence score
-    pub fn initialize_score(
-        score: &mut SecurityExcellenceMetadata,
+impl SecurityExcellenceMetadata {
+    /// Create initialized security excellence metadata
+    pub fn initialize(
         score_id: u64,
         overall_score: u8,
         score_data_hash: [u8; 32],
         current_time: i64,
-    ) -> Result<(), FsmError> {
+    ) -> Result<Self, FsmError> {
         if score_id == 0 {
             return Err(FsmError::InvalidInput);
         }
@@ -43,12 +39,56 @@ pub mod onchain {
             return Err(FsmError::InvalidInput);
         }
 
-        score.score_id = score_id;
-        score.overall_score = overall_score;
-        score.created_at = current_time;
-        score.updated_at = current_time;
-        score.score_data_hash = score_data_hash;
+        Ok(Self {
+            score_id,
+            overall_score,
+            created_at: current_time,
+            updated_at: current_time,
+            score_data_hash,
+        })
+    }
+}
+
+/// On-chain functions for security excellence
+pub mod onchain {
+    use super::*;
+    use crate::governance::ensure_uninitialized;
+
+    /// Initialize security excellence score in place (zero-copy accounts)
+    ///
+    /// `AlreadyInitialized` if `score` already has a non-zero `score_id`; use
+    /// `reinitialize_score` to overwrite it deliberately.
+    pub fn initialize_score(
+        score: &mut SecurityExcellenceMetadata,
+        score_id: u64,
+        overall_score: u8,
+        score_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ensure_uninitialized(score.score_id)?;
+        reinitialize_score(
+            score,
+            score_id,
+            overall_score,
+            score_data_hash,
+            current_time,
+        )
+    }
 
+    /// `initialize_score` without the already-initialized check; overwrites `score`
+    pub fn reinitialize_score(
+        score: &mut SecurityExcellenceMetadata,
+        score_id: u64,
+        overall_score: u8,
+        score_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *score = SecurityExcellenceMetadata::initialize(
+            score_id,
+            overall_score,
+            score_data_hash,
+            current_time,
+        )?;
         Ok(())
     }
 }
@@ -88,18 +128,8 @@ mod tests {
 
     #[test]
     fn test_initialize_score() {
-        let mut score = SecurityExcellenceMetadata {
-            score_id: 0,
-            overall_score: 0,
-            created_at: 0,
-            updated_at: 0,
-            score_data_hash: [0u8; 32],
-        };
-
         let data_hash = [5u8; 32];
-        let result = onchain::initialize_score(&mut score, 600, 85, data_hash, 11000);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(600, 85, data_hash, 11000).unwrap();
         assert_eq!(score.score_id, 600);
         assert_eq!(score.overall_score, 85);
         assert_eq!(score.created_at, 11000);
@@ -109,10 +139,8 @@ mod tests {
 
     #[test]
     fn test_initialize_score_invalid_id() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(
-            &mut score, 0, // Invalid: score_id must be > 0
+        let result = SecurityExcellenceMetadata::initialize(
+            0, // Invalid: score_id must be > 0
             85, [0u8; 32], 1000,
         );
 
@@ -121,10 +149,8 @@ mod tests {
 
     #[test]
     fn test_initialize_score_invalid_percentage() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(
-            &mut score, 1, 101, // Invalid: overall_score must be <= 100
+        let result = SecurityExcellenceMetadata::initialize(
+            1, 101, // Invalid: overall_score must be <= 100
             [0u8; 32], 1000,
         );
 
@@ -136,32 +162,25 @@ mod tests {
         let mut score = create_test_score();
 
         // Test 0
-        assert!(onchain::initialize_score(&mut score, 1, 0, [0u8; 32], 1000).is_ok());
+        assert!(onchain::reinitialize_score(&mut score, 1, 0, [0u8; 32], 1000).is_ok());
         assert_eq!(score.overall_score, 0);
 
         // Test 100
-        assert!(onchain::initialize_score(&mut score, 1, 100, [0u8; 32], 2000).is_ok());
+        assert!(onchain::reinitialize_score(&mut score, 1, 100, [0u8; 32], 2000).is_ok());
         assert_eq!(score.overall_score, 100);
     }
 
     #[test]
     fn test_initialize_score_data_hash() {
-        let mut score = create_test_score();
         let custom_hash = [99u8; 32];
 
-        let result = onchain::initialize_score(&mut score, 1, 90, custom_hash, 3000);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(1, 90, custom_hash, 3000).unwrap();
         assert_eq!(score.score_data_hash, custom_hash);
     }
 
     #[test]
     fn test_initialize_score_timestamps() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(&mut score, 1, 50, [0u8; 32], 54321);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(1, 50, [0u8; 32], 54321).unwrap();
         // Both created_at and updated_at should be set to current_time
         assert_eq!(score.created_at, 54321);
         assert_eq!(score.updated_at, 54321);
@@ -172,10 +191,8 @@ mod tests {
         let scores = vec![0u8, 25u8, 50u8, 75u8, 100u8];
 
         for overall_score in scores {
-            let mut score = create_test_score();
-            let result = onchain::initialize_score(&mut score, 1, overall_score, [0u8; 32], 1000);
-
-            assert!(result.is_ok());
+            let score =
+                SecurityExcellenceMetadata::initialize(1, overall_score, [0u8; 32], 1000).unwrap();
             assert_eq!(score.overall_score, overall_score);
         }
     }
@@ -185,42 +202,29 @@ mod tests {
         let percentages = vec![0u8, 1u8, 50u8, 99u8, 100u8];
 
         for percentage in percentages {
-            let mut score = create_test_score();
-            let result = onchain::initialize_score(&mut score, 1, percentage, [0u8; 32], 1000);
-
-            assert!(result.is_ok());
+            let score =
+                SecurityExcellenceMetadata::initialize(1, percentage, [0u8; 32], 1000).unwrap();
             assert_eq!(score.overall_score, percentage);
         }
     }
 
     #[test]
     fn test_initialize_score_large_score_id() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(&mut score, u64::MAX, 75, [0u8; 32], 1000);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(u64::MAX, 75, [0u8; 32], 1000).unwrap();
         assert_eq!(score.score_id, u64::MAX);
     }
 
     #[test]
     fn test_initialize_score_custom_hash() {
-        let mut score = create_test_score();
         let custom_hash = [255u8; 32];
 
-        let result = onchain::initialize_score(&mut score, 1, 80, custom_hash, 1000);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(1, 80, custom_hash, 1000).unwrap();
         assert_eq!(score.score_data_hash, custom_hash);
     }
 
     #[test]
     fn test_initialize_score_different_timestamps() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(&mut score, 1, 75, [0u8; 32], 7777);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(1, 75, [0u8; 32], 7777).unwrap();
         assert_eq!(score.created_at, 7777);
         assert_eq!(score.updated_at, 7777);
     }
@@ -236,7 +240,7 @@ mod tests {
         };
 
         let new_hash = [2u8; 32];
-        let result = onchain::initialize_score(&mut score, 1, 90, new_hash, 3000);
+        let result = onchain::reinitialize_score(&mut score, 1, 90, new_hash, 3000);
 
         assert!(result.is_ok());
         // All fields should be updated
@@ -266,13 +270,21 @@ mod tests {
 
     #[test]
     fn test_initialize_score_timestamps_same() {
-        let mut score = create_test_score();
-
-        let result = onchain::initialize_score(&mut score, 1, 75, [0u8; 32], 8888);
-
-        assert!(result.is_ok());
+        let score = SecurityExcellenceMetadata::initialize(1, 75, [0u8; 32], 8888).unwrap();
         // Both timestamps should be the same
         assert_eq!(score.created_at, score.updated_at);
         assert_eq!(score.created_at, 8888);
     }
+
+    #[test]
+    fn test_initialize_score_rejects_initialized() {
+        let mut score = create_test_score();
+        assert_eq!(
+            onchain::initialize_score(&mut score, 2, 50, [0u8; 32], 2000),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(score, create_test_score());
+        onchain::reinitialize_score(&mut score, 2, 50, [0u8; 32], 2000).unwrap();
+        assert_eq!(score.created_at, 2000);
+    }
 }
@@ -8,20 +8,21 @@
 use crate::error::FsmError;
 
 /// Security policy status
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum SecurityPolicyStatus {
     /// Policy active
     Active,
     /// Policy inactive
     Inactive,
     /// Policy draft
+    #[default]
     Draft,
 }
 
 /// Security policy metadata (on-chain)
 ///
 /// Stores metadata for security policies
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SecurityPolicyMetadata {
     /// Policy ID
     pub policy_id: u64,
@@ -37,18 +38,14 @@ pub struct SecurityPolicyMetadata {
     pub policy_data_hash: [u8; 32],
 }
 
-/// On-chain functions for security policies
-pub mod onchain {
-    use super::*;
-
-    /// Initialize security policy
-    pub fn initialize_policy(
-        policy: &mut SecurityPolicyMetadata,
+impl SecurityPolicyMetadata {
+    /// Create initialized security policy metadata (starts as Draft)
+    pub fn initialize(
         policy_id: u64,
         name: String,
         policy_data_hash: [u8; 32],
         current_time: i64,
-    ) -> Result<(), FsmError> {
+    ) -> Result<Self, FsmError> {
         if policy_id == 0 {
             return Err(FsmError::InvalidInput);
         }
@@ -59,13 +56,47 @@ pub mod onchain {
             return Err(FsmError::InvalidInput);
         }
 
-        policy.policy_id = policy_id;
-        policy.name = name;
-        policy.status = SecurityPolicyStatus::Draft;
-        policy.created_at = current_time;
-        policy.updated_at = current_time;
-        policy.policy_data_hash = policy_data_hash;
+        Ok(Self {
+            policy_id,
+            name,
+            status: SecurityPolicyStatus::Draft,
+            created_at: current_time,
+            updated_at: current_time,
+            policy_data_hash,
+        })
+    }
+}
 
+/// On-chain functions for security policies
+pub mod onchain {
+    use super::*;
+    use crate::governance::ensure_uninitialized;
+
+    /// Initialize security policy in place (zero-copy accounts)
+    ///
+    /// `AlreadyInitialized` if `policy` already has a non-zero `policy_id`; use
+    /// `reinitialize_policy` to overwrite it deliberately.
+    pub fn initialize_policy(
+        policy: &mut SecurityPolicyMetadata,
+        policy_id: u64,
+        name: String,
+        policy_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ensure_uninitialized(policy.policy_id)?;
+        reinitialize_policy(policy, policy_id, name, policy_data_hash, current_time)
+    }
+
+    /// `initialize_policy` without the already-initialized check; overwrites `policy`
+    pub fn reinitialize_policy(
+        policy: &mut SecurityPolicyMetadata,
+        policy_id: u64,
+        name: String,
+        policy_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *policy =
+            SecurityPolicyMetadata::initialize(policy_id, name, policy_data_hash, current_time)?;
         Ok(())
     }
 }
@@ -118,25 +149,14 @@ mod tests {
 
     #[test]
     fn test_initialize_policy() {
-        let mut policy = SecurityPolicyMetadata {
-            policy_id: 0,
-            name: String::new(),
-            status: SecurityPolicyStatus::Draft,
-            created_at: 0,
-            updated_at: 0,
-            policy_data_hash: [0u8; 32],
-        };
-
         let data_hash = [4u8; 32];
-        let result = onchain::initialize_policy(
-            &mut policy,
+        let policy = SecurityPolicyMetadata::initialize(
             500,
             "New Security Policy".to_string(),
             data_hash,
             10000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(policy.policy_id, 500);
         assert_eq!(policy.name, "New Security Policy");
         assert_eq!(policy.status, SecurityPolicyStatus::Draft);
@@ -147,10 +167,7 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_invalid_id() {
-        let mut policy = create_test_policy();
-
-        let result = onchain::initialize_policy(
-            &mut policy,
+        let result = SecurityPolicyMetadata::initialize(
             0, // Invalid: policy_id must be > 0
             "Policy Name".to_string(),
             [0u8; 32],
@@ -162,10 +179,7 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_empty_name() {
-        let mut policy = create_test_policy();
-
-        let result = onchain::initialize_policy(
-            &mut policy,
+        let result = SecurityPolicyMetadata::initialize(
             1,
             String::new(), // Invalid: name must not be empty
             [0u8; 32],
@@ -177,22 +191,19 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_name_too_long() {
-        let mut policy = create_test_policy();
         let long_name = "a".repeat(101); // 101 chars, max is 100
 
-        let result = onchain::initialize_policy(&mut policy, 1, long_name, [0u8; 32], 1000);
+        let result = SecurityPolicyMetadata::initialize(1, long_name, [0u8; 32], 1000);
 
         assert_eq!(result.unwrap_err(), FsmError::InvalidInput);
     }
 
     #[test]
     fn test_initialize_policy_name_max_length() {
-        let mut policy = create_test_policy();
         let max_name = "a".repeat(100); // Exactly 100 chars
 
-        let result = onchain::initialize_policy(&mut policy, 1, max_name.clone(), [0u8; 32], 1000);
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, max_name.clone(), [0u8; 32], 1000).unwrap();
         assert_eq!(policy.name.len(), 100);
     }
 
@@ -202,7 +213,7 @@ mod tests {
         policy.status = SecurityPolicyStatus::Active;
 
         let result =
-            onchain::initialize_policy(&mut policy, 1, "New Policy".to_string(), [0u8; 32], 1000);
+            onchain::reinitialize_policy(&mut policy, 1, "New Policy".to_string(), [0u8; 32], 1000);
 
         assert!(result.is_ok());
         // Status should always be set to Draft on initialization
@@ -211,29 +222,19 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_data_hash() {
-        let mut policy = create_test_policy();
         let custom_hash = [88u8; 32];
 
-        let result = onchain::initialize_policy(
-            &mut policy,
-            1,
-            "Policy Name".to_string(),
-            custom_hash,
-            2000,
-        );
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, "Policy Name".to_string(), custom_hash, 2000)
+                .unwrap();
         assert_eq!(policy.policy_data_hash, custom_hash);
     }
 
     #[test]
     fn test_initialize_policy_timestamps() {
-        let mut policy = create_test_policy();
-
-        let result =
-            onchain::initialize_policy(&mut policy, 1, "Policy Name".to_string(), [0u8; 32], 12345);
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, "Policy Name".to_string(), [0u8; 32], 12345)
+                .unwrap();
         // Both created_at and updated_at should be set to current_time
         assert_eq!(policy.created_at, 12345);
         assert_eq!(policy.updated_at, 12345);
@@ -252,12 +253,9 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_name_exact_max_length() {
-        let mut policy = create_test_policy();
-
         let max_name = "a".repeat(100); // Exactly max_len(100)
-        let result = onchain::initialize_policy(&mut policy, 1, max_name.clone(), [0u8; 32], 1000);
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, max_name.clone(), [0u8; 32], 1000).unwrap();
         assert_eq!(policy.name.len(), 100);
     }
 
@@ -267,7 +265,7 @@ mod tests {
 
         // Initialize should always set status to Draft
         let result =
-            onchain::initialize_policy(&mut policy, 1, "Policy".to_string(), [0u8; 32], 1000);
+            onchain::reinitialize_policy(&mut policy, 1, "Policy".to_string(), [0u8; 32], 1000);
 
         assert!(result.is_ok());
         assert_eq!(policy.status, SecurityPolicyStatus::Draft);
@@ -275,41 +273,26 @@ mod tests {
 
     #[test]
     fn test_initialize_policy_custom_hash() {
-        let mut policy = create_test_policy();
         let custom_hash = [255u8; 32];
 
-        let result =
-            onchain::initialize_policy(&mut policy, 1, "Policy".to_string(), custom_hash, 1000);
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, "Policy".to_string(), custom_hash, 1000).unwrap();
         assert_eq!(policy.policy_data_hash, custom_hash);
     }
 
     #[test]
     fn test_initialize_policy_different_timestamps() {
-        let mut policy = create_test_policy();
-
-        let result =
-            onchain::initialize_policy(&mut policy, 1, "Policy".to_string(), [0u8; 32], 5000);
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(1, "Policy".to_string(), [0u8; 32], 5000).unwrap();
         assert_eq!(policy.created_at, 5000);
         assert_eq!(policy.updated_at, 5000);
     }
 
     #[test]
     fn test_initialize_policy_large_policy_id() {
-        let mut policy = create_test_policy();
-
-        let result = onchain::initialize_policy(
-            &mut policy,
-            u64::MAX,
-            "Policy".to_string(),
-            [0u8; 32],
-            1000,
-        );
-
-        assert!(result.is_ok());
+        let policy =
+            SecurityPolicyMetadata::initialize(u64::MAX, "Policy".to_string(), [0u8; 32], 1000)
+                .unwrap();
         assert_eq!(policy.policy_id, u64::MAX);
     }
 
@@ -338,7 +321,8 @@ mod tests {
         ];
 
         for name in names {
-            let result = onchain::initialize_policy(&mut policy, 1, name.clone(), [0u8; 32], 1000);
+            let result =
+                onchain::reinitialize_policy(&mut policy, 1, name.clone(), [0u8; 32], 1000);
 
             assert!(result.is_ok());
             assert_eq!(policy.name, name);
@@ -376,7 +360,7 @@ mod tests {
         };
 
         let result =
-            onchain::initialize_policy(&mut policy, 1, "New Name".to_string(), [2u8; 32], 3000);
+            onchain::reinitialize_policy(&mut policy, 1, "New Name".to_string(), [2u8; 32], 3000);
 
         assert!(result.is_ok());
         // All fields should be updated
@@ -425,4 +409,16 @@ mod tests {
         assert!(!result1);
         assert!(!result2);
     }
+
+    #[test]
+    fn test_initialize_policy_rejects_initialized() {
+        let mut policy = create_test_policy();
+        assert_eq!(
+            onchain::initialize_policy(&mut policy, 2, "Other".to_string(), [0u8; 32], 2000),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(policy, create_test_policy());
+        onchain::reinitialize_policy(&mut policy, 2, "Other".to_string(), [0u8; 32], 2000).unwrap();
+        assert_eq!(policy.created_at, 2000);
+    }
 }
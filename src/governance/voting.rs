@@ -6,11 +6,13 @@
 //! Off-chain: Actual voting, tallying
 
 use crate::error::FsmError;
+use crate::proposal::{Proposal, ProposalStatus};
 
 /// Voting type
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceVotingType {
     /// Simple majority
+    #[default]
     SimpleMajority,
     /// Super majority
     SuperMajority,
@@ -21,9 +23,10 @@ pub enum GovernanceVotingType {
 }
 
 /// Voting status
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum GovernanceVotingStatus {
     /// Voting open
+    #[default]
     Open,
     /// Voting closed
     Closed,
@@ -32,7 +35,7 @@ pub enum GovernanceVotingStatus {
 }
 
 /// Governance voting metadata (on-chain)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct GovernanceVotingMetadata {
     /// Voting ID
     pub voting_id: u64,
@@ -46,12 +49,86 @@ pub struct GovernanceVotingMetadata {
     pub created_at: i64,
     /// Voting data hash
     pub voting_data_hash: [u8; 32],
+    /// End of the proposal's voting window; set by `initialize_for`
+    pub voting_ends_at: Option<i64>,
+}
+
+impl GovernanceVotingMetadata {
+    /// Create initialized governance voting metadata
+    pub fn initialize(
+        voting_id: u64,
+        proposal_id: u64,
+        voting_type: GovernanceVotingType,
+        voting_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if voting_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            voting_id,
+            proposal_id,
+            voting_type,
+            status: GovernanceVotingStatus::Open,
+            created_at: current_time,
+            voting_data_hash,
+            voting_ends_at: None,
+        })
+    }
+
+    /// Create voting metadata linked to `proposal` and record the link on it
+    ///
+    /// `proposal_id` and `voting_ends_at` come from the proposal. `InvalidState`
+    /// unless the proposal is Active, `AlreadyInitialized` if it already has a
+    /// linked voting id, `InvalidInput` for a zero `voting_id`.
+    pub fn initialize_for<P>(
+        voting_id: u64,
+        proposal: &mut Proposal<P>,
+        voting_type: GovernanceVotingType,
+        voting_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if proposal.status != ProposalStatus::Active {
+            return Err(FsmError::InvalidState);
+        }
+        if proposal.voting_id.is_some() {
+            return Err(FsmError::AlreadyInitialized);
+        }
+        let mut voting = Self::initialize(
+            voting_id,
+            proposal.id,
+            voting_type,
+            voting_data_hash,
+            current_time,
+        )?;
+        voting.voting_ends_at = Some(proposal.voting_ends_at()?);
+        proposal.voting_id = Some(voting_id);
+        Ok(voting)
+    }
+
+    /// Close an Open voting; `InvalidStateTransition` otherwise
+    pub fn close(&mut self) -> Result<(), FsmError> {
+        if self.status != GovernanceVotingStatus::Open {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        self.status = GovernanceVotingStatus::Closed;
+        Ok(())
+    }
 }
 
 /// On-chain functions
 pub mod onchain {
     use super::*;
-
+    use crate::governance::ensure_uninitialized;
+
+    /// Initialize in place (zero-copy accounts); see `GovernanceVotingMetadata::initialize`
+    ///
+    /// Unchecked: `proposal_id` is stored as given, without looking at the
+    /// proposal. Prefer `initialize_governance_voting_for` where the proposal
+    /// is at hand.
+    ///
+    /// `AlreadyInitialized` if `voting` already has a non-zero `voting_id`; use
+    /// `reinitialize_governance_voting` to overwrite it deliberately.
     pub fn initialize_governance_voting(
         voting: &mut GovernanceVotingMetadata,
         voting_id: u64,
@@ -60,15 +137,56 @@ pub mod onchain {
         voting_data_hash: [u8; 32],
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if voting_id == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-        voting.voting_id = voting_id;
-        voting.proposal_id = proposal_id;
-        voting.voting_type = voting_type;
-        voting.status = GovernanceVotingStatus::Open;
-        voting.created_at = current_time;
-        voting.voting_data_hash = voting_data_hash;
+        ensure_uninitialized(voting.voting_id)?;
+        reinitialize_governance_voting(
+            voting,
+            voting_id,
+            proposal_id,
+            voting_type,
+            voting_data_hash,
+            current_time,
+        )
+    }
+
+    /// Initialize in place linked to `proposal`; see `GovernanceVotingMetadata::initialize_for`
+    ///
+    /// `AlreadyInitialized` if `voting` already has a non-zero `voting_id`.
+    /// Nothing is modified on error.
+    pub fn initialize_governance_voting_for<P>(
+        voting: &mut GovernanceVotingMetadata,
+        voting_id: u64,
+        proposal: &mut Proposal<P>,
+        voting_type: GovernanceVotingType,
+        voting_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ensure_uninitialized(voting.voting_id)?;
+        *voting = GovernanceVotingMetadata::initialize_for(
+            voting_id,
+            proposal,
+            voting_type,
+            voting_data_hash,
+            current_time,
+        )?;
+        Ok(())
+    }
+
+    /// `initialize_governance_voting` without the already-initialized check; overwrites `voting`
+    pub fn reinitialize_governance_voting(
+        voting: &mut GovernanceVotingMetadata,
+        voting_id: u64,
+        proposal_id: u64,
+        voting_type: GovernanceVotingType,
+        voting_data_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        *voting = GovernanceVotingMetadata::initialize(
+            voting_id,
+            proposal_id,
+            voting_type,
+            voting_data_hash,
+            current_time,
+        )?;
         Ok(())
     }
 }
@@ -94,6 +212,7 @@ mod tests {
             status: GovernanceVotingStatus::Open,
             created_at: 1000,
             voting_data_hash: [0u8; 32],
+            voting_ends_at: None,
         }
     }
 
@@ -138,27 +257,26 @@ mod tests {
     }
 
     #[test]
-    fn test_initialize_governance_voting() {
-        let mut voting = GovernanceVotingMetadata {
-            voting_id: 0,
-            proposal_id: 0,
-            voting_type: GovernanceVotingType::SimpleMajority,
-            status: GovernanceVotingStatus::Open,
-            created_at: 0,
-            voting_data_hash: [0u8; 32],
-        };
+    fn test_close_only_from_open() {
+        let mut voting = create_test_voting();
+        voting.close().unwrap();
+        assert_eq!(voting.status, GovernanceVotingStatus::Closed);
+        assert_eq!(voting.close(), Err(FsmError::InvalidStateTransition));
+        voting.status = GovernanceVotingStatus::Cancelled;
+        assert_eq!(voting.close(), Err(FsmError::InvalidStateTransition));
+    }
 
+    #[test]
+    fn test_initialize_governance_voting() {
         let data_hash = [3u8; 32];
-        let result = onchain::initialize_governance_voting(
-            &mut voting,
+        let voting = GovernanceVotingMetadata::initialize(
             300,
             400,
             GovernanceVotingType::SuperMajority,
             data_hash,
             9000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(voting.voting_id, 300);
         assert_eq!(voting.proposal_id, 400);
         assert_eq!(voting.voting_type, GovernanceVotingType::SuperMajority);
@@ -169,10 +287,7 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_voting_invalid_id() {
-        let mut voting = create_test_voting();
-
-        let result = onchain::initialize_governance_voting(
-            &mut voting,
+        let result = GovernanceVotingMetadata::initialize(
             0, // Invalid: voting_id must be > 0
             400,
             GovernanceVotingType::Unanimous,
@@ -193,25 +308,8 @@ mod tests {
         ];
 
         for voting_type in types {
-            let mut voting = GovernanceVotingMetadata {
-                voting_id: 0,
-                proposal_id: 0,
-                voting_type: GovernanceVotingType::SimpleMajority,
-                status: GovernanceVotingStatus::Open,
-                created_at: 0,
-                voting_data_hash: [0u8; 32],
-            };
-
-            let result = onchain::initialize_governance_voting(
-                &mut voting,
-                1,
-                1,
-                voting_type,
-                [0u8; 32],
-                1000,
-            );
-
-            assert!(result.is_ok());
+            let voting =
+                GovernanceVotingMetadata::initialize(1, 1, voting_type, [0u8; 32], 1000).unwrap();
             assert_eq!(voting.voting_type, voting_type);
         }
     }
@@ -221,7 +319,7 @@ mod tests {
         let mut voting = create_test_voting();
         voting.status = GovernanceVotingStatus::Closed;
 
-        let result = onchain::initialize_governance_voting(
+        let result = onchain::reinitialize_governance_voting(
             &mut voting,
             1,
             1,
@@ -237,36 +335,29 @@ mod tests {
 
     #[test]
     fn test_governance_voting_data_hash() {
-        let mut voting = create_test_voting();
         let custom_hash = [77u8; 32];
 
-        let result = onchain::initialize_governance_voting(
-            &mut voting,
+        let voting = GovernanceVotingMetadata::initialize(
             1,
             1,
             GovernanceVotingType::Custom,
             custom_hash,
             5000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(voting.voting_data_hash, custom_hash);
     }
 
     #[test]
     fn test_governance_voting_proposal_id() {
-        let mut voting = create_test_voting();
-
-        let result = onchain::initialize_governance_voting(
-            &mut voting,
+        let voting = GovernanceVotingMetadata::initialize(
             1,
             88888,
             GovernanceVotingType::SimpleMajority,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(voting.proposal_id, 88888);
     }
 
@@ -335,18 +426,14 @@ mod tests {
 
     #[test]
     fn test_initialize_governance_voting_large_ids() {
-        let mut voting = create_test_voting();
-
-        let result = onchain::initialize_governance_voting(
-            &mut voting,
+        let voting = GovernanceVotingMetadata::initialize(
             u64::MAX,
             u64::MAX,
             GovernanceVotingType::Custom,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(voting.voting_id, u64::MAX);
         assert_eq!(voting.proposal_id, u64::MAX);
     }
@@ -360,10 +447,11 @@ mod tests {
             status: GovernanceVotingStatus::Closed,
             created_at: 1000,
             voting_data_hash: [1u8; 32],
+            voting_ends_at: None,
         };
 
         let new_hash = [2u8; 32];
-        let result = onchain::initialize_governance_voting(
+        let result = onchain::reinitialize_governance_voting(
             &mut voting,
             1,
             2,
@@ -391,6 +479,7 @@ mod tests {
             status: GovernanceVotingStatus::Closed,
             created_at: 5000,
             voting_data_hash: [42u8; 32],
+            voting_ends_at: None,
         };
 
         assert_eq!(voting.voting_id, 123);
@@ -416,4 +505,113 @@ mod tests {
         assert_eq!(result1, Vec::<u8>::new());
         assert_eq!(result2, Vec::<u8>::new());
     }
+
+    #[test]
+    fn test_initialize_governance_voting_rejects_initialized() {
+        let mut voting = create_test_voting();
+        assert_eq!(
+            onchain::initialize_governance_voting(
+                &mut voting,
+                2,
+                2,
+                GovernanceVotingType::SimpleMajority,
+                [0u8; 32],
+                2000
+            ),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(voting, create_test_voting());
+        onchain::reinitialize_governance_voting(
+            &mut voting,
+            2,
+            2,
+            GovernanceVotingType::SimpleMajority,
+            [0u8; 32],
+            2000,
+        )
+        .unwrap();
+        assert_eq!(voting.created_at, 2000);
+    }
+
+    fn draft_proposal() -> Proposal<u8> {
+        Proposal::builder(
+            9,
+            "Title".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(crate::duration::DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_initialize_for_links_active_proposal() {
+        let mut proposal = draft_proposal();
+        proposal.activate_with_time(1, 1, 50).unwrap();
+        let mut voting = GovernanceVotingMetadata::default();
+        onchain::initialize_governance_voting_for(
+            &mut voting,
+            3,
+            &mut proposal,
+            GovernanceVotingType::SimpleMajority,
+            [1u8; 32],
+            60,
+        )
+        .unwrap();
+        assert_eq!(voting.proposal_id, 9);
+        assert_eq!(voting.voting_ends_at, Some(150));
+        assert_eq!(voting.created_at, 60);
+        assert_eq!(proposal.voting_id, Some(3));
+    }
+
+    #[test]
+    fn test_initialize_for_rejects_unlinkable_proposals() {
+        let mut proposal = draft_proposal();
+        assert_eq!(
+            GovernanceVotingMetadata::initialize_for(
+                3,
+                &mut proposal,
+                GovernanceVotingType::SimpleMajority,
+                [0u8; 32],
+                60
+            ),
+            Err(FsmError::InvalidState)
+        );
+        proposal.activate_with_time(1, 1, 50).unwrap();
+        assert_eq!(
+            GovernanceVotingMetadata::initialize_for(
+                0,
+                &mut proposal,
+                GovernanceVotingType::SimpleMajority,
+                [0u8; 32],
+                60
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.voting_id, None);
+        GovernanceVotingMetadata::initialize_for(
+            3,
+            &mut proposal,
+            GovernanceVotingType::SimpleMajority,
+            [0u8; 32],
+            60,
+        )
+        .unwrap();
+        let mut second = GovernanceVotingMetadata::default();
+        assert_eq!(
+            onchain::initialize_governance_voting_for(
+                &mut second,
+                4,
+                &mut proposal,
+                GovernanceVotingType::SimpleMajority,
+                [0u8; 32],
+                60
+            ),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(second, GovernanceVotingMetadata::default());
+        assert_eq!(proposal.voting_id, Some(3));
+    }
 }
@@ -5,23 +5,126 @@
 //! - vote_duration_hours - voting duration in hours
 //! - delegate_weight_percentage - delegate weight (0-100)
 //! - early_quorum_enabled - early quorum enabled
+//! - max_suspension_hours - longest suspension window (24-2160)
+//!
+//! Recommendations (`ParamRecommendation`) from analytics, operators or
+//! external services are applied with `apply_recommendation`, which keeps a
+//! record of what was applied in `history`.
 
+use crate::duration::DurationSecs;
 use crate::error::FsmError;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Number of applied recommendations kept in `GovernanceParams::history`
+pub const PARAMS_HISTORY_LEN: usize = 20;
+
+/// Allowed range of `GovernanceParams::vote_duration_hours` (1 to 30 days)
+pub const VOTE_DURATION_HOURS_RANGE: std::ops::RangeInclusive<u64> = 24..=720;
+
+/// Allowed range of `GovernanceParams::max_suspension_hours` (1 to 90 days)
+pub const SUSPENSION_HOURS_RANGE: std::ops::RangeInclusive<u64> = 24..=2160;
+
+/// `max_suspension_hours` of new params and of params stored before it existed
+fn default_max_suspension_hours() -> u64 {
+    *SUSPENSION_HOURS_RANGE.end()
+}
 
 /// Adaptive governance parameters
 ///
-/// Governance parameters are not static - they adapt based on metrics and AI recommendations.
+/// Governance parameters are not static - they adapt based on metrics and
+/// recommendations (`ParamRecommendation`).
 /// This is a shift from "code as law" to "code as living process".
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GovernanceParams {
     pub quorum_percentage: u8,          // Quorum percentage (0-100)
     pub vote_duration_hours: u64,       // Voting duration in hours
     pub delegate_weight_percentage: u8, // Delegate weight (0-100)
     pub early_quorum_enabled: bool,     // Early quorum enabled
     pub update_timestamp: i64,          // Last update time
+    /// Applied recommendations, oldest first; keeps the last `PARAMS_HISTORY_LEN`
+    pub history: Vec<AppliedRecommendation>,
+    /// Longest suspension window (auto-resume or auto-cancel) in hours
+    #[cfg_attr(feature = "serde", serde(default = "default_max_suspension_hours"))]
+    pub max_suspension_hours: u64,
+}
+
+/// Where a recommendation came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RecommendationSource {
+    /// Derived from governance analytics
+    Analytics,
+    /// Entered by an operator
+    Manual,
+    /// Supplied by an external service
+    External,
+}
+
+/// Parameter values to change; `None` keeps the current value
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParamChanges {
+    pub quorum_percentage: Option<u8>,
+    pub vote_duration_hours: Option<u64>,
+    pub delegate_weight_percentage: Option<u8>,
+    pub early_quorum_enabled: Option<bool>,
+    pub max_suspension_hours: Option<u64>,
+}
+
+/// Recommended parameter change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParamRecommendation {
+    pub id: u64,
+    pub recommended: ParamChanges,
+    /// Hash of the off-chain rationale document
+    pub rationale_hash: [u8; 32],
+    pub source: RecommendationSource,
+    pub created_at: i64,
+    /// First time the recommendation can no longer be applied
+    pub expires_at: i64,
+}
+
+/// Record of an applied recommendation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AppliedRecommendation {
+    pub recommendation_id: u64,
+    pub rationale_hash: [u8; 32],
+    pub source: RecommendationSource,
+    pub applied_at: i64,
+}
+
+/// Why a recommendation could not be applied
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecommendationError {
+    /// `current_time` is at or past `expires_at`
+    Expired,
+    /// `current_time` is before `created_at`
+    NotYetValid,
+    /// The named parameter is outside its allowed range
+    OutOfRange(&'static str),
+    /// The named parameter moves further than one update allows (±10, or ±10% for hours)
+    StepTooLarge(&'static str),
 }
 
+impl fmt::Display for RecommendationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecommendationError::Expired => write!(f, "Recommendation has expired"),
+            RecommendationError::NotYetValid => write!(f, "Recommendation is not valid yet"),
+            RecommendationError::OutOfRange(param) => write!(f, "{param} is out of range"),
+            RecommendationError::StepTooLarge(param) => {
+                write!(f, "{param} changes too much in one update")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RecommendationError {}
+
 impl GovernanceParams {
     /// Create new governance parameters
     pub fn new(
@@ -35,8 +138,7 @@ impl GovernanceParams {
         if quorum_percentage == 0 || quorum_percentage > 100 {
             return Err(FsmError::InvalidInput);
         }
-        if !(24..=720).contains(&vote_duration_hours) {
-            // 24 hours - 30 days
+        if !VOTE_DURATION_HOURS_RANGE.contains(&vote_duration_hours) {
             return Err(FsmError::InvalidInput);
         }
         if delegate_weight_percentage > 100 {
@@ -49,9 +151,71 @@ impl GovernanceParams {
             delegate_weight_percentage,
             early_quorum_enabled,
             update_timestamp: current_time,
+            history: Vec::new(),
+            max_suspension_hours: default_max_suspension_hours(),
         })
     }
 
+    /// Change `max_suspension_hours`
+    ///
+    /// `InvalidInput` outside `SUSPENSION_HOURS_RANGE` or for a step of more
+    /// than ±10% of the current value.
+    pub fn set_max_suspension_hours(
+        &mut self,
+        hours: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if !SUSPENSION_HOURS_RANGE.contains(&hours) {
+            return Err(FsmError::InvalidInput);
+        }
+        if !self.suspension_step_allowed(hours) {
+            return Err(FsmError::InvalidInput);
+        }
+        self.max_suspension_hours = hours;
+        self.update_timestamp = current_time;
+        Ok(())
+    }
+
+    /// `ExceedsPolicyLimit` if `window` is longer than `max_suspension_hours`
+    ///
+    /// For grant suspensions (`Grant::suspend_with_window`) and any other
+    /// time-boxed pause.
+    pub fn check_suspension_window(&self, window: DurationSecs) -> Result<(), FsmError> {
+        let limit =
+            DurationSecs::from_hours(self.max_suspension_hours).unwrap_or(DurationSecs::MAX);
+        if window > limit {
+            return Err(FsmError::ExceedsPolicyLimit {
+                max_hours: self.max_suspension_hours,
+            });
+        }
+        Ok(())
+    }
+
+    fn suspension_step_allowed(&self, hours: u64) -> bool {
+        u128::from(hours.abs_diff(self.max_suspension_hours)) * 10
+            <= u128::from(self.max_suspension_hours)
+    }
+
+    /// Check the ranges `new` and `set_max_suspension_hours` enforce
+    ///
+    /// For params that did not come through them, e.g. deserialized ones.
+    pub fn validate(&self) -> Result<(), FsmError> {
+        if self.quorum_percentage == 0
+            || self.quorum_percentage > 100
+            || !VOTE_DURATION_HOURS_RANGE.contains(&self.vote_duration_hours)
+            || self.delegate_weight_percentage > 100
+            || !SUSPENSION_HOURS_RANGE.contains(&self.max_suspension_hours)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+
+    /// `vote_duration_hours` as a duration; `DurationSecs::MAX` if it overflows
+    pub fn vote_duration(&self) -> DurationSecs {
+        DurationSecs::from_hours(self.vote_duration_hours).unwrap_or(DurationSecs::MAX)
+    }
+
     /// Update governance parameters
     ///
     /// Updates parameters with validation and constraints:
@@ -79,7 +243,7 @@ impl GovernanceParams {
         }
 
         if let Some(duration) = vote_duration_hours {
-            if !(24..=720).contains(&duration) {
+            if !VOTE_DURATION_HOURS_RANGE.contains(&duration) {
                 return Err(FsmError::InvalidInput);
             }
             self.vote_duration_hours = duration;
@@ -101,6 +265,80 @@ impl GovernanceParams {
 
         Ok(())
     }
+
+    /// Apply a recommendation and record it in `history`
+    ///
+    /// The recommendation must be valid at `current_time` (`created_at <=
+    /// current_time < expires_at`) and every recommended value must satisfy the
+    /// same range and ±10 quorum step constraints as `update`. On error the
+    /// parameters are left untouched.
+    pub fn apply_recommendation(
+        &mut self,
+        recommendation: &ParamRecommendation,
+        current_time: i64,
+    ) -> Result<(), RecommendationError> {
+        if current_time < recommendation.created_at {
+            return Err(RecommendationError::NotYetValid);
+        }
+        if current_time >= recommendation.expires_at {
+            return Err(RecommendationError::Expired);
+        }
+        let changes = &recommendation.recommended;
+        if let Some(quorum) = changes.quorum_percentage {
+            if quorum == 0 || quorum > 100 {
+                return Err(RecommendationError::OutOfRange("quorum_percentage"));
+            }
+            if quorum.abs_diff(self.quorum_percentage) > 10 {
+                return Err(RecommendationError::StepTooLarge("quorum_percentage"));
+            }
+        }
+        if let Some(duration) = changes.vote_duration_hours
+            && !VOTE_DURATION_HOURS_RANGE.contains(&duration)
+        {
+            return Err(RecommendationError::OutOfRange("vote_duration_hours"));
+        }
+        if let Some(weight) = changes.delegate_weight_percentage
+            && weight > 100
+        {
+            return Err(RecommendationError::OutOfRange(
+                "delegate_weight_percentage",
+            ));
+        }
+        if let Some(hours) = changes.max_suspension_hours {
+            if !SUSPENSION_HOURS_RANGE.contains(&hours) {
+                return Err(RecommendationError::OutOfRange("max_suspension_hours"));
+            }
+            if !self.suspension_step_allowed(hours) {
+                return Err(RecommendationError::StepTooLarge("max_suspension_hours"));
+            }
+        }
+
+        // All values validated; apply them together
+        self.quorum_percentage = changes.quorum_percentage.unwrap_or(self.quorum_percentage);
+        self.vote_duration_hours = changes
+            .vote_duration_hours
+            .unwrap_or(self.vote_duration_hours);
+        self.delegate_weight_percentage = changes
+            .delegate_weight_percentage
+            .unwrap_or(self.delegate_weight_percentage);
+        self.early_quorum_enabled = changes
+            .early_quorum_enabled
+            .unwrap_or(self.early_quorum_enabled);
+        self.max_suspension_hours = changes
+            .max_suspension_hours
+            .unwrap_or(self.max_suspension_hours);
+        self.update_timestamp = current_time;
+        if self.history.len() >= PARAMS_HISTORY_LEN {
+            self.history.remove(0);
+        }
+        self.history.push(AppliedRecommendation {
+            recommendation_id: recommendation.id,
+            rationale_hash: recommendation.rationale_hash,
+            source: recommendation.source,
+            applied_at: current_time,
+        });
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -125,6 +363,14 @@ mod tests {
         assert_eq!(params.update_timestamp, 1000);
     }
 
+    #[test]
+    fn test_vote_duration_in_seconds() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        assert_eq!(params.vote_duration().as_secs(), 7 * 24 * 3600);
+        params.vote_duration_hours = u64::MAX;
+        assert_eq!(params.vote_duration(), DurationSecs::MAX);
+    }
+
     #[test]
     fn test_governance_params_new_validation() {
         // Test quorum_percentage = 0
@@ -412,6 +658,178 @@ mod tests {
         assert_eq!(params.vote_duration_hours, 720);
     }
 
+    fn recommendation(recommended: ParamChanges) -> ParamRecommendation {
+        ParamRecommendation {
+            id: 7,
+            recommended,
+            rationale_hash: [3; 32],
+            source: RecommendationSource::Analytics,
+            created_at: 1500,
+            expires_at: 3000,
+        }
+    }
+
+    #[test]
+    fn test_apply_recommendation_records_history() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        let rec = recommendation(ParamChanges {
+            quorum_percentage: Some(58),
+            early_quorum_enabled: Some(false),
+            ..ParamChanges::default()
+        });
+
+        assert!(params.apply_recommendation(&rec, 2000).is_ok());
+        assert_eq!(params.quorum_percentage, 58);
+        assert!(!params.early_quorum_enabled);
+        assert_eq!(params.vote_duration_hours, 168); // Unchanged
+        assert_eq!(params.update_timestamp, 2000);
+        assert_eq!(
+            params.history,
+            vec![AppliedRecommendation {
+                recommendation_id: 7,
+                rationale_hash: [3; 32],
+                source: RecommendationSource::Analytics,
+                applied_at: 2000,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_apply_recommendation_outside_validity_window() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        let before = params.clone();
+        let rec = recommendation(ParamChanges {
+            quorum_percentage: Some(55),
+            ..ParamChanges::default()
+        });
+
+        assert_eq!(
+            params.apply_recommendation(&rec, 3000),
+            Err(RecommendationError::Expired)
+        );
+        assert_eq!(
+            params.apply_recommendation(&rec, 1400),
+            Err(RecommendationError::NotYetValid)
+        );
+        assert_eq!(params, before);
+    }
+
+    #[test]
+    fn test_apply_recommendation_out_of_bounds_leaves_params_untouched() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        let before = params.clone();
+        let cases = [
+            (
+                ParamChanges {
+                    quorum_percentage: Some(61),
+                    ..ParamChanges::default()
+                },
+                RecommendationError::StepTooLarge("quorum_percentage"),
+            ),
+            (
+                ParamChanges {
+                    quorum_percentage: Some(0),
+                    ..ParamChanges::default()
+                },
+                RecommendationError::OutOfRange("quorum_percentage"),
+            ),
+            (
+                // A valid quorum must not be applied when another value fails
+                ParamChanges {
+                    quorum_percentage: Some(55),
+                    vote_duration_hours: Some(721),
+                    ..ParamChanges::default()
+                },
+                RecommendationError::OutOfRange("vote_duration_hours"),
+            ),
+            (
+                ParamChanges {
+                    delegate_weight_percentage: Some(101),
+                    ..ParamChanges::default()
+                },
+                RecommendationError::OutOfRange("delegate_weight_percentage"),
+            ),
+        ];
+        for (changes, error) in cases {
+            assert_eq!(
+                params.apply_recommendation(&recommendation(changes), 2000),
+                Err(error)
+            );
+            assert_eq!(params, before);
+        }
+    }
+
+    #[test]
+    fn test_max_suspension_hours() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        assert_eq!(params.max_suspension_hours, 2160);
+        // ±10% of the current value per update
+        assert_eq!(
+            params.set_max_suspension_hours(1943, 2000),
+            Err(FsmError::InvalidInput)
+        );
+        params.set_max_suspension_hours(1944, 2000).unwrap();
+        assert_eq!(params.update_timestamp, 2000);
+        assert_eq!(
+            params.set_max_suspension_hours(23, 3000),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            params.set_max_suspension_hours(2161, 3000),
+            Err(FsmError::InvalidInput)
+        );
+        let limit = DurationSecs::from_hours(1944).unwrap();
+        assert_eq!(params.check_suspension_window(limit), Ok(()));
+        assert_eq!(
+            params.check_suspension_window(DurationSecs::from_secs(limit.as_secs() + 1)),
+            Err(FsmError::ExceedsPolicyLimit { max_hours: 1944 })
+        );
+
+        let rec = recommendation(ParamChanges {
+            max_suspension_hours: Some(1800),
+            ..ParamChanges::default()
+        });
+        params.apply_recommendation(&rec, 2000).unwrap();
+        assert_eq!(params.max_suspension_hours, 1800);
+        let rec = recommendation(ParamChanges {
+            max_suspension_hours: Some(1500),
+            ..ParamChanges::default()
+        });
+        assert_eq!(
+            params.apply_recommendation(&rec, 2000),
+            Err(RecommendationError::StepTooLarge("max_suspension_hours"))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_params_without_suspension_ceiling_deserialize_to_maximum() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        params.set_max_suspension_hours(2000, 1000).unwrap();
+        let json = serde_json::to_value(&params).unwrap();
+        let restored: GovernanceParams = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(restored, params);
+        let mut legacy = json;
+        legacy
+            .as_object_mut()
+            .unwrap()
+            .remove("max_suspension_hours");
+        let restored: GovernanceParams = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.max_suspension_hours, 2160);
+    }
+
+    #[test]
+    fn test_apply_recommendation_history_is_capped() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
+        for id in 0..(PARAMS_HISTORY_LEN as u64 + 2) {
+            let mut rec = recommendation(ParamChanges::default());
+            rec.id = id;
+            params.apply_recommendation(&rec, 2000).unwrap();
+        }
+        assert_eq!(params.history.len(), PARAMS_HISTORY_LEN);
+        assert_eq!(params.history[0].recommendation_id, 2);
+    }
+
     #[test]
     fn test_governance_params_update_vote_duration_invalid() {
         let mut params = GovernanceParams::new(50, 168, 30, true, 1000).unwrap();
@@ -0,0 +1,412 @@
+//! Batch approval of a grant cohort from one executed proposal.
+//!
+//! An umbrella proposal lists the grants it approves in its `execution_data`
+//! as a `GrantBatchPayload`:
+//!
+//! ```json
+//! {"type": "grant_batch", "grants": [{"grant_id": 7, "amount": 1000}]}
+//! ```
+//!
+//! `approve_from_proposal` checks the proposal and the whole payload first and
+//! fails without touching any grant if either is unusable. It then approves
+//! each supplied Pending grant whose id and `total_amount` match an entry,
+//! linking it through `source_proposal_id`, and reports every grant it left
+//! alone with the reason.
+
+use crate::error::FsmError;
+use crate::grant::lifecycle::Grant;
+use crate::grant::types::GrantStatus;
+use crate::proposal::{Proposal, ProposalStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// One grant approved by a batch payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrantBatchEntry {
+    pub grant_id: u64,
+    /// Must equal the grant's `total_amount`
+    pub amount: u64,
+}
+
+/// `execution_data` of a proposal approving a grant cohort
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "grant_batch")]
+pub struct GrantBatchPayload {
+    pub grants: Vec<GrantBatchEntry>,
+}
+
+impl GrantBatchPayload {
+    /// JSON for `ProposalBuilder::execution_data`
+    pub fn to_execution_data(&self) -> Result<String, FsmError> {
+        serde_json::to_string(self).map_err(|_| FsmError::InvalidInput)
+    }
+
+    /// Parse `execution_data`; `InvalidInput` if it is not a batch payload
+    pub fn from_execution_data(data: &str) -> Result<Self, FsmError> {
+        serde_json::from_str(data).map_err(|_| FsmError::InvalidInput)
+    }
+}
+
+/// Limits on what one umbrella proposal may approve
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchApprovalConfig {
+    /// Most entries a payload may list
+    pub max_grants: usize,
+    /// Largest sum of entry amounts
+    pub max_total_amount: u64,
+}
+
+impl Default for BatchApprovalConfig {
+    fn default() -> Self {
+        Self {
+            max_grants: 100,
+            max_total_amount: u64::MAX,
+        }
+    }
+}
+
+/// Why a grant was not approved
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// The payload does not list the grant
+    NotInPayload,
+    /// The payload lists another amount than the grant's `total_amount`
+    AmountMismatch { expected: u64, actual: u64 },
+    /// The grant is not Pending
+    NotPending(GrantStatus),
+    /// The grant is already linked to another proposal
+    LinkedElsewhere { proposal_id: u64 },
+    /// Approval failed, e.g. `TimeRegression`
+    Failed(FsmError),
+}
+
+/// A grant left unchanged by `approve_from_proposal`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedGrant {
+    pub grant_id: u64,
+    pub reason: SkipReason,
+}
+
+/// Outcome of `approve_from_proposal`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchApprovalReport {
+    pub proposal_id: u64,
+    /// Approved grant ids, in the order supplied
+    pub approved: Vec<u64>,
+    /// Supplied grants left unchanged, in the order supplied
+    pub skipped: Vec<SkippedGrant>,
+    /// Payload entries without a supplied grant, in payload order
+    pub missing: Vec<u64>,
+}
+
+impl BatchApprovalReport {
+    /// Every payload entry was approved and nothing was skipped
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Approve the Pending grants an executed umbrella `proposal` lists
+///
+/// Fails without modifying any grant: `InvalidState` unless the proposal is
+/// Executed; `InvalidInput` if its `execution_data` is missing or not a
+/// `GrantBatchPayload`, lists a grant twice or with a zero amount, exceeds
+/// `config`, or if `grants` holds the same id twice; `Overflow` if the amounts
+/// overflow. Otherwise each matching grant moves to Approved with
+/// `source_proposal_id` set and the rest are reported as skipped.
+pub fn approve_from_proposal<P>(
+    proposal: &Proposal<P>,
+    grants: &mut [Grant],
+    config: &BatchApprovalConfig,
+    current_time: i64,
+) -> Result<BatchApprovalReport, FsmError> {
+    if proposal.status != ProposalStatus::Executed {
+        return Err(FsmError::InvalidState);
+    }
+    let data = proposal
+        .execution_data
+        .as_deref()
+        .ok_or(FsmError::InvalidInput)?;
+    let payload = GrantBatchPayload::from_execution_data(data)?;
+    if payload.grants.len() > config.max_grants {
+        return Err(FsmError::InvalidInput);
+    }
+    let mut amounts = BTreeMap::new();
+    let mut total = 0u64;
+    for entry in &payload.grants {
+        if entry.amount == 0 || amounts.insert(entry.grant_id, entry.amount).is_some() {
+            return Err(FsmError::InvalidInput);
+        }
+        total = total.checked_add(entry.amount).ok_or(FsmError::Overflow)?;
+    }
+    if total > config.max_total_amount {
+        return Err(FsmError::InvalidInput);
+    }
+    let mut supplied = BTreeSet::new();
+    if !grants.iter().all(|grant| supplied.insert(grant.id)) {
+        return Err(FsmError::InvalidInput);
+    }
+
+    let mut report = BatchApprovalReport {
+        proposal_id: proposal.id,
+        missing: payload
+            .grants
+            .iter()
+            .map(|entry| entry.grant_id)
+            .filter(|id| !supplied.contains(id))
+            .collect(),
+        ..BatchApprovalReport::default()
+    };
+    for grant in grants.iter_mut() {
+        let outcome = match amounts.get(&grant.id) {
+            None => Err(SkipReason::NotInPayload),
+            Some(&expected) => approve_entry(grant, proposal.id, expected, current_time),
+        };
+        match outcome {
+            Ok(()) => report.approved.push(grant.id),
+            Err(reason) => report.skipped.push(SkippedGrant {
+                grant_id: grant.id,
+                reason,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+fn approve_entry(
+    grant: &mut Grant,
+    proposal_id: u64,
+    expected: u64,
+    current_time: i64,
+) -> Result<(), SkipReason> {
+    if grant.total_amount != expected {
+        return Err(SkipReason::AmountMismatch {
+            expected,
+            actual: grant.total_amount,
+        });
+    }
+    if grant.status != GrantStatus::Pending {
+        return Err(SkipReason::NotPending(grant.status));
+    }
+    if let Some(linked) = grant.source_proposal_id.filter(|id| *id != proposal_id) {
+        return Err(SkipReason::LinkedElsewhere {
+            proposal_id: linked,
+        });
+    }
+    grant
+        .approve_with_time(current_time)
+        .map_err(SkipReason::Failed)?;
+    grant.source_proposal_id = Some(proposal_id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+
+    fn pending_grant(id: u64, amount: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [1u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            amount,
+            0,
+            100,
+        )
+        .unwrap()
+    }
+
+    fn executed_proposal(execution_data: Option<String>) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            9,
+            "Cohort".to_string(),
+            "Q3 research cohort".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap();
+        proposal.execution_data = execution_data;
+        proposal.status = ProposalStatus::Executed;
+        proposal
+    }
+
+    fn payload(entries: &[(u64, u64)]) -> Option<String> {
+        let payload = GrantBatchPayload {
+            grants: entries
+                .iter()
+                .map(|&(grant_id, amount)| GrantBatchEntry { grant_id, amount })
+                .collect(),
+        };
+        Some(payload.to_execution_data().unwrap())
+    }
+
+    #[test]
+    fn approves_matching_grants_and_reports_the_rest() {
+        let proposal = executed_proposal(payload(&[(1, 500), (2, 700), (3, 900), (4, 100)]));
+        let mut linked = pending_grant(3, 900);
+        linked.source_proposal_id = Some(5);
+        let mut approved = pending_grant(6, 100);
+        approved.approve().unwrap();
+        let mut grants = vec![
+            pending_grant(1, 500),
+            pending_grant(2, 650),
+            linked,
+            approved,
+            pending_grant(8, 10),
+        ];
+        let report =
+            approve_from_proposal(&proposal, &mut grants, &BatchApprovalConfig::default(), 200)
+                .unwrap();
+
+        assert_eq!(report.proposal_id, 9);
+        assert_eq!(report.approved, vec![1]);
+        assert_eq!(
+            report.skipped,
+            vec![
+                SkippedGrant {
+                    grant_id: 2,
+                    reason: SkipReason::AmountMismatch {
+                        expected: 700,
+                        actual: 650,
+                    },
+                },
+                SkippedGrant {
+                    grant_id: 3,
+                    reason: SkipReason::LinkedElsewhere { proposal_id: 5 },
+                },
+                SkippedGrant {
+                    grant_id: 6,
+                    reason: SkipReason::NotInPayload,
+                },
+                SkippedGrant {
+                    grant_id: 8,
+                    reason: SkipReason::NotInPayload,
+                },
+            ]
+        );
+        assert_eq!(report.missing, vec![4]);
+        assert!(!report.is_complete());
+        assert_eq!(grants[0].status, GrantStatus::Approved);
+        assert_eq!(grants[0].source_proposal_id, Some(9));
+        assert_eq!(grants[0].last_event_at, 200);
+        assert_eq!(grants[1].status, GrantStatus::Pending);
+        assert_eq!(grants[1].source_proposal_id, None);
+    }
+
+    #[test]
+    fn not_pending_and_failed_approvals_are_skipped() {
+        let proposal = executed_proposal(payload(&[(1, 500), (2, 500)]));
+        let mut active = pending_grant(1, 500);
+        active.approve().unwrap();
+        active.activate().unwrap();
+        let mut grants = vec![active, pending_grant(2, 500)];
+        let report =
+            approve_from_proposal(&proposal, &mut grants, &BatchApprovalConfig::default(), 50)
+                .unwrap();
+        assert!(report.approved.is_empty());
+        assert_eq!(
+            report.skipped,
+            vec![
+                SkippedGrant {
+                    grant_id: 1,
+                    reason: SkipReason::NotPending(GrantStatus::Active),
+                },
+                SkippedGrant {
+                    grant_id: 2,
+                    reason: SkipReason::Failed(FsmError::TimeRegression),
+                },
+            ]
+        );
+
+        let report = approve_from_proposal(
+            &proposal,
+            &mut grants[1..],
+            &BatchApprovalConfig::default(),
+            200,
+        )
+        .unwrap();
+        assert_eq!(report.approved, vec![2]);
+        assert_eq!(report.missing, vec![1]);
+    }
+
+    #[test]
+    fn malformed_payload_modifies_nothing() {
+        let config = BatchApprovalConfig {
+            max_grants: 2,
+            max_total_amount: 1_000,
+        };
+        let cases = [
+            (executed_proposal(None), FsmError::InvalidInput),
+            (
+                executed_proposal(Some("{\"type\":\"role_change\"}".to_string())),
+                FsmError::InvalidInput,
+            ),
+            (
+                executed_proposal(payload(&[(1, 500), (1, 500)])),
+                FsmError::InvalidInput,
+            ),
+            (
+                executed_proposal(payload(&[(1, 500), (2, 0)])),
+                FsmError::InvalidInput,
+            ),
+            (
+                executed_proposal(payload(&[(1, 500), (2, 501)])),
+                FsmError::InvalidInput,
+            ),
+            (
+                executed_proposal(payload(&[(1, 100), (2, 100), (3, 100)])),
+                FsmError::InvalidInput,
+            ),
+        ];
+        let mut grants = vec![pending_grant(1, 500), pending_grant(2, 500)];
+        let before = grants.clone();
+        for (proposal, error) in cases {
+            assert_eq!(
+                approve_from_proposal(&proposal, &mut grants, &config, 200),
+                Err(error)
+            );
+            assert_eq!(grants, before);
+        }
+
+        let mut draft = executed_proposal(payload(&[(1, 500)]));
+        draft.status = ProposalStatus::Passed;
+        assert_eq!(
+            approve_from_proposal(&draft, &mut grants, &config, 200),
+            Err(FsmError::InvalidState)
+        );
+        let mut duplicated = vec![pending_grant(1, 500), pending_grant(1, 500)];
+        assert_eq!(
+            approve_from_proposal(
+                &executed_proposal(payload(&[(1, 500)])),
+                &mut duplicated,
+                &config,
+                200
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        assert!(duplicated.iter().all(|g| g.status == GrantStatus::Pending));
+    }
+
+    #[test]
+    fn payload_json_shape() {
+        let data = payload(&[(7, 1000)]).unwrap();
+        assert_eq!(
+            data,
+            r#"{"type":"grant_batch","grants":[{"grant_id":7,"amount":1000}]}"#
+        );
+        assert_eq!(
+            GrantBatchPayload::from_execution_data(&data)
+                .unwrap()
+                .grants,
+            vec![GrantBatchEntry {
+                grant_id: 7,
+                amount: 1000
+            }]
+        );
+    }
+}
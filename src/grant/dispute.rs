@@ -0,0 +1,337 @@
+//! Grant disputes after completion.
+//!
+//! A Completed grant stays open to disputes for `dispute_window_seconds` after
+//! `completed_at`. It cannot be archived until the window has elapsed and
+//! every dispute is resolved. An upheld dispute suspends the grant for
+//! clawback handling; a dismissed one changes nothing.
+
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::grant::lifecycle::{EntityId, Grant};
+use crate::grant::types::GrantStatus;
+use crate::reason::SuspensionCode;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DisputeStatus {
+    Open,
+    Upheld,
+    Dismissed,
+}
+
+/// How a dispute was resolved
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum DisputeOutcome {
+    /// The complaint stands; the grant is suspended
+    Upheld,
+    Dismissed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Dispute {
+    pub raised_by: EntityId,
+    pub reason: String,
+    pub raised_at: i64,
+    pub status: DisputeStatus,
+    pub resolved_by: Option<EntityId>,
+    pub resolved_at: Option<i64>,
+}
+
+impl Grant {
+    /// Set how long after completion disputes may be raised
+    pub fn with_dispute_window(mut self, dispute_window_seconds: DurationSecs) -> Self {
+        self.dispute_window_seconds = dispute_window_seconds;
+        self
+    }
+
+    /// `dispute_window_seconds` in seconds
+    #[deprecated(note = "use `dispute_window_seconds.as_secs()`")]
+    pub fn dispute_window_secs(&self) -> i64 {
+        self.dispute_window_seconds.as_secs()
+    }
+
+    /// Whether any dispute is still open
+    pub fn has_open_dispute(&self) -> bool {
+        self.disputes
+            .iter()
+            .any(|d| d.status == DisputeStatus::Open)
+    }
+
+    /// End of the dispute window; None unless Completed with a `completed_at`
+    pub fn dispute_window_ends_at(&self) -> Option<i64> {
+        if self.status != GrantStatus::Completed {
+            return None;
+        }
+        self.completed_at
+            .map(|at| at.saturating_add(self.dispute_window_seconds.as_secs().max(0)))
+    }
+
+    /// Whether disputes still hold a Completed grant back from archiving
+    pub(crate) fn archive_blocked_by_disputes(&self, current_time: i64) -> bool {
+        self.has_open_dispute()
+            || self
+                .dispute_window_ends_at()
+                .is_some_and(|end| current_time < end)
+    }
+
+    /// Raise a dispute; returns its index in `disputes`
+    ///
+    /// Errors: `InvalidInput` for an empty reason, `InvalidState` unless the
+    /// grant is Completed and `current_time` is inside the window, or if
+    /// `raised_by` already has an open dispute, `TimeRegression` if
+    /// `current_time` breaks `time_ordering`.
+    pub fn raise_dispute(
+        &mut self,
+        raised_by: EntityId,
+        reason: String,
+        current_time: i64,
+    ) -> Result<usize, FsmError> {
+        if reason.is_empty() {
+            return Err(FsmError::InvalidInput);
+        }
+        let (Some(completed_at), Some(end)) = (self.completed_at, self.dispute_window_ends_at())
+        else {
+            return Err(FsmError::InvalidState);
+        };
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        if current_time < completed_at || current_time >= end {
+            return Err(FsmError::InvalidState);
+        }
+        if self
+            .disputes
+            .iter()
+            .any(|d| d.raised_by == raised_by && d.status == DisputeStatus::Open)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        self.disputes.push(Dispute {
+            raised_by,
+            reason,
+            raised_at: current_time,
+            status: DisputeStatus::Open,
+            resolved_by: None,
+            resolved_at: None,
+        });
+        self.last_event_at = self.last_event_at.max(current_time);
+        Ok(self.disputes.len() - 1)
+    }
+
+    /// Resolve the open dispute at `index`
+    ///
+    /// An upheld dispute moves a Completed grant to Suspended with
+    /// `SuspensionCode::DisputeUpheld`; a grant already suspended stays so.
+    /// Errors: `InvalidInput` for an unknown index, `InvalidState` if the
+    /// dispute is already resolved, `TimeRegression` if `current_time` breaks
+    /// `time_ordering`.
+    pub fn resolve_dispute(
+        &mut self,
+        index: usize,
+        outcome: DisputeOutcome,
+        resolver: EntityId,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let dispute = self.disputes.get(index).ok_or(FsmError::InvalidInput)?;
+        if dispute.status != DisputeStatus::Open {
+            return Err(FsmError::InvalidState);
+        }
+        self.observe_transition_at("resolve_dispute", current_time, |grant, current_time| {
+            if outcome == DisputeOutcome::Upheld && grant.status != GrantStatus::Suspended {
+                grant.status.validate_transition(GrantStatus::Suspended)?;
+                grant.reason_policy.check(
+                    &grant.status,
+                    &GrantStatus::Suspended,
+                    Some(&grant.disputes[index].reason),
+                )?;
+                grant.status = GrantStatus::Suspended;
+                grant.suspension_code = Some(SuspensionCode::DisputeUpheld);
+                grant.suspension_reason = Some(grant.disputes[index].reason.clone());
+            }
+            let dispute = &mut grant.disputes[index];
+            dispute.status = match outcome {
+                DisputeOutcome::Upheld => DisputeStatus::Upheld,
+                DisputeOutcome::Dismissed => DisputeStatus::Dismissed,
+            };
+            dispute.resolved_by = Some(resolver);
+            dispute.resolved_at = Some(current_time);
+            Ok(())
+        })
+    }
+
+    /// Archive if the grant is finished and nothing holds it back
+    ///
+    /// Returns whether the grant was archived. A Completed grant waits for the
+    /// dispute window to elapse and for every dispute to be resolved.
+    pub fn check_and_auto_archive(&mut self, current_time: i64) -> Result<bool, FsmError> {
+        let finished = matches!(
+            self.status,
+            GrantStatus::Completed
+                | GrantStatus::Cancelled
+                | GrantStatus::Rejected
+                | GrantStatus::Expired
+        );
+        if !finished || self.archive_blocked_by_disputes(current_time) {
+            return Ok(false);
+        }
+        self.archive_with_time(current_time)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+
+    // Completed at 100 with a 50 second window
+    fn completed_grant() -> Grant {
+        let mut grant = Grant::new(
+            1,
+            1,
+            [1u8; 32],
+            GrantCategory::Development,
+            GrantType::Initial,
+            GrantDisbursementType::Escrow,
+            1_000,
+            0,
+            0,
+        )
+        .unwrap()
+        .with_dispute_window(DurationSecs::from_secs(50));
+        grant.approve().unwrap();
+        grant.activate_with_time(10).unwrap();
+        grant.disburse_with_time(1_000, 100).unwrap();
+        grant
+    }
+
+    #[test]
+    fn raise_dispute_only_inside_window() {
+        let mut grant = completed_grant();
+        assert_eq!(grant.dispute_window_ends_at(), Some(150));
+        assert_eq!(
+            grant.raise_dispute([2u8; 32], String::new(), 110),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.raise_dispute([2u8; 32], "Late".to_string(), 110),
+            Ok(0)
+        );
+        assert_eq!(
+            grant.raise_dispute([2u8; 32], "Again".to_string(), 120),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            grant.raise_dispute([3u8; 32], "Quality".to_string(), 149),
+            Ok(1)
+        );
+        assert_eq!(
+            grant.raise_dispute([4u8; 32], "Too late".to_string(), 150),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            grant.raise_dispute([4u8; 32], "Earlier".to_string(), 140),
+            Err(FsmError::TimeRegression)
+        );
+
+        let mut active = completed_grant();
+        active.status = GrantStatus::Active;
+        assert_eq!(
+            active.raise_dispute([2u8; 32], "Early".to_string(), 110),
+            Err(FsmError::InvalidState)
+        );
+    }
+
+    #[test]
+    fn disputes_block_auto_archive() {
+        let mut grant = completed_grant();
+        // Window still open
+        assert_eq!(grant.check_and_auto_archive(149), Ok(false));
+        grant
+            .raise_dispute([2u8; 32], "Late".to_string(), 149)
+            .unwrap();
+        assert_eq!(grant.check_and_auto_archive(200), Ok(false));
+        assert_eq!(grant.archive_with_time(200), Err(FsmError::InvalidState));
+
+        grant
+            .resolve_dispute(0, DisputeOutcome::Dismissed, [9u8; 32], 210)
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Completed);
+        // A raiser may dispute again once the first one is resolved, inside the window only
+        assert_eq!(
+            grant.raise_dispute([2u8; 32], "Again".to_string(), 220),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(grant.check_and_auto_archive(220), Ok(true));
+        assert_eq!(grant.status, GrantStatus::Archived);
+        assert_eq!(grant.check_and_auto_archive(230), Ok(false));
+    }
+
+    #[test]
+    fn upheld_dispute_suspends_grant() {
+        let mut grant = completed_grant();
+        grant
+            .raise_dispute([2u8; 32], "Not delivered".to_string(), 110)
+            .unwrap();
+        grant
+            .raise_dispute([3u8; 32], "Also missing".to_string(), 120)
+            .unwrap();
+        assert_eq!(
+            grant.resolve_dispute(5, DisputeOutcome::Upheld, [9u8; 32], 130),
+            Err(FsmError::InvalidInput)
+        );
+        grant
+            .resolve_dispute(0, DisputeOutcome::Upheld, [9u8; 32], 130)
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Suspended);
+        assert_eq!(grant.suspension_code, Some(SuspensionCode::DisputeUpheld));
+        assert_eq!(grant.suspension_reason, Some("Not delivered".to_string()));
+        assert_eq!(grant.disputes[0].status, DisputeStatus::Upheld);
+        assert_eq!(grant.disputes[0].resolved_by, Some([9u8; 32]));
+        assert_eq!(
+            grant.resolve_dispute(0, DisputeOutcome::Dismissed, [9u8; 32], 140),
+            Err(FsmError::InvalidState)
+        );
+        // A second upheld dispute leaves the grant suspended
+        grant
+            .resolve_dispute(1, DisputeOutcome::Upheld, [9u8; 32], 140)
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Suspended);
+        assert_eq!(grant.check_and_auto_archive(500), Ok(false));
+        // Clawback continues on the existing edges
+        grant.cancel().unwrap();
+        assert_eq!(grant.check_and_auto_archive(500), Ok(true));
+    }
+
+    #[test]
+    fn no_window_archives_immediately() {
+        let mut grant = completed_grant().with_dispute_window(DurationSecs::ZERO);
+        assert_eq!(
+            grant.raise_dispute([2u8; 32], "Late".to_string(), 100),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(grant.check_and_auto_archive(100), Ok(true));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn dispute_window_keeps_raw_seconds() {
+        let grant = completed_grant().with_dispute_window(DurationSecs::from_hours(1).unwrap());
+        assert_eq!(grant.dispute_window_secs(), 3600);
+        #[cfg(feature = "serde")]
+        {
+            let value = serde_json::to_value(&grant).unwrap();
+            assert_eq!(value["dispute_window_seconds"], serde_json::json!(3600));
+        }
+        #[cfg(feature = "borsh")]
+        {
+            let bytes = borsh::to_vec(&grant).unwrap();
+            assert_eq!(borsh::from_slice::<Grant>(&bytes).unwrap(), grant);
+        }
+    }
+}
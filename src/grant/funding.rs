@@ -0,0 +1,474 @@
+//! Grant funding sources.
+//!
+//! A grant's `total_amount` can be funded from several treasury pools. Each
+//! pool's share is a `FundingAllocation`; disbursements are attributed to
+//! pools in allocation order, so the first allocated pool is drawn down first.
+//!
+//! When the grant is paid out in instalments, `payout_schedule` lists them by
+//! due date. Disbursements are attributed to instalments the same way: the
+//! earliest instalment is paid first.
+
+use crate::error::FsmError;
+use crate::grant::lifecycle::Grant;
+use crate::grant::types::GrantStatus;
+use crate::reason::ReasonCode;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Amount committed to a grant from one treasury pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FundingAllocation {
+    pub pool_id: u64,
+    pub amount: u64,
+}
+
+/// Per-pool summary across grants
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PoolExposure {
+    /// Sum of allocations
+    pub committed: u64,
+    /// Part of `committed` already disbursed
+    pub disbursed: u64,
+}
+
+/// Planned disbursement of `amount` at `due_at`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScheduledPayout {
+    pub due_at: i64,
+    pub amount: u64,
+}
+
+impl PoolExposure {
+    /// Committed but not yet disbursed
+    pub fn outstanding(&self) -> u64 {
+        self.committed.saturating_sub(self.disbursed)
+    }
+}
+
+pub(crate) fn accepts_funding_changes(status: GrantStatus) -> bool {
+    matches!(
+        status,
+        GrantStatus::Pending | GrantStatus::Approved | GrantStatus::Active | GrantStatus::Suspended
+    )
+}
+
+impl Grant {
+    /// Sum of all funding allocations
+    pub fn allocated_amount(&self) -> Result<u64, FsmError> {
+        self.funding.iter().try_fold(0u64, |sum, allocation| {
+            sum.checked_add(allocation.amount).ok_or(FsmError::Overflow)
+        })
+    }
+
+    /// Commit `amount` from `pool_id`; the total may not exceed `total_amount`
+    pub fn allocate_funding(&mut self, pool_id: u64, amount: u64) -> Result<(), FsmError> {
+        if !accepts_funding_changes(self.status) {
+            return Err(FsmError::InvalidState);
+        }
+        if amount == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        let allocated = self
+            .allocated_amount()?
+            .checked_add(amount)
+            .ok_or(FsmError::Overflow)?;
+        if allocated > self.total_amount {
+            return Err(FsmError::InvalidInput);
+        }
+        match self.funding.iter_mut().find(|a| a.pool_id == pool_id) {
+            Some(allocation) => allocation.amount += amount,
+            None => self.funding.push(FundingAllocation { pool_id, amount }),
+        }
+        Ok(())
+    }
+
+    /// Sum of all scheduled payouts
+    pub fn scheduled_amount(&self) -> Result<u64, FsmError> {
+        self.payout_schedule.iter().try_fold(0u64, |sum, payout| {
+            sum.checked_add(payout.amount).ok_or(FsmError::Overflow)
+        })
+    }
+
+    /// Plan a payout of `amount` at `due_at`; the schedule may not exceed `total_amount`
+    ///
+    /// Payouts stay ordered by due date; one added at an existing date goes
+    /// after those already there.
+    pub fn schedule_payout(&mut self, due_at: i64, amount: u64) -> Result<(), FsmError> {
+        if !accepts_funding_changes(self.status) {
+            return Err(FsmError::InvalidState);
+        }
+        if amount == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        let scheduled = self
+            .scheduled_amount()?
+            .checked_add(amount)
+            .ok_or(FsmError::Overflow)?;
+        if scheduled > self.total_amount {
+            return Err(FsmError::InvalidInput);
+        }
+        let index = self
+            .payout_schedule
+            .partition_point(|payout| payout.due_at <= due_at);
+        self.payout_schedule
+            .insert(index, ScheduledPayout { due_at, amount });
+        Ok(())
+    }
+
+    /// Scheduled payouts not yet covered by `disbursed_amount`, earliest first
+    ///
+    /// A partly paid instalment is returned with its unpaid part.
+    pub fn remaining_payouts(&self) -> Vec<ScheduledPayout> {
+        self.unpaid_payouts().collect()
+    }
+
+    /// `remaining_payouts` without collecting them
+    pub(crate) fn unpaid_payouts(&self) -> impl Iterator<Item = ScheduledPayout> + '_ {
+        let mut paid = self.disbursed_amount;
+        self.payout_schedule.iter().filter_map(move |payout| {
+            let covered = payout.amount.min(paid);
+            paid -= covered;
+            let amount = payout.amount - covered;
+            (amount > 0).then_some(ScheduledPayout {
+                due_at: payout.due_at,
+                amount,
+            })
+        })
+    }
+
+    /// Release `amount` of the allocation from `pool_id`
+    ///
+    /// Allocations may not drop below what has already been disbursed.
+    pub fn deallocate(&mut self, pool_id: u64, amount: u64) -> Result<(), FsmError> {
+        if !accepts_funding_changes(self.status) {
+            return Err(FsmError::InvalidState);
+        }
+        if amount == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        let index = self
+            .funding
+            .iter()
+            .position(|a| a.pool_id == pool_id)
+            .ok_or(FsmError::InvalidInput)?;
+        let remaining = self.funding[index]
+            .amount
+            .checked_sub(amount)
+            .ok_or(FsmError::InvalidInput)?;
+        let allocated = self.allocated_amount()? - amount;
+        if allocated < self.disbursed_amount {
+            return Err(FsmError::InvalidInput);
+        }
+        if remaining == 0 {
+            self.funding.remove(index);
+        } else {
+            self.funding[index].amount = remaining;
+        }
+        Ok(())
+    }
+
+    /// Per-pool disbursed amounts, attributing disbursements in allocation order
+    pub fn disbursed_by_pool(&self) -> Vec<FundingAllocation> {
+        let mut left = self.disbursed_amount;
+        self.funding
+            .iter()
+            .map(|allocation| {
+                let drawn = allocation.amount.min(left);
+                left -= drawn;
+                FundingAllocation {
+                    pool_id: allocation.pool_id,
+                    amount: drawn,
+                }
+            })
+            .collect()
+    }
+
+    /// Cancel the grant (move from Active or Suspended to Cancelled)
+    ///
+    /// Records `ReasonCode::Other`. Returns the undisbursed amount to release
+    /// back to each pool. `ReasonRequired` if `reason_policy` requires a reason
+    /// (use `cancel_with_code`), `ReasonNotAllowed` if `cancel_with_code` gives
+    /// one it forbids.
+    pub fn cancel(&mut self) -> Result<Vec<FundingAllocation>, FsmError> {
+        self.cancel_at(ReasonCode::Other, None)
+    }
+
+    /// Cancel with a reason code and free-text detail (see `cancel`)
+    pub fn cancel_with_code(
+        &mut self,
+        code: ReasonCode,
+        reason: String,
+    ) -> Result<Vec<FundingAllocation>, FsmError> {
+        self.cancel_at(code, Some(reason))
+    }
+
+    fn cancel_at(
+        &mut self,
+        code: ReasonCode,
+        reason: Option<String>,
+    ) -> Result<Vec<FundingAllocation>, FsmError> {
+        self.observe_transition("cancel", |grant| {
+            if grant.status != GrantStatus::Active && grant.status != GrantStatus::Suspended {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant
+                .reason_policy
+                .check(&grant.status, &GrantStatus::Cancelled, reason.as_deref())?;
+            grant.status = GrantStatus::Cancelled;
+            grant.auto_resume_at = None;
+            grant.auto_cancel_at = None;
+            grant.cancellation_code = Some(code);
+            grant.cancellation_reason = reason;
+            Ok(())
+        })?;
+        let release = self
+            .funding
+            .iter()
+            .zip(self.disbursed_by_pool())
+            .map(|(allocation, drawn)| FundingAllocation {
+                pool_id: allocation.pool_id,
+                amount: allocation.amount - drawn.amount,
+            })
+            .filter(|release| release.amount > 0)
+            .collect();
+        Ok(release)
+    }
+}
+
+/// Committed vs disbursed per pool across all non-terminal grants, keyed by pool id
+///
+/// Grants in Pending, Approved, Active or Suspended status are counted.
+pub fn pool_exposure(grants: &[Grant]) -> BTreeMap<u64, PoolExposure> {
+    let mut exposure: BTreeMap<u64, PoolExposure> = BTreeMap::new();
+    for grant in grants
+        .iter()
+        .filter(|grant| accepts_funding_changes(grant.status))
+    {
+        for (allocation, drawn) in grant.funding.iter().zip(grant.disbursed_by_pool()) {
+            let entry = exposure.entry(allocation.pool_id).or_default();
+            entry.committed = entry.committed.saturating_add(allocation.amount);
+            entry.disbursed = entry.disbursed.saturating_add(drawn.amount);
+        }
+    }
+    exposure
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+
+    fn create_test_grant(id: u64, amount: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            amount,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn activate(grant: &mut Grant) {
+        grant.approve().unwrap();
+        grant.activate().unwrap();
+    }
+
+    #[test]
+    fn allocate_within_total() {
+        let mut grant = create_test_grant(1, 1_000);
+        grant.allocate_funding(10, 600).unwrap();
+        grant.allocate_funding(20, 300).unwrap();
+        grant.allocate_funding(10, 100).unwrap();
+        assert_eq!(
+            grant.funding,
+            vec![
+                FundingAllocation {
+                    pool_id: 10,
+                    amount: 700
+                },
+                FundingAllocation {
+                    pool_id: 20,
+                    amount: 300
+                },
+            ]
+        );
+        assert_eq!(grant.allocate_funding(30, 1), Err(FsmError::InvalidInput));
+        assert_eq!(grant.allocate_funding(30, 0), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn allocate_overflow() {
+        let mut grant = create_test_grant(1, u64::MAX);
+        grant.allocate_funding(10, u64::MAX).unwrap();
+        assert_eq!(grant.allocate_funding(20, 1), Err(FsmError::Overflow));
+    }
+
+    #[test]
+    fn deallocate_respects_disbursed() {
+        let mut grant = create_test_grant(1, 1_000);
+        grant.allocate_funding(10, 600).unwrap();
+        grant.allocate_funding(20, 400).unwrap();
+        activate(&mut grant);
+        grant.disburse(700).unwrap();
+
+        assert_eq!(grant.deallocate(20, 400), Err(FsmError::InvalidInput));
+        grant.deallocate(20, 300).unwrap();
+        assert_eq!(grant.allocated_amount(), Ok(700));
+        assert_eq!(grant.deallocate(30, 1), Err(FsmError::InvalidInput));
+        assert_eq!(grant.deallocate(10, 700), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn partially_disbursed_then_cancelled() {
+        let mut grant = create_test_grant(1, 1_000);
+        grant.allocate_funding(10, 600).unwrap();
+        grant.allocate_funding(20, 400).unwrap();
+        activate(&mut grant);
+        grant.disburse(750).unwrap();
+
+        assert_eq!(
+            grant.disbursed_by_pool(),
+            vec![
+                FundingAllocation {
+                    pool_id: 10,
+                    amount: 600
+                },
+                FundingAllocation {
+                    pool_id: 20,
+                    amount: 150
+                },
+            ]
+        );
+
+        let release = grant
+            .cancel_with_code(ReasonCode::SecurityVeto, "Board veto".to_string())
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Cancelled);
+        assert_eq!(grant.cancellation_code, Some(ReasonCode::SecurityVeto));
+        assert_eq!(grant.cancellation_reason, Some("Board veto".to_string()));
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&grant).unwrap();
+            assert_eq!(json["cancellation_code"], "SecurityVeto");
+            assert_eq!(json["cancellation_reason"], "Board veto");
+        }
+        assert_eq!(
+            release,
+            vec![FundingAllocation {
+                pool_id: 20,
+                amount: 250
+            }]
+        );
+        assert_eq!(grant.cancel(), Err(FsmError::InvalidStateTransition));
+        assert_eq!(grant.allocate_funding(10, 1), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn cancel_requires_active_or_suspended() {
+        let mut grant = create_test_grant(1, 1_000);
+        assert_eq!(grant.cancel(), Err(FsmError::InvalidStateTransition));
+        assert_eq!(grant.status, GrantStatus::Pending);
+    }
+
+    #[test]
+    fn exposure_across_grants() {
+        let mut first = create_test_grant(1, 1_000);
+        first.allocate_funding(10, 600).unwrap();
+        first.allocate_funding(20, 400).unwrap();
+        activate(&mut first);
+        first.disburse(700).unwrap();
+
+        let mut second = create_test_grant(2, 500);
+        second.allocate_funding(20, 500).unwrap();
+
+        let mut cancelled = create_test_grant(3, 200);
+        cancelled.allocate_funding(10, 200).unwrap();
+        activate(&mut cancelled);
+        cancelled.cancel().unwrap();
+
+        let exposure = pool_exposure(&[first, second, cancelled]);
+        assert_eq!(
+            exposure.get(&10),
+            Some(&PoolExposure {
+                committed: 600,
+                disbursed: 600
+            })
+        );
+        assert_eq!(
+            exposure.get(&20),
+            Some(&PoolExposure {
+                committed: 900,
+                disbursed: 100
+            })
+        );
+        assert_eq!(exposure[&20].outstanding(), 800);
+        assert_eq!(exposure.len(), 2);
+    }
+
+    #[test]
+    fn payout_schedule_ordered_and_bounded() {
+        let mut grant = create_test_grant(1, 1_000);
+        grant.schedule_payout(300, 400).unwrap();
+        grant.schedule_payout(100, 200).unwrap();
+        grant.schedule_payout(300, 100).unwrap();
+        assert_eq!(
+            grant.payout_schedule,
+            vec![
+                ScheduledPayout {
+                    due_at: 100,
+                    amount: 200
+                },
+                ScheduledPayout {
+                    due_at: 300,
+                    amount: 400
+                },
+                ScheduledPayout {
+                    due_at: 300,
+                    amount: 100
+                },
+            ]
+        );
+        assert_eq!(grant.scheduled_amount(), Ok(700));
+        assert_eq!(grant.schedule_payout(400, 301), Err(FsmError::InvalidInput));
+        assert_eq!(grant.schedule_payout(400, 0), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn remaining_payouts_after_disbursement() {
+        let mut grant = create_test_grant(1, 1_000);
+        grant.schedule_payout(100, 300).unwrap();
+        grant.schedule_payout(200, 300).unwrap();
+        grant.schedule_payout(300, 400).unwrap();
+        activate(&mut grant);
+        grant.disburse(450).unwrap();
+        assert_eq!(
+            grant.remaining_payouts(),
+            vec![
+                ScheduledPayout {
+                    due_at: 200,
+                    amount: 150
+                },
+                ScheduledPayout {
+                    due_at: 300,
+                    amount: 400
+                },
+            ]
+        );
+        grant.disburse(550).unwrap();
+        assert!(grant.remaining_payouts().is_empty());
+        assert_eq!(grant.schedule_payout(400, 1), Err(FsmError::InvalidState));
+    }
+}
@@ -1,13 +1,39 @@
 //! FSM Grant lifecycle helpers.
 
+use crate::duration::DurationSecs;
 use crate::error::FsmError;
+use crate::governance_params::GovernanceParams;
+use crate::grant::dispute::Dispute;
+use crate::grant::funding::{FundingAllocation, ScheduledPayout};
 use crate::grant::types::*;
+use crate::grant::vote::{GrantTally, GrantVote, GrantVotingOutcome, VotePrivacy, VoterToken};
+use crate::metrics;
+use crate::proposal::TallyOverflow;
+use crate::reason::{ReasonCode, SuspensionCode};
+use crate::reason_policy::ReasonPolicy;
+use crate::telemetry::OperationSpan;
+use crate::time_guard::{EventTime, TimeOrdering, check_construction_time};
+#[cfg(feature = "borsh")]
 use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 pub type EntityId = [u8; 32];
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// Deadlines requested with a suspension (see `Grant::suspend_with_window`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SuspensionWindow {
+    /// Resume automatically at this time
+    pub auto_resume_at: Option<i64>,
+    /// Cancel automatically this long after the suspension
+    pub auto_cancel_after: Option<DurationSecs>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Grant {
     pub id: u64,
     pub idea_id: u64,
@@ -23,6 +49,75 @@ pub struct Grant {
     pub verification_status: VerificationStatus,
     pub created_at: i64,
     pub enabled: bool,
+    /// Treasury pools funding this grant (see `grant::funding`)
+    pub funding: Vec<FundingAllocation>,
+    /// Set by `activate_with_time`
+    pub activated_at: Option<i64>,
+    /// Set by `disburse_with_time` when the final amount is disbursed
+    pub completed_at: Option<i64>,
+    /// Set by `archive_with_time`
+    pub archived_at: Option<i64>,
+    /// Set by `cancel` / `cancel_with_code`
+    pub cancellation_code: Option<ReasonCode>,
+    pub cancellation_reason: Option<String>,
+    /// Set while Suspended
+    pub suspension_code: Option<SuspensionCode>,
+    pub suspension_reason: Option<String>,
+    /// Timestamp of the latest timestamped event (creation included)
+    pub last_event_at: i64,
+    /// Rule applied to `current_time` against `last_event_at`
+    pub time_ordering: TimeOrdering,
+    /// Seconds a transition may lag `last_event_at` and be clamped (see `time_guard`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub clock_skew_tolerance_seconds: u32,
+    /// Votes recorded by `cast_vote`, in casting order
+    pub votes: Vec<GrantVote>,
+    /// Running weight per vote type of `votes`
+    pub tally: GrantTally,
+    /// What votes do when a tally would overflow
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tally_overflow: TallyOverflow,
+    /// Set when voting is finalized
+    pub voting_outcome: Option<GrantVotingOutcome>,
+    /// How long after `completed_at` disputes may be raised
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dispute_window_seconds: DurationSecs,
+    /// Disputes in the order raised
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub disputes: Vec<Dispute>,
+    /// Sorted, lowercased tags (see `tags`)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, serialize_with = "crate::tags::serialize_sorted")
+    )]
+    pub tags: Vec<String>,
+    /// Executed proposal that funded this grant (see `links`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source_proposal_id: Option<u64>,
+    /// Whether votes carry voter identities (see `set_vote_privacy`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vote_privacy: VotePrivacy,
+    /// Blinded voter tokens of private votes, sorted
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vote_tokens: Vec<VoterToken>,
+    /// Planned disbursements ordered by due date (see `schedule_payout`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub payout_schedule: Vec<ScheduledPayout>,
+    /// Which transitions must or must not carry a reason (see `reason_policy`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reason_policy: ReasonPolicy<GrantStatus>,
+    /// Resume deadline of the current suspension (see `suspend_with_window`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_resume_at: Option<i64>,
+    /// Cancellation deadline of the current suspension (see `suspend_with_window`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub auto_cancel_at: Option<i64>,
+    /// Set by `approve_with_time` and by voting that approves the grant
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub approved_at: Option<i64>,
+    /// Spawned straight from an approved idea by `Idea::spawn_grant`, without a proposal
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fast_track: bool,
 }
 
 impl Grant {
@@ -38,6 +133,7 @@ impl Grant {
         reputation_bonus: u64,
         created_at: i64,
     ) -> Result<Self, FsmError> {
+        check_construction_time(created_at)?;
         let total_amount = base_amount
             .checked_add(reputation_bonus)
             .ok_or(FsmError::Overflow)?;
@@ -56,41 +152,307 @@ impl Grant {
             verification_status: VerificationStatus::Pending,
             created_at,
             enabled: true,
+            funding: Vec::new(),
+            activated_at: None,
+            completed_at: None,
+            archived_at: None,
+            cancellation_code: None,
+            cancellation_reason: None,
+            suspension_code: None,
+            suspension_reason: None,
+            last_event_at: created_at,
+            time_ordering: TimeOrdering::default(),
+            clock_skew_tolerance_seconds: 0,
+            votes: Vec::new(),
+            tally: GrantTally::default(),
+            tally_overflow: TallyOverflow::Reject,
+            voting_outcome: None,
+            dispute_window_seconds: DurationSecs::ZERO,
+            disputes: Vec::new(),
+            tags: Vec::new(),
+            source_proposal_id: None,
+            vote_privacy: VotePrivacy::Public,
+            vote_tokens: Vec::new(),
+            payout_schedule: Vec::new(),
+            reason_policy: ReasonPolicy::default(),
+            auto_resume_at: None,
+            auto_cancel_at: None,
+            approved_at: None,
+            fast_track: false,
         })
     }
 
+    /// Set the rule for lifecycle timestamps; `Unchecked` disables it for tests and simulations
+    pub fn with_time_ordering(mut self, time_ordering: TimeOrdering) -> Self {
+        self.time_ordering = time_ordering;
+        self
+    }
+
+    /// Let transitions lag `last_event_at` by up to `seconds` of clock skew (see `time_guard`)
+    pub fn with_clock_skew_tolerance(mut self, seconds: u32) -> Self {
+        self.clock_skew_tolerance_seconds = seconds;
+        self
+    }
+
+    /// Set which transitions must or must not carry a reason
+    pub fn with_reason_policy(mut self, reason_policy: ReasonPolicy<GrantStatus>) -> Self {
+        self.reason_policy = reason_policy;
+        self
+    }
+
+    /// Set what votes do when a tally would overflow
+    pub fn with_tally_overflow(mut self, tally_overflow: TallyOverflow) -> Self {
+        self.tally_overflow = tally_overflow;
+        self
+    }
+
     pub fn approve(&mut self) -> Result<(), FsmError> {
-        if self.status != GrantStatus::Pending {
-            return Err(FsmError::InvalidState);
-        }
-        self.status = GrantStatus::Approved;
-        Ok(())
+        self.approve_at(None)
+    }
+
+    /// Approve, checking `current_time` against `time_ordering`
+    pub fn approve_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.approve_at(Some(current_time))
+    }
+
+    fn approve_at(&mut self, current_time: Option<i64>) -> Result<(), FsmError> {
+        self.observe_timed_transition("approve", current_time, |grant, current_time| {
+            if grant.status != GrantStatus::Pending {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant.status = GrantStatus::Approved;
+            grant.approved_at = current_time;
+            Ok(())
+        })
     }
 
     pub fn activate(&mut self) -> Result<(), FsmError> {
-        if self.status != GrantStatus::Approved {
-            return Err(FsmError::InvalidState);
-        }
-        self.status = GrantStatus::Active;
-        Ok(())
+        self.activate_at(None)
+    }
+
+    /// Activate and record `activated_at`
+    pub fn activate_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.activate_at(Some(current_time))
+    }
+
+    fn activate_at(&mut self, current_time: Option<i64>) -> Result<(), FsmError> {
+        self.observe_timed_transition("activate", current_time, |grant, current_time| {
+            if grant.status != GrantStatus::Approved {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant.status = GrantStatus::Active;
+            grant.activated_at = current_time;
+            Ok(())
+        })
     }
 
     pub fn disburse(&mut self, amount: u64) -> Result<(), FsmError> {
-        if self.status != GrantStatus::Active {
-            return Err(FsmError::InvalidState);
-        }
-        let new_amount = self
-            .disbursed_amount
-            .checked_add(amount)
-            .ok_or(FsmError::Overflow)?;
-        if new_amount > self.total_amount {
-            return Err(FsmError::InvalidInput);
-        }
-        self.disbursed_amount = new_amount;
-        if self.disbursed_amount == self.total_amount {
-            self.status = GrantStatus::Completed;
-        }
-        Ok(())
+        self.disburse_at(amount, None)
+    }
+
+    /// Disburse and record `completed_at` if this completes the grant
+    pub fn disburse_with_time(&mut self, amount: u64, current_time: i64) -> Result<(), FsmError> {
+        self.disburse_at(amount, Some(current_time))
+    }
+
+    fn disburse_at(&mut self, amount: u64, current_time: Option<i64>) -> Result<(), FsmError> {
+        self.observe_timed_transition("disburse", current_time, |grant, current_time| {
+            if grant.status != GrantStatus::Active {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            let new_amount = grant
+                .disbursed_amount
+                .checked_add(amount)
+                .ok_or(FsmError::Overflow)?;
+            if new_amount > grant.total_amount {
+                return Err(FsmError::InvalidInput);
+            }
+            grant.disbursed_amount = new_amount;
+            if grant.disbursed_amount == grant.total_amount {
+                grant.status = GrantStatus::Completed;
+                grant.completed_at = current_time;
+            }
+            Ok(())
+        })
+    }
+
+    /// Archive a finished grant (Completed, Cancelled, Rejected or Expired)
+    ///
+    /// A Completed grant inside its dispute window or with an open dispute is
+    /// `InvalidState`.
+    pub fn archive_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.observe_transition_at("archive", current_time, |grant, current_time| {
+            if grant.status == GrantStatus::Completed
+                && grant.archive_blocked_by_disputes(current_time)
+            {
+                return Err(FsmError::InvalidState);
+            }
+            if !matches!(
+                grant.status,
+                GrantStatus::Completed
+                    | GrantStatus::Cancelled
+                    | GrantStatus::Rejected
+                    | GrantStatus::Expired
+            ) {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant.status = GrantStatus::Archived;
+            grant.archived_at = Some(current_time);
+            Ok(())
+        })
+    }
+
+    /// Suspend an Active grant
+    ///
+    /// `ReasonRequired` or `ReasonNotAllowed` if `reason` breaks `reason_policy`.
+    pub fn suspend(&mut self, code: SuspensionCode, reason: String) -> Result<(), FsmError> {
+        self.suspend_at(code, reason, None, (None, None))
+    }
+
+    /// Suspend an Active grant with automatic resume or cancellation deadlines
+    ///
+    /// Each requested window, measured from `current_time`, must be positive
+    /// (`InvalidInput`) and no longer than `params.max_suspension_hours`
+    /// (`ExceedsPolicyLimit`). The deadlines are kept in `auto_resume_at` and
+    /// `auto_cancel_at` until the grant resumes or is cancelled; acting on them
+    /// is up to the caller.
+    pub fn suspend_with_window(
+        &mut self,
+        code: SuspensionCode,
+        reason: String,
+        window: SuspensionWindow,
+        params: &GovernanceParams,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let auto_resume_at = match window.auto_resume_at {
+            Some(at) if at <= current_time => return Err(FsmError::InvalidInput),
+            Some(at) => {
+                let length = at.checked_sub(current_time).ok_or(FsmError::Overflow)?;
+                params.check_suspension_window(DurationSecs::from_secs(length))?;
+                Some(at)
+            }
+            None => None,
+        };
+        let auto_cancel_at = match window.auto_cancel_after {
+            Some(after) if after <= DurationSecs::ZERO => return Err(FsmError::InvalidInput),
+            Some(after) => {
+                params.check_suspension_window(after)?;
+                Some(
+                    current_time
+                        .checked_add(after.as_secs())
+                        .ok_or(FsmError::Overflow)?,
+                )
+            }
+            None => None,
+        };
+        self.suspend_at(
+            code,
+            reason,
+            Some(current_time),
+            (auto_resume_at, auto_cancel_at),
+        )
+    }
+
+    fn suspend_at(
+        &mut self,
+        code: SuspensionCode,
+        reason: String,
+        current_time: Option<i64>,
+        (auto_resume_at, auto_cancel_at): (Option<i64>, Option<i64>),
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition("suspend", current_time, |grant, _| {
+            if grant.status != GrantStatus::Active {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant
+                .reason_policy
+                .check(&grant.status, &GrantStatus::Suspended, Some(&reason))?;
+            grant.status = GrantStatus::Suspended;
+            grant.suspension_code = Some(code);
+            grant.suspension_reason = Some(reason);
+            grant.auto_resume_at = auto_resume_at;
+            grant.auto_cancel_at = auto_cancel_at;
+            Ok(())
+        })
+    }
+
+    /// Resume a Suspended grant, clearing the suspension reason and deadlines
+    pub fn resume(&mut self) -> Result<(), FsmError> {
+        self.observe_transition("resume", |grant| {
+            if grant.status != GrantStatus::Suspended {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            grant.status = GrantStatus::Active;
+            grant.suspension_code = None;
+            grant.suspension_reason = None;
+            grant.auto_resume_at = None;
+            grant.auto_cancel_at = None;
+            Ok(())
+        })
+    }
+
+    /// Run a lifecycle step and report its outcome to the metrics sink
+    pub(crate) fn observe_transition(
+        &mut self,
+        action: &str,
+        apply: impl FnOnce(&mut Self) -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        let from = self.status;
+        let span = OperationSpan::transition("grant", self.id, action, &from);
+        let result = apply(self);
+        metrics::record_transition("grant", action, &from, &self.status, &result);
+        span.finish(Some(&self.status), &result);
+        result
+    }
+}
+
+impl Grant {
+    /// `observe_transition` for a step taken at `current_time`, if known
+    ///
+    /// A timestamped step fails with `TimeRegression` if `current_time` breaks
+    /// `time_ordering` by more than the clock skew tolerance. `apply` gets the
+    /// time to record, clamped if it was within the tolerance, and
+    /// `last_event_at` advances to it.
+    pub(crate) fn observe_timed_transition(
+        &mut self,
+        action: &str,
+        current_time: Option<i64>,
+        apply: impl FnOnce(&mut Self, Option<i64>) -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        self.observe_transition(action, |grant| {
+            let current_time = current_time
+                .map(|time| grant.event_time(time).map(|at| at.time))
+                .transpose()?;
+            apply(grant, current_time)?;
+            if let Some(current_time) = current_time {
+                grant.last_event_at = grant.last_event_at.max(current_time);
+            }
+            Ok(())
+        })
+    }
+
+    /// `observe_timed_transition` for a step whose time is always known
+    pub(crate) fn observe_transition_at(
+        &mut self,
+        action: &str,
+        current_time: i64,
+        apply: impl FnOnce(&mut Self, i64) -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition(action, Some(current_time), |grant, at| {
+            apply(grant, at.unwrap_or(current_time))
+        })
+    }
+
+    /// Time a transition at `current_time` would be recorded at
+    ///
+    /// `TimeRegression` if it lags `last_event_at` by more than
+    /// `clock_skew_tolerance_seconds` (see `TimeOrdering::resolve`).
+    pub fn event_time(&self, current_time: i64) -> Result<EventTime, FsmError> {
+        self.time_ordering.resolve(
+            self.last_event_at,
+            current_time,
+            self.clock_skew_tolerance_seconds,
+        )
     }
 }
 
@@ -111,6 +473,33 @@ impl Default for Grant {
             verification_status: VerificationStatus::Pending,
             created_at: 0,
             enabled: true,
+            funding: Vec::new(),
+            activated_at: None,
+            completed_at: None,
+            archived_at: None,
+            cancellation_code: None,
+            cancellation_reason: None,
+            suspension_code: None,
+            suspension_reason: None,
+            last_event_at: 0,
+            time_ordering: TimeOrdering::default(),
+            clock_skew_tolerance_seconds: 0,
+            votes: Vec::new(),
+            tally: GrantTally::default(),
+            tally_overflow: TallyOverflow::Reject,
+            voting_outcome: None,
+            dispute_window_seconds: DurationSecs::ZERO,
+            disputes: Vec::new(),
+            tags: Vec::new(),
+            source_proposal_id: None,
+            vote_privacy: VotePrivacy::Public,
+            vote_tokens: Vec::new(),
+            payout_schedule: Vec::new(),
+            reason_policy: ReasonPolicy::default(),
+            auto_resume_at: None,
+            auto_cancel_at: None,
+            approved_at: None,
+            fast_track: false,
         }
     }
 }
@@ -118,6 +507,7 @@ impl Default for Grant {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reason_policy::ReasonRequirement;
 
     fn sample_id() -> EntityId {
         [2u8; 32]
@@ -163,4 +553,240 @@ mod tests {
         grant.activate().unwrap();
         assert!(grant.disburse(3_000).is_err());
     }
+
+    #[test]
+    fn timed_lifecycle_records_timestamps() {
+        let mut grant = Grant::new(
+            3,
+            5,
+            sample_id(),
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1_000,
+            0,
+            100,
+        )
+        .unwrap();
+
+        grant.approve_with_time(150).unwrap();
+        assert_eq!(grant.approved_at, Some(150));
+        grant.activate_with_time(200).unwrap();
+        assert_eq!(grant.activated_at, Some(200));
+        grant.disburse_with_time(400, 300).unwrap();
+        assert_eq!(grant.completed_at, None);
+        grant.disburse_with_time(600, 400).unwrap();
+        assert_eq!(grant.status, GrantStatus::Completed);
+        assert_eq!(grant.completed_at, Some(400));
+    }
+
+    #[test]
+    fn suspend_and_resume() {
+        let mut grant = Grant::new(
+            4,
+            5,
+            sample_id(),
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1_000,
+            0,
+            100,
+        )
+        .unwrap();
+        assert_eq!(
+            grant.suspend(SuspensionCode::Other, "early".to_string()),
+            Err(FsmError::InvalidStateTransition)
+        );
+
+        grant.approve().unwrap();
+        grant.activate().unwrap();
+        grant
+            .suspend(SuspensionCode::MilestoneMissed, "M2 late".to_string())
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Suspended);
+        assert_eq!(grant.suspension_code, Some(SuspensionCode::MilestoneMissed));
+        assert_eq!(grant.disburse(1), Err(FsmError::InvalidStateTransition));
+
+        grant.resume().unwrap();
+        assert_eq!(grant.status, GrantStatus::Active);
+        assert_eq!(grant.suspension_code, None);
+        assert_eq!(grant.suspension_reason, None);
+    }
+
+    #[test]
+    fn suspension_windows_respect_the_policy_ceiling() {
+        let mut params = GovernanceParams::new(50, 168, 30, true, 0).unwrap();
+        params.set_max_suspension_hours(2000, 0).unwrap();
+        let mut grant = Grant::new(
+            4,
+            5,
+            sample_id(),
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1_000,
+            0,
+            100,
+        )
+        .unwrap();
+        grant.approve().unwrap();
+        grant.activate_with_time(100).unwrap();
+        let hour = 3600;
+        let too_long = SuspensionWindow {
+            auto_resume_at: Some(1_000 + 2001 * hour),
+            auto_cancel_after: None,
+        };
+        assert_eq!(
+            grant.suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                too_long,
+                &params,
+                1_000
+            ),
+            Err(FsmError::ExceedsPolicyLimit { max_hours: 2000 })
+        );
+        let too_long = SuspensionWindow {
+            auto_resume_at: None,
+            auto_cancel_after: DurationSecs::from_hours(2001),
+        };
+        assert_eq!(
+            grant.suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                too_long,
+                &params,
+                1_000
+            ),
+            Err(FsmError::ExceedsPolicyLimit { max_hours: 2000 })
+        );
+        let in_the_past = SuspensionWindow {
+            auto_resume_at: Some(1_000),
+            auto_cancel_after: None,
+        };
+        assert_eq!(
+            grant.suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                in_the_past,
+                &params,
+                1_000
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(grant.status, GrantStatus::Active);
+
+        let window = SuspensionWindow {
+            auto_resume_at: Some(1_000 + 2000 * hour),
+            auto_cancel_after: DurationSecs::from_hours(48),
+        };
+        grant
+            .suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                window,
+                &params,
+                1_000,
+            )
+            .unwrap();
+        assert_eq!(grant.auto_resume_at, Some(1_000 + 2000 * hour));
+        assert_eq!(grant.auto_cancel_at, Some(1_000 + 48 * hour));
+        assert_eq!(grant.last_event_at, 1_000);
+        grant.resume().unwrap();
+        assert_eq!((grant.auto_resume_at, grant.auto_cancel_at), (None, None));
+    }
+
+    #[test]
+    fn reason_policy_guards_suspend_and_cancel() {
+        let active = |policy: ReasonPolicy<GrantStatus>| {
+            let mut grant = Grant::new(
+                4,
+                5,
+                sample_id(),
+                GrantCategory::Research,
+                GrantType::Initial,
+                GrantDisbursementType::Standard,
+                1_000,
+                0,
+                100,
+            )
+            .unwrap()
+            .with_reason_policy(policy);
+            grant.approve().unwrap();
+            grant.activate().unwrap();
+            grant
+        };
+
+        let mut grant = active(ReasonPolicy::default());
+        grant.suspend(SuspensionCode::Other, String::new()).unwrap();
+        grant.cancel().unwrap();
+
+        let mut grant = active(ReasonPolicy::strict());
+        assert!(matches!(
+            grant.suspend(SuspensionCode::Other, String::new()),
+            Err(FsmError::ReasonRequired(_))
+        ));
+        assert!(matches!(grant.cancel(), Err(FsmError::ReasonRequired(_))));
+        assert_eq!(grant.status, GrantStatus::Active);
+        grant
+            .cancel_with_code(ReasonCode::SecurityVeto, "Board veto".to_string())
+            .unwrap();
+
+        let mut grant = active(ReasonPolicy::new().with_rule(
+            GrantStatus::Active,
+            GrantStatus::Cancelled,
+            ReasonRequirement::Forbidden,
+        ));
+        assert!(matches!(
+            grant.cancel_with_code(ReasonCode::Spam, "spam".to_string()),
+            Err(FsmError::ReasonNotAllowed(_))
+        ));
+        grant.cancel().unwrap();
+    }
+
+    fn grant_at(created_at: i64) -> Result<Grant, FsmError> {
+        Grant::new(
+            7,
+            10,
+            sample_id(),
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1_000,
+            0,
+            created_at,
+        )
+    }
+
+    #[test]
+    fn time_regression_rejected() {
+        let mut grant = grant_at(1_000).unwrap();
+        grant.approve().unwrap();
+        assert_eq!(grant.activate_with_time(999), Err(FsmError::TimeRegression));
+        assert_eq!(grant.status, GrantStatus::Approved);
+        grant.activate_with_time(1_000).unwrap();
+        grant.disburse_with_time(1_000, 2_000).unwrap();
+        assert_eq!(grant.last_event_at, 2_000);
+        assert_eq!(
+            grant.archive_with_time(1_500),
+            Err(FsmError::TimeRegression)
+        );
+        grant.archive_with_time(2_000).unwrap();
+    }
+
+    #[test]
+    fn negative_creation_time_rejected() {
+        assert_eq!(grant_at(-5), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn time_ordering_opt_out() {
+        let mut grant = grant_at(1_000)
+            .unwrap()
+            .with_time_ordering(TimeOrdering::Unchecked);
+        grant.approve().unwrap();
+        grant.activate_with_time(10).unwrap();
+        assert_eq!(grant.activated_at, Some(10));
+    }
 }
@@ -2,12 +2,35 @@
 //! It exposes a simple Grant data model and lifecycle helpers without DAO-specific
 //! dependencies.
 
+#[cfg(feature = "serde")]
+pub mod batch;
+pub mod dispute;
+pub mod funding;
 pub mod lifecycle;
+pub mod reporting;
+#[cfg(feature = "governance-meta")]
+pub mod review;
 pub mod types;
 pub mod vote;
 pub mod voting_types;
 
-pub use lifecycle::Grant;
+#[cfg(feature = "serde")]
+pub use batch::{
+    BatchApprovalConfig, BatchApprovalReport, GrantBatchEntry, GrantBatchPayload, SkipReason,
+    SkippedGrant, approve_from_proposal,
+};
+pub use dispute::{Dispute, DisputeOutcome, DisputeStatus};
+pub use funding::{FundingAllocation, PoolExposure, ScheduledPayout};
+pub use lifecycle::{Grant, SuspensionWindow};
+pub use reporting::{
+    AmountBucket, BUCKET_SECONDS, CohortReport, MonthBucket, OutcomeTotals, amount_histogram,
+    cohort_analysis, unlock_schedule,
+};
+#[cfg(feature = "governance-meta")]
+pub use review::{AuthorshipIndex, ReviewAssignment, ReviewAssignments, ReviewCommittee};
 pub use types::*;
-pub use vote::{GrantVote, VoterType};
+pub use vote::{
+    GrantTally, GrantVote, GrantVotingOutcome, GrantVotingPolicy, RevealPolicy, VotePrivacy,
+    VoterToken, VoterType,
+};
 pub use voting_types::VoteType;
@@ -0,0 +1,484 @@
+//! Treasury views over a grant portfolio.
+//!
+//! `unlock_schedule` spreads the remaining scheduled payouts of open grants
+//! over consecutive 30-day buckets, and `amount_histogram` counts grants by
+//! total amount. `cohort_analysis` follows the grants approved in a window to
+//! their outcomes. Bucket boundaries depend only on the arguments, and the
+//! grants may come in any order. Totals saturate at `u64::MAX` under
+//! `ArithmeticPolicy::Saturating` and mark the bucket `degraded`.
+
+use crate::error::FsmError;
+use crate::grant::funding::accepts_funding_changes;
+use crate::grant::lifecycle::Grant;
+use crate::grant::types::GrantStatus;
+use crate::math::{ArithmeticPolicy, Computed};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Length of an `unlock_schedule` bucket: 30 days, regardless of the calendar
+pub const BUCKET_SECONDS: i64 = 30 * 24 * 3600;
+
+/// Payouts falling due in `[starts_at, ends_at)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MonthBucket {
+    pub starts_at: i64,
+    pub ends_at: i64,
+    /// Sum of the unpaid payout amounts
+    pub amount: u64,
+    /// Number of payouts
+    pub payouts: u64,
+    /// Number of distinct grants with a payout in the bucket
+    pub grants: u64,
+    /// `amount` was clamped at `u64::MAX`
+    pub degraded: bool,
+}
+
+/// Grants whose total amount is in `[min, max)`; `max` is `None` for the last bucket
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AmountBucket {
+    pub min: u64,
+    pub max: Option<u64>,
+    pub grants: u64,
+    /// Sum of `total_amount`
+    pub amount: u64,
+    /// `amount` was clamped at `u64::MAX`
+    pub degraded: bool,
+}
+
+/// Remaining scheduled payouts of open grants in `months` buckets from `from`
+///
+/// Bucket `i` covers `[from + i * BUCKET_SECONDS, from + (i + 1) * BUCKET_SECONDS)`.
+/// Only Pending, Approved, Active and Suspended grants are counted; their
+/// payouts come from `Grant::remaining_payouts`. Overdue payouts (due before
+/// `from`) are still owed and land in the first bucket, payouts after the
+/// last bucket are left out, and grants without a schedule contribute
+/// nothing. Buckets that would end past `i64::MAX` are not returned.
+pub fn unlock_schedule(grants: &[Grant], from: i64, months: u32) -> Vec<MonthBucket> {
+    let mut buckets: Vec<MonthBucket> = Vec::new();
+    for index in 0..i64::from(months) {
+        let Some(starts_at) = index
+            .checked_mul(BUCKET_SECONDS)
+            .and_then(|offset| from.checked_add(offset))
+        else {
+            break;
+        };
+        let Some(ends_at) = starts_at.checked_add(BUCKET_SECONDS) else {
+            break;
+        };
+        buckets.push(MonthBucket {
+            starts_at,
+            ends_at,
+            amount: 0,
+            payouts: 0,
+            grants: 0,
+            degraded: false,
+        });
+    }
+    let Some(horizon) = buckets.last().map(|bucket| bucket.ends_at) else {
+        return buckets;
+    };
+    for grant in grants
+        .iter()
+        .filter(|grant| accepts_funding_changes(grant.status))
+    {
+        let mut touched = BTreeSet::new();
+        for payout in grant.remaining_payouts() {
+            if payout.due_at >= horizon {
+                continue;
+            }
+            // Below `horizon`, so the offset from `from` fits in i64
+            let index = (payout.due_at.saturating_sub(from).max(0) / BUCKET_SECONDS) as usize;
+            let bucket = &mut buckets[index];
+            add_saturating(&mut bucket.amount, &mut bucket.degraded, payout.amount);
+            bucket.payouts = bucket.payouts.saturating_add(1);
+            touched.insert(index);
+        }
+        for index in touched {
+            buckets[index].grants = buckets[index].grants.saturating_add(1);
+        }
+    }
+    buckets
+}
+
+/// Grants counted by `total_amount` between strictly increasing `bucket_edges`
+///
+/// `n` edges give `n + 1` buckets: `[0, e0)`, `[e0, e1)`, ..., `[e(n-1), ∞)`;
+/// an amount equal to an edge falls in the bucket that starts there. Every
+/// grant passed is counted, whatever its status. `InvalidInput` if the edges
+/// are not strictly increasing.
+pub fn amount_histogram(
+    grants: &[Grant],
+    bucket_edges: &[u64],
+) -> Result<Vec<AmountBucket>, FsmError> {
+    if bucket_edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(FsmError::InvalidInput);
+    }
+    let mut buckets: Vec<AmountBucket> = std::iter::once(0)
+        .chain(bucket_edges.iter().copied())
+        .zip(bucket_edges.iter().copied().map(Some).chain([None]))
+        .map(|(min, max)| AmountBucket {
+            min,
+            max,
+            grants: 0,
+            amount: 0,
+            degraded: false,
+        })
+        .collect();
+    for grant in grants {
+        let index = bucket_edges.partition_point(|edge| *edge <= grant.total_amount);
+        let bucket = &mut buckets[index];
+        bucket.grants = bucket.grants.saturating_add(1);
+        add_saturating(&mut bucket.amount, &mut bucket.degraded, grant.total_amount);
+    }
+    Ok(buckets)
+}
+
+/// Grants with one outcome and their summed `total_amount`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutcomeTotals {
+    pub grants: u64,
+    pub amount: u64,
+    /// `amount` was clamped at `u64::MAX`
+    pub degraded: bool,
+}
+
+impl OutcomeTotals {
+    fn add(&mut self, grant: &Grant) {
+        self.grants = self.grants.saturating_add(1);
+        add_saturating(&mut self.amount, &mut self.degraded, grant.total_amount);
+    }
+}
+
+/// Outcomes of the grants approved in `[starts_at, ends_at)`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CohortReport {
+    pub starts_at: i64,
+    pub ends_at: i64,
+    /// Grants in the cohort; the outcome totals below add up to it
+    pub grants: u64,
+    /// Completed, or Archived with a `completed_at`
+    pub completed: OutcomeTotals,
+    pub cancelled: OutcomeTotals,
+    pub expired: OutcomeTotals,
+    /// Archived without a `completed_at`, so the outcome is no longer known, or Rejected
+    pub other: OutcomeTotals,
+    /// Approved, Active or Suspended (or still Pending)
+    pub in_flight: OutcomeTotals,
+    /// Completed grants with both `activated_at` and `completed_at`, the sample of the durations
+    pub timed_completions: u64,
+    /// Median seconds from `activated_at` to `completed_at`, nearest rank
+    pub median_completion_seconds: Option<i64>,
+    /// 90th percentile seconds from `activated_at` to `completed_at`, nearest rank
+    pub p90_completion_seconds: Option<i64>,
+}
+
+/// Outcomes of the grants whose `approved_at` is in `[cohort_window.0, cohort_window.1)`
+///
+/// Grants without an `approved_at` (approved without a time, or never
+/// approved) are not in any cohort. Durations are taken from the timestamps
+/// of completed grants only; in-flight grants are counted but not sampled.
+/// Percentiles use the nearest-rank method on the sorted durations: the
+/// median of an even sample is the lower middle value. An empty or inverted
+/// window gives an empty report.
+pub fn cohort_analysis(grants: &[Grant], cohort_window: (i64, i64)) -> CohortReport {
+    let (starts_at, ends_at) = cohort_window;
+    let mut report = CohortReport {
+        starts_at,
+        ends_at,
+        ..CohortReport::default()
+    };
+    let mut durations = Vec::new();
+    for grant in grants.iter().filter(|grant| {
+        grant
+            .approved_at
+            .is_some_and(|at| at >= starts_at && at < ends_at)
+    }) {
+        report.grants = report.grants.saturating_add(1);
+        let outcome = match grant.status {
+            GrantStatus::Completed => &mut report.completed,
+            GrantStatus::Archived if grant.completed_at.is_some() => &mut report.completed,
+            GrantStatus::Cancelled => &mut report.cancelled,
+            GrantStatus::Expired => &mut report.expired,
+            GrantStatus::Archived | GrantStatus::Rejected => &mut report.other,
+            GrantStatus::Pending
+            | GrantStatus::Approved
+            | GrantStatus::Active
+            | GrantStatus::Suspended => &mut report.in_flight,
+        };
+        outcome.add(grant);
+        let completed = matches!(grant.status, GrantStatus::Completed | GrantStatus::Archived);
+        if let (true, Some(activated_at), Some(completed_at)) =
+            (completed, grant.activated_at, grant.completed_at)
+        {
+            durations.push(completed_at.saturating_sub(activated_at));
+        }
+    }
+    durations.sort_unstable();
+    report.timed_completions = durations.len() as u64;
+    report.median_completion_seconds = nearest_rank(&durations, 50);
+    report.p90_completion_seconds = nearest_rank(&durations, 90);
+    report
+}
+
+/// Value at rank `ceil(percent * n / 100)` of a sorted sample
+fn nearest_rank(sorted: &[i64], percent: usize) -> Option<i64> {
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+fn add_saturating(total: &mut u64, degraded: &mut bool, amount: u64) {
+    let sum = ArithmeticPolicy::Saturating
+        .add(*total, amount)
+        .unwrap_or(Computed {
+            value: u64::MAX,
+            degraded: true,
+        });
+    *total = sum.value;
+    *degraded |= sum.degraded;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+
+    const FROM: i64 = 1_000_000;
+
+    fn create_test_grant(id: u64, amount: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Development,
+            GrantType::Core,
+            GrantDisbursementType::Standard,
+            amount,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn scheduled(id: u64, payouts: &[(i64, u64)]) -> Grant {
+        let mut grant = create_test_grant(id, payouts.iter().map(|(_, amount)| amount).sum());
+        for (due_at, amount) in payouts {
+            grant.schedule_payout(*due_at, *amount).unwrap();
+        }
+        grant
+    }
+
+    #[test]
+    fn payouts_straddling_bucket_edges() {
+        let first = scheduled(
+            1,
+            &[
+                (FROM - 10, 5),
+                (FROM + BUCKET_SECONDS - 1, 10),
+                (FROM + BUCKET_SECONDS, 20),
+            ],
+        );
+        let second = scheduled(
+            2,
+            &[
+                (FROM + BUCKET_SECONDS, 100),
+                (FROM + 2 * BUCKET_SECONDS - 1, 200),
+                (FROM + 2 * BUCKET_SECONDS, 400),
+            ],
+        );
+        let schedule = unlock_schedule(&[first.clone(), second.clone()], FROM, 2);
+        assert_eq!(
+            schedule,
+            vec![
+                MonthBucket {
+                    starts_at: FROM,
+                    ends_at: FROM + BUCKET_SECONDS,
+                    amount: 15,
+                    payouts: 2,
+                    grants: 1,
+                    degraded: false,
+                },
+                MonthBucket {
+                    starts_at: FROM + BUCKET_SECONDS,
+                    ends_at: FROM + 2 * BUCKET_SECONDS,
+                    amount: 320,
+                    payouts: 3,
+                    grants: 2,
+                    degraded: false,
+                },
+            ]
+        );
+        assert_eq!(unlock_schedule(&[second, first], FROM, 2), schedule);
+    }
+
+    #[test]
+    fn skips_paid_and_settled_grants() {
+        let mut partly_paid = scheduled(1, &[(FROM, 30), (FROM + 1, 70)]);
+        partly_paid.approve().unwrap();
+        partly_paid.activate().unwrap();
+        partly_paid.disburse(40).unwrap();
+        let mut cancelled = scheduled(2, &[(FROM, 500)]);
+        cancelled.approve().unwrap();
+        cancelled.activate().unwrap();
+        cancelled.cancel().unwrap();
+        let unscheduled = create_test_grant(3, 900);
+
+        let schedule = unlock_schedule(&[partly_paid, cancelled, unscheduled], FROM, 1);
+        assert_eq!(schedule[0].amount, 60);
+        assert_eq!(schedule[0].payouts, 1);
+        assert_eq!(schedule[0].grants, 1);
+    }
+
+    #[test]
+    fn schedule_bounds() {
+        let grant = scheduled(1, &[(FROM, 1)]);
+        assert!(unlock_schedule(std::slice::from_ref(&grant), FROM, 0).is_empty());
+        assert_eq!(unlock_schedule(&[grant], FROM, 12).len(), 12);
+        let late = i64::MAX - BUCKET_SECONDS - 1;
+        assert_eq!(unlock_schedule(&[], late, 12).len(), 1);
+    }
+
+    #[test]
+    fn saturated_bucket_is_degraded() {
+        let big = scheduled(1, &[(FROM, u64::MAX)]);
+        let small = scheduled(2, &[(FROM, 1)]);
+        let schedule = unlock_schedule(&[big, small], FROM, 1);
+        assert_eq!(schedule[0].amount, u64::MAX);
+        assert!(schedule[0].degraded);
+        assert_eq!(schedule[0].grants, 2);
+    }
+
+    #[test]
+    fn histogram_edges() {
+        let grants: Vec<Grant> = [50, 100, 999, 1_000, 5_000]
+            .into_iter()
+            .enumerate()
+            .map(|(id, amount)| create_test_grant(id as u64, amount))
+            .collect();
+        assert_eq!(
+            amount_histogram(&grants, &[100, 1_000]),
+            Ok(vec![
+                AmountBucket {
+                    min: 0,
+                    max: Some(100),
+                    grants: 1,
+                    amount: 50,
+                    degraded: false,
+                },
+                AmountBucket {
+                    min: 100,
+                    max: Some(1_000),
+                    grants: 2,
+                    amount: 1_099,
+                    degraded: false,
+                },
+                AmountBucket {
+                    min: 1_000,
+                    max: None,
+                    grants: 2,
+                    amount: 6_000,
+                    degraded: false,
+                },
+            ])
+        );
+        assert_eq!(amount_histogram(&grants, &[]).unwrap()[0].grants, 5);
+        assert_eq!(
+            amount_histogram(&grants, &[1_000, 100]),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            amount_histogram(&grants, &[100, 100]),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn histogram_saturates() {
+        let grants = [
+            create_test_grant(1, u64::MAX),
+            create_test_grant(2, u64::MAX),
+        ];
+        let histogram = amount_histogram(&grants, &[]).unwrap();
+        assert_eq!(histogram[0].amount, u64::MAX);
+        assert!(histogram[0].degraded);
+    }
+
+    fn cohort_grant(id: u64, amount: u64, approved_at: i64) -> Grant {
+        let mut grant = create_test_grant(id, amount);
+        grant.approve_with_time(approved_at).unwrap();
+        grant
+    }
+
+    fn completed(id: u64, amount: u64, approved_at: i64, active: (i64, i64)) -> Grant {
+        let mut grant = cohort_grant(id, amount, approved_at);
+        grant.activate_with_time(active.0).unwrap();
+        grant.disburse_with_time(amount, active.1).unwrap();
+        grant
+    }
+
+    #[test]
+    fn cohort_of_seven_grants() {
+        let mut archived = completed(3, 300, 300, (400, 2_400));
+        archived.archive_with_time(2_500).unwrap();
+        let mut cancelled = cohort_grant(4, 400, 400);
+        cancelled.activate_with_time(500).unwrap();
+        cancelled.cancel().unwrap();
+        let mut active = cohort_grant(5, 500, 500);
+        active.activate_with_time(600).unwrap();
+        let grants = vec![
+            completed(2, 200, 150, (200, 3_200)),
+            cancelled,
+            // Approved at the window end: next cohort
+            cohort_grant(7, 700, 1_000),
+            archived,
+            active,
+            cohort_grant(6, 600, 999),
+            completed(1, 100, 100, (200, 1_200)),
+        ];
+        let report = cohort_analysis(&grants, (100, 1_000));
+        assert_eq!(
+            report,
+            CohortReport {
+                starts_at: 100,
+                ends_at: 1_000,
+                grants: 6,
+                completed: OutcomeTotals {
+                    grants: 3,
+                    amount: 600,
+                    degraded: false,
+                },
+                cancelled: OutcomeTotals {
+                    grants: 1,
+                    amount: 400,
+                    degraded: false,
+                },
+                expired: OutcomeTotals::default(),
+                other: OutcomeTotals::default(),
+                in_flight: OutcomeTotals {
+                    grants: 2,
+                    amount: 1_100,
+                    degraded: false,
+                },
+                timed_completions: 3,
+                median_completion_seconds: Some(2_000),
+                p90_completion_seconds: Some(3_000),
+            }
+        );
+        let mut reversed = grants.clone();
+        reversed.reverse();
+        assert_eq!(cohort_analysis(&reversed, (100, 1_000)), report);
+        assert_eq!(cohort_analysis(&grants, (1_000, 100)).grants, 0);
+    }
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        assert_eq!(nearest_rank(&[], 50), None);
+        assert_eq!(nearest_rank(&[7], 90), Some(7));
+        assert_eq!(nearest_rank(&[1, 2, 3, 4], 50), Some(2));
+        assert_eq!(nearest_rank(&[1, 2, 3, 4], 90), Some(4));
+    }
+}
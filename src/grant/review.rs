@@ -0,0 +1,380 @@
+//! Grant review assignments.
+//!
+//! Grants are reviewed by committee members. A member who authored a proposal
+//! for the grant's idea has a conflict of interest and cannot be assigned.
+//! Each member takes at most `max_assignments_per_epoch` reviews per epoch, and
+//! grant voting cannot be finalized until enough assigned reviewers have voted.
+
+use crate::error::FsmError;
+use crate::governance::security_committees::CommitteeMemberRole;
+use crate::grant::lifecycle::{EntityId, Grant};
+use crate::grant::types::GrantStatus;
+use crate::grant::vote::{GrantTally, GrantVote, GrantVotingOutcome};
+use crate::proposal::Proposal;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A committee member assigned to review a grant
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReviewAssignment {
+    pub grant_id: u64,
+    pub member_id: EntityId,
+    pub role: CommitteeMemberRole,
+    pub assigned_at: i64,
+}
+
+/// Members eligible to review and their assignment limits
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReviewCommittee {
+    pub members: BTreeMap<EntityId, CommitteeMemberRole>,
+    pub max_assignments_per_epoch: u32,
+    /// Epoch length; epochs start at multiples of this from time zero
+    pub epoch_seconds: i64,
+}
+
+impl ReviewCommittee {
+    pub fn new(max_assignments_per_epoch: u32, epoch_seconds: i64) -> Result<Self, FsmError> {
+        if max_assignments_per_epoch == 0 || epoch_seconds <= 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            members: BTreeMap::new(),
+            max_assignments_per_epoch,
+            epoch_seconds,
+        })
+    }
+
+    pub fn add_member(&mut self, member_id: EntityId, role: CommitteeMemberRole) {
+        self.members.insert(member_id, role);
+    }
+
+    fn epoch(&self, time: i64) -> i64 {
+        time.div_euclid(self.epoch_seconds)
+    }
+}
+
+/// Authors of proposals per idea
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AuthorshipIndex {
+    authors: BTreeMap<u64, BTreeSet<EntityId>>,
+}
+
+impl AuthorshipIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index the authors of proposals linked to an idea
+    pub fn from_proposals<'a>(proposals: impl IntoIterator<Item = &'a Proposal<EntityId>>) -> Self {
+        let mut index = Self::new();
+        for proposal in proposals {
+            if let Some(idea_id) = proposal.idea_id {
+                index.record(idea_id, proposal.author);
+            }
+        }
+        index
+    }
+
+    pub fn record(&mut self, idea_id: u64, author: EntityId) {
+        self.authors.entry(idea_id).or_default().insert(author);
+    }
+
+    /// Whether `member` authored a proposal for `idea_id`
+    pub fn is_author(&self, idea_id: u64, member: &EntityId) -> bool {
+        self.authors
+            .get(&idea_id)
+            .is_some_and(|authors| authors.contains(member))
+    }
+}
+
+/// Review assignments across grants
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReviewAssignments {
+    assignments: Vec<ReviewAssignment>,
+}
+
+impl ReviewAssignments {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign `member` to review `grant`
+    ///
+    /// Errors:
+    /// - `InvalidInput` if `member` is not on the committee
+    /// - `ConflictOfInterest` if `member` authored a proposal for the grant's idea
+    /// - `InvalidState` if `member` is already assigned to the grant
+    /// - `InsufficientMembers` if `member` reached the per-epoch cap
+    pub fn assign_reviewer(
+        &mut self,
+        grant: &Grant,
+        member: EntityId,
+        committee: &ReviewCommittee,
+        authorship_index: &AuthorshipIndex,
+        current_time: i64,
+    ) -> Result<&ReviewAssignment, FsmError> {
+        let role = *committee
+            .members
+            .get(&member)
+            .ok_or(FsmError::InvalidInput)?;
+        if authorship_index.is_author(grant.idea_id, &member) {
+            return Err(FsmError::ConflictOfInterest);
+        }
+        if self
+            .assignments
+            .iter()
+            .any(|a| a.grant_id == grant.id && a.member_id == member)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        let epoch = committee.epoch(current_time);
+        let in_epoch = self
+            .assignments
+            .iter()
+            .filter(|a| a.member_id == member && committee.epoch(a.assigned_at) == epoch)
+            .count();
+        if in_epoch >= committee.max_assignments_per_epoch as usize {
+            return Err(FsmError::InsufficientMembers);
+        }
+        self.assignments.push(ReviewAssignment {
+            grant_id: grant.id,
+            member_id: member,
+            role,
+            assigned_at: current_time,
+        });
+        Ok(&self.assignments[self.assignments.len() - 1])
+    }
+
+    /// Assignments for `grant_id`, in assignment order
+    pub fn reviewers_for(&self, grant_id: u64) -> Vec<&ReviewAssignment> {
+        self.assignments
+            .iter()
+            .filter(|a| a.grant_id == grant_id)
+            .collect()
+    }
+
+    /// Number of reviewers assigned to `grant_id` who cast a vote on it
+    pub fn reviewers_voted(&self, grant_id: u64, votes: &[GrantVote]) -> usize {
+        self.reviewers_for(grant_id)
+            .iter()
+            .filter(|a| {
+                votes
+                    .iter()
+                    .any(|v| v.grant_id == grant_id && v.voter_id == a.member_id)
+            })
+            .count()
+    }
+}
+
+impl Grant {
+    /// Decide a Pending grant by approve vs reject weight
+    ///
+    /// At least `min_reviewers` assigned reviewers must have voted
+    /// (`InsufficientMembers` otherwise). The grant is Approved when approve
+    /// weight exceeds reject weight and Rejected otherwise; abstentions and
+    /// votes for other grants are ignored. Quorum is not checked; see
+    /// `finalize_voting_with_quorum` for votes recorded on the grant.
+    pub fn finalize_voting(
+        &mut self,
+        votes: &[GrantVote],
+        reviews: &ReviewAssignments,
+        min_reviewers: usize,
+    ) -> Result<GrantStatus, FsmError> {
+        self.observe_transition("finalize_voting", |grant| {
+            if grant.status != GrantStatus::Pending {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            if reviews.reviewers_voted(grant.id, votes) < min_reviewers {
+                return Err(FsmError::InsufficientMembers);
+            }
+            let tally = GrantTally::from_votes(grant.id, votes)?;
+            let (status, outcome) = if tally.approve > tally.reject {
+                (GrantStatus::Approved, GrantVotingOutcome::Approved)
+            } else {
+                (GrantStatus::Rejected, GrantVotingOutcome::Rejected)
+            };
+            grant.status = status;
+            grant.voting_outcome = Some(outcome);
+            Ok(())
+        })?;
+        Ok(self.status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+    use crate::grant::vote::VoterType;
+    use crate::grant::voting_types::VoteType;
+
+    const DAY: i64 = 24 * 3600;
+
+    fn member(n: u8) -> EntityId {
+        [n; 32]
+    }
+
+    fn sample_grant(id: u64, idea_id: u64) -> Grant {
+        Grant::new(
+            id,
+            idea_id,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1000,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn committee(cap: u32) -> ReviewCommittee {
+        let mut committee = ReviewCommittee::new(cap, DAY).unwrap();
+        committee.add_member(member(1), CommitteeMemberRole::Chairperson);
+        committee.add_member(member(2), CommitteeMemberRole::Member);
+        committee.add_member(member(3), CommitteeMemberRole::Member);
+        committee
+    }
+
+    fn vote(grant_id: u64, voter: EntityId, vote_type: VoteType) -> GrantVote {
+        GrantVote {
+            grant_id,
+            voter_id: voter,
+            vote_type,
+            weight: 1,
+            voter_type: VoterType::Expert,
+            cast_at: 0,
+        }
+    }
+
+    #[test]
+    fn assigns_and_lists_reviewers() {
+        let committee = committee(5);
+        let mut reviews = ReviewAssignments::new();
+        let grant = sample_grant(1, 10);
+        let assignment = reviews
+            .assign_reviewer(&grant, member(1), &committee, &AuthorshipIndex::new(), 100)
+            .unwrap();
+        assert_eq!(assignment.role, CommitteeMemberRole::Chairperson);
+        reviews
+            .assign_reviewer(&grant, member(2), &committee, &AuthorshipIndex::new(), 100)
+            .unwrap();
+        let reviewers: Vec<EntityId> = reviews
+            .reviewers_for(1)
+            .iter()
+            .map(|a| a.member_id)
+            .collect();
+        assert_eq!(reviewers, vec![member(1), member(2)]);
+        assert!(reviews.reviewers_for(2).is_empty());
+        assert_eq!(
+            reviews.assign_reviewer(&grant, member(2), &committee, &AuthorshipIndex::new(), 100),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            reviews.assign_reviewer(&grant, member(9), &committee, &AuthorshipIndex::new(), 100),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn rejects_proposal_author() {
+        let author = member(2);
+        let proposal = Proposal::builder(
+            7,
+            "Fund idea".to_string(),
+            "Description".to_string(),
+            "grant".to_string(),
+            author,
+        )
+        .idea_id(10)
+        .build(0)
+        .unwrap();
+        let index = AuthorshipIndex::from_proposals([&proposal]);
+        let mut reviews = ReviewAssignments::new();
+
+        assert_eq!(
+            reviews.assign_reviewer(&sample_grant(1, 10), author, &committee(5), &index, 0),
+            Err(FsmError::ConflictOfInterest)
+        );
+        // No conflict on a grant for another idea
+        assert!(
+            reviews
+                .assign_reviewer(&sample_grant(2, 11), author, &committee(5), &index, 0)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn caps_assignments_per_epoch() {
+        let committee = committee(2);
+        let index = AuthorshipIndex::new();
+        let mut reviews = ReviewAssignments::new();
+        for id in 1..=2 {
+            reviews
+                .assign_reviewer(&sample_grant(id, id), member(1), &committee, &index, 10)
+                .unwrap();
+        }
+        assert_eq!(
+            reviews.assign_reviewer(&sample_grant(3, 3), member(1), &committee, &index, DAY - 1),
+            Err(FsmError::InsufficientMembers)
+        );
+        // Next epoch
+        assert!(
+            reviews
+                .assign_reviewer(&sample_grant(3, 3), member(1), &committee, &index, DAY)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn finalize_requires_reviewer_votes() {
+        let committee = committee(5);
+        let index = AuthorshipIndex::new();
+        let mut reviews = ReviewAssignments::new();
+        let mut grant = sample_grant(1, 10);
+        for n in 1..=3 {
+            reviews
+                .assign_reviewer(&grant, member(n), &committee, &index, 0)
+                .unwrap();
+        }
+        // Non-reviewer and other-grant votes do not count toward the minimum
+        let mut votes = vec![
+            vote(1, member(1), VoteType::Approve),
+            vote(1, member(8), VoteType::Approve),
+            vote(2, member(2), VoteType::Approve),
+        ];
+        assert_eq!(
+            grant.finalize_voting(&votes, &reviews, 2),
+            Err(FsmError::InsufficientMembers)
+        );
+        assert_eq!(grant.status, GrantStatus::Pending);
+
+        votes.push(vote(1, member(2), VoteType::Abstain));
+        assert_eq!(
+            grant.finalize_voting(&votes, &reviews, 2),
+            Ok(GrantStatus::Approved)
+        );
+        assert_eq!(
+            grant.finalize_voting(&votes, &reviews, 2),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn finalize_rejects_on_tie() {
+        let committee = committee(5);
+        let mut reviews = ReviewAssignments::new();
+        let mut grant = sample_grant(1, 10);
+        reviews
+            .assign_reviewer(&grant, member(1), &committee, &AuthorshipIndex::new(), 0)
+            .unwrap();
+        let votes = vec![
+            vote(1, member(1), VoteType::Approve),
+            vote(1, member(2), VoteType::Reject),
+        ];
+        assert_eq!(
+            grant.finalize_voting(&votes, &reviews, 1),
+            Ok(GrantStatus::Rejected)
+        );
+    }
+}
@@ -1,21 +1,15 @@
 //! Grant-specific enums for the FSM governance engine.
 
+#[cfg(feature = "borsh")]
 use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 macro_rules! grant_enum {
     ($name:ident { $($variant:ident),* $(,)? }) => {
-        #[derive(
-            BorshSerialize,
-            BorshDeserialize,
-            Serialize,
-            Deserialize,
-            Clone,
-            Copy,
-            PartialEq,
-            Eq,
-            Debug,
-        )]
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        #[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u8)]
         pub enum $name {
             $($variant,)*
@@ -1,12 +1,20 @@
 //! Simple grant vote types for FSM governance.
 
+use crate::error::FsmError;
+use crate::grant::lifecycle::Grant;
+use crate::grant::types::GrantStatus;
 use crate::grant::voting_types::VoteType;
+use crate::math::ArithmeticPolicy;
+use crate::metrics;
+use crate::proposal::TallyOverflow;
+#[cfg(feature = "borsh")]
 use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-#[derive(
-    BorshSerialize, BorshDeserialize, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, Debug,
-)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum VoterType {
     MeshGroupMember,
     DaoMember,
@@ -14,7 +22,9 @@ pub enum VoterType {
     Expert,
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GrantVote {
     pub grant_id: u64,
     pub voter_id: [u8; 32],
@@ -37,6 +47,295 @@ impl GrantVote {
     }
 }
 
+/// Blinded voter token of a private vote
+///
+/// Issued outside this crate, one per voter and grant; this crate only checks
+/// that each token votes once.
+pub type VoterToken = [u8; 32];
+
+/// Whether private vote tokens may later be linked to voters
+///
+/// Linking is done by the token issuer, never by this crate; the policy is
+/// stored so it travels with the grant.
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RevealPolicy {
+    /// Tokens must never be linked to voters
+    #[default]
+    Never,
+    /// The issuer may link tokens to voters, e.g. for an audit
+    ByIssuer,
+}
+
+/// How a grant records votes
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VotePrivacy {
+    /// `GrantVote`s with voter identities, cast with `cast_vote`
+    #[default]
+    Public,
+    /// Voter tokens and tallies only, cast with `cast_private_vote`
+    Private { reveal_policy: RevealPolicy },
+}
+
+/// Vote weight per vote type
+#[derive(Copy, Clone, Default, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GrantTally {
+    pub approve: u64,
+    pub reject: u64,
+    pub abstain: u64,
+    /// A weight was clamped under `TallyOverflow::Clamp`; the tally understates the votes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub degraded: bool,
+}
+
+impl GrantTally {
+    /// Add `vote`'s weight to its vote type; `Overflow` past `u64::MAX`
+    pub fn add(&mut self, vote: &GrantVote) -> Result<(), FsmError> {
+        self.add_with(vote, TallyOverflow::Reject)
+    }
+
+    /// Add `vote`'s weight to its vote type under `overflow`
+    pub fn add_with(&mut self, vote: &GrantVote, overflow: TallyOverflow) -> Result<(), FsmError> {
+        self.add_weight(&vote.vote_type, vote.weight, overflow)
+    }
+
+    fn add_weight(
+        &mut self,
+        vote_type: &VoteType,
+        weight: u64,
+        overflow: TallyOverflow,
+    ) -> Result<(), FsmError> {
+        let tally = match vote_type {
+            VoteType::Approve => &mut self.approve,
+            VoteType::Reject => &mut self.reject,
+            VoteType::Abstain => &mut self.abstain,
+        };
+        let sum = ArithmeticPolicy::from(overflow).add(*tally, weight)?;
+        *tally = sum.value;
+        self.degraded |= sum.degraded;
+        Ok(())
+    }
+
+    /// Tally of the votes for `grant_id`, ignoring the rest
+    pub fn from_votes(grant_id: u64, votes: &[GrantVote]) -> Result<Self, FsmError> {
+        let mut tally = Self::default();
+        for vote in votes.iter().filter(|v| v.grant_id == grant_id) {
+            tally.add(vote)?;
+        }
+        Ok(tally)
+    }
+
+    /// Approve, reject and abstain weight together; counts toward quorum
+    pub fn participating(&self) -> u128 {
+        u128::from(self.approve) + u128::from(self.reject) + u128::from(self.abstain)
+    }
+}
+
+/// How grant voting ended
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GrantVotingOutcome {
+    Approved,
+    Rejected,
+    /// Participation fell short of quorum; the grant is Rejected
+    QuorumNotMet,
+}
+
+/// Threshold and quorum for `Grant::finalize_voting_under`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GrantVotingPolicy {
+    /// Approve weight needed, as a percentage of approve + reject weight (exceeded, not met)
+    pub threshold_percent: u8,
+    /// Participation needed, as a percentage of the eligible weight
+    pub quorum_percent: u8,
+}
+
+impl Default for GrantVotingPolicy {
+    /// Simple majority with no quorum
+    fn default() -> Self {
+        Self {
+            threshold_percent: 50,
+            quorum_percent: 0,
+        }
+    }
+}
+
+impl GrantVotingPolicy {
+    /// `InvalidInput` if a percentage exceeds 100
+    pub fn validate(&self) -> Result<(), FsmError> {
+        if self.threshold_percent > 100 || self.quorum_percent > 100 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+}
+
+impl Grant {
+    /// Record a vote on a Pending grant at `vote.cast_at`
+    ///
+    /// Errors: `InvalidState` if not Pending, voting is private or the voter
+    /// already voted, `InvalidInput` if the vote is for another grant or has
+    /// zero weight, `TimeRegression` if `cast_at` breaks `time_ordering`,
+    /// `Overflow` if the tally would pass `u64::MAX` under `TallyOverflow::Reject`.
+    pub fn cast_vote(&mut self, vote: GrantVote) -> Result<(), FsmError> {
+        let result = self.try_cast_vote(vote);
+        metrics::record_vote("grant", "cast", &result);
+        result
+    }
+
+    fn try_cast_vote(&mut self, vote: GrantVote) -> Result<(), FsmError> {
+        if self.status != GrantStatus::Pending || self.vote_privacy != VotePrivacy::Public {
+            return Err(FsmError::InvalidState);
+        }
+        if vote.grant_id != self.id || vote.weight == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.time_ordering.check(self.last_event_at, vote.cast_at)?;
+        if self.votes.iter().any(|v| v.voter_id == vote.voter_id) {
+            return Err(FsmError::InvalidState);
+        }
+        let mut tally = self.tally;
+        tally.add_with(&vote, self.tally_overflow)?;
+        self.tally = tally;
+        self.last_event_at = self.last_event_at.max(vote.cast_at);
+        self.votes.push(vote);
+        Ok(())
+    }
+
+    /// Choose how votes are recorded
+    ///
+    /// The mode is fixed by the first vote: changing it afterwards fails with
+    /// `InvalidState`.
+    pub fn set_vote_privacy(&mut self, privacy: VotePrivacy) -> Result<(), FsmError> {
+        if privacy != self.vote_privacy && (!self.votes.is_empty() || !self.vote_tokens.is_empty())
+        {
+            return Err(FsmError::InvalidState);
+        }
+        self.vote_privacy = privacy;
+        Ok(())
+    }
+
+    /// Record a private vote on a Pending grant under a blinded voter `token`
+    ///
+    /// Only the token and the tally change; neither the voter nor `cast_at` is
+    /// kept per vote. Errors as `cast_vote`, with `InvalidState` if voting is
+    /// public or the token already voted.
+    pub fn cast_private_vote(
+        &mut self,
+        token: VoterToken,
+        vote_type: VoteType,
+        weight: u64,
+        cast_at: i64,
+    ) -> Result<(), FsmError> {
+        let result = self.try_cast_private_vote(token, vote_type, weight, cast_at);
+        metrics::record_vote("grant", "cast_private", &result);
+        result
+    }
+
+    fn try_cast_private_vote(
+        &mut self,
+        token: VoterToken,
+        vote_type: VoteType,
+        weight: u64,
+        cast_at: i64,
+    ) -> Result<(), FsmError> {
+        if self.status != GrantStatus::Pending
+            || !matches!(self.vote_privacy, VotePrivacy::Private { .. })
+        {
+            return Err(FsmError::InvalidState);
+        }
+        if weight == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.time_ordering.check(self.last_event_at, cast_at)?;
+        let Err(slot) = self.vote_tokens.binary_search(&token) else {
+            return Err(FsmError::InvalidState);
+        };
+        let mut tally = self.tally;
+        tally.add_weight(&vote_type, weight, self.tally_overflow)?;
+        self.tally = tally;
+        self.last_event_at = self.last_event_at.max(cast_at);
+        self.vote_tokens.insert(slot, token);
+        Ok(())
+    }
+
+    /// Finalize recorded voting against a quorum and an approval threshold
+    ///
+    /// `total_eligible` is the eligible voting weight. If approve, reject and
+    /// abstain weight together are below `quorum_percent` of it, the grant is
+    /// Rejected with `QuorumNotMet`. Otherwise abstentions are ignored and the
+    /// grant is Approved when approve weight is more than `threshold_percent`
+    /// of approve + reject weight, else Rejected.
+    ///
+    /// Errors: `InvalidInput` if a percentage exceeds 100 or `total_eligible`
+    /// is zero, `InvalidStateTransition` if not Pending, `TimeRegression` if
+    /// `current_time` breaks `time_ordering`.
+    pub fn finalize_voting_with_quorum(
+        &mut self,
+        threshold_percent: u8,
+        quorum_percent: u8,
+        total_eligible: u64,
+        current_time: i64,
+    ) -> Result<GrantVotingOutcome, FsmError> {
+        if threshold_percent > 100 || quorum_percent > 100 || total_eligible == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.observe_transition_at("finalize_voting", current_time, |grant, current_time| {
+            if grant.status != GrantStatus::Pending {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            let tally = grant.tally;
+            let outcome = if tally.participating() * 100
+                < u128::from(quorum_percent) * u128::from(total_eligible)
+            {
+                GrantVotingOutcome::QuorumNotMet
+            } else {
+                let decided = u128::from(tally.approve) + u128::from(tally.reject);
+                if decided > 0
+                    && u128::from(tally.approve) * 100 > u128::from(threshold_percent) * decided
+                {
+                    GrantVotingOutcome::Approved
+                } else {
+                    GrantVotingOutcome::Rejected
+                }
+            };
+            grant.status = match outcome {
+                GrantVotingOutcome::Approved => GrantStatus::Approved,
+                _ => GrantStatus::Rejected,
+            };
+            if outcome == GrantVotingOutcome::Approved {
+                grant.approved_at = Some(current_time);
+            }
+            grant.voting_outcome = Some(outcome);
+            Ok(())
+        })?;
+        Ok(self.voting_outcome.unwrap_or(GrantVotingOutcome::Rejected))
+    }
+
+    /// `finalize_voting_with_quorum` with the percentages of `policy`
+    pub fn finalize_voting_under(
+        &mut self,
+        policy: &GrantVotingPolicy,
+        total_eligible: u64,
+        current_time: i64,
+    ) -> Result<GrantVotingOutcome, FsmError> {
+        self.finalize_voting_with_quorum(
+            policy.threshold_percent,
+            policy.quorum_percent,
+            total_eligible,
+            current_time,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +352,325 @@ mod tests {
         assert_eq!(GrantVote::calculate_final_weight(1, 5), 5);
     }
 
+    fn vote(voter: u8, vote_type: VoteType, weight: u64, cast_at: i64) -> GrantVote {
+        GrantVote {
+            grant_id: 1,
+            voter_id: [voter; 32],
+            vote_type,
+            weight,
+            voter_type: VoterType::DaoMember,
+            cast_at,
+        }
+    }
+
+    fn pending_grant() -> Grant {
+        Grant {
+            id: 1,
+            ..Grant::default()
+        }
+    }
+
+    #[test]
+    fn cast_vote_overflow_modes() {
+        let mut grant = pending_grant();
+        grant.tally.approve = u64::MAX - 1;
+        assert_eq!(
+            grant.cast_vote(vote(1, VoteType::Approve, 2, 10)),
+            Err(FsmError::Overflow)
+        );
+        assert_eq!(grant.tally.approve, u64::MAX - 1);
+        assert!(!grant.tally.degraded);
+        assert!(grant.votes.is_empty());
+
+        let mut grant = pending_grant().with_tally_overflow(TallyOverflow::Clamp);
+        grant.tally.approve = u64::MAX - 1;
+        grant.cast_vote(vote(1, VoteType::Approve, 1, 10)).unwrap();
+        assert!(!grant.tally.degraded);
+        grant.cast_vote(vote(2, VoteType::Approve, 2, 10)).unwrap();
+        assert_eq!(grant.tally.approve, u64::MAX);
+        assert!(grant.tally.degraded);
+        assert_eq!(grant.votes.len(), 2);
+    }
+
+    #[test]
+    fn cast_vote_tallies_abstentions_separately() {
+        let mut grant = pending_grant();
+        grant.cast_vote(vote(1, VoteType::Approve, 2, 10)).unwrap();
+        grant.cast_vote(vote(2, VoteType::Abstain, 3, 20)).unwrap();
+        grant.cast_vote(vote(3, VoteType::Reject, 1, 20)).unwrap();
+        assert_eq!(
+            grant.tally,
+            GrantTally {
+                approve: 2,
+                reject: 1,
+                abstain: 3,
+                degraded: false,
+            }
+        );
+        assert_eq!(
+            grant.tally,
+            GrantTally::from_votes(1, &grant.votes).unwrap()
+        );
+        assert_eq!(
+            grant.cast_vote(vote(1, VoteType::Reject, 1, 30)),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            grant.cast_vote(vote(4, VoteType::Reject, 0, 30)),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.cast_vote(GrantVote {
+                grant_id: 2,
+                ..vote(4, VoteType::Reject, 1, 30)
+            }),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.cast_vote(vote(4, VoteType::Reject, 1, 15)),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(grant.votes.len(), 3);
+    }
+
+    #[test]
+    fn cast_vote_overflow_leaves_tally_unchanged() {
+        let mut grant = pending_grant();
+        grant
+            .cast_vote(vote(1, VoteType::Approve, u64::MAX, 0))
+            .unwrap();
+        assert_eq!(
+            grant.cast_vote(vote(2, VoteType::Approve, 1, 0)),
+            Err(FsmError::Overflow)
+        );
+        assert_eq!(grant.tally.approve, u64::MAX);
+        assert_eq!(grant.votes.len(), 1);
+    }
+
+    #[test]
+    fn quorum_not_met_rejects() {
+        let mut grant = pending_grant();
+        grant.cast_vote(vote(1, VoteType::Approve, 3, 10)).unwrap();
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 40, 10, 20),
+            Ok(GrantVotingOutcome::QuorumNotMet)
+        );
+        assert_eq!(grant.status, GrantStatus::Rejected);
+        assert_eq!(grant.voting_outcome, Some(GrantVotingOutcome::QuorumNotMet));
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 40, 10, 30),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn abstentions_reach_quorum_ratio_decides() {
+        // 3 of 10 without abstentions misses 40% quorum; 2 abstentions reach it
+        let mut approved = pending_grant();
+        approved
+            .cast_vote(vote(1, VoteType::Approve, 2, 10))
+            .unwrap();
+        approved
+            .cast_vote(vote(2, VoteType::Reject, 1, 10))
+            .unwrap();
+        approved
+            .cast_vote(vote(3, VoteType::Abstain, 2, 10))
+            .unwrap();
+        assert_eq!(
+            approved.finalize_voting_with_quorum(50, 40, 10, 20),
+            Ok(GrantVotingOutcome::Approved)
+        );
+        assert_eq!(approved.status, GrantStatus::Approved);
+        assert_eq!(approved.approved_at, Some(20));
+
+        let mut rejected = pending_grant();
+        rejected
+            .cast_vote(vote(1, VoteType::Approve, 1, 10))
+            .unwrap();
+        rejected
+            .cast_vote(vote(2, VoteType::Reject, 2, 10))
+            .unwrap();
+        rejected
+            .cast_vote(vote(3, VoteType::Abstain, 2, 10))
+            .unwrap();
+        assert_eq!(
+            rejected.finalize_voting_with_quorum(50, 40, 10, 20),
+            Ok(GrantVotingOutcome::Rejected)
+        );
+        assert_eq!(rejected.status, GrantStatus::Rejected);
+        assert_eq!(rejected.voting_outcome, Some(GrantVotingOutcome::Rejected));
+    }
+
+    #[test]
+    fn only_abstentions_reject() {
+        let mut grant = pending_grant();
+        grant.cast_vote(vote(1, VoteType::Abstain, 10, 10)).unwrap();
+        assert_eq!(
+            grant.finalize_voting_with_quorum(0, 100, 10, 20),
+            Ok(GrantVotingOutcome::Rejected)
+        );
+    }
+
+    #[test]
+    fn finalize_with_quorum_validation() {
+        let mut grant = pending_grant();
+        assert_eq!(
+            grant.finalize_voting_with_quorum(101, 40, 10, 0),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 101, 10, 0),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 40, 0, 0),
+            Err(FsmError::InvalidInput)
+        );
+        grant.last_event_at = 100;
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 40, 10, 99),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(grant.status, GrantStatus::Pending);
+    }
+
+    #[test]
+    fn finalize_under_policy() {
+        let policy = GrantVotingPolicy {
+            threshold_percent: 50,
+            quorum_percent: 40,
+        };
+        assert_eq!(policy.validate(), Ok(()));
+        let mut grant = pending_grant();
+        grant.cast_vote(vote(1, VoteType::Approve, 3, 10)).unwrap();
+        assert_eq!(
+            grant.finalize_voting_under(&policy, 10, 20),
+            Ok(GrantVotingOutcome::QuorumNotMet)
+        );
+        let invalid = GrantVotingPolicy {
+            quorum_percent: 101,
+            ..policy
+        };
+        assert_eq!(invalid.validate(), Err(FsmError::InvalidInput));
+        assert_eq!(GrantVotingPolicy::default().validate(), Ok(()));
+    }
+
+    fn private_grant() -> Grant {
+        let mut grant = pending_grant();
+        grant
+            .set_vote_privacy(VotePrivacy::Private {
+                reveal_policy: RevealPolicy::Never,
+            })
+            .unwrap();
+        grant
+    }
+
+    #[test]
+    fn private_votes_keep_tokens_and_tallies_only() {
+        let mut grant = private_grant();
+        grant
+            .cast_private_vote([9; 32], VoteType::Approve, 2, 10)
+            .unwrap();
+        grant
+            .cast_private_vote([3; 32], VoteType::Reject, 1, 20)
+            .unwrap();
+        assert_eq!(
+            grant.cast_private_vote([9; 32], VoteType::Reject, 1, 30),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            grant.cast_private_vote([4; 32], VoteType::Reject, 0, 30),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            grant.cast_vote(vote(4, VoteType::Approve, 1, 30)),
+            Err(FsmError::InvalidState)
+        );
+        assert!(grant.votes.is_empty());
+        // Sorted, so the order of casting is not kept either
+        assert_eq!(grant.vote_tokens, vec![[3; 32], [9; 32]]);
+        assert_eq!(
+            grant.tally,
+            GrantTally {
+                approve: 2,
+                reject: 1,
+                abstain: 0,
+                degraded: false,
+            }
+        );
+        assert_eq!(
+            grant.finalize_voting_with_quorum(50, 20, 10, 40),
+            Ok(GrantVotingOutcome::Approved)
+        );
+        assert_eq!(
+            grant.cast_private_vote([5; 32], VoteType::Approve, 1, 50),
+            Err(FsmError::InvalidState)
+        );
+    }
+
+    #[test]
+    fn privacy_mode_fixed_by_first_vote() {
+        let mut public = pending_grant();
+        assert_eq!(
+            public.cast_private_vote([1; 32], VoteType::Approve, 1, 10),
+            Err(FsmError::InvalidState)
+        );
+        public.cast_vote(vote(1, VoteType::Approve, 1, 10)).unwrap();
+        assert_eq!(
+            public.set_vote_privacy(VotePrivacy::Private {
+                reveal_policy: RevealPolicy::ByIssuer,
+            }),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(public.set_vote_privacy(VotePrivacy::Public), Ok(()));
+
+        let mut private = private_grant();
+        // Still free to change before the first vote
+        private.set_vote_privacy(VotePrivacy::Public).unwrap();
+        let mode = VotePrivacy::Private {
+            reveal_policy: RevealPolicy::ByIssuer,
+        };
+        private.set_vote_privacy(mode).unwrap();
+        private
+            .cast_private_vote([1; 32], VoteType::Abstain, 1, 10)
+            .unwrap();
+        assert_eq!(
+            private.set_vote_privacy(VotePrivacy::Public),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(private.vote_privacy, mode);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn private_serde_output_has_no_voter_fields() {
+        let mut grant = private_grant();
+        grant
+            .cast_private_vote([7; 32], VoteType::Approve, 3, 10)
+            .unwrap();
+        let json = serde_json::to_string(&grant).unwrap();
+        for field in ["voter_id", "voter_type", "cast_at"] {
+            assert!(!json.contains(field), "{field} in {json}");
+        }
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["votes"], serde_json::json!([]));
+        assert_eq!(
+            value["vote_privacy"],
+            serde_json::json!({"Private": {"reveal_policy": "Never"}})
+        );
+        assert_eq!(serde_json::from_str::<Grant>(&json).unwrap(), grant);
+
+        // Grants stored before privacy modes existed stay public
+        let mut legacy = value;
+        let object = legacy.as_object_mut().unwrap();
+        object.remove("vote_privacy");
+        object.remove("vote_tokens");
+        let restored: Grant = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.vote_privacy, VotePrivacy::Public);
+        assert!(restored.vote_tokens.is_empty());
+    }
+
+    #[cfg(feature = "borsh")]
     #[test]
     fn serialization() {
         let vote = GrantVote {
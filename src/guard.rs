@@ -0,0 +1,383 @@
+//! Guard expressions of FSM definitions.
+//!
+//! A guard is a boolean expression over named variables:
+//!
+//! - integer literals (`-3`, `100`), quoted strings (`'Active'` or `"Active"`),
+//!   `true`, `false` and `null`
+//! - variables: identifiers, optionally dotted (`votes.yes`)
+//! - comparisons `==`, `!=`, `<`, `<=`, `>`, `>=`; ordering applies to integers
+//!   only, equality to two values of the same type (or `null`)
+//! - `not`, `and`, `or` (in decreasing precedence) and parentheses
+//!
+//! Evaluation fails with `InvalidInput` on a syntax error, an unknown
+//! variable, a type mismatch or a result that is not a boolean, so a typo in a
+//! definition is never read as "guard closed".
+
+use crate::error::FsmError;
+use std::collections::BTreeMap;
+
+/// Value of a guard variable
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GuardValue {
+    Int(i128),
+    Bool(bool),
+    Str(String),
+    Null,
+}
+
+impl From<i64> for GuardValue {
+    fn from(value: i64) -> Self {
+        GuardValue::Int(i128::from(value))
+    }
+}
+
+impl From<u64> for GuardValue {
+    fn from(value: u64) -> Self {
+        GuardValue::Int(i128::from(value))
+    }
+}
+
+impl From<bool> for GuardValue {
+    fn from(value: bool) -> Self {
+        GuardValue::Bool(value)
+    }
+}
+
+impl From<&str> for GuardValue {
+    fn from(value: &str) -> Self {
+        GuardValue::Str(value.to_string())
+    }
+}
+
+impl<T: Into<GuardValue>> From<Option<T>> for GuardValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(GuardValue::Null, Into::into)
+    }
+}
+
+/// Variables a guard is evaluated against
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GuardContext {
+    variables: BTreeMap<String, GuardValue>,
+}
+
+impl GuardContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `name`, replacing any previous value
+    pub fn set(&mut self, name: &str, value: impl Into<GuardValue>) {
+        self.variables.insert(name.to_string(), value.into());
+    }
+
+    /// `set` for chaining
+    pub fn with(mut self, name: &str, value: impl Into<GuardValue>) -> Self {
+        self.set(name, value);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GuardValue> {
+        self.variables.get(name)
+    }
+
+    /// Variable names in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.variables.keys().map(String::as_str)
+    }
+
+    /// Evaluate `guard`; see the module docs for the grammar
+    pub fn evaluate(&self, guard: &str) -> Result<bool, FsmError> {
+        let tokens = tokenize(guard)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+        };
+        let expression = parser.or()?;
+        if parser.position != tokens.len() {
+            return Err(FsmError::InvalidInput);
+        }
+        match expression.eval(self)? {
+            GuardValue::Bool(result) => Ok(result),
+            _ => Err(FsmError::InvalidInput),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Value(GuardValue),
+    Variable(String),
+    Compare(Comparison),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+fn tokenize(guard: &str) -> Result<Vec<Token>, FsmError> {
+    let chars: Vec<char> = guard.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            i += 1;
+        } else if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let value = literal.parse().map_err(|_| FsmError::InvalidInput)?;
+            tokens.push(Token::Value(GuardValue::Int(value)));
+        } else if c == '\'' || c == '"' {
+            let end = chars[i + 1..]
+                .iter()
+                .position(|&q| q == c)
+                .ok_or(FsmError::InvalidInput)?;
+            let literal = chars[i + 1..i + 1 + end].iter().collect();
+            tokens.push(Token::Value(GuardValue::Str(literal)));
+            i += end + 2;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(match word.as_str() {
+                "true" => Token::Value(GuardValue::Bool(true)),
+                "false" => Token::Value(GuardValue::Bool(false)),
+                "null" => Token::Value(GuardValue::Null),
+                "and" => Token::And,
+                "or" => Token::Or,
+                "not" => Token::Not,
+                _ => Token::Variable(word),
+            });
+        } else {
+            let (comparison, width) = match (c, next) {
+                ('=', Some('=')) => (Comparison::Eq, 2),
+                ('!', Some('=')) => (Comparison::Ne, 2),
+                ('<', Some('=')) => (Comparison::Le, 2),
+                ('>', Some('=')) => (Comparison::Ge, 2),
+                ('<', _) => (Comparison::Lt, 1),
+                ('>', _) => (Comparison::Gt, 1),
+                _ => return Err(FsmError::InvalidInput),
+            };
+            tokens.push(Token::Compare(comparison));
+            i += width;
+        }
+    }
+    Ok(tokens)
+}
+
+enum Expression {
+    Value(GuardValue),
+    Variable(String),
+    Compare(Box<Expression>, Comparison, Box<Expression>),
+    Not(Box<Expression>),
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn or(&mut self) -> Result<Expression, FsmError> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            left = Expression::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expression, FsmError> {
+        let mut left = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            left = Expression::And(Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expression, FsmError> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            return Ok(Expression::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expression, FsmError> {
+        let left = self.primary()?;
+        if let Some(Token::Compare(comparison)) = self.peek() {
+            let comparison = *comparison;
+            self.position += 1;
+            let right = self.primary()?;
+            return Ok(Expression::Compare(
+                Box::new(left),
+                comparison,
+                Box::new(right),
+            ));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Expression, FsmError> {
+        let token = self.peek().cloned().ok_or(FsmError::InvalidInput)?;
+        self.position += 1;
+        match token {
+            Token::Value(value) => Ok(Expression::Value(value)),
+            Token::Variable(name) => Ok(Expression::Variable(name)),
+            Token::Open => {
+                let inner = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(FsmError::InvalidInput);
+                }
+                self.position += 1;
+                Ok(inner)
+            }
+            _ => Err(FsmError::InvalidInput),
+        }
+    }
+}
+
+impl Expression {
+    fn eval_bool(&self, context: &GuardContext) -> Result<bool, FsmError> {
+        match self.eval(context)? {
+            GuardValue::Bool(value) => Ok(value),
+            _ => Err(FsmError::InvalidInput),
+        }
+    }
+
+    fn eval(&self, context: &GuardContext) -> Result<GuardValue, FsmError> {
+        Ok(match self {
+            Expression::Value(value) => value.clone(),
+            Expression::Variable(name) => {
+                context.get(name).cloned().ok_or(FsmError::InvalidInput)?
+            }
+            Expression::Not(inner) => GuardValue::Bool(!inner.eval_bool(context)?),
+            Expression::And(left, right) => {
+                GuardValue::Bool(left.eval_bool(context)? && right.eval_bool(context)?)
+            }
+            Expression::Or(left, right) => {
+                GuardValue::Bool(left.eval_bool(context)? || right.eval_bool(context)?)
+            }
+            Expression::Compare(left, comparison, right) => {
+                let (left, right) = (left.eval(context)?, right.eval(context)?);
+                GuardValue::Bool(compare(&left, *comparison, &right)?)
+            }
+        })
+    }
+}
+
+fn compare(
+    left: &GuardValue,
+    comparison: Comparison,
+    right: &GuardValue,
+) -> Result<bool, FsmError> {
+    use GuardValue::*;
+    match (left, right) {
+        (Int(l), Int(r)) => Ok(match comparison {
+            Comparison::Eq => l == r,
+            Comparison::Ne => l != r,
+            Comparison::Lt => l < r,
+            Comparison::Le => l <= r,
+            Comparison::Gt => l > r,
+            Comparison::Ge => l >= r,
+        }),
+        (Bool(_), Bool(_)) | (Str(_), Str(_)) | (Null, _) | (_, Null) => match comparison {
+            Comparison::Eq => Ok(left == right),
+            Comparison::Ne => Ok(left != right),
+            _ => Err(FsmError::InvalidInput),
+        },
+        _ => Err(FsmError::InvalidInput),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> GuardContext {
+        GuardContext::new()
+            .with("yes_votes", 5u64)
+            .with("no_votes", 3u64)
+            .with("status", "Active")
+            .with("votes.quorum", true)
+            .with("expires_at", None::<i64>)
+    }
+
+    #[test]
+    fn evaluates_comparisons_and_logic() {
+        let context = context();
+        assert_eq!(context.evaluate("yes_votes > no_votes"), Ok(true));
+        assert_eq!(
+            context.evaluate("yes_votes >= 6 or no_votes == 3"),
+            Ok(true)
+        );
+        assert_eq!(
+            context.evaluate("not (status == 'Active') and votes.quorum"),
+            Ok(false)
+        );
+        assert_eq!(context.evaluate("status != \"Draft\""), Ok(true));
+        assert_eq!(context.evaluate("expires_at == null"), Ok(true));
+        assert_eq!(
+            context.evaluate("no_votes - 1"),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(context.evaluate("-1 < no_votes"), Ok(true));
+        // `not` binds tighter than `and`, which binds tighter than `or`
+        assert_eq!(context.evaluate("true or false and false"), Ok(true));
+        assert_eq!(context.evaluate("not false and false"), Ok(false));
+    }
+
+    #[test]
+    fn errors_are_not_false() {
+        let context = context();
+        for guard in [
+            "",
+            "unknown > 1",
+            "yes_votes > 'a'",
+            "status > 'A'",
+            "yes_votes",
+            "(yes_votes > 1",
+            "yes_votes > 1)",
+            "yes_votes = 1",
+            "'unterminated",
+            "yes_votes > 1 2",
+        ] {
+            assert_eq!(
+                context.evaluate(guard),
+                Err(FsmError::InvalidInput),
+                "{guard}"
+            );
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Canonical hashes of off-chain documents.
+//!
+//! The `*_data_hash` and `*_config_hash` fields of the governance metadata are
+//! SHA-256 digests of a document kept off-chain. To let every service compute
+//! the same digest, the document is encoded in the canonical form of
+//! `canonical` first.
+//!
+//! `tests/hash_vectors.json` lists inputs with their canonical form and digest
+//! for checking other implementations.
+
+use crate::canonical::{canonical_hash, to_canonical_string};
+use crate::error::FsmError;
+use serde::Serialize;
+
+/// Canonical JSON encoding of `value` (see `canonical`)
+///
+/// `InvalidInput` if `value` cannot be represented as JSON or contains a
+/// non-integer number.
+pub fn canonical_json<T: Serialize>(value: &T) -> Result<String, FsmError> {
+    to_canonical_string(value)
+}
+
+/// SHA-256 of `canonical_json(value)`
+pub fn hash_canonical<T: Serialize>(value: &T) -> Result<[u8; 32], FsmError> {
+    canonical_hash(value)
+}
+
+/// Whether `value` hashes to `expected`; false if it cannot be encoded
+pub fn verify<T: Serialize>(expected: &[u8; 32], value: &T) -> bool {
+    hash_canonical(value).is_ok_and(|hash| hash == *expected)
+}
+
+#[cfg(feature = "governance-meta")]
+mod governance {
+    use super::verify;
+    use crate::governance::{
+        GovernanceAnalyticsMetadata, GovernanceParticipationMetadata, GovernanceVotingMetadata,
+        SecurityBoardDecisionMetadata, SecurityExcellenceMetadata, SecurityPolicyMetadata,
+    };
+    use serde::Serialize;
+
+    impl SecurityPolicyMetadata {
+        /// Whether `policy_doc` matches `policy_data_hash`
+        pub fn verify_policy_data<T: Serialize>(&self, policy_doc: &T) -> bool {
+            verify(&self.policy_data_hash, policy_doc)
+        }
+    }
+
+    impl<P> SecurityBoardDecisionMetadata<P> {
+        /// Whether `decision_doc` matches `decision_data_hash`
+        pub fn verify_decision_data<T: Serialize>(&self, decision_doc: &T) -> bool {
+            verify(&self.decision_data_hash, decision_doc)
+        }
+    }
+
+    impl GovernanceVotingMetadata {
+        /// Whether `voting_doc` matches `voting_data_hash`
+        pub fn verify_voting_data<T: Serialize>(&self, voting_doc: &T) -> bool {
+            verify(&self.voting_data_hash, voting_doc)
+        }
+    }
+
+    impl GovernanceAnalyticsMetadata {
+        /// Whether `config` matches `analytics_config_hash`
+        pub fn verify_analytics_config<T: Serialize>(&self, config: &T) -> bool {
+            verify(&self.analytics_config_hash, config)
+        }
+    }
+
+    impl GovernanceParticipationMetadata {
+        /// Whether `config` matches `participation_config_hash`
+        pub fn verify_participation_config<T: Serialize>(&self, config: &T) -> bool {
+            verify(&self.participation_config_hash, config)
+        }
+    }
+
+    impl SecurityExcellenceMetadata {
+        /// Whether `score_doc` matches `score_data_hash`
+        pub fn verify_score_data<T: Serialize>(&self, score_doc: &T) -> bool {
+            verify(&self.score_data_hash, score_doc)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn keys_are_sorted_at_every_level() {
+        let value = json!({"b": 1, "a": {"z": [true, null], "y": "x"}, "A": -2});
+        assert_eq!(
+            canonical_json(&value).unwrap(),
+            r#"{"A":-2,"a":{"y":"x","z":[true,null]},"b":1}"#
+        );
+        #[derive(Serialize)]
+        struct Doc {
+            zeta: u64,
+            alpha: &'static str,
+        }
+        assert_eq!(
+            canonical_json(&Doc {
+                zeta: 1,
+                alpha: "é\n"
+            })
+            .unwrap(),
+            "{\"alpha\":\"é\\n\",\"zeta\":1}"
+        );
+    }
+
+    #[test]
+    fn floats_and_non_string_keys_are_refused() {
+        assert_eq!(
+            canonical_json(&json!({"a": 1.5})),
+            Err(FsmError::InvalidInput)
+        );
+        let map: BTreeMap<(u8, u8), u8> = BTreeMap::from([((1, 2), 3)]);
+        assert_eq!(hash_canonical(&map), Err(FsmError::InvalidInput));
+        assert!(!verify(&[0; 32], &map));
+    }
+
+    #[test]
+    fn verify_matches_hash() {
+        let doc = json!({"rule": "two-of-three"});
+        let hash = hash_canonical(&doc).unwrap();
+        assert!(verify(&hash, &doc));
+        assert!(!verify(&hash, &json!({"rule": "one-of-three"})));
+        // Key order in the input does not matter
+        let reordered = json!({"b": 2, "a": 1});
+        assert_eq!(
+            hash_canonical(&reordered).unwrap(),
+            hash_canonical(&json!({"a": 1, "b": 2})).unwrap()
+        );
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn typed_wrappers_check_their_field() {
+        use crate::governance::{SecurityBoardDecisionMetadata, SecurityPolicyMetadata};
+        let doc = json!({"action": "pause", "target": "bridge"});
+        let hash = hash_canonical(&doc).unwrap();
+        let policy =
+            SecurityPolicyMetadata::initialize(1, "Pause".to_string(), hash, 1000).unwrap();
+        assert!(policy.verify_policy_data(&doc));
+        assert!(!policy.verify_policy_data(&json!({"action": "resume"})));
+        let decision =
+            SecurityBoardDecisionMetadata::<u8>::initialize(1, None, hash, 1000).unwrap();
+        assert!(decision.verify_decision_data(&doc));
+    }
+}
@@ -0,0 +1,636 @@
+//! Governance health score.
+//!
+//! Six signals are normalized to 0-100 and combined into one weighted score.
+//! Integer math only. Each ratio component is
+//! `min(rate_bps(numerator, denominator), 10_000) / 100`, where `rate_bps`
+//! rounds half up; a component with a zero denominator has no evidence of
+//! health and scores 0.
+//!
+//! | Component | Formula |
+//! |---|---|
+//! | Quorum | `quorum_reached / quorum_evaluated` |
+//! | Throughput | `proposals_executed / proposals_created` |
+//! | Participation | `min(participation_bps, 10_000) / 100` |
+//! | Milestones | `milestones_on_time / milestones_due` |
+//! | Security | `active_policies / total_policies` |
+//! | Liveness | `jobs_live / jobs_total` |
+//!
+//! The overall score is `sum(score * weight) / 100`, rounded down. Liveness
+//! weighs 0 unless set with `HealthWeights::with_liveness`, so scores of
+//! callers that do not track jobs are unchanged.
+//!
+//! `HealthInputs::add_proposal` and `add_grant` count the quorum, throughput
+//! and milestone signals from the entities themselves, `add_jobs` the
+//! liveness signal from `JobHeartbeat`s; participation and security are set
+//! directly.
+//!
+//! ## Job liveness
+//!
+//! Auto transitions, expirations and sweeps only happen while an off-chain
+//! job runs them. A `JobHeartbeat` records each run of such a job
+//! (`maintenance::execute` updates one when given it), and `job_liveness`
+//! flags the jobs that have not succeeded within `overdue_factor` times their
+//! expected interval.
+
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::grant::Grant;
+use crate::projections::{RATE_SCALE, rate_bps};
+use crate::proposal::{Proposal, ProposalStatus};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A health score component, in report order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HealthComponent {
+    Quorum,
+    Throughput,
+    Participation,
+    Milestones,
+    Security,
+    Liveness,
+}
+
+const COMPONENTS: [HealthComponent; 6] = [
+    HealthComponent::Quorum,
+    HealthComponent::Throughput,
+    HealthComponent::Participation,
+    HealthComponent::Milestones,
+    HealthComponent::Security,
+    HealthComponent::Liveness,
+];
+
+/// Component weights, summing to 100
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HealthWeights {
+    weights: [u8; 6],
+}
+
+impl Default for HealthWeights {
+    /// 20 each, liveness 0
+    fn default() -> Self {
+        Self {
+            weights: [20, 20, 20, 20, 20, 0],
+        }
+    }
+}
+
+impl HealthWeights {
+    /// `InvalidInput` unless the weights sum to 100; liveness weighs 0
+    pub fn new(
+        quorum: u8,
+        throughput: u8,
+        participation: u8,
+        milestones: u8,
+        security: u8,
+    ) -> Result<Self, FsmError> {
+        Self::with_liveness(quorum, throughput, participation, milestones, security, 0)
+    }
+
+    /// `new` with a weight for the liveness component
+    pub fn with_liveness(
+        quorum: u8,
+        throughput: u8,
+        participation: u8,
+        milestones: u8,
+        security: u8,
+        liveness: u8,
+    ) -> Result<Self, FsmError> {
+        let weights = [
+            quorum,
+            throughput,
+            participation,
+            milestones,
+            security,
+            liveness,
+        ];
+        if weights.iter().map(|w| u32::from(*w)).sum::<u32>() != 100 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self { weights })
+    }
+
+    pub fn weight(&self, component: HealthComponent) -> u8 {
+        self.weights[component as usize]
+    }
+}
+
+/// Raw signals for one reporting period
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HealthInputs {
+    pub quorum_reached: u64,
+    pub quorum_evaluated: u64,
+    pub proposals_created: u64,
+    pub proposals_executed: u64,
+    /// Average voting participation in basis points
+    pub participation_bps: u64,
+    pub milestones_on_time: u64,
+    pub milestones_due: u64,
+    pub active_policies: u64,
+    pub total_policies: u64,
+    /// Tracked jobs not flagged by `job_liveness`
+    pub jobs_live: u64,
+    pub jobs_total: u64,
+    pub weights: HealthWeights,
+}
+
+/// One component's contribution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentScore {
+    pub component: HealthComponent,
+    /// 0-100
+    pub score: u8,
+    pub weight: u8,
+}
+
+impl ComponentScore {
+    /// Points lost to the overall score, in hundredths of a point
+    pub fn drag(&self) -> u32 {
+        u32::from(self.weight) * u32::from(100 - self.score)
+    }
+}
+
+/// Health score with its breakdown
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthReport {
+    /// 0-100
+    pub overall: u8,
+    /// Every component in `HealthComponent` order
+    pub components: Vec<ComponentScore>,
+    /// Up to two components costing the most points, largest first; components costing nothing are left out
+    pub top_drags: Vec<HealthComponent>,
+}
+
+fn percent(numerator: u64, denominator: u64) -> u8 {
+    rate_bps(numerator, denominator).map_or(0, |bps| (bps.min(RATE_SCALE) / 100) as u8)
+}
+
+impl HealthInputs {
+    /// Count `proposal` as created, and as executed or quorum-checked if it got that far
+    ///
+    /// Voting counts as evaluated once it settled the proposal (Passed or
+    /// later, Rejected, Tied or QuorumFailed); only QuorumFailed misses quorum.
+    pub fn add_proposal<P>(&mut self, proposal: &Proposal<P>) {
+        self.proposals_created = self.proposals_created.saturating_add(1);
+        if proposal.executed_at.is_some() {
+            self.proposals_executed = self.proposals_executed.saturating_add(1);
+        }
+        let evaluated = proposal.passed_at.is_some()
+            || matches!(
+                proposal.status,
+                ProposalStatus::Rejected | ProposalStatus::Tied | ProposalStatus::QuorumFailed
+            );
+        if evaluated {
+            self.quorum_evaluated = self.quorum_evaluated.saturating_add(1);
+            if proposal.status != ProposalStatus::QuorumFailed {
+                self.quorum_reached = self.quorum_reached.saturating_add(1);
+            }
+        }
+    }
+
+    /// Count the scheduled payouts of `grant` due by `now` as milestones
+    ///
+    /// A due payout is on time once disbursements cover it.
+    pub fn add_grant(&mut self, grant: &Grant, now: i64) {
+        let due = grant
+            .payout_schedule
+            .iter()
+            .filter(|payout| payout.due_at <= now)
+            .count() as u64;
+        let unpaid = grant
+            .remaining_payouts()
+            .iter()
+            .filter(|payout| payout.due_at <= now)
+            .count() as u64;
+        self.milestones_due = self.milestones_due.saturating_add(due);
+        self.milestones_on_time = self
+            .milestones_on_time
+            .saturating_add(due.saturating_sub(unpaid));
+    }
+
+    /// Count `heartbeats` as tracked jobs, live unless named in `alerts`
+    ///
+    /// `alerts` is the `job_liveness` result for the same heartbeats.
+    pub fn add_jobs(&mut self, heartbeats: &[JobHeartbeat], alerts: &[JobAlert]) {
+        let live = heartbeats
+            .iter()
+            .filter(|heartbeat| !alerts.iter().any(|alert| alert.job == heartbeat.job))
+            .count() as u64;
+        self.jobs_total = self.jobs_total.saturating_add(heartbeats.len() as u64);
+        self.jobs_live = self.jobs_live.saturating_add(live);
+    }
+
+    /// Normalized 0-100 score of one component
+    pub fn component_score(&self, component: HealthComponent) -> u8 {
+        match component {
+            HealthComponent::Quorum => percent(self.quorum_reached, self.quorum_evaluated),
+            HealthComponent::Throughput => percent(self.proposals_executed, self.proposals_created),
+            HealthComponent::Participation => (self.participation_bps.min(RATE_SCALE) / 100) as u8,
+            HealthComponent::Milestones => percent(self.milestones_on_time, self.milestones_due),
+            HealthComponent::Security => percent(self.active_policies, self.total_policies),
+            HealthComponent::Liveness => percent(self.jobs_live, self.jobs_total),
+        }
+    }
+}
+
+/// Combine the inputs into a weighted 0-100 score
+pub fn score(inputs: HealthInputs) -> HealthReport {
+    let components: Vec<ComponentScore> = COMPONENTS
+        .iter()
+        .map(|component| ComponentScore {
+            component: *component,
+            score: inputs.component_score(*component),
+            weight: inputs.weights.weight(*component),
+        })
+        .collect();
+    let weighted: u32 = components
+        .iter()
+        .map(|c| u32::from(c.score) * u32::from(c.weight))
+        .sum();
+
+    let mut drags: Vec<&ComponentScore> = components.iter().filter(|c| c.drag() > 0).collect();
+    // Stable sort keeps component order among equal drags
+    drags.sort_by_key(|c| std::cmp::Reverse(c.drag()));
+    let top_drags = drags.iter().take(2).map(|c| c.component).collect();
+
+    HealthReport {
+        overall: (weighted / 100) as u8,
+        components,
+        top_drags,
+    }
+}
+
+/// `job_liveness` flags a job this many expected intervals after its last success
+pub const DEFAULT_OVERDUE_FACTOR: u32 = 2;
+
+/// How one run of a job ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum JobOutcome {
+    /// Succeeded, with the hash of its report if it produced one
+    Succeeded {
+        report_hash: Option<[u8; 32]>,
+    },
+    Failed,
+}
+
+/// Last runs of one automated governance job
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JobHeartbeat {
+    pub job: String,
+    pub last_run_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+    /// Failed runs since the last success
+    pub consecutive_failures: u32,
+    /// Report hash of the last successful run
+    pub last_report_hash: Option<[u8; 32]>,
+}
+
+impl JobHeartbeat {
+    /// A job that has not run yet
+    pub fn new(job: String) -> Self {
+        Self {
+            job,
+            ..Self::default()
+        }
+    }
+
+    /// Record a run that ended at `current_time`
+    ///
+    /// A run reported out of order still counts, but never moves
+    /// `last_run_at` or `last_success_at` back.
+    pub fn record_run(&mut self, outcome: JobOutcome, current_time: i64) {
+        self.last_run_at = self.last_run_at.max(Some(current_time));
+        match outcome {
+            JobOutcome::Succeeded { report_hash } => {
+                self.last_success_at = self.last_success_at.max(Some(current_time));
+                self.consecutive_failures = 0;
+                self.last_report_hash = report_hash;
+            }
+            JobOutcome::Failed => {
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+            }
+        }
+    }
+}
+
+/// Why a job was flagged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum JobAlertKind {
+    /// No run recorded
+    NeverRun,
+    /// Runs recorded, none of them successful
+    NeverSucceeded,
+    /// The last success is older than the allowed interval
+    Overdue {
+        /// When a success was last due
+        deadline: i64,
+        overdue_by: DurationSecs,
+    },
+}
+
+/// A job that needs attention
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JobAlert {
+    pub job: String,
+    pub kind: JobAlertKind,
+    pub consecutive_failures: u32,
+}
+
+/// `job_liveness_with_factor` with `DEFAULT_OVERDUE_FACTOR`
+pub fn job_liveness(
+    heartbeats: &[JobHeartbeat],
+    expected_interval: DurationSecs,
+    current_time: i64,
+) -> Vec<JobAlert> {
+    job_liveness_with_factor(
+        heartbeats,
+        expected_interval,
+        DEFAULT_OVERDUE_FACTOR,
+        current_time,
+    )
+}
+
+/// Jobs with no success in the last `overdue_factor * expected_interval`, in input order
+///
+/// A job is overdue once `current_time` is past its deadline, its last
+/// success plus the allowed interval (saturating).
+pub fn job_liveness_with_factor(
+    heartbeats: &[JobHeartbeat],
+    expected_interval: DurationSecs,
+    overdue_factor: u32,
+    current_time: i64,
+) -> Vec<JobAlert> {
+    let allowed = expected_interval
+        .as_secs()
+        .saturating_mul(i64::from(overdue_factor));
+    heartbeats
+        .iter()
+        .filter_map(|heartbeat| {
+            let kind = match (heartbeat.last_run_at, heartbeat.last_success_at) {
+                (None, _) => JobAlertKind::NeverRun,
+                (Some(_), None) => JobAlertKind::NeverSucceeded,
+                (Some(_), Some(success)) => {
+                    let deadline = success.saturating_add(allowed);
+                    if current_time <= deadline {
+                        return None;
+                    }
+                    JobAlertKind::Overdue {
+                        deadline,
+                        overdue_by: DurationSecs::from_secs(current_time.saturating_sub(deadline)),
+                    }
+                }
+            };
+            Some(JobAlert {
+                job: heartbeat.job.clone(),
+                kind,
+                consecutive_failures: heartbeat.consecutive_failures,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn perfect_inputs() -> HealthInputs {
+        HealthInputs {
+            quorum_reached: 10,
+            quorum_evaluated: 10,
+            proposals_created: 4,
+            proposals_executed: 4,
+            participation_bps: 10_000,
+            milestones_on_time: 3,
+            milestones_due: 3,
+            active_policies: 5,
+            total_policies: 5,
+            jobs_live: 2,
+            jobs_total: 2,
+            weights: HealthWeights::default(),
+        }
+    }
+
+    #[test]
+    fn test_perfect_inputs() {
+        let report = score(perfect_inputs());
+        assert_eq!(report.overall, 100);
+        assert!(report.components.iter().all(|c| c.score == 100));
+        assert!(report.top_drags.is_empty());
+    }
+
+    #[test]
+    fn test_zero_inputs() {
+        let report = score(HealthInputs::default());
+        assert_eq!(report.overall, 0);
+        assert!(report.components.iter().all(|c| c.score == 0));
+        assert_eq!(
+            report.top_drags,
+            vec![HealthComponent::Quorum, HealthComponent::Throughput]
+        );
+    }
+
+    #[test]
+    fn test_weighted_score_and_drags() {
+        let inputs = HealthInputs {
+            quorum_reached: 1,
+            quorum_evaluated: 2,
+            participation_bps: 2_500,
+            proposals_executed: 9,
+            weights: HealthWeights::new(10, 20, 40, 10, 20).unwrap(),
+            ..perfect_inputs()
+        };
+        let report = score(inputs);
+        let scores: Vec<u8> = report.components.iter().map(|c| c.score).collect();
+        // Executing more than were created is capped at 100
+        assert_eq!(scores, vec![50, 100, 25, 100, 100, 100]);
+        // 5 + 20 + 10 + 10 + 20
+        assert_eq!(report.overall, 65);
+        assert_eq!(
+            report.top_drags,
+            vec![HealthComponent::Participation, HealthComponent::Quorum]
+        );
+    }
+
+    #[test]
+    fn test_rounding_is_deterministic() {
+        let inputs = HealthInputs {
+            quorum_reached: 2,
+            quorum_evaluated: 3,
+            ..perfect_inputs()
+        };
+        // 6667 bps -> 66
+        assert_eq!(inputs.component_score(HealthComponent::Quorum), 66);
+        assert_eq!(score(inputs).overall, 93);
+        assert_eq!(score(inputs), score(inputs));
+    }
+
+    #[test]
+    fn test_weights_must_sum_to_100() {
+        assert_eq!(
+            HealthWeights::new(20, 20, 20, 20, 19),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            HealthWeights::new(255, 255, 0, 0, 0),
+            Err(FsmError::InvalidInput)
+        );
+        let weights = HealthWeights::new(100, 0, 0, 0, 0).unwrap();
+        assert_eq!(weights.weight(HealthComponent::Quorum), 100);
+        assert_eq!(weights.weight(HealthComponent::Security), 0);
+        assert_eq!(weights.weight(HealthComponent::Liveness), 0);
+        assert_eq!(
+            HealthWeights::with_liveness(20, 20, 20, 20, 20, 1),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_records_runs() {
+        let mut heartbeat = JobHeartbeat::new("maintenance".to_string());
+        heartbeat.record_run(JobOutcome::Failed, 100);
+        heartbeat.record_run(JobOutcome::Failed, 200);
+        assert_eq!(
+            (heartbeat.last_run_at, heartbeat.consecutive_failures),
+            (Some(200), 2)
+        );
+        heartbeat.record_run(
+            JobOutcome::Succeeded {
+                report_hash: Some([7u8; 32]),
+            },
+            300,
+        );
+        assert_eq!(heartbeat.last_success_at, Some(300));
+        assert_eq!(heartbeat.consecutive_failures, 0);
+        assert_eq!(heartbeat.last_report_hash, Some([7u8; 32]));
+        // A late report of an earlier run does not move the timestamps back
+        heartbeat.record_run(JobOutcome::Succeeded { report_hash: None }, 250);
+        assert_eq!(
+            (heartbeat.last_run_at, heartbeat.last_success_at),
+            (Some(300), Some(300))
+        );
+    }
+
+    #[test]
+    fn test_job_liveness_flags_overdue_jobs() {
+        let interval = DurationSecs::from_secs(60);
+        let job = |name: &str, runs: &[(JobOutcome, i64)]| {
+            let mut heartbeat = JobHeartbeat::new(name.to_string());
+            for (outcome, at) in runs {
+                heartbeat.record_run(*outcome, *at);
+            }
+            heartbeat
+        };
+        let ok = JobOutcome::Succeeded { report_hash: None };
+        let heartbeats = vec![
+            job("fresh", &[(ok, 900)]),
+            job("edge", &[(ok, 880)]),
+            job("stale", &[(ok, 800), (JobOutcome::Failed, 950)]),
+            job("failing", &[(JobOutcome::Failed, 990)]),
+            job("idle", &[]),
+        ];
+        let alerts = job_liveness(&heartbeats, interval, 1000);
+        assert_eq!(
+            alerts,
+            vec![
+                JobAlert {
+                    job: "stale".to_string(),
+                    kind: JobAlertKind::Overdue {
+                        deadline: 920,
+                        overdue_by: DurationSecs::from_secs(80),
+                    },
+                    consecutive_failures: 1,
+                },
+                JobAlert {
+                    job: "failing".to_string(),
+                    kind: JobAlertKind::NeverSucceeded,
+                    consecutive_failures: 1,
+                },
+                JobAlert {
+                    job: "idle".to_string(),
+                    kind: JobAlertKind::NeverRun,
+                    consecutive_failures: 0,
+                },
+            ]
+        );
+        assert_eq!(
+            job_liveness_with_factor(&heartbeats, interval, 1, 1000).len(),
+            5
+        );
+
+        let mut inputs = HealthInputs {
+            weights: HealthWeights::with_liveness(20, 20, 20, 20, 0, 20).unwrap(),
+            ..perfect_inputs()
+        };
+        inputs.jobs_live = 0;
+        inputs.jobs_total = 0;
+        inputs.add_jobs(&heartbeats, &alerts);
+        assert_eq!((inputs.jobs_live, inputs.jobs_total), (2, 5));
+        let report = score(inputs);
+        // 80 + 40% of 20
+        assert_eq!(report.overall, 88);
+        assert_eq!(report.top_drags, vec![HealthComponent::Liveness]);
+    }
+
+    #[test]
+    fn test_counts_from_entities() {
+        use crate::duration::DurationSecs;
+        use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+        let proposal = |id| {
+            let mut proposal = Proposal::builder(
+                id,
+                "Title".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                1u8,
+            )
+            .voting_duration(DurationSecs::from_secs(10))
+            .build(0)
+            .unwrap();
+            proposal.activate_with_time(1, 1, 0).unwrap();
+            proposal
+        };
+        let mut executed = proposal(1);
+        executed.pass_with_time(10).unwrap();
+        executed.execute_with_time(20).unwrap();
+        let mut failed = proposal(2);
+        failed.fail_quorum_with_time(10).unwrap();
+        let mut inputs = HealthInputs::default();
+        for p in [&executed, &failed, &proposal(3)] {
+            inputs.add_proposal(p);
+        }
+        assert_eq!(
+            (
+                inputs.proposals_created,
+                inputs.proposals_executed,
+                inputs.quorum_evaluated,
+                inputs.quorum_reached
+            ),
+            (3, 1, 2, 1)
+        );
+
+        let mut grant = Grant::new(
+            1,
+            1,
+            [0u8; 32],
+            GrantCategory::Development,
+            GrantType::Core,
+            GrantDisbursementType::Standard,
+            300,
+            0,
+            0,
+        )
+        .unwrap();
+        for due_at in [100, 200, 300] {
+            grant.schedule_payout(due_at, 100).unwrap();
+        }
+        grant.approve().unwrap();
+        grant.activate().unwrap();
+        grant.disburse(150).unwrap();
+        inputs.add_grant(&grant, 250);
+        assert_eq!((inputs.milestones_due, inputs.milestones_on_time), (2, 1));
+    }
+}
@@ -0,0 +1,267 @@
+//! Pipeline metrics over a portfolio of ideas
+//!
+//! `pipeline` counts ideas per status, averages how long they have been in
+//! it, and lists the ideas that have been in their status longer than the
+//! `StallThresholds` allow. Ages come from `Idea::status_changed_at`; ideas
+//! stored before that field existed fall back to `last_event_at`, which can
+//! only make them look younger. All figures are whole seconds.
+
+use super::Idea;
+use crate::duration::DurationSecs;
+use crate::enums::IdeaStatus;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Statuses in the order `PipelineReport::statuses` lists them
+const STATUS_ORDER: [IdeaStatus; 13] = [
+    IdeaStatus::Draft,
+    IdeaStatus::UnderReview,
+    IdeaStatus::Resubmitted,
+    IdeaStatus::Approved,
+    IdeaStatus::Voting,
+    IdeaStatus::InProgress,
+    IdeaStatus::Paused,
+    IdeaStatus::Completed,
+    IdeaStatus::Executed,
+    IdeaStatus::Commercialization,
+    IdeaStatus::Rejected,
+    IdeaStatus::Expired,
+    IdeaStatus::Archived,
+];
+
+/// Longest time an idea may stay in a status before it counts as stalled
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StallThresholds {
+    /// Statuses without an entry never stall
+    limits: Vec<(IdeaStatus, DurationSecs)>,
+}
+
+impl Default for StallThresholds {
+    /// 30 days in Draft, Approved and Paused; 14 in UnderReview, Resubmitted
+    /// and Voting; 180 in InProgress; outcome statuses never stall
+    fn default() -> Self {
+        let days = |days: i64| DurationSecs::from_secs(days * 24 * 3600);
+        Self {
+            limits: vec![
+                (IdeaStatus::Draft, days(30)),
+                (IdeaStatus::UnderReview, days(14)),
+                (IdeaStatus::Resubmitted, days(14)),
+                (IdeaStatus::Approved, days(30)),
+                (IdeaStatus::Voting, days(14)),
+                (IdeaStatus::InProgress, days(180)),
+                (IdeaStatus::Paused, days(30)),
+            ],
+        }
+    }
+}
+
+impl StallThresholds {
+    /// No status ever stalls
+    pub fn none() -> Self {
+        Self { limits: Vec::new() }
+    }
+
+    /// Set the threshold of `status`; `None` means it never stalls
+    pub fn with(mut self, status: IdeaStatus, limit: Option<DurationSecs>) -> Self {
+        self.limits.retain(|(s, _)| *s != status);
+        if let Some(limit) = limit {
+            self.limits.push((status, limit));
+        }
+        self
+    }
+
+    pub fn get(&self, status: IdeaStatus) -> Option<DurationSecs> {
+        self.limits
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map(|(_, limit)| *limit)
+    }
+}
+
+/// Ideas in one status
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusStats {
+    pub status: IdeaStatus,
+    pub ideas: u64,
+    /// Mean seconds in the status, rounded down
+    pub average_age_seconds: i64,
+}
+
+/// An idea past its status threshold
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StalledIdea {
+    pub id: u64,
+    pub status: IdeaStatus,
+    pub age_seconds: i64,
+    pub threshold_seconds: i64,
+    /// `age_seconds - threshold_seconds`, always positive
+    pub overdue_seconds: i64,
+}
+
+/// Result of `pipeline`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PipelineReport {
+    pub generated_at: i64,
+    /// Statuses holding at least one idea, in pipeline order (Draft first, Archived last)
+    pub statuses: Vec<StatusStats>,
+    /// Most overdue first; ties by id ascending
+    pub stalled: Vec<StalledIdea>,
+}
+
+/// Status counts, average ages and stalled ideas at `current_time` (see the module docs)
+///
+/// An idea whose status changed after `current_time` counts with age 0. An
+/// idea stalls once its age exceeds the threshold, not when it reaches it.
+pub fn pipeline(ideas: &[Idea], thresholds: &StallThresholds, current_time: i64) -> PipelineReport {
+    let mut totals = [(0u64, 0i128); STATUS_ORDER.len()];
+    let mut stalled = Vec::new();
+    for idea in ideas {
+        let changed_at = idea.status_changed_at.unwrap_or(idea.last_event_at);
+        let age = current_time.saturating_sub(changed_at).max(0);
+        if let Some(index) = STATUS_ORDER.iter().position(|s| *s == idea.status) {
+            totals[index].0 += 1;
+            totals[index].1 += i128::from(age);
+        }
+        if let Some(limit) = thresholds.get(idea.status)
+            && age > limit.as_secs()
+        {
+            stalled.push(StalledIdea {
+                id: idea.id,
+                status: idea.status,
+                age_seconds: age,
+                threshold_seconds: limit.as_secs(),
+                overdue_seconds: age.saturating_sub(limit.as_secs()),
+            });
+        }
+    }
+    stalled.sort_by(|a, b| {
+        b.overdue_seconds
+            .cmp(&a.overdue_seconds)
+            .then(a.id.cmp(&b.id))
+    });
+    let statuses = STATUS_ORDER
+        .iter()
+        .zip(totals)
+        .filter(|(_, (count, _))| *count > 0)
+        .map(|(status, (count, total))| StatusStats {
+            status: *status,
+            ideas: count,
+            // Mean of i64 ages fits in i64
+            average_age_seconds: (total / i128::from(count)) as i64,
+        })
+        .collect();
+    PipelineReport {
+        generated_at: current_time,
+        statuses,
+        stalled,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 3600;
+
+    fn idea(id: u64, status: IdeaStatus, changed_at: i64) -> Idea {
+        let mut idea = Idea::new(id, 0).unwrap();
+        idea.status = status;
+        idea.status_changed_at = Some(changed_at);
+        idea.last_event_at = changed_at;
+        idea
+    }
+
+    #[test]
+    fn test_pipeline_counts_and_stalls() {
+        let now = 100 * DAY;
+        let ideas = vec![
+            idea(1, IdeaStatus::UnderReview, now - 20 * DAY),
+            idea(2, IdeaStatus::UnderReview, now - 10 * DAY),
+            // Exactly at the threshold: not stalled
+            idea(3, IdeaStatus::UnderReview, now - 14 * DAY),
+            idea(4, IdeaStatus::Paused, now - 90 * DAY),
+            idea(5, IdeaStatus::Archived, 0),
+            idea(6, IdeaStatus::Draft, now + DAY),
+        ];
+        let report = pipeline(&ideas, &StallThresholds::default(), now);
+        assert_eq!(
+            report.statuses,
+            vec![
+                StatusStats {
+                    status: IdeaStatus::Draft,
+                    ideas: 1,
+                    average_age_seconds: 0,
+                },
+                StatusStats {
+                    status: IdeaStatus::UnderReview,
+                    ideas: 3,
+                    average_age_seconds: 44 * DAY / 3,
+                },
+                StatusStats {
+                    status: IdeaStatus::Paused,
+                    ideas: 1,
+                    average_age_seconds: 90 * DAY,
+                },
+                StatusStats {
+                    status: IdeaStatus::Archived,
+                    ideas: 1,
+                    average_age_seconds: 100 * DAY,
+                },
+            ]
+        );
+        assert_eq!(
+            report.stalled,
+            vec![
+                StalledIdea {
+                    id: 4,
+                    status: IdeaStatus::Paused,
+                    age_seconds: 90 * DAY,
+                    threshold_seconds: 30 * DAY,
+                    overdue_seconds: 60 * DAY,
+                },
+                StalledIdea {
+                    id: 1,
+                    status: IdeaStatus::UnderReview,
+                    age_seconds: 20 * DAY,
+                    threshold_seconds: 14 * DAY,
+                    overdue_seconds: 6 * DAY,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_thresholds_are_configurable() {
+        let now = 100 * DAY;
+        let mut legacy = idea(2, IdeaStatus::Archived, 0);
+        legacy.status_changed_at = None;
+        legacy.last_event_at = now - 5 * DAY;
+        let ideas = vec![idea(1, IdeaStatus::Paused, now - 90 * DAY), legacy];
+        let thresholds =
+            StallThresholds::none().with(IdeaStatus::Archived, Some(DurationSecs::from_secs(DAY)));
+        assert_eq!(thresholds.get(IdeaStatus::Paused), None);
+        let report = pipeline(&ideas, &thresholds, now);
+        assert_eq!(report.stalled.len(), 1);
+        assert_eq!(report.stalled[0].id, 2);
+        assert_eq!(report.stalled[0].age_seconds, 5 * DAY);
+
+        let thresholds = StallThresholds::default().with(IdeaStatus::Paused, None);
+        assert!(pipeline(&ideas, &thresholds, now).stalled.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_report_serializes() {
+        let ideas = vec![idea(1, IdeaStatus::Paused, 0)];
+        let report = pipeline(&ideas, &StallThresholds::default(), 100 * DAY);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PipelineReport>(&json).unwrap(),
+            report
+        );
+    }
+}
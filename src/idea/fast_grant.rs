@@ -0,0 +1,233 @@
+//! Fast grants straight from approved ideas
+//!
+//! Small grants skip the proposal process: `Idea::spawn_grant` turns an
+//! Approved idea into a Pending grant below `FastGrantPolicy::max_fast_grant`
+//! and moves the idea to InProgress. The grant keeps the idea's id in
+//! `idea_id` and is marked `fast_track`; it then goes through the usual grant
+//! lifecycle.
+//!
+//! An idea has at most one fast grant under way. The idea remembers the last
+//! one in `fast_grant_id`, and the next spawn needs that grant back to show it
+//! has finished (Completed, Cancelled, Rejected, Expired or Archived).
+
+use super::Idea;
+use crate::enums::IdeaStatus;
+use crate::error::FsmError;
+use crate::grant::lifecycle::EntityId;
+use crate::grant::{Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Limits and terms of fast grants
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FastGrantPolicy {
+    /// Fast grants must be below this amount; 0 disables them
+    pub max_fast_grant: u64,
+    /// Let the recipient differ from the idea's author
+    pub allow_other_recipients: bool,
+    pub category: GrantCategory,
+    pub grant_type: GrantType,
+    pub disbursement_type: GrantDisbursementType,
+}
+
+impl Default for FastGrantPolicy {
+    /// Disabled; Initial Development grants paid as Standard once enabled
+    fn default() -> Self {
+        Self {
+            max_fast_grant: 0,
+            allow_other_recipients: false,
+            category: GrantCategory::Development,
+            grant_type: GrantType::Initial,
+            disbursement_type: GrantDisbursementType::Standard,
+        }
+    }
+}
+
+fn is_finished(status: GrantStatus) -> bool {
+    matches!(
+        status,
+        GrantStatus::Completed
+            | GrantStatus::Cancelled
+            | GrantStatus::Rejected
+            | GrantStatus::Expired
+            | GrantStatus::Archived
+    )
+}
+
+impl Idea {
+    /// Spawn a Pending fast grant of `amount` to `recipient` (see the module docs)
+    ///
+    /// The first fast grant needs an Approved idea, later ones an Approved or
+    /// InProgress idea and `previous`, the grant named by `fast_grant_id`,
+    /// finished. `InvalidInput` for a zero amount, an amount not below
+    /// `max_fast_grant`, or a recipient other than `author` unless the policy
+    /// allows it; `InvalidState` if the idea's status or `previous` does not
+    /// permit a spawn; `TimeRegression` if `current_time` breaks
+    /// `time_ordering`. Nothing changes on error.
+    pub fn spawn_grant(
+        &mut self,
+        grant_id: u64,
+        amount: u64,
+        recipient: EntityId,
+        policy: &FastGrantPolicy,
+        previous: Option<&Grant>,
+        current_time: i64,
+    ) -> Result<Grant, FsmError> {
+        if amount == 0 || amount >= policy.max_fast_grant {
+            return Err(FsmError::InvalidInput);
+        }
+        if !policy.allow_other_recipients && self.author != Some(recipient) {
+            return Err(FsmError::InvalidInput);
+        }
+        let ready = match self.fast_grant_id {
+            None => self.status == IdeaStatus::Approved,
+            Some(last_id) => {
+                matches!(self.status, IdeaStatus::Approved | IdeaStatus::InProgress)
+                    && previous.is_some_and(|grant| {
+                        grant.id == last_id && grant.idea_id == self.id && is_finished(grant.status)
+                    })
+            }
+        };
+        if !ready {
+            return Err(FsmError::InvalidState);
+        }
+        let mut grant = Grant::new(
+            grant_id,
+            self.id,
+            recipient,
+            policy.category,
+            policy.grant_type,
+            policy.disbursement_type,
+            amount,
+            0,
+            current_time,
+        )?;
+        grant.fast_track = true;
+        if self.status == IdeaStatus::InProgress {
+            self.time_ordering.check(self.last_event_at, current_time)?;
+            self.last_event_at = current_time;
+        } else {
+            self.transition(IdeaStatus::InProgress, current_time)?;
+        }
+        self.fast_grant_id = Some(grant_id);
+        Ok(grant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idea::ReviewAssessment;
+
+    const AUTHOR: [u8; 32] = [7; 32];
+
+    fn create_approved_idea() -> Idea {
+        let mut idea = Idea::new(1, 0).unwrap().with_author(AUTHOR);
+        idea.submit_for_review(10).unwrap();
+        idea.add_assessment(ReviewAssessment {
+            reviewer: [1; 32],
+            score: 90,
+            category_scores: Vec::new(),
+            summary_hash: [0; 32],
+            assessed_at: 20,
+        })
+        .unwrap();
+        idea.conclude_review(60, 30).unwrap();
+        idea
+    }
+
+    fn policy() -> FastGrantPolicy {
+        FastGrantPolicy {
+            max_fast_grant: 1000,
+            ..FastGrantPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_spawn_grant_from_approved_idea() {
+        let mut idea = create_approved_idea();
+        let grant = idea
+            .spawn_grant(10, 999, AUTHOR, &policy(), None, 100)
+            .unwrap();
+        assert_eq!(grant.status, GrantStatus::Pending);
+        assert_eq!((grant.idea_id, grant.mesh_group_id), (1, AUTHOR));
+        assert_eq!(grant.total_amount, 999);
+        assert!(grant.fast_track);
+        assert_eq!(idea.status, IdeaStatus::InProgress);
+        assert_eq!(idea.status_changed_at, Some(100));
+        assert_eq!(idea.fast_grant_id, Some(10));
+    }
+
+    #[test]
+    fn test_amount_and_recipient_are_checked() {
+        let mut idea = create_approved_idea();
+        let before = idea.clone();
+        for amount in [0, 1000, 5000] {
+            assert_eq!(
+                idea.spawn_grant(10, amount, AUTHOR, &policy(), None, 100),
+                Err(FsmError::InvalidInput)
+            );
+        }
+        assert_eq!(
+            idea.spawn_grant(10, 1000, AUTHOR, &FastGrantPolicy::default(), None, 100),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            idea.spawn_grant(10, 500, [8; 32], &policy(), None, 100),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            idea.spawn_grant(10, 500, AUTHOR, &policy(), None, 20),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(idea, before);
+
+        let open = FastGrantPolicy {
+            allow_other_recipients: true,
+            ..policy()
+        };
+        let grant = idea
+            .spawn_grant(10, 500, [8; 32], &open, None, 100)
+            .unwrap();
+        assert_eq!(grant.mesh_group_id, [8; 32]);
+
+        let mut draft = Idea::new(2, 0).unwrap().with_author(AUTHOR);
+        assert_eq!(
+            draft.spawn_grant(11, 500, AUTHOR, &policy(), None, 100),
+            Err(FsmError::InvalidState)
+        );
+    }
+
+    #[test]
+    fn test_second_spawn_needs_first_grant_finished() {
+        let mut idea = create_approved_idea();
+        let mut first = idea
+            .spawn_grant(10, 500, AUTHOR, &policy(), None, 100)
+            .unwrap();
+        assert_eq!(
+            idea.spawn_grant(11, 500, AUTHOR, &policy(), None, 110),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            idea.spawn_grant(11, 500, AUTHOR, &policy(), Some(&first), 110),
+            Err(FsmError::InvalidState)
+        );
+        first.approve_with_time(120).unwrap();
+        first.activate_with_time(130).unwrap();
+        first.disburse_with_time(500, 140).unwrap();
+        let mut other = first.clone();
+        other.id = 12;
+        assert_eq!(
+            idea.spawn_grant(11, 500, AUTHOR, &policy(), Some(&other), 150),
+            Err(FsmError::InvalidState)
+        );
+        let second = idea
+            .spawn_grant(11, 500, AUTHOR, &policy(), Some(&first), 150)
+            .unwrap();
+        assert_eq!(second.id, 11);
+        assert_eq!(idea.status, IdeaStatus::InProgress);
+        assert_eq!(idea.status_changed_at, Some(100));
+        assert_eq!(idea.fast_grant_id, Some(11));
+    }
+}
@@ -0,0 +1,396 @@
+//! Ideas and their review
+//!
+//! An idea under review collects up to `MAX_ASSESSMENTS` `ReviewAssessment`s,
+//! typically written by AI reviewers, each with an overall score and per
+//! category scores. `conclude_review` turns their average into an Approved
+//! or Rejected idea and keeps the assessments behind that decision in a
+//! `ReviewDecision`. The idea itself carries no content; its id is what
+//! proposals and grants link to.
+//!
+//! Every status change follows the idea's `IdeaFsmProfile`; under
+//! `StrictTerminal` a rejected or archived idea cannot be resubmitted.
+//! `analytics::pipeline` summarizes a portfolio of ideas by status and finds
+//! the ones stalled in their current status.
+
+pub mod analytics;
+pub mod fast_grant;
+
+use crate::enums::{IdeaFsmProfile, IdeaStatus};
+use crate::error::FsmError;
+use crate::time_guard::{TimeOrdering, check_construction_time};
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Assessments an idea may collect in one review
+pub const MAX_ASSESSMENTS: usize = 5;
+
+/// Highest score an assessment may give
+pub const MAX_SCORE: u8 = 100;
+
+/// One reviewer's assessment of an idea
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReviewAssessment {
+    /// Same identifier as an `AuditActor`
+    pub reviewer: [u8; 32],
+    /// 0-100
+    pub score: u8,
+    /// Named scores, each 0-100
+    pub category_scores: Vec<(String, u8)>,
+    /// Hash of the review text, which is kept off-chain
+    pub summary_hash: [u8; 32],
+    pub assessed_at: i64,
+}
+
+/// Result of a review
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReviewOutcome {
+    Approve,
+    Reject,
+}
+
+/// How a review was concluded
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReviewDecision {
+    pub outcome: ReviewOutcome,
+    /// Average of the assessment scores, rounded down
+    pub average_score: u8,
+    pub threshold: u8,
+    /// The assessments averaged, in the order they were added
+    pub assessments: Vec<ReviewAssessment>,
+    /// Reviewers whose own score fell on the side of `outcome`
+    pub supporting_reviewers: Vec<[u8; 32]>,
+    pub decided_at: i64,
+}
+
+/// An idea and its current review
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Idea {
+    pub id: u64,
+    pub status: IdeaStatus,
+    /// Assessments of the review in progress
+    pub assessments: Vec<ReviewAssessment>,
+    /// Decision of the latest concluded review
+    pub review_decision: Option<ReviewDecision>,
+    pub time_ordering: TimeOrdering,
+    pub last_event_at: i64,
+    /// FSM variant the lifecycle methods check against
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub profile: IdeaFsmProfile,
+    /// When `status` last changed; set by `new` and every lifecycle method.
+    /// `None` for ideas stored before the field existed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub status_changed_at: Option<i64>,
+    /// Same identifier as a grant's `mesh_group_id`; the default recipient of fast grants
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub author: Option<[u8; 32]>,
+    /// Latest grant spawned by `spawn_grant`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fast_grant_id: Option<u64>,
+}
+
+impl Idea {
+    /// A Draft idea
+    pub fn new(id: u64, created_at: i64) -> Result<Self, FsmError> {
+        check_construction_time(created_at)?;
+        Ok(Self {
+            id,
+            status: IdeaStatus::Draft,
+            assessments: Vec::new(),
+            review_decision: None,
+            time_ordering: TimeOrdering::default(),
+            last_event_at: created_at,
+            profile: IdeaFsmProfile::default(),
+            status_changed_at: Some(created_at),
+            author: None,
+            fast_grant_id: None,
+        })
+    }
+
+    /// Run the lifecycle under `profile` instead of `Standard`
+    pub fn with_profile(mut self, profile: IdeaFsmProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Record who proposed the idea
+    pub fn with_author(mut self, author: [u8; 32]) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    fn transition(&mut self, target: IdeaStatus, current_time: i64) -> Result<(), FsmError> {
+        self.status.validate_transition_in(self.profile, target)?;
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        self.status = target;
+        self.last_event_at = current_time;
+        self.status_changed_at = Some(current_time);
+        Ok(())
+    }
+
+    /// Move a Draft or Resubmitted idea to UnderReview with no assessments
+    ///
+    /// `InvalidStateTransition` from any other status.
+    pub fn submit_for_review(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(IdeaStatus::UnderReview, current_time)?;
+        self.assessments.clear();
+        Ok(())
+    }
+
+    /// Move the idea to Archived; `InvalidStateTransition` where the profile has no such edge
+    pub fn archive(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(IdeaStatus::Archived, current_time)
+    }
+
+    /// Move a Rejected or Archived idea to Resubmitted
+    ///
+    /// `InvalidStateTransition` from other statuses and always under
+    /// `IdeaFsmProfile::StrictTerminal`.
+    pub fn resubmit(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(IdeaStatus::Resubmitted, current_time)
+    }
+
+    /// Attach an assessment to the review in progress
+    ///
+    /// `InvalidState` unless the idea is UnderReview (so assessments after
+    /// `conclude_review` are refused), once `MAX_ASSESSMENTS` are attached or
+    /// if the reviewer already assessed it; `InvalidInput` for a score above
+    /// `MAX_SCORE`; `TimeRegression` if `assessed_at` breaks `time_ordering`.
+    pub fn add_assessment(&mut self, assessment: ReviewAssessment) -> Result<(), FsmError> {
+        if self.status != IdeaStatus::UnderReview
+            || self.assessments.len() >= MAX_ASSESSMENTS
+            || self
+                .assessments
+                .iter()
+                .any(|a| a.reviewer == assessment.reviewer)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        if assessment.score > MAX_SCORE
+            || assessment
+                .category_scores
+                .iter()
+                .any(|(_, score)| *score > MAX_SCORE)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        self.time_ordering
+            .check(self.last_event_at, assessment.assessed_at)?;
+        self.last_event_at = assessment.assessed_at;
+        self.assessments.push(assessment);
+        Ok(())
+    }
+
+    /// Average assessment score, rounded down; `None` without assessments
+    pub fn average_score(&self) -> Option<u8> {
+        let count = self.assessments.len() as u32;
+        let total: u32 = self.assessments.iter().map(|a| u32::from(a.score)).sum();
+        total.checked_div(count).map(|average| average as u8)
+    }
+
+    /// Approve if the average score is at least `threshold`, else Reject
+    ///
+    /// `InvalidInput` for a threshold above `MAX_SCORE`; `InvalidState`
+    /// without assessments.
+    pub fn review_outcome(&self, threshold: u8) -> Result<ReviewOutcome, FsmError> {
+        if threshold > MAX_SCORE {
+            return Err(FsmError::InvalidInput);
+        }
+        let average = self.average_score().ok_or(FsmError::InvalidState)?;
+        Ok(if average >= threshold {
+            ReviewOutcome::Approve
+        } else {
+            ReviewOutcome::Reject
+        })
+    }
+
+    /// Apply `review_outcome(threshold)`: Approved or Rejected, with a `ReviewDecision`
+    ///
+    /// `InvalidStateTransition` unless UnderReview; otherwise the errors of
+    /// `review_outcome` and `TimeRegression`. Nothing changes on error.
+    pub fn conclude_review(
+        &mut self,
+        threshold: u8,
+        current_time: i64,
+    ) -> Result<ReviewOutcome, FsmError> {
+        if self.status != IdeaStatus::UnderReview {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        let outcome = self.review_outcome(threshold)?;
+        let target = match outcome {
+            ReviewOutcome::Approve => IdeaStatus::Approved,
+            ReviewOutcome::Reject => IdeaStatus::Rejected,
+        };
+        self.status.validate_transition_in(self.profile, target)?;
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        let supporting_reviewers = self
+            .assessments
+            .iter()
+            .filter(|a| (a.score >= threshold) == (outcome == ReviewOutcome::Approve))
+            .map(|a| a.reviewer)
+            .collect();
+        self.review_decision = Some(ReviewDecision {
+            outcome,
+            average_score: self.average_score().unwrap_or_default(),
+            threshold,
+            assessments: std::mem::take(&mut self.assessments),
+            supporting_reviewers,
+            decided_at: current_time,
+        });
+        self.status = target;
+        self.last_event_at = current_time;
+        self.status_changed_at = Some(current_time);
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assessment(reviewer: u8, score: u8, assessed_at: i64) -> ReviewAssessment {
+        ReviewAssessment {
+            reviewer: [reviewer; 32],
+            score,
+            category_scores: vec![("feasibility".to_string(), score)],
+            summary_hash: [0; 32],
+            assessed_at,
+        }
+    }
+
+    fn idea_under_review() -> Idea {
+        let mut idea = Idea::new(1, 0).unwrap();
+        idea.submit_for_review(10).unwrap();
+        idea
+    }
+
+    #[test]
+    fn test_assessments_only_while_under_review() {
+        let mut idea = Idea::new(1, 0).unwrap();
+        assert_eq!(
+            idea.add_assessment(assessment(1, 80, 5)),
+            Err(FsmError::InvalidState)
+        );
+        idea.submit_for_review(10).unwrap();
+        idea.add_assessment(assessment(1, 80, 20)).unwrap();
+        assert_eq!(
+            idea.add_assessment(assessment(1, 60, 20)),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            idea.add_assessment(assessment(2, 101, 20)),
+            Err(FsmError::InvalidInput)
+        );
+        let mut bad_category = assessment(2, 50, 20);
+        bad_category
+            .category_scores
+            .push(("impact".to_string(), 120));
+        assert_eq!(
+            idea.add_assessment(bad_category),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            idea.add_assessment(assessment(2, 50, 15)),
+            Err(FsmError::TimeRegression)
+        );
+        for reviewer in 2..=5 {
+            idea.add_assessment(assessment(reviewer, 50, 30)).unwrap();
+        }
+        assert_eq!(
+            idea.add_assessment(assessment(6, 50, 30)),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(idea.assessments.len(), MAX_ASSESSMENTS);
+    }
+
+    #[test]
+    fn test_review_outcome_uses_average() {
+        let mut idea = idea_under_review();
+        assert_eq!(idea.review_outcome(50), Err(FsmError::InvalidState));
+        idea.add_assessment(assessment(1, 90, 20)).unwrap();
+        idea.add_assessment(assessment(2, 59, 20)).unwrap();
+        assert_eq!(idea.average_score(), Some(74));
+        assert_eq!(idea.review_outcome(74), Ok(ReviewOutcome::Approve));
+        assert_eq!(idea.review_outcome(75), Ok(ReviewOutcome::Reject));
+        assert_eq!(idea.review_outcome(101), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn test_conclude_review_records_decision() {
+        let mut idea = idea_under_review();
+        assert_eq!(idea.status_changed_at, Some(10));
+        idea.add_assessment(assessment(1, 90, 20)).unwrap();
+        idea.add_assessment(assessment(2, 40, 20)).unwrap();
+        idea.add_assessment(assessment(3, 70, 20)).unwrap();
+        assert_eq!(idea.status_changed_at, Some(10));
+        assert_eq!(idea.conclude_review(60, 30), Ok(ReviewOutcome::Approve));
+        assert_eq!(idea.status, IdeaStatus::Approved);
+        assert_eq!(idea.status_changed_at, Some(30));
+        let decision = idea.review_decision.clone().unwrap();
+        assert_eq!(decision.average_score, 66);
+        assert_eq!(decision.assessments.len(), 3);
+        assert_eq!(decision.supporting_reviewers, vec![[1; 32], [3; 32]]);
+        assert_eq!(decision.decided_at, 30);
+        assert!(idea.assessments.is_empty());
+        assert_eq!(
+            idea.add_assessment(assessment(4, 90, 40)),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            idea.conclude_review(60, 40),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn test_conclude_review_rejects() {
+        let mut idea = idea_under_review();
+        assert_eq!(idea.conclude_review(60, 30), Err(FsmError::InvalidState));
+        assert_eq!(idea.status, IdeaStatus::UnderReview);
+        idea.add_assessment(assessment(1, 30, 20)).unwrap();
+        idea.add_assessment(assessment(2, 80, 20)).unwrap();
+        assert_eq!(idea.conclude_review(60, 10), Err(FsmError::TimeRegression));
+        assert_eq!(idea.conclude_review(60, 30), Ok(ReviewOutcome::Reject));
+        assert_eq!(idea.status, IdeaStatus::Rejected);
+        assert_eq!(
+            idea.review_decision.unwrap().supporting_reviewers,
+            vec![[1; 32]]
+        );
+        assert_eq!(
+            Idea::new(2, 0).unwrap().conclude_review(60, 30),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn test_resubmission_depends_on_profile() {
+        let rejected = |profile| {
+            let mut idea = Idea::new(1, 0).unwrap().with_profile(profile);
+            idea.submit_for_review(10).unwrap();
+            idea.add_assessment(assessment(1, 10, 20)).unwrap();
+            idea.conclude_review(60, 30).unwrap();
+            idea
+        };
+        let mut standard = rejected(IdeaFsmProfile::Standard);
+        standard.archive(40).unwrap();
+        standard.resubmit(50).unwrap();
+        standard.submit_for_review(60).unwrap();
+        assert_eq!(standard.status, IdeaStatus::UnderReview);
+
+        let mut strict = rejected(IdeaFsmProfile::StrictTerminal);
+        assert_eq!(strict.resubmit(40), Err(FsmError::InvalidStateTransition));
+        strict.archive(40).unwrap();
+        assert_eq!(strict.resubmit(50), Err(FsmError::InvalidStateTransition));
+        assert_eq!(strict.status, IdeaStatus::Archived);
+        assert_eq!(strict.last_event_at, 40);
+    }
+}
@@ -0,0 +1,686 @@
+//! Idempotency keys for state-mutating operations.
+//!
+//! `*_idempotent` variants of lifecycle methods consult an `IdempotencyLedger`
+//! before applying an operation. A repeated call with the same entity id,
+//! operation and key returns the recorded result without re-applying, so a
+//! retried RPC cannot execute a proposal or disburse a grant twice.
+//!
+//! Each record keeps a `fingerprint` of the call's arguments: reusing a key
+//! with different arguments fails with `FsmError::IdempotencyKeyReused`
+//! rather than returning the other call's result. Only successes and errors
+//! that a retry cannot clear are recorded; `InvalidState`, `TimeRegression`
+//! and `TooFrequentUpdate` may pass later, so those calls run again.
+
+use crate::error::FsmError;
+use crate::grant::{Grant, GrantVote};
+use crate::proposal::Proposal;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+
+/// Client-supplied idempotency key
+pub type IdempotencyKey = [u8; 16];
+
+/// Default number of records kept by `IdempotencyLedger::default`
+pub const DEFAULT_LEDGER_CAPACITY: usize = 1024;
+
+/// One recorded operation outcome
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct IdempotencyRecord {
+    pub entity_id: u64,
+    pub operation: String,
+    pub key: IdempotencyKey,
+    /// `fingerprint` of the arguments the operation ran with
+    pub arguments: u64,
+    pub result: Result<(), FsmError>,
+}
+
+/// Stable 64-bit FNV-1a digest of `arguments`, for `IdempotencyRecord::arguments`
+///
+/// Integers are hashed little-endian, so records persisted on one platform
+/// match calls on another.
+pub fn fingerprint<T: Hash + ?Sized>(arguments: &T) -> u64 {
+    let mut hasher = Fnv1a(0xcbf2_9ce4_8422_2325);
+    arguments.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, value: u16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, value: u128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn write_i16(&mut self, value: i16) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i64(&mut self, value: i64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_i128(&mut self, value: i128) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_isize(&mut self, value: isize) {
+        self.write_i64(value as i64);
+    }
+}
+
+/// Errors a retry may clear, left out of the ledger
+fn is_transient(error: &FsmError) -> bool {
+    matches!(
+        error,
+        FsmError::InvalidState | FsmError::TimeRegression | FsmError::TooFrequentUpdate
+    )
+}
+
+/// Bounded ledger of operation outcomes with LRU eviction
+///
+/// Records are kept in recency order (least recently used first); a lookup hit
+/// refreshes the record. When full, the least recently used record is evicted.
+///
+/// Deserializing checks the capacity as `new` does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "LedgerState")
+)]
+pub struct IdempotencyLedger {
+    capacity: usize,
+    records: VecDeque<IdempotencyRecord>,
+}
+
+/// Persisted form of a ledger, checked on the way in
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct LedgerState {
+    capacity: usize,
+    records: VecDeque<IdempotencyRecord>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<LedgerState> for IdempotencyLedger {
+    type Error = FsmError;
+
+    fn try_from(state: LedgerState) -> Result<Self, FsmError> {
+        let mut ledger = Self::new(state.capacity)?;
+        if state.records.len() > state.capacity {
+            return Err(FsmError::InvalidInput);
+        }
+        ledger.records = state.records;
+        Ok(ledger)
+    }
+}
+
+impl Default for IdempotencyLedger {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_LEDGER_CAPACITY,
+            records: VecDeque::new(),
+        }
+    }
+}
+
+impl IdempotencyLedger {
+    /// Create a ledger holding at most `capacity` records (must be > 0)
+    pub fn new(capacity: usize) -> Result<Self, FsmError> {
+        if capacity == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            capacity,
+            records: VecDeque::new(),
+        })
+    }
+
+    /// Maximum number of records
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of records currently held
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the ledger holds no records
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Recorded result for `(entity_id, operation, key)`, refreshing its recency
+    ///
+    /// `IdempotencyKeyReused` if it was recorded with other `arguments`.
+    pub fn lookup(
+        &mut self,
+        entity_id: u64,
+        operation: &str,
+        key: &IdempotencyKey,
+        arguments: u64,
+    ) -> Option<Result<(), FsmError>> {
+        let index = self.records.iter().position(|record| {
+            record.entity_id == entity_id && record.operation == operation && &record.key == key
+        })?;
+        let record = self.records.remove(index)?;
+        let result = if record.arguments == arguments {
+            record.result
+        } else {
+            Err(FsmError::IdempotencyKeyReused)
+        };
+        self.records.push_back(record);
+        Some(result)
+    }
+
+    /// Record a result, evicting the least recently used record when full
+    pub fn record(
+        &mut self,
+        entity_id: u64,
+        operation: &str,
+        key: IdempotencyKey,
+        arguments: u64,
+        result: Result<(), FsmError>,
+    ) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(IdempotencyRecord {
+            entity_id,
+            operation: operation.to_string(),
+            key,
+            arguments,
+            result,
+        });
+    }
+
+    /// Return the recorded result, or run `apply` and record its result
+    ///
+    /// `arguments` is the `fingerprint` of the call. Transient errors (see the
+    /// module docs) are returned without being recorded.
+    pub fn run(
+        &mut self,
+        entity_id: u64,
+        operation: &str,
+        key: IdempotencyKey,
+        arguments: u64,
+        apply: impl FnOnce() -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        if let Some(result) = self.lookup(entity_id, operation, &key, arguments) {
+            return result;
+        }
+        let result = apply();
+        if result
+            .as_ref()
+            .err()
+            .is_none_or(|error| !is_transient(error))
+        {
+            self.record(entity_id, operation, key, arguments, result);
+        }
+        result
+    }
+}
+
+impl<P> Proposal<P> {
+    /// Idempotent `activate_with_time`
+    pub fn activate_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        min_quorum: u64,
+        total_members: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let arguments = fingerprint(&(min_quorum, total_members, current_time));
+        ledger.run(self.id, "proposal.activate", key, arguments, || {
+            self.activate_with_time(min_quorum, total_members, current_time)
+        })
+    }
+
+    /// Idempotent `pass_with_time`
+    pub fn pass_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ledger.run(
+            self.id,
+            "proposal.pass",
+            key,
+            fingerprint(&current_time),
+            || self.pass_with_time(current_time),
+        )
+    }
+
+    /// Idempotent `reject_with_time`
+    pub fn reject_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ledger.run(
+            self.id,
+            "proposal.reject",
+            key,
+            fingerprint(&current_time),
+            || self.reject_with_time(current_time),
+        )
+    }
+
+    /// Idempotent `execute_with_time`
+    pub fn execute_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ledger.run(
+            self.id,
+            "proposal.execute",
+            key,
+            fingerprint(&current_time),
+            || self.execute_with_time(current_time),
+        )
+    }
+
+    /// Idempotent `cancel_with_time`
+    pub fn cancel_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        reason: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let arguments = fingerprint(&(&reason, current_time));
+        ledger.run(self.id, "proposal.cancel", key, arguments, || {
+            self.cancel_with_time(reason, current_time)
+        })
+    }
+
+    /// Idempotent `archive_with_time`
+    pub fn archive_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        ledger.run(
+            self.id,
+            "proposal.archive",
+            key,
+            fingerprint(&current_time),
+            || self.archive_with_time(current_time),
+        )
+    }
+}
+
+impl<P: Ord + Hash> Proposal<P> {
+    /// Idempotent `cast_vote`
+    ///
+    /// A retried vote returns the recorded result instead of failing with
+    /// `InvalidState` because `voter` already voted.
+    pub fn cast_vote_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let arguments = fingerprint(&(&voter, support, weight, current_time));
+        ledger.run(self.id, "proposal.cast_vote", key, arguments, || {
+            self.cast_vote(voter, support, weight, current_time)
+        })
+    }
+}
+
+impl Grant {
+    /// Idempotent `approve`
+    pub fn approve_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+    ) -> Result<(), FsmError> {
+        ledger.run(self.id, "grant.approve", key, fingerprint(&()), || {
+            self.approve()
+        })
+    }
+
+    /// Idempotent `activate`
+    pub fn activate_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+    ) -> Result<(), FsmError> {
+        ledger.run(self.id, "grant.activate", key, fingerprint(&()), || {
+            self.activate()
+        })
+    }
+
+    /// Idempotent `disburse`
+    pub fn disburse_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        amount: u64,
+    ) -> Result<(), FsmError> {
+        ledger.run(self.id, "grant.disburse", key, fingerprint(&amount), || {
+            self.disburse(amount)
+        })
+    }
+
+    /// Idempotent `cast_vote`; a retried vote returns the recorded result
+    pub fn cast_vote_idempotent(
+        &mut self,
+        key: IdempotencyKey,
+        ledger: &mut IdempotencyLedger,
+        vote: GrantVote,
+    ) -> Result<(), FsmError> {
+        ledger.run(self.id, "grant.cast_vote", key, fingerprint(&vote), || {
+            self.cast_vote(vote)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantType};
+    use crate::grant::{GrantStatus, VoteType, VoterType};
+    use crate::proposal::ProposalStatus;
+
+    fn create_active_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal
+    }
+
+    fn create_passed_proposal() -> Proposal<u8> {
+        let mut proposal = create_active_proposal();
+        proposal.pass_with_time(10).unwrap();
+        proposal
+    }
+
+    fn create_active_grant() -> Grant {
+        let mut grant = Grant::new(
+            7,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1000,
+            0,
+            0,
+        )
+        .unwrap();
+        grant.approve().unwrap();
+        grant.activate().unwrap();
+        grant
+    }
+
+    #[test]
+    fn test_retried_execute_keeps_executed_at() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut proposal = create_passed_proposal();
+        let key = [1u8; 16];
+
+        proposal.execute_idempotent(key, &mut ledger, 100).unwrap();
+        assert_eq!(proposal.executed_at, Some(100));
+
+        // Retry after a lost response: same result, nothing re-applied
+        proposal.execute_idempotent(key, &mut ledger, 100).unwrap();
+        assert_eq!(proposal.executed_at, Some(100));
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(ledger.len(), 1);
+
+        // A fresh key runs the operation and gets the real outcome
+        assert_eq!(
+            proposal.execute_idempotent([2u8; 16], &mut ledger, 300),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+
+    #[test]
+    fn test_retried_failure_returns_recorded_error() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut proposal = create_passed_proposal();
+        proposal.status = ProposalStatus::Active;
+        let key = [3u8; 16];
+
+        assert_eq!(
+            proposal.execute_idempotent(key, &mut ledger, 100),
+            Err(FsmError::InvalidStateTransition)
+        );
+        proposal.status = ProposalStatus::Passed;
+        assert_eq!(
+            proposal.execute_idempotent(key, &mut ledger, 100),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(proposal.executed_at, None);
+    }
+
+    #[test]
+    fn test_retried_disburse_applies_once() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut grant = create_active_grant();
+        let key = [4u8; 16];
+
+        grant.disburse_idempotent(key, &mut ledger, 400).unwrap();
+        grant.disburse_idempotent(key, &mut ledger, 400).unwrap();
+        assert_eq!(grant.disbursed_amount, 400);
+    }
+
+    #[test]
+    fn test_retried_proposal_vote_counts_once() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut proposal = create_active_proposal();
+        let key = [6u8; 16];
+
+        proposal
+            .cast_vote_idempotent(key, &mut ledger, 2, true, 5, 5)
+            .unwrap();
+        proposal
+            .cast_vote_idempotent(key, &mut ledger, 2, true, 5, 5)
+            .unwrap();
+        assert_eq!((proposal.yes_votes, proposal.votes.len()), (5, 1));
+
+        // Without the key the repeat is a second vote by the same voter
+        assert_eq!(
+            proposal.cast_vote_idempotent([7u8; 16], &mut ledger, 2, true, 5, 6),
+            Err(FsmError::InvalidState)
+        );
+    }
+
+    #[test]
+    fn test_retried_grant_vote_counts_once() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut grant = create_active_grant();
+        grant.status = GrantStatus::Pending;
+        let vote = GrantVote {
+            grant_id: grant.id,
+            voter_id: [9u8; 32],
+            vote_type: VoteType::Approve,
+            weight: 2,
+            voter_type: VoterType::MeshGroupMember,
+            cast_at: 10,
+        };
+        let key = [8u8; 16];
+
+        grant
+            .cast_vote_idempotent(key, &mut ledger, vote.clone())
+            .unwrap();
+        grant
+            .cast_vote_idempotent(key, &mut ledger, vote.clone())
+            .unwrap();
+        assert_eq!(grant.votes.len(), 1);
+        assert_eq!(
+            grant.cast_vote_idempotent([9u8; 16], &mut ledger, vote),
+            Err(FsmError::InvalidState)
+        );
+    }
+
+    #[test]
+    fn test_reused_key_with_other_arguments_rejected() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut grant = create_active_grant();
+        let key = [10u8; 16];
+
+        grant.disburse_idempotent(key, &mut ledger, 400).unwrap();
+        assert_eq!(
+            grant.disburse_idempotent(key, &mut ledger, 500),
+            Err(FsmError::IdempotencyKeyReused)
+        );
+        assert_eq!(grant.disbursed_amount, 400);
+
+        let mut proposal = create_passed_proposal();
+        proposal.execute_idempotent(key, &mut ledger, 100).unwrap();
+        assert_eq!(
+            proposal.execute_idempotent(key, &mut ledger, 200),
+            Err(FsmError::IdempotencyKeyReused)
+        );
+        assert_eq!(proposal.executed_at, Some(100));
+    }
+
+    #[test]
+    fn test_transient_errors_not_recorded() {
+        let mut ledger = IdempotencyLedger::default();
+        let mut proposal = create_active_proposal();
+        proposal.status = ProposalStatus::Draft;
+        let key = [11u8; 16];
+
+        // Not open for voting yet; the retry runs again once it is
+        assert_eq!(
+            proposal.cast_vote_idempotent(key, &mut ledger, 2, true, 5, 5),
+            Err(FsmError::InvalidState)
+        );
+        assert!(ledger.is_empty());
+        proposal.status = ProposalStatus::Active;
+        proposal
+            .cast_vote_idempotent(key, &mut ledger, 2, true, 5, 5)
+            .unwrap();
+        assert_eq!((proposal.yes_votes, ledger.len()), (5, 1));
+    }
+
+    #[test]
+    fn test_keys_scoped_by_entity_and_operation() {
+        let mut ledger = IdempotencyLedger::default();
+        let key = [5u8; 16];
+        ledger.record(1, "proposal.execute", key, 0, Ok(()));
+
+        assert_eq!(ledger.lookup(1, "proposal.execute", &key, 0), Some(Ok(())));
+        assert_eq!(ledger.lookup(2, "proposal.execute", &key, 0), None);
+        assert_eq!(ledger.lookup(1, "proposal.cancel", &key, 0), None);
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        // Recorded fingerprints must not change between builds or platforms
+        assert_eq!(fingerprint(&()), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fingerprint(&1u64), 0x89cd_3129_1d2a_efa4);
+        assert_ne!(fingerprint(&(1u64, 2i64)), fingerprint(&(2u64, 1i64)));
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let mut ledger = IdempotencyLedger::new(2).unwrap();
+        ledger.record(1, "op", [1u8; 16], 0, Ok(()));
+        ledger.record(2, "op", [2u8; 16], 0, Ok(()));
+
+        // Touch entity 1 so entity 2 becomes least recently used
+        assert!(ledger.lookup(1, "op", &[1u8; 16], 0).is_some());
+        ledger.record(3, "op", [3u8; 16], 0, Err(FsmError::Overflow));
+
+        assert_eq!(ledger.len(), 2);
+        assert!(ledger.lookup(2, "op", &[2u8; 16], 0).is_none());
+        assert!(ledger.lookup(1, "op", &[1u8; 16], 0).is_some());
+        assert_eq!(
+            ledger.lookup(3, "op", &[3u8; 16], 0),
+            Some(Err(FsmError::Overflow))
+        );
+    }
+
+    #[test]
+    fn test_zero_capacity_rejected() {
+        assert_eq!(IdempotencyLedger::new(0), Err(FsmError::InvalidInput));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ledger_serde_roundtrip() {
+        let mut ledger = IdempotencyLedger::new(4).unwrap();
+        ledger.record(1, "proposal.execute", [1u8; 16], 7, Ok(()));
+        ledger.record(2, "grant.disburse", [2u8; 16], 8, Err(FsmError::Overflow));
+
+        let json = serde_json::to_string(&ledger).unwrap();
+        let mut restored: IdempotencyLedger = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, ledger);
+        assert_eq!(restored.capacity(), 4);
+        assert_eq!(
+            restored.lookup(2, "grant.disburse", &[2u8; 16], 8),
+            Some(Err(FsmError::Overflow))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_ledger_deserialization_checks_capacity() {
+        let empty = r#"{"capacity":0,"records":[]}"#;
+        assert!(serde_json::from_str::<IdempotencyLedger>(empty).is_err());
+
+        let mut ledger = IdempotencyLedger::new(2).unwrap();
+        ledger.record(1, "op", [1u8; 16], 0, Ok(()));
+        ledger.record(2, "op", [2u8; 16], 0, Ok(()));
+        let overfull = serde_json::to_string(&ledger)
+            .unwrap()
+            .replace(r#""capacity":2"#, r#""capacity":1"#);
+        assert!(serde_json::from_str::<IdempotencyLedger>(&overfull).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Running an `FsmDefinition`.
+//!
+//! An `FsmInstance` holds the current state of one entity in a validated
+//! definition and fires actions against it, evaluating transition guards with
+//! a `GuardContext` (see `guard`). It only tracks the state name; applying the
+//! matching change to a domain entity is up to the caller.
+
+use crate::definition::FsmDefinition;
+use crate::error::FsmError;
+use crate::guard::GuardContext;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FsmInstance<'a> {
+    definition: &'a FsmDefinition,
+    state: String,
+}
+
+impl<'a> FsmInstance<'a> {
+    /// Start at the definition's initial state, or its first state without one
+    ///
+    /// Errors: whatever `FsmDefinition::validate` reports, `InvalidInput` if
+    /// the definition has no states.
+    pub fn new(definition: &'a FsmDefinition) -> Result<Self, FsmError> {
+        definition.validate()?;
+        let state = definition
+            .defaults
+            .as_ref()
+            .and_then(|d| d.initial_state.clone())
+            .or_else(|| definition.states.first().cloned())
+            .ok_or(FsmError::InvalidInput)?;
+        Self::at(definition, &state)
+    }
+
+    /// Resume at `state`; `InvalidInput` if the definition does not declare it
+    pub fn at(definition: &'a FsmDefinition, state: &str) -> Result<Self, FsmError> {
+        if !definition.states.iter().any(|s| s == state) {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            definition,
+            state: state.to_string(),
+        })
+    }
+
+    pub fn state(&self) -> &str {
+        &self.state
+    }
+
+    /// Take the first transition, in definition order, that leaves the current
+    /// state on `action` and whose guard holds; returns the new state
+    ///
+    /// Errors: `InvalidStateTransition` if no transition leaves the current
+    /// state on `action`, `InvalidState` if every such guard is false, and
+    /// `InvalidInput` if a guard cannot be evaluated.
+    pub fn fire(&mut self, action: &str, context: &GuardContext) -> Result<&str, FsmError> {
+        let mut candidates = self
+            .definition
+            .transitions
+            .iter()
+            .filter(|t| t.from == self.state && t.action == action)
+            .peekable();
+        if candidates.peek().is_none() {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        for transition in candidates {
+            let open = match &transition.guard {
+                Some(guard) => context.evaluate(guard)?,
+                None => true,
+            };
+            if open {
+                self.state = transition.to.clone();
+                return Ok(&self.state);
+            }
+        }
+        Err(FsmError::InvalidState)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{FsmDefaults, FsmTransition};
+
+    fn transition(from: &str, to: &str, action: &str, guard: Option<&str>) -> FsmTransition {
+        FsmTransition {
+            from: from.into(),
+            to: to.into(),
+            action: action.into(),
+            guard: guard.map(Into::into),
+            metadata: None,
+        }
+    }
+
+    fn definition() -> FsmDefinition {
+        FsmDefinition {
+            name: None,
+            states: vec!["Open".into(), "Big".into(), "Small".into()],
+            transitions: vec![
+                transition("Open", "Big", "close", Some("amount >= 100")),
+                transition("Open", "Small", "close", Some("amount > 0")),
+            ],
+            defaults: Some(FsmDefaults {
+                initial_state: Some("Open".into()),
+            }),
+            invariants: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn first_open_guard_wins() {
+        let definition = definition();
+        let mut instance = FsmInstance::new(&definition).unwrap();
+        assert_eq!(instance.state(), "Open");
+        let zero = GuardContext::new().with("amount", 0u64);
+        assert_eq!(instance.fire("close", &zero), Err(FsmError::InvalidState));
+        assert_eq!(
+            instance.fire("close", &GuardContext::new()),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            instance.fire("reopen", &zero),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(instance.state(), "Open");
+        let small = GuardContext::new().with("amount", 50u64);
+        assert_eq!(instance.fire("close", &small), Ok("Small"));
+
+        let mut instance = FsmInstance::at(&definition, "Open").unwrap();
+        let big = GuardContext::new().with("amount", 100u64);
+        assert_eq!(instance.fire("close", &big), Ok("Big"));
+        assert_eq!(
+            FsmInstance::at(&definition, "Closed"),
+            Err(FsmError::InvalidInput)
+        );
+    }
+}
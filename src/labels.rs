@@ -0,0 +1,190 @@
+//! Stable i18n keys for status enums.
+//!
+//! Keys are `<entity>.status.<variant>` in snake case. They are part of the
+//! public contract: renaming a variant must not change its key.
+
+use crate::enums::{IdeaStatus, MemberStatus, OffchainVoteStatus};
+#[cfg(feature = "governance-meta")]
+use crate::governance::{
+    CommitteeDecisionStatus, GovernanceAnalyticsStatus, GovernanceParticipationStatus,
+    GovernanceVotingStatus, SecurityBoardDecisionStatus, SecurityPolicyStatus,
+};
+use crate::grant::{GrantStatus, VerificationStatus};
+use crate::proposal::{AmendmentStatus, ProposalAnalyticsStatus, ProposalStatus};
+
+macro_rules! label_keys {
+    ($ty:ty, $prefix:literal, { $($variant:ident => $key:literal),* $(,)? }) => {
+        impl $ty {
+            /// Every variant, in declaration order
+            pub const VARIANTS: &'static [Self] = &[$(Self::$variant),*];
+
+            /// Stable i18n key for this status
+            pub fn label_key(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => concat!($prefix, ".", $key),)*
+                }
+            }
+        }
+    };
+}
+
+label_keys!(ProposalStatus, "proposal.status", {
+    Draft => "draft",
+    Active => "active",
+    Passed => "passed",
+    Rejected => "rejected",
+    Executed => "executed",
+    Cancelled => "cancelled",
+    Archived => "archived",
+    Tied => "tied",
+    QuorumFailed => "quorum_failed",
+    Executing => "executing",
+});
+
+label_keys!(AmendmentStatus, "amendment.status", {
+    Proposed => "proposed",
+    Voting => "voting",
+    Accepted => "accepted",
+    Rejected => "rejected",
+    Withdrawn => "withdrawn",
+});
+
+label_keys!(GrantStatus, "grant.status", {
+    Pending => "pending",
+    Approved => "approved",
+    Active => "active",
+    Suspended => "suspended",
+    Completed => "completed",
+    Cancelled => "cancelled",
+    Rejected => "rejected",
+    Expired => "expired",
+    Archived => "archived",
+});
+
+label_keys!(VerificationStatus, "grant.verification_status", {
+    Pending => "pending",
+    Verified => "verified",
+    Rejected => "rejected",
+});
+
+label_keys!(IdeaStatus, "idea.status", {
+    Draft => "draft",
+    UnderReview => "under_review",
+    Approved => "approved",
+    Rejected => "rejected",
+    InProgress => "in_progress",
+    Paused => "paused",
+    Completed => "completed",
+    Executed => "executed",
+    Commercialization => "commercialization",
+    Archived => "archived",
+    Resubmitted => "resubmitted",
+    Voting => "voting",
+    Expired => "expired",
+});
+
+label_keys!(OffchainVoteStatus, "offchain_vote.status", {
+    Pending => "pending",
+    Active => "active",
+    Completed => "completed",
+    Cancelled => "cancelled",
+    Failed => "failed",
+});
+
+label_keys!(MemberStatus, "member.status", {
+    Active => "active",
+    Suspended => "suspended",
+    Banned => "banned",
+    Inactive => "inactive",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(GovernanceVotingStatus, "governance_voting.status", {
+    Open => "open",
+    Closed => "closed",
+    Cancelled => "cancelled",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(GovernanceParticipationStatus, "governance_participation.status", {
+    Active => "active",
+    Paused => "paused",
+    Disabled => "disabled",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(GovernanceAnalyticsStatus, "governance_analytics.status", {
+    Active => "active",
+    Paused => "paused",
+    Disabled => "disabled",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(SecurityPolicyStatus, "security_policy.status", {
+    Active => "active",
+    Inactive => "inactive",
+    Draft => "draft",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(SecurityBoardDecisionStatus, "security_board_decision.status", {
+    Pending => "pending",
+    Approved => "approved",
+    Rejected => "rejected",
+    Deferred => "deferred",
+});
+
+#[cfg(feature = "governance-meta")]
+label_keys!(CommitteeDecisionStatus, "committee_decision.status", {
+    Pending => "pending",
+    Approved => "approved",
+    Rejected => "rejected",
+    Escalated => "escalated",
+});
+
+label_keys!(ProposalAnalyticsStatus, "proposal_analytics.status", {
+    Active => "active",
+    Paused => "paused",
+    Disabled => "disabled",
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_keys() {
+        assert_eq!(ProposalStatus::Active.label_key(), "proposal.status.active");
+        assert_eq!(GrantStatus::Suspended.label_key(), "grant.status.suspended");
+        assert_eq!(
+            AmendmentStatus::Withdrawn.label_key(),
+            "amendment.status.withdrawn"
+        );
+        assert_eq!(
+            IdeaStatus::UnderReview.label_key(),
+            "idea.status.under_review"
+        );
+        #[cfg(feature = "governance-meta")]
+        assert_eq!(
+            SecurityBoardDecisionStatus::Deferred.label_key(),
+            "security_board_decision.status.deferred"
+        );
+        #[cfg(feature = "governance-meta")]
+        assert_eq!(
+            CommitteeDecisionStatus::Escalated.label_key(),
+            "committee_decision.status.escalated"
+        );
+    }
+
+    #[test]
+    fn test_proposal_label_keys_unique() {
+        let keys: Vec<&str> = ProposalStatus::VARIANTS
+            .iter()
+            .map(ProposalStatus::label_key)
+            .collect();
+        let mut unique = keys.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), keys.len());
+    }
+}
@@ -2,22 +2,130 @@
 //!
 //! A standalone, reusable Finite State Machine (FSM) library for declarative,
 //! validation-only governance process checks with auditability.
+//!
+//! ## Cargo features
+//!
+//! The FSM enums, `FsmError` and the proposal and grant lifecycles are always
+//! available. Everything else is behind a feature, all enabled by default:
+//!
+//! | Feature | Enables |
+//! |---|---|
+//! | `serde` | `Serialize`/`Deserialize` on public types, JSON helpers |
+//! | `borsh` | `BorshSerialize`/`BorshDeserialize` on public types |
+//! | `hash` | `audit` (hash-chained trail), `retention::purge` and `transaction`; implies `borsh` |
+//! | `definitions` | `definition`, `conformance`, `instance`, `lint`, `role_matrix` and the `fsm_validate` binary; implies `serde` |
+//! | `governance-meta` | `governance` metadata modules and `grant::review` |
+//! | `compat-v1` | `compat`: `FsmErrorV1` and deprecated 0.1 API wrappers |
+//!
+//! `reporting` and `maintenance` need `governance-meta`, `hash` and `serde`
+//! together; `hashing`
+//! needs `hash` and `serde`.
+//! `scxml` (implies `definitions`) and `tracing` are off by default.
 
+pub mod archive;
+#[cfg(feature = "hash")]
 pub mod audit;
+pub mod calendar;
+#[cfg(feature = "serde")]
+pub mod canonical;
+#[cfg(feature = "compat-v1")]
+pub mod compat;
+#[cfg(feature = "definitions")]
+pub mod conformance;
+#[cfg(feature = "definitions")]
 pub mod definition;
+pub mod duration;
 pub mod enums;
 pub mod error;
+pub mod events;
 pub mod fsm;
+#[cfg(feature = "governance-meta")]
 pub mod governance;
+pub mod governance_params;
 pub mod grant;
+pub mod guard;
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub mod hashing;
+pub mod health;
+pub mod idea;
+pub mod idempotency;
+#[cfg(feature = "definitions")]
+pub mod instance;
+pub mod labels;
+pub mod links;
+#[cfg(feature = "definitions")]
+pub mod lint;
+#[cfg(all(feature = "governance-meta", feature = "hash", feature = "serde"))]
+pub mod maintenance;
+pub mod math;
+pub mod member;
+pub mod meta;
+pub mod metrics;
+#[cfg(feature = "governance-meta")]
+pub mod orchestration;
+#[cfg(feature = "serde")]
+pub mod policy_bundle;
+pub mod prelude;
+pub mod projections;
 pub mod proposal;
+pub mod query;
+pub mod reason;
+pub mod reason_policy;
+#[cfg(all(feature = "governance-meta", feature = "hash", feature = "serde"))]
+pub mod reporting;
+pub mod retention;
+#[cfg(feature = "definitions")]
+pub mod role_matrix;
+#[cfg(feature = "scxml")]
+pub mod scxml;
+pub mod sequence;
+pub mod simulation;
+pub mod summary;
+pub mod tags;
+pub mod telemetry;
+pub mod time_guard;
+#[cfg(feature = "hash")]
+pub mod transaction;
 
-// Re-export key types for easy access
-pub use audit::{AuditEntry, AuditTrail};
+// Re-export key types for easy access; `prelude` has the common set in one glob
+#[cfg(feature = "hash")]
+pub use audit::{
+    AuditActor, AuditEntry, AuditSubscriber, AuditTrail, ChannelSubscriber, ChronologyViolation,
+    RedactionEntry, SubscriberError,
+};
+#[cfg(feature = "definitions")]
 pub use definition::{
-    FsmDefaults, FsmDefinition, FsmInvariant, FsmTransition, FsmTransitionMetadata,
-    FsmTransitionRef,
+    DefinitionFailure, DefinitionResult, FsmDefaults, FsmDefinition, FsmInvariant, FsmTransition,
+    FsmTransitionMetadata, FsmTransitionRef, InvariantKind, ValidationReport, validate_many,
+};
+pub use duration::DurationSecs;
+pub use enums::{IdeaFsmProfile, IdeaStatus};
+pub use error::{ErrorClass, FsmError};
+#[cfg(feature = "governance-meta")]
+pub use governance::{
+    QuorumMetadata, SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus,
+    SecurityBoardMemberMetadata,
+};
+pub use governance_params::{
+    GovernanceParams, ParamChanges, ParamRecommendation, RecommendationError, RecommendationSource,
+};
+pub use grant::{
+    Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType, GrantVote,
+    VerificationStatus, VoteType,
 };
-pub use enums::IdeaStatus;
-pub use error::FsmError;
-pub use grant::{Grant, GrantDisbursementType, GrantStatus, GrantVote, VoteType};
+pub use idea::fast_grant::FastGrantPolicy;
+pub use idea::{Idea, ReviewAssessment, ReviewDecision, ReviewOutcome};
+#[cfg(feature = "definitions")]
+pub use lint::{Lint, LintCode, LintSeverity, deny_warnings};
+pub use math::ArithmeticPolicy;
+pub use member::{Member, Obligation, ObligationIndex, Reassignment};
+pub use metrics::MetricsSink;
+#[cfg(feature = "governance-meta")]
+pub use orchestration::{FinalizedRound, GovernanceRound, RoundConfig, TickOutcome};
+pub use proposal::{AmendmentStatus, Proposal, ProposalBuilder, ProposalStatus};
+pub use reason::{ReasonCode, SuspensionCode};
+pub use reason_policy::{ReasonPolicy, ReasonRequirement, ReasonTransition};
+#[cfg(feature = "definitions")]
+pub use role_matrix::{MAX_ROLE_NAME_LENGTH, MatrixRow};
+pub use sequence::SequenceGenerator;
+pub use time_guard::{EventTime, SimClock, TimeOrdering};
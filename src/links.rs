@@ -0,0 +1,325 @@
+//! Cross-entity references checked when they are written.
+//!
+//! `Proposal::set_idea_link` and `Grant::set_source_proposal` consult a
+//! `LinkValidator` before storing an id, so a dangling link fails with
+//! `FsmError::BrokenReference` at the point of mutation instead of surfacing
+//! later. `InMemoryLinks` is a validator over known ids and a
+//! `VersionedRegistry`. `Grant::from_treasury_proposal` hands an executed
+//! treasury withdrawal over to a new grant linked back to it.
+
+use crate::enums::EntityKind;
+use crate::error::FsmError;
+use crate::grant::lifecycle::EntityId;
+use crate::grant::{Grant, GrantCategory, GrantDisbursementType, GrantType};
+use crate::proposal::registry::VersionedRegistry;
+use crate::proposal::{Proposal, ProposalStatus, TreasuryProposalType};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Lookup of the entities a link may point at
+pub trait LinkValidator {
+    fn idea_exists(&self, id: u64) -> bool;
+    /// Current status, `None` if there is no such proposal
+    fn proposal_status(&self, id: u64) -> Option<ProposalStatus>;
+    fn grant_exists(&self, id: u64) -> bool;
+}
+
+/// `LinkValidator` over ids held in memory
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InMemoryLinks {
+    ideas: BTreeSet<u64>,
+    proposals: BTreeMap<u64, ProposalStatus>,
+    grants: BTreeSet<u64>,
+}
+
+impl InMemoryLinks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Live proposals of `registry`; purged ones do not resolve
+    pub fn from_registry<P: Clone>(registry: &VersionedRegistry<P>) -> Self {
+        let mut links = Self::new();
+        for entry in registry.iter() {
+            links.add_proposal(entry.proposal.id, entry.proposal.status.clone());
+        }
+        links
+    }
+
+    pub fn add_idea(&mut self, id: u64) {
+        self.ideas.insert(id);
+    }
+
+    /// Add or update a proposal's status
+    pub fn add_proposal(&mut self, id: u64, status: ProposalStatus) {
+        self.proposals.insert(id, status);
+    }
+
+    pub fn add_grant(&mut self, id: u64) {
+        self.grants.insert(id);
+    }
+}
+
+impl LinkValidator for InMemoryLinks {
+    fn idea_exists(&self, id: u64) -> bool {
+        self.ideas.contains(&id)
+    }
+
+    fn proposal_status(&self, id: u64) -> Option<ProposalStatus> {
+        self.proposals.get(&id).cloned()
+    }
+
+    fn grant_exists(&self, id: u64) -> bool {
+        self.grants.contains(&id)
+    }
+}
+
+impl<P> Proposal<P> {
+    /// Set `idea_id` after checking the idea exists
+    ///
+    /// `BrokenReference` naming the idea if `validator` does not know it.
+    pub fn set_idea_link(
+        &mut self,
+        validator: &impl LinkValidator,
+        idea_id: u64,
+    ) -> Result<(), FsmError> {
+        if !validator.idea_exists(idea_id) {
+            return Err(FsmError::BrokenReference {
+                entity: EntityKind::Idea,
+                id: idea_id,
+            });
+        }
+        self.idea_id = Some(idea_id);
+        Ok(())
+    }
+}
+
+impl Grant {
+    /// Set `source_proposal_id` after checking the proposal was executed
+    ///
+    /// `BrokenReference` naming the proposal if `validator` does not know it;
+    /// `InvalidState` if it exists but is not Executed.
+    pub fn set_source_proposal(
+        &mut self,
+        validator: &impl LinkValidator,
+        proposal_id: u64,
+    ) -> Result<(), FsmError> {
+        match validator.proposal_status(proposal_id) {
+            None => Err(FsmError::BrokenReference {
+                entity: EntityKind::Proposal,
+                id: proposal_id,
+            }),
+            Some(ProposalStatus::Executed) => {
+                self.source_proposal_id = Some(proposal_id);
+                Ok(())
+            }
+            Some(_) => Err(FsmError::InvalidState),
+        }
+    }
+
+    /// Pending grant funding the treasury withdrawal of an executed `proposal`
+    ///
+    /// The grant takes its amount from the withdrawal and its idea from
+    /// `proposal.idea_id`, and is linked back with `set_source_proposal`.
+    /// `InvalidInput` unless the proposal carries a withdrawal with an amount
+    /// and an idea link; otherwise the errors of `Grant::new` and
+    /// `set_source_proposal`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_treasury_proposal<P>(
+        id: u64,
+        proposal: &Proposal<P>,
+        validator: &impl LinkValidator,
+        mesh_group_id: EntityId,
+        category: GrantCategory,
+        grant_type: GrantType,
+        disbursement_type: GrantDisbursementType,
+        created_at: i64,
+    ) -> Result<Self, FsmError> {
+        let amount = proposal
+            .treasury_operation
+            .as_ref()
+            .filter(|op| op.operation_type == TreasuryProposalType::Withdrawal)
+            .and_then(|op| op.amount)
+            .ok_or(FsmError::InvalidInput)?;
+        let idea_id = proposal.idea_id.ok_or(FsmError::InvalidInput)?;
+        let mut grant = Grant::new(
+            id,
+            idea_id,
+            mesh_group_id,
+            category,
+            grant_type,
+            disbursement_type,
+            amount,
+            0,
+            created_at,
+        )?;
+        grant.set_source_proposal(validator, proposal.id)?;
+        Ok(grant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::TreasuryOperationData;
+
+    fn proposal(id: u64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .build(0)
+        .unwrap()
+    }
+
+    fn grant() -> Grant {
+        Grant::new(
+            1,
+            7,
+            [0; 32],
+            GrantCategory::Development,
+            GrantType::Initial,
+            GrantDisbursementType::Escrow,
+            1_000,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn links() -> InMemoryLinks {
+        let mut registry = VersionedRegistry::new();
+        let mut executed = proposal(1);
+        executed.activate_with_time(1, 10, 0).unwrap();
+        executed.pass_with_time(10).unwrap();
+        executed.execute_with_time(20).unwrap();
+        registry.insert(executed).unwrap();
+        registry.insert(proposal(2)).unwrap();
+        let mut links = InMemoryLinks::from_registry(&registry);
+        links.add_idea(7);
+        links.add_grant(1);
+        links
+    }
+
+    #[test]
+    fn test_idea_link() {
+        let links = links();
+        let mut proposal = proposal(3);
+        proposal.set_idea_link(&links, 7).unwrap();
+        assert_eq!(proposal.idea_id, Some(7));
+        assert_eq!(
+            proposal.set_idea_link(&links, 8),
+            Err(FsmError::BrokenReference {
+                entity: EntityKind::Idea,
+                id: 8
+            })
+        );
+        assert_eq!(proposal.idea_id, Some(7));
+    }
+
+    #[test]
+    fn test_source_proposal_must_exist() {
+        let links = links();
+        let mut grant = grant();
+        assert_eq!(
+            grant.set_source_proposal(&links, 99),
+            Err(FsmError::BrokenReference {
+                entity: EntityKind::Proposal,
+                id: 99
+            })
+        );
+        assert_eq!(grant.source_proposal_id, None);
+    }
+
+    #[test]
+    fn test_source_proposal_must_be_executed() {
+        let links = links();
+        let mut grant = grant();
+        assert_eq!(
+            grant.set_source_proposal(&links, 2),
+            Err(FsmError::InvalidState)
+        );
+        grant.set_source_proposal(&links, 1).unwrap();
+        assert_eq!(grant.source_proposal_id, Some(1));
+    }
+
+    #[test]
+    fn test_in_memory_links() {
+        let mut links = links();
+        assert!(links.grant_exists(1));
+        assert!(!links.grant_exists(2));
+        assert_eq!(links.proposal_status(2), Some(ProposalStatus::Draft));
+        links.add_proposal(2, ProposalStatus::Active);
+        assert_eq!(links.proposal_status(2), Some(ProposalStatus::Active));
+        assert!(!links.idea_exists(1));
+    }
+
+    #[test]
+    fn test_broken_reference_display() {
+        let error = FsmError::BrokenReference {
+            entity: EntityKind::Idea,
+            id: 8,
+        };
+        assert_eq!(error.to_string(), "Broken reference: Idea 8 does not exist");
+    }
+
+    #[test]
+    fn test_grant_from_treasury_proposal() {
+        let withdrawal = |amount| {
+            TreasuryOperationData::new(
+                TreasuryProposalType::Withdrawal,
+                amount,
+                None,
+                None,
+                None,
+                None,
+                "Fund".to_string(),
+            )
+        };
+        let mut links = links();
+        let mut proposal = Proposal::builder(
+            4,
+            "Fund".to_string(),
+            "Description".to_string(),
+            "treasury".to_string(),
+            1u8,
+        )
+        .treasury_operation(withdrawal(Some(500)))
+        .build(0)
+        .unwrap();
+        let from = |proposal: &Proposal<u8>, links: &InMemoryLinks| {
+            Grant::from_treasury_proposal(
+                9,
+                proposal,
+                links,
+                [2u8; 32],
+                GrantCategory::Development,
+                GrantType::Core,
+                GrantDisbursementType::Standard,
+                30,
+            )
+        };
+        assert_eq!(from(&proposal, &links), Err(FsmError::InvalidInput));
+        proposal.set_idea_link(&links, 7).unwrap();
+        assert_eq!(
+            from(&proposal, &links),
+            Err(FsmError::BrokenReference {
+                entity: EntityKind::Proposal,
+                id: 4
+            })
+        );
+        links.add_proposal(4, ProposalStatus::Active);
+        assert_eq!(from(&proposal, &links), Err(FsmError::InvalidState));
+        links.add_proposal(4, ProposalStatus::Executed);
+        let grant = from(&proposal, &links).unwrap();
+        assert_eq!(grant.total_amount, 500);
+        assert_eq!(grant.idea_id, 7);
+        assert_eq!(grant.source_proposal_id, Some(4));
+        proposal.treasury_operation = Some(withdrawal(None));
+        assert_eq!(from(&proposal, &links), Err(FsmError::InvalidInput));
+    }
+}
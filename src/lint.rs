@@ -0,0 +1,423 @@
+//! Advisory checks for FSM definitions.
+//!
+//! `FsmDefinition::lint` reports style and consistency problems that
+//! `validate` accepts. Linting never affects validation; use `deny_warnings`
+//! to fail strict CI runs on warnings.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::definition::FsmDefinition;
+
+/// How serious a lint is
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Info,
+    Warning,
+}
+
+/// What a lint found
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCode {
+    /// State name written in a different case style from most states
+    MixedStateCase,
+    /// Transition without `metadata.description`
+    MissingDescription,
+    /// Guard variable that appears in no other guard and names nothing in the definition
+    IsolatedGuardVariable,
+    /// Invariant that holds trivially because it names an undeclared state
+    TrivialInvariant,
+}
+
+impl LintCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LintCode::MixedStateCase => "mixed_state_case",
+            LintCode::MissingDescription => "missing_description",
+            LintCode::IsolatedGuardVariable => "isolated_guard_variable",
+            LintCode::TrivialInvariant => "trivial_invariant",
+        }
+    }
+
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            LintCode::IsolatedGuardVariable => LintSeverity::Info,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+/// One finding
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    pub severity: LintSeverity,
+    pub code: LintCode,
+    pub message: String,
+    /// Offending element, e.g. `transitions[2].guard`
+    pub path: String,
+    /// How to resolve it
+    pub suggestion: String,
+}
+
+impl Lint {
+    fn new(code: LintCode, path: String, message: String, suggestion: String) -> Self {
+        Self {
+            severity: code.severity(),
+            code,
+            message,
+            path,
+            suggestion,
+        }
+    }
+}
+
+/// `Err` with the warnings if there are any, otherwise `Ok` with the remaining lints
+pub fn deny_warnings(lints: Vec<Lint>) -> Result<Vec<Lint>, Vec<Lint>> {
+    let (warnings, rest): (Vec<Lint>, Vec<Lint>) = lints
+        .into_iter()
+        .partition(|lint| lint.severity == LintSeverity::Warning);
+    if warnings.is_empty() {
+        Ok(rest)
+    } else {
+        Err(warnings)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum CaseStyle {
+    Pascal,
+    Snake,
+    Screaming,
+    Mixed,
+}
+
+impl CaseStyle {
+    fn of(name: &str) -> Self {
+        let has_upper = name.chars().any(|c| c.is_uppercase());
+        let has_lower = name.chars().any(|c| c.is_lowercase());
+        let starts_upper = name.chars().next().is_some_and(|c| c.is_uppercase());
+        if !has_upper {
+            CaseStyle::Snake
+        } else if !has_lower {
+            CaseStyle::Screaming
+        } else if starts_upper && name.chars().all(|c| c.is_alphanumeric()) {
+            CaseStyle::Pascal
+        } else {
+            CaseStyle::Mixed
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            CaseStyle::Pascal => "PascalCase",
+            CaseStyle::Snake => "snake_case",
+            CaseStyle::Screaming => "SCREAMING_CASE",
+            CaseStyle::Mixed => "mixed case",
+        }
+    }
+}
+
+const GUARD_KEYWORDS: [&str; 6] = ["true", "false", "and", "or", "not", "null"];
+
+fn guard_variables(guard: &str) -> BTreeSet<&str> {
+    guard
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|token| {
+            token
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+                && !GUARD_KEYWORDS.contains(token)
+        })
+        .collect()
+}
+
+impl FsmDefinition {
+    /// Advisory findings in definition order: states, transitions, invariants
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        self.lint_state_case(&mut lints);
+        self.lint_transitions(&mut lints);
+        self.lint_invariants(&mut lints);
+        lints
+    }
+
+    fn lint_state_case(&self, lints: &mut Vec<Lint>) {
+        let styles: Vec<CaseStyle> = self.states.iter().map(|s| CaseStyle::of(s)).collect();
+        let mut counts: BTreeMap<CaseStyle, usize> = BTreeMap::new();
+        for style in &styles {
+            *counts.entry(*style).or_default() += 1;
+        }
+        // Most common style; ties go to the style of the earliest state
+        let Some(dominant) = styles.iter().copied().max_by_key(|style| {
+            let first = styles.iter().position(|s| s == style).unwrap_or(0);
+            (counts[style], std::cmp::Reverse(first))
+        }) else {
+            return;
+        };
+        for (index, (state, style)) in self.states.iter().zip(&styles).enumerate() {
+            if *style != dominant {
+                lints.push(Lint::new(
+                    LintCode::MixedStateCase,
+                    format!("states[{}]", index),
+                    format!(
+                        "state '{}' is {} but most states are {}",
+                        state,
+                        style.describe(),
+                        dominant.describe()
+                    ),
+                    format!("rename '{}' to {}", state, dominant.describe()),
+                ));
+            }
+        }
+    }
+
+    fn lint_transitions(&self, lints: &mut Vec<Lint>) {
+        let mut known: BTreeSet<&str> = self.states.iter().map(|s| s.as_str()).collect();
+        let mut guard_uses: BTreeMap<&str, usize> = BTreeMap::new();
+        for transition in &self.transitions {
+            known.insert(transition.action.as_str());
+            if let Some(metadata) = &transition.metadata {
+                known.extend(metadata.roles.iter().map(|r| r.as_str()));
+            }
+            if let Some(guard) = &transition.guard {
+                for variable in guard_variables(guard) {
+                    *guard_uses.entry(variable).or_default() += 1;
+                }
+            }
+        }
+
+        for (index, transition) in self.transitions.iter().enumerate() {
+            let described = transition
+                .metadata
+                .as_ref()
+                .and_then(|m| m.description.as_ref())
+                .is_some_and(|d| !d.trim().is_empty());
+            if !described {
+                lints.push(Lint::new(
+                    LintCode::MissingDescription,
+                    format!("transitions[{}].metadata.description", index),
+                    format!(
+                        "transition '{}' ({} -> {}) has no description",
+                        transition.action, transition.from, transition.to
+                    ),
+                    "add metadata.description explaining when the transition applies".to_string(),
+                ));
+            }
+            if let Some(guard) = &transition.guard {
+                for variable in guard_variables(guard) {
+                    if guard_uses[variable] == 1 && !known.contains(variable) {
+                        lints.push(Lint::new(
+                            LintCode::IsolatedGuardVariable,
+                            format!("transitions[{}].guard", index),
+                            format!("guard variable '{}' is not referenced elsewhere", variable),
+                            format!("check '{}' for typos", variable),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    fn lint_invariants(&self, lints: &mut Vec<Lint>) {
+        let state_set: BTreeSet<&str> = self.states.iter().map(|s| s.as_str()).collect();
+        for (index, invariant) in self.invariants.iter().enumerate() {
+            let trivial_states: Vec<(String, &str)> = match invariant.kind.as_str() {
+                "terminal_states" | "forbidden_cycles" => invariant
+                    .states
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| (format!("invariants[{}].states[{}]", index, i), s.as_str()))
+                    .collect(),
+                "forbidden_transitions" => invariant
+                    .transitions
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, t)| {
+                        let path = format!("invariants[{}].transitions[{}]", index, i);
+                        [(path.clone(), t.from.as_str()), (path, t.to.as_str())]
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let mut reported = BTreeSet::new();
+            for (path, state) in trivial_states {
+                if !state_set.contains(state) && reported.insert(path.clone()) {
+                    lints.push(Lint::new(
+                        LintCode::TrivialInvariant,
+                        path,
+                        format!(
+                            "{} invariant names undeclared state '{}' and always holds",
+                            invariant.kind, state
+                        ),
+                        format!("declare '{}' or remove it from the invariant", state),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{FsmInvariant, FsmTransition, FsmTransitionMetadata, FsmTransitionRef};
+
+    fn transition(from: &str, to: &str, action: &str, guard: Option<&str>) -> FsmTransition {
+        FsmTransition {
+            from: from.into(),
+            to: to.into(),
+            action: action.into(),
+            guard: guard.map(Into::into),
+            metadata: Some(FsmTransitionMetadata {
+                description: Some(format!("{} the item", action)),
+                roles: vec!["reviewer".into()],
+            }),
+        }
+    }
+
+    fn create_test_definition() -> FsmDefinition {
+        FsmDefinition {
+            name: None,
+            states: vec!["Draft".into(), "Review".into(), "Approved".into()],
+            transitions: vec![
+                transition("Draft", "Review", "submit", Some("quorum_met && reviewer")),
+                transition("Review", "Approved", "approve", Some("!quorum_met")),
+            ],
+            defaults: None,
+            invariants: Vec::new(),
+        }
+    }
+
+    fn codes(lints: &[Lint]) -> Vec<(LintCode, &str)> {
+        lints.iter().map(|l| (l.code, l.path.as_str())).collect()
+    }
+
+    #[test]
+    fn test_clean_definition_has_no_lints() {
+        assert_eq!(create_test_definition().lint(), Vec::new());
+    }
+
+    #[test]
+    fn test_mixed_state_case() {
+        let mut definition = create_test_definition();
+        definition.states.push("in_review".into());
+        definition.states.push("ARCHIVED".into());
+        let lints = definition.lint();
+        assert_eq!(
+            codes(&lints),
+            vec![
+                (LintCode::MixedStateCase, "states[3]"),
+                (LintCode::MixedStateCase, "states[4]")
+            ]
+        );
+        assert_eq!(lints[0].severity, LintSeverity::Warning);
+        assert!(lints[0].message.contains("PascalCase"));
+        assert_eq!(lints[0].suggestion, "rename 'in_review' to PascalCase");
+    }
+
+    #[test]
+    fn test_missing_description_and_isolated_guard() {
+        let mut definition = create_test_definition();
+        definition.transitions[1].metadata = None;
+        definition.transitions[1].guard = Some("votes.yes > 3 and true".into());
+        let lints = definition.lint();
+        assert_eq!(
+            codes(&lints),
+            vec![
+                (LintCode::IsolatedGuardVariable, "transitions[0].guard"),
+                (
+                    LintCode::MissingDescription,
+                    "transitions[1].metadata.description"
+                ),
+                (LintCode::IsolatedGuardVariable, "transitions[1].guard"),
+            ]
+        );
+        assert_eq!(lints[0].severity, LintSeverity::Info);
+        assert!(lints[2].message.contains("votes.yes"));
+    }
+
+    #[test]
+    fn test_trivial_invariants() {
+        let mut definition = create_test_definition();
+        definition.invariants = vec![
+            FsmInvariant {
+                kind: "forbidden_transitions".into(),
+                states: Vec::new(),
+                transitions: vec![
+                    FsmTransitionRef {
+                        from: "Approved".into(),
+                        to: "Draft".into(),
+                    },
+                    FsmTransitionRef {
+                        from: "Archived".into(),
+                        to: "Gone".into(),
+                    },
+                ],
+                description: None,
+            },
+            FsmInvariant {
+                kind: "terminal_states".into(),
+                states: vec!["Approved".into(), "Archived".into()],
+                transitions: Vec::new(),
+                description: None,
+            },
+        ];
+        assert_eq!(definition.validate(), Ok(()));
+        assert_eq!(
+            codes(&definition.lint()),
+            vec![
+                (LintCode::TrivialInvariant, "invariants[0].transitions[1]"),
+                (LintCode::TrivialInvariant, "invariants[1].states[1]"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_does_not_change_validation() {
+        let mut definition = create_test_definition();
+        definition.states.push("in_review".into());
+        definition.transitions[0].metadata = None;
+        assert!(!definition.lint().is_empty());
+        assert_eq!(definition.validate(), Ok(()));
+
+        // Invalid definitions are still linted
+        definition.states.clear();
+        assert_eq!(
+            codes(&definition.lint()),
+            vec![(
+                LintCode::MissingDescription,
+                "transitions[0].metadata.description"
+            )]
+        );
+        assert!(definition.validate().is_err());
+    }
+
+    #[test]
+    fn test_deny_warnings() {
+        let mut definition = create_test_definition();
+        definition.transitions[0].guard = Some("isolated".into());
+        // `isolated` and the now single `quorum_met` are infos only
+        let infos = deny_warnings(definition.lint()).unwrap();
+        assert_eq!(infos.len(), 2);
+
+        definition.transitions[0].metadata = None;
+        let warnings = deny_warnings(definition.lint()).unwrap_err();
+        assert_eq!(
+            codes(&warnings),
+            vec![(
+                LintCode::MissingDescription,
+                "transitions[0].metadata.description"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_example_definition_is_lint_clean() {
+        let raw = include_str!("../docs/example_fsm_definition.json");
+        let definition: FsmDefinition = serde_json::from_str(raw).unwrap();
+        assert_eq!(deny_warnings(definition.lint()), Ok(Vec::new()));
+    }
+}
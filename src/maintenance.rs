@@ -0,0 +1,642 @@
+//! Background maintenance: what a scheduler should do next, and doing it.
+//!
+//! `plan` looks at borrowed proposals, grants and votings and lists the
+//! maintenance that is due at `current_time` as typed `MaintenanceTask`s:
+//!
+//! - `AutoTransitionProposal`: an Active proposal whose voting has ended
+//! - `CloseVoting`: an Open voting past its end, or whose proposal is no
+//!   has left Draft and Active
+//! - `ExpireGrant`: a Suspended grant past its `auto_cancel_at`
+//! - `ResumeSuspendedGrant`: a Suspended grant past its `auto_resume_at`
+//! - `ArchiveExpired`: a finished proposal past its `expires_at`
+//! - `PurgeEligible`: archived proposals past the retention period
+//!
+//! Tasks come most urgent first, in that order of kinds, then by how long
+//! they have been due, then by id. Planning reads only and is deterministic:
+//! the same inputs give the same plan.
+//!
+//! `execute` applies a plan in order and reports each task. The first task
+//! that fails stops the run; it is reported with its error and the tasks after
+//! it as `NotRun`, so nothing is applied on top of a state the plan did not
+//! expect. Re-plan and retry after inspecting the failure.
+//!
+//! Given a `JobHeartbeat` in the state, `execute` records the run on it: a
+//! success with the canonical hash of the report when every task applied, a
+//! failure otherwise. `health::job_liveness` then notices when runs stop.
+
+use crate::audit::AuditTrail;
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::governance::{GovernanceVotingMetadata, GovernanceVotingStatus};
+use crate::grant::{FundingAllocation, Grant, GrantStatus};
+use crate::hashing::hash_canonical;
+use crate::health::{JobHeartbeat, JobOutcome};
+use crate::proposal::{ProposalStatus, VersionedRegistry};
+use crate::reason::ReasonCode;
+use crate::retention::{self, Retained, Tombstone};
+use serde::{Deserialize, Serialize};
+
+/// Cancellation reason recorded by `ExpireGrant`
+pub const SUSPENSION_EXPIRED_REASON: &str = "suspension window expired";
+
+/// Read-only view of the state `plan` looks at
+#[derive(Clone, Copy, Debug)]
+pub struct MaintenanceInputs<'a, P> {
+    pub proposals: &'a VersionedRegistry<P>,
+    pub grants: &'a [Grant],
+    pub votings: &'a [GovernanceVotingMetadata],
+    /// Retention period of archived proposals; `None` or negative plans no purge
+    pub retention: Option<DurationSecs>,
+}
+
+/// Mutable state `execute` applies a plan to
+#[derive(Debug)]
+pub struct MaintenanceState<'a, P> {
+    pub proposals: &'a mut VersionedRegistry<P>,
+    pub grants: &'a mut [Grant],
+    pub votings: &'a mut [GovernanceVotingMetadata],
+    /// Receives one purge record per purged proposal
+    pub trail: &'a mut AuditTrail,
+    /// Heartbeat of the job running maintenance, updated by every `execute`
+    pub heartbeat: Option<&'a mut JobHeartbeat>,
+}
+
+/// One maintenance step
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaintenanceTask {
+    /// Settle an Active proposal whose voting has ended (`auto_transition_detailed`)
+    AutoTransitionProposal { id: u64 },
+    /// Close an Open voting
+    CloseVoting { voting_id: u64 },
+    /// Cancel a Suspended grant with `ReasonCode::Expired`
+    ExpireGrant { id: u64 },
+    /// Resume a Suspended grant
+    ResumeSuspendedGrant { id: u64 },
+    /// Archive a finished proposal (`check_and_auto_archive`)
+    ArchiveExpired { id: u64 },
+    /// Purge archived proposals (`retention::purge`), ids ascending
+    ///
+    /// `retention` is re-checked when the task runs.
+    PurgeEligible {
+        ids: Vec<u64>,
+        retention: DurationSecs,
+    },
+}
+
+impl MaintenanceTask {
+    /// Rank of the task kind; lower runs first
+    fn rank(&self) -> u8 {
+        match self {
+            MaintenanceTask::AutoTransitionProposal { .. } => 0,
+            MaintenanceTask::CloseVoting { .. } => 1,
+            MaintenanceTask::ExpireGrant { .. } => 2,
+            MaintenanceTask::ResumeSuspendedGrant { .. } => 3,
+            MaintenanceTask::ArchiveExpired { .. } => 4,
+            MaintenanceTask::PurgeEligible { .. } => 5,
+        }
+    }
+
+    /// Id the task acts on; the first id for `PurgeEligible`
+    fn id(&self) -> u64 {
+        match self {
+            MaintenanceTask::AutoTransitionProposal { id }
+            | MaintenanceTask::ExpireGrant { id }
+            | MaintenanceTask::ResumeSuspendedGrant { id }
+            | MaintenanceTask::ArchiveExpired { id } => *id,
+            MaintenanceTask::CloseVoting { voting_id } => *voting_id,
+            MaintenanceTask::PurgeEligible { ids, .. } => ids.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+/// What happened to one task
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskOutcome {
+    /// Applied
+    Applied,
+    /// Grant cancelled; funding to return to the pools
+    GrantCancelled { released: Vec<FundingAllocation> },
+    /// Proposals purged
+    Purged { tombstones: Vec<Tombstone> },
+    /// Failed; the run stopped here
+    Failed(FsmError),
+    /// Not attempted because an earlier task failed
+    NotRun,
+}
+
+/// One task with its outcome
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub task: MaintenanceTask,
+    pub outcome: TaskOutcome,
+}
+
+/// Outcome of `execute`, one result per planned task in plan order
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaintenanceReport {
+    pub results: Vec<TaskResult>,
+}
+
+impl MaintenanceReport {
+    /// Whether every task was applied
+    pub fn is_complete(&self) -> bool {
+        self.failure().is_none()
+    }
+
+    /// The task that stopped the run, with its error
+    pub fn failure(&self) -> Option<(&MaintenanceTask, FsmError)> {
+        self.results.iter().find_map(|result| match result.outcome {
+            TaskOutcome::Failed(error) => Some((&result.task, error)),
+            _ => None,
+        })
+    }
+}
+
+/// Maintenance due at `current_time`, most urgent first (see the module docs)
+///
+/// Entities whose deadline does not fit an `i64` are never due.
+pub fn plan<P: Clone>(inputs: MaintenanceInputs<'_, P>, current_time: i64) -> Vec<MaintenanceTask> {
+    // (task, due since)
+    let mut due: Vec<(MaintenanceTask, i64)> = Vec::new();
+    for entry in inputs.proposals.iter() {
+        let proposal = &entry.proposal;
+        if proposal.status == ProposalStatus::Active
+            && let Ok(ends_at) = proposal.voting_ends_at()
+            && current_time >= ends_at
+        {
+            due.push((
+                MaintenanceTask::AutoTransitionProposal { id: proposal.id },
+                ends_at,
+            ));
+        }
+        if let Some(expires_at) = proposal.expires_at
+            && current_time >= expires_at
+            && matches!(
+                proposal.status,
+                ProposalStatus::Executed
+                    | ProposalStatus::Rejected
+                    | ProposalStatus::QuorumFailed
+                    | ProposalStatus::Cancelled
+            )
+        {
+            due.push((
+                MaintenanceTask::ArchiveExpired { id: proposal.id },
+                expires_at,
+            ));
+        }
+    }
+    for voting in inputs
+        .votings
+        .iter()
+        .filter(|voting| voting.status == GovernanceVotingStatus::Open)
+    {
+        let ended = voting
+            .voting_ends_at
+            .filter(|&ends_at| current_time >= ends_at);
+        let settled = inputs
+            .proposals
+            .get(voting.proposal_id)
+            .is_some_and(|entry| {
+                !matches!(
+                    entry.proposal.status,
+                    ProposalStatus::Draft | ProposalStatus::Active
+                )
+            });
+        if ended.is_some() || settled {
+            due.push((
+                MaintenanceTask::CloseVoting {
+                    voting_id: voting.voting_id,
+                },
+                ended.unwrap_or(current_time),
+            ));
+        }
+    }
+    for grant in inputs
+        .grants
+        .iter()
+        .filter(|grant| grant.status == GrantStatus::Suspended)
+    {
+        let deadlines = [
+            grant
+                .auto_resume_at
+                .map(|at| (MaintenanceTask::ResumeSuspendedGrant { id: grant.id }, at)),
+            grant
+                .auto_cancel_at
+                .map(|at| (MaintenanceTask::ExpireGrant { id: grant.id }, at)),
+        ];
+        // The earlier deadline wins; a tie resumes
+        if let Some((task, at)) = deadlines
+            .into_iter()
+            .flatten()
+            .filter(|(_, at)| current_time >= *at)
+            .min_by_key(|(_, at)| *at)
+        {
+            due.push((task, at));
+        }
+    }
+    if let Some(retention) = inputs.retention
+        && retention >= DurationSecs::ZERO
+    {
+        // As `retention::purge_eligible`, skipping deadlines that overflow
+        let eligible: Vec<u64> = inputs
+            .proposals
+            .iter()
+            .filter(|entry| {
+                Retained::archived_at(&entry.proposal).is_some_and(|archived_at| {
+                    archived_at
+                        .checked_add(retention.as_secs())
+                        .is_some_and(|expires| current_time > expires)
+                })
+            })
+            .map(|entry| entry.proposal.id)
+            .collect();
+        if !eligible.is_empty() {
+            due.push((
+                MaintenanceTask::PurgeEligible {
+                    ids: eligible,
+                    retention,
+                },
+                current_time,
+            ));
+        }
+    }
+    due.sort_by_key(|(task, since)| (task.rank(), *since, task.id()));
+    due.into_iter().map(|(task, _)| task).collect()
+}
+
+/// Apply `plan` in order, stopping at the first task that fails
+///
+/// A task whose entity is missing is `InvalidInput`; one that is no longer
+/// due fails with the error of the lifecycle step, or `InvalidState` if the
+/// step did nothing.
+pub fn execute<P: Clone + Serialize>(
+    plan: &[MaintenanceTask],
+    state: &mut MaintenanceState<'_, P>,
+    current_time: i64,
+) -> MaintenanceReport {
+    let mut results = Vec::with_capacity(plan.len());
+    let mut failed = false;
+    for task in plan {
+        let outcome = if failed {
+            TaskOutcome::NotRun
+        } else {
+            apply(task, state, current_time).unwrap_or_else(|error| {
+                failed = true;
+                TaskOutcome::Failed(error)
+            })
+        };
+        results.push(TaskResult {
+            task: task.clone(),
+            outcome,
+        });
+    }
+    let report = MaintenanceReport { results };
+    if let Some(heartbeat) = state.heartbeat.as_deref_mut() {
+        let outcome = if failed {
+            JobOutcome::Failed
+        } else {
+            JobOutcome::Succeeded {
+                report_hash: hash_canonical(&report).ok(),
+            }
+        };
+        heartbeat.record_run(outcome, current_time);
+    }
+    report
+}
+
+fn apply<P: Clone + Serialize>(
+    task: &MaintenanceTask,
+    state: &mut MaintenanceState<'_, P>,
+    current_time: i64,
+) -> Result<TaskOutcome, FsmError> {
+    match task {
+        MaintenanceTask::AutoTransitionProposal { id } => {
+            modify_proposal(state.proposals, *id, |proposal| {
+                if !proposal
+                    .auto_transition_detailed(current_time)?
+                    .transitioned()
+                {
+                    return Err(FsmError::InvalidState);
+                }
+                Ok(())
+            })?;
+        }
+        MaintenanceTask::ArchiveExpired { id } => {
+            modify_proposal(state.proposals, *id, |proposal| {
+                if !proposal.check_and_auto_archive(current_time)? {
+                    return Err(FsmError::InvalidState);
+                }
+                Ok(())
+            })?;
+        }
+        MaintenanceTask::CloseVoting { voting_id } => {
+            state
+                .votings
+                .iter_mut()
+                .find(|voting| voting.voting_id == *voting_id)
+                .ok_or(FsmError::InvalidInput)?
+                .close()?;
+        }
+        MaintenanceTask::ExpireGrant { id } => {
+            let grant = grant_mut(state.grants, *id)?;
+            if grant.auto_cancel_at.is_none_or(|at| current_time < at) {
+                return Err(FsmError::InvalidState);
+            }
+            let released = grant
+                .cancel_with_code(ReasonCode::Expired, SUSPENSION_EXPIRED_REASON.to_string())?;
+            return Ok(TaskOutcome::GrantCancelled { released });
+        }
+        MaintenanceTask::ResumeSuspendedGrant { id } => {
+            let grant = grant_mut(state.grants, *id)?;
+            if grant.auto_resume_at.is_none_or(|at| current_time < at) {
+                return Err(FsmError::InvalidState);
+            }
+            grant.resume()?;
+        }
+        MaintenanceTask::PurgeEligible { ids, retention } => {
+            let tombstones = retention::purge(
+                state.proposals,
+                ids,
+                retention.as_secs(),
+                state.trail,
+                current_time,
+            )?;
+            return Ok(TaskOutcome::Purged { tombstones });
+        }
+    }
+    Ok(TaskOutcome::Applied)
+}
+
+fn modify_proposal<P: Clone>(
+    proposals: &mut VersionedRegistry<P>,
+    id: u64,
+    f: impl FnOnce(&mut crate::proposal::Proposal<P>) -> Result<(), FsmError>,
+) -> Result<u64, FsmError> {
+    let revision = proposals.revision(id).ok_or(FsmError::InvalidInput)?;
+    proposals.modify(id, revision, f)
+}
+
+fn grant_mut(grants: &mut [Grant], id: u64) -> Result<&mut Grant, FsmError> {
+    grants
+        .iter_mut()
+        .find(|grant| grant.id == id)
+        .ok_or(FsmError::InvalidInput)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::governance::GovernanceVotingType;
+    use crate::governance_params::GovernanceParams;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType, SuspensionWindow};
+    use crate::proposal::Proposal;
+    use crate::reason::SuspensionCode;
+    use crate::reason_policy::{ReasonPolicy, ReasonRequirement};
+
+    fn create_proposal(id: u64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap()
+    }
+
+    fn create_suspended_grant(id: u64, window: SuspensionWindow) -> Grant {
+        let mut grant = Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap();
+        grant.approve().unwrap();
+        grant.activate_with_time(10).unwrap();
+        let params = GovernanceParams::new(50, 72, 0, false, 0).unwrap();
+        grant
+            .suspend_with_window(
+                SuspensionCode::ComplianceReview,
+                "KYC".to_string(),
+                window,
+                &params,
+                10,
+            )
+            .unwrap();
+        grant
+    }
+
+    /// Proposal 1 voting until 110 under voting 7, proposal 2 cancelled and
+    /// expiring at 150, proposal 3 archived at 0; grant 1 resuming at 150,
+    /// grant 2 cancelled automatically at 70
+    fn fixture() -> (
+        VersionedRegistry<u8>,
+        Vec<Grant>,
+        Vec<GovernanceVotingMetadata>,
+    ) {
+        let mut voting_proposal = create_proposal(1);
+        voting_proposal.activate_with_time(1, 1, 10).unwrap();
+        let voting = GovernanceVotingMetadata::initialize_for(
+            7,
+            &mut voting_proposal,
+            GovernanceVotingType::SimpleMajority,
+            [0u8; 32],
+            10,
+        )
+        .unwrap();
+        let mut expiring = create_proposal(2);
+        expiring
+            .cancel_with_time("withdrawn".to_string(), 0)
+            .unwrap();
+        expiring.set_expiration(Some(150)).unwrap();
+        let mut archived = create_proposal(3);
+        archived
+            .cancel_with_time("withdrawn".to_string(), 0)
+            .unwrap();
+        archived.archive_with_time(0).unwrap();
+        let mut proposals = VersionedRegistry::new();
+        for proposal in [voting_proposal, expiring, archived] {
+            proposals.insert(proposal).unwrap();
+        }
+        let grants = vec![
+            create_suspended_grant(
+                1,
+                SuspensionWindow {
+                    auto_resume_at: Some(150),
+                    auto_cancel_after: None,
+                },
+            ),
+            create_suspended_grant(
+                2,
+                SuspensionWindow {
+                    auto_resume_at: None,
+                    auto_cancel_after: Some(DurationSecs::from_secs(60)),
+                },
+            ),
+        ];
+        (proposals, grants, vec![voting])
+    }
+
+    fn inputs<'a>(
+        proposals: &'a VersionedRegistry<u8>,
+        grants: &'a [Grant],
+        votings: &'a [GovernanceVotingMetadata],
+    ) -> MaintenanceInputs<'a, u8> {
+        MaintenanceInputs {
+            proposals,
+            grants,
+            votings,
+            retention: Some(DurationSecs::from_secs(100)),
+        }
+    }
+
+    #[test]
+    fn test_plan_orders_tasks_by_urgency() {
+        let (proposals, mut grants, votings) = fixture();
+        assert!(plan(inputs(&proposals, &grants, &votings), 60).is_empty());
+        let expected = vec![
+            MaintenanceTask::AutoTransitionProposal { id: 1 },
+            MaintenanceTask::CloseVoting { voting_id: 7 },
+            MaintenanceTask::ExpireGrant { id: 2 },
+            MaintenanceTask::ResumeSuspendedGrant { id: 1 },
+            MaintenanceTask::ArchiveExpired { id: 2 },
+            MaintenanceTask::PurgeEligible {
+                ids: vec![3],
+                retention: DurationSecs::from_secs(100),
+            },
+        ];
+        assert_eq!(plan(inputs(&proposals, &grants, &votings), 200), expected);
+        grants.reverse();
+        assert_eq!(plan(inputs(&proposals, &grants, &votings), 200), expected);
+        let no_retention = MaintenanceInputs {
+            retention: None,
+            ..inputs(&proposals, &grants, &votings)
+        };
+        assert_eq!(plan(no_retention, 200).len(), 5);
+    }
+
+    #[test]
+    fn test_execute_applies_the_plan() {
+        let (mut proposals, mut grants, mut votings) = fixture();
+        let tasks = plan(inputs(&proposals, &grants, &votings), 200);
+        let mut trail = AuditTrail::new();
+        let mut heartbeat = JobHeartbeat::new("maintenance".to_string());
+        let report = execute(
+            &tasks,
+            &mut MaintenanceState {
+                proposals: &mut proposals,
+                grants: &mut grants,
+                votings: &mut votings,
+                trail: &mut trail,
+                heartbeat: Some(&mut heartbeat),
+            },
+            200,
+        );
+        assert!(report.is_complete());
+        assert_eq!(heartbeat.last_success_at, Some(200));
+        assert_eq!(heartbeat.last_report_hash, hash_canonical(&report).ok());
+        assert_eq!(report.results.len(), tasks.len());
+        assert_eq!(
+            report.results[2].outcome,
+            TaskOutcome::GrantCancelled {
+                released: Vec::new()
+            }
+        );
+        assert_eq!(
+            proposals.get(1).unwrap().proposal.status,
+            ProposalStatus::Tied
+        );
+        assert_eq!(
+            proposals.get(2).unwrap().proposal.status,
+            ProposalStatus::Archived
+        );
+        assert!(proposals.get(3).is_none());
+        assert_eq!(trail.purges().len(), 1);
+        assert_eq!(votings[0].status, GovernanceVotingStatus::Closed);
+        assert_eq!(
+            (grants[0].status, grants[1].status),
+            (GrantStatus::Active, GrantStatus::Cancelled)
+        );
+        assert_eq!(grants[1].cancellation_code, Some(ReasonCode::Expired));
+        assert!(plan(inputs(&proposals, &grants, &votings), 200).is_empty());
+    }
+
+    #[test]
+    fn test_execute_stops_at_first_failure() {
+        let (mut proposals, mut grants, mut votings) = fixture();
+        grants[1].reason_policy = ReasonPolicy::new().with_rule(
+            GrantStatus::Suspended,
+            GrantStatus::Cancelled,
+            ReasonRequirement::Forbidden,
+        );
+        let tasks = plan(inputs(&proposals, &grants, &votings), 200);
+        let mut trail = AuditTrail::new();
+        let mut heartbeat = JobHeartbeat::new("maintenance".to_string());
+        heartbeat.record_run(JobOutcome::Succeeded { report_hash: None }, 100);
+        let report = execute(
+            &tasks,
+            &mut MaintenanceState {
+                proposals: &mut proposals,
+                grants: &mut grants,
+                votings: &mut votings,
+                trail: &mut trail,
+                heartbeat: Some(&mut heartbeat),
+            },
+            200,
+        );
+        assert_eq!(
+            (
+                heartbeat.last_run_at,
+                heartbeat.last_success_at,
+                heartbeat.consecutive_failures
+            ),
+            (Some(200), Some(100), 1)
+        );
+        let (task, error) = report.failure().unwrap();
+        assert_eq!(task, &MaintenanceTask::ExpireGrant { id: 2 });
+        assert!(matches!(error, FsmError::ReasonNotAllowed(_)));
+        let outcomes: Vec<&TaskOutcome> = report.results.iter().map(|r| &r.outcome).collect();
+        assert_eq!(
+            outcomes[..2],
+            [&TaskOutcome::Applied, &TaskOutcome::Applied]
+        );
+        assert!(outcomes[3..].iter().all(|o| **o == TaskOutcome::NotRun));
+        assert_eq!(grants[0].status, GrantStatus::Suspended);
+        assert!(proposals.get(3).is_some());
+        assert!(trail.purges().is_empty());
+    }
+
+    #[test]
+    fn test_stale_task_fails() {
+        let (mut proposals, mut grants, mut votings) = fixture();
+        let mut trail = AuditTrail::new();
+        let report = execute(
+            &[
+                MaintenanceTask::AutoTransitionProposal { id: 1 },
+                MaintenanceTask::ResumeSuspendedGrant { id: 9 },
+            ],
+            &mut MaintenanceState {
+                proposals: &mut proposals,
+                grants: &mut grants,
+                votings: &mut votings,
+                trail: &mut trail,
+                heartbeat: None,
+            },
+            50,
+        );
+        assert_eq!(
+            report.results[0].outcome,
+            TaskOutcome::Failed(FsmError::InvalidState)
+        );
+        assert_eq!(report.results[1].outcome, TaskOutcome::NotRun);
+        assert_eq!(proposals.revision(1), Some(0));
+    }
+}
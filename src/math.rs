@@ -0,0 +1,147 @@
+//! Overflow policy for counters and weights.
+//!
+//! On-chain callers must never fail a vote over an overflowing counter and
+//! prefer to saturate; off-chain callers want the overflow reported so bugs
+//! surface early. `ArithmeticPolicy` names the choice, and its helpers return
+//! a `Computed` value that says whether saturation kicked in. Entities keep
+//! their choice in a `TallyOverflow` field, which converts to and from a policy.
+
+use crate::error::FsmError;
+use crate::proposal::TallyOverflow;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How arithmetic on counters and weights treats overflow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ArithmeticPolicy {
+    /// Fail with `FsmError::Overflow`
+    #[default]
+    Strict,
+    /// Clamp at the bound and mark the result degraded
+    Saturating,
+}
+
+/// Result of a policy-aware operation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Computed {
+    pub value: u64,
+    /// The exact result did not fit and `value` was clamped
+    pub degraded: bool,
+}
+
+impl ArithmeticPolicy {
+    /// `a + b`
+    pub fn add(self, a: u64, b: u64) -> Result<Computed, FsmError> {
+        self.apply(a.checked_add(b))
+    }
+
+    /// `a * b`
+    pub fn mul(self, a: u64, b: u64) -> Result<Computed, FsmError> {
+        self.apply(a.checked_mul(b))
+    }
+
+    /// `a / b`, rounded down; `InvalidInput` for a zero divisor under either policy
+    pub fn div(self, a: u64, b: u64) -> Result<u64, FsmError> {
+        a.checked_div(b).ok_or(FsmError::InvalidInput)
+    }
+
+    fn apply(self, exact: Option<u64>) -> Result<Computed, FsmError> {
+        match (exact, self) {
+            (Some(value), _) => Ok(Computed {
+                value,
+                degraded: false,
+            }),
+            (None, ArithmeticPolicy::Strict) => Err(FsmError::Overflow),
+            (None, ArithmeticPolicy::Saturating) => Ok(Computed {
+                value: u64::MAX,
+                degraded: true,
+            }),
+        }
+    }
+}
+
+impl From<TallyOverflow> for ArithmeticPolicy {
+    fn from(overflow: TallyOverflow) -> Self {
+        match overflow {
+            TallyOverflow::Reject => ArithmeticPolicy::Strict,
+            TallyOverflow::Clamp => ArithmeticPolicy::Saturating,
+        }
+    }
+}
+
+impl From<ArithmeticPolicy> for TallyOverflow {
+    fn from(policy: ArithmeticPolicy) -> Self {
+        match policy {
+            ArithmeticPolicy::Strict => TallyOverflow::Reject,
+            ArithmeticPolicy::Saturating => TallyOverflow::Clamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_reports_overflow() {
+        let strict = ArithmeticPolicy::Strict;
+        assert_eq!(
+            strict.add(u64::MAX - 1, 1),
+            Ok(Computed {
+                value: u64::MAX,
+                degraded: false
+            })
+        );
+        assert_eq!(strict.add(u64::MAX, 1), Err(FsmError::Overflow));
+        assert_eq!(strict.mul(u64::MAX / 2, 3), Err(FsmError::Overflow));
+        assert_eq!(strict.mul(1 << 32, 1 << 31).map(|c| c.value), Ok(1 << 63));
+    }
+
+    #[test]
+    fn test_saturating_clamps_and_flags() {
+        let saturating = ArithmeticPolicy::Saturating;
+        assert_eq!(
+            saturating.add(u64::MAX, 1),
+            Ok(Computed {
+                value: u64::MAX,
+                degraded: true
+            })
+        );
+        assert_eq!(
+            saturating.mul(u64::MAX / 2, 3),
+            Ok(Computed {
+                value: u64::MAX,
+                degraded: true
+            })
+        );
+        assert_eq!(saturating.add(1, 2).map(|c| c.degraded), Ok(false));
+    }
+
+    #[test]
+    fn test_division() {
+        for policy in [ArithmeticPolicy::Strict, ArithmeticPolicy::Saturating] {
+            assert_eq!(policy.div(7, 2), Ok(3));
+            assert_eq!(policy.div(7, 0), Err(FsmError::InvalidInput));
+        }
+    }
+
+    #[test]
+    fn test_tally_overflow_conversions() {
+        assert_eq!(
+            ArithmeticPolicy::from(TallyOverflow::Clamp),
+            ArithmeticPolicy::Saturating
+        );
+        assert_eq!(
+            TallyOverflow::from(ArithmeticPolicy::Strict),
+            TallyOverflow::Reject
+        );
+        assert_eq!(
+            ArithmeticPolicy::from(TallyOverflow::default()),
+            ArithmeticPolicy::default()
+        );
+    }
+}
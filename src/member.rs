@@ -0,0 +1,481 @@
+//! Member records
+//!
+//! A member's standing and the capabilities it holds, as consulted by quorum
+//! and authorization checks. Membership changes themselves are tracked by the
+//! caller.
+//!
+//! `Member::apply_action` changes the status alone. `checked_apply_action`
+//! also consults an `ObligationIndex`, so that a member who is the board's
+//! only chairperson, reviews an undecided grant or authored an Active proposal
+//! cannot leave, be suspended or be banned until each of those duties is
+//! handed over to someone else.
+
+use crate::enums::{CapabilityType, MemberAction, MemberStatus};
+use crate::error::FsmError;
+#[cfg(feature = "governance-meta")]
+use crate::governance::security_board::{SecurityBoardMemberMetadata, SecurityBoardMemberRole};
+#[cfg(feature = "governance-meta")]
+use crate::grant::lifecycle::EntityId;
+#[cfg(feature = "governance-meta")]
+use crate::grant::{Grant, GrantStatus, ReviewAssignments};
+use crate::proposal::{Proposal, ProposalStatus};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A governance member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member<P> {
+    pub id: P,
+    pub status: MemberStatus,
+    pub capabilities: Vec<CapabilityType>,
+}
+
+impl<P> Member<P> {
+    /// An Active member holding `capabilities`
+    pub fn new(id: P, capabilities: Vec<CapabilityType>) -> Self {
+        Self {
+            id,
+            status: MemberStatus::Active,
+            capabilities,
+        }
+    }
+
+    pub fn has_capability(&self, capability: CapabilityType) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Suspended and Banned members may not take part in decisions
+    pub fn is_in_good_standing(&self) -> bool {
+        !matches!(self.status, MemberStatus::Suspended | MemberStatus::Banned)
+    }
+
+    /// Apply `action` to the status, without looking at obligations
+    ///
+    /// | Action | From | To |
+    /// |---|---|---|
+    /// | Join | Inactive | Active |
+    /// | Leave | Active, Suspended | Inactive |
+    /// | Suspend | Active | Suspended |
+    /// | Activate | Suspended | Active |
+    /// | Ban | Active, Suspended, Inactive | Banned |
+    ///
+    /// `InvalidStateTransition` otherwise. See `checked_apply_action`.
+    pub fn apply_action(&mut self, action: MemberAction) -> Result<(), FsmError> {
+        self.status = next_status(self.status, action)?;
+        Ok(())
+    }
+}
+
+fn next_status(status: MemberStatus, action: MemberAction) -> Result<MemberStatus, FsmError> {
+    use MemberStatus::*;
+    match (action, status) {
+        (MemberAction::Join, Inactive) => Ok(Active),
+        (MemberAction::Leave, Active | Suspended) => Ok(Inactive),
+        (MemberAction::Suspend, Active) => Ok(Suspended),
+        (MemberAction::Activate, Suspended) => Ok(Active),
+        (MemberAction::Ban, Active | Suspended | Inactive) => Ok(Banned),
+        _ => Err(FsmError::InvalidStateTransition),
+    }
+}
+
+/// A duty that must be handed over before its holder steps away
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Obligation {
+    /// The only Chairperson of the security board
+    SoleChairperson,
+    /// Assigned reviewer of a grant that is still Pending
+    Reviewer { grant_id: u64 },
+    /// Author of an Active proposal
+    ProposalAuthor { proposal_id: u64 },
+}
+
+/// Hand `obligation` over to `to`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reassignment<P> {
+    pub obligation: Obligation,
+    pub to: P,
+}
+
+/// Outstanding obligations per member
+///
+/// Build it from the records that create obligations (`record_board`,
+/// `record_reviews`, `record_active_proposals`) or `record` them directly.
+/// `checked_apply_action` moves handed-over obligations to their new holder
+/// here; mirroring the handover in those records is up to the caller.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObligationIndex<P> {
+    obligations: BTreeMap<P, BTreeSet<Obligation>>,
+}
+
+impl<P> Default for ObligationIndex<P> {
+    fn default() -> Self {
+        Self {
+            obligations: BTreeMap::new(),
+        }
+    }
+}
+
+impl<P: Ord + Clone> ObligationIndex<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, holder: P, obligation: Obligation) {
+        self.obligations
+            .entry(holder)
+            .or_default()
+            .insert(obligation);
+    }
+
+    /// Obligations held by `member`, sorted
+    pub fn obligations_of(&self, member: &P) -> Vec<Obligation> {
+        self.obligations
+            .get(member)
+            .map(|held| held.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Record the authors of Active proposals
+    pub fn record_active_proposals<'a>(
+        &mut self,
+        proposals: impl IntoIterator<Item = &'a Proposal<P>>,
+    ) where
+        P: 'a,
+    {
+        for proposal in proposals {
+            if proposal.status == ProposalStatus::Active {
+                self.record(
+                    proposal.author.clone(),
+                    Obligation::ProposalAuthor {
+                        proposal_id: proposal.id,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Record the chairperson if the board has exactly one
+    #[cfg(feature = "governance-meta")]
+    pub fn record_board(&mut self, board: &[SecurityBoardMemberMetadata<P>]) {
+        let mut chairs = board
+            .iter()
+            .filter(|member| member.role == SecurityBoardMemberRole::Chairperson);
+        if let (Some(chair), None) = (chairs.next(), chairs.next()) {
+            self.record(chair.member_pubkey.clone(), Obligation::SoleChairperson);
+        }
+    }
+
+    fn remove(&mut self, holder: &P, obligation: &Obligation) {
+        if let Some(held) = self.obligations.get_mut(holder) {
+            held.remove(obligation);
+            if held.is_empty() {
+                self.obligations.remove(holder);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "governance-meta")]
+impl ObligationIndex<EntityId> {
+    /// Record the reviewers assigned to Pending grants
+    pub fn record_reviews(&mut self, reviews: &ReviewAssignments, grants: &[Grant]) {
+        for grant in grants
+            .iter()
+            .filter(|grant| grant.status == GrantStatus::Pending)
+        {
+            for assignment in reviews.reviewers_for(grant.id) {
+                self.record(
+                    assignment.member_id,
+                    Obligation::Reviewer { grant_id: grant.id },
+                );
+            }
+        }
+    }
+}
+
+/// Apply `action` to `member` unless it would leave obligations behind
+///
+/// Leave, Suspend and Ban need a `handover` entry for every obligation the
+/// member holds in `obligations`; Join and Activate take none. Errors, with
+/// nothing changed:
+/// - `InvalidStateTransition` if `action` does not apply to the member's status
+/// - `InvalidState` if an obligation is not handed over
+/// - `InvalidInput` if a handover names an obligation the member does not
+///   hold, repeats one, hands it to the member itself, or comes with Join or
+///   Activate
+///
+/// On success the obligations move to their new holders in `obligations`
+/// and the status changes.
+pub fn checked_apply_action<P: Ord + Clone>(
+    member: &mut Member<P>,
+    action: MemberAction,
+    obligations: &mut ObligationIndex<P>,
+    handover: Vec<Reassignment<P>>,
+) -> Result<(), FsmError> {
+    let status = next_status(member.status, action)?;
+    let held: BTreeSet<Obligation> = obligations.obligations_of(&member.id).into_iter().collect();
+    let steps_away = matches!(
+        action,
+        MemberAction::Leave | MemberAction::Suspend | MemberAction::Ban
+    );
+    if !steps_away && !handover.is_empty() {
+        return Err(FsmError::InvalidInput);
+    }
+    let mut covered = BTreeSet::new();
+    for reassignment in &handover {
+        if !held.contains(&reassignment.obligation)
+            || reassignment.to == member.id
+            || !covered.insert(reassignment.obligation)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+    }
+    if steps_away && covered != held {
+        return Err(FsmError::InvalidState);
+    }
+    for reassignment in handover {
+        obligations.remove(&member.id, &reassignment.obligation);
+        obligations.record(reassignment.to, reassignment.obligation);
+    }
+    member.status = status;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_good_standing() {
+        let mut member = Member::new(1u8, vec![CapabilityType::Vote]);
+        assert!(member.is_in_good_standing());
+        assert!(member.has_capability(CapabilityType::Vote));
+        assert!(!member.has_capability(CapabilityType::Manage));
+        member.status = MemberStatus::Inactive;
+        assert!(member.is_in_good_standing());
+        member.status = MemberStatus::Suspended;
+        assert!(!member.is_in_good_standing());
+        member.status = MemberStatus::Banned;
+        assert!(!member.is_in_good_standing());
+    }
+
+    #[test]
+    fn test_apply_action_transitions() {
+        let mut member = Member::new(1u8, vec![]);
+        assert_eq!(
+            member.apply_action(MemberAction::Join),
+            Err(FsmError::InvalidStateTransition)
+        );
+        member.apply_action(MemberAction::Suspend).unwrap();
+        member.apply_action(MemberAction::Activate).unwrap();
+        member.apply_action(MemberAction::Leave).unwrap();
+        assert_eq!(member.status, MemberStatus::Inactive);
+        member.apply_action(MemberAction::Join).unwrap();
+        member.apply_action(MemberAction::Ban).unwrap();
+        for action in [
+            MemberAction::Join,
+            MemberAction::Leave,
+            MemberAction::Suspend,
+            MemberAction::Activate,
+            MemberAction::Ban,
+        ] {
+            assert_eq!(
+                member.apply_action(action),
+                Err(FsmError::InvalidStateTransition)
+            );
+        }
+    }
+
+    #[cfg(feature = "governance-meta")]
+    fn board(chairs: &[u8]) -> Vec<SecurityBoardMemberMetadata<u8>> {
+        (1..=4u8)
+            .map(|id| {
+                let role = if chairs.contains(&id) {
+                    SecurityBoardMemberRole::Chairperson
+                } else {
+                    SecurityBoardMemberRole::Member
+                };
+                SecurityBoardMemberMetadata::initialize(u64::from(id), id, role, 0).unwrap()
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_sole_chairperson_cannot_leave_without_handover() {
+        let mut index = ObligationIndex::new();
+        index.record_board(&board(&[1]));
+        let mut chair = Member::new(1u8, vec![]);
+        for action in [
+            MemberAction::Leave,
+            MemberAction::Suspend,
+            MemberAction::Ban,
+        ] {
+            assert_eq!(
+                checked_apply_action(&mut chair, action, &mut index, vec![]),
+                Err(FsmError::InvalidState)
+            );
+        }
+        assert_eq!(chair.status, MemberStatus::Active);
+        assert_eq!(index.obligations_of(&1), vec![Obligation::SoleChairperson]);
+
+        let mut co_chair_index = ObligationIndex::new();
+        co_chair_index.record_board(&board(&[1, 2]));
+        assert!(co_chair_index.obligations_of(&1).is_empty());
+        checked_apply_action(&mut chair, MemberAction::Leave, &mut co_chair_index, vec![]).unwrap();
+        assert_eq!(chair.status, MemberStatus::Inactive);
+    }
+
+    #[test]
+    fn test_handover_moves_obligations() {
+        let mut index = ObligationIndex::new();
+        index.record(1u8, Obligation::SoleChairperson);
+        index.record(1, Obligation::ProposalAuthor { proposal_id: 5 });
+        let mut member = Member::new(1u8, vec![]);
+        checked_apply_action(
+            &mut member,
+            MemberAction::Ban,
+            &mut index,
+            vec![
+                Reassignment {
+                    obligation: Obligation::SoleChairperson,
+                    to: 2,
+                },
+                Reassignment {
+                    obligation: Obligation::ProposalAuthor { proposal_id: 5 },
+                    to: 3,
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(member.status, MemberStatus::Banned);
+        assert!(index.obligations_of(&1).is_empty());
+        assert_eq!(index.obligations_of(&2), vec![Obligation::SoleChairperson]);
+        assert_eq!(
+            index.obligations_of(&3),
+            vec![Obligation::ProposalAuthor { proposal_id: 5 }]
+        );
+    }
+
+    #[test]
+    fn test_rejected_handover_changes_nothing() {
+        let mut index = ObligationIndex::new();
+        index.record(1u8, Obligation::SoleChairperson);
+        index.record(1, Obligation::ProposalAuthor { proposal_id: 5 });
+        let before = index.clone();
+        let mut member = Member::new(1u8, vec![]);
+        let chair_to = |to| Reassignment {
+            obligation: Obligation::SoleChairperson,
+            to,
+        };
+        // Partial handover
+        assert_eq!(
+            checked_apply_action(
+                &mut member,
+                MemberAction::Leave,
+                &mut index,
+                vec![chair_to(2)]
+            ),
+            Err(FsmError::InvalidState)
+        );
+        // Handed to the member itself
+        assert_eq!(
+            checked_apply_action(
+                &mut member,
+                MemberAction::Leave,
+                &mut index,
+                vec![chair_to(1)]
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        // Not held
+        assert_eq!(
+            checked_apply_action(
+                &mut member,
+                MemberAction::Leave,
+                &mut index,
+                vec![Reassignment {
+                    obligation: Obligation::Reviewer { grant_id: 9 },
+                    to: 2,
+                }]
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        // Invalid transition is checked first
+        assert_eq!(
+            checked_apply_action(&mut member, MemberAction::Join, &mut index, vec![]),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(index, before);
+        assert_eq!(member.status, MemberStatus::Active);
+    }
+
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_reviewers_of_pending_grants() {
+        use crate::governance::security_committees::CommitteeMemberRole;
+        use crate::grant::{
+            AuthorshipIndex, GrantCategory, GrantDisbursementType, GrantType, ReviewCommittee,
+        };
+        let grants: Vec<Grant> = (1..=2)
+            .map(|id| {
+                Grant::new(
+                    id,
+                    id,
+                    [0u8; 32],
+                    GrantCategory::Research,
+                    GrantType::Initial,
+                    GrantDisbursementType::Standard,
+                    100,
+                    0,
+                    0,
+                )
+                .unwrap()
+            })
+            .collect();
+        let mut committee = ReviewCommittee::new(5, 3600).unwrap();
+        committee.add_member([1; 32], CommitteeMemberRole::Member);
+        let mut reviews = ReviewAssignments::new();
+        for grant in &grants {
+            reviews
+                .assign_reviewer(grant, [1; 32], &committee, &AuthorshipIndex::new(), 0)
+                .unwrap();
+        }
+        let mut decided = grants.clone();
+        decided[0].approve().unwrap();
+        let mut index = ObligationIndex::new();
+        index.record_reviews(&reviews, &decided);
+        assert_eq!(
+            index.obligations_of(&[1; 32]),
+            vec![Obligation::Reviewer { grant_id: 2 }]
+        );
+    }
+
+    #[test]
+    fn test_active_proposal_authors() {
+        let mut proposals: Vec<Proposal<u8>> = (1..=2)
+            .map(|id| {
+                Proposal::builder(
+                    id,
+                    "Title".to_string(),
+                    "Description".to_string(),
+                    "governance".to_string(),
+                    7,
+                )
+                .build(0)
+                .unwrap()
+            })
+            .collect();
+        proposals[1].activate(1, 1).unwrap();
+        let mut index = ObligationIndex::new();
+        index.record_active_proposals(&proposals);
+        assert_eq!(
+            index.obligations_of(&7),
+            vec![Obligation::ProposalAuthor { proposal_id: 2 }]
+        );
+        let mut author = Member::new(7u8, vec![]);
+        author.status = MemberStatus::Suspended;
+        // Activate is never blocked
+        checked_apply_action(&mut author, MemberAction::Activate, &mut index, vec![]).unwrap();
+        assert_eq!(author.status, MemberStatus::Active);
+    }
+}
@@ -0,0 +1,374 @@
+//! What this build of the crate supports.
+//!
+//! `capabilities` reports the enabled cargo features, the invariant kinds
+//! `FsmDefinition::validate_invariants` accepts, the status machines with
+//! their variants and terminal states, the reason requirements of the
+//! transitions that take a reason, and the `FsmError` codes. Every list is
+//! read from the table the crate itself uses, so the report cannot drift from
+//! the code. With `serde` it serializes to JSON for service "about" pages.
+
+use crate::enums::IdeaStatus;
+use crate::error::FsmError;
+#[cfg(feature = "governance-meta")]
+use crate::governance::{CommitteeDecisionStatus, SecurityBoardDecisionStatus};
+use crate::grant::GrantStatus;
+use crate::proposal::{AmendmentStatus, ProposalStatus};
+use crate::reason_policy::{ReasonPolicy, ReasonRequirement, ReasonStatus};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Cargo features this crate declares
+const FEATURES: &[(&str, bool)] = &[
+    ("serde", cfg!(feature = "serde")),
+    ("borsh", cfg!(feature = "borsh")),
+    ("hash", cfg!(feature = "hash")),
+    ("definitions", cfg!(feature = "definitions")),
+    ("governance-meta", cfg!(feature = "governance-meta")),
+    ("compat-v1", cfg!(feature = "compat-v1")),
+    ("scxml", cfg!(feature = "scxml")),
+    ("tracing", cfg!(feature = "tracing")),
+];
+
+/// One status enum and its transition graph's shape
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct StatusMachine {
+    pub name: &'static str,
+    /// Variant names, in declaration order
+    pub variants: Vec<String>,
+    /// Variants with no outgoing transitions
+    pub terminal: Vec<String>,
+}
+
+/// Reason requirement of one transition that takes a reason
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ReasonRuleInfo {
+    pub machine: &'static str,
+    pub from: String,
+    pub to: String,
+    /// Under `ReasonPolicy::default()`
+    pub default: ReasonRequirement,
+    /// Under `ReasonPolicy::strict()`
+    pub strict: ReasonRequirement,
+}
+
+/// Capability report of this build
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct CrateCapabilities {
+    pub version: &'static str,
+    /// Enabled cargo features
+    pub features: Vec<&'static str>,
+    /// `FsmInvariant::kind` values; empty without `definitions`
+    pub invariant_kinds: Vec<&'static str>,
+    pub status_machines: Vec<StatusMachine>,
+    /// One row per `ReasonStatus::REASON_TRANSITIONS` entry
+    pub reason_policy: Vec<ReasonRuleInfo>,
+    /// `FsmError::code` values
+    pub error_codes: Vec<&'static str>,
+    /// `FsmError::message_key` values, in the order of `error_codes`
+    pub error_message_keys: Vec<&'static str>,
+}
+
+impl CrateCapabilities {
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains(&feature)
+    }
+
+    pub fn status_machine(&self, name: &str) -> Option<&StatusMachine> {
+        self.status_machines.iter().find(|m| m.name == name)
+    }
+}
+
+fn status_machine<S: Debug>(
+    name: &'static str,
+    variants: &[S],
+    is_terminal: impl Fn(&S) -> bool,
+) -> StatusMachine {
+    StatusMachine {
+        name,
+        variants: variants.iter().map(|v| format!("{v:?}")).collect(),
+        terminal: variants
+            .iter()
+            .filter(|v| is_terminal(v))
+            .map(|v| format!("{v:?}"))
+            .collect(),
+    }
+}
+
+fn reason_rules<S: ReasonStatus>(machine: &'static str) -> Vec<ReasonRuleInfo> {
+    let (default, strict) = (ReasonPolicy::<S>::default(), ReasonPolicy::<S>::strict());
+    S::REASON_TRANSITIONS
+        .iter()
+        .map(|(from, to)| ReasonRuleInfo {
+            machine,
+            from: format!("{from:?}"),
+            to: format!("{to:?}"),
+            default: default.requirement(from, to),
+            strict: strict.requirement(from, to),
+        })
+        .collect()
+}
+
+/// Capabilities of the running build
+pub fn capabilities() -> CrateCapabilities {
+    #[cfg(feature = "definitions")]
+    let invariant_kinds = crate::definition::InvariantKind::ALL
+        .iter()
+        .map(|kind| kind.as_str())
+        .collect();
+    #[cfg(not(feature = "definitions"))]
+    let invariant_kinds = Vec::new();
+
+    #[allow(unused_mut)]
+    let mut status_machines = vec![
+        status_machine("IdeaStatus", IdeaStatus::VARIANTS, |s| {
+            s.next_states().is_empty()
+        }),
+        status_machine("ProposalStatus", ProposalStatus::VARIANTS, |s| {
+            s.next_states().is_empty()
+        }),
+        status_machine("AmendmentStatus", AmendmentStatus::VARIANTS, |s| {
+            s.next_states().is_empty()
+        }),
+        status_machine("GrantStatus", GrantStatus::VARIANTS, |s| {
+            s.next_states().is_empty()
+        }),
+    ];
+    #[cfg(feature = "governance-meta")]
+    status_machines.extend([
+        status_machine(
+            "SecurityBoardDecisionStatus",
+            SecurityBoardDecisionStatus::VARIANTS,
+            |s| s.next_states().is_empty(),
+        ),
+        status_machine(
+            "CommitteeDecisionStatus",
+            CommitteeDecisionStatus::VARIANTS,
+            |s| s.next_states().is_empty(),
+        ),
+    ]);
+
+    CrateCapabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        invariant_kinds,
+        status_machines,
+        reason_policy: [
+            reason_rules::<ProposalStatus>("ProposalStatus"),
+            reason_rules::<GrantStatus>("GrantStatus"),
+        ]
+        .concat(),
+        error_codes: FsmError::CODES.to_vec(),
+        error_message_keys: FsmError::MESSAGE_KEYS.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{CapabilityType, EntityKind};
+    use crate::error::{DefinitionLimit, ErrorClass};
+    use crate::reason_policy::ReasonTransition;
+
+    #[test]
+    fn test_features_match_cfg() {
+        let caps = capabilities();
+        assert_eq!(caps.has_feature("serde"), cfg!(feature = "serde"));
+        assert_eq!(caps.has_feature("scxml"), cfg!(feature = "scxml"));
+        assert_eq!(
+            caps.has_feature("governance-meta"),
+            cfg!(feature = "governance-meta")
+        );
+        assert!(!caps.has_feature("std"));
+        assert_eq!(caps.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_status_machines() {
+        let caps = capabilities();
+        let proposal = caps.status_machine("ProposalStatus").unwrap();
+        assert_eq!(proposal.variants.len(), 10);
+        assert_eq!(proposal.variants[0], "Draft");
+        assert!(proposal.terminal.contains(&"Archived".to_string()));
+        assert!(!proposal.terminal.contains(&"Active".to_string()));
+        let amendment = caps.status_machine("AmendmentStatus").unwrap();
+        assert_eq!(amendment.terminal, ["Accepted", "Rejected", "Withdrawn"]);
+        assert_eq!(
+            caps.status_machine("CommitteeDecisionStatus").is_some(),
+            cfg!(feature = "governance-meta")
+        );
+    }
+
+    #[test]
+    fn test_reason_policy_rows() {
+        let caps = capabilities();
+        assert_eq!(
+            caps.reason_policy.len(),
+            ProposalStatus::REASON_TRANSITIONS.len() + GrantStatus::REASON_TRANSITIONS.len()
+        );
+        let suspend = caps
+            .reason_policy
+            .iter()
+            .find(|row| row.machine == "GrantStatus" && row.to == "Suspended")
+            .unwrap();
+        assert_eq!(suspend.from, "Active");
+        assert_eq!(suspend.default, ReasonRequirement::Optional);
+        assert_eq!(suspend.strict, ReasonRequirement::Required);
+    }
+
+    #[cfg(feature = "definitions")]
+    #[test]
+    fn test_invariant_kinds_come_from_the_validator_table() {
+        use crate::definition::InvariantKind;
+        let caps = capabilities();
+        assert_eq!(caps.invariant_kinds.len(), InvariantKind::ALL.len());
+        for name in &caps.invariant_kinds {
+            assert!(InvariantKind::from_name(name).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_error_codes_cover_every_variant() {
+        // One sample per variant; add one with every new variant
+        let samples = [
+            FsmError::InvalidStateTransition,
+            FsmError::InvalidInput,
+            FsmError::InsufficientMembers,
+            FsmError::InvalidState,
+            FsmError::Overflow,
+            FsmError::IdempotencyKeyReused,
+            FsmError::ConflictDetected,
+            FsmError::ConflictOfInterest,
+            FsmError::TimeRegression,
+            FsmError::TooFrequentUpdate,
+            FsmError::AlreadyInitialized,
+            FsmError::BrokenReference {
+                entity: EntityKind::Idea,
+                id: 1,
+            },
+            FsmError::DefinitionTooLarge(DefinitionLimit::States),
+            FsmError::ReplayDetected,
+            FsmError::NotInSnapshot,
+            FsmError::BelowMinimumWeight,
+            FsmError::ReasonRequired(ReasonTransition {
+                entity: EntityKind::Proposal,
+                from: 0,
+                to: 6,
+            }),
+            FsmError::ReasonNotAllowed(ReasonTransition {
+                entity: EntityKind::Grant,
+                from: 2,
+                to: 3,
+            }),
+            FsmError::ExceedsPolicyLimit { max_hours: 24 },
+            FsmError::InsufficientSponsors {
+                required: 3,
+                actual: 1,
+            },
+            FsmError::CapabilityRequired {
+                capability: CapabilityType::Withdraw,
+            },
+        ];
+        let codes: Vec<&str> = samples.iter().map(FsmError::code).collect();
+        assert_eq!(codes, FsmError::CODES);
+        let classes: Vec<(&str, ErrorClass)> = samples
+            .iter()
+            .map(|error| (error.code(), error.class()))
+            .collect();
+        assert_eq!(classes, FsmError::CLASSES);
+        assert_eq!(capabilities().error_codes, FsmError::CODES);
+        let keys: Vec<&str> = samples.iter().map(FsmError::message_key).collect();
+        assert_eq!(keys, FsmError::MESSAGE_KEYS);
+        let unique: std::collections::BTreeSet<&str> = keys.iter().copied().collect();
+        assert_eq!(unique.len(), keys.len(), "message keys are unique");
+        assert!(keys.iter().all(|key| key.starts_with("error.")));
+        assert_eq!(capabilities().error_message_keys, FsmError::MESSAGE_KEYS);
+    }
+
+    #[test]
+    fn test_error_params_carry_variant_fields() {
+        let transition = FsmError::ReasonRequired(ReasonTransition {
+            entity: EntityKind::Grant,
+            from: 2,
+            to: 3,
+        });
+        assert_eq!(transition.message_key(), "error.reason_required");
+        assert_eq!(
+            transition.params(),
+            vec![
+                ("entity", "Grant".to_string()),
+                ("from", "Active".to_string()),
+                ("to", "Suspended".to_string()),
+            ]
+        );
+        assert_eq!(
+            FsmError::ExceedsPolicyLimit { max_hours: 72 }.params(),
+            vec![("max_hours", "72".to_string())]
+        );
+        assert_eq!(
+            FsmError::BrokenReference {
+                entity: EntityKind::Idea,
+                id: 7,
+            }
+            .params(),
+            vec![("entity", "Idea".to_string()), ("id", "7".to_string())]
+        );
+        assert_eq!(
+            FsmError::DefinitionTooLarge(DefinitionLimit::States).params(),
+            vec![("limit", "States".to_string())]
+        );
+        assert_eq!(
+            FsmError::InsufficientMembers.message_key(),
+            "error.quorum_not_met"
+        );
+        assert!(FsmError::InsufficientMembers.params().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_codes_match_serde_tags() {
+        for (error, code) in [
+            (FsmError::InvalidInput, "InvalidInput"),
+            (
+                FsmError::DefinitionTooLarge(DefinitionLimit::States),
+                "DefinitionTooLarge",
+            ),
+        ] {
+            let json = serde_json::to_value(error).unwrap();
+            let tag = json
+                .as_str()
+                .or_else(|| {
+                    json.as_object()
+                        .and_then(|o| o.keys().next())
+                        .map(|k| k.as_str())
+                })
+                .unwrap()
+                .to_string();
+            assert_eq!(tag, code);
+            assert_eq!(error.code(), code);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializes_for_about_endpoint() {
+        let json = serde_json::to_value(capabilities()).unwrap();
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(json["status_machines"][0]["name"], "IdeaStatus");
+        assert_eq!(json["error_codes"][1], "InvalidInput");
+        assert_eq!(json["reason_policy"][0]["strict"], "Required");
+        assert!(
+            json["features"]
+                .as_array()
+                .unwrap()
+                .contains(&"serde".into())
+        );
+    }
+}
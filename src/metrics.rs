@@ -0,0 +1,443 @@
+//! Metrics emission hooks.
+//!
+//! Lifecycle methods, vote-casting methods and transition validation report
+//! counters to a `MetricsSink` registered for the current thread. Without a registered sink
+//! every emission is a no-op, so callers never need to instrument call sites.
+
+use crate::error::FsmError;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Counter incremented for every successful status change.
+/// Labels: `entity`, `action`, `from`, `to`.
+pub const TRANSITIONS_TOTAL: &str = "fsm_transitions_total";
+/// Counter incremented when a lifecycle method rejects a transition.
+/// Labels: `entity`, `action`, `from`, `error`.
+pub const TRANSITION_FAILURES_TOTAL: &str = "fsm_transition_failures_total";
+/// Counter incremented when `validate_transition` fails.
+/// Labels: `entity`, `from`, `to`.
+pub const VALIDATION_FAILURES_TOTAL: &str = "fsm_validation_failures_total";
+/// Counter incremented for every vote recorded.
+/// Labels: `entity`, `action`.
+pub const VOTES_TOTAL: &str = "fsm_votes_total";
+/// Counter incremented when a vote-casting method refuses a vote.
+/// Labels: `entity`, `action`, `error`.
+pub const VOTE_FAILURES_TOTAL: &str = "fsm_vote_failures_total";
+
+/// Receiver for counters and observations.
+///
+/// Both methods default to no-ops so sinks only implement what they export.
+pub trait MetricsSink {
+    /// Increment the counter `name` by one.
+    fn incr(&self, _name: &str, _labels: &[(&str, &str)]) {}
+    /// Record `value` for the histogram/summary `name`.
+    fn observe(&self, _name: &str, _value: f64, _labels: &[(&str, &str)]) {}
+}
+
+/// Sink that discards everything.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoopSink;
+
+impl MetricsSink for NoopSink {}
+
+/// One recorded emission.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MetricEvent {
+    /// Counter increment
+    Incr {
+        name: String,
+        labels: Vec<(String, String)>,
+    },
+    /// Observed value
+    Observe {
+        name: String,
+        value: f64,
+        labels: Vec<(String, String)>,
+    },
+}
+
+/// Sink that keeps every emission in memory, for tests.
+#[derive(Debug, Default)]
+pub struct VecSink {
+    events: RefCell<Vec<MetricEvent>>,
+}
+
+impl VecSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All recorded events in emission order
+    pub fn events(&self) -> Vec<MetricEvent> {
+        self.events.borrow().clone()
+    }
+
+    /// Label sets of every increment of counter `name`, in emission order
+    pub fn increments(&self, name: &str) -> Vec<Vec<(String, String)>> {
+        self.events
+            .borrow()
+            .iter()
+            .filter_map(|event| match event {
+                MetricEvent::Incr { name: n, labels } if n == name => Some(labels.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+fn owned_labels(labels: &[(&str, &str)]) -> Vec<(String, String)> {
+    labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+impl MetricsSink for VecSink {
+    fn incr(&self, name: &str, labels: &[(&str, &str)]) {
+        self.events.borrow_mut().push(MetricEvent::Incr {
+            name: name.to_string(),
+            labels: owned_labels(labels),
+        });
+    }
+
+    fn observe(&self, name: &str, value: f64, labels: &[(&str, &str)]) {
+        self.events.borrow_mut().push(MetricEvent::Observe {
+            name: name.to_string(),
+            value,
+            labels: owned_labels(labels),
+        });
+    }
+}
+
+thread_local! {
+    static SINK: RefCell<Option<Rc<dyn MetricsSink>>> = const { RefCell::new(None) };
+}
+
+/// Register `sink` for the current thread, returning the previous one
+pub fn set_sink(sink: Rc<dyn MetricsSink>) -> Option<Rc<dyn MetricsSink>> {
+    SINK.with(|slot| slot.borrow_mut().replace(sink))
+}
+
+/// Remove the sink registered for the current thread
+pub fn clear_sink() -> Option<Rc<dyn MetricsSink>> {
+    SINK.with(|slot| slot.borrow_mut().take())
+}
+
+fn with_sink(f: impl FnOnce(&dyn MetricsSink)) {
+    let sink = SINK.with(|slot| slot.borrow().clone());
+    if let Some(sink) = sink {
+        f(sink.as_ref());
+    }
+}
+
+/// Increment counter `name` on the registered sink
+pub fn incr(name: &str, labels: &[(&str, &str)]) {
+    with_sink(|sink| sink.incr(name, labels));
+}
+
+/// Record `value` for `name` on the registered sink
+pub fn observe(name: &str, value: f64, labels: &[(&str, &str)]) {
+    with_sink(|sink| sink.observe(name, value, labels));
+}
+
+/// Report the outcome of a lifecycle method
+///
+/// Successful calls that changed status emit `TRANSITIONS_TOTAL`; failed calls
+/// emit `TRANSITION_FAILURES_TOTAL`. Labels are only formatted when a sink is
+/// registered.
+pub(crate) fn record_transition<S: Debug>(
+    entity: &str,
+    action: &str,
+    from: &S,
+    to: &S,
+    result: &Result<(), FsmError>,
+) {
+    with_sink(|sink| {
+        let from = format!("{from:?}");
+        match result {
+            Ok(()) => {
+                let to = format!("{to:?}");
+                if from != to {
+                    sink.incr(
+                        TRANSITIONS_TOTAL,
+                        &[
+                            ("entity", entity),
+                            ("action", action),
+                            ("from", &from),
+                            ("to", &to),
+                        ],
+                    );
+                }
+            }
+            Err(err) => {
+                let error = format!("{err:?}");
+                sink.incr(
+                    TRANSITION_FAILURES_TOTAL,
+                    &[
+                        ("entity", entity),
+                        ("action", action),
+                        ("from", &from),
+                        ("error", &error),
+                    ],
+                );
+            }
+        }
+    });
+}
+
+/// Report the outcome of a vote-casting method
+///
+/// Methods that delegate to another vote-casting method leave the report to
+/// it, so each vote is counted once.
+pub(crate) fn record_vote<E: Debug>(entity: &str, action: &str, result: &Result<(), E>) {
+    with_sink(|sink| match result {
+        Ok(()) => sink.incr(VOTES_TOTAL, &[("entity", entity), ("action", action)]),
+        Err(err) => {
+            let error = format!("{err:?}");
+            sink.incr(
+                VOTE_FAILURES_TOTAL,
+                &[("entity", entity), ("action", action), ("error", &error)],
+            );
+        }
+    });
+}
+
+/// Report a failed `validate_transition`
+pub(crate) fn record_validation_failure<S: Debug>(entity: &str, from: &S, to: &S) {
+    with_sink(|sink| {
+        let from = format!("{from:?}");
+        let to = format!("{to:?}");
+        sink.incr(
+            VALIDATION_FAILURES_TOTAL,
+            &[("entity", entity), ("from", &from), ("to", &to)],
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::Grant;
+    use crate::grant::types::{GrantCategory, GrantDisbursementType, GrantStatus, GrantType};
+    use crate::grant::vote::{GrantVote, VoterType};
+    use crate::grant::voting_types::VoteType;
+    use crate::proposal::{ImportMode, ImportedVote, Proposal};
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        owned_labels(pairs)
+    }
+
+    fn install_vec_sink() -> Rc<VecSink> {
+        let sink = Rc::new(VecSink::new());
+        set_sink(sink.clone());
+        sink
+    }
+
+    #[test]
+    fn test_no_sink_is_noop() {
+        clear_sink();
+        incr(TRANSITIONS_TOTAL, &[("entity", "proposal")]);
+        let sink = install_vec_sink();
+        assert!(sink.events().is_empty());
+        clear_sink();
+    }
+
+    #[test]
+    fn test_proposal_happy_path_emits_transitions() {
+        let sink = install_vec_sink();
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal.pass_with_time(100).unwrap();
+        proposal.execute_with_time(200).unwrap();
+        clear_sink();
+
+        let expected = vec![
+            labels(&[
+                ("entity", "proposal"),
+                ("action", "activate"),
+                ("from", "Draft"),
+                ("to", "Active"),
+            ]),
+            labels(&[
+                ("entity", "proposal"),
+                ("action", "pass"),
+                ("from", "Active"),
+                ("to", "Passed"),
+            ]),
+            labels(&[
+                ("entity", "proposal"),
+                ("action", "execute"),
+                ("from", "Passed"),
+                ("to", "Executed"),
+            ]),
+        ];
+        assert_eq!(sink.increments(TRANSITIONS_TOTAL), expected);
+        assert!(sink.increments(TRANSITION_FAILURES_TOTAL).is_empty());
+    }
+
+    #[test]
+    fn test_proposal_failure_emits_failure_counter() {
+        let sink = install_vec_sink();
+        let mut proposal = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+            0,
+        )
+        .unwrap();
+
+        assert!(proposal.execute_with_time(10).is_err());
+        clear_sink();
+
+        assert_eq!(
+            sink.increments(TRANSITION_FAILURES_TOTAL),
+            vec![labels(&[
+                ("entity", "proposal"),
+                ("action", "execute"),
+                ("from", "Draft"),
+                ("error", "InvalidStateTransition"),
+            ])]
+        );
+        assert!(sink.increments(TRANSITIONS_TOTAL).is_empty());
+    }
+
+    #[test]
+    fn test_validation_failure_counter() {
+        let sink = install_vec_sink();
+        assert!(
+            GrantStatus::Pending
+                .validate_transition(GrantStatus::Completed)
+                .is_err()
+        );
+        assert!(
+            GrantStatus::Pending
+                .validate_transition(GrantStatus::Approved)
+                .is_ok()
+        );
+        clear_sink();
+
+        assert_eq!(
+            sink.increments(VALIDATION_FAILURES_TOTAL),
+            vec![labels(&[
+                ("entity", "grant"),
+                ("from", "Pending"),
+                ("to", "Completed"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_observe_records_value() {
+        let sink = install_vec_sink();
+        observe("fsm_vote_weight", 2.5, &[("entity", "grant")]);
+        clear_sink();
+
+        assert_eq!(
+            sink.events(),
+            vec![MetricEvent::Observe {
+                name: "fsm_vote_weight".to_string(),
+                value: 2.5,
+                labels: labels(&[("entity", "grant")]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_vote_casts_are_counted() {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        let mut grant = Grant::new(
+            1,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap();
+        let sink = install_vec_sink();
+
+        proposal.cast_vote(1, true, 1, 10).unwrap();
+        assert!(proposal.cast_vote(1, true, 1, 11).is_err());
+        proposal
+            .cast_vote_with_nonce(2, false, 1, None, 12)
+            .unwrap();
+        proposal.change_vote(&2, true, 1, 13).unwrap();
+        assert!(proposal.cast_weighted_vote(3, true, 14).is_err());
+        let votes = vec![
+            ImportedVote {
+                voter: 4,
+                support: true,
+                weight: 1,
+                cast_at: 15,
+            },
+            ImportedVote {
+                voter: 1,
+                support: true,
+                weight: 1,
+                cast_at: 15,
+            },
+        ];
+        proposal.import_votes(votes, ImportMode::Lenient);
+        grant
+            .cast_vote(GrantVote {
+                grant_id: 1,
+                voter_id: [1u8; 32],
+                vote_type: VoteType::Approve,
+                weight: 1,
+                voter_type: VoterType::Expert,
+                cast_at: 0,
+            })
+            .unwrap();
+        clear_sink();
+
+        let cast = |entity, action| labels(&[("entity", entity), ("action", action)]);
+        assert_eq!(
+            sink.increments(VOTES_TOTAL),
+            vec![
+                cast("proposal", "cast"),
+                cast("proposal", "cast"),
+                cast("proposal", "change"),
+                cast("proposal", "import"),
+                cast("grant", "cast"),
+            ]
+        );
+        let failed =
+            |action, error| labels(&[("entity", "proposal"), ("action", action), ("error", error)]);
+        assert_eq!(
+            sink.increments(VOTE_FAILURES_TOTAL),
+            vec![
+                failed("cast", "InvalidState"),
+                failed("cast", "InvalidState"),
+                failed("import", "DuplicateVoter"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,601 @@
+//! One governance round, end to end.
+//!
+//! `GovernanceRound` owns a proposal together with its quorum record, its
+//! voting metadata and the events of the round, and moves them in step:
+//!
+//! - `open` builds both records for the proposal from `GovernanceParams` and
+//!   activates it under the same parameters
+//! - `vote` casts a vote and refreshes the quorum from the voter count, or
+//!   from snapshot power once `attach_snapshot` has fixed it
+//! - `require_commit_reveal` seals the round instead: voters `commit_vote` a
+//!   `vote_commitment` before the reveal time and `reveal_vote` after it, and
+//!   only revealed votes are cast
+//! - `tick` closes voting once the period has ended: a proposal short of
+//!   quorum becomes QuorumFailed, otherwise its tally policy decides
+//! - `finalize` hands back the closed records
+//!
+//! Between calls the records agree: both point at the proposal, voting is
+//! Open exactly while the proposal is Active, and the quorum percentage
+//! matches the recorded voters (see `is_consistent`).
+
+use crate::error::FsmError;
+use crate::events::{EventBuffer, GovernanceEvent};
+use crate::governance::quorum::onchain::update_quorum_from_counts;
+use crate::governance::{
+    GovernanceVotingMetadata, GovernanceVotingStatus, GovernanceVotingType,
+    QuorumCalculationMethod, QuorumMetadata,
+};
+use crate::governance_params::GovernanceParams;
+use crate::proposal::{ActivationContext, PowerSnapshot, Proposal, ProposalStatus};
+#[cfg(all(feature = "hash", feature = "serde"))]
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Identifiers and membership of a round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundConfig {
+    pub quorum_id: u64,
+    pub voting_id: u64,
+    pub voting_type: GovernanceVotingType,
+    pub voting_data_hash: [u8; 32],
+    /// Eligible voters; the quorum base
+    pub total_members: u64,
+    /// Members required to activate the proposal
+    pub min_quorum: u64,
+}
+
+/// Result of `GovernanceRound::tick`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TickOutcome {
+    /// Voting is still open
+    Voting { ends_at: i64 },
+    /// Voting is closed with the proposal in `status`
+    Closed {
+        status: ProposalStatus,
+        quorum_reached: bool,
+    },
+}
+
+/// Records of a closed round
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FinalizedRound<P> {
+    pub proposal: Proposal<P>,
+    pub quorum: QuorumMetadata,
+    pub voting: GovernanceVotingMetadata,
+    /// Proposal status changes of the round, oldest first
+    pub events: Vec<GovernanceEvent>,
+}
+
+/// A proposal with its quorum record, voting metadata and events
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GovernanceRound<P> {
+    proposal: Proposal<P>,
+    quorum: QuorumMetadata,
+    voting: GovernanceVotingMetadata,
+    total_members: u64,
+    events: EventBuffer,
+    /// Start of the reveal phase once the round is sealed
+    reveal_from: Option<i64>,
+    /// Unrevealed commitments by voter
+    commitments: BTreeMap<P, [u8; 32]>,
+}
+
+/// Commitment a voter submits to `GovernanceRound::commit_vote`
+///
+/// `canonical_hash` of `(proposal_id, voter, support, weight, salt)`; the
+/// proposal id keeps a commitment from being replayed in another round.
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub fn vote_commitment<P: Serialize>(
+    proposal_id: u64,
+    voter: &P,
+    support: bool,
+    weight: u64,
+    salt: &[u8; 32],
+) -> Result<[u8; 32], FsmError> {
+    crate::canonical::canonical_hash(&(proposal_id, voter, support, weight, salt))
+}
+
+impl<P> GovernanceRound<P> {
+    /// Open voting on a Draft `proposal` under `params`
+    ///
+    /// The proposal's voting duration becomes `params.vote_duration()` and its
+    /// quorum requirement `params.quorum_percentage`. Errors: `InvalidState`
+    /// unless the proposal is Draft, `InvalidInput` for zero ids or members,
+    /// and the activation errors of `Proposal::activate_in`.
+    pub fn open(
+        mut proposal: Proposal<P>,
+        params: &GovernanceParams,
+        config: RoundConfig,
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if proposal.status != ProposalStatus::Draft {
+            return Err(FsmError::InvalidState);
+        }
+        if config.total_members == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        let quorum = QuorumMetadata::initialize(
+            config.quorum_id,
+            Some(proposal.id),
+            params.quorum_percentage,
+            QuorumCalculationMethod::FixedPercentage,
+            current_time,
+        )?;
+        proposal.voting_duration = params.vote_duration();
+        let mut events = EventBuffer::new();
+        let ctx = ActivationContext::new(config.min_quorum, config.total_members, current_time)
+            .params(params);
+        events.record_proposal(&mut proposal, current_time, |p| p.activate_in(&ctx))?;
+        let voting = GovernanceVotingMetadata::initialize_for(
+            config.voting_id,
+            &mut proposal,
+            config.voting_type,
+            config.voting_data_hash,
+            current_time,
+        )?;
+        Ok(Self {
+            proposal,
+            quorum,
+            voting,
+            total_members: config.total_members,
+            events,
+            reveal_from: None,
+            commitments: BTreeMap::new(),
+        })
+    }
+
+    pub fn proposal(&self) -> &Proposal<P> {
+        &self.proposal
+    }
+
+    pub fn quorum(&self) -> &QuorumMetadata {
+        &self.quorum
+    }
+
+    pub fn voting(&self) -> &GovernanceVotingMetadata {
+        &self.voting
+    }
+
+    pub fn events(&self) -> &EventBuffer {
+        &self.events
+    }
+
+    /// End of the voting period
+    pub fn voting_ends_at(&self) -> Result<i64, FsmError> {
+        self.proposal.voting_ends_at()
+    }
+
+    /// Quorum base: snapshot power if one is attached, otherwise `voters` of `total_members`
+    fn quorum_counts(&self, voters: u64) -> (u64, u64) {
+        self.proposal
+            .quorum_counts()
+            .unwrap_or((voters, self.total_members))
+    }
+
+    /// Whether the records agree with each other (see the module docs)
+    pub fn is_consistent(&self) -> bool {
+        let voting_open = self.voting.status == GovernanceVotingStatus::Open;
+        let (current, total) = self.quorum_counts(self.proposal.votes.len() as u64);
+        let expected_percentage = (u128::from(current) * 100 / u128::from(total.max(1))) as u8;
+        self.quorum.proposal_id == Some(self.proposal.id)
+            && self.voting.proposal_id == self.proposal.id
+            && self.proposal.voting_id == Some(self.voting.voting_id)
+            && voting_open == (self.proposal.status == ProposalStatus::Active)
+            && self.quorum.current_percentage == expected_percentage
+    }
+
+    /// Close voting if its period has ended and settle the proposal
+    ///
+    /// Short of quorum the proposal is QuorumFailed; otherwise its tally policy
+    /// makes it Passed, Rejected or Tied. Calling again after closing reports
+    /// the same outcome.
+    pub fn tick(&mut self, current_time: i64) -> Result<TickOutcome, FsmError> {
+        if self.voting.status == GovernanceVotingStatus::Open {
+            let ends_at = self.voting_ends_at()?;
+            if current_time < ends_at {
+                return Ok(TickOutcome::Voting { ends_at });
+            }
+            let (participation, required) = (
+                self.quorum.current_percentage,
+                self.quorum.required_percentage,
+            );
+            self.events
+                .record_proposal(&mut self.proposal, current_time, |p| {
+                    p.auto_transition_with_quorum(participation, required, current_time)
+                })?;
+            self.voting.close()?;
+        }
+        Ok(TickOutcome::Closed {
+            status: self.proposal.status.clone(),
+            quorum_reached: self.quorum.quorum_reached,
+        })
+    }
+
+    /// Hand back the records of a closed round; `InvalidState` while voting is open
+    pub fn finalize(mut self) -> Result<FinalizedRound<P>, FsmError> {
+        if self.voting.status == GovernanceVotingStatus::Open {
+            return Err(FsmError::InvalidState);
+        }
+        Ok(FinalizedRound {
+            events: self.events.drain(),
+            proposal: self.proposal,
+            quorum: self.quorum,
+            voting: self.voting,
+        })
+    }
+}
+
+impl<P: Ord + Clone> GovernanceRound<P> {
+    /// Cast a vote (see `Proposal::cast_vote_with_nonce`) and refresh the quorum
+    ///
+    /// `InvalidState` once voting is closed or if the round is sealed;
+    /// `InvalidInput` if the vote would take the voter count past
+    /// `total_members`. Nothing changes on error.
+    pub fn vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        nonce: Option<u64>,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if self.reveal_from.is_some() {
+            return Err(FsmError::InvalidState);
+        }
+        self.cast(voter, support, weight, nonce, current_time)
+    }
+
+    /// Seal the round: votes are committed before `reveal_from` and revealed from it
+    ///
+    /// `InvalidState` once voting is closed, if the round is already sealed
+    /// or if a vote has been cast; `InvalidInput` unless `reveal_from` is
+    /// before the end of voting.
+    pub fn require_commit_reveal(&mut self, reveal_from: i64) -> Result<(), FsmError> {
+        if self.voting.status != GovernanceVotingStatus::Open
+            || self.reveal_from.is_some()
+            || !self.proposal.votes.is_empty()
+        {
+            return Err(FsmError::InvalidState);
+        }
+        if reveal_from >= self.voting_ends_at()? {
+            return Err(FsmError::InvalidInput);
+        }
+        self.reveal_from = Some(reveal_from);
+        Ok(())
+    }
+
+    /// Record `voter`'s `vote_commitment` in a sealed round
+    ///
+    /// `InvalidState` if voting is closed, the round is not sealed,
+    /// `current_time` is in the reveal phase or `voter` already committed;
+    /// `InvalidInput` past `total_members` commitments.
+    pub fn commit_vote(
+        &mut self,
+        voter: P,
+        commitment: [u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let Some(reveal_from) = self.reveal_from else {
+            return Err(FsmError::InvalidState);
+        };
+        if self.voting.status != GovernanceVotingStatus::Open
+            || current_time >= reveal_from
+            || self.commitments.contains_key(&voter)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        if self.commitments.len() as u64 >= self.total_members {
+            return Err(FsmError::InvalidInput);
+        }
+        self.commitments.insert(voter, commitment);
+        Ok(())
+    }
+
+    /// Commitments not yet revealed
+    pub fn pending_reveals(&self) -> usize {
+        self.commitments.len()
+    }
+
+    fn cast(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        nonce: Option<u64>,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if self.voting.status != GovernanceVotingStatus::Open {
+            return Err(FsmError::InvalidState);
+        }
+        let voters = self.proposal.votes.len() as u64 + 1;
+        if voters > self.total_members {
+            return Err(FsmError::InvalidInput);
+        }
+        self.proposal
+            .cast_vote_with_nonce(voter, support, weight, nonce, current_time)?;
+        let (current, total) = self.quorum_counts(voters);
+        update_quorum_from_counts(&mut self.quorum, current, total, current_time)
+    }
+
+    /// Fix voting power for the round (see `Proposal::attach_snapshot`)
+    ///
+    /// From then on the quorum counts snapshot power instead of voters.
+    pub fn attach_snapshot(&mut self, snapshot: PowerSnapshot<P>) -> Result<(), FsmError> {
+        self.proposal.attach_snapshot(snapshot)
+    }
+}
+
+#[cfg(all(feature = "hash", feature = "serde"))]
+impl<P: Ord + Clone + Serialize> GovernanceRound<P> {
+    /// Open `voter`'s commitment and cast the vote it hides
+    ///
+    /// `InvalidState` if the round is not sealed, `current_time` is before
+    /// the reveal phase or `voter` has no commitment; `InvalidInput` if the
+    /// vote and `salt` do not hash to the commitment; otherwise as `vote`.
+    /// The commitment is spent only once the vote is cast.
+    pub fn reveal_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        salt: &[u8; 32],
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if self.reveal_from.is_none_or(|from| current_time < from) {
+            return Err(FsmError::InvalidState);
+        }
+        let commitment = *self.commitments.get(&voter).ok_or(FsmError::InvalidState)?;
+        if vote_commitment(self.proposal.id, &voter, support, weight, salt)? != commitment {
+            return Err(FsmError::InvalidInput);
+        }
+        self.cast(voter.clone(), support, weight, None, current_time)?;
+        self.commitments.remove(&voter);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params() -> GovernanceParams {
+        GovernanceParams::new(50, 24, 0, false, 0).unwrap()
+    }
+
+    fn config() -> RoundConfig {
+        RoundConfig {
+            quorum_id: 1,
+            voting_id: 2,
+            voting_type: GovernanceVotingType::SimpleMajority,
+            voting_data_hash: [0; 32],
+            total_members: 4,
+            min_quorum: 1,
+        }
+    }
+
+    fn open_round() -> GovernanceRound<u8> {
+        let proposal = Proposal::builder(
+            7,
+            "Title".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap();
+        GovernanceRound::open(proposal, &params(), config(), 10).unwrap()
+    }
+
+    #[test]
+    fn test_open_links_records() {
+        let round = open_round();
+        assert_eq!(round.proposal().status, ProposalStatus::Active);
+        assert_eq!(round.proposal().voting_duration.as_secs(), 24 * 3600);
+        assert_eq!(round.quorum().proposal_id, Some(7));
+        assert_eq!(round.quorum().required_percentage, 50);
+        assert_eq!(round.voting().proposal_id, 7);
+        assert_eq!(round.voting().status, GovernanceVotingStatus::Open);
+        assert_eq!(round.voting_ends_at(), Ok(10 + 24 * 3600));
+        assert_eq!(round.voting().voting_ends_at, Some(10 + 24 * 3600));
+        assert_eq!(round.proposal().voting_id, Some(2));
+        assert_eq!(round.events().len(), 1);
+        assert!(round.is_consistent());
+    }
+
+    #[test]
+    fn test_open_rejects_bad_input() {
+        let round = open_round();
+        let active = round.proposal().clone();
+        assert_eq!(
+            GovernanceRound::open(active.clone(), &params(), config(), 20).map(|_| ()),
+            Err(FsmError::InvalidState)
+        );
+        let mut draft = active;
+        draft.status = ProposalStatus::Draft;
+        let no_members = RoundConfig {
+            total_members: 0,
+            ..config()
+        };
+        assert_eq!(
+            GovernanceRound::open(draft.clone(), &params(), no_members, 20).map(|_| ()),
+            Err(FsmError::InvalidInput)
+        );
+        let no_quorum_id = RoundConfig {
+            quorum_id: 0,
+            ..config()
+        };
+        assert_eq!(
+            GovernanceRound::open(draft, &params(), no_quorum_id, 20).map(|_| ()),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_votes_refresh_quorum() {
+        let mut round = open_round();
+        round.vote(2, true, 1, None, 20).unwrap();
+        assert_eq!(round.quorum().current_percentage, 25);
+        assert!(!round.quorum().quorum_reached);
+        round.vote(3, false, 1, Some(1), 30).unwrap();
+        assert_eq!(round.quorum().current_percentage, 50);
+        assert!(round.quorum().quorum_reached);
+        assert_eq!(round.quorum().updated_at, 30);
+        assert!(round.is_consistent());
+        // A failed vote leaves both records alone
+        assert_eq!(
+            round.vote(3, true, 1, Some(1), 40),
+            Err(FsmError::ReplayDetected)
+        );
+        assert_eq!(round.quorum().current_percentage, 50);
+        assert!(round.is_consistent());
+    }
+
+    #[test]
+    fn test_vote_count_capped_by_members() {
+        let mut round = open_round();
+        for voter in 1..=4 {
+            round.vote(voter, true, 1, None, 20).unwrap();
+        }
+        assert_eq!(
+            round.vote(5, true, 1, None, 20),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(round.proposal().votes.len(), 4);
+        assert_eq!(round.quorum().current_percentage, 100);
+    }
+
+    #[test]
+    fn test_tick_without_quorum_fails_quorum() {
+        let mut round = open_round();
+        round.vote(2, true, 1, None, 20).unwrap();
+        let ends_at = round.voting_ends_at().unwrap();
+        assert_eq!(round.tick(ends_at - 1), Ok(TickOutcome::Voting { ends_at }));
+        let closed = TickOutcome::Closed {
+            status: ProposalStatus::QuorumFailed,
+            quorum_reached: false,
+        };
+        assert_eq!(round.tick(ends_at), Ok(closed.clone()));
+        assert_eq!(round.tick(ends_at + 10), Ok(closed));
+        assert_eq!(round.voting().status, GovernanceVotingStatus::Closed);
+        assert_eq!(
+            round.vote(3, true, 1, None, ends_at + 20),
+            Err(FsmError::InvalidState)
+        );
+        assert!(round.is_consistent());
+    }
+
+    #[test]
+    fn test_finalize_requires_closed_voting() {
+        let round = open_round();
+        assert_eq!(
+            round.clone().finalize().map(|_| ()),
+            Err(FsmError::InvalidState)
+        );
+        let mut round = round;
+        round.vote(2, true, 1, None, 20).unwrap();
+        round.vote(3, true, 1, None, 20).unwrap();
+        let ends_at = round.voting_ends_at().unwrap();
+        round.tick(ends_at).unwrap();
+        let finalized = round.finalize().unwrap();
+        assert_eq!(finalized.proposal.status, ProposalStatus::Passed);
+        assert_eq!(finalized.voting.status, GovernanceVotingStatus::Closed);
+        assert_eq!(finalized.events.len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_power_drives_quorum() {
+        let mut round = open_round();
+        let snapshot = PowerSnapshot::new(vec![(2, 60), (3, 30), (4, 10)], 10).unwrap();
+        round.attach_snapshot(snapshot).unwrap();
+        round.vote(4, true, 10, None, 20).unwrap();
+        assert_eq!(round.quorum().current_percentage, 10);
+        assert!(!round.quorum().quorum_reached);
+        assert_eq!(
+            round.vote(2, true, 1, None, 20),
+            Err(FsmError::InvalidInput)
+        );
+        round.vote(2, true, 60, None, 20).unwrap();
+        assert_eq!(round.quorum().current_percentage, 70);
+        assert!(round.quorum().quorum_reached);
+        assert!(round.is_consistent());
+    }
+    #[test]
+    fn test_sealed_round_refuses_open_votes() {
+        let mut round = open_round();
+        assert_eq!(
+            round.commit_vote(2, [0; 32], 20),
+            Err(FsmError::InvalidState)
+        );
+        let ends_at = round.voting_ends_at().unwrap();
+        assert_eq!(
+            round.require_commit_reveal(ends_at),
+            Err(FsmError::InvalidInput)
+        );
+        round.require_commit_reveal(100).unwrap();
+        assert_eq!(
+            round.require_commit_reveal(100),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            round.vote(2, true, 1, None, 20),
+            Err(FsmError::InvalidState)
+        );
+        round.commit_vote(2, [7; 32], 20).unwrap();
+        assert_eq!(
+            round.commit_vote(2, [8; 32], 30),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            round.commit_vote(3, [8; 32], 100),
+            Err(FsmError::InvalidState)
+        );
+        for voter in 3..=6 {
+            let committed = round.commit_vote(voter, [0; 32], 30);
+            assert_eq!(committed.is_ok(), voter <= 5);
+        }
+        assert_eq!(round.pending_reveals(), 4);
+    }
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    #[test]
+    fn test_commit_reveal_casts_revealed_votes() {
+        let mut round = open_round();
+        round.require_commit_reveal(100).unwrap();
+        let salt = [9u8; 32];
+        let commitment = vote_commitment(7, &2u8, true, 3, &salt).unwrap();
+        assert_ne!(vote_commitment(8, &2u8, true, 3, &salt), Ok(commitment));
+        round.commit_vote(2, commitment, 20).unwrap();
+        round
+            .commit_vote(3, vote_commitment(7, &3u8, false, 1, &salt).unwrap(), 20)
+            .unwrap();
+        assert_eq!(
+            round.reveal_vote(2, true, 3, &salt, 99),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            round.reveal_vote(2, false, 3, &salt, 100),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            round.reveal_vote(4, true, 1, &salt, 100),
+            Err(FsmError::InvalidState)
+        );
+        round.reveal_vote(2, true, 3, &salt, 100).unwrap();
+        assert_eq!(
+            round.reveal_vote(2, true, 3, &salt, 110),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(round.proposal().yes_votes, 3);
+        assert_eq!(round.pending_reveals(), 1);
+        assert_eq!(round.quorum().current_percentage, 25);
+        assert!(round.is_consistent());
+        // The unrevealed vote is never counted
+        let ends_at = round.voting_ends_at().unwrap();
+        assert_eq!(
+            round.tick(ends_at),
+            Ok(TickOutcome::Closed {
+                status: ProposalStatus::QuorumFailed,
+                quorum_reached: false,
+            })
+        );
+    }
+}
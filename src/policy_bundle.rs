@@ -0,0 +1,377 @@
+//! Every deployment policy in one reviewed JSON document.
+//!
+//! A `PolicyBundle` gathers the policy objects a deployment otherwise builds
+//! in code: governance parameters, the proposal type registry (tally rules,
+//! quorum overrides, timelocks), security restrictions, reason policies, the
+//! execution retry policy, grant voting thresholds, the fast grant limits, the
+//! retention period, the clock skew tolerance and, with `definitions`, the
+//! definition size limits. `PolicyBundle::from_json` parses and validates a
+//! bundle, so a deployment can load one file at startup and refuse to start on
+//! a bad one.
+//!
+//! `validate` checks each policy as its own constructor would and then across
+//! policies: quorum overrides are at most 100, every timelock fits in the
+//! longest voting period (`VOTE_DURATION_HOURS_RANGE`) and every restricted
+//! type is registered. Accessors hand the policies to the lifecycle entry
+//! points: `activation_context` for `Proposal::activate_in`, `type_registry`
+//! for `Proposal::new_typed`, `execution_retry` for
+//! `prepare_execute_with_policy`, `grant_voting` for
+//! `Grant::finalize_voting_under`, `fast_grant` for `Idea::spawn_grant`, the
+//! reason policies for `ProposalBuilder::reason_policy` and
+//! `Grant::with_reason_policy`, and `clock_skew_tolerance_seconds` for
+//! `ProposalBuilder::clock_skew_tolerance` and
+//! `Grant::with_clock_skew_tolerance`.
+//!
+//! `version` is the bundle schema version. Bundles of a newer version are
+//! refused rather than read with fields silently dropped.
+
+#[cfg(feature = "definitions")]
+use crate::definition::DefinitionLimits;
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::governance_params::{GovernanceParams, VOTE_DURATION_HOURS_RANGE};
+use crate::grant::{GrantStatus, GrantVotingPolicy};
+use crate::idea::fast_grant::FastGrantPolicy;
+use crate::proposal::activation::{ActivationContext, SecurityPolicySet};
+use crate::proposal::execution::ExecutionRetryPolicy;
+use crate::proposal::{ProposalStatus, ProposalTypeRegistry, TypePolicy};
+use crate::reason_policy::ReasonPolicy;
+use crate::retention::DEFAULT_RETENTION_SECONDS;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Bundle schema version this release reads and writes
+pub const POLICY_BUNDLE_VERSION: u32 = 1;
+
+fn default_retention() -> DurationSecs {
+    DurationSecs::from_secs(DEFAULT_RETENTION_SECONDS)
+}
+
+/// All deployment policies (see the module docs)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyBundle {
+    /// Schema version; `POLICY_BUNDLE_VERSION` for bundles written by this release
+    pub version: u32,
+    pub governance: GovernanceParams,
+    #[serde(default)]
+    pub proposal_types: ProposalTypeRegistry,
+    #[serde(default)]
+    pub security: SecurityPolicySet,
+    #[serde(default)]
+    pub proposal_reasons: ReasonPolicy<ProposalStatus>,
+    #[serde(default)]
+    pub grant_reasons: ReasonPolicy<GrantStatus>,
+    #[serde(default)]
+    pub execution_retry: ExecutionRetryPolicy,
+    #[serde(default)]
+    pub grant_voting: GrantVotingPolicy,
+    /// How long archived entities are kept before purging
+    #[serde(default = "default_retention")]
+    pub retention: DurationSecs,
+    /// How far a transition time may lag the last event before it is refused
+    #[serde(default)]
+    pub clock_skew_tolerance_seconds: u32,
+    /// Limits of `Idea::spawn_grant`; disabled by default
+    #[serde(default)]
+    pub fast_grant: FastGrantPolicy,
+    #[cfg(feature = "definitions")]
+    #[serde(default)]
+    pub definition_limits: DefinitionLimits,
+}
+
+/// Why a bundle was refused
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PolicyBundleError {
+    /// Not a bundle document; carries the parser message
+    Parse(String),
+    /// Written for a schema this release does not read
+    UnsupportedVersion { found: u32, supported: u32 },
+    /// `field` fails its checks
+    Invalid { field: String, error: FsmError },
+}
+
+impl fmt::Display for PolicyBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyBundleError::Parse(message) => write!(f, "Invalid policy bundle: {message}"),
+            PolicyBundleError::UnsupportedVersion { found, supported } => write!(
+                f,
+                "Policy bundle version {found} is not supported (up to {supported})"
+            ),
+            PolicyBundleError::Invalid { field, error } => write!(f, "{field}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for PolicyBundleError {}
+
+fn invalid(field: impl Into<String>, error: FsmError) -> PolicyBundleError {
+    PolicyBundleError::Invalid {
+        field: field.into(),
+        error,
+    }
+}
+
+impl PolicyBundle {
+    /// Bundle of `governance` with every other policy at its default
+    pub fn new(governance: GovernanceParams) -> Self {
+        Self {
+            version: POLICY_BUNDLE_VERSION,
+            governance,
+            proposal_types: ProposalTypeRegistry::default(),
+            security: SecurityPolicySet::default(),
+            proposal_reasons: ReasonPolicy::default(),
+            grant_reasons: ReasonPolicy::default(),
+            execution_retry: ExecutionRetryPolicy::default(),
+            grant_voting: GrantVotingPolicy::default(),
+            retention: default_retention(),
+            clock_skew_tolerance_seconds: 0,
+            fast_grant: FastGrantPolicy::default(),
+            #[cfg(feature = "definitions")]
+            definition_limits: DefinitionLimits::default(),
+        }
+    }
+
+    /// Parse and `validate` a bundle
+    pub fn from_json(json: &str) -> Result<Self, PolicyBundleError> {
+        let bundle: Self =
+            serde_json::from_str(json).map_err(|e| PolicyBundleError::Parse(e.to_string()))?;
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    /// Pretty-printed JSON of the bundle
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("policy bundles serialize to JSON")
+    }
+
+    /// Check every policy and their consistency; the first problem found is returned
+    pub fn validate(&self) -> Result<(), PolicyBundleError> {
+        if self.version != POLICY_BUNDLE_VERSION {
+            return Err(PolicyBundleError::UnsupportedVersion {
+                found: self.version,
+                supported: POLICY_BUNDLE_VERSION,
+            });
+        }
+        self.governance
+            .validate()
+            .map_err(|e| invalid("governance", e))?;
+        let max_voting =
+            DurationSecs::from_hours(*VOTE_DURATION_HOURS_RANGE.end()).unwrap_or(DurationSecs::MAX);
+        for name in self.proposal_types.names() {
+            let field = || format!("proposal_types.{name}");
+            let policy = self
+                .proposal_types
+                .get(name)
+                .filter(|_| !name.trim().is_empty() && name.len() <= 50)
+                .ok_or_else(|| invalid(field(), FsmError::InvalidInput))?;
+            policy.validate().map_err(|e| invalid(field(), e))?;
+            if policy
+                .min_quorum_override
+                .is_some_and(|quorum| quorum > 100)
+            {
+                return Err(invalid(
+                    format!("{}.min_quorum_override", field()),
+                    FsmError::InvalidInput,
+                ));
+            }
+            if policy.execution_timelock > max_voting {
+                return Err(invalid(
+                    format!("{}.execution_timelock", field()),
+                    FsmError::InvalidInput,
+                ));
+            }
+        }
+        if let Some(unknown) = self
+            .security
+            .restricted_proposal_types
+            .iter()
+            .find(|name| !self.proposal_types.contains(name))
+        {
+            return Err(invalid(
+                format!("security.restricted_proposal_types.{unknown}"),
+                FsmError::InvalidInput,
+            ));
+        }
+        ExecutionRetryPolicy::new(
+            self.execution_retry.max_attempts,
+            self.execution_retry.backoff,
+        )
+        .map_err(|e| invalid("execution_retry", e))?;
+        self.grant_voting
+            .validate()
+            .map_err(|e| invalid("grant_voting", e))?;
+        if self.retention.is_negative() {
+            return Err(invalid("retention", FsmError::InvalidInput));
+        }
+        Ok(())
+    }
+
+    pub fn governance(&self) -> &GovernanceParams {
+        &self.governance
+    }
+
+    /// Registry for `Proposal::new_typed`
+    pub fn type_registry(&self) -> &ProposalTypeRegistry {
+        &self.proposal_types
+    }
+
+    pub fn type_policy(&self, proposal_type: &str) -> Option<&TypePolicy> {
+        self.proposal_types.get(proposal_type)
+    }
+
+    /// Activation gates of the bundle for a proposal of `proposal_type`
+    ///
+    /// Runs the governance params and security gates, and the type policy
+    /// gate if the type is registered; add discussion and review gates with
+    /// the `ActivationContext` builder methods.
+    pub fn activation_context(
+        &self,
+        proposal_type: &str,
+        min_quorum: u64,
+        total_members: u64,
+        current_time: i64,
+    ) -> ActivationContext<'_> {
+        let ctx = ActivationContext::new(min_quorum, total_members, current_time)
+            .params(&self.governance)
+            .security_policies(&self.security);
+        match self.type_policy(proposal_type) {
+            Some(policy) => ctx.type_policy(policy),
+            None => ctx,
+        }
+    }
+
+    pub fn proposal_reason_policy(&self) -> &ReasonPolicy<ProposalStatus> {
+        &self.proposal_reasons
+    }
+
+    pub fn grant_reason_policy(&self) -> &ReasonPolicy<GrantStatus> {
+        &self.grant_reasons
+    }
+
+    /// Policy for `prepare_execute_with_policy`
+    pub fn execution_retry(&self) -> &ExecutionRetryPolicy {
+        &self.execution_retry
+    }
+
+    /// Policy for `Grant::finalize_voting_under`
+    pub fn grant_voting(&self) -> &GrantVotingPolicy {
+        &self.grant_voting
+    }
+
+    /// Policy for `Idea::spawn_grant`
+    pub fn fast_grant(&self) -> &FastGrantPolicy {
+        &self.fast_grant
+    }
+
+    /// Retention period in seconds, as `retention::purge_eligible` takes it
+    pub fn retention_seconds(&self) -> i64 {
+        self.retention.as_secs()
+    }
+
+    #[cfg(feature = "definitions")]
+    pub fn definition_limits(&self) -> &DefinitionLimits {
+        &self.definition_limits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proposal::TallyPolicy;
+
+    fn create_bundle() -> PolicyBundle {
+        let mut bundle = PolicyBundle::new(GovernanceParams::new(50, 72, 0, false, 0).unwrap());
+        bundle
+            .proposal_types
+            .register(
+                "treasury",
+                TypePolicy {
+                    requires_treasury_op: true,
+                    tally: TallyPolicy::Supermajority { percent: 60 },
+                    min_quorum_override: Some(5),
+                    execution_timelock: DurationSecs::from_secs(86_400),
+                    ..TypePolicy::default()
+                },
+            )
+            .unwrap();
+        bundle
+    }
+
+    #[test]
+    fn test_default_bundle_validates() {
+        let bundle = create_bundle();
+        assert_eq!(bundle.validate(), Ok(()));
+        assert_eq!(bundle.retention_seconds(), DEFAULT_RETENTION_SECONDS);
+        assert_eq!(bundle.clock_skew_tolerance_seconds, 0);
+        assert_eq!(PolicyBundle::from_json(&bundle.to_json()), Ok(bundle));
+    }
+
+    #[test]
+    fn test_version_is_checked() {
+        let mut bundle = create_bundle();
+        bundle.version = 2;
+        assert_eq!(
+            bundle.validate(),
+            Err(PolicyBundleError::UnsupportedVersion {
+                found: 2,
+                supported: POLICY_BUNDLE_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn test_cross_checks_name_the_field() {
+        let field = |bundle: &PolicyBundle| match bundle.validate() {
+            Err(PolicyBundleError::Invalid { field, .. }) => field,
+            other => panic!("{other:?}"),
+        };
+        let mut bundle = create_bundle();
+        bundle.governance.quorum_percentage = 0;
+        assert_eq!(field(&bundle), "governance");
+
+        let mut bundle = create_bundle();
+        let mut policy = bundle.type_policy("treasury").unwrap().clone();
+        policy.execution_timelock = DurationSecs::from_hours(721).unwrap();
+        bundle
+            .proposal_types
+            .update("treasury", policy.clone())
+            .unwrap();
+        assert_eq!(field(&bundle), "proposal_types.treasury.execution_timelock");
+
+        policy.execution_timelock = DurationSecs::ZERO;
+        policy.min_quorum_override = Some(101);
+        bundle.proposal_types.update("treasury", policy).unwrap();
+        assert_eq!(
+            field(&bundle),
+            "proposal_types.treasury.min_quorum_override"
+        );
+
+        let mut bundle = create_bundle();
+        bundle
+            .security
+            .restricted_proposal_types
+            .push("tresury".to_string());
+        assert_eq!(field(&bundle), "security.restricted_proposal_types.tresury");
+
+        let mut bundle = create_bundle();
+        bundle.grant_voting.threshold_percent = 101;
+        assert_eq!(field(&bundle), "grant_voting");
+
+        let mut bundle = create_bundle();
+        bundle.execution_retry.max_attempts = 0;
+        assert_eq!(field(&bundle), "execution_retry");
+
+        let mut bundle = create_bundle();
+        bundle.retention = DurationSecs::from_secs(-1);
+        assert_eq!(field(&bundle), "retention");
+    }
+
+    #[test]
+    fn test_unparseable_bundle() {
+        assert!(matches!(
+            PolicyBundle::from_json("{\"version\": 1}"),
+            Err(PolicyBundleError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,79 @@
+//! Common imports in one `use`.
+//!
+//! Brings in the status enums, `FsmError`, the proposal and grant types with
+//! their builder, `DurationSecs`, `GovernanceParams`, the crate's traits and, when their
+//! features are on, the audit trail, the definition types and the governance
+//! metadata with its initializers.
+//!
+//! ```
+//! use fsm_governance_engine_lib::prelude::*;
+//!
+//! fn run() -> Result<(), FsmError> {
+//!     let mut proposal: Proposal<u8> = ProposalBuilder::new(
+//!         1,
+//!         "Fund the bridge".to_string(),
+//!         "Repairs".to_string(),
+//!         "governance".to_string(),
+//!         7,
+//!     )
+//!     .voting_duration(DurationSecs::from_secs(100))
+//!     .build(0)?;
+//!     proposal.activate_with_time(1, 10, 5)?;
+//!     assert_eq!(proposal.status, ProposalStatus::Active);
+//!
+//!     let mut grant = Grant::new(
+//!         1,
+//!         1,
+//!         [0u8; 32],
+//!         GrantCategory::Development,
+//!         GrantType::Initial,
+//!         GrantDisbursementType::Escrow,
+//!         1_000,
+//!         0,
+//!         0,
+//!     )?;
+//!     grant.approve()?;
+//!     assert_eq!(grant.status, GrantStatus::Approved);
+//!     assert!(GrantStatus::Approved.can_transition_to(GrantStatus::Active));
+//!     Ok(())
+//! }
+//! # run().unwrap();
+//! ```
+
+pub use crate::duration::DurationSecs;
+pub use crate::enums::{IdeaFsmProfile, IdeaStatus};
+pub use crate::error::FsmError;
+pub use crate::governance_params::GovernanceParams;
+pub use crate::grant::{
+    Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType, GrantVote,
+    VerificationStatus, VoteType,
+};
+pub use crate::math::ArithmeticPolicy;
+pub use crate::member::Member;
+pub use crate::metrics::MetricsSink;
+pub use crate::proposal::{
+    AmendmentStatus, Proposal, ProposalBuilder, ProposalStatus, TallyOverflow, TallyPolicy,
+    TieBreakPolicy,
+};
+pub use crate::reason::{ReasonCode, SuspensionCode};
+pub use crate::retention::Retained;
+pub use crate::time_guard::{SimClock, TimeOrdering};
+
+#[cfg(feature = "hash")]
+pub use crate::audit::{AuditEntry, AuditTrail};
+#[cfg(feature = "definitions")]
+pub use crate::definition::{
+    FsmDefaults, FsmDefinition, FsmInvariant, FsmTransition, FsmTransitionMetadata,
+    FsmTransitionRef, ValidationReport,
+};
+#[cfg(feature = "governance-meta")]
+pub use crate::governance::{
+    GovernanceAnalyticsStatus, GovernanceParticipationStatus, GovernanceVotingStatus,
+    QuorumMetadata, SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus,
+    SecurityBoardMemberMetadata, SecurityPolicyStatus, initialize_board_member,
+    initialize_committee, initialize_decision, initialize_governance_analytics,
+    initialize_governance_participation, initialize_governance_voting,
+    initialize_governance_voting_for, initialize_lifecycle, initialize_policy,
+    initialize_quorum_metadata, initialize_score, update_quorum_from_counts,
+    update_quorum_percentage, update_required_percentage,
+};
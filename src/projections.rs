@@ -0,0 +1,503 @@
+//! Read-model projections for dashboards.
+//!
+//! Aggregates are computed with integer math only. Rates are in basis points
+//! and averages in seconds, both rounded half up. Entities that completed a
+//! stage but lack the timestamps to measure it are counted in `skipped`
+//! rather than guessed at.
+
+use crate::grant::{Grant, GrantStatus};
+use crate::proposal::{Proposal, ProposalStatus};
+use std::collections::BTreeMap;
+
+/// Denominator of conversion rates (basis points)
+pub const RATE_SCALE: u64 = 10_000;
+
+const PROPOSAL_STATUSES: [ProposalStatus; 10] = [
+    ProposalStatus::Draft,
+    ProposalStatus::Active,
+    ProposalStatus::Passed,
+    ProposalStatus::Executing,
+    ProposalStatus::Rejected,
+    ProposalStatus::Executed,
+    ProposalStatus::Cancelled,
+    ProposalStatus::Archived,
+    ProposalStatus::Tied,
+    ProposalStatus::QuorumFailed,
+];
+
+const GRANT_STATUSES: [GrantStatus; 9] = [
+    GrantStatus::Pending,
+    GrantStatus::Approved,
+    GrantStatus::Active,
+    GrantStatus::Suspended,
+    GrantStatus::Completed,
+    GrantStatus::Cancelled,
+    GrantStatus::Rejected,
+    GrantStatus::Expired,
+    GrantStatus::Archived,
+];
+
+/// Average stage duration in seconds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AverageDuration {
+    /// Rounded half up; None without samples
+    pub average: Option<i64>,
+    /// Entities measured
+    pub samples: u64,
+    /// Entities that completed the stage but lack usable timestamps
+    pub skipped: u64,
+}
+
+#[derive(Default)]
+struct DurationAccumulator {
+    sum: i128,
+    samples: u64,
+    skipped: u64,
+}
+
+impl DurationAccumulator {
+    /// Record a stage that was completed; `start`/`end` may be missing
+    fn add(&mut self, start: Option<i64>, end: Option<i64>) {
+        match (start, end) {
+            (Some(start), Some(end)) if end >= start => {
+                self.sum += i128::from(end) - i128::from(start);
+                self.samples += 1;
+            }
+            _ => self.skipped += 1,
+        }
+    }
+
+    fn finish(self) -> AverageDuration {
+        let average = (self.samples > 0).then(|| {
+            let samples = i128::from(self.samples);
+            // Durations are non-negative, so floor((2 * sum + n) / 2n) rounds half up
+            let rounded = (2 * self.sum + samples) / (2 * samples);
+            i64::try_from(rounded).unwrap_or(i64::MAX)
+        });
+        AverageDuration {
+            average,
+            samples: self.samples,
+            skipped: self.skipped,
+        }
+    }
+}
+
+/// `numerator / denominator` in basis points, rounded half up
+pub(crate) fn rate_bps(numerator: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let scaled = u128::from(numerator) * u128::from(RATE_SCALE);
+    let denominator = u128::from(denominator);
+    let rounded = (2 * scaled + denominator) / (2 * denominator);
+    Some(u64::try_from(rounded).unwrap_or(u64::MAX))
+}
+
+/// Proposal funnel
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Funnel {
+    /// Count per status, in declaration order of `ProposalStatus`
+    pub status_counts: Vec<(ProposalStatus, u64)>,
+    pub total: u64,
+    /// Proposals that reached Active
+    pub activated: u64,
+    /// Proposals that reached Passed
+    pub passed: u64,
+    /// Proposals whose vote ended short of quorum (status QuorumFailed); not in `passed`
+    pub quorum_failed: u64,
+    /// Proposals that reached Executed
+    pub executed: u64,
+    /// `activated / total` in basis points
+    pub draft_to_active_bps: Option<u64>,
+    /// `passed / activated` in basis points
+    pub active_to_passed_bps: Option<u64>,
+    /// `executed / passed` in basis points
+    pub passed_to_executed_bps: Option<u64>,
+    /// `created_at` to `submitted_at`
+    pub draft_duration: AverageDuration,
+    /// `submitted_at` to `executed_at` (voting plus execution)
+    pub submitted_to_executed_duration: AverageDuration,
+}
+
+/// Build the proposal funnel
+///
+/// Archived and Cancelled proposals count as having reached a stage only if the
+/// timestamp of that stage is set, since their status no longer says.
+pub fn proposal_funnel<P>(proposals: &[Proposal<P>]) -> Funnel {
+    funnel_of(proposals)
+}
+
+/// Funnel per tag, in tag order
+///
+/// A proposal with several tags counts in each of them; untagged proposals
+/// appear in none.
+pub fn proposal_funnel_by_tag<P>(proposals: &[Proposal<P>]) -> Vec<(String, Funnel)> {
+    by_tag(proposals, |p| &p.tags)
+        .into_iter()
+        .map(|(tag, tagged)| (tag, funnel_of(tagged)))
+        .collect()
+}
+
+/// Entities grouped by each of their tags
+fn by_tag<T>(entities: &[T], tags: impl Fn(&T) -> &Vec<String>) -> BTreeMap<String, Vec<&T>> {
+    let mut groups: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    for entity in entities {
+        for tag in tags(entity) {
+            groups.entry(tag.clone()).or_default().push(entity);
+        }
+    }
+    groups
+}
+
+fn funnel_of<'a, P: 'a>(proposals: impl IntoIterator<Item = &'a Proposal<P>>) -> Funnel {
+    let mut funnel = Funnel {
+        status_counts: PROPOSAL_STATUSES.iter().map(|s| (s.clone(), 0)).collect(),
+        ..Funnel::default()
+    };
+    let mut draft = DurationAccumulator::default();
+    let mut submitted_to_executed = DurationAccumulator::default();
+
+    for proposal in proposals {
+        funnel.total += 1;
+        if let Some(entry) = funnel
+            .status_counts
+            .iter_mut()
+            .find(|(status, _)| *status == proposal.status)
+        {
+            entry.1 += 1;
+        }
+
+        let executed =
+            proposal.status == ProposalStatus::Executed || proposal.executed_at.is_some();
+        let passed = executed
+            || matches!(
+                proposal.status,
+                ProposalStatus::Passed | ProposalStatus::Executing
+            );
+        let activated = passed
+            || proposal.submitted_at.is_some()
+            || matches!(
+                proposal.status,
+                ProposalStatus::Active
+                    | ProposalStatus::Rejected
+                    | ProposalStatus::Tied
+                    | ProposalStatus::QuorumFailed
+            );
+
+        if activated {
+            funnel.activated += 1;
+            draft.add(Some(proposal.created_at), proposal.submitted_at);
+        }
+        if passed {
+            funnel.passed += 1;
+        }
+        if proposal.status == ProposalStatus::QuorumFailed {
+            funnel.quorum_failed += 1;
+        }
+        if executed {
+            funnel.executed += 1;
+            submitted_to_executed.add(proposal.submitted_at, proposal.executed_at);
+        }
+    }
+
+    funnel.draft_to_active_bps = rate_bps(funnel.activated, funnel.total);
+    funnel.active_to_passed_bps = rate_bps(funnel.passed, funnel.activated);
+    funnel.passed_to_executed_bps = rate_bps(funnel.executed, funnel.passed);
+    funnel.draft_duration = draft.finish();
+    funnel.submitted_to_executed_duration = submitted_to_executed.finish();
+    funnel
+}
+
+/// Totals for one grant status
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StatusTotals {
+    pub count: u64,
+    pub total_amount: u128,
+    pub disbursed: u128,
+}
+
+/// Grant portfolio
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Portfolio {
+    /// `total_amount` of Approved, Active, Suspended and Completed grants
+    pub committed: u128,
+    /// `disbursed_amount` across all grants
+    pub disbursed: u128,
+    /// Totals per status, in declaration order of `GrantStatus`
+    pub by_status: Vec<(GrantStatus, StatusTotals)>,
+    /// `activated_at` to `completed_at` of completed grants
+    pub active_to_completed_duration: AverageDuration,
+}
+
+/// Build the grant portfolio
+pub fn grant_portfolio(grants: &[Grant]) -> Portfolio {
+    portfolio_of(grants)
+}
+
+/// Portfolio per tag, in tag order
+///
+/// A grant with several tags counts in each of them; untagged grants appear
+/// in none.
+pub fn grant_portfolio_by_tag(grants: &[Grant]) -> Vec<(String, Portfolio)> {
+    by_tag(grants, |g| &g.tags)
+        .into_iter()
+        .map(|(tag, tagged)| (tag, portfolio_of(tagged)))
+        .collect()
+}
+
+fn portfolio_of<'a>(grants: impl IntoIterator<Item = &'a Grant>) -> Portfolio {
+    let mut portfolio = Portfolio {
+        by_status: GRANT_STATUSES
+            .iter()
+            .map(|status| (*status, StatusTotals::default()))
+            .collect(),
+        ..Portfolio::default()
+    };
+    let mut active_to_completed = DurationAccumulator::default();
+
+    for grant in grants {
+        if let Some((_, totals)) = portfolio
+            .by_status
+            .iter_mut()
+            .find(|(status, _)| *status == grant.status)
+        {
+            totals.count += 1;
+            totals.total_amount += u128::from(grant.total_amount);
+            totals.disbursed += u128::from(grant.disbursed_amount);
+        }
+        if matches!(
+            grant.status,
+            GrantStatus::Approved
+                | GrantStatus::Active
+                | GrantStatus::Suspended
+                | GrantStatus::Completed
+        ) {
+            portfolio.committed += u128::from(grant.total_amount);
+        }
+        portfolio.disbursed += u128::from(grant.disbursed_amount);
+
+        if grant.status == GrantStatus::Completed || grant.completed_at.is_some() {
+            active_to_completed.add(grant.activated_at, grant.completed_at);
+        }
+    }
+
+    portfolio.active_to_completed_duration = active_to_completed.finish();
+    portfolio
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+
+    fn create_test_proposal(id: u64, created_at: i64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .build(created_at)
+        .unwrap()
+    }
+
+    fn create_test_grant(id: u64, amount: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            amount,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn count(funnel: &Funnel, status: ProposalStatus) -> u64 {
+        funnel
+            .status_counts
+            .iter()
+            .find(|(s, _)| *s == status)
+            .unwrap()
+            .1
+    }
+
+    #[test]
+    fn test_proposal_funnel() {
+        let draft = create_test_proposal(1, 0);
+
+        let mut rejected = create_test_proposal(2, 0);
+        rejected.activate_with_time(1, 10, 10).unwrap();
+        rejected.reject_with_time(20).unwrap();
+
+        let mut executed = create_test_proposal(3, 0);
+        executed.activate_with_time(1, 10, 5).unwrap();
+        executed.pass_with_time(20).unwrap();
+        executed.execute_with_time(30).unwrap();
+
+        // Executed without a submission timestamp: counted, not measured
+        let mut legacy = create_test_proposal(4, 0);
+        legacy.status = ProposalStatus::Executed;
+        legacy.executed_at = Some(50);
+
+        let funnel = proposal_funnel(&[draft, rejected, executed, legacy]);
+        assert_eq!(funnel.total, 4);
+        assert_eq!(count(&funnel, ProposalStatus::Draft), 1);
+        assert_eq!(count(&funnel, ProposalStatus::Executed), 2);
+        assert_eq!(funnel.activated, 3);
+        assert_eq!(funnel.passed, 2);
+        assert_eq!(funnel.executed, 2);
+        assert_eq!(funnel.draft_to_active_bps, Some(7_500));
+        // 2 / 3 = 6666.67 bps, rounded up
+        assert_eq!(funnel.active_to_passed_bps, Some(6_667));
+        assert_eq!(funnel.passed_to_executed_bps, Some(10_000));
+
+        // (10 + 5) / 2 = 7.5, rounded half up
+        assert_eq!(
+            funnel.draft_duration,
+            AverageDuration {
+                average: Some(8),
+                samples: 2,
+                skipped: 1,
+            }
+        );
+        assert_eq!(
+            funnel.submitted_to_executed_duration,
+            AverageDuration {
+                average: Some(25),
+                samples: 1,
+                skipped: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_quorum_failures_counted_separately() {
+        let mut rejected = create_test_proposal(1, 0);
+        rejected.activate_with_time(1, 10, 0).unwrap();
+        rejected.reject_with_time(10).unwrap();
+        let mut quorum_failed = create_test_proposal(2, 0);
+        quorum_failed.activate_with_time(1, 10, 0).unwrap();
+        quorum_failed.yes_votes = 5;
+        quorum_failed
+            .auto_transition_with_quorum(0, 20, 10)
+            .unwrap();
+
+        let funnel = proposal_funnel(&[rejected, quorum_failed]);
+        assert_eq!(funnel.activated, 2);
+        assert_eq!(funnel.passed, 0);
+        assert_eq!(funnel.quorum_failed, 1);
+        assert_eq!(count(&funnel, ProposalStatus::Rejected), 1);
+        assert_eq!(count(&funnel, ProposalStatus::QuorumFailed), 1);
+    }
+
+    #[test]
+    fn test_empty_funnel() {
+        let funnel = proposal_funnel::<u8>(&[]);
+        assert_eq!(funnel.total, 0);
+        assert_eq!(funnel.draft_to_active_bps, None);
+        assert_eq!(funnel.draft_duration.average, None);
+        assert_eq!(funnel.status_counts.len(), PROPOSAL_STATUSES.len());
+    }
+
+    #[test]
+    fn test_grant_portfolio() {
+        let pending = create_test_grant(1, 100);
+
+        let mut active = create_test_grant(2, 1_000);
+        active.approve().unwrap();
+        active.activate_with_time(10).unwrap();
+        active.disburse_with_time(400, 20).unwrap();
+
+        let mut completed = create_test_grant(3, 500);
+        completed.approve().unwrap();
+        completed.activate_with_time(100).unwrap();
+        completed.disburse_with_time(500, 201).unwrap();
+
+        // Completed through the untimed API: no timestamps to measure
+        let mut untimed = create_test_grant(4, 300);
+        untimed.approve().unwrap();
+        untimed.activate().unwrap();
+        untimed.disburse(300).unwrap();
+
+        let portfolio = grant_portfolio(&[pending, active, completed, untimed]);
+        assert_eq!(portfolio.committed, 1_800);
+        assert_eq!(portfolio.disbursed, 1_200);
+
+        let completed_totals = portfolio
+            .by_status
+            .iter()
+            .find(|(status, _)| *status == GrantStatus::Completed)
+            .unwrap()
+            .1;
+        assert_eq!(
+            completed_totals,
+            StatusTotals {
+                count: 2,
+                total_amount: 800,
+                disbursed: 800,
+            }
+        );
+        assert_eq!(
+            portfolio.active_to_completed_duration,
+            AverageDuration {
+                average: Some(101),
+                samples: 1,
+                skipped: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_breakdown_by_tag() {
+        let mut untagged = create_test_proposal(1, 0);
+        untagged.activate_with_time(1, 10, 5).unwrap();
+        let mut infra = create_test_proposal(2, 0);
+        infra.add_tag("infrastructure", None).unwrap();
+        infra.activate_with_time(1, 10, 5).unwrap();
+        infra.pass_with_time(20).unwrap();
+        let mut both = create_test_proposal(3, 0);
+        both.add_tag("marketing", None).unwrap();
+        both.add_tag("infrastructure", None).unwrap();
+
+        let by_tag = proposal_funnel_by_tag(&[untagged, infra, both]);
+        let tags: Vec<&str> = by_tag.iter().map(|(tag, _)| tag.as_str()).collect();
+        assert_eq!(tags, vec!["infrastructure", "marketing"]);
+        assert_eq!((by_tag[0].1.total, by_tag[0].1.passed), (2, 1));
+        assert_eq!(by_tag[0].1.active_to_passed_bps, Some(10_000));
+        assert_eq!((by_tag[1].1.total, by_tag[1].1.activated), (1, 0));
+
+        let mut research = create_test_grant(1, 100);
+        research.add_tag("research", None).unwrap();
+        let by_tag = grant_portfolio_by_tag(&[research, create_test_grant(2, 50)]);
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].0, "research");
+        assert_eq!(by_tag[0].1.by_status[0].1.total_amount, 100);
+    }
+
+    #[test]
+    fn test_rounding_and_out_of_order_timestamps() {
+        assert_eq!(rate_bps(1, 3), Some(3_333));
+        assert_eq!(rate_bps(1, 20_000), Some(1));
+        assert_eq!(rate_bps(1, 20_001), Some(0));
+
+        let mut accumulator = DurationAccumulator::default();
+        accumulator.add(Some(10), Some(5));
+        accumulator.add(Some(0), Some(3));
+        accumulator.add(Some(0), Some(4));
+        assert_eq!(
+            accumulator.finish(),
+            AverageDuration {
+                average: Some(4),
+                samples: 2,
+                skipped: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Proposal activation gates
+//!
+//! `ActivationContext` bundles everything activation may be checked against, so
+//! new requirements add an optional field instead of another parameter.
+//! `Proposal::activate_in` runs the gates in a fixed order and stops at the
+//! first failure:
+//!
+//! 0. `current_time` respects `time_ordering` (`TimeRegression`)
+//! 1. Status is Draft (`InvalidInput`)
+//! 2. Member quorum: eligible members `>= min_quorum` (`InsufficientMembers`),
+//!    then `min_quorum` and the eligible count non-zero (`InvalidInput`)
+//! 3. Governance params: voting period at least `vote_duration_hours` (`InvalidInput`)
+//! 4. Security policies: proposal type not restricted (`InvalidState`)
+//! 5. Discussion: enough comments (`InvalidState`)
+//! 6. Review period: `min_review` seconds since creation (`InvalidState`)
+//! 7. Type policy: treasury data present if required (`InvalidState`), then
+//!    eligible members at least `min_quorum_override` (`InsufficientMembers`)
+//! 8. Sponsors: `sponsor_count` at least `min_sponsors` (`InsufficientSponsors`)
+//!
+//! Gates 1-2 are the checks `activate_with_time` has always made, in the same order.
+//!
+//! The eligible member count is `total_members` under the default
+//! `EligibilityPolicy::AllMembers` and `active_members` (for example from
+//! `quorum::active_member_count`) under `ActiveMembers`. The report records
+//! both counts whenever `active_members` is given, so the effect of the policy
+//! is visible.
+use super::type_registry::TypePolicy;
+use super::types::{Proposal, ProposalStatus};
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::governance_params::GovernanceParams;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Security restrictions applied at activation
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SecurityPolicySet {
+    /// Proposal types that may not be activated
+    pub restricted_proposal_types: Vec<String>,
+}
+impl SecurityPolicySet {
+    /// Whether `proposal_type` may be activated
+    pub fn allows(&self, proposal_type: &str) -> bool {
+        !self
+            .restricted_proposal_types
+            .iter()
+            .any(|restricted| restricted == proposal_type)
+    }
+}
+/// Discussion needed before activation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiscussionRequirement {
+    pub min_comments: u64,
+    /// Comments observed so far
+    pub comments: u64,
+}
+/// Gate evaluated during activation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivationCheck {
+    Status,
+    Quorum,
+    GovernanceParams,
+    SecurityPolicy,
+    Discussion,
+    ReviewPeriod,
+    TypePolicy,
+    Sponsors,
+}
+/// Which member count activation quorum math uses
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EligibilityPolicy {
+    /// Every member, `ActivationContext::total_members`
+    #[default]
+    AllMembers,
+    /// Recently active members only, `ActivationContext::active_members`
+    ActiveMembers,
+}
+/// Gates that passed, in evaluation order
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActivationReport {
+    pub evaluated: Vec<ActivationCheck>,
+    /// `ActivationContext::total_members`
+    pub total_members: u64,
+    /// `ActivationContext::active_members`, if given
+    pub active_members: Option<u64>,
+    /// The count quorum math used under the eligibility policy
+    pub eligible_members: u64,
+}
+impl ActivationReport {
+    /// Whether `check` was evaluated
+    pub fn evaluated(&self, check: ActivationCheck) -> bool {
+        self.evaluated.contains(&check)
+    }
+}
+/// Inputs to `Proposal::activate_in`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ActivationContext<'a> {
+    pub min_quorum: u64,
+    pub total_members: u64,
+    pub current_time: i64,
+    pub params: Option<&'a GovernanceParams>,
+    pub security_policies: Option<&'a SecurityPolicySet>,
+    pub discussion: Option<DiscussionRequirement>,
+    /// Minimum seconds between creation and activation
+    pub min_review: Option<i64>,
+    pub type_policy: Option<&'a TypePolicy>,
+    /// Members active within the inactivity cutoff; at most `total_members`
+    pub active_members: Option<u64>,
+    pub eligibility_policy: EligibilityPolicy,
+    /// Minimum sponsors, the author included (see `sponsors`)
+    pub min_sponsors: Option<u32>,
+}
+impl<'a> ActivationContext<'a> {
+    /// Context with only the member quorum gate
+    pub fn new(min_quorum: u64, total_members: u64, current_time: i64) -> Self {
+        Self {
+            min_quorum,
+            total_members,
+            current_time,
+            params: None,
+            security_policies: None,
+            discussion: None,
+            min_review: None,
+            type_policy: None,
+            active_members: None,
+            eligibility_policy: EligibilityPolicy::AllMembers,
+            min_sponsors: None,
+        }
+    }
+    pub fn params(mut self, params: &'a GovernanceParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+    pub fn security_policies(mut self, policies: &'a SecurityPolicySet) -> Self {
+        self.security_policies = Some(policies);
+        self
+    }
+    pub fn discussion(mut self, discussion: DiscussionRequirement) -> Self {
+        self.discussion = Some(discussion);
+        self
+    }
+    pub fn min_review(mut self, seconds: i64) -> Self {
+        self.min_review = Some(seconds);
+        self
+    }
+    pub fn type_policy(mut self, policy: &'a TypePolicy) -> Self {
+        self.type_policy = Some(policy);
+        self
+    }
+    pub fn active_members(mut self, active_members: u64) -> Self {
+        self.active_members = Some(active_members);
+        self
+    }
+    pub fn eligibility_policy(mut self, policy: EligibilityPolicy) -> Self {
+        self.eligibility_policy = policy;
+        self
+    }
+    /// Member count used for quorum math under `eligibility_policy`
+    ///
+    /// `InvalidInput` if `ActiveMembers` is selected without `active_members`,
+    /// or if `active_members` exceeds `total_members`.
+    pub fn eligible_members(&self) -> Result<u64, FsmError> {
+        if self
+            .active_members
+            .is_some_and(|active| active > self.total_members)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        match self.eligibility_policy {
+            EligibilityPolicy::AllMembers => Ok(self.total_members),
+            EligibilityPolicy::ActiveMembers => self.active_members.ok_or(FsmError::InvalidInput),
+        }
+    }
+    pub fn min_sponsors(mut self, min_sponsors: u32) -> Self {
+        self.min_sponsors = Some(min_sponsors);
+        self
+    }
+}
+impl<P> Proposal<P> {
+    /// Activate proposal (move from Draft to Active) after running every gate in `ctx`
+    pub fn activate_in(&mut self, ctx: &ActivationContext) -> Result<ActivationReport, FsmError> {
+        let mut report = ActivationReport::default();
+        self.observe_timed_transition("activate", ctx.current_time, |proposal, current_time| {
+            proposal.check_activation(ctx, &mut report)?;
+            proposal.status = ProposalStatus::Active;
+            proposal.submitted_at = Some(current_time);
+            Ok(())
+        })?;
+        Ok(report)
+    }
+    fn check_activation(
+        &self,
+        ctx: &ActivationContext,
+        report: &mut ActivationReport,
+    ) -> Result<(), FsmError> {
+        if self.status != ProposalStatus::Draft {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        report.evaluated.push(ActivationCheck::Status);
+
+        let eligible_members = ctx.eligible_members()?;
+        report.total_members = ctx.total_members;
+        report.active_members = ctx.active_members;
+        report.eligible_members = eligible_members;
+        if eligible_members < ctx.min_quorum {
+            return Err(FsmError::InsufficientMembers);
+        }
+        if ctx.min_quorum == 0 || eligible_members == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        report.evaluated.push(ActivationCheck::Quorum);
+
+        if let Some(params) = ctx.params {
+            let min_duration =
+                DurationSecs::from_hours(params.vote_duration_hours).ok_or(FsmError::Overflow)?;
+            if self.voting_duration < min_duration {
+                return Err(FsmError::InvalidInput);
+            }
+            report.evaluated.push(ActivationCheck::GovernanceParams);
+        }
+
+        if let Some(policies) = ctx.security_policies {
+            if !policies.allows(&self.proposal_type) {
+                return Err(FsmError::InvalidState);
+            }
+            report.evaluated.push(ActivationCheck::SecurityPolicy);
+        }
+
+        if let Some(discussion) = ctx.discussion {
+            if discussion.comments < discussion.min_comments {
+                return Err(FsmError::InvalidState);
+            }
+            report.evaluated.push(ActivationCheck::Discussion);
+        }
+
+        if let Some(min_review) = ctx.min_review {
+            if min_review < 0 {
+                return Err(FsmError::InvalidInput);
+            }
+            let review_end = self
+                .created_at
+                .checked_add(min_review)
+                .ok_or(FsmError::Overflow)?;
+            if ctx.current_time < review_end {
+                return Err(FsmError::InvalidState);
+            }
+            report.evaluated.push(ActivationCheck::ReviewPeriod);
+        }
+
+        if let Some(policy) = ctx.type_policy {
+            if policy.requires_treasury_op && self.treasury_operation.is_none() {
+                return Err(FsmError::InvalidState);
+            }
+            if let Some(min_members) = policy.min_quorum_override
+                && eligible_members < u64::from(min_members)
+            {
+                return Err(FsmError::InsufficientMembers);
+            }
+            report.evaluated.push(ActivationCheck::TypePolicy);
+        }
+
+        if let Some(required) = ctx.min_sponsors {
+            let actual = u32::try_from(self.sponsor_count()).unwrap_or(u32::MAX);
+            if actual < required {
+                return Err(FsmError::InsufficientSponsors { required, actual });
+            }
+            report.evaluated.push(ActivationCheck::Sponsors);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const DAY: i64 = 24 * 3600;
+    fn create_test_proposal() -> Proposal<u8> {
+        Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "grant".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(2 * DAY))
+        .build(1000)
+        .unwrap()
+    }
+    fn create_test_params() -> GovernanceParams {
+        GovernanceParams::new(50, 48, 30, false, 0).unwrap()
+    }
+    #[test]
+    fn test_activate_in_all_gates_pass() {
+        let params = create_test_params();
+        let policies = SecurityPolicySet {
+            restricted_proposal_types: vec!["emergency".to_string()],
+        };
+        let ctx = ActivationContext::new(3, 10, 1000 + DAY)
+            .params(&params)
+            .security_policies(&policies)
+            .discussion(DiscussionRequirement {
+                min_comments: 2,
+                comments: 5,
+            })
+            .min_review(DAY);
+
+        let mut proposal = create_test_proposal();
+        let report = proposal.activate_in(&ctx).unwrap();
+        assert_eq!(
+            report.evaluated,
+            vec![
+                ActivationCheck::Status,
+                ActivationCheck::Quorum,
+                ActivationCheck::GovernanceParams,
+                ActivationCheck::SecurityPolicy,
+                ActivationCheck::Discussion,
+                ActivationCheck::ReviewPeriod,
+            ]
+        );
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert_eq!(proposal.submitted_at, Some(1000 + DAY));
+    }
+    #[test]
+    fn test_minimal_context_reports_only_base_gates() {
+        let mut proposal = create_test_proposal();
+        let report = proposal
+            .activate_in(&ActivationContext::new(1, 1, 2000))
+            .unwrap();
+        assert!(report.evaluated(ActivationCheck::Quorum));
+        assert!(!report.evaluated(ActivationCheck::GovernanceParams));
+        assert_eq!(report.evaluated.len(), 2);
+    }
+    #[test]
+    fn test_status_gate() {
+        let mut proposal = create_test_proposal();
+        proposal.status = ProposalStatus::Active;
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 1, 2000)),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+    #[test]
+    fn test_quorum_gate() {
+        let mut proposal = create_test_proposal();
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(5, 4, 2000)),
+            Err(FsmError::InsufficientMembers)
+        );
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(0, 4, 2000)),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+    }
+    #[test]
+    fn test_eligibility_policy() {
+        // 10 members, 4 of them active; quorum needs 5
+        let all = ActivationContext::new(5, 10, 2000).active_members(4);
+        let mut proposal = create_test_proposal();
+        assert_eq!(
+            proposal.activate_in(
+                &all.clone()
+                    .eligibility_policy(EligibilityPolicy::ActiveMembers)
+            ),
+            Err(FsmError::InsufficientMembers)
+        );
+        let report = proposal.activate_in(&all).unwrap();
+        assert_eq!(
+            (
+                report.total_members,
+                report.active_members,
+                report.eligible_members
+            ),
+            (10, Some(4), 10)
+        );
+
+        let mut proposal = create_test_proposal();
+        let report = proposal
+            .activate_in(
+                &ActivationContext::new(3, 10, 2000)
+                    .active_members(4)
+                    .eligibility_policy(EligibilityPolicy::ActiveMembers),
+            )
+            .unwrap();
+        assert_eq!(report.eligible_members, 4);
+    }
+    #[test]
+    fn test_eligibility_policy_needs_a_consistent_active_count() {
+        let mut proposal = create_test_proposal();
+        let ctx = ActivationContext::new(1, 10, 2000)
+            .eligibility_policy(EligibilityPolicy::ActiveMembers);
+        assert_eq!(proposal.activate_in(&ctx), Err(FsmError::InvalidInput));
+        assert_eq!(
+            proposal.activate_in(&ctx.active_members(11)),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+    }
+    #[test]
+    fn test_params_gate() {
+        let params = GovernanceParams::new(50, 72, 30, false, 0).unwrap();
+        let mut proposal = create_test_proposal();
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 1, 2000).params(&params)),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+    }
+    #[test]
+    fn test_security_policy_gate() {
+        let policies = SecurityPolicySet {
+            restricted_proposal_types: vec!["grant".to_string()],
+        };
+        let mut proposal = create_test_proposal();
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 1, 2000).security_policies(&policies)),
+            Err(FsmError::InvalidState)
+        );
+    }
+    #[test]
+    fn test_discussion_gate() {
+        let mut proposal = create_test_proposal();
+        let ctx = ActivationContext::new(1, 1, 2000).discussion(DiscussionRequirement {
+            min_comments: 3,
+            comments: 2,
+        });
+        assert_eq!(proposal.activate_in(&ctx), Err(FsmError::InvalidState));
+    }
+    #[test]
+    fn test_review_period_gate() {
+        let mut proposal = create_test_proposal();
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 1, 1000 + DAY - 1).min_review(DAY)),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 1, 2000).min_review(-1)),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+    }
+    #[test]
+    fn test_error_precedence() {
+        // Quorum is checked before every optional gate
+        let policies = SecurityPolicySet {
+            restricted_proposal_types: vec!["grant".to_string()],
+        };
+        let mut proposal = create_test_proposal();
+        let ctx = ActivationContext::new(5, 4, 1000)
+            .security_policies(&policies)
+            .min_review(DAY);
+        assert_eq!(
+            proposal.activate_in(&ctx),
+            Err(FsmError::InsufficientMembers)
+        );
+
+        // Params are checked before the policy gate
+        let params = GovernanceParams::new(50, 72, 30, false, 0).unwrap();
+        let ctx = ActivationContext::new(1, 1, 1000)
+            .params(&params)
+            .security_policies(&policies);
+        assert_eq!(proposal.activate_in(&ctx), Err(FsmError::InvalidInput));
+    }
+}
@@ -1,8 +1,24 @@
 //! Proposal Amendment module
 //!
-//! Handles amendments to proposals during the Draft phase
+//! Handles amendments to proposals during the Draft phase. An amendment is
+//! voted on before it changes the parent: Proposed -> Voting -> Accepted or
+//! Rejected, or Withdrawn before a decision. Voting runs only while the
+//! parent is Draft, unless the amendment allows an Active parent; accepting
+//! such an amendment restarts the parent's voting clock when applied.
+use super::types::{Proposal, ProposalStatus};
+use super::votes::VoteRecord;
 use crate::error::FsmError;
-use std::marker::PhantomData;
+use crate::metrics;
+/// Amendment status
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmendmentStatus {
+    #[default]
+    Proposed,
+    Voting,
+    Accepted,
+    Rejected,
+    Withdrawn,
+}
 /// Proposal Amendment account structure
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ProposalAmendment<P> {
@@ -11,7 +27,16 @@ pub struct ProposalAmendment<P> {
     pub author: P,
     pub content: String,
     pub created_at: i64,
-    _phantom: PhantomData<P>,
+    pub status: AmendmentStatus,
+    /// Voting may run while the parent is Active
+    pub allow_active_parent: bool,
+    /// Set by `open_amendment_voting`
+    pub voting_ends_at: Option<i64>,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub votes: Vec<VoteRecord<P>>,
+    /// When the amendment was accepted, rejected or withdrawn
+    pub resolved_at: Option<i64>,
 }
 impl<P> ProposalAmendment<P> {
     /// Create a new proposal amendment
@@ -43,14 +68,176 @@ impl<P> ProposalAmendment<P> {
             author,
             content,
             created_at: current_time,
-            _phantom: PhantomData,
+            status: AmendmentStatus::Proposed,
+            allow_active_parent: false,
+            voting_ends_at: None,
+            yes_votes: 0,
+            no_votes: 0,
+            votes: Vec::new(),
+            resolved_at: None,
         })
     }
+    /// Allow voting while the parent proposal is Active
+    pub fn with_active_parent_allowed(mut self) -> Self {
+        self.allow_active_parent = true;
+        self
+    }
+    fn transition(&mut self, to: AmendmentStatus) -> Result<(), FsmError> {
+        if self.status == to {
+            return Err(FsmError::InvalidState);
+        }
+        self.status.validate_transition(to)?;
+        self.status = to;
+        Ok(())
+    }
+    fn check_parent(&self, parent: &Proposal<P>) -> Result<(), FsmError> {
+        if parent.id != self.proposal_id {
+            return Err(FsmError::InvalidInput);
+        }
+        match parent.status {
+            ProposalStatus::Draft => Ok(()),
+            ProposalStatus::Active if self.allow_active_parent => Ok(()),
+            _ => Err(FsmError::InvalidState),
+        }
+    }
+    /// Start voting for `duration` seconds
+    ///
+    /// Errors: `InvalidInput` if `duration` is not positive or `parent` is
+    /// another proposal, `InvalidState` if the parent is not Draft (or Active
+    /// when allowed), `InvalidStateTransition` unless Proposed,
+    /// `TimeRegression` before `created_at`.
+    pub fn open_amendment_voting(
+        &mut self,
+        parent: &Proposal<P>,
+        duration: i64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if duration <= 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.check_parent(parent)?;
+        if current_time < self.created_at {
+            return Err(FsmError::TimeRegression);
+        }
+        let ends_at = current_time
+            .checked_add(duration)
+            .ok_or(FsmError::Overflow)?;
+        self.transition(AmendmentStatus::Voting)?;
+        self.voting_ends_at = Some(ends_at);
+        Ok(())
+    }
+    /// Record a vote while voting is open
+    ///
+    /// Errors: `InvalidInput` for zero weight, `InvalidState` if not Voting,
+    /// voting has ended or `voter` already voted, `Overflow` past `u64::MAX`.
+    pub fn cast_amendment_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: PartialEq,
+    {
+        let result = self.try_cast_amendment_vote(voter, support, weight, current_time);
+        metrics::record_vote("proposal_amendment", "cast", &result);
+        result
+    }
+    fn try_cast_amendment_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: PartialEq,
+    {
+        if weight == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.status != AmendmentStatus::Voting
+            || self.voting_ends_at.is_none_or(|end| current_time >= end)
+            || self.votes.iter().any(|vote| vote.voter == voter)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        if support {
+            self.yes_votes = self
+                .yes_votes
+                .checked_add(weight)
+                .ok_or(FsmError::Overflow)?;
+        } else {
+            self.no_votes = self
+                .no_votes
+                .checked_add(weight)
+                .ok_or(FsmError::Overflow)?;
+        }
+        self.votes.push(VoteRecord {
+            voter,
+            support,
+            weight,
+            cast_at: current_time,
+        });
+        Ok(())
+    }
+    /// Close voting once it has ended: Accepted on more yes than no, else Rejected
+    ///
+    /// Returns the new status. `InvalidState` before `voting_ends_at`,
+    /// `InvalidStateTransition` unless Voting.
+    pub fn finalize_amendment(&mut self, current_time: i64) -> Result<AmendmentStatus, FsmError> {
+        self.status.validate_transition(AmendmentStatus::Accepted)?;
+        if self.voting_ends_at.is_some_and(|end| current_time < end) {
+            return Err(FsmError::InvalidState);
+        }
+        let to = if self.yes_votes > self.no_votes {
+            AmendmentStatus::Accepted
+        } else {
+            AmendmentStatus::Rejected
+        };
+        self.transition(to)?;
+        self.resolved_at = Some(current_time);
+        Ok(to)
+    }
+    /// Withdraw before a decision
+    pub fn withdraw(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.transition(AmendmentStatus::Withdrawn)?;
+        self.resolved_at = Some(current_time);
+        Ok(())
+    }
+}
+impl<P> Proposal<P> {
+    /// Replace the description with an Accepted amendment's content
+    ///
+    /// An Active proposal's voting clock restarts at `current_time`.
+    /// Errors: `InvalidInput` if the amendment is for another proposal,
+    /// `InvalidState` unless the amendment is Accepted and this proposal is
+    /// Draft (or Active when the amendment allows it), `TimeRegression` if
+    /// `current_time` breaks `time_ordering`.
+    pub fn apply_amendment(
+        &mut self,
+        amendment: &ProposalAmendment<P>,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        amendment.check_parent(self)?;
+        if amendment.status != AmendmentStatus::Accepted {
+            return Err(FsmError::InvalidState);
+        }
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        if self.status == ProposalStatus::Active {
+            self.submitted_at = Some(current_time);
+        }
+        self.description = amendment.content.clone();
+        self.updated_at = Some(current_time);
+        self.last_event_at = self.last_event_at.max(current_time);
+        Ok(())
+    }
 }
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::FsmError;
+    use crate::duration::DurationSecs;
     fn create_test_pubkey(seed: u8) -> u8 {
         seed
     }
@@ -71,6 +258,129 @@ mod tests {
         assert_eq!(amendment.content, "Amendment content");
         assert_eq!(amendment.created_at, 1000);
     }
+    fn create_draft_parent() -> Proposal<u8> {
+        Proposal::builder(
+            100,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap()
+    }
+    fn create_test_amendment() -> ProposalAmendment<u8> {
+        ProposalAmendment::new_with_time(1, 100, 2, "Amended description".to_string(), 10).unwrap()
+    }
+    #[test]
+    fn test_amendment_lifecycle_accepted_and_applied() {
+        let mut parent = create_draft_parent();
+        let mut amendment = create_test_amendment();
+        assert_eq!(amendment.status, AmendmentStatus::Proposed);
+        assert_eq!(
+            amendment.cast_amendment_vote(3, true, 1, 10),
+            Err(FsmError::InvalidState)
+        );
+        amendment.open_amendment_voting(&parent, 50, 20).unwrap();
+        assert_eq!(amendment.status, AmendmentStatus::Voting);
+        assert_eq!(amendment.voting_ends_at, Some(70));
+        amendment.cast_amendment_vote(3, true, 2, 30).unwrap();
+        amendment.cast_amendment_vote(4, false, 1, 40).unwrap();
+        assert_eq!(
+            amendment.cast_amendment_vote(3, false, 1, 50),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            amendment.cast_amendment_vote(5, false, 5, 70),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            parent.apply_amendment(&amendment, 60),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            amendment.finalize_amendment(69),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            amendment.finalize_amendment(70),
+            Ok(AmendmentStatus::Accepted)
+        );
+        assert_eq!(amendment.resolved_at, Some(70));
+        assert_eq!((amendment.yes_votes, amendment.no_votes), (2, 1));
+
+        parent.apply_amendment(&amendment, 80).unwrap();
+        assert_eq!(parent.description, "Amended description");
+        assert_eq!(parent.updated_at, Some(80));
+        assert_eq!(parent.status, ProposalStatus::Draft);
+        assert_eq!(
+            amendment.withdraw(90),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+    #[test]
+    fn test_amendment_rejected_on_tie() {
+        let parent = create_draft_parent();
+        let mut amendment = create_test_amendment();
+        amendment.open_amendment_voting(&parent, 50, 20).unwrap();
+        amendment.cast_amendment_vote(3, true, 1, 30).unwrap();
+        amendment.cast_amendment_vote(4, false, 1, 30).unwrap();
+        assert_eq!(
+            amendment.finalize_amendment(70),
+            Ok(AmendmentStatus::Rejected)
+        );
+        let mut parent = parent;
+        assert_eq!(
+            parent.apply_amendment(&amendment, 80),
+            Err(FsmError::InvalidState)
+        );
+    }
+    #[test]
+    fn test_amendment_requires_draft_parent() {
+        let mut parent = create_draft_parent();
+        parent.activate_with_time(1, 10, 20).unwrap();
+        let mut amendment = create_test_amendment();
+        assert_eq!(
+            amendment.open_amendment_voting(&parent, 50, 30),
+            Err(FsmError::InvalidState)
+        );
+        let mut other = create_draft_parent();
+        other.id = 101;
+        assert_eq!(
+            amendment.open_amendment_voting(&other, 50, 30),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            amendment.open_amendment_voting(&create_draft_parent(), 0, 30),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            amendment.open_amendment_voting(&create_draft_parent(), 50, 5),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(amendment.status, AmendmentStatus::Proposed);
+        amendment.withdraw(40).unwrap();
+        assert_eq!(amendment.status, AmendmentStatus::Withdrawn);
+        assert_eq!(
+            amendment.open_amendment_voting(&create_draft_parent(), 50, 50),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+    #[test]
+    fn test_amendment_to_active_parent_resets_voting_clock() {
+        let mut parent = create_draft_parent();
+        parent.activate_with_time(1, 10, 20).unwrap();
+        let mut amendment = create_test_amendment().with_active_parent_allowed();
+        amendment.open_amendment_voting(&parent, 30, 30).unwrap();
+        amendment.cast_amendment_vote(3, true, 1, 40).unwrap();
+        amendment.finalize_amendment(60).unwrap();
+        parent.apply_amendment(&amendment, 90).unwrap();
+        assert_eq!(parent.submitted_at, Some(90));
+        // Voting now ends at 190 instead of 120
+        parent.cast_vote(5, true, 1, 150).unwrap();
+        assert!(!parent.auto_transition_detailed(150).unwrap().transitioned());
+    }
     #[test]
     fn test_proposal_amendment_validation_empty_content() {
         let author = create_test_pubkey(1);
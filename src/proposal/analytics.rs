@@ -4,11 +4,21 @@
 //!
 //! On-chain: Metadata for proposal analytics
 //! Off-chain: Actual analytics, reporting
+//!
+//! `cancellation_breakdown` summarizes why proposals were cancelled;
+//! `vote_time_distribution` shows when during its window a proposal's votes arrived.
+use super::types::{Proposal, ProposalStatus};
 use crate::error::FsmError;
+use crate::projections::rate_bps;
+use crate::reason::ReasonCode;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 /// Analytics type
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ProposalAnalyticsType {
     /// Support analytics
+    #[default]
     Support,
     /// Opposition analytics
     Opposition,
@@ -18,9 +28,10 @@ pub enum ProposalAnalyticsType {
     Custom,
 }
 /// Analytics status
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ProposalAnalyticsStatus {
     /// Analytics active
+    #[default]
     Active,
     /// Analytics paused
     Paused,
@@ -28,7 +39,7 @@ pub enum ProposalAnalyticsStatus {
     Disabled,
 }
 /// Proposal analytics metadata (on-chain)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ProposalAnalyticsMetadata {
     /// Analytics ID
     pub analytics_id: u64,
@@ -43,10 +54,34 @@ pub struct ProposalAnalyticsMetadata {
     /// Analytics config hash
     pub analytics_config_hash: [u8; 32],
 }
+impl ProposalAnalyticsMetadata {
+    /// Create initialized proposal analytics metadata
+    pub fn initialize(
+        analytics_id: u64,
+        proposal_id: u64,
+        analytics_type: ProposalAnalyticsType,
+        analytics_config_hash: [u8; 32],
+        current_time: i64,
+    ) -> Result<Self, FsmError> {
+        if analytics_id == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            analytics_id,
+            proposal_id,
+            analytics_type,
+            status: ProposalAnalyticsStatus::Active,
+            created_at: current_time,
+            analytics_config_hash,
+        })
+    }
+}
+
 /// On-chain functions
 pub mod onchain {
     use super::*;
 
+    /// Initialize in place (zero-copy accounts); see `ProposalAnalyticsMetadata::initialize`
     pub fn initialize_proposal_analytics(
         analytics: &mut ProposalAnalyticsMetadata,
         analytics_id: u64,
@@ -55,15 +90,13 @@ pub mod onchain {
         analytics_config_hash: [u8; 32],
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if analytics_id == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-        analytics.analytics_id = analytics_id;
-        analytics.proposal_id = proposal_id;
-        analytics.analytics_type = analytics_type;
-        analytics.status = ProposalAnalyticsStatus::Active;
-        analytics.created_at = current_time;
-        analytics.analytics_config_hash = analytics_config_hash;
+        *analytics = ProposalAnalyticsMetadata::initialize(
+            analytics_id,
+            proposal_id,
+            analytics_type,
+            analytics_config_hash,
+            current_time,
+        )?;
         Ok(())
     }
 }
@@ -73,32 +106,366 @@ pub mod offchain {
         vec![]
     }
 }
+/// Cancelled proposals of one reason and proposal type
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BreakdownRow {
+    /// `ReasonCode::label_key` of the cancellation code
+    pub reason: String,
+    pub proposal_type: String,
+    pub count: u64,
+    /// `count` as basis points of `Breakdown::created`; None if nothing was created
+    pub share_bps: Option<u64>,
+}
+/// Cancellations among proposals created in a window
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Breakdown {
+    /// Proposals created in the window
+    pub created: u64,
+    /// Of those, proposals that were cancelled
+    pub cancelled: u64,
+    /// Of those, proposals whose vote ended short of quorum; not counted as cancelled
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quorum_failed: u64,
+    /// Sorted by reason, then proposal type
+    pub rows: Vec<BreakdownRow>,
+    /// Count per reason, sorted by reason
+    pub by_reason: Vec<(String, u64)>,
+}
+/// Group proposals created in `[window.0, window.1)` that were cancelled by reason code and type
+///
+/// A proposal counts as cancelled if it is Cancelled or has `cancelled_at` set
+/// (so archived cancellations are included). Cancellations recorded before
+/// reason codes existed have no code and are bucketed as `ReasonCode::Other`.
+/// `SupersededBy` is grouped regardless of the superseding id.
+pub fn cancellation_breakdown<P>(proposals: &[Proposal<P>], window: (i64, i64)) -> Breakdown {
+    let (start, end) = window;
+    let mut breakdown = Breakdown::default();
+    let mut rows: BTreeMap<(&'static str, &str), u64> = BTreeMap::new();
+    let mut by_reason: BTreeMap<&'static str, u64> = BTreeMap::new();
+    for proposal in proposals
+        .iter()
+        .filter(|p| p.created_at >= start && p.created_at < end)
+    {
+        breakdown.created += 1;
+        if proposal.status == ProposalStatus::QuorumFailed {
+            breakdown.quorum_failed += 1;
+        }
+        if proposal.status != ProposalStatus::Cancelled && proposal.cancelled_at.is_none() {
+            continue;
+        }
+        breakdown.cancelled += 1;
+        let reason = proposal
+            .cancellation_code
+            .unwrap_or(ReasonCode::Other)
+            .label_key();
+        *rows.entry((reason, &proposal.proposal_type)).or_default() += 1;
+        *by_reason.entry(reason).or_default() += 1;
+    }
+    breakdown.rows = rows
+        .into_iter()
+        .map(|((reason, proposal_type), count)| BreakdownRow {
+            reason: reason.to_string(),
+            proposal_type: proposal_type.to_string(),
+            count,
+            share_bps: rate_bps(count, breakdown.created),
+        })
+        .collect();
+    breakdown.by_reason = by_reason
+        .into_iter()
+        .map(|(reason, count)| (reason.to_string(), count))
+        .collect();
+    breakdown
+}
+/// Votes cast during one slice of a voting window
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VoteBucket {
+    /// First second of the bucket, inclusive
+    pub starts_at: i64,
+    /// End of the bucket, exclusive except for the last bucket
+    pub ends_at: i64,
+    /// Votes in favour cast in the bucket
+    pub yes: u64,
+    /// Votes against cast in the bucket
+    pub no: u64,
+    /// Votes cast up to the end of this bucket
+    pub cumulative_votes: u64,
+    /// `cumulative_votes` as basis points of all votes in the window; None if there were none
+    pub cumulative_turnout_bps: Option<u64>,
+}
+/// Split the voting window of `proposal` into `bucket_count` equal buckets of vote counts
+///
+/// The window runs from `submitted_at` to `voting_ends_at`. Bucket `i` starts at
+/// `submitted_at + ceil(i * duration / bucket_count)`, so bucket lengths differ by
+/// at most one second when the duration does not divide evenly. Each bucket is
+/// half-open, except that the last one also holds votes recorded exactly at the
+/// window end. Votes outside the window (such as votes carried over from a
+/// merged proposal) are left out of every bucket and of the turnout total.
+/// Counts are votes, not weights; proposal votes have no abstain option.
+///
+/// Errors: `InvalidState` if voting never opened or the votes were compacted,
+/// `InvalidInput` for a zero `bucket_count` or an empty window, `Overflow` if
+/// the window end overflows.
+pub fn vote_time_distribution<P>(
+    proposal: &Proposal<P>,
+    bucket_count: usize,
+) -> Result<Vec<VoteBucket>, FsmError> {
+    let start = proposal.submitted_at.ok_or(FsmError::InvalidState)?;
+    if proposal.compacted {
+        return Err(FsmError::InvalidState);
+    }
+    let end = proposal.voting_ends_at()?;
+    if bucket_count == 0 || end <= start {
+        return Err(FsmError::InvalidInput);
+    }
+    let duration = (end - start) as i128;
+    let buckets = bucket_count as i128;
+    // ceil(i * duration / buckets), in i128 so large windows cannot overflow
+    let boundary = |i: i128| start + ((i * duration + buckets - 1) / buckets) as i64;
+    let mut distribution: Vec<VoteBucket> = (0..buckets)
+        .map(|i| VoteBucket {
+            starts_at: boundary(i),
+            ends_at: boundary(i + 1),
+            ..VoteBucket::default()
+        })
+        .collect();
+    for vote in proposal
+        .votes
+        .iter()
+        .filter(|v| v.cast_at >= start && v.cast_at <= end)
+    {
+        let offset = (vote.cast_at - start) as i128;
+        let index = ((offset * buckets / duration) as usize).min(bucket_count - 1);
+        if vote.support {
+            distribution[index].yes += 1;
+        } else {
+            distribution[index].no += 1;
+        }
+    }
+    let total: u64 = distribution.iter().map(|b| b.yes + b.no).sum();
+    let mut cumulative = 0;
+    for bucket in &mut distribution {
+        cumulative += bucket.yes + bucket.no;
+        bucket.cumulative_votes = cumulative;
+        bucket.cumulative_turnout_bps = rate_bps(cumulative, total);
+    }
+    Ok(distribution)
+}
 #[cfg(test)]
 mod tests {
     #![allow(clippy::useless_vec)]
     use super::*;
     use crate::error::FsmError;
+    fn create_test_proposal(id: u64, proposal_type: &str, created_at: i64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            proposal_type.to_string(),
+            1,
+        )
+        .build(created_at)
+        .unwrap()
+    }
+    fn cancelled(
+        id: u64,
+        proposal_type: &str,
+        created_at: i64,
+        code: Option<ReasonCode>,
+    ) -> Proposal<u8> {
+        let mut proposal = create_test_proposal(id, proposal_type, created_at);
+        proposal
+            .cancel_with_code(ReasonCode::Other, "reason".to_string(), created_at)
+            .unwrap();
+        // None simulates a cancellation recorded before reason codes
+        proposal.cancellation_code = code;
+        proposal
+    }
     #[test]
-    fn test_initialize_proposal_analytics() {
-        let mut analytics = ProposalAnalyticsMetadata {
-            analytics_id: 0,
-            proposal_id: 0,
-            analytics_type: ProposalAnalyticsType::Support,
-            status: ProposalAnalyticsStatus::Disabled,
-            created_at: 0,
-            analytics_config_hash: [0u8; 32],
-        };
+    fn test_cancellation_breakdown_mixed_reasons() {
+        let proposals = vec![
+            cancelled(1, "grant", 100, Some(ReasonCode::Spam)),
+            cancelled(2, "governance", 110, None),
+            cancelled(3, "grant", 120, Some(ReasonCode::SupersededBy { id: 9 })),
+            cancelled(4, "grant", 130, Some(ReasonCode::SupersededBy { id: 10 })),
+            cancelled(5, "governance", 140, Some(ReasonCode::Other)),
+            create_test_proposal(6, "grant", 150),
+            create_test_proposal(7, "grant", 160),
+            create_test_proposal(8, "grant", 170),
+            // Outside the window
+            cancelled(9, "grant", 200, Some(ReasonCode::Spam)),
+            cancelled(10, "grant", 99, Some(ReasonCode::Spam)),
+        ];
+        let breakdown = cancellation_breakdown(&proposals, (100, 200));
+        assert_eq!(breakdown.created, 8);
+        assert_eq!(breakdown.cancelled, 5);
+        let rows: Vec<(&str, &str, u64, Option<u64>)> = breakdown
+            .rows
+            .iter()
+            .map(|r| {
+                (
+                    r.reason.as_str(),
+                    r.proposal_type.as_str(),
+                    r.count,
+                    r.share_bps,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("reason.cancellation.other", "governance", 2, Some(2500)),
+                ("reason.cancellation.spam", "grant", 1, Some(1250)),
+                ("reason.cancellation.superseded_by", "grant", 2, Some(2500)),
+            ]
+        );
+        assert_eq!(
+            breakdown.by_reason,
+            vec![
+                ("reason.cancellation.other".to_string(), 2),
+                ("reason.cancellation.spam".to_string(), 1),
+                ("reason.cancellation.superseded_by".to_string(), 2),
+            ]
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_cancellation_breakdown_deterministic_and_serializable() {
+        let mut proposals = vec![
+            cancelled(1, "grant", 100, Some(ReasonCode::Spam)),
+            cancelled(2, "governance", 110, Some(ReasonCode::SecurityVeto)),
+            cancelled(3, "grant", 120, None),
+        ];
+        let forward = cancellation_breakdown(&proposals, (0, 1000));
+        proposals.reverse();
+        assert_eq!(cancellation_breakdown(&proposals, (0, 1000)), forward);
+        let json = serde_json::to_string(&forward).unwrap();
+        assert_eq!(serde_json::from_str::<Breakdown>(&json).unwrap(), forward);
 
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let empty = cancellation_breakdown(&proposals, (5000, 6000));
+        assert_eq!(empty, Breakdown::default());
+    }
+    #[test]
+    fn test_cancellation_breakdown_counts_quorum_failures_separately() {
+        let mut quorum_failed = create_test_proposal(2, "grant", 100);
+        quorum_failed.activate_with_time(1, 10, 100).unwrap();
+        let voting_end = 100 + quorum_failed.voting_duration.as_secs();
+        quorum_failed.fail_quorum_with_time(voting_end).unwrap();
+        let proposals = vec![
+            cancelled(1, "grant", 100, Some(ReasonCode::Spam)),
+            quorum_failed,
+        ];
+        let breakdown = cancellation_breakdown(&proposals, (0, 1000));
+        assert_eq!(breakdown.created, 2);
+        assert_eq!(breakdown.cancelled, 1);
+        assert_eq!(breakdown.quorum_failed, 1);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_breakdown_without_quorum_failed_deserializes() {
+        let json = r#"{"created":3,"cancelled":1,"rows":[],"by_reason":[]}"#;
+        let breakdown: Breakdown = serde_json::from_str(json).unwrap();
+        assert_eq!(breakdown.cancelled, 1);
+        assert_eq!(breakdown.quorum_failed, 0);
+    }
+    fn voting_proposal() -> Proposal<u8> {
+        let mut proposal = create_test_proposal(1, "governance", 1000);
+        proposal.voting_duration = crate::duration::DurationSecs::from_secs(100);
+        proposal.activate_with_time(1, 1, 1000).unwrap();
+        proposal
+    }
+    #[test]
+    fn test_vote_time_distribution_hand_computed() {
+        let mut proposal = voting_proposal();
+        for (voter, support, at) in [
+            (2, true, 1000),
+            (3, false, 1033),
+            (4, true, 1034),
+            (5, true, 1066),
+            (6, false, 1067),
+            (7, true, 1099),
+        ] {
+            proposal.cast_vote(voter, support, 1, at).unwrap();
+        }
+        // Records outside `cast_vote`: exactly at the window end, and outside it
+        for (voter, at) in [(8, 1100), (9, 999), (10, 1101)] {
+            proposal.votes.push(crate::proposal::votes::VoteRecord {
+                voter,
+                support: true,
+                weight: 1,
+                cast_at: at,
+            });
+        }
+        // 100s over 3 buckets: boundaries at +0, +34, +67, +100
+        let buckets = vote_time_distribution(&proposal, 3).unwrap();
+        let rows: Vec<(i64, i64, u64, u64, u64, Option<u64>)> = buckets
+            .iter()
+            .map(|b| {
+                (
+                    b.starts_at,
+                    b.ends_at,
+                    b.yes,
+                    b.no,
+                    b.cumulative_votes,
+                    b.cumulative_turnout_bps,
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (1000, 1034, 1, 1, 2, Some(2857)),
+                (1034, 1067, 2, 0, 4, Some(5714)),
+                (1067, 1100, 2, 1, 7, Some(10000)),
+            ]
+        );
+    }
+    #[test]
+    fn test_vote_time_distribution_rejections() {
+        let draft = create_test_proposal(1, "governance", 1000);
+        assert_eq!(
+            vote_time_distribution(&draft, 4),
+            Err(FsmError::InvalidState)
+        );
+        let proposal = voting_proposal();
+        assert_eq!(
+            vote_time_distribution(&proposal, 0),
+            Err(FsmError::InvalidInput)
+        );
+        let empty = vote_time_distribution(&proposal, 4).unwrap();
+        assert_eq!(empty.len(), 4);
+        assert!(
+            empty
+                .iter()
+                .all(|b| b.cumulative_votes == 0 && b.cumulative_turnout_bps.is_none())
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_vote_bucket_serializes() {
+        let bucket = VoteBucket {
+            starts_at: 0,
+            ends_at: 10,
+            yes: 1,
+            no: 0,
+            cumulative_votes: 1,
+            cumulative_turnout_bps: Some(10000),
+        };
+        let json = serde_json::to_string(&bucket).unwrap();
+        assert_eq!(serde_json::from_str::<VoteBucket>(&json).unwrap(), bucket);
+    }
+    #[test]
+    fn test_initialize_proposal_analytics() {
+        let analytics = ProposalAnalyticsMetadata::initialize(
             1,
             10,
             ProposalAnalyticsType::Engagement,
             [1u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_id, 1);
         assert_eq!(analytics.proposal_id, 10);
         assert_eq!(analytics.analytics_type, ProposalAnalyticsType::Engagement);
@@ -107,17 +474,7 @@ mod tests {
     }
     #[test]
     fn test_initialize_proposal_analytics_invalid_id() {
-        let mut analytics = ProposalAnalyticsMetadata {
-            analytics_id: 0,
-            proposal_id: 0,
-            analytics_type: ProposalAnalyticsType::Support,
-            status: ProposalAnalyticsStatus::Disabled,
-            created_at: 0,
-            analytics_config_hash: [0u8; 32],
-        };
-
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let result = ProposalAnalyticsMetadata::initialize(
             0, // Invalid: must be > 0
             10,
             ProposalAnalyticsType::Engagement,
@@ -186,23 +543,9 @@ mod tests {
             ProposalAnalyticsType::Custom,
         ];
         for analytics_type in types {
-            let mut analytics = ProposalAnalyticsMetadata {
-                analytics_id: 0,
-                proposal_id: 0,
-                analytics_type: ProposalAnalyticsType::Support,
-                status: ProposalAnalyticsStatus::Active,
-                created_at: 0,
-                analytics_config_hash: [0u8; 32],
-            };
-            let result = onchain::initialize_proposal_analytics(
-                &mut analytics,
-                1,
-                1,
-                analytics_type,
-                [0u8; 32],
-                1000,
-            );
-            assert!(result.is_ok());
+            let analytics =
+                ProposalAnalyticsMetadata::initialize(1, 1, analytics_type, [0u8; 32], 1000)
+                    .unwrap();
             assert_eq!(analytics.analytics_type, analytics_type);
         }
     }
@@ -224,45 +567,39 @@ mod tests {
     }
     #[test]
     fn test_initialize_proposal_analytics_config_hash() {
-        let mut analytics = create_test_analytics();
         let custom_hash = [177u8; 32];
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let analytics = ProposalAnalyticsMetadata::initialize(
             1,
             1,
             ProposalAnalyticsType::Opposition,
             custom_hash,
             7000,
-        );
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_config_hash, custom_hash);
     }
     #[test]
     fn test_initialize_proposal_analytics_proposal_id() {
-        let mut analytics = create_test_analytics();
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let analytics = ProposalAnalyticsMetadata::initialize(
             1,
             99999,
             ProposalAnalyticsType::Engagement,
             [0u8; 32],
             1000,
-        );
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.proposal_id, 99999);
     }
     #[test]
     fn test_initialize_proposal_analytics_timestamp() {
-        let mut analytics = create_test_analytics();
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let analytics = ProposalAnalyticsMetadata::initialize(
             1,
             1,
             ProposalAnalyticsType::Custom,
             [0u8; 32],
             33445,
-        );
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.created_at, 33445);
     }
     #[test]
@@ -337,25 +674,14 @@ mod tests {
     }
     #[test]
     fn test_initialize_proposal_analytics_large_ids() {
-        let mut analytics = ProposalAnalyticsMetadata {
-            analytics_id: 0,
-            proposal_id: 0,
-            analytics_type: ProposalAnalyticsType::Support,
-            status: ProposalAnalyticsStatus::Disabled,
-            created_at: 0,
-            analytics_config_hash: [0u8; 32],
-        };
-
-        let result = onchain::initialize_proposal_analytics(
-            &mut analytics,
+        let analytics = ProposalAnalyticsMetadata::initialize(
             u64::MAX,
             u64::MAX,
             ProposalAnalyticsType::Custom,
             [0u8; 32],
             1000,
-        );
-
-        assert!(result.is_ok());
+        )
+        .unwrap();
         assert_eq!(analytics.analytics_id, u64::MAX);
         assert_eq!(analytics.proposal_id, u64::MAX);
     }
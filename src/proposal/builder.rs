@@ -0,0 +1,426 @@
+//! Proposal builder
+//!
+//! Fluent construction of proposals. Optional fields are collected by the
+//! builder and every constraint is checked once, in `build`.
+use super::treasury::{TreasuryAuthorization, TreasuryOperationData, TreasuryPolicy};
+use super::type_registry::{TallyOverflow, TallyPolicy, TieBreakPolicy};
+use super::types::{Proposal, ProposalStatus};
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::reason_policy::ReasonPolicy;
+use crate::time_guard::{TimeOrdering, check_construction_time};
+
+/// Proposal type that requires attached treasury operation data
+pub const TREASURY_PROPOSAL_TYPE: &str = "treasury";
+
+/// Default voting duration (7 days)
+pub const DEFAULT_VOTING_DURATION: DurationSecs = DurationSecs::from_secs(7 * 24 * 3600);
+
+/// Builder for `Proposal`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalBuilder<P> {
+    id: u64,
+    title: String,
+    description: String,
+    proposal_type: String,
+    author: P,
+    voting_duration: DurationSecs,
+    expires_at: Option<i64>,
+    idea_id: Option<u64>,
+    execution_data: Option<String>,
+    treasury_operation: Option<TreasuryOperationData<P>>,
+    treasury_authorization: Option<(TreasuryAuthorization, TreasuryPolicy)>,
+    time_ordering: TimeOrdering,
+    clock_skew_tolerance_seconds: u32,
+    depends_on: Vec<u64>,
+    reason_policy: ReasonPolicy<ProposalStatus>,
+}
+
+impl<P> ProposalBuilder<P> {
+    /// Start a builder with the required proposal fields
+    pub fn new(
+        id: u64,
+        title: String,
+        description: String,
+        proposal_type: String,
+        author: P,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            description,
+            proposal_type,
+            author,
+            voting_duration: DEFAULT_VOTING_DURATION,
+            expires_at: None,
+            idea_id: None,
+            execution_data: None,
+            treasury_operation: None,
+            treasury_authorization: None,
+            time_ordering: TimeOrdering::default(),
+            clock_skew_tolerance_seconds: 0,
+            depends_on: Vec::new(),
+            reason_policy: ReasonPolicy::default(),
+        }
+    }
+
+    /// Set voting duration
+    pub fn voting_duration(mut self, voting_duration: DurationSecs) -> Self {
+        self.voting_duration = voting_duration;
+        self
+    }
+
+    /// Set expiration timestamp
+    pub fn expires_at(mut self, expires_at: i64) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Link the proposal to the Idea it was created from
+    pub fn idea_id(mut self, idea_id: u64) -> Self {
+        self.idea_id = Some(idea_id);
+        self
+    }
+
+    /// Set execution data (JSON-encoded)
+    pub fn execution_data(mut self, execution_data: String) -> Self {
+        self.execution_data = Some(execution_data);
+        self
+    }
+
+    /// Attach treasury operation data
+    pub fn treasury_operation(mut self, treasury_operation: TreasuryOperationData<P>) -> Self {
+        self.treasury_operation = Some(treasury_operation);
+        self
+    }
+
+    /// Check the treasury operation against `authorization` under `policy` in `build`
+    pub fn treasury_authorization(
+        mut self,
+        authorization: TreasuryAuthorization,
+        policy: TreasuryPolicy,
+    ) -> Self {
+        self.treasury_authorization = Some((authorization, policy));
+        self
+    }
+
+    /// Set the rule for lifecycle timestamps; `Unchecked` disables it for tests and simulations
+    pub fn time_ordering(mut self, time_ordering: TimeOrdering) -> Self {
+        self.time_ordering = time_ordering;
+        self
+    }
+
+    /// Let transitions lag `last_event_at` by up to `seconds` of clock skew (see `time_guard`)
+    pub fn clock_skew_tolerance(mut self, seconds: u32) -> Self {
+        self.clock_skew_tolerance_seconds = seconds;
+        self
+    }
+
+    /// Set which transitions must or must not carry a reason
+    pub fn reason_policy(mut self, reason_policy: ReasonPolicy<ProposalStatus>) -> Self {
+        self.reason_policy = reason_policy;
+        self
+    }
+
+    /// Set the proposals that must be executed before this one
+    pub fn depends_on(mut self, depends_on: Vec<u64>) -> Self {
+        self.depends_on = depends_on;
+        self
+    }
+
+    pub(crate) fn proposal_type(&self) -> &str {
+        &self.proposal_type
+    }
+
+    pub(crate) fn has_treasury_operation(&self) -> bool {
+        self.treasury_operation.is_some()
+    }
+
+    /// Validate all fields and build the proposal
+    ///
+    /// Field constraints:
+    /// - current_time >= 0
+    /// - title: 1-200 chars, description: 1-2000 chars, proposal_type: 1-50 chars
+    /// - voting_duration > 0
+    /// - expires_at > created_at
+    /// - "treasury" proposals must carry a valid treasury_operation
+    /// - with a treasury_authorization, the operation's capability must be held
+    ///   (`CapabilityRequired`)
+    /// - depends_on must not contain the proposal's own id
+    pub fn build(self, current_time: i64) -> Result<Proposal<P>, FsmError> {
+        check_construction_time(current_time)?;
+        if self.title.is_empty() {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.title.len() > 200 {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.description.is_empty() {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.description.len() > 2000 {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.proposal_type.is_empty() {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.proposal_type.len() > 50 {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.voting_duration <= DurationSecs::ZERO {
+            return Err(FsmError::InvalidInput);
+        }
+        if let Some(exp) = self.expires_at
+            && exp <= current_time
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        if self.depends_on.contains(&self.id) {
+            return Err(FsmError::InvalidInput);
+        }
+        match &self.treasury_operation {
+            Some(operation) => {
+                operation.validate(current_time)?;
+                if let Some((authorization, policy)) = &self.treasury_authorization {
+                    authorization.check(operation, policy)?;
+                }
+            }
+            None => {
+                if self.proposal_type == TREASURY_PROPOSAL_TYPE {
+                    return Err(FsmError::InvalidInput);
+                }
+            }
+        }
+
+        Ok(Proposal {
+            id: self.id,
+            title: self.title,
+            description: self.description,
+            proposal_type: self.proposal_type,
+            author: self.author,
+            created_at: current_time,
+            updated_at: None,
+            submitted_at: None,
+            cancelled_at: None,
+            executed_at: None,
+            passed_at: None,
+            archived_at: None,
+            voting_duration: self.voting_duration,
+            status: ProposalStatus::Draft,
+            yes_votes: 0,
+            no_votes: 0,
+            total_votes: 0,
+            votes: Vec::new(),
+            vote_nonces: Vec::new(),
+            last_tallied_at: None,
+            cancellation_reason: None,
+            cancellation_code: None,
+            superseded_by: None,
+            depends_on: self.depends_on,
+            sponsors: Vec::new(),
+            execution_data: self.execution_data,
+            expires_at: self.expires_at,
+            idea_id: self.idea_id,
+            treasury_operation: self.treasury_operation,
+            execution_ticket: None,
+            execution_attempts: 0,
+            execution_abort_reason: None,
+            execution_history: Vec::new(),
+            last_event_at: current_time,
+            time_ordering: self.time_ordering,
+            clock_skew_tolerance_seconds: self.clock_skew_tolerance_seconds,
+            tally_policy: TallyPolicy::SimpleMajority,
+            tally_overflow: TallyOverflow::Reject,
+            tally_degraded: false,
+            voting_id: None,
+            compacted: false,
+            compacted_hash: None,
+            power_snapshot: None,
+            min_vote_weight: 0,
+            weight_divisor: None,
+            reason_policy: self.reason_policy,
+            execution_timelock: DurationSecs::ZERO,
+            tags: Vec::new(),
+            tie_break_policy: TieBreakPolicy::Manual,
+            tie_break_applied: None,
+        })
+    }
+}
+
+impl<P> Proposal<P> {
+    /// Start building a proposal
+    pub fn builder(
+        id: u64,
+        title: String,
+        description: String,
+        proposal_type: String,
+        author: P,
+    ) -> ProposalBuilder<P> {
+        ProposalBuilder::new(id, title, description, proposal_type, author)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::error::FsmError;
+    use crate::proposal::treasury::TreasuryProposalType;
+    fn create_test_pubkey(seed: u8) -> u8 {
+        seed
+    }
+    fn create_test_builder(proposal_type: &str) -> ProposalBuilder<u8> {
+        Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            proposal_type.to_string(),
+            create_test_pubkey(1),
+        )
+    }
+    fn create_test_withdrawal() -> TreasuryOperationData<u8> {
+        TreasuryOperationData::new(
+            TreasuryProposalType::Withdrawal,
+            Some(1000),
+            None,
+            None,
+            None,
+            None,
+            "Withdraw for expenses".to_string(),
+        )
+    }
+    #[test]
+    fn test_builder_defaults_match_new_with_time() {
+        let built = create_test_builder("governance").build(1000).unwrap();
+        let direct = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+        assert_eq!(built, direct);
+    }
+    #[test]
+    fn test_builder_optional_fields() {
+        let proposal = create_test_builder("governance")
+            .voting_duration(DurationSecs::from_hours(1).unwrap())
+            .expires_at(5000)
+            .idea_id(42)
+            .execution_data(r#"{"type": "test"}"#.to_string())
+            .build(1000)
+            .unwrap();
+        assert_eq!(proposal.voting_duration.as_secs(), 3600);
+        assert_eq!(proposal.expires_at, Some(5000));
+        assert_eq!(proposal.idea_id, Some(42));
+        assert_eq!(
+            proposal.execution_data,
+            Some(r#"{"type": "test"}"#.to_string())
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+        assert_eq!(proposal.created_at, 1000);
+    }
+    #[test]
+    fn test_builder_expires_at_not_after_creation() {
+        assert_eq!(
+            create_test_builder("governance")
+                .expires_at(1000)
+                .build(1000)
+                .unwrap_err(),
+            FsmError::InvalidInput
+        );
+        assert_eq!(
+            create_test_builder("governance")
+                .expires_at(500)
+                .build(1000)
+                .unwrap_err(),
+            FsmError::InvalidInput
+        );
+    }
+    #[test]
+    fn test_builder_rejects_self_dependency() {
+        let builder = create_test_builder("governance");
+        assert_eq!(
+            builder.clone().depends_on(vec![1]).build(1000).unwrap_err(),
+            FsmError::InvalidInput
+        );
+        let proposal = builder.depends_on(vec![2, 3]).build(1000).unwrap();
+        assert_eq!(proposal.depends_on, vec![2, 3]);
+    }
+    #[test]
+    fn test_builder_zero_voting_duration() {
+        assert_eq!(
+            create_test_builder("governance")
+                .voting_duration(DurationSecs::ZERO)
+                .build(1000)
+                .unwrap_err(),
+            FsmError::InvalidInput
+        );
+    }
+    #[test]
+    fn test_builder_treasury_requires_operation() {
+        assert_eq!(
+            create_test_builder(TREASURY_PROPOSAL_TYPE)
+                .build(1000)
+                .unwrap_err(),
+            FsmError::InvalidInput
+        );
+        let proposal = create_test_builder(TREASURY_PROPOSAL_TYPE)
+            .treasury_operation(create_test_withdrawal())
+            .build(1000)
+            .unwrap();
+        assert_eq!(proposal.treasury_operation, Some(create_test_withdrawal()));
+    }
+    #[test]
+    fn test_builder_invalid_treasury_operation() {
+        let mut operation = create_test_withdrawal();
+        operation.amount = None;
+        assert_eq!(
+            create_test_builder(TREASURY_PROPOSAL_TYPE)
+                .treasury_operation(operation)
+                .build(1000)
+                .unwrap_err(),
+            FsmError::InvalidInput
+        );
+    }
+    #[test]
+    fn test_builder_checks_treasury_authorization() {
+        use crate::enums::CapabilityType;
+        use crate::proposal::treasury::{TreasuryAuthorization, TreasuryPolicy};
+        let mut transfer = create_test_withdrawal();
+        transfer.operation_type = TreasuryProposalType::Transfer;
+        transfer.target_treasury = Some(create_test_pubkey(2));
+        let builder = create_test_builder(TREASURY_PROPOSAL_TYPE).treasury_operation(transfer);
+        assert_eq!(
+            builder
+                .clone()
+                .treasury_authorization(TreasuryAuthorization::default(), TreasuryPolicy::default())
+                .build(1000)
+                .unwrap_err(),
+            FsmError::CapabilityRequired {
+                capability: CapabilityType::Withdraw
+            }
+        );
+        let authorization = TreasuryAuthorization::new(vec![CapabilityType::Withdraw]);
+        assert!(
+            builder
+                .treasury_authorization(authorization, TreasuryPolicy::default())
+                .build(1000)
+                .is_ok()
+        );
+    }
+    #[test]
+    fn test_builder_title_too_long() {
+        let result = Proposal::builder(
+            1,
+            "a".repeat(201),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+        )
+        .build(1000);
+        assert_eq!(result.unwrap_err(), FsmError::InvalidInput);
+    }
+}
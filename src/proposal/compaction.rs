@@ -0,0 +1,163 @@
+//! Compaction of archived proposals
+//!
+//! `compact` moves the bulky parts of an Archived proposal (description,
+//! execution data and history, vote records and nonces) into a
+//! `CompactedData` for cold storage. The proposal keeps its tallies,
+//! timestamps and status, plus the `canonical_hash` of the moved data so
+//! `verify_against` can check a copy brought back from storage. A compacted
+//! proposal serializes like any other, with the moved fields empty.
+use super::execution::ExecutionResult;
+use super::types::{Proposal, ProposalStatus};
+use super::votes::{VoteRecord, VoterNonce};
+use crate::canonical::canonical_hash;
+use crate::error::FsmError;
+use serde::{Deserialize, Serialize};
+use std::mem;
+/// Fields moved out of a proposal by `compact`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactedData<P> {
+    pub proposal_id: u64,
+    pub description: String,
+    pub execution_data: Option<String>,
+    pub execution_history: Vec<ExecutionResult>,
+    pub votes: Vec<VoteRecord<P>>,
+    pub vote_nonces: Vec<VoterNonce<P>>,
+}
+impl<P: Serialize> Proposal<P> {
+    /// Move the heavy fields out for cold storage
+    ///
+    /// Sets `compacted` and `compacted_hash`; lifecycle steps fail afterwards.
+    /// `InvalidState` unless the proposal is Archived and not yet compacted;
+    /// nothing changes on error.
+    pub fn compact(&mut self) -> Result<CompactedData<P>, FsmError> {
+        if self.status != ProposalStatus::Archived || self.compacted {
+            return Err(FsmError::InvalidState);
+        }
+        let cold = CompactedData {
+            proposal_id: self.id,
+            description: mem::take(&mut self.description),
+            execution_data: self.execution_data.take(),
+            execution_history: mem::take(&mut self.execution_history),
+            votes: mem::take(&mut self.votes),
+            vote_nonces: mem::take(&mut self.vote_nonces),
+        };
+        match canonical_hash(&cold) {
+            Ok(hash) => {
+                self.compacted = true;
+                self.compacted_hash = Some(hash);
+                Ok(cold)
+            }
+            Err(err) => {
+                self.description = cold.description;
+                self.execution_data = cold.execution_data;
+                self.execution_history = cold.execution_history;
+                self.votes = cold.votes;
+                self.vote_nonces = cold.vote_nonces;
+                Err(err)
+            }
+        }
+    }
+    /// Whether `cold` is the data `compact` moved out of this proposal
+    ///
+    /// False for a proposal that is not compacted.
+    pub fn verify_against(&self, cold: &CompactedData<P>) -> bool {
+        self.compacted
+            && cold.proposal_id == self.id
+            && self.compacted_hash.is_some()
+            && canonical_hash(cold).ok() == self.compacted_hash
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn archived_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            3,
+            "Title".to_string(),
+            "A long description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 1, 10).unwrap();
+        proposal.cast_vote(2, true, 5, 20).unwrap();
+        proposal.cast_vote(3, false, 1, 30).unwrap();
+        proposal
+            .cancel_with_time("Withdrawn".to_string(), 40)
+            .unwrap();
+        proposal.archive_with_time(50).unwrap();
+        proposal
+    }
+    #[test]
+    fn test_compact_moves_heavy_fields() {
+        let mut proposal = archived_proposal();
+        let before = proposal.clone();
+        let cold = proposal.compact().unwrap();
+        assert_eq!(cold.description, "A long description");
+        assert_eq!(cold.votes, before.votes);
+        assert!(proposal.description.is_empty());
+        assert!(proposal.votes.is_empty());
+        assert!(proposal.compacted);
+        assert_eq!(proposal.yes_votes, 5);
+        assert_eq!(proposal.no_votes, 1);
+        assert_eq!(proposal.status, ProposalStatus::Archived);
+        assert!(proposal.verify_against(&cold));
+        let mut tampered = cold.clone();
+        tampered.votes.pop();
+        assert!(!proposal.verify_against(&tampered));
+        let mut other = cold.clone();
+        other.proposal_id = 4;
+        assert!(!proposal.verify_against(&other));
+        assert!(!before.verify_against(&cold));
+    }
+    #[test]
+    fn test_compact_requires_archived_once() {
+        let mut draft = Proposal::builder(
+            3,
+            "Title".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1u8,
+        )
+        .build(0)
+        .unwrap();
+        assert_eq!(draft.compact(), Err(FsmError::InvalidState));
+        assert_eq!(draft.description, "Description");
+        let mut proposal = archived_proposal();
+        proposal.compact().unwrap();
+        let compacted = proposal.clone();
+        assert_eq!(proposal.compact(), Err(FsmError::InvalidState));
+        assert_eq!(proposal, compacted);
+    }
+    #[test]
+    fn test_compacted_proposal_refuses_lifecycle_steps() {
+        let mut proposal = archived_proposal();
+        proposal.compact().unwrap();
+        proposal.status = ProposalStatus::Draft;
+        assert_eq!(proposal.activate(1, 1), Err(FsmError::InvalidState));
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+        proposal.status = ProposalStatus::Cancelled;
+        assert_eq!(proposal.archive_with_time(60), Err(FsmError::InvalidState));
+    }
+    #[test]
+    fn test_serde_reads_both_forms() {
+        let full = archived_proposal();
+        let mut compacted = full.clone();
+        compacted.compact().unwrap();
+        for proposal in [full, compacted] {
+            let json = serde_json::to_string(&proposal).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Proposal<u8>>(&json).unwrap(),
+                proposal
+            );
+        }
+        let mut legacy = serde_json::to_value(archived_proposal()).unwrap();
+        let object = legacy.as_object_mut().unwrap();
+        object.remove("compacted");
+        object.remove("compacted_hash");
+        let restored: Proposal<u8> = serde_json::from_value(legacy).unwrap();
+        assert!(!restored.compacted);
+        assert_eq!(restored.compacted_hash, None);
+    }
+}
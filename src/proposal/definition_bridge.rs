@@ -0,0 +1,89 @@
+//! Guard variables of a live proposal.
+//!
+//! `context_for` exposes a proposal to the guards of a custom `FsmDefinition`
+//! workflow, so an `FsmInstance` can drive it:
+//!
+//! | Variable | Value |
+//! |---|---|
+//! | `status` | status name, e.g. `'Active'` |
+//! | `yes_votes`, `no_votes`, `total_votes` | tallies |
+//! | `created_at` | creation time |
+//! | `submitted_at` | activation time, `null` while Draft |
+//! | `voting_end` | `submitted_at` (else `created_at`) plus `voting_duration`, saturating |
+//! | `current_time` | the time passed in |
+//! | `quorum_reached` | only with `context_with_quorum` |
+
+use super::types::Proposal;
+use crate::guard::GuardContext;
+
+/// Guard variables of `proposal` at `current_time`
+pub fn context_for<P>(proposal: &Proposal<P>, current_time: i64) -> GuardContext {
+    let voting_start = proposal.submitted_at.unwrap_or(proposal.created_at);
+    GuardContext::new()
+        .with("status", format!("{:?}", proposal.status).as_str())
+        .with("yes_votes", proposal.yes_votes)
+        .with("no_votes", proposal.no_votes)
+        .with("total_votes", proposal.total_votes)
+        .with("created_at", proposal.created_at)
+        .with("submitted_at", proposal.submitted_at)
+        .with(
+            "voting_end",
+            voting_start.saturating_add(proposal.voting_duration.as_secs()),
+        )
+        .with("current_time", current_time)
+}
+
+/// `context_for` plus `quorum_reached`
+pub fn context_with_quorum<P>(
+    proposal: &Proposal<P>,
+    current_time: i64,
+    quorum_reached: bool,
+) -> GuardContext {
+    context_for(proposal, current_time).with("quorum_reached", quorum_reached)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::guard::GuardValue;
+
+    #[test]
+    fn test_context_variables() {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1u8,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        let context = context_for(&proposal, 5);
+        assert_eq!(context.get("submitted_at"), Some(&GuardValue::Null));
+        assert_eq!(context.get("voting_end"), Some(&GuardValue::Int(100)));
+        assert_eq!(context.get("quorum_reached"), None);
+        proposal.activate_with_time(1, 10, 20).unwrap();
+        proposal.yes_votes = 3;
+        let context = context_with_quorum(&proposal, 50, true);
+        assert_eq!(
+            context.names().collect::<Vec<_>>(),
+            vec![
+                "created_at",
+                "current_time",
+                "no_votes",
+                "quorum_reached",
+                "status",
+                "submitted_at",
+                "total_votes",
+                "voting_end",
+                "yes_votes"
+            ]
+        );
+        assert_eq!(
+            context.evaluate("status == 'Active' and voting_end == 120 and yes_votes > no_votes"),
+            Ok(true)
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Field-level diff of a proposal change
+//!
+//! `begin_change` records the serialized fields of a proposal before a change;
+//! `ChangeTracker::diff` lists every field whose serialized value differs
+//! afterwards, with the old and new JSON values, so a persistence layer can
+//! build a partial UPDATE and an audit entry from the same list.
+//!
+//! Fields are compared by their serde form, so the diff covers every field of
+//! `Proposal` without a hand-kept list and names fields as serde does.
+use super::types::Proposal;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One changed field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Changed fields, sorted by field name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub changes: Vec<FieldChange>,
+}
+impl FieldDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+    /// Names of the changed fields, sorted
+    pub fn fields(&self) -> impl Iterator<Item = &str> {
+        self.changes.iter().map(|change| change.field.as_str())
+    }
+    /// The change of `field`, if it changed
+    pub fn get(&self, field: &str) -> Option<&FieldChange> {
+        self.changes.iter().find(|change| change.field == field)
+    }
+}
+
+/// Serialized fields of a proposal before a change (see `Proposal::begin_change`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeTracker {
+    before: Map<String, Value>,
+}
+impl ChangeTracker {
+    /// Fields of `after` that differ from the tracked proposal
+    pub fn diff<P: Serialize>(&self, after: &Proposal<P>) -> FieldDiff {
+        field_diff(&self.before, &serialized_fields(after))
+    }
+}
+impl<P: Serialize> Proposal<P> {
+    /// Start tracking a change; pass the changed proposal to `ChangeTracker::diff`
+    pub fn begin_change(&self) -> ChangeTracker {
+        ChangeTracker {
+            before: serialized_fields(self),
+        }
+    }
+}
+
+/// Serialized fields of `value`; empty if it does not serialize to a JSON object
+pub(crate) fn serialized_fields<T: Serialize>(value: &T) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Fields whose value differs between `before` and `after`, sorted by name
+pub(crate) fn field_diff(before: &Map<String, Value>, after: &Map<String, Value>) -> FieldDiff {
+    let names = before
+        .keys()
+        .chain(after.keys().filter(|name| !before.contains_key(*name)));
+    let mut changes: Vec<FieldChange> = names
+        .filter_map(|name| {
+            let old = before.get(name).unwrap_or(&Value::Null);
+            let new = after.get(name).unwrap_or(&Value::Null);
+            (old != new).then(|| FieldChange {
+                field: name.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    FieldDiff { changes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::execution::{ExecutionResult, ExecutionTicket};
+    use crate::proposal::snapshot::PowerSnapshot;
+    use crate::proposal::treasury::{TreasuryOperationData, TreasuryProposalType};
+    use crate::proposal::type_registry::{TallyOverflow, TallyPolicy, TieBreakPolicy};
+    use crate::proposal::types::ProposalStatus;
+    use crate::proposal::votes::{VoteRecord, VoterNonce};
+    use crate::reason::ReasonCode;
+    use crate::reason_policy::ReasonPolicy;
+    use crate::time_guard::TimeOrdering;
+    use std::collections::BTreeSet;
+    type Mutation = (&'static str, fn(&mut Proposal<u8>));
+    fn create_test_proposal() -> Proposal<u8> {
+        Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(100)
+        .unwrap()
+    }
+    /// One mutation per field; a new field without one fails the test
+    const MUTATIONS: &[Mutation] = &[
+        ("id", |p| p.id = 2),
+        ("title", |p| p.title.push('!')),
+        ("description", |p| p.description.clear()),
+        ("proposal_type", |p| {
+            p.proposal_type = "treasury".to_string()
+        }),
+        ("author", |p| p.author = 9),
+        ("created_at", |p| p.created_at = 1),
+        ("updated_at", |p| p.updated_at = Some(200)),
+        ("submitted_at", |p| p.submitted_at = Some(200)),
+        ("cancelled_at", |p| p.cancelled_at = Some(200)),
+        ("executed_at", |p| p.executed_at = Some(200)),
+        ("passed_at", |p| p.passed_at = Some(200)),
+        ("archived_at", |p| p.archived_at = Some(200)),
+        ("voting_duration", |p| {
+            p.voting_duration = DurationSecs::from_secs(60)
+        }),
+        ("status", |p| p.status = ProposalStatus::Active),
+        ("yes_votes", |p| p.yes_votes = 1),
+        ("no_votes", |p| p.no_votes = 1),
+        ("total_votes", |p| p.total_votes = 1),
+        ("votes", |p| {
+            p.votes.push(VoteRecord {
+                voter: 2,
+                support: true,
+                weight: 1,
+                cast_at: 200,
+            })
+        }),
+        ("vote_nonces", |p| {
+            p.vote_nonces.push(VoterNonce {
+                voter: 2,
+                last_nonce: 1,
+            })
+        }),
+        ("last_tallied_at", |p| p.last_tallied_at = Some(200)),
+        ("cancellation_reason", |p| {
+            p.cancellation_reason = Some("Spam".to_string())
+        }),
+        ("cancellation_code", |p| {
+            p.cancellation_code = Some(ReasonCode::Spam)
+        }),
+        ("superseded_by", |p| p.superseded_by = Some(3)),
+        ("depends_on", |p| p.depends_on.push(3)),
+        ("execution_data", |p| {
+            p.execution_data = Some("{}".to_string())
+        }),
+        ("expires_at", |p| p.expires_at = Some(500)),
+        ("idea_id", |p| p.idea_id = Some(4)),
+        ("treasury_operation", |p| {
+            p.treasury_operation = Some(TreasuryOperationData::new(
+                TreasuryProposalType::Withdrawal,
+                Some(10),
+                None,
+                None,
+                None,
+                None,
+                "Hosting".to_string(),
+            ))
+        }),
+        ("execution_ticket", |p| {
+            p.execution_ticket = Some(ExecutionTicket {
+                proposal_id: 1,
+                ticket_id: 1,
+                prepared_at: 200,
+            })
+        }),
+        ("execution_attempts", |p| p.execution_attempts = 1),
+        ("execution_abort_reason", |p| {
+            p.execution_abort_reason = Some("RPC down".to_string())
+        }),
+        ("execution_history", |p| {
+            p.execution_history.push(ExecutionResult {
+                attempted_at: 200,
+                success: false,
+                detail: "RPC down".to_string(),
+                attempt: 1,
+            })
+        }),
+        ("last_event_at", |p| p.last_event_at = 200),
+        ("time_ordering", |p| p.time_ordering = TimeOrdering::Strict),
+        ("clock_skew_tolerance_seconds", |p| {
+            p.clock_skew_tolerance_seconds = 2
+        }),
+        ("tally_policy", |p| {
+            p.tally_policy = TallyPolicy::Supermajority { percent: 60 }
+        }),
+        ("tally_overflow", |p| {
+            p.tally_overflow = TallyOverflow::Clamp
+        }),
+        ("tally_degraded", |p| p.tally_degraded = true),
+        ("voting_id", |p| p.voting_id = Some(5)),
+        ("compacted", |p| p.compacted = true),
+        ("compacted_hash", |p| p.compacted_hash = Some([1; 32])),
+        ("power_snapshot", |p| {
+            p.power_snapshot = Some(PowerSnapshot::new(vec![(2, 10)], 100).unwrap())
+        }),
+        ("min_vote_weight", |p| p.min_vote_weight = 2),
+        ("weight_divisor", |p| p.weight_divisor = Some(10)),
+        ("sponsors", |p| p.sponsors.push(2)),
+        ("reason_policy", |p| {
+            p.reason_policy = ReasonPolicy::strict()
+        }),
+        ("execution_timelock", |p| {
+            p.execution_timelock = DurationSecs::from_secs(60)
+        }),
+        ("tags", |p| p.tags.push("infrastructure".to_string())),
+        ("tie_break_policy", |p| {
+            p.tie_break_policy = TieBreakPolicy::StatusQuo
+        }),
+        ("tie_break_applied", |p| {
+            p.tie_break_applied = Some(TieBreakPolicy::AuthorLoses)
+        }),
+    ];
+    #[test]
+    fn test_diff_is_exhaustive_over_fields() {
+        let proposal = create_test_proposal();
+        let serialized: BTreeSet<String> = serialized_fields(&proposal).keys().cloned().collect();
+        let mutated: BTreeSet<String> = MUTATIONS.iter().map(|(f, _)| f.to_string()).collect();
+        assert_eq!(mutated, serialized, "every field needs a mutation");
+        for (field, mutate) in MUTATIONS {
+            let tracker = proposal.begin_change();
+            let mut after = proposal.clone();
+            mutate(&mut after);
+            let diff = tracker.diff(&after);
+            assert_eq!(diff.fields().collect::<Vec<_>>(), vec![*field]);
+        }
+    }
+    #[test]
+    fn test_diff_of_a_lifecycle_step() {
+        let mut proposal = create_test_proposal();
+        let tracker = proposal.begin_change();
+        assert!(tracker.diff(&proposal).is_empty());
+        proposal.activate_with_time(1, 1, 150).unwrap();
+        let diff = tracker.diff(&proposal);
+        assert_eq!(
+            diff.fields().collect::<Vec<_>>(),
+            vec!["last_event_at", "status", "submitted_at"]
+        );
+        let status = diff.get("status").unwrap();
+        assert_eq!(
+            (status.old.clone(), status.new.clone()),
+            (Value::from("Draft"), Value::from("Active"))
+        );
+        assert_eq!(diff.get("submitted_at").unwrap().old, Value::Null);
+        let json = serde_json::to_string(&diff).unwrap();
+        assert_eq!(serde_json::from_str::<FieldDiff>(&json).unwrap(), diff);
+    }
+}
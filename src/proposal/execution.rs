@@ -0,0 +1,875 @@
+//! Two-phase proposal execution
+//!
+//! `prepare_execute` moves a Passed proposal to Executing and hands out a
+//! ticket; the caller performs the external side effect and then either
+//! `commit_execute`s (Executed) or `abort_execute`s (back to Passed). Tickets
+//! left open longer than a timeout are reverted by `check_stale_execution`.
+//!
+//! With an `ExecutionRetryPolicy`, `prepare_execute_with_policy` and
+//! `record_execution_attempt` add a capped result history, a backoff between
+//! failed attempts and auto-cancellation (`ReasonCode::ExecutionFailed`) once
+//! the attempts are used up.
+//!
+//! With `serde`, `dry_run` reports what executing a Passed proposal would
+//! change without changing it: the proposal's own `prepare_execute` and
+//! `commit_execute`, then, for a `GrantBatchPayload`,
+//! `grant::batch::approve_from_proposal` over the grants in the
+//! `ExecutionTargets`. It takes shared references only and works on copies.
+//! Parameter and treasury effects are carried out by the caller, not by this
+//! crate, so they are not part of the report.
+#[cfg(feature = "serde")]
+use super::diff::{field_diff, serialized_fields};
+use super::types::{Proposal, ProposalStatus};
+#[cfg(feature = "serde")]
+use crate::enums::EntityKind;
+use crate::error::FsmError;
+#[cfg(feature = "serde")]
+use crate::grant::{BatchApprovalConfig, Grant, GrantBatchPayload, approve_from_proposal};
+use crate::reason::ReasonCode;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde")]
+use serde_json::Value;
+
+/// Reason recorded when a stale ticket is reverted
+pub const STALE_EXECUTION_REASON: &str = "execution ticket expired";
+
+/// Maximum length of `ExecutionResult::detail`
+pub const MAX_EXECUTION_DETAIL_LEN: usize = 500;
+
+/// Number of results kept in `Proposal::execution_history`
+pub const EXECUTION_HISTORY_LEN: usize = 10;
+
+/// Outcome of one execution attempt
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExecutionResult {
+    pub attempted_at: i64,
+    pub success: bool,
+    /// Downstream message, e.g. the RPC error
+    pub detail: String,
+    /// Ticket id of the attempt (1-based)
+    pub attempt: u32,
+}
+
+/// How often and how fast a failed execution may be retried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExecutionRetryPolicy {
+    /// Attempts allowed in total, the first included
+    pub max_attempts: u32,
+    /// Seconds after a failed attempt before the next may be prepared
+    pub backoff: i64,
+}
+
+impl ExecutionRetryPolicy {
+    /// `InvalidInput` unless `max_attempts >= 1` and `backoff >= 0`
+    pub fn new(max_attempts: u32, backoff: i64) -> Result<Self, FsmError> {
+        if max_attempts == 0 || backoff < 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(Self {
+            max_attempts,
+            backoff,
+        })
+    }
+}
+
+impl Default for ExecutionRetryPolicy {
+    /// Three attempts, one hour apart
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff: 3600,
+        }
+    }
+}
+
+/// Handle for an in-flight execution
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ExecutionTicket {
+    pub proposal_id: u64,
+    /// Sequence number of the attempt (1-based)
+    pub ticket_id: u32,
+    pub prepared_at: i64,
+}
+
+impl<P> Proposal<P> {
+    /// Prepare execution (move from Passed to Executing)
+    pub fn prepare_execute(&mut self, current_time: i64) -> Result<ExecutionTicket, FsmError> {
+        let ticket = ExecutionTicket {
+            proposal_id: self.id,
+            ticket_id: self
+                .execution_attempts
+                .checked_add(1)
+                .ok_or(FsmError::Overflow)?,
+            prepared_at: current_time,
+        };
+        self.observe_timed_transition(
+            "prepare_execute",
+            current_time,
+            |proposal, current_time| {
+                if proposal.status != ProposalStatus::Passed {
+                    return Err(FsmError::InvalidStateTransition);
+                }
+                proposal.check_timelock(current_time)?;
+                proposal.status = ProposalStatus::Executing;
+                proposal.execution_ticket = Some(ticket);
+                proposal.execution_attempts = ticket.ticket_id;
+                Ok(())
+            },
+        )?;
+        Ok(ticket)
+    }
+
+    /// Commit a prepared execution (move from Executing to Executed)
+    pub fn commit_execute(
+        &mut self,
+        ticket: &ExecutionTicket,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition("commit_execute", current_time, |proposal, current_time| {
+            proposal.check_ticket(ticket)?;
+            proposal.status = ProposalStatus::Executed;
+            proposal.executed_at = Some(current_time);
+            proposal.execution_ticket = None;
+            proposal.execution_abort_reason = None;
+            Ok(())
+        })
+    }
+
+    /// Abort a prepared execution (move from Executing back to Passed)
+    pub fn abort_execute(
+        &mut self,
+        ticket: &ExecutionTicket,
+        reason: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition("abort_execute", current_time, |proposal, current_time| {
+            proposal.check_ticket(ticket)?;
+            proposal.status = ProposalStatus::Passed;
+            proposal.updated_at = Some(current_time);
+            proposal.execution_ticket = None;
+            proposal.execution_abort_reason = Some(reason);
+            Ok(())
+        })
+    }
+
+    /// Revert an Executing proposal whose ticket is older than `timeout` seconds
+    /// Returns true if the proposal was reverted to Passed
+    pub fn check_stale_execution(
+        &mut self,
+        timeout: i64,
+        current_time: i64,
+    ) -> Result<bool, FsmError> {
+        if timeout <= 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        let Some(ticket) = self.execution_ticket else {
+            return Ok(false);
+        };
+        if self.status != ProposalStatus::Executing {
+            return Ok(false);
+        }
+        let deadline = ticket
+            .prepared_at
+            .checked_add(timeout)
+            .ok_or(FsmError::Overflow)?;
+        if current_time < deadline {
+            return Ok(false);
+        }
+        self.abort_execute(&ticket, STALE_EXECUTION_REASON.to_string(), current_time)?;
+        Ok(true)
+    }
+
+    /// `prepare_execute` under a retry policy
+    ///
+    /// `InvalidState` once `policy.max_attempts` attempts were prepared;
+    /// `TooFrequentUpdate` before the last failed attempt's `attempted_at +
+    /// backoff`.
+    pub fn prepare_execute_with_policy(
+        &mut self,
+        policy: &ExecutionRetryPolicy,
+        current_time: i64,
+    ) -> Result<ExecutionTicket, FsmError> {
+        if self.execution_attempts >= policy.max_attempts {
+            return Err(FsmError::InvalidState);
+        }
+        if let Some(last) = self.execution_history.last()
+            && !last.success
+        {
+            let next_allowed = last
+                .attempted_at
+                .checked_add(policy.backoff)
+                .ok_or(FsmError::Overflow)?;
+            if current_time < next_allowed {
+                return Err(FsmError::TooFrequentUpdate);
+            }
+        }
+        self.prepare_execute(current_time)
+    }
+
+    /// Record the result of the attempt behind `ticket`
+    ///
+    /// Success commits the execution (Executed). Failure aborts it back to
+    /// Passed with `detail` as the abort reason, or, when the ticket was the
+    /// last attempt `policy` allows, cancels the proposal with
+    /// `ReasonCode::ExecutionFailed`. Once the ticket is committed or aborted
+    /// the result is appended to `execution_history`, which keeps the last
+    /// `EXECUTION_HISTORY_LEN`, even if the follow-up cancel fails.
+    /// `InvalidInput` for a `detail` over `MAX_EXECUTION_DETAIL_LEN` bytes or a
+    /// ticket that is not in flight.
+    pub fn record_execution_attempt(
+        &mut self,
+        ticket: &ExecutionTicket,
+        success: bool,
+        detail: String,
+        policy: &ExecutionRetryPolicy,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if detail.len() > MAX_EXECUTION_DETAIL_LEN {
+            return Err(FsmError::InvalidInput);
+        }
+        if success {
+            self.commit_execute(ticket, current_time)?;
+        } else {
+            self.abort_execute(ticket, detail.clone(), current_time)?;
+        }
+        if self.execution_history.len() >= EXECUTION_HISTORY_LEN {
+            self.execution_history.remove(0);
+        }
+        self.execution_history.push(ExecutionResult {
+            attempted_at: current_time,
+            success,
+            detail: detail.clone(),
+            attempt: ticket.ticket_id,
+        });
+        if !success && ticket.ticket_id >= policy.max_attempts {
+            self.cancel_failed_execution(detail, current_time)?;
+        }
+        Ok(())
+    }
+
+    fn cancel_failed_execution(
+        &mut self,
+        reason: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition("cancel", current_time, |proposal, current_time| {
+            proposal
+                .status
+                .validate_transition(&ProposalStatus::Cancelled)?;
+            proposal.status = ProposalStatus::Cancelled;
+            proposal.cancelled_at = Some(current_time);
+            proposal.cancellation_reason = Some(reason);
+            proposal.cancellation_code = Some(ReasonCode::ExecutionFailed);
+            Ok(())
+        })
+    }
+
+    fn check_ticket(&self, ticket: &ExecutionTicket) -> Result<(), FsmError> {
+        if self.status != ProposalStatus::Executing {
+            return Err(FsmError::InvalidStateTransition);
+        }
+        if self.execution_ticket.as_ref() != Some(ticket) {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+}
+
+/// What one `check_stale_executions` run did
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaleExecutionReport {
+    /// Proposals reverted to Passed, ids ascending
+    pub reverted: Vec<u64>,
+    /// Proposals whose check failed, ids ascending; they are left unchanged
+    pub failed: Vec<(u64, FsmError)>,
+}
+
+/// Revert every stale execution
+///
+/// A failing proposal is reported in `failed`; the rest are still checked.
+pub fn check_stale_executions<P>(
+    proposals: &mut [Proposal<P>],
+    timeout: i64,
+    current_time: i64,
+) -> StaleExecutionReport {
+    let mut report = StaleExecutionReport::default();
+    for proposal in proposals.iter_mut() {
+        match proposal.check_stale_execution(timeout, current_time) {
+            Ok(true) => report.reverted.push(proposal.id),
+            Ok(false) => {}
+            Err(error) => report.failed.push((proposal.id, error)),
+        }
+    }
+    report.reverted.sort_unstable();
+    report.failed.sort_unstable_by_key(|(id, _)| *id);
+    report
+}
+
+/// Entities an execution would touch, for `dry_run`
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug)]
+pub struct ExecutionTargets<'a> {
+    pub current_time: i64,
+    /// Grants a `GrantBatchPayload` may approve
+    pub grants: &'a [Grant],
+    pub batch_config: BatchApprovalConfig,
+}
+
+#[cfg(feature = "serde")]
+impl<'a> ExecutionTargets<'a> {
+    /// No grants and the default `BatchApprovalConfig`
+    pub fn new(current_time: i64) -> Self {
+        Self {
+            current_time,
+            grants: &[],
+            batch_config: BatchApprovalConfig::default(),
+        }
+    }
+
+    pub fn grants(mut self, grants: &'a [Grant]) -> Self {
+        self.grants = grants;
+        self
+    }
+
+    pub fn batch_config(mut self, batch_config: BatchApprovalConfig) -> Self {
+        self.batch_config = batch_config;
+        self
+    }
+}
+
+/// One field an execution would change, with its serialized old and new value
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlannedMutation {
+    pub entity: EntityKind,
+    pub id: u64,
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// What `dry_run` found
+#[cfg(feature = "serde")]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DryRunReport {
+    /// Proposal fields first, then grant fields in the order the grants were given;
+    /// fields of one entity sorted by name
+    pub mutations: Vec<PlannedMutation>,
+    /// Error the execution would stop with; `mutations` holds what the steps before it change
+    pub error: Option<FsmError>,
+}
+
+#[cfg(feature = "serde")]
+impl DryRunReport {
+    /// Would execute without error
+    pub fn succeeds(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Report what executing `proposal` against `targets` would change (see the module docs)
+///
+/// Mirrors `prepare_execute` and `commit_execute` at `targets.current_time`
+/// and, if the `execution_data` is a `GrantBatchPayload`,
+/// `approve_from_proposal` with `targets.batch_config`. Other execution data
+/// is left to the caller and adds nothing to the report.
+#[cfg(feature = "serde")]
+pub fn dry_run<P: Clone + Serialize>(
+    proposal: &Proposal<P>,
+    targets: ExecutionTargets<'_>,
+) -> DryRunReport {
+    let mut report = DryRunReport::default();
+    let mut executed = proposal.clone();
+    let committed = executed
+        .prepare_execute(targets.current_time)
+        .and_then(|ticket| executed.commit_execute(&ticket, targets.current_time));
+    plan(
+        &mut report,
+        EntityKind::Proposal,
+        proposal.id,
+        proposal,
+        &executed,
+    );
+    if let Err(error) = committed {
+        report.error = Some(error);
+        return report;
+    }
+    let payload = executed
+        .execution_data
+        .as_deref()
+        .and_then(|data| GrantBatchPayload::from_execution_data(data).ok());
+    if payload.is_some() {
+        let mut grants = targets.grants.to_vec();
+        if let Err(error) = approve_from_proposal(
+            &executed,
+            &mut grants,
+            &targets.batch_config,
+            targets.current_time,
+        ) {
+            report.error = Some(error);
+            return report;
+        }
+        for (before, after) in targets.grants.iter().zip(&grants) {
+            plan(&mut report, EntityKind::Grant, before.id, before, after);
+        }
+    }
+    report
+}
+
+#[cfg(feature = "serde")]
+fn plan<T: Serialize>(
+    report: &mut DryRunReport,
+    entity: EntityKind,
+    id: u64,
+    before: &T,
+    after: &T,
+) {
+    let diff = field_diff(&serialized_fields(before), &serialized_fields(after));
+    report
+        .mutations
+        .extend(diff.changes.into_iter().map(|change| PlannedMutation {
+            entity,
+            id,
+            field: change.field,
+            old: change.old,
+            new: change.new,
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    fn create_passed_proposal(id: u64) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal.pass_with_time(10).unwrap();
+        proposal
+    }
+    #[test]
+    fn test_prepare_and_commit() {
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+        assert_eq!(ticket.proposal_id, 1);
+        assert_eq!(ticket.ticket_id, 1);
+        assert_eq!(proposal.status, ProposalStatus::Executing);
+        assert_eq!(proposal.executed_at, None);
+
+        proposal.commit_execute(&ticket, 150).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(proposal.executed_at, Some(150));
+        assert_eq!(proposal.execution_ticket, None);
+    }
+    #[test]
+    fn test_abort_returns_to_passed() {
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+        proposal
+            .abort_execute(&ticket, "transfer failed".to_string(), 120)
+            .unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.executed_at, None);
+        assert_eq!(
+            proposal.execution_abort_reason,
+            Some("transfer failed".to_string())
+        );
+
+        // The aborted ticket can no longer be committed
+        assert_eq!(
+            proposal.commit_execute(&ticket, 130),
+            Err(FsmError::InvalidStateTransition)
+        );
+
+        // A new attempt gets a new ticket
+        let retry = proposal.prepare_execute(200).unwrap();
+        assert_eq!(retry.ticket_id, 2);
+        proposal.commit_execute(&retry, 210).unwrap();
+        assert_eq!(proposal.execution_abort_reason, None);
+    }
+    #[test]
+    fn test_prepare_requires_passed() {
+        let mut proposal = create_passed_proposal(1);
+        proposal.prepare_execute(100).unwrap();
+        assert_eq!(
+            proposal.prepare_execute(101),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(
+            proposal.execute_with_time(101),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(
+            proposal.cancel_with_time("no".to_string(), 101),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+    #[test]
+    fn test_commit_with_foreign_ticket() {
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+        let forged = ExecutionTicket {
+            prepared_at: 99,
+            ..ticket
+        };
+        assert_eq!(
+            proposal.commit_execute(&forged, 110),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Executing);
+    }
+    #[test]
+    fn test_stale_execution_timeout() {
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+
+        assert!(!proposal.check_stale_execution(60, 159).unwrap());
+        assert_eq!(proposal.status, ProposalStatus::Executing);
+
+        assert!(proposal.check_stale_execution(60, 160).unwrap());
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(
+            proposal.execution_abort_reason,
+            Some(STALE_EXECUTION_REASON.to_string())
+        );
+        assert_eq!(
+            proposal.commit_execute(&ticket, 170),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(
+            proposal.check_stale_execution(0, 170),
+            Err(FsmError::InvalidInput)
+        );
+    }
+    #[test]
+    fn test_check_stale_executions_batch() {
+        let mut stale = create_passed_proposal(1);
+        stale.prepare_execute(100).unwrap();
+        let mut fresh = create_passed_proposal(2);
+        fresh.prepare_execute(150).unwrap();
+        let idle = create_passed_proposal(3);
+
+        let mut proposals = vec![stale, fresh, idle];
+        let report = check_stale_executions(&mut proposals, 60, 170);
+        assert_eq!(report.reverted, vec![1]);
+        assert!(report.failed.is_empty());
+        assert_eq!(proposals[1].status, ProposalStatus::Executing);
+    }
+    #[test]
+    fn test_check_stale_executions_continues_past_failures() {
+        let mut broken = create_passed_proposal(1);
+        broken.prepare_execute(100).unwrap();
+        broken.execution_ticket.as_mut().unwrap().prepared_at = i64::MAX;
+        let mut stale = create_passed_proposal(2);
+        stale.prepare_execute(100).unwrap();
+
+        let mut proposals = vec![stale, broken];
+        let report = check_stale_executions(&mut proposals, 60, 170);
+        assert_eq!(report.reverted, vec![2]);
+        assert_eq!(report.failed, vec![(1, FsmError::Overflow)]);
+        assert_eq!(proposals[0].status, ProposalStatus::Passed);
+        assert_eq!(proposals[1].status, ProposalStatus::Executing);
+    }
+    #[test]
+    fn test_failed_attempt_returns_to_passed_with_backoff() {
+        let policy = ExecutionRetryPolicy::new(3, 60).unwrap();
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute_with_policy(&policy, 100).unwrap();
+        proposal
+            .record_execution_attempt(&ticket, false, "rpc timeout".to_string(), &policy, 110)
+            .unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(
+            proposal.execution_abort_reason,
+            Some("rpc timeout".to_string())
+        );
+        assert_eq!(
+            proposal.execution_history,
+            vec![ExecutionResult {
+                attempted_at: 110,
+                success: false,
+                detail: "rpc timeout".to_string(),
+                attempt: 1,
+            }]
+        );
+
+        // Not before attempted_at + backoff
+        assert_eq!(
+            proposal.prepare_execute_with_policy(&policy, 169),
+            Err(FsmError::TooFrequentUpdate)
+        );
+        let retry = proposal.prepare_execute_with_policy(&policy, 170).unwrap();
+        assert_eq!(retry.ticket_id, 2);
+        proposal
+            .record_execution_attempt(&retry, true, "tx 0xabc".to_string(), &policy, 180)
+            .unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Executed);
+        assert_eq!(proposal.executed_at, Some(180));
+        assert_eq!(proposal.execution_history.len(), 2);
+        assert!(proposal.execution_history[1].success);
+    }
+    #[test]
+    fn test_retries_exhausted_cancel() {
+        let policy = ExecutionRetryPolicy::new(2, 0).unwrap();
+        let mut proposal = create_passed_proposal(1);
+        for time in [100, 200] {
+            let ticket = proposal.prepare_execute_with_policy(&policy, time).unwrap();
+            proposal
+                .record_execution_attempt(&ticket, false, "reverted".to_string(), &policy, time + 1)
+                .unwrap();
+        }
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+        assert_eq!(proposal.cancelled_at, Some(201));
+        assert_eq!(
+            proposal.cancellation_code,
+            Some(ReasonCode::ExecutionFailed)
+        );
+        assert_eq!(proposal.cancellation_reason, Some("reverted".to_string()));
+        assert_eq!(proposal.execution_history.len(), 2);
+        assert_eq!(
+            proposal.prepare_execute_with_policy(&policy, 300),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            proposal.prepare_execute(300),
+            Err(FsmError::InvalidStateTransition)
+        );
+    }
+    #[test]
+    fn test_execution_history_is_capped() {
+        let policy = ExecutionRetryPolicy::new(20, 0).unwrap();
+        let mut proposal = create_passed_proposal(1);
+        for time in 100..112 {
+            let ticket = proposal.prepare_execute_with_policy(&policy, time).unwrap();
+            proposal
+                .record_execution_attempt(&ticket, false, format!("fail {time}"), &policy, time)
+                .unwrap();
+        }
+        assert_eq!(proposal.execution_history.len(), EXECUTION_HISTORY_LEN);
+        assert_eq!(proposal.execution_history[0].attempt, 3);
+        assert_eq!(proposal.execution_history[9].attempt, 12);
+    }
+    #[test]
+    fn test_record_execution_attempt_validates() {
+        let policy = ExecutionRetryPolicy::default();
+        assert_eq!(
+            ExecutionRetryPolicy::new(0, 10),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            ExecutionRetryPolicy::new(1, -1),
+            Err(FsmError::InvalidInput)
+        );
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+        assert_eq!(
+            proposal.record_execution_attempt(
+                &ticket,
+                false,
+                "x".repeat(MAX_EXECUTION_DETAIL_LEN + 1),
+                &policy,
+                110
+            ),
+            Err(FsmError::InvalidInput)
+        );
+        let forged = ExecutionTicket {
+            ticket_id: 9,
+            ..ticket
+        };
+        assert_eq!(
+            proposal.record_execution_attempt(&forged, true, String::new(), &policy, 110),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Executing);
+        assert!(proposal.execution_history.is_empty());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_execution_history_serializes() {
+        let policy = ExecutionRetryPolicy::default();
+        let mut proposal = create_passed_proposal(1);
+        let ticket = proposal.prepare_execute(100).unwrap();
+        proposal
+            .record_execution_attempt(&ticket, false, "rpc error".to_string(), &policy, 110)
+            .unwrap();
+        let json = serde_json::to_string(&proposal).unwrap();
+        let back: Proposal<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.execution_history, proposal.execution_history);
+
+        // Records from before the field existed load with an empty history
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value.as_object_mut().unwrap().remove("execution_history");
+        let legacy: Proposal<u8> = serde_json::from_value(value).unwrap();
+        assert!(legacy.execution_history.is_empty());
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_executing_status_and_ticket_serialize() {
+        let json = serde_json::to_string(&ProposalStatus::Executing).unwrap();
+        let status: ProposalStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(status, ProposalStatus::Executing);
+
+        let ticket = ExecutionTicket {
+            proposal_id: 1,
+            ticket_id: 3,
+            prepared_at: 100,
+        };
+        let json = serde_json::to_string(&ticket).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ExecutionTicket>(&json).unwrap(),
+            ticket
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    fn pending_grant(id: u64, amount: u64) -> Grant {
+        use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+        Grant::new(
+            id,
+            1,
+            [1u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            amount,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    fn create_batch_proposal() -> Proposal<u8> {
+        use crate::grant::GrantBatchEntry;
+        let mut proposal = create_passed_proposal(9);
+        let payload = GrantBatchPayload {
+            grants: vec![
+                GrantBatchEntry {
+                    grant_id: 1,
+                    amount: 500,
+                },
+                GrantBatchEntry {
+                    grant_id: 2,
+                    amount: 999,
+                },
+            ],
+        };
+        proposal.execution_data = Some(payload.to_execution_data().unwrap());
+        proposal
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_dry_run_predicts_execution() {
+        let proposal = create_batch_proposal();
+        let grants = vec![
+            pending_grant(1, 500),
+            pending_grant(2, 700),
+            pending_grant(3, 900),
+        ];
+        let before = (proposal.clone(), grants.clone());
+        let report = dry_run(&proposal, ExecutionTargets::new(20).grants(&grants));
+        assert!(report.succeeds());
+        assert_eq!((proposal.clone(), grants.clone()), before);
+
+        let mut executed = proposal.clone();
+        let ticket = executed.prepare_execute(20).unwrap();
+        executed.commit_execute(&ticket, 20).unwrap();
+        let mut approved = grants.clone();
+        approve_from_proposal(
+            &executed,
+            &mut approved,
+            &BatchApprovalConfig::default(),
+            20,
+        )
+        .unwrap();
+
+        let mut expected: Vec<(EntityKind, u64, _)> = proposal
+            .begin_change()
+            .diff(&executed)
+            .changes
+            .into_iter()
+            .map(|change| (EntityKind::Proposal, 9, change))
+            .collect();
+        for (before, after) in grants.iter().zip(&approved) {
+            let diff = field_diff(&serialized_fields(before), &serialized_fields(after));
+            expected.extend(
+                diff.changes
+                    .into_iter()
+                    .map(|change| (EntityKind::Grant, before.id, change)),
+            );
+        }
+        let predicted: Vec<(EntityKind, u64, _)> = report
+            .mutations
+            .iter()
+            .map(|m| {
+                (
+                    m.entity,
+                    m.id,
+                    crate::proposal::FieldChange {
+                        field: m.field.clone(),
+                        old: m.old.clone(),
+                        new: m.new.clone(),
+                    },
+                )
+            })
+            .collect();
+        assert_eq!(predicted, expected);
+        let status = |entity, id| {
+            report
+                .mutations
+                .iter()
+                .find(|m| m.entity == entity && m.id == id && m.field == "status")
+                .map(|m| m.new.clone())
+        };
+        assert_eq!(status(EntityKind::Proposal, 9), Some("Executed".into()));
+        assert_eq!(status(EntityKind::Grant, 1), Some("Approved".into()));
+        assert!(
+            !report
+                .mutations
+                .iter()
+                .any(|m| m.entity == EntityKind::Grant && m.id != 1)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_dry_run_reports_the_execution_error() {
+        let mut active = create_batch_proposal();
+        active.status = ProposalStatus::Active;
+        let report = dry_run(&active, ExecutionTargets::new(20));
+        assert_eq!(report.error, Some(FsmError::InvalidStateTransition));
+        assert!(report.mutations.is_empty());
+
+        let grants = [pending_grant(1, 500)];
+        let targets = ExecutionTargets::new(20)
+            .grants(&grants)
+            .batch_config(BatchApprovalConfig {
+                max_grants: 1,
+                max_total_amount: u64::MAX,
+            });
+        let report = dry_run(&create_batch_proposal(), targets);
+        assert_eq!(report.error, Some(FsmError::InvalidInput));
+        // The proposal is executed before the payload is refused
+        assert!(
+            report
+                .mutations
+                .iter()
+                .all(|m| m.entity == EntityKind::Proposal)
+        );
+        assert!(!report.mutations.is_empty());
+    }
+}
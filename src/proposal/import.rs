@@ -0,0 +1,334 @@
+//! Bulk vote import for migrations
+//!
+//! `import_votes` loads votes recorded by another system onto an Active
+//! proposal. Each vote is checked the way `cast_vote` would check it, except
+//! that its own `cast_at` is used instead of the current time: it must fall
+//! inside the voting window `[submitted_at, voting_ends_at)`, and the
+//! `time_ordering` of later events does not apply. Weights go through the
+//! snapshot, floor and divisor rules unchanged, so importing a sequence gives
+//! the same tallies as casting it.
+//!
+//! `import_historical_votes` also accepts proposals whose voting has already
+//! closed (any status with a `submitted_at`), and sets `last_tallied_at` to
+//! the latest imported vote even if that is earlier than its current value.
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+use crate::metrics;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One vote from the system being migrated
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImportedVote<P> {
+    pub voter: P,
+    pub support: bool,
+    /// Raw weight, as passed to `cast_vote`
+    pub weight: u64,
+    pub cast_at: i64,
+}
+
+/// What to do with a vote that fails validation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ImportMode {
+    /// Import nothing if any vote fails
+    #[default]
+    Strict,
+    /// Skip failing votes and import the rest
+    Lenient,
+}
+
+/// Why an imported vote was not recorded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SkipReason {
+    /// The voter already voted on the proposal or earlier in the batch
+    DuplicateVoter,
+    ZeroWeight,
+    /// `cast_at` is outside `[submitted_at, voting_ends_at)`
+    OutsideWindow,
+    /// The proposal is not Active and the import is not historical
+    NotActive,
+    /// Rejected by the weight or tally rules of `cast_vote`
+    Rejected(FsmError),
+}
+
+/// Outcome of an import; positions refer to the input list
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ImportReport {
+    /// Positions of the recorded votes, ascending
+    pub accepted: Vec<usize>,
+    /// Positions of the votes not recorded, with the reason, ascending
+    pub skipped: Vec<(usize, SkipReason)>,
+    /// A Strict import stopped at the first failure and recorded nothing
+    pub aborted: bool,
+}
+
+impl<P: Ord + Clone> Proposal<P> {
+    /// Import votes onto an Active proposal (see the module docs)
+    ///
+    /// Votes are applied in input order. In Strict mode the first failing vote
+    /// is the only one reported and the proposal is left unchanged.
+    pub fn import_votes(&mut self, votes: Vec<ImportedVote<P>>, mode: ImportMode) -> ImportReport {
+        self.import(votes, mode, false)
+    }
+
+    /// `import_votes` that also accepts proposals whose voting has closed
+    ///
+    /// Back-dates `last_tallied_at` to the latest imported vote.
+    pub fn import_historical_votes(
+        &mut self,
+        votes: Vec<ImportedVote<P>>,
+        mode: ImportMode,
+    ) -> ImportReport {
+        self.import(votes, mode, true)
+    }
+
+    fn import(
+        &mut self,
+        votes: Vec<ImportedVote<P>>,
+        mode: ImportMode,
+        historical: bool,
+    ) -> ImportReport {
+        let report = self.apply_import(votes, mode, historical);
+        for _ in &report.accepted {
+            metrics::record_vote::<SkipReason>("proposal", "import", &Ok(()));
+        }
+        for (_, reason) in &report.skipped {
+            metrics::record_vote("proposal", "import", &Err(reason));
+        }
+        report
+    }
+
+    fn apply_import(
+        &mut self,
+        votes: Vec<ImportedVote<P>>,
+        mode: ImportMode,
+        historical: bool,
+    ) -> ImportReport {
+        // Strict imports work on a copy so a late failure leaves nothing behind
+        let mut target = match mode {
+            ImportMode::Strict => Some(self.clone()),
+            ImportMode::Lenient => None,
+        };
+        let proposal = target.as_mut().unwrap_or(self);
+        let mut report = ImportReport::default();
+        let mut seen: BTreeSet<P> = BTreeSet::new();
+        let mut latest = None;
+        for (index, vote) in votes.into_iter().enumerate() {
+            let cast_at = vote.cast_at;
+            match proposal.import_one(vote, historical, &mut seen) {
+                Ok(()) => {
+                    report.accepted.push(index);
+                    latest = latest.max(Some(cast_at));
+                }
+                Err(reason) => {
+                    report.skipped.push((index, reason));
+                    if mode == ImportMode::Strict {
+                        report.accepted.clear();
+                        report.aborted = true;
+                        return report;
+                    }
+                }
+            }
+        }
+        if let Some(latest) = latest {
+            proposal.last_tallied_at = if historical {
+                Some(latest)
+            } else {
+                proposal.last_tallied_at.max(Some(latest))
+            };
+        }
+        if let Some(imported) = target {
+            *self = imported;
+        }
+        report
+    }
+
+    fn import_one(
+        &mut self,
+        vote: ImportedVote<P>,
+        historical: bool,
+        seen: &mut BTreeSet<P>,
+    ) -> Result<(), SkipReason> {
+        if self.compacted {
+            return Err(SkipReason::Rejected(FsmError::InvalidState));
+        }
+        let Some(opened_at) = self.submitted_at else {
+            return Err(SkipReason::NotActive);
+        };
+        if self.status != ProposalStatus::Active && !historical {
+            return Err(SkipReason::NotActive);
+        }
+        if vote.weight == 0 {
+            return Err(SkipReason::ZeroWeight);
+        }
+        if self.has_voted(&vote.voter) || seen.contains(&vote.voter) {
+            return Err(SkipReason::DuplicateVoter);
+        }
+        let closes_at = self.voting_ends_at().map_err(SkipReason::Rejected)?;
+        if vote.cast_at < opened_at || vote.cast_at >= closes_at {
+            return Err(SkipReason::OutsideWindow);
+        }
+        self.check_snapshot_weight(&vote.voter, vote.weight)
+            .map_err(SkipReason::Rejected)?;
+        let weight = self.weigh(vote.weight).map_err(SkipReason::Rejected)?;
+        seen.insert(vote.voter.clone());
+        self.add_vote(vote.voter, vote.support, weight, vote.cast_at)
+            .map_err(SkipReason::Rejected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+
+    fn create_active_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(1000)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 1000).unwrap();
+        proposal
+    }
+
+    fn vote(voter: u8, support: bool, weight: u64, cast_at: i64) -> ImportedVote<u8> {
+        ImportedVote {
+            voter,
+            support,
+            weight,
+            cast_at,
+        }
+    }
+
+    fn mixed_batch() -> Vec<ImportedVote<u8>> {
+        vec![
+            vote(2, true, 50, 1000),
+            vote(3, false, 0, 1010),
+            vote(4, false, 30, 1020),
+            vote(2, false, 10, 1030),
+            vote(5, true, 5, 1040),
+            vote(6, true, 70, 1100),
+            vote(7, true, 20, 999),
+            vote(8, true, 25, 1099),
+        ]
+    }
+
+    #[test]
+    fn test_import_matches_cast_vote_sequence() {
+        let mut proposal = create_active_proposal();
+        proposal.normalize_weights(10).unwrap();
+        let mut cast = proposal.clone();
+        let batch = mixed_batch();
+        let report = proposal.import_votes(batch.clone(), ImportMode::Lenient);
+        assert_eq!(report.accepted, vec![0, 2, 7]);
+        assert_eq!(
+            report.skipped,
+            vec![
+                (1, SkipReason::ZeroWeight),
+                (3, SkipReason::DuplicateVoter),
+                (4, SkipReason::Rejected(FsmError::BelowMinimumWeight)),
+                (5, SkipReason::OutsideWindow),
+                (6, SkipReason::OutsideWindow),
+            ]
+        );
+        assert!(!report.aborted);
+
+        for &index in &report.accepted {
+            let v = &batch[index];
+            cast.cast_vote(v.voter, v.support, v.weight, v.cast_at)
+                .unwrap();
+        }
+        for &(index, _) in &report.skipped {
+            let v = &batch[index];
+            assert!(
+                cast.clone()
+                    .cast_vote(v.voter, v.support, v.weight, v.cast_at)
+                    .is_err()
+            );
+        }
+        assert_eq!(proposal, cast);
+        assert_eq!((proposal.yes_votes, proposal.no_votes), (7, 3));
+    }
+
+    #[test]
+    fn test_strict_import_is_all_or_nothing() {
+        let mut proposal = create_active_proposal();
+        let before = proposal.clone();
+        let report = proposal.import_votes(mixed_batch(), ImportMode::Strict);
+        assert_eq!(
+            report,
+            ImportReport {
+                accepted: Vec::new(),
+                skipped: vec![(1, SkipReason::ZeroWeight)],
+                aborted: true,
+            }
+        );
+        assert_eq!(proposal, before);
+
+        let report = proposal.import_votes(
+            vec![vote(2, true, 5, 1000), vote(3, false, 4, 1050)],
+            ImportMode::Strict,
+        );
+        assert_eq!(report.accepted, vec![0, 1]);
+        assert_eq!((proposal.yes_votes, proposal.no_votes), (5, 4));
+    }
+
+    #[test]
+    fn test_historical_import_on_closed_proposal() {
+        let mut proposal = create_active_proposal();
+        proposal.cast_vote(2, true, 5, 1090).unwrap();
+        proposal.pass_with_time(1200).unwrap();
+        let status = proposal.status.clone();
+        assert_ne!(status, ProposalStatus::Active);
+
+        let votes = vec![vote(3, true, 4, 1010), vote(4, false, 2, 1020)];
+        let report = proposal.import_votes(votes.clone(), ImportMode::Lenient);
+        assert_eq!(
+            report.skipped,
+            vec![(0, SkipReason::NotActive), (1, SkipReason::NotActive)]
+        );
+
+        let report = proposal.import_historical_votes(votes, ImportMode::Strict);
+        assert_eq!(report.accepted, vec![0, 1]);
+        assert_eq!(proposal.status, status);
+        assert_eq!((proposal.yes_votes, proposal.no_votes), (9, 2));
+        // Back-dated to the latest imported vote
+        assert_eq!(proposal.last_tallied_at, Some(1020));
+
+        let mut draft = Proposal::builder(
+            2,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1u8,
+        )
+        .build(1000)
+        .unwrap();
+        let report =
+            draft.import_historical_votes(vec![vote(3, true, 1, 1000)], ImportMode::Lenient);
+        assert_eq!(report.skipped, vec![(0, SkipReason::NotActive)]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_import_report_serializes() {
+        let report = ImportReport {
+            accepted: vec![0],
+            skipped: vec![(1, SkipReason::Rejected(FsmError::Overflow))],
+            aborted: false,
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(serde_json::from_str::<ImportReport>(&json).unwrap(), report);
+    }
+}
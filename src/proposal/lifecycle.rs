@@ -1,7 +1,16 @@
 //! Proposal lifecycle methods
+use super::activation::ActivationContext;
+use super::builder::ProposalBuilder;
+use super::outcome::TransitionOutcome;
+use super::type_registry::TieBreakPolicy;
 use super::types::{Proposal, ProposalStatus};
 use crate::error::FsmError;
-use std::marker::PhantomData;
+#[cfg(feature = "governance-meta")]
+use crate::governance::{SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus};
+use crate::metrics;
+use crate::reason::ReasonCode;
+use crate::telemetry::OperationSpan;
+use crate::time_guard::EventTime;
 impl<P> Proposal<P> {
     /// Create a new proposal with current time
     pub fn new(
@@ -22,77 +31,35 @@ impl<P> Proposal<P> {
         author: P,
         current_time: i64,
     ) -> Result<Proposal<P>, FsmError> {
-        if title.is_empty() {
-            return Err(FsmError::InvalidInput);
-        }
-        if title.len() > 200 {
-            return Err(FsmError::InvalidInput);
-        }
-        if description.is_empty() {
-            return Err(FsmError::InvalidInput);
-        }
-        if description.len() > 2000 {
-            return Err(FsmError::InvalidInput);
-        }
-        if proposal_type.is_empty() {
-            return Err(FsmError::InvalidInput);
-        }
-        if proposal_type.len() > 50 {
-            return Err(FsmError::InvalidInput);
-        }
-        Ok(Self {
-            id,
-            title,
-            description,
-            proposal_type,
-            author,
-            created_at: current_time,
-            updated_at: None,
-            submitted_at: None,
-            cancelled_at: None,
-            executed_at: None,
-            archived_at: None,
-            voting_duration: 7 * 24 * 3600, // 7 days default
-            status: ProposalStatus::Draft,
-            yes_votes: 0,
-            no_votes: 0,
-            total_votes: 0,
-            last_tallied_at: None,
-            cancellation_reason: None,
-            execution_data: None,
-            expires_at: None,
-            idea_id: None,
-            treasury_operation: None,
-            _phantom: PhantomData,
-        })
+        ProposalBuilder::new(id, title, description, proposal_type, author).build(current_time)
+    }
+    /// End of the voting window: `submitted_at` (else `created_at`) plus
+    /// `voting_duration`; `Overflow` past `i64::MAX`
+    pub fn voting_ends_at(&self) -> Result<i64, FsmError> {
+        self.submitted_at
+            .unwrap_or(self.created_at)
+            .checked_add(self.voting_duration.as_secs())
+            .ok_or(FsmError::Overflow)
     }
     /// Activate proposal (move from Draft to Active)
     pub fn activate(&mut self, min_quorum: u64, total_members: u64) -> Result<(), FsmError> {
         self.activate_with_time(min_quorum, total_members, 0)
     }
     /// Activate proposal with specified time
+    ///
+    /// Runs the member quorum gate only; see `activate_in` for the full set.
     pub fn activate_with_time(
         &mut self,
         min_quorum: u64,
         total_members: u64,
         current_time: i64,
     ) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Draft {
-            return Err(FsmError::InvalidInput);
-        }
-        if total_members < min_quorum {
-            return Err(FsmError::InsufficientMembers);
-        }
-        if min_quorum == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-        if total_members == 0 {
-            return Err(FsmError::InvalidInput);
-        }
-
-        self.status = ProposalStatus::Active;
-        self.submitted_at = Some(current_time);
-        Ok(())
+        self.activate_in(&ActivationContext::new(
+            min_quorum,
+            total_members,
+            current_time,
+        ))
+        .map(|_| ())
     }
     /// Pass proposal (move from Active to Passed)
     pub fn pass(&mut self) -> Result<(), FsmError> {
@@ -100,18 +67,20 @@ impl<P> Proposal<P> {
     }
     /// Pass proposal with specified time
     pub fn pass_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Active {
-            return Err(FsmError::InvalidInput);
-        }
+        self.observe_timed_transition("pass", current_time, |proposal, current_time| {
+            if proposal.status != ProposalStatus::Active {
+                return Err(FsmError::InvalidStateTransition);
+            }
 
-        // Check that voting is completed
-        let voting_end = self.created_at + self.voting_duration;
-        if current_time < voting_end {
-            return Err(FsmError::InvalidState);
-        }
+            // Check that voting is completed
+            if current_time < proposal.voting_ends_at()? {
+                return Err(FsmError::InvalidState);
+            }
 
-        self.status = ProposalStatus::Passed;
-        Ok(())
+            proposal.status = ProposalStatus::Passed;
+            proposal.passed_at = Some(current_time);
+            Ok(())
+        })
     }
     /// Reject proposal (move from Active to Rejected)
     pub fn reject(&mut self) -> Result<(), FsmError> {
@@ -119,18 +88,35 @@ impl<P> Proposal<P> {
     }
     /// Reject proposal with specified time
     pub fn reject_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Active {
-            return Err(FsmError::InvalidInput);
-        }
+        self.observe_timed_transition("reject", current_time, |proposal, current_time| {
+            if proposal.status != ProposalStatus::Active {
+                return Err(FsmError::InvalidStateTransition);
+            }
 
-        // Check that voting is completed
-        let voting_end = self.created_at + self.voting_duration;
-        if current_time < voting_end {
-            return Err(FsmError::InvalidState);
-        }
+            // Check that voting is completed
+            if current_time < proposal.voting_ends_at()? {
+                return Err(FsmError::InvalidState);
+            }
 
-        self.status = ProposalStatus::Rejected;
-        Ok(())
+            proposal.status = ProposalStatus::Rejected;
+            Ok(())
+        })
+    }
+    /// Move an Active proposal whose voting ended short of quorum to QuorumFailed
+    pub fn fail_quorum_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
+        self.observe_timed_transition("fail_quorum", current_time, |proposal, current_time| {
+            if proposal.status != ProposalStatus::Active {
+                return Err(FsmError::InvalidStateTransition);
+            }
+
+            // Check that voting is completed
+            if current_time < proposal.voting_ends_at()? {
+                return Err(FsmError::InvalidState);
+            }
+
+            proposal.status = ProposalStatus::QuorumFailed;
+            Ok(())
+        })
     }
     /// Execute proposal (move from Passed to Executed)
     pub fn execute(&mut self) -> Result<(), FsmError> {
@@ -138,61 +124,99 @@ impl<P> Proposal<P> {
     }
     /// Execute proposal with specified time
     pub fn execute_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Passed {
-            return Err(FsmError::InvalidInput);
-        }
-        if self.executed_at.is_some() {
-            return Err(FsmError::InvalidState);
-        }
+        self.observe_timed_transition("execute", current_time, |proposal, current_time| {
+            if proposal.status != ProposalStatus::Passed {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            if proposal.executed_at.is_some() {
+                return Err(FsmError::InvalidState);
+            }
+            proposal.check_timelock(current_time)?;
 
-        self.status = ProposalStatus::Executed;
-        self.executed_at = Some(current_time);
-        Ok(())
+            proposal.status = ProposalStatus::Executed;
+            proposal.executed_at = Some(current_time);
+            Ok(())
+        })
     }
-    /// Cancel proposal (move from Draft or Active to Cancelled)
+    /// Cancel proposal (move from Draft, Active or Passed to Cancelled)
     pub fn cancel(&mut self, reason: String) -> Result<(), FsmError> {
         self.cancel_with_time(reason, 0)
     }
     /// Cancel proposal with specified time
+    ///
+    /// Records `ReasonCode::Other`; use `cancel_with_code` to give a code.
     pub fn cancel_with_time(&mut self, reason: String, current_time: i64) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Draft && self.status != ProposalStatus::Active {
-            return Err(FsmError::InvalidInput);
-        }
-        self.status = ProposalStatus::Cancelled;
-        self.cancelled_at = Some(current_time);
-        self.cancellation_reason = Some(reason);
-        Ok(())
+        self.cancel_with_code(ReasonCode::Other, reason, current_time)
+    }
+    /// Cancel proposal with a reason code and free-text detail
+    ///
+    /// `ReasonRequired` or `ReasonNotAllowed` if `reason` breaks `reason_policy`.
+    pub fn cancel_with_code(
+        &mut self,
+        code: ReasonCode,
+        reason: String,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        self.observe_timed_transition("cancel", current_time, |proposal, current_time| {
+            if !matches!(
+                proposal.status,
+                ProposalStatus::Draft | ProposalStatus::Active | ProposalStatus::Passed
+            ) {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            proposal.reason_policy.check(
+                &proposal.status,
+                &ProposalStatus::Cancelled,
+                Some(&reason),
+            )?;
+            proposal.status = ProposalStatus::Cancelled;
+            proposal.cancelled_at = Some(current_time);
+            proposal.cancellation_reason = Some(reason);
+            proposal.cancellation_code = Some(code);
+            Ok(())
+        })
     }
-    /// Archive proposal (move from Executed, Rejected, or Cancelled to Archived)
+    /// Archive proposal (move from Executed, Rejected, QuorumFailed or Cancelled to Archived)
     pub fn archive(&mut self) -> Result<(), FsmError> {
         self.archive_with_time(0)
     }
     /// Archive proposal with specified time
     pub fn archive_with_time(&mut self, current_time: i64) -> Result<(), FsmError> {
-        if self.status != ProposalStatus::Executed
-            && self.status != ProposalStatus::Rejected
-            && self.status != ProposalStatus::Cancelled
-        {
-            return Err(FsmError::InvalidInput);
-        }
-        self.status = ProposalStatus::Archived;
-        self.archived_at = Some(current_time);
-        Ok(())
+        self.observe_timed_transition("archive", current_time, |proposal, current_time| {
+            if proposal.status != ProposalStatus::Executed
+                && proposal.status != ProposalStatus::Rejected
+                && proposal.status != ProposalStatus::QuorumFailed
+                && proposal.status != ProposalStatus::Cancelled
+            {
+                return Err(FsmError::InvalidStateTransition);
+            }
+            proposal.status = ProposalStatus::Archived;
+            proposal.archived_at = Some(current_time);
+            Ok(())
+        })
     }
     /// Check if proposal has expired and auto-archive if needed
     /// Returns true if proposal was archived, false otherwise
     pub fn check_and_auto_archive(&mut self, current_time: i64) -> Result<bool, FsmError> {
         if let Some(expires_at) = self.expires_at
             && current_time >= expires_at
-            && (self.status == ProposalStatus::Executed
-                || self.status == ProposalStatus::Rejected
-                || self.status == ProposalStatus::Cancelled)
+            && self.archives_on_expiry()
         {
             self.archive_with_time(current_time)?;
             return Ok(true);
         }
         Ok(false)
     }
+    /// Whether `check_and_auto_archive` archives the proposal once `expires_at` passes
+    pub(crate) fn archives_on_expiry(&self) -> bool {
+        matches!(
+            self.status,
+            ProposalStatus::Executed
+                | ProposalStatus::Rejected
+                | ProposalStatus::QuorumFailed
+                | ProposalStatus::Cancelled
+        )
+    }
     /// Set expiration time for proposal
     pub fn set_expiration(&mut self, expires_at: Option<i64>) -> Result<(), FsmError> {
         if let Some(exp) = expires_at
@@ -205,32 +229,193 @@ impl<P> Proposal<P> {
     }
     /// Automatically transition Active proposal to Passed/Rejected based on votes
     /// This checks voting period end and vote counts
+    #[deprecated(note = "use `auto_transition_detailed`, which reports why")]
     pub fn auto_transition_after_voting(&mut self, current_time: i64) -> Result<bool, FsmError> {
+        Ok(self.auto_transition_detailed(current_time)?.transitioned())
+    }
+    /// `voting_duration` in seconds
+    #[deprecated(note = "use `voting_duration.as_secs()`")]
+    pub fn voting_duration_secs(&self) -> i64 {
+        self.voting_duration.as_secs()
+    }
+    /// `execution_timelock` in seconds
+    #[deprecated(note = "use `execution_timelock.as_secs()`")]
+    pub fn execution_timelock_secs(&self) -> i64 {
+        self.execution_timelock.as_secs()
+    }
+    /// Move an Active proposal whose voting has ended to Passed, Rejected or
+    /// Tied under its tally policy, and say what happened
+    ///
+    /// A tie is settled by `tie_break_policy`: StatusQuo and AuthorLoses
+    /// reject, Manual and an unsupplied ChairDecides leave the proposal Tied.
+    /// A policy that settles the tie is recorded in `tie_break_applied`.
+    pub fn auto_transition_detailed(
+        &mut self,
+        current_time: i64,
+    ) -> Result<TransitionOutcome, FsmError> {
+        self.settle_voting(None, current_time)
+    }
+    /// `auto_transition_detailed` with the board decision a `ChairDecides` tie-break needs
+    ///
+    /// The decision passes a tied proposal only if it is Approved, about this
+    /// proposal, and the policy's `decision_ref`; otherwise it is ignored.
+    #[cfg(feature = "governance-meta")]
+    pub fn auto_transition_with_chair_decision<Q>(
+        &mut self,
+        decision: &SecurityBoardDecisionMetadata<Q>,
+        current_time: i64,
+    ) -> Result<TransitionOutcome, FsmError> {
+        let approved = (decision.status == SecurityBoardDecisionStatus::Approved
+            && decision.proposal_id == Some(self.id))
+        .then_some(decision.decision_id);
+        self.settle_voting(approved, current_time)
+    }
+    /// `approved_decision` is the id of an Approved board decision on this proposal
+    fn settle_voting(
+        &mut self,
+        approved_decision: Option<u64>,
+        current_time: i64,
+    ) -> Result<TransitionOutcome, FsmError> {
         if self.status != ProposalStatus::Active {
-            return Ok(false);
+            return Ok(TransitionOutcome::SkippedWrongStatus {
+                status: self.status.clone(),
+            });
         }
-        // Check if voting period has ended
-        // Use submitted_at if available (when proposal was activated), otherwise created_at
-        let voting_start = self.submitted_at.unwrap_or(self.created_at);
-        let voting_end = voting_start
-            .checked_add(self.voting_duration)
-            .ok_or(FsmError::Overflow)?;
-        if current_time >= voting_end {
-            // Determine result based on votes
-            if self.yes_votes > self.no_votes {
+        let voting_end = self.voting_ends_at()?;
+        if current_time < voting_end {
+            return Ok(TransitionOutcome::NotDue {
+                ends_at: voting_end,
+            });
+        }
+        let (yes, no) = (self.yes_votes, self.no_votes);
+        // Determine result based on votes and the tally policy
+        match self.tally_policy.decide(yes, no) {
+            Some(true) => {
                 self.pass_with_time(current_time)?;
-                return Ok(true);
-            } else if self.no_votes > self.yes_votes {
+                Ok(TransitionOutcome::Passed { yes, no })
+            }
+            Some(false) => {
                 self.reject_with_time(current_time)?;
-                return Ok(true);
-            } else {
+                Ok(TransitionOutcome::Rejected { yes, no })
+            }
+            None => {
+                let tie_break = self.tie_break_policy;
+                let passes = match tie_break {
+                    TieBreakPolicy::Manual => None,
+                    TieBreakPolicy::StatusQuo | TieBreakPolicy::AuthorLoses => Some(false),
+                    TieBreakPolicy::ChairDecides { decision_ref } => {
+                        (approved_decision == Some(decision_ref)).then_some(true)
+                    }
+                };
+                if let Some(passes) = passes {
+                    let outcome = if passes {
+                        self.pass_with_time(current_time)?;
+                        TransitionOutcome::Passed { yes, no }
+                    } else {
+                        self.reject_with_time(current_time)?;
+                        TransitionOutcome::Rejected { yes, no }
+                    };
+                    self.tie_break_applied = Some(tie_break);
+                    return Ok(outcome);
+                }
                 // Tied - set status to Tied
-                self.status = ProposalStatus::Tied;
-                self.last_tallied_at = Some(current_time);
-                return Ok(true);
+                self.observe_timed_transition("tally", current_time, |proposal, current_time| {
+                    proposal.status = ProposalStatus::Tied;
+                    proposal.last_tallied_at = Some(current_time);
+                    Ok(())
+                })?;
+                Ok(TransitionOutcome::Tied { each: yes })
             }
         }
-        Ok(false)
+    }
+    /// `auto_transition_detailed` for a vote with a quorum requirement
+    ///
+    /// `participation` and `required` are percentages of the eligible weight,
+    /// as `QuorumMetadata` keeps them; quorum is met when `participation`
+    /// reaches `required`. Once voting has ended a proposal short of quorum
+    /// becomes QuorumFailed, even with majority support; with quorum the tally
+    /// policy decides.
+    pub fn auto_transition_with_quorum(
+        &mut self,
+        participation: u8,
+        required: u8,
+        current_time: i64,
+    ) -> Result<TransitionOutcome, FsmError> {
+        if participation >= required || self.status != ProposalStatus::Active {
+            return self.auto_transition_detailed(current_time);
+        }
+        let voting_end = self.voting_ends_at()?;
+        if current_time < voting_end {
+            return Ok(TransitionOutcome::NotDue {
+                ends_at: voting_end,
+            });
+        }
+        self.fail_quorum_with_time(current_time)?;
+        Ok(TransitionOutcome::QuorumFailed {
+            participation,
+            required,
+        })
+    }
+    /// `InvalidState` until `execution_timelock` has passed since `passed_at`
+    pub(crate) fn check_timelock(&self, current_time: i64) -> Result<(), FsmError> {
+        if let Some(passed_at) = self.passed_at {
+            let unlocks_at = passed_at
+                .checked_add(self.execution_timelock.as_secs())
+                .ok_or(FsmError::Overflow)?;
+            if current_time < unlocks_at {
+                return Err(FsmError::InvalidState);
+            }
+        }
+        Ok(())
+    }
+    /// Run a lifecycle step and report its outcome to the metrics sink
+    ///
+    /// A compacted proposal fails every step with `InvalidState`.
+    pub(crate) fn observe_transition(
+        &mut self,
+        action: &str,
+        apply: impl FnOnce(&mut Self) -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        let from = self.status.clone();
+        let span = OperationSpan::transition("proposal", self.id, action, &from);
+        let result = if self.compacted {
+            Err(FsmError::InvalidState)
+        } else {
+            apply(self)
+        };
+        metrics::record_transition("proposal", action, &from, &self.status, &result);
+        span.finish(Some(&self.status), &result);
+        result
+    }
+    /// `observe_transition` for a step taken at `current_time`
+    ///
+    /// Fails with `TimeRegression` before running `apply` if `current_time` breaks
+    /// `time_ordering` by more than the clock skew tolerance. `apply` gets the
+    /// time to record, clamped if it was within the tolerance; on success
+    /// `last_event_at` advances to it.
+    pub(crate) fn observe_timed_transition(
+        &mut self,
+        action: &str,
+        current_time: i64,
+        apply: impl FnOnce(&mut Self, i64) -> Result<(), FsmError>,
+    ) -> Result<(), FsmError> {
+        self.observe_transition(action, |proposal| {
+            let current_time = proposal.event_time(current_time)?.time;
+            apply(proposal, current_time)?;
+            proposal.last_event_at = proposal.last_event_at.max(current_time);
+            Ok(())
+        })
+    }
+    /// Time a transition at `current_time` would be recorded at
+    ///
+    /// `TimeRegression` if it lags `last_event_at` by more than
+    /// `clock_skew_tolerance_seconds` (see `TimeOrdering::resolve`).
+    pub fn event_time(&self, current_time: i64) -> Result<EventTime, FsmError> {
+        self.time_ordering.resolve(
+            self.last_event_at,
+            current_time,
+            self.clock_skew_tolerance_seconds,
+        )
     }
     /// Check if proposal can be auto-activated (for future use)
     /// Currently returns false - activation requires manual call
@@ -243,6 +428,7 @@ impl<P> Proposal<P> {
 mod tests {
     use super::*;
     use crate::error::FsmError;
+    use crate::time_guard::TimeOrdering;
     fn create_test_pubkey(seed: u8) -> u8 {
         seed
     }
@@ -330,7 +516,7 @@ mod tests {
         proposal.activate_with_time(10, 20, 2000).unwrap();
 
         // Pass after voting duration
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         assert!(proposal.pass_with_time(voting_end + 1).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Passed);
     }
@@ -356,6 +542,27 @@ mod tests {
         );
     }
     #[test]
+    fn test_late_activation_keeps_voting_open_until_its_own_end() {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(crate::duration::DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 500).unwrap();
+        // created_at + duration has long passed, but voting opened at 500
+        assert_eq!(proposal.pass_with_time(500), Err(FsmError::InvalidState));
+        assert_eq!(proposal.reject_with_time(599), Err(FsmError::InvalidState));
+        proposal.cast_vote(2, true, 1, 599).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        assert!(proposal.clone().reject_with_time(600).is_ok());
+        assert!(proposal.pass_with_time(600).is_ok());
+    }
+    #[test]
     fn test_proposal_reject_with_time() {
         let author = create_test_pubkey(1);
         let mut proposal = Proposal::<u8>::new_with_time(
@@ -369,7 +576,7 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
 
         assert!(proposal.reject_with_time(voting_end + 1).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Rejected);
@@ -415,12 +622,12 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.pass_with_time(voting_end + 1).unwrap();
 
-        assert!(proposal.execute_with_time(5000).is_ok());
+        assert!(proposal.execute_with_time(voting_end + 2).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Executed);
-        assert_eq!(proposal.executed_at, Some(5000));
+        assert_eq!(proposal.executed_at, Some(voting_end + 2));
         // execution_data is set separately in real usage
     }
     #[test]
@@ -437,14 +644,14 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.pass_with_time(voting_end + 1).unwrap();
-        proposal.execute_with_time(3000).unwrap();
+        proposal.execute_with_time(voting_end + 2).unwrap();
 
         // Can archive executed proposal
-        assert!(proposal.archive_with_time(4000).is_ok());
+        assert!(proposal.archive_with_time(voting_end + 3).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Archived);
-        assert_eq!(proposal.archived_at, Some(4000));
+        assert_eq!(proposal.archived_at, Some(voting_end + 3));
     }
     #[test]
     fn test_proposal_archive_with_time_rejected() {
@@ -460,11 +667,11 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.reject_with_time(voting_end + 1).unwrap();
 
         // Can archive rejected proposal
-        assert!(proposal.archive_with_time(4000).is_ok());
+        assert!(proposal.archive_with_time(voting_end + 2).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Archived);
     }
     #[test]
@@ -505,13 +712,13 @@ mod tests {
         // Cannot archive Draft or Active proposal
         assert_eq!(
             proposal.archive_with_time(4000).unwrap_err(),
-            FsmError::InvalidInput
+            FsmError::InvalidStateTransition
         );
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
         assert_eq!(
             proposal.archive_with_time(4000).unwrap_err(),
-            FsmError::InvalidInput
+            FsmError::InvalidStateTransition
         );
     }
     #[test]
@@ -534,7 +741,7 @@ mod tests {
         assert_eq!(proposal.author, author);
         assert_eq!(proposal.created_at, 5000);
         assert_eq!(proposal.status, ProposalStatus::Draft);
-        assert_eq!(proposal.voting_duration, 7 * 24 * 3600);
+        assert_eq!(proposal.voting_duration.as_secs(), 7 * 24 * 3600);
     }
     #[test]
     fn test_proposal_activate_with_time_zero_quorum() {
@@ -590,7 +797,7 @@ mod tests {
         proposal.activate_with_time(10, 20, 2000).unwrap();
 
         // Pass exactly at voting end
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         assert!(proposal.pass_with_time(voting_end).is_ok());
         assert_eq!(proposal.status, ProposalStatus::Passed);
     }
@@ -608,14 +815,14 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.pass_with_time(voting_end + 1).unwrap();
-        proposal.execute_with_time(5000).unwrap();
+        proposal.execute_with_time(voting_end + 2).unwrap();
 
         // Try to execute again - should fail
         assert_eq!(
-            proposal.execute_with_time(6000).unwrap_err(),
-            FsmError::InvalidInput
+            proposal.execute_with_time(voting_end + 3).unwrap_err(),
+            FsmError::InvalidStateTransition
         );
     }
     #[test]
@@ -639,6 +846,35 @@ mod tests {
         );
         assert_eq!(proposal.status, ProposalStatus::Cancelled);
         assert_eq!(proposal.cancellation_reason, Some("Reason".to_string()));
+        assert_eq!(proposal.cancellation_code, Some(ReasonCode::Other));
+    }
+    #[test]
+    fn test_proposal_cancel_with_code() {
+        let mut proposal = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+
+        proposal
+            .cancel_with_code(
+                ReasonCode::SupersededBy { id: 2 },
+                "Merged into #2".to_string(),
+                3000,
+            )
+            .unwrap();
+        assert_eq!(
+            proposal.cancellation_code,
+            Some(ReasonCode::SupersededBy { id: 2 })
+        );
+        assert_eq!(
+            proposal.cancellation_reason,
+            Some("Merged into #2".to_string())
+        );
     }
     #[test]
     fn test_proposal_cancel_with_time_invalid_status() {
@@ -654,16 +890,88 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.pass_with_time(voting_end + 1).unwrap();
+        proposal.execute_with_time(voting_end + 2).unwrap();
 
-        // Cannot cancel Passed proposal
+        // Cannot cancel Executed proposal
         assert_eq!(
             proposal
-                .cancel_with_time("Reason".to_string(), 3000)
+                .cancel_with_time("Reason".to_string(), voting_end + 3)
                 .unwrap_err(),
-            FsmError::InvalidInput
+            FsmError::InvalidStateTransition
+        );
+    }
+    #[test]
+    fn test_proposal_cancel_passed() {
+        let mut proposal = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+        proposal.activate_with_time(10, 20, 2000).unwrap();
+        let voting_end = proposal.voting_ends_at().unwrap();
+        proposal.pass_with_time(voting_end + 1).unwrap();
+        // The transition table lists Passed -> Cancelled
+        assert!(
+            proposal
+                .status
+                .can_transition_to(&ProposalStatus::Cancelled)
+        );
+        proposal
+            .cancel_with_time("Withdrawn".to_string(), voting_end + 2)
+            .unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+    }
+    #[test]
+    fn test_proposal_cancel_reason_policy() {
+        use crate::reason_policy::{ReasonPolicy, ReasonRequirement};
+        let draft = |policy: ReasonPolicy<ProposalStatus>| {
+            Proposal::builder(
+                1,
+                "Test".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                create_test_pubkey(1),
+            )
+            .reason_policy(policy)
+            .build(1000)
+            .unwrap()
+        };
+        // Optional (default): an empty reason is still accepted
+        let mut proposal = draft(ReasonPolicy::new());
+        proposal.cancel_with_time(String::new(), 2000).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+        // Required: a blank reason is refused and nothing changes
+        let mut proposal = draft(ReasonPolicy::strict());
+        let err = proposal
+            .cancel_with_time(" ".to_string(), 2000)
+            .unwrap_err();
+        assert!(matches!(err, FsmError::ReasonRequired(_)));
+        assert_eq!(
+            err.to_string(),
+            "A reason is required for the Proposal Draft -> Cancelled transition"
         );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+        proposal
+            .cancel_with_time("Duplicate".to_string(), 2000)
+            .unwrap();
+        // Forbidden: only reason-less cancellations
+        let mut proposal = draft(ReasonPolicy::new().with_rule(
+            ProposalStatus::Draft,
+            ProposalStatus::Cancelled,
+            ReasonRequirement::Forbidden,
+        ));
+        assert!(matches!(
+            proposal.cancel_with_time("Why".to_string(), 2000),
+            Err(FsmError::ReasonNotAllowed(_))
+        ));
+        proposal.cancel_with_time(String::new(), 2000).unwrap();
+        assert_eq!(proposal.cancellation_reason, Some(String::new()));
     }
     // ========== New lifecycle methods tests ==========
     #[test]
@@ -720,14 +1028,14 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.reject_with_time(voting_end + 1).unwrap();
 
         // Set expiration in the past
-        proposal.expires_at = Some(5000);
+        proposal.expires_at = Some(voting_end + 2);
 
         // Should auto-archive
-        assert!(proposal.check_and_auto_archive(6000).unwrap());
+        assert!(proposal.check_and_auto_archive(voting_end + 3).unwrap());
         assert_eq!(proposal.status, ProposalStatus::Archived);
     }
     #[test]
@@ -744,7 +1052,7 @@ mod tests {
         .unwrap();
 
         proposal.activate_with_time(10, 20, 2000).unwrap();
-        let voting_end = proposal.created_at + proposal.voting_duration;
+        let voting_end = proposal.voting_ends_at().unwrap();
         proposal.reject_with_time(voting_end + 1).unwrap();
 
         // Set expiration in the future
@@ -771,13 +1079,12 @@ mod tests {
         proposal.yes_votes = 100;
         proposal.no_votes = 50;
 
-        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration;
+        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration.as_secs();
 
         // Should auto-transition to Passed
-        assert!(
-            proposal
-                .auto_transition_after_voting(voting_end + 1)
-                .unwrap()
+        assert_eq!(
+            proposal.auto_transition_detailed(voting_end + 1),
+            Ok(TransitionOutcome::Passed { yes: 100, no: 50 })
         );
         assert_eq!(proposal.status, ProposalStatus::Passed);
     }
@@ -798,13 +1105,12 @@ mod tests {
         proposal.yes_votes = 50;
         proposal.no_votes = 100;
 
-        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration;
+        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration.as_secs();
 
         // Should auto-transition to Rejected
-        assert!(
-            proposal
-                .auto_transition_after_voting(voting_end + 1)
-                .unwrap()
+        assert_eq!(
+            proposal.auto_transition_detailed(voting_end + 1),
+            Ok(TransitionOutcome::Rejected { yes: 50, no: 100 })
         );
         assert_eq!(proposal.status, ProposalStatus::Rejected);
     }
@@ -825,15 +1131,169 @@ mod tests {
         proposal.yes_votes = 100;
         proposal.no_votes = 100;
 
-        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration;
+        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration.as_secs();
 
         // Should auto-transition to Tied
-        assert!(
-            proposal
-                .auto_transition_after_voting(voting_end + 1)
-                .unwrap()
+        assert_eq!(
+            proposal.auto_transition_detailed(voting_end + 1),
+            Ok(TransitionOutcome::Tied { each: 100 })
         );
         assert_eq!(proposal.status, ProposalStatus::Tied);
+        assert_eq!(proposal.tie_break_applied, None);
+    }
+    fn create_tied_proposal(tie_break: TieBreakPolicy) -> (Proposal<u8>, i64) {
+        let mut proposal = Proposal::<u8>::new_with_time(
+            7,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+        proposal.tie_break_policy = tie_break;
+        proposal.activate_with_time(10, 20, 2000).unwrap();
+        proposal.yes_votes = 100;
+        proposal.no_votes = 100;
+        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration.as_secs();
+        (proposal, voting_end)
+    }
+    #[test]
+    fn test_tie_break_rejecting_policies() {
+        for tie_break in [TieBreakPolicy::StatusQuo, TieBreakPolicy::AuthorLoses] {
+            let (mut proposal, voting_end) = create_tied_proposal(tie_break);
+            assert_eq!(
+                proposal.auto_transition_detailed(voting_end),
+                Ok(TransitionOutcome::Rejected { yes: 100, no: 100 })
+            );
+            assert_eq!(proposal.status, ProposalStatus::Rejected);
+            assert_eq!(proposal.tie_break_applied, Some(tie_break));
+        }
+        // A decided vote does not fire the tie-break
+        let (mut proposal, voting_end) = create_tied_proposal(TieBreakPolicy::StatusQuo);
+        proposal.yes_votes = 101;
+        proposal.auto_transition_detailed(voting_end).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.tie_break_applied, None);
+    }
+    #[cfg(feature = "governance-meta")]
+    #[test]
+    fn test_tie_break_chair_decides() {
+        use crate::governance::{SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus};
+        let tie_break = TieBreakPolicy::ChairDecides { decision_ref: 3 };
+        let decision = |decision_id, proposal_id, status| {
+            SecurityBoardDecisionMetadata::<u8>::new(
+                decision_id,
+                Some(proposal_id),
+                status,
+                1500,
+                Some(1600),
+                [0u8; 32],
+            )
+        };
+        // No decision, or one that does not qualify, leaves the tie for manual resolution
+        let ignored = [
+            decision(3, 7, SecurityBoardDecisionStatus::Pending),
+            decision(4, 7, SecurityBoardDecisionStatus::Approved),
+            decision(3, 8, SecurityBoardDecisionStatus::Approved),
+        ];
+        for candidate in &ignored {
+            let (mut proposal, voting_end) = create_tied_proposal(tie_break);
+            assert_eq!(
+                proposal.auto_transition_with_chair_decision(candidate, voting_end),
+                Ok(TransitionOutcome::Tied { each: 100 })
+            );
+            assert_eq!(proposal.tie_break_applied, None);
+        }
+        let (mut proposal, voting_end) = create_tied_proposal(tie_break);
+        assert_eq!(
+            proposal.auto_transition_detailed(voting_end),
+            Ok(TransitionOutcome::Tied { each: 100 })
+        );
+
+        let (mut proposal, voting_end) = create_tied_proposal(tie_break);
+        let approved = decision(3, 7, SecurityBoardDecisionStatus::Approved);
+        assert_eq!(
+            proposal.auto_transition_with_chair_decision(&approved, voting_end),
+            Ok(TransitionOutcome::Passed { yes: 100, no: 100 })
+        );
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.tie_break_applied, Some(tie_break));
+    }
+    #[test]
+    fn test_quorum_failure_waits_for_late_activation_end() {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(crate::duration::DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 500).unwrap();
+        assert_eq!(
+            proposal.clone().fail_quorum_with_time(500),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            proposal.auto_transition_with_quorum(5, 20, 500),
+            Ok(TransitionOutcome::NotDue { ends_at: 600 })
+        );
+        assert_eq!(
+            proposal.auto_transition_with_quorum(5, 20, 600),
+            Ok(TransitionOutcome::QuorumFailed {
+                participation: 5,
+                required: 20
+            })
+        );
+    }
+    #[test]
+    fn test_proposal_auto_transition_with_quorum() {
+        let mut proposal = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+        proposal.activate_with_time(10, 20, 2000).unwrap();
+        proposal.yes_votes = 100;
+        proposal.no_votes = 50;
+        let voting_end = proposal.submitted_at.unwrap() + proposal.voting_duration.as_secs();
+
+        assert_eq!(
+            proposal.auto_transition_with_quorum(10, 20, voting_end - 1),
+            Ok(TransitionOutcome::NotDue {
+                ends_at: voting_end
+            })
+        );
+        let mut with_quorum = proposal.clone();
+        assert_eq!(
+            with_quorum.auto_transition_with_quorum(20, 20, voting_end),
+            Ok(TransitionOutcome::Passed { yes: 100, no: 50 })
+        );
+        // Majority support does not save a vote short of quorum
+        assert_eq!(
+            proposal.auto_transition_with_quorum(10, 20, voting_end),
+            Ok(TransitionOutcome::QuorumFailed {
+                participation: 10,
+                required: 20
+            })
+        );
+        assert_eq!(proposal.status, ProposalStatus::QuorumFailed);
+        assert_eq!(
+            proposal.auto_transition_with_quorum(10, 20, voting_end + 1),
+            Ok(TransitionOutcome::SkippedWrongStatus {
+                status: ProposalStatus::QuorumFailed
+            })
+        );
+        proposal.set_expiration(Some(voting_end + 10)).unwrap();
+        assert_eq!(proposal.check_and_auto_archive(voting_end + 10), Ok(true));
+        assert_eq!(proposal.status, ProposalStatus::Archived);
     }
     #[test]
     fn test_proposal_auto_transition_before_voting_end() {
@@ -853,7 +1313,115 @@ mod tests {
         proposal.no_votes = 50;
 
         // Try to auto-transition before voting ends - should not transition
-        assert!(!proposal.auto_transition_after_voting(2000).unwrap());
+        assert!(matches!(
+            proposal.auto_transition_detailed(2000),
+            Ok(TransitionOutcome::NotDue { .. })
+        ));
         assert_eq!(proposal.status, ProposalStatus::Active);
     }
+    #[test]
+    fn test_proposal_time_regression_rejected() {
+        let mut proposal = Proposal::<u8>::new_with_time(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+            1000,
+        )
+        .unwrap();
+        assert_eq!(proposal.last_event_at, 1000);
+        assert_eq!(
+            proposal.activate_with_time(1, 1, 999),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+        // Same-second operations are allowed
+        proposal.activate_with_time(1, 1, 1000).unwrap();
+        let voting_end = proposal.voting_ends_at().unwrap();
+        proposal.pass_with_time(voting_end).unwrap();
+        assert_eq!(proposal.last_event_at, voting_end);
+        assert_eq!(
+            proposal.execute_with_time(voting_end - 1),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(proposal.executed_at, None);
+        proposal.execute_with_time(voting_end).unwrap();
+    }
+    #[test]
+    fn test_proposal_negative_creation_time_rejected() {
+        assert_eq!(
+            Proposal::<u8>::new_with_time(
+                1,
+                "Test".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                create_test_pubkey(1),
+                -5
+            ),
+            Err(FsmError::InvalidInput)
+        );
+    }
+    #[test]
+    fn test_proposal_time_ordering_opt_out() {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+        )
+        .time_ordering(TimeOrdering::Unchecked)
+        .build(1000)
+        .unwrap();
+        proposal.activate_with_time(1, 1, 10).unwrap();
+        proposal.cancel_with_time("replay".to_string(), 5).unwrap();
+        assert_eq!(proposal.cancelled_at, Some(5));
+        assert_eq!(proposal.last_event_at, 1000);
+    }
+    #[test]
+    fn test_proposal_clock_skew_tolerance() {
+        let activated = || {
+            let mut proposal = Proposal::<u8>::builder(
+                1,
+                "Test".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                create_test_pubkey(1),
+            )
+            .clock_skew_tolerance(3)
+            .build(1000)
+            .unwrap();
+            proposal.activate_with_time(1, 1, 1000).unwrap();
+            proposal
+        };
+        let mut proposal = activated();
+        proposal
+            .cancel_with_time("skewed".to_string(), 997)
+            .unwrap();
+        assert_eq!(proposal.cancelled_at, Some(1000));
+        assert_eq!(proposal.last_event_at, 1000);
+        assert_eq!(
+            activated().cancel_with_time("skewed".to_string(), 996),
+            Err(FsmError::TimeRegression)
+        );
+    }
+    #[test]
+    fn test_proposal_strict_time_ordering() {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            create_test_pubkey(1),
+        )
+        .time_ordering(TimeOrdering::Strict)
+        .build(1000)
+        .unwrap();
+        assert_eq!(
+            proposal.activate_with_time(1, 1, 1000),
+            Err(FsmError::TimeRegression)
+        );
+        proposal.activate_with_time(1, 1, 1001).unwrap();
+    }
 }
@@ -0,0 +1,296 @@
+//! Superseding and merging proposals
+//!
+//! `supersede` cancels a proposal in favour of a successor. A registry
+//! `merge` does that for the loser and carries its recorded votes over to the
+//! winner, skipping voters who already voted on the winner so no vote is
+//! counted twice.
+use super::registry::VersionedRegistry;
+use super::types::{Proposal, ProposalStatus};
+use super::votes::VoteRecord;
+use crate::error::FsmError;
+use crate::events::{EventBuffer, GovernanceEvent};
+use crate::reason::ReasonCode;
+
+impl<P> Proposal<P> {
+    /// Cancel with `ReasonCode::SupersededBy` and record the successor
+    ///
+    /// Same rules as `cancel_with_code`; `InvalidInput` if `superseded_by` is
+    /// this proposal's own id.
+    pub fn supersede(&mut self, superseded_by: u64, current_time: i64) -> Result<(), FsmError> {
+        if superseded_by == self.id {
+            return Err(FsmError::InvalidInput);
+        }
+        self.cancel_with_code(
+            ReasonCode::SupersededBy { id: superseded_by },
+            format!("Superseded by proposal {}", superseded_by),
+            current_time,
+        )?;
+        self.superseded_by = Some(superseded_by);
+        Ok(())
+    }
+}
+impl<P: Ord + Clone> Proposal<P> {
+    /// Add `votes` from voters who have not voted here; returns how many were added
+    fn absorb_votes(
+        &mut self,
+        votes: &[VoteRecord<P>],
+        current_time: i64,
+    ) -> Result<usize, FsmError> {
+        if self.status != ProposalStatus::Draft && self.status != ProposalStatus::Active {
+            return Err(FsmError::InvalidState);
+        }
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        let mut added = 0;
+        for vote in votes {
+            if self.has_voted(&vote.voter) {
+                continue;
+            }
+            self.check_snapshot_stored(&vote.voter, vote.weight)?;
+            if self.raw_weight(vote.weight) < self.min_vote_weight {
+                return Err(FsmError::BelowMinimumWeight);
+            }
+            let tally = if vote.support {
+                &mut self.yes_votes
+            } else {
+                &mut self.no_votes
+            };
+            *tally = self.tally_overflow.add(*tally, vote.weight)?;
+            self.total_votes = self.tally_overflow.add(self.total_votes, vote.weight)?;
+            self.votes.push(vote.clone());
+            added += 1;
+        }
+        if added > 0 {
+            self.last_tallied_at = Some(current_time);
+        }
+        self.updated_at = Some(current_time);
+        self.last_event_at = self.last_event_at.max(current_time);
+        Ok(added)
+    }
+}
+impl<P: Ord + Clone> VersionedRegistry<P> {
+    /// Supersede `loser_id` by `winner_id` and move the loser's votes over
+    ///
+    /// The winner must be Draft or Active (`InvalidState` otherwise, so
+    /// merging into a terminal proposal fails) and the loser must be
+    /// cancellable. A winner with a `PowerSnapshot` only takes votes its
+    /// snapshot backs (`NotInSnapshot` or `InvalidInput` otherwise), both must
+    /// share a `weight_divisor` (`InvalidInput` otherwise) and the copied votes
+    /// must clear the winner's `min_vote_weight` (`BelowMinimumWeight`). Both
+    /// are updated together or not at all, each gaining a
+    /// revision, and `events` receives the loser's status change followed by
+    /// `ProposalVotesMerged` for the winner. Returns the number of votes copied.
+    pub fn merge(
+        &mut self,
+        loser_id: u64,
+        winner_id: u64,
+        current_time: i64,
+        events: &mut EventBuffer,
+    ) -> Result<usize, FsmError> {
+        if loser_id == winner_id {
+            return Err(FsmError::InvalidInput);
+        }
+        let loser = self.get(loser_id).ok_or(FsmError::InvalidInput)?;
+        let winner = self.get(winner_id).ok_or(FsmError::InvalidInput)?;
+        let (loser_rev, winner_rev) = (loser.revision, winner.revision);
+        if loser_rev.checked_add(1).is_none()
+            || winner_rev.checked_add(1).is_none()
+            || events.sequence_generator().last().checked_add(2).is_none()
+        {
+            return Err(FsmError::Overflow);
+        }
+        let mut loser = loser.proposal.clone();
+        let mut winner = winner.proposal.clone();
+        if loser.weight_divisor.unwrap_or(1) != winner.weight_divisor.unwrap_or(1) {
+            return Err(FsmError::InvalidInput);
+        }
+
+        let copied = winner.absorb_votes(&loser.votes, current_time)?;
+        let loser_from = loser.status.clone();
+        loser.supersede(winner_id, current_time)?;
+
+        self.modify(loser_id, loser_rev, |p| {
+            *p = loser.clone();
+            Ok(())
+        })?;
+        self.modify(winner_id, winner_rev, |p| {
+            *p = winner;
+            Ok(())
+        })?;
+        events.push(GovernanceEvent::ProposalStatusChanged {
+            proposal_id: loser_id,
+            from: loser_from,
+            to: loser.status,
+            at: current_time,
+        })?;
+        events.push(GovernanceEvent::ProposalVotesMerged {
+            proposal_id: winner_id,
+            from_proposal_id: loser_id,
+            votes_copied: copied as u64,
+            at: current_time,
+        })?;
+        Ok(copied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    fn create_active_proposal(id: u64) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal
+    }
+    fn create_test_registry() -> VersionedRegistry<u8> {
+        let mut registry = VersionedRegistry::new();
+        let mut loser = create_active_proposal(1);
+        loser.cast_vote(2, true, 3, 10).unwrap();
+        loser.cast_vote(3, false, 2, 10).unwrap();
+        let mut winner = create_active_proposal(2);
+        winner.cast_vote(3, true, 1, 10).unwrap();
+        registry.insert(loser).unwrap();
+        registry.insert(winner).unwrap();
+        registry
+    }
+    #[test]
+    fn test_supersede() {
+        let mut proposal = create_active_proposal(1);
+        assert_eq!(proposal.supersede(1, 10), Err(FsmError::InvalidInput));
+        proposal.supersede(7, 10).unwrap();
+        assert_eq!(proposal.status, ProposalStatus::Cancelled);
+        assert_eq!(proposal.superseded_by, Some(7));
+        assert_eq!(
+            proposal.cancellation_code,
+            Some(ReasonCode::SupersededBy { id: 7 })
+        );
+        assert_eq!(
+            proposal.supersede(8, 20),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(proposal.superseded_by, Some(7));
+    }
+    #[test]
+    fn test_merge_copies_votes_without_double_counting() {
+        let mut registry = create_test_registry();
+        let mut events = EventBuffer::new();
+        assert_eq!(registry.merge(1, 2, 20, &mut events), Ok(1));
+
+        let loser = &registry.get(1).unwrap().proposal;
+        assert_eq!(loser.status, ProposalStatus::Cancelled);
+        assert_eq!(loser.superseded_by, Some(2));
+        let winner = &registry.get(2).unwrap().proposal;
+        // Voter 3 already voted on the winner; only voter 2 is copied
+        assert_eq!(
+            (winner.yes_votes, winner.no_votes, winner.total_votes),
+            (4, 0, 4)
+        );
+        assert_eq!(winner.votes.len(), 2);
+        assert_eq!(winner.last_tallied_at, Some(20));
+        assert_eq!(registry.revision(1), Some(1));
+        assert_eq!(registry.revision(2), Some(1));
+        assert_eq!(
+            events.drain(),
+            vec![
+                GovernanceEvent::ProposalStatusChanged {
+                    proposal_id: 1,
+                    from: ProposalStatus::Active,
+                    to: ProposalStatus::Cancelled,
+                    at: 20,
+                },
+                GovernanceEvent::ProposalVotesMerged {
+                    proposal_id: 2,
+                    from_proposal_id: 1,
+                    votes_copied: 1,
+                    at: 20,
+                },
+            ]
+        );
+    }
+    #[test]
+    fn test_merge_into_terminal_fails() {
+        let mut registry = create_test_registry();
+        registry
+            .modify(2, 0, |p| p.cancel_with_time("Done".to_string(), 10))
+            .unwrap();
+        let mut events = EventBuffer::new();
+        assert_eq!(
+            registry.merge(1, 2, 20, &mut events),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            registry.get(1).unwrap().proposal.status,
+            ProposalStatus::Active
+        );
+        assert_eq!(registry.revision(1), Some(0));
+        assert!(events.is_empty());
+    }
+    #[test]
+    fn test_merge_validation() {
+        let mut registry = create_test_registry();
+        let mut events = EventBuffer::new();
+        assert_eq!(
+            registry.merge(1, 1, 20, &mut events),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            registry.merge(1, 9, 20, &mut events),
+            Err(FsmError::InvalidInput)
+        );
+        // A loser that cannot be cancelled leaves the winner untouched
+        registry
+            .modify(1, 0, |p| p.cancel_with_time("Done".to_string(), 10))
+            .unwrap();
+        assert_eq!(
+            registry.merge(1, 2, 20, &mut events),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(registry.get(2).unwrap().proposal.votes.len(), 1);
+        assert_eq!(registry.revision(2), Some(0));
+        assert!(events.is_empty());
+    }
+    #[test]
+    fn test_merge_respects_weight_policy() {
+        let mut registry = VersionedRegistry::new();
+        let mut loser = create_active_proposal(1);
+        loser.normalize_weights(10).unwrap();
+        loser.cast_vote(2, true, 25, 10).unwrap();
+        let mut winner = create_active_proposal(2);
+        winner.set_min_vote_weight(30).unwrap();
+        registry.insert(loser.clone()).unwrap();
+        registry.insert(winner.clone()).unwrap();
+        let mut events = EventBuffer::new();
+        assert_eq!(
+            registry.merge(1, 2, 20, &mut events),
+            Err(FsmError::InvalidInput)
+        );
+        winner.normalize_weights(10).unwrap();
+        registry
+            .modify(2, 0, |p| {
+                *p = winner.clone();
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(
+            registry.merge(1, 2, 20, &mut events),
+            Err(FsmError::BelowMinimumWeight)
+        );
+        winner.set_min_vote_weight(20).unwrap();
+        registry
+            .modify(2, 1, |p| {
+                *p = winner;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(registry.merge(1, 2, 20, &mut events), Ok(1));
+        assert_eq!(registry.get(2).unwrap().proposal.yes_votes, 2);
+    }
+}
@@ -1,25 +1,89 @@
 //! Proposal module
 //!
 //! Provides proposal management functionality:
+//! - builder: ProposalBuilder for fluent construction with deferred validation
 //! - types: ProposalStatus enum and Proposal struct
+//! - activation: ActivationContext gates for activate_in
 //! - lifecycle: Proposal lifecycle methods (new, activate, pass, reject, execute, cancel, archive)
+//! - execution: Two-phase execution (prepare/commit/abort) and dry runs
+//! - outcome: TransitionOutcome of auto transitions and process_batch
+//! - projection: Outcome projection (can this proposal still pass?)
+//! - readiness: Execution readiness of Passed proposals (timelock, approvals, dependencies)
+//! - registry: VersionedRegistry with optimistic revision checks
+//! - merge: Superseding proposals and merging their votes
+//! - import: Bulk vote import for migrations (import_votes)
+//! - snapshot: Voting power fixed at activation for token-weighted votes
+//! - sponsors: Co-sponsors and the min_sponsors activation gate
 //! - analytics: Proposal analytics and metrics
-//! - amendment: Proposal amendment support
+//! - amendment: Proposal amendments and their voting
 //! - template: Proposal template system
+//! - type_registry: ProposalTypeRegistry with per-type lifecycle policy
+//! - votes: Recorded votes and participation (cast_vote, non_voters)
+//! - weights: Vote weight floor and normalization (set_min_vote_weight, normalize_weights)
+//! - diff: Field-level diff of a proposal change for persistence layers (serde)
+//! - definition_bridge: GuardContext of a proposal for custom FsmDefinition workflows
 
+pub mod activation;
 pub mod amendment;
 pub mod analytics;
+pub mod builder;
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub mod compaction;
+pub mod definition_bridge;
+#[cfg(feature = "serde")]
+pub mod diff;
+pub mod execution;
+pub mod import;
 pub mod lifecycle;
+pub mod merge;
+pub mod outcome;
+pub mod projection;
+pub mod readiness;
+pub mod registry;
+pub mod snapshot;
+pub mod sponsors;
 pub mod template;
 pub mod treasury;
+pub mod type_registry;
 pub mod types;
+pub mod votes;
+pub mod weights;
 
 // Re-export types
-pub use amendment::ProposalAmendment;
+pub use activation::{
+    ActivationCheck, ActivationContext, ActivationReport, DiscussionRequirement, EligibilityPolicy,
+    SecurityPolicySet,
+};
+pub use amendment::{AmendmentStatus, ProposalAmendment};
 pub use analytics::{
-    ProposalAnalyticsMetadata, ProposalAnalyticsStatus, ProposalAnalyticsType,
-    onchain::initialize_proposal_analytics,
+    Breakdown, BreakdownRow, ProposalAnalyticsMetadata, ProposalAnalyticsStatus,
+    ProposalAnalyticsType, VoteBucket, cancellation_breakdown,
+    onchain::initialize_proposal_analytics, vote_time_distribution,
+};
+pub use builder::ProposalBuilder;
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub use compaction::CompactedData;
+#[cfg(feature = "serde")]
+pub use diff::{ChangeTracker, FieldChange, FieldDiff};
+#[cfg(feature = "serde")]
+pub use execution::{DryRunReport, ExecutionTargets, PlannedMutation, dry_run};
+pub use execution::{
+    ExecutionResult, ExecutionRetryPolicy, ExecutionTicket, StaleExecutionReport,
+    check_stale_executions,
 };
+pub use import::{ImportMode, ImportReport, ImportedVote, SkipReason};
+pub use outcome::{BatchReport, TransitionOutcome, process_batch};
+pub use projection::{Projection, ProjectionOutcome};
+pub use readiness::{ExecutionApprovals, Readiness, ready_for_execution};
+pub use registry::{Resolved, Versioned, VersionedRegistry};
+pub use snapshot::PowerSnapshot;
+pub use sponsors::MAX_SPONSORS;
 pub use template::{ProposalTemplate, TemplateField, TemplateFieldType};
-pub use treasury::{TreasuryOperationData, TreasuryProposalType};
+pub use treasury::{
+    TreasuryAuthorization, TreasuryOperationData, TreasuryPolicy, TreasuryProposalType,
+};
+pub use type_registry::{
+    ProposalTypeRegistry, TallyOverflow, TallyPolicy, TieBreakPolicy, TypePolicy,
+};
 pub use types::{Proposal, ProposalStatus};
+pub use votes::{VoteRecord, VoterNonce, outstanding_votes};
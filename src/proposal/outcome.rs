@@ -0,0 +1,186 @@
+//! Voting outcomes
+//!
+//! `auto_transition_detailed` reports why a proposal did or did not move
+//! when its voting period was checked; `process_batch` runs it over many
+//! proposals and counts the outcomes.
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+
+/// Result of checking one proposal after voting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransitionOutcome {
+    /// Voting is still open until `ends_at`
+    NotDue {
+        ends_at: i64,
+    },
+    Passed {
+        yes: u64,
+        no: u64,
+    },
+    Rejected {
+        yes: u64,
+        no: u64,
+    },
+    /// Neither side won; `each` is the yes (and no) weight
+    Tied {
+        each: u64,
+    },
+    /// Voting ended short of quorum (`auto_transition_with_quorum`); both are
+    /// percentages of the eligible weight
+    QuorumFailed {
+        participation: u8,
+        required: u8,
+    },
+    /// The proposal was not Active
+    SkippedWrongStatus {
+        status: ProposalStatus,
+    },
+}
+impl TransitionOutcome {
+    /// Whether the proposal changed status
+    pub fn transitioned(&self) -> bool {
+        matches!(
+            self,
+            TransitionOutcome::Passed { .. }
+                | TransitionOutcome::Rejected { .. }
+                | TransitionOutcome::Tied { .. }
+                | TransitionOutcome::QuorumFailed { .. }
+        )
+    }
+}
+/// Outcomes of `process_batch`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    /// `(proposal id, outcome)` in input order, for proposals that did not fail
+    pub outcomes: Vec<(u64, TransitionOutcome)>,
+    /// `(proposal id, error)` in input order
+    pub errors: Vec<(u64, FsmError)>,
+    pub passed: usize,
+    pub rejected: usize,
+    pub tied: usize,
+    pub quorum_failed: usize,
+    pub not_due: usize,
+    pub skipped: usize,
+}
+/// Run `auto_transition_detailed` on every proposal at `current_time`
+///
+/// A failing proposal is left unchanged and reported in `errors`; the rest
+/// are still processed.
+pub fn process_batch<P>(proposals: &mut [Proposal<P>], current_time: i64) -> BatchReport {
+    let mut report = BatchReport::default();
+    for proposal in proposals.iter_mut() {
+        match proposal.auto_transition_detailed(current_time) {
+            Ok(outcome) => {
+                let counter = match outcome {
+                    TransitionOutcome::NotDue { .. } => &mut report.not_due,
+                    TransitionOutcome::Passed { .. } => &mut report.passed,
+                    TransitionOutcome::Rejected { .. } => &mut report.rejected,
+                    TransitionOutcome::Tied { .. } => &mut report.tied,
+                    TransitionOutcome::QuorumFailed { .. } => &mut report.quorum_failed,
+                    TransitionOutcome::SkippedWrongStatus { .. } => &mut report.skipped,
+                };
+                *counter += 1;
+                report.outcomes.push((proposal.id, outcome));
+            }
+            Err(error) => report.errors.push((proposal.id, error)),
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    // Voting ends at 100
+    fn create_active_proposal(id: u64, yes_votes: u64, no_votes: u64) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal.yes_votes = yes_votes;
+        proposal.no_votes = no_votes;
+        proposal
+    }
+    #[test]
+    fn test_outcomes() {
+        let mut proposal = create_active_proposal(1, 3, 1);
+        assert_eq!(
+            proposal.auto_transition_detailed(99),
+            Ok(TransitionOutcome::NotDue { ends_at: 100 })
+        );
+        assert_eq!(
+            proposal.auto_transition_detailed(100),
+            Ok(TransitionOutcome::Passed { yes: 3, no: 1 })
+        );
+        assert_eq!(
+            proposal.auto_transition_detailed(101),
+            Ok(TransitionOutcome::SkippedWrongStatus {
+                status: ProposalStatus::Passed
+            })
+        );
+        let mut proposal = create_active_proposal(2, 1, 3);
+        assert_eq!(
+            proposal.auto_transition_detailed(100),
+            Ok(TransitionOutcome::Rejected { yes: 1, no: 3 })
+        );
+        let mut proposal = create_active_proposal(3, 2, 2);
+        let outcome = proposal.auto_transition_detailed(100).unwrap();
+        assert_eq!(outcome, TransitionOutcome::Tied { each: 2 });
+        assert!(outcome.transitioned());
+        assert!(!TransitionOutcome::NotDue { ends_at: 1 }.transitioned());
+    }
+    #[test]
+    #[allow(deprecated)]
+    fn test_bool_wrapper_matches_outcome() {
+        let mut proposal = create_active_proposal(1, 3, 1);
+        assert_eq!(proposal.auto_transition_after_voting(99), Ok(false));
+        assert_eq!(proposal.auto_transition_after_voting(100), Ok(true));
+        assert_eq!(proposal.auto_transition_after_voting(101), Ok(false));
+    }
+    #[test]
+    fn test_process_batch_aggregates() {
+        let mut late = create_active_proposal(5, 1, 0);
+        late.activate_with_time(1, 10, 50).unwrap_err();
+        late.submitted_at = Some(50);
+        let mut broken = create_active_proposal(6, 1, 0);
+        broken.voting_duration = DurationSecs::MAX;
+        broken.submitted_at = Some(1);
+        let mut proposals = vec![
+            create_active_proposal(1, 3, 1),
+            create_active_proposal(2, 1, 3),
+            create_active_proposal(3, 2, 2),
+            create_active_proposal(4, 5, 0),
+            late,
+            broken,
+        ];
+        proposals[3]
+            .cancel_with_time("Withdrawn".to_string(), 10)
+            .unwrap();
+        let report = process_batch(&mut proposals, 100);
+        assert_eq!(
+            (
+                report.passed,
+                report.rejected,
+                report.tied,
+                report.not_due,
+                report.skipped
+            ),
+            (1, 1, 1, 1, 1)
+        );
+        assert_eq!(report.outcomes.len(), 5);
+        assert_eq!(
+            report.outcomes[4],
+            (5, TransitionOutcome::NotDue { ends_at: 150 })
+        );
+        assert_eq!(report.errors, vec![(6, FsmError::Overflow)]);
+        assert_eq!(proposals[5].status, ProposalStatus::Active);
+    }
+}
@@ -0,0 +1,278 @@
+//! Proposal outcome projection
+//!
+//! Answers whether an open proposal is already mathematically decided, under
+//! the rules `auto_transition_after_voting` settles it by: the proposal's
+//! `tally_policy`, its `tie_break_policy` for a tie, and, through
+//! `projection_with_quorum`, the quorum `auto_transition_with_quorum` checks.
+//! A tie only a chair decision can settle may still pass.
+use super::type_registry::TieBreakPolicy;
+use super::types::{Proposal, ProposalStatus};
+
+/// Projected outcome of a proposal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProjectionOutcome {
+    /// Passes even if every outstanding voter votes no
+    WillPass,
+    /// Cannot pass even if every outstanding voter votes yes
+    WillFail,
+    /// Outstanding votes can still change the outcome
+    Undecided,
+    /// Voting period is over or the proposal is no longer open
+    VotingEnded,
+    /// Vote counts are inconsistent with `total_eligible` or overflow
+    DataInconsistent,
+}
+
+/// Projection result
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Projection {
+    pub outcome: ProjectionOutcome,
+    /// Minimum additional yes votes needed to pass outright (quorum included),
+    /// assuming no further no votes; `u64::MAX` if no number of yes votes passes
+    pub additional_yes_needed: u64,
+    /// Seconds until voting ends (0 once ended)
+    pub time_remaining: i64,
+}
+
+impl Projection {
+    fn closed(outcome: ProjectionOutcome) -> Self {
+        Self {
+            outcome,
+            additional_yes_needed: 0,
+            time_remaining: 0,
+        }
+    }
+}
+
+impl<P> Proposal<P> {
+    /// Project the outcome given the number of eligible voters
+    ///
+    /// Voters who have not voted yet are `total_eligible - max(total_votes, yes + no)`.
+    /// Draft proposals are projected as if voting opened now.
+    pub fn projection(&self, total_eligible: u64, current_time: i64) -> Projection {
+        self.projection_with_quorum(total_eligible, 0, current_time)
+    }
+
+    /// `projection` for a vote that needs `required` percent of `total_eligible` to take part
+    ///
+    /// Participation counts the voters who have cast a vote. A proposal
+    /// short of quorum cannot be `WillPass`, since outstanding voters may
+    /// abstain.
+    pub fn projection_with_quorum(
+        &self,
+        total_eligible: u64,
+        required: u8,
+        current_time: i64,
+    ) -> Projection {
+        let Some(cast) = self.yes_votes.checked_add(self.no_votes) else {
+            return Projection::closed(ProjectionOutcome::DataInconsistent);
+        };
+        let cast = cast.max(self.total_votes);
+        let Some(outstanding) = total_eligible.checked_sub(cast) else {
+            return Projection::closed(ProjectionOutcome::DataInconsistent);
+        };
+
+        let time_remaining = match self.status {
+            ProposalStatus::Draft => self.voting_duration.as_secs().max(0),
+            ProposalStatus::Active => {
+                let Ok(voting_end) = self.voting_ends_at() else {
+                    return Projection::closed(ProjectionOutcome::DataInconsistent);
+                };
+                if current_time >= voting_end {
+                    return Projection::closed(ProjectionOutcome::VotingEnded);
+                }
+                voting_end.saturating_sub(current_time)
+            }
+            _ => return Projection::closed(ProjectionOutcome::VotingEnded),
+        };
+
+        let quorum_met = |participants: u64| {
+            participants as u128 * 100 >= required as u128 * total_eligible as u128
+        };
+        let (yes, no) = (self.yes_votes, self.no_votes);
+        let passes_with = |additional_yes: u64| {
+            quorum_met(cast.saturating_add(additional_yes))
+                && self
+                    .tally_policy
+                    .decide(yes.saturating_add(additional_yes), no)
+                    == Some(true)
+        };
+        // Smallest count for which `passes_with` holds; it is monotone in the count
+        let additional_yes_needed = if passes_with(u64::MAX) {
+            let (mut low, mut high) = (0, u64::MAX);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if passes_with(middle) {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            low
+        } else {
+            u64::MAX
+        };
+
+        let worst = self.settles(yes, no.saturating_add(outstanding));
+        let best = self.settles(yes.saturating_add(outstanding), no);
+        let outcome = if quorum_met(cast) && worst == Some(true) {
+            ProjectionOutcome::WillPass
+        } else if best == Some(false) || !quorum_met(total_eligible) {
+            ProjectionOutcome::WillFail
+        } else {
+            ProjectionOutcome::Undecided
+        };
+
+        Projection {
+            outcome,
+            additional_yes_needed,
+            time_remaining,
+        }
+    }
+
+    /// Whether voting ending at `yes`/`no` passes; `None` for a tie only a chair decision settles
+    fn settles(&self, yes: u64, no: u64) -> Option<bool> {
+        match (self.tally_policy.decide(yes, no), self.tie_break_policy) {
+            (Some(passes), _) => Some(passes),
+            (None, TieBreakPolicy::ChairDecides { .. }) => None,
+            (
+                None,
+                TieBreakPolicy::Manual | TieBreakPolicy::StatusQuo | TieBreakPolicy::AuthorLoses,
+            ) => Some(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::type_registry::TallyPolicy;
+    fn create_active_proposal(yes_votes: u64, no_votes: u64) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(1000))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 100).unwrap();
+        proposal.yes_votes = yes_votes;
+        proposal.no_votes = no_votes;
+        proposal.total_votes = yes_votes + no_votes;
+        proposal
+    }
+    #[test]
+    fn test_projection_undecided() {
+        let projection = create_active_proposal(3, 3).projection(10, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::Undecided);
+        assert_eq!(projection.additional_yes_needed, 1);
+        assert_eq!(projection.time_remaining, 500);
+    }
+    #[test]
+    fn test_projection_will_pass() {
+        // 4 outstanding voters cannot overturn a lead of 5
+        let projection = create_active_proposal(5, 0).projection(9, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::WillPass);
+        assert_eq!(projection.additional_yes_needed, 0);
+    }
+    #[test]
+    fn test_projection_lead_equal_to_outstanding_is_undecided() {
+        // 5-0 with 5 outstanding can still end 5-5 (tie)
+        let projection = create_active_proposal(5, 0).projection(10, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::Undecided);
+    }
+    #[test]
+    fn test_projection_will_fail() {
+        let projection = create_active_proposal(1, 6).projection(10, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::WillFail);
+        assert_eq!(projection.additional_yes_needed, 6);
+    }
+    #[test]
+    fn test_projection_voting_ended() {
+        let projection = create_active_proposal(1, 0).projection(10, 1100);
+        assert_eq!(projection.outcome, ProjectionOutcome::VotingEnded);
+        assert_eq!(projection.time_remaining, 0);
+
+        let mut passed = create_active_proposal(6, 0);
+        passed.pass_with_time(1100).unwrap();
+        assert_eq!(
+            passed.projection(10, 1100).outcome,
+            ProjectionOutcome::VotingEnded
+        );
+    }
+    #[test]
+    fn test_projection_data_inconsistent() {
+        let projection = create_active_proposal(5, 5).projection(9, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::DataInconsistent);
+
+        let mut overflow = create_active_proposal(u64::MAX, 0);
+        overflow.no_votes = 1;
+        assert_eq!(
+            overflow.projection(u64::MAX, 600).outcome,
+            ProjectionOutcome::DataInconsistent
+        );
+    }
+    #[test]
+    fn test_projection_follows_tally_policy() {
+        // 6-4 with nobody left is a simple majority but short of 67%
+        let mut proposal = create_active_proposal(6, 4);
+        assert_eq!(
+            proposal.projection(10, 600).outcome,
+            ProjectionOutcome::WillPass
+        );
+        proposal.tally_policy = TallyPolicy::Supermajority { percent: 67 };
+        let projection = proposal.projection(10, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::WillFail);
+        // 67% of 6 + 4 + 3 yes is met at 9 yes
+        assert_eq!(projection.additional_yes_needed, 3);
+    }
+    #[test]
+    fn test_projection_follows_tie_break_policy() {
+        // 3-5 with 2 outstanding can at best tie
+        let mut proposal = create_active_proposal(3, 5);
+        assert_eq!(
+            proposal.projection(10, 600).outcome,
+            ProjectionOutcome::WillFail
+        );
+        proposal.tie_break_policy = TieBreakPolicy::ChairDecides { decision_ref: 9 };
+        let projection = proposal.projection(10, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::Undecided);
+        assert_eq!(projection.additional_yes_needed, 3);
+
+        // 5-3 with 2 outstanding can at worst tie
+        let mut leading = create_active_proposal(5, 3);
+        leading.tie_break_policy = TieBreakPolicy::ChairDecides { decision_ref: 9 };
+        assert_eq!(
+            leading.projection(10, 600).outcome,
+            ProjectionOutcome::Undecided
+        );
+    }
+    #[test]
+    fn test_projection_with_quorum() {
+        // 6-0 leads by more than the 4 outstanding, but 6 of 10 is short of 70%
+        let proposal = create_active_proposal(6, 0);
+        assert_eq!(
+            proposal.projection(10, 600).outcome,
+            ProjectionOutcome::WillPass
+        );
+        let projection = proposal.projection_with_quorum(10, 70, 600);
+        assert_eq!(projection.outcome, ProjectionOutcome::Undecided);
+        assert_eq!(projection.additional_yes_needed, 1);
+        assert_eq!(
+            proposal.projection_with_quorum(10, 60, 600).outcome,
+            ProjectionOutcome::WillPass
+        );
+    }
+    #[test]
+    fn test_projection_draft_uses_full_duration() {
+        let mut proposal = create_active_proposal(0, 0);
+        proposal.status = ProposalStatus::Draft;
+        let projection = proposal.projection(10, 50);
+        assert_eq!(projection.outcome, ProjectionOutcome::Undecided);
+        assert_eq!(projection.time_remaining, 1000);
+    }
+}
@@ -0,0 +1,317 @@
+//! Execution readiness
+//!
+//! A Passed proposal may still be held back by an expired window, unexecuted
+//! dependencies, missing multi-sig approvals or its execution timelock.
+//! `execution_readiness` reports the first of these that applies, in that
+//! order: permanent blocks first, then ones that need someone else to act,
+//! then ones that only need time. It never changes the proposal.
+use super::registry::VersionedRegistry;
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Whether a proposal can be executed now
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Readiness {
+    Ready,
+    /// Not Passed; only Passed proposals are executed
+    NotPassed {
+        status: ProposalStatus,
+    },
+    /// `expires_at` has been reached
+    WindowExpired,
+    /// Dependencies that are not executed, in `depends_on` order
+    BlockedByDependency {
+        ids: Vec<u64>,
+    },
+    WaitingApprovals {
+        have: u32,
+        need: u32,
+    },
+    WaitingTimelock {
+        until: i64,
+    },
+}
+/// Multi-sig approvals collected for execution
+///
+/// Proposals without a requirement need no approvals. Each signer counts once.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionApprovals<P> {
+    required: BTreeMap<u64, u32>,
+    signers: BTreeMap<u64, BTreeSet<P>>,
+}
+impl<P> Default for ExecutionApprovals<P> {
+    fn default() -> Self {
+        Self {
+            required: BTreeMap::new(),
+            signers: BTreeMap::new(),
+        }
+    }
+}
+impl<P: Ord> ExecutionApprovals<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Require `need` approvals before `proposal_id` executes
+    pub fn require(&mut self, proposal_id: u64, need: u32) {
+        self.required.insert(proposal_id, need);
+    }
+    /// Record `signer`'s approval; returns the approvals collected so far
+    ///
+    /// `InvalidState` if `signer` already approved.
+    pub fn approve(&mut self, proposal_id: u64, signer: P) -> Result<u32, FsmError> {
+        let signers = self.signers.entry(proposal_id).or_default();
+        if !signers.insert(signer) {
+            return Err(FsmError::InvalidState);
+        }
+        Ok(u32::try_from(signers.len()).unwrap_or(u32::MAX))
+    }
+    /// `(have, need)` for `proposal_id`
+    pub fn status(&self, proposal_id: u64) -> (u32, u32) {
+        let have = self
+            .signers
+            .get(&proposal_id)
+            .map_or(0, |s| u32::try_from(s.len()).unwrap_or(u32::MAX));
+        (have, self.required.get(&proposal_id).copied().unwrap_or(0))
+    }
+}
+fn is_executed<P>(proposal: &Proposal<P>) -> bool {
+    match proposal.status {
+        ProposalStatus::Executed => true,
+        ProposalStatus::Archived => proposal.executed_at.is_some(),
+        _ => false,
+    }
+}
+impl<P> Proposal<P> {
+    /// What, if anything, holds this proposal back from execution at `current_time`
+    ///
+    /// Dependencies are looked up in `registry`; unknown and purged ones block,
+    /// since they cannot be shown to have executed.
+    pub fn execution_readiness<A: Ord>(
+        &self,
+        registry: &VersionedRegistry<P>,
+        approvals: &ExecutionApprovals<A>,
+        current_time: i64,
+    ) -> Readiness
+    where
+        P: Clone,
+    {
+        if self.status != ProposalStatus::Passed {
+            return Readiness::NotPassed {
+                status: self.status.clone(),
+            };
+        }
+        if self.expires_at.is_some_and(|exp| current_time >= exp) {
+            return Readiness::WindowExpired;
+        }
+        let blocking: Vec<u64> = self
+            .depends_on
+            .iter()
+            .copied()
+            .filter(|id| !registry.get(*id).is_some_and(|e| is_executed(&e.proposal)))
+            .collect();
+        if !blocking.is_empty() {
+            return Readiness::BlockedByDependency { ids: blocking };
+        }
+        let (have, need) = approvals.status(self.id);
+        if have < need {
+            return Readiness::WaitingApprovals { have, need };
+        }
+        if let Some(passed_at) = self.passed_at {
+            let until = passed_at.saturating_add(self.execution_timelock.as_secs());
+            if current_time < until {
+                return Readiness::WaitingTimelock { until };
+            }
+        }
+        Readiness::Ready
+    }
+}
+/// Ids of the `proposals` that are `Ready`, in input order
+pub fn ready_for_execution<P: Clone, A: Ord>(
+    proposals: &[Proposal<P>],
+    registry: &VersionedRegistry<P>,
+    approvals: &ExecutionApprovals<A>,
+    current_time: i64,
+) -> Vec<u64> {
+    proposals
+        .iter()
+        .filter(|p| p.execution_readiness(registry, approvals, current_time) == Readiness::Ready)
+        .map(|p| p.id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    // Passes at 110; unlocks at 160
+    fn create_passed_proposal(id: u64, depends_on: Vec<u64>) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .depends_on(depends_on)
+        .build(0)
+        .unwrap();
+        proposal.execution_timelock = DurationSecs::from_secs(50);
+        proposal.activate_with_time(1, 10, 10).unwrap();
+        proposal.yes_votes = 5;
+        assert!(
+            proposal
+                .auto_transition_detailed(110)
+                .unwrap()
+                .transitioned()
+        );
+        proposal
+    }
+    fn create_test_registry(dependency_executed: bool) -> VersionedRegistry<u8> {
+        let mut dependency = create_passed_proposal(1, Vec::new());
+        if dependency_executed {
+            dependency.execution_timelock = DurationSecs::ZERO;
+            dependency.execute_with_time(110).unwrap();
+        }
+        let mut registry = VersionedRegistry::new();
+        registry.insert(dependency).unwrap();
+        registry
+    }
+    #[test]
+    fn test_ready_without_gates() {
+        let proposal = create_passed_proposal(2, Vec::new());
+        let registry = VersionedRegistry::new();
+        let approvals = ExecutionApprovals::<u8>::new();
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 159),
+            Readiness::WaitingTimelock { until: 160 }
+        );
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 160),
+            Readiness::Ready
+        );
+    }
+    #[test]
+    fn test_not_passed() {
+        let mut proposal = create_passed_proposal(2, Vec::new());
+        proposal.execute_with_time(160).unwrap();
+        assert_eq!(
+            proposal.execution_readiness(
+                &VersionedRegistry::new(),
+                &ExecutionApprovals::<u8>::new(),
+                200
+            ),
+            Readiness::NotPassed {
+                status: ProposalStatus::Executed
+            }
+        );
+    }
+    #[test]
+    fn test_approvals() {
+        let proposal = create_passed_proposal(2, Vec::new());
+        let registry = VersionedRegistry::new();
+        let mut approvals = ExecutionApprovals::new();
+        approvals.require(2, 2);
+        assert_eq!(approvals.approve(2, 7), Ok(1));
+        assert_eq!(approvals.approve(2, 7), Err(FsmError::InvalidState));
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 200),
+            Readiness::WaitingApprovals { have: 1, need: 2 }
+        );
+        assert_eq!(approvals.approve(2, 8), Ok(2));
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 200),
+            Readiness::Ready
+        );
+    }
+    #[test]
+    fn test_dependencies() {
+        let proposal = create_passed_proposal(2, vec![9, 1]);
+        let approvals = ExecutionApprovals::<u8>::new();
+        // 9 is unknown, 1 passed but not executed
+        assert_eq!(
+            proposal.execution_readiness(&create_test_registry(false), &approvals, 200),
+            Readiness::BlockedByDependency { ids: vec![9, 1] }
+        );
+        let proposal = create_passed_proposal(2, vec![1]);
+        assert_eq!(
+            proposal.execution_readiness(&create_test_registry(true), &approvals, 200),
+            Readiness::Ready
+        );
+        let mut registry = create_test_registry(true);
+        registry.modify(1, 0, |p| p.archive_with_time(200)).unwrap();
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 200),
+            Readiness::Ready
+        );
+    }
+    #[test]
+    fn test_gate_precedence() {
+        let mut proposal = create_passed_proposal(2, vec![1]);
+        proposal.expires_at = Some(150);
+        let registry = create_test_registry(false);
+        let mut approvals = ExecutionApprovals::new();
+        approvals.require(2, 1);
+        // All four gates apply before the timelock ends
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 150),
+            Readiness::WindowExpired
+        );
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 140),
+            Readiness::BlockedByDependency { ids: vec![1] }
+        );
+        let registry = create_test_registry(true);
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 140),
+            Readiness::WaitingApprovals { have: 0, need: 1 }
+        );
+        approvals.approve(2, 7).unwrap();
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 140),
+            Readiness::WaitingTimelock { until: 160 }
+        );
+        // Once the timelock ends the window has closed
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 160),
+            Readiness::WindowExpired
+        );
+    }
+    #[test]
+    fn test_ready_matches_execute() {
+        let mut proposal = create_passed_proposal(2, Vec::new());
+        let registry = VersionedRegistry::new();
+        let approvals = ExecutionApprovals::<u8>::new();
+        assert_ne!(
+            proposal.execution_readiness(&registry, &approvals, 159),
+            Readiness::Ready
+        );
+        assert_eq!(proposal.execute_with_time(159), Err(FsmError::InvalidState));
+        assert_eq!(
+            proposal.execution_readiness(&registry, &approvals, 160),
+            Readiness::Ready
+        );
+        proposal.execute_with_time(160).unwrap();
+    }
+    #[test]
+    fn test_ready_for_execution() {
+        let registry = create_test_registry(true);
+        let mut approvals = ExecutionApprovals::<u8>::new();
+        approvals.require(4, 1);
+        let proposals = vec![
+            create_passed_proposal(2, vec![1]),
+            create_passed_proposal(3, vec![5]),
+            create_passed_proposal(4, Vec::new()),
+            create_passed_proposal(6, Vec::new()),
+        ];
+        assert_eq!(
+            ready_for_execution(&proposals, &registry, &approvals, 160),
+            vec![2, 6]
+        );
+        assert_eq!(
+            ready_for_execution(&proposals, &registry, &approvals, 159),
+            Vec::<u64>::new()
+        );
+    }
+}
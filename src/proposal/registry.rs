@@ -0,0 +1,386 @@
+//! Versioned proposal registry
+//!
+//! Every stored proposal has a `revision` that is bumped on each successful
+//! mutation. Mutations take the revision the caller last read and fail with
+//! `FsmError::ConflictDetected` if another writer got there first, so a
+//! read-modify-write cycle never silently overwrites a concurrent update.
+//! The registry itself is not synchronized; share it behind a `Mutex` or
+//! `RwLock` and hold the lock only for the individual calls.
+//!
+//! Proposals removed by `retention::purge` leave a `Tombstone`, so ids
+//! referenced elsewhere still resolve (to "purged") and are never reused.
+//!
+//! `page` and `iter_filtered` read the registry lazily in id order, so a
+//! caller can walk tens of thousands of entries without collecting them.
+use super::types::Proposal;
+use crate::error::FsmError;
+use crate::query::{Page, ProposalQuery};
+use crate::retention::Tombstone;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// Proposal with its revision
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Versioned<P> {
+    pub proposal: Proposal<P>,
+    pub revision: u64,
+}
+/// Result of resolving an id
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolved<'a, P> {
+    Live(&'a Versioned<P>),
+    Purged(&'a Tombstone),
+}
+/// Proposals keyed by id, iterated in ascending id order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionedRegistry<P> {
+    entries: BTreeMap<u64, Versioned<P>>,
+    tombstones: BTreeMap<u64, Tombstone>,
+}
+impl<P> Default for VersionedRegistry<P> {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            tombstones: BTreeMap::new(),
+        }
+    }
+}
+impl<P: Clone> VersionedRegistry<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a proposal at revision 0; the id must be unused and not purged
+    pub fn insert(&mut self, proposal: Proposal<P>) -> Result<u64, FsmError> {
+        if self.entries.contains_key(&proposal.id) || self.tombstones.contains_key(&proposal.id) {
+            return Err(FsmError::InvalidInput);
+        }
+        self.entries.insert(
+            proposal.id,
+            Versioned {
+                proposal,
+                revision: 0,
+            },
+        );
+        Ok(0)
+    }
+    /// Proposal and revision for `id`
+    pub fn get(&self, id: u64) -> Option<&Versioned<P>> {
+        self.entries.get(&id)
+    }
+    /// Live entry or tombstone for `id`
+    pub fn resolve(&self, id: u64) -> Option<Resolved<'_, P>> {
+        match self.entries.get(&id) {
+            Some(entry) => Some(Resolved::Live(entry)),
+            None => self.tombstones.get(&id).map(Resolved::Purged),
+        }
+    }
+    /// Tombstones in ascending id order
+    pub fn tombstones(&self) -> impl Iterator<Item = &Tombstone> {
+        self.tombstones.values()
+    }
+    /// Replace a live entry with its tombstone
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    pub(crate) fn bury(&mut self, tombstone: Tombstone) -> Option<Proposal<P>> {
+        let entry = self.entries.remove(&tombstone.id)?;
+        self.tombstones.insert(tombstone.id, tombstone);
+        Some(entry.proposal)
+    }
+    /// Current revision of `id`
+    pub fn revision(&self, id: u64) -> Option<u64> {
+        self.entries.get(&id).map(|entry| entry.revision)
+    }
+    /// Apply `f` if `id` is still at `expected_rev`; returns the new revision
+    ///
+    /// `f` runs on a copy, so a failing `f` leaves the stored proposal and its
+    /// revision untouched. Unknown ids are `InvalidInput`.
+    pub fn modify(
+        &mut self,
+        id: u64,
+        expected_rev: u64,
+        f: impl FnOnce(&mut Proposal<P>) -> Result<(), FsmError>,
+    ) -> Result<u64, FsmError> {
+        let entry = self.entries.get_mut(&id).ok_or(FsmError::InvalidInput)?;
+        if entry.revision != expected_rev {
+            return Err(FsmError::ConflictDetected);
+        }
+        let revision = entry.revision.checked_add(1).ok_or(FsmError::Overflow)?;
+        let mut proposal = entry.proposal.clone();
+        f(&mut proposal)?;
+        if proposal.id != id {
+            return Err(FsmError::InvalidInput);
+        }
+        entry.proposal = proposal;
+        entry.revision = revision;
+        Ok(revision)
+    }
+    /// Remove `id` if it is still at `expected_rev`
+    pub fn remove(&mut self, id: u64, expected_rev: u64) -> Result<Proposal<P>, FsmError> {
+        let entry = self.entries.get(&id).ok_or(FsmError::InvalidInput)?;
+        if entry.revision != expected_rev {
+            return Err(FsmError::ConflictDetected);
+        }
+        self.entries
+            .remove(&id)
+            .map(|entry| entry.proposal)
+            .ok_or(FsmError::InvalidInput)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Entries in ascending id order
+    pub fn iter(&self) -> impl Iterator<Item = &Versioned<P>> {
+        self.entries.values()
+    }
+}
+impl<P: PartialEq> VersionedRegistry<P> {
+    /// Proposals matching the filters of `filter`, in ascending id order, read lazily
+    pub fn iter_filtered<'a>(
+        &'a self,
+        filter: &'a ProposalQuery<P>,
+    ) -> impl Iterator<Item = &'a Proposal<P>> + 'a {
+        self.entries
+            .values()
+            .map(|entry| &entry.proposal)
+            .filter(move |proposal| filter.matches(proposal))
+    }
+    /// Matches with an id above `after_id`, at most `limit` (at least one), by id
+    ///
+    /// Resume with `next_cursor` as `after_id`. A walk sees every entry that
+    /// matches throughout exactly once, including ones inserted during the
+    /// walk with an id past the cursor; entries inserted behind it are not seen.
+    pub fn page(
+        &self,
+        after_id: Option<u64>,
+        limit: usize,
+        filter: &ProposalQuery<P>,
+    ) -> Page<&Proposal<P>> {
+        let from = after_id.map_or(Bound::Unbounded, Bound::Excluded);
+        let matches = self
+            .entries
+            .range((from, Bound::Unbounded))
+            .map(|(_, entry)| &entry.proposal)
+            .filter(|proposal| filter.matches(proposal));
+        Page::from_ascending(matches, limit, |proposal| proposal.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::ProposalStatus;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    fn create_test_proposal(id: u64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+        .build(0)
+        .unwrap()
+    }
+    #[test]
+    fn test_modify_bumps_revision() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_test_proposal(1)).unwrap();
+        let revision = registry
+            .modify(1, 0, |p| p.activate_with_time(1, 1, 5))
+            .unwrap();
+        assert_eq!(revision, 1);
+        assert_eq!(registry.revision(1), Some(1));
+        assert_eq!(
+            registry.get(1).unwrap().proposal.status,
+            ProposalStatus::Active
+        );
+    }
+    #[test]
+    fn test_interleaved_modification_conflicts() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_test_proposal(1)).unwrap();
+
+        // Handlers A and B both read revision 0
+        let read_a = registry.revision(1).unwrap();
+        let read_b = registry.revision(1).unwrap();
+
+        // A writes first
+        registry
+            .modify(1, read_a, |p| p.cancel_with_time("spam".to_string(), 5))
+            .unwrap();
+
+        // B's write is based on stale data and is refused
+        assert_eq!(
+            registry.modify(1, read_b, |p| p.activate_with_time(1, 1, 5)),
+            Err(FsmError::ConflictDetected)
+        );
+
+        // B re-reads, sees the cancellation and gets the real outcome
+        let reread = registry.revision(1).unwrap();
+        assert_eq!(
+            registry.modify(1, reread, |p| p.activate_with_time(1, 1, 5)),
+            Err(FsmError::InvalidStateTransition)
+        );
+        assert_eq!(
+            registry.get(1).unwrap().proposal.status,
+            ProposalStatus::Cancelled
+        );
+    }
+    #[test]
+    fn test_failed_modify_leaves_entry_untouched() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_test_proposal(1)).unwrap();
+        assert_eq!(
+            registry.modify(1, 0, |p| {
+                p.title = "Changed".to_string();
+                Err(FsmError::InvalidState)
+            }),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(registry.revision(1), Some(0));
+        assert_eq!(registry.get(1).unwrap().proposal.title, "Test");
+        assert_eq!(
+            registry.modify(1, 0, |p| {
+                p.id = 2;
+                Ok(())
+            }),
+            Err(FsmError::InvalidInput)
+        );
+    }
+    #[test]
+    fn test_insert_and_remove() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_test_proposal(2)).unwrap();
+        registry.insert(create_test_proposal(1)).unwrap();
+        assert_eq!(
+            registry.insert(create_test_proposal(1)),
+            Err(FsmError::InvalidInput)
+        );
+        let ids: Vec<u64> = registry.iter().map(|e| e.proposal.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        registry.modify(1, 0, |_| Ok(())).unwrap();
+        assert_eq!(registry.remove(1, 0), Err(FsmError::ConflictDetected));
+        assert_eq!(registry.remove(1, 1).unwrap().id, 1);
+        assert_eq!(
+            registry.modify(1, 1, |_| Ok(())),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(registry.len(), 1);
+    }
+    #[test]
+    fn test_concurrent_retry_loses_no_updates() {
+        let registry = Arc::new(Mutex::new(VersionedRegistry::new()));
+        registry
+            .lock()
+            .unwrap()
+            .insert(create_test_proposal(1))
+            .unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let registry = Arc::clone(&registry);
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        loop {
+                            // Read and write under separate lock acquisitions
+                            let (revision, yes) = {
+                                let guard = registry.lock().unwrap();
+                                let entry = guard.get(1).unwrap();
+                                (entry.revision, entry.proposal.yes_votes)
+                            };
+                            let result = registry.lock().unwrap().modify(1, revision, |p| {
+                                p.yes_votes = yes + 1;
+                                Ok(())
+                            });
+                            match result {
+                                Ok(_) => break,
+                                Err(FsmError::ConflictDetected) => continue,
+                                Err(other) => panic!("unexpected error: {other}"),
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let guard = registry.lock().unwrap();
+        assert_eq!(guard.get(1).unwrap().proposal.yes_votes, 100);
+        assert_eq!(guard.revision(1), Some(100));
+    }
+    #[test]
+    fn test_pages_cover_the_filtered_set() {
+        let mut registry = VersionedRegistry::new();
+        for id in 1..=10_000 {
+            let mut proposal = create_test_proposal(id);
+            proposal.author = (id % 3) as u8;
+            registry.insert(proposal).unwrap();
+        }
+        let filter = ProposalQuery::new().author(0);
+        let expected: Vec<u64> = (1..=10_000).filter(|id| id % 3 == 0).collect();
+        assert_eq!(
+            registry
+                .iter_filtered(&filter)
+                .map(|p| p.id)
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+        loop {
+            let page = registry.page(cursor, 100, &filter);
+            assert!(page.items.len() <= 100);
+            seen.extend(page.items.iter().map(|p| p.id));
+            pages += 1;
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(seen, expected);
+        assert_eq!(pages, expected.len().div_ceil(100));
+    }
+    #[test]
+    fn test_inserts_during_a_walk() {
+        let mut registry = VersionedRegistry::new();
+        for id in (10..=100).step_by(10) {
+            registry.insert(create_test_proposal(id)).unwrap();
+        }
+        let filter = ProposalQuery::new();
+        let ids = |page: Page<&Proposal<u8>>| {
+            let ids: Vec<u64> = page.items.iter().map(|p| p.id).collect();
+            (ids, page.next_cursor)
+        };
+        let (mut seen, mut cursor) = ids(registry.page(None, 3, &filter));
+        assert_eq!(seen, vec![10, 20, 30]);
+        assert_eq!(cursor, Some(30));
+
+        // Behind the cursor: not seen. Past it: seen once.
+        registry.insert(create_test_proposal(15)).unwrap();
+        registry.insert(create_test_proposal(35)).unwrap();
+        registry.insert(create_test_proposal(1_000)).unwrap();
+        while let Some(after) = cursor {
+            let (ids, next) = ids(registry.page(Some(after), 3, &filter));
+            seen.extend(ids);
+            cursor = next;
+        }
+        assert_eq!(
+            seen,
+            vec![10, 20, 30, 35, 40, 50, 60, 70, 80, 90, 100, 1_000]
+        );
+        let last = registry.page(Some(1_000), 3, &filter);
+        assert!(last.items.is_empty());
+        assert_eq!(last.next_cursor, None);
+        assert_eq!(registry.page(None, 0, &filter).items.len(), 1);
+    }
+}
@@ -0,0 +1,260 @@
+//! Voting power snapshots
+//!
+//! Token-weighted votes must count balances as of activation, not as of the
+//! vote, or power bought mid-vote would count. A `PowerSnapshot` records each
+//! voter's power at one moment; once `attach_snapshot` has fixed it on an
+//! Active proposal, every vote weighs exactly the voter's snapshot power and
+//! voters absent from it fail with `NotInSnapshot`. `total_power` is the
+//! quorum base (see `Proposal::quorum_counts`).
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+use crate::metrics;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Voting power per voter at `taken_at`
+///
+/// Fields are private so a snapshot cannot be edited after `new`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PowerSnapshot<P> {
+    /// Sorted by voter, each voter once
+    powers: Vec<(P, u64)>,
+    taken_at: i64,
+    total_power: u64,
+}
+impl<P: Ord> PowerSnapshot<P> {
+    /// Snapshot of `powers` taken at `taken_at`
+    ///
+    /// `InvalidInput` for a duplicate voter or a zero power; `Overflow` if the
+    /// total passes `u64::MAX`.
+    pub fn new(mut powers: Vec<(P, u64)>, taken_at: i64) -> Result<Self, FsmError> {
+        powers.sort_by(|a, b| a.0.cmp(&b.0));
+        if powers.windows(2).any(|pair| pair[0].0 == pair[1].0)
+            || powers.iter().any(|(_, power)| *power == 0)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        let total_power = powers.iter().try_fold(0u64, |total, (_, power)| {
+            total.checked_add(*power).ok_or(FsmError::Overflow)
+        })?;
+        Ok(Self {
+            powers,
+            taken_at,
+            total_power,
+        })
+    }
+    /// Power of `voter`, `None` if absent
+    pub fn power_of(&self, voter: &P) -> Option<u64> {
+        self.powers
+            .binary_search_by(|(entry, _)| entry.cmp(voter))
+            .ok()
+            .map(|index| self.powers[index].1)
+    }
+}
+impl<P> PowerSnapshot<P> {
+    pub fn taken_at(&self) -> i64 {
+        self.taken_at
+    }
+    pub fn total_power(&self) -> u64 {
+        self.total_power
+    }
+    /// `(voter, power)` sorted by voter
+    pub fn powers(&self) -> &[(P, u64)] {
+        &self.powers
+    }
+}
+impl<P> Proposal<P> {
+    /// Fix the voting power of this proposal's vote
+    ///
+    /// Only at activation: the proposal must be Active with no votes yet
+    /// (`InvalidState` otherwise) and the snapshot taken no later than
+    /// `submitted_at` (`InvalidInput` otherwise). `AlreadyInitialized` if a
+    /// snapshot is attached; it cannot be replaced.
+    pub fn attach_snapshot(&mut self, snapshot: PowerSnapshot<P>) -> Result<(), FsmError> {
+        if self.power_snapshot.is_some() {
+            return Err(FsmError::AlreadyInitialized);
+        }
+        if self.status != ProposalStatus::Active || !self.votes.is_empty() {
+            return Err(FsmError::InvalidState);
+        }
+        if self
+            .submitted_at
+            .is_none_or(|activated_at| snapshot.taken_at > activated_at)
+        {
+            return Err(FsmError::InvalidInput);
+        }
+        self.power_snapshot = Some(snapshot);
+        Ok(())
+    }
+    /// `(votes cast, total power)` for `update_quorum_from_counts`; `None` without a snapshot
+    ///
+    /// Under `normalize_weights` the votes cast are reconstructed raw weights,
+    /// so both counts share the snapshot's unit.
+    pub fn quorum_counts(&self) -> Option<(u64, u64)> {
+        self.power_snapshot.as_ref().map(|snapshot| {
+            let cast = self.raw_weight(self.total_votes).min(snapshot.total_power);
+            (cast, snapshot.total_power)
+        })
+    }
+}
+impl<P: Ord> Proposal<P> {
+    /// Cast a vote weighing `voter`'s snapshot power
+    ///
+    /// `InvalidState` without a snapshot, `NotInSnapshot` if `voter` is absent
+    /// from it, then the errors of `cast_vote`.
+    pub fn cast_weighted_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let result = match &self.power_snapshot {
+            None => Err(FsmError::InvalidState),
+            Some(snapshot) => match snapshot.power_of(&voter) {
+                None => Err(FsmError::NotInSnapshot),
+                Some(weight) => self.try_cast_vote(voter, support, weight, current_time),
+            },
+        };
+        metrics::record_vote("proposal", "cast", &result);
+        result
+    }
+    /// With a snapshot attached, `weight` must be `voter`'s snapshot power
+    ///
+    /// `NotInSnapshot` if `voter` is absent, `InvalidInput` for another weight.
+    pub(crate) fn check_snapshot_weight(&self, voter: &P, weight: u64) -> Result<(), FsmError> {
+        self.check_snapshot_power(voter, |power| power == weight)
+    }
+    /// `check_snapshot_weight` for an already recorded, normalized weight
+    pub(crate) fn check_snapshot_stored(&self, voter: &P, stored: u64) -> Result<(), FsmError> {
+        self.check_snapshot_power(voter, |power| self.normalized_weight(power) == stored)
+    }
+    fn check_snapshot_power(
+        &self,
+        voter: &P,
+        matches: impl Fn(u64) -> bool,
+    ) -> Result<(), FsmError> {
+        let Some(snapshot) = &self.power_snapshot else {
+            return Ok(());
+        };
+        match snapshot.power_of(voter) {
+            None => Err(FsmError::NotInSnapshot),
+            Some(power) if !matches(power) => Err(FsmError::InvalidInput),
+            Some(_) => Ok(()),
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    fn create_active_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 10).unwrap();
+        proposal
+    }
+    fn create_test_snapshot() -> PowerSnapshot<u8> {
+        PowerSnapshot::new(vec![(3, 50), (2, 30), (4, 20)], 10).unwrap()
+    }
+    #[test]
+    fn test_snapshot_new() {
+        let snapshot = create_test_snapshot();
+        assert_eq!(snapshot.total_power(), 100);
+        assert_eq!(snapshot.powers(), &[(2, 30), (3, 50), (4, 20)]);
+        assert_eq!(snapshot.power_of(&3), Some(50));
+        assert_eq!(snapshot.power_of(&5), None);
+        assert_eq!(
+            PowerSnapshot::new(vec![(2, 1), (2, 1)], 0),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            PowerSnapshot::new(vec![(2, 0)], 0),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            PowerSnapshot::new(vec![(2, u64::MAX), (3, 1)], 0),
+            Err(FsmError::Overflow)
+        );
+    }
+    #[test]
+    fn test_attach_snapshot_only_at_activation() {
+        let mut draft = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1u8,
+        )
+        .build(0)
+        .unwrap();
+        assert_eq!(
+            draft.attach_snapshot(create_test_snapshot()),
+            Err(FsmError::InvalidState)
+        );
+        let mut proposal = create_active_proposal();
+        let late = PowerSnapshot::new(vec![(2, 30)], 11).unwrap();
+        assert_eq!(proposal.attach_snapshot(late), Err(FsmError::InvalidInput));
+        let mut voted = proposal.clone();
+        voted.cast_vote(2, true, 1, 20).unwrap();
+        assert_eq!(
+            voted.attach_snapshot(create_test_snapshot()),
+            Err(FsmError::InvalidState)
+        );
+        proposal.attach_snapshot(create_test_snapshot()).unwrap();
+        assert_eq!(
+            proposal.attach_snapshot(PowerSnapshot::new(vec![(2, 99)], 10).unwrap()),
+            Err(FsmError::AlreadyInitialized)
+        );
+        assert_eq!(proposal.power_snapshot, Some(create_test_snapshot()));
+    }
+    #[test]
+    fn test_votes_weigh_snapshot_power() {
+        let mut proposal = create_active_proposal();
+        assert_eq!(
+            proposal.cast_weighted_vote(2, true, 20),
+            Err(FsmError::InvalidState)
+        );
+        proposal.attach_snapshot(create_test_snapshot()).unwrap();
+        // Voter 2 held 30 at activation and has since bought more
+        let live_power = 500;
+        assert_eq!(
+            proposal.cast_vote(2, true, live_power, 20),
+            Err(FsmError::InvalidInput)
+        );
+        proposal.cast_weighted_vote(2, true, 20).unwrap();
+        proposal.cast_vote(3, false, 50, 20).unwrap();
+        assert_eq!(
+            proposal.cast_weighted_vote(5, true, 20),
+            Err(FsmError::NotInSnapshot)
+        );
+        assert_eq!(
+            proposal.cast_vote_with_nonce(5, true, 10, Some(1), 20),
+            Err(FsmError::NotInSnapshot)
+        );
+        assert_eq!((proposal.yes_votes, proposal.no_votes), (30, 50));
+        assert_eq!(proposal.quorum_counts(), Some((80, 100)));
+        assert_eq!(create_active_proposal().quorum_counts(), None);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_snapshot_serde_roundtrip() {
+        let mut proposal = create_active_proposal();
+        proposal.attach_snapshot(create_test_snapshot()).unwrap();
+        let json = serde_json::to_string(&proposal).unwrap();
+        let restored: Proposal<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, proposal);
+        let mut legacy = serde_json::to_value(create_active_proposal()).unwrap();
+        legacy.as_object_mut().unwrap().remove("power_snapshot");
+        let restored: Proposal<u8> = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.power_snapshot, None);
+    }
+}
@@ -0,0 +1,198 @@
+//! Proposal sponsors (co-authors)
+//!
+//! Sponsors endorse a Draft proposal; `ActivationContext::min_sponsors` turns
+//! the endorsement count into an activation gate. The author always counts as
+//! a sponsor and is never stored in `Proposal::sponsors`, which holds at most
+//! `MAX_SPONSORS` co-sponsors in the order they signed on.
+//!
+//! Sponsors are only checked at activation. A sponsor may withdraw from an
+//! Active proposal, but that does not undo the activation even if the count
+//! drops below the minimum that was required.
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+
+/// Maximum co-sponsors on one proposal, not counting the author
+pub const MAX_SPONSORS: usize = 20;
+
+impl<P: PartialEq> Proposal<P> {
+    /// Add `sponsor` to a Draft proposal
+    ///
+    /// Returns `false` without changing anything if `sponsor` is the author or
+    /// already a sponsor. Errors: `InvalidState` if not Draft, compacted or
+    /// already at `MAX_SPONSORS`, `TimeRegression` if `current_time` breaks
+    /// `time_ordering`.
+    pub fn add_sponsor(&mut self, sponsor: P, current_time: i64) -> Result<bool, FsmError> {
+        self.check_sponsor_change(current_time)?;
+        if self.status != ProposalStatus::Draft {
+            return Err(FsmError::InvalidState);
+        }
+        if self.is_sponsor(&sponsor) {
+            return Ok(false);
+        }
+        if self.sponsors.len() >= MAX_SPONSORS {
+            return Err(FsmError::InvalidState);
+        }
+        self.sponsors.push(sponsor);
+        self.record_sponsor_change(current_time);
+        Ok(true)
+    }
+
+    /// Withdraw `sponsor`, in any status
+    ///
+    /// Returns `false` if `sponsor` was not a co-sponsor. Errors: `InvalidInput`
+    /// for the author, `InvalidState` if compacted, `TimeRegression` if
+    /// `current_time` breaks `time_ordering`. Withdrawing after activation
+    /// leaves the proposal Active (see the module docs).
+    pub fn remove_sponsor(&mut self, sponsor: &P, current_time: i64) -> Result<bool, FsmError> {
+        self.check_sponsor_change(current_time)?;
+        if *sponsor == self.author {
+            return Err(FsmError::InvalidInput);
+        }
+        let Some(index) = self.sponsors.iter().position(|s| s == sponsor) else {
+            return Ok(false);
+        };
+        self.sponsors.remove(index);
+        self.record_sponsor_change(current_time);
+        Ok(true)
+    }
+
+    /// Whether `member` is the author or a co-sponsor
+    pub fn is_sponsor(&self, member: &P) -> bool {
+        *member == self.author || self.sponsors.contains(member)
+    }
+
+    fn check_sponsor_change(&self, current_time: i64) -> Result<(), FsmError> {
+        if self.compacted {
+            return Err(FsmError::InvalidState);
+        }
+        self.time_ordering.check(self.last_event_at, current_time)
+    }
+
+    fn record_sponsor_change(&mut self, current_time: i64) {
+        self.updated_at = Some(current_time);
+        self.last_event_at = self.last_event_at.max(current_time);
+    }
+}
+
+impl<P> Proposal<P> {
+    /// Number of sponsors, the author included
+    pub fn sponsor_count(&self) -> usize {
+        self.sponsors.len() + 1
+    }
+
+    /// The author followed by the co-sponsors
+    pub fn all_sponsors(&self) -> impl Iterator<Item = &P> {
+        std::iter::once(&self.author).chain(self.sponsors.iter())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proposal::activation::{ActivationCheck, ActivationContext};
+
+    fn create_test_proposal() -> Proposal<u8> {
+        Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(1000)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_add_sponsor_deduplicates_and_counts_author() {
+        let mut proposal = create_test_proposal();
+        assert_eq!(proposal.sponsor_count(), 1);
+        assert_eq!(proposal.add_sponsor(1, 1000), Ok(false));
+        assert_eq!(proposal.add_sponsor(2, 1010), Ok(true));
+        assert_eq!(proposal.add_sponsor(2, 1020), Ok(false));
+        assert_eq!(proposal.sponsors, vec![2]);
+        assert_eq!(proposal.sponsor_count(), 2);
+        assert_eq!(proposal.all_sponsors().collect::<Vec<_>>(), vec![&1, &2]);
+        assert!(proposal.is_sponsor(&1));
+        assert!(!proposal.is_sponsor(&3));
+        assert_eq!(proposal.updated_at, Some(1010));
+    }
+
+    #[test]
+    fn test_add_sponsor_cap_status_and_time() {
+        let mut proposal = create_test_proposal();
+        for sponsor in 0..MAX_SPONSORS as u8 {
+            proposal.add_sponsor(sponsor + 10, 1000).unwrap();
+        }
+        assert_eq!(proposal.add_sponsor(99, 1000), Err(FsmError::InvalidState));
+        assert_eq!(proposal.add_sponsor(10, 1000), Ok(false));
+        assert_eq!(proposal.add_sponsor(99, 999), Err(FsmError::TimeRegression));
+
+        let mut proposal = create_test_proposal();
+        proposal.activate_with_time(1, 1, 1000).unwrap();
+        assert_eq!(proposal.add_sponsor(2, 1000), Err(FsmError::InvalidState));
+    }
+
+    #[test]
+    fn test_remove_sponsor() {
+        let mut proposal = create_test_proposal();
+        proposal.add_sponsor(2, 1000).unwrap();
+        assert_eq!(
+            proposal.remove_sponsor(&1, 1000),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(proposal.remove_sponsor(&3, 1000), Ok(false));
+        assert_eq!(proposal.remove_sponsor(&2, 1000), Ok(true));
+        assert!(proposal.sponsors.is_empty());
+    }
+
+    #[test]
+    fn test_min_sponsors_gate() {
+        let mut proposal = create_test_proposal();
+        proposal.add_sponsor(2, 1000).unwrap();
+        let ctx = ActivationContext::new(1, 1, 1100).min_sponsors(3);
+        assert_eq!(
+            proposal.activate_in(&ctx),
+            Err(FsmError::InsufficientSponsors {
+                required: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(proposal.status, ProposalStatus::Draft);
+
+        proposal.add_sponsor(3, 1000).unwrap();
+        let report = proposal.activate_in(&ctx).unwrap();
+        assert!(report.evaluated(ActivationCheck::Sponsors));
+        assert_eq!(proposal.status, ProposalStatus::Active);
+    }
+
+    #[test]
+    fn test_withdrawal_after_activation_keeps_proposal_active() {
+        let mut proposal = create_test_proposal();
+        proposal.add_sponsor(2, 1000).unwrap();
+        proposal.add_sponsor(3, 1000).unwrap();
+        proposal
+            .activate_in(&ActivationContext::new(1, 1, 1100).min_sponsors(3))
+            .unwrap();
+
+        assert_eq!(proposal.remove_sponsor(&3, 1200), Ok(true));
+        assert_eq!(proposal.sponsor_count(), 2);
+        assert_eq!(proposal.status, ProposalStatus::Active);
+        proposal.cast_vote(4, true, 1, 1300).unwrap();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_sponsors_serde_roundtrip() {
+        let mut proposal = create_test_proposal();
+        proposal.add_sponsor(2, 1000).unwrap();
+        let json = serde_json::to_value(&proposal).unwrap();
+        assert_eq!(json["sponsors"], serde_json::json!([2]));
+        let restored: Proposal<u8> = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, proposal);
+        let mut legacy = serde_json::to_value(create_test_proposal()).unwrap();
+        legacy.as_object_mut().unwrap().remove("sponsors");
+        let restored: Proposal<u8> = serde_json::from_value(legacy).unwrap();
+        assert!(restored.sponsors.is_empty());
+    }
+}
@@ -15,6 +15,24 @@ pub struct TemplateField<P> {
     _phantom: PhantomData<P>,
 }
 
+impl<P> TemplateField<P> {
+    /// Create a new template field
+    pub fn new(
+        name: String,
+        description: String,
+        field_type: TemplateFieldType,
+        required: bool,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            field_type,
+            required,
+            _phantom: PhantomData,
+        }
+    }
+}
+
 /// Template field type
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum TemplateFieldType {
@@ -36,7 +54,6 @@ pub struct ProposalTemplate<P> {
     pub created_at: i64,
     pub updated_at: Option<i64>,
     pub is_active: bool,
-    _phantom: PhantomData<P>,
 }
 
 impl<P> ProposalTemplate<P> {
@@ -115,7 +132,6 @@ impl<P> ProposalTemplate<P> {
             created_at: current_time,
             updated_at: None,
             is_active: true,
-            _phantom: PhantomData,
         })
     }
 
@@ -215,20 +231,18 @@ impl<P> ProposalTemplate<P> {
 mod tests {
     use super::*;
     use crate::error::FsmError;
-    use std::marker::PhantomData;
 
     fn create_test_pubkey(seed: u8) -> u8 {
         seed
     }
 
     fn create_test_field(name: &str) -> TemplateField<u8> {
-        TemplateField {
-            name: name.to_string(),
-            description: "Test field".to_string(),
-            field_type: TemplateFieldType::Text,
-            required: false,
-            _phantom: PhantomData,
-        }
+        TemplateField::new(
+            name.to_string(),
+            "Test field".to_string(),
+            TemplateFieldType::Text,
+            false,
+        )
     }
 
     #[test]
@@ -1,12 +1,22 @@
 //! Treasury Proposal types
 //!
 //! Specialized proposal types for Treasury operations
+//!
+//! Moving funds out is capability-gated: a transfer above
+//! `TreasuryPolicy::exempt_transfer_max` needs `CapabilityType::Withdraw`,
+//! held by the author or by a member sponsoring the proposal. Pass a
+//! `TreasuryAuthorization` to `ProposalBuilder::treasury_authorization` to
+//! have the builder check it.
 
+use crate::enums::CapabilityType;
 use crate::error::FsmError;
-use std::marker::PhantomData;
+use crate::member::Member;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Treasury Proposal Type
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TreasuryProposalType {
     /// Withdraw funds from treasury
     Withdrawal,
@@ -24,6 +34,7 @@ pub enum TreasuryProposalType {
 
 /// Treasury Proposal Operation Data
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TreasuryOperationData<P> {
     pub operation_type: TreasuryProposalType,
     pub amount: Option<u64>,             // For withdrawal, deposit, transfer
@@ -32,7 +43,6 @@ pub struct TreasuryOperationData<P> {
     pub capability_type: Option<String>, // For grant capability
     pub expires_at: Option<i64>,         // For grant capability
     pub description: String,             // Operation description
-    pub(crate) _phantom: PhantomData<P>,
 }
 
 impl<P> TreasuryOperationData<P> {
@@ -53,7 +63,6 @@ impl<P> TreasuryOperationData<P> {
             capability_type,
             expires_at,
             description,
-            _phantom: PhantomData,
         }
     }
 
@@ -109,11 +118,79 @@ impl<P> TreasuryOperationData<P> {
     }
 }
 
+impl<P> TreasuryOperationData<P> {
+    /// Capability needed to propose this operation under `policy`, if any
+    pub fn required_capability(&self, policy: &TreasuryPolicy) -> Option<CapabilityType> {
+        match self.operation_type {
+            TreasuryProposalType::Transfer
+                if self.amount.unwrap_or(0) > policy.exempt_transfer_max =>
+            {
+                Some(CapabilityType::Withdraw)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Limits on who may propose treasury operations
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TreasuryPolicy {
+    /// Transfers of at most this amount need no capability; the default 0 exempts none
+    pub exempt_transfer_max: u64,
+}
+
+/// Capabilities backing a treasury proposal: the author's and its sponsors'
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreasuryAuthorization {
+    capabilities: Vec<CapabilityType>,
+}
+
+impl TreasuryAuthorization {
+    /// Authorization from a plain capability list
+    pub fn new(capabilities: Vec<CapabilityType>) -> Self {
+        Self { capabilities }
+    }
+
+    /// The author's capabilities; a member not in good standing contributes none
+    pub fn from_member<P>(author: &Member<P>) -> Self {
+        Self::default().with_sponsor(author)
+    }
+
+    /// Add the capabilities of a sponsoring member in good standing
+    pub fn with_sponsor<P>(mut self, sponsor: &Member<P>) -> Self {
+        if sponsor.is_in_good_standing() {
+            self.capabilities.extend(&sponsor.capabilities);
+        }
+        self
+    }
+
+    /// Whether the author or a sponsor holds `capability`
+    pub fn holds(&self, capability: CapabilityType) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Ok if `operation` is exempt under `policy` or its capability is held
+    ///
+    /// Errors: `CapabilityRequired` naming the missing capability.
+    pub fn check<P>(
+        &self,
+        operation: &TreasuryOperationData<P>,
+        policy: &TreasuryPolicy,
+    ) -> Result<(), FsmError> {
+        match operation.required_capability(policy) {
+            Some(capability) if !self.holds(capability) => {
+                Err(FsmError::CapabilityRequired { capability })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::error::FsmError;
-    use std::marker::PhantomData;
 
     fn create_test_pubkey(seed: u8) -> u8 {
         seed
@@ -129,7 +206,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Withdraw for expenses".to_string(),
-            _phantom: PhantomData,
         };
         // Validation would require Clock, so skip in unit test
         assert_eq!(data.operation_type, TreasuryProposalType::Withdrawal);
@@ -147,7 +223,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Transfer to secondary treasury".to_string(),
-            _phantom: PhantomData,
         };
         assert_eq!(data.operation_type, TreasuryProposalType::Transfer);
         assert_eq!(data.target_treasury, Some(target));
@@ -163,7 +238,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Withdrawal description".to_string(),
-            _phantom: PhantomData,
         };
         assert!(data.validate(0).is_ok());
 
@@ -175,7 +249,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Withdrawal description".to_string(),
-            _phantom: PhantomData,
         };
         assert_eq!(
             invalid_data.validate(0).unwrap_err(),
@@ -194,7 +267,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Transfer description".to_string(),
-            _phantom: PhantomData,
         };
         assert!(data.validate(0).is_ok());
 
@@ -206,7 +278,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Transfer description".to_string(),
-            _phantom: PhantomData,
         };
         assert_eq!(
             invalid_data_no_target.validate(0).unwrap_err(),
@@ -226,7 +297,6 @@ mod tests {
             capability_type: Some("Admin".to_string()),
             expires_at: Some(expires),
             description: "Grant capability description".to_string(),
-            _phantom: PhantomData,
         };
         assert!(data.validate(expires - 1).is_ok()); // current_time < expires
 
@@ -238,7 +308,6 @@ mod tests {
             capability_type: Some("Admin".to_string()),
             expires_at: Some(expires),
             description: "Grant capability description".to_string(),
-            _phantom: PhantomData,
         };
         assert_eq!(
             invalid_data_expired.validate(expires + 1).unwrap_err(),
@@ -257,7 +326,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Revoke capability description".to_string(),
-            _phantom: PhantomData,
         };
         assert!(data.validate(0).is_ok());
 
@@ -269,7 +337,6 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "Revoke capability description".to_string(),
-            _phantom: PhantomData,
         };
         assert_eq!(
             invalid_data_no_grantee.validate(0).unwrap_err(),
@@ -287,8 +354,77 @@ mod tests {
             capability_type: None,
             expires_at: None,
             description: "".to_string(), // Invalid
-            _phantom: PhantomData,
         };
         assert_eq!(data.validate(0).unwrap_err(), FsmError::InvalidInput);
     }
+
+    fn create_test_transfer(amount: u64) -> TreasuryOperationData<u8> {
+        TreasuryOperationData::new(
+            TreasuryProposalType::Transfer,
+            Some(amount),
+            Some(create_test_pubkey(2)),
+            None,
+            None,
+            None,
+            "Transfer description".to_string(),
+        )
+    }
+
+    #[test]
+    fn test_small_transfer_is_exempt() {
+        let policy = TreasuryPolicy {
+            exempt_transfer_max: 500,
+        };
+        let author = Member::new(1u8, vec![CapabilityType::Propose]);
+        let authorization = TreasuryAuthorization::from_member(&author);
+        assert_eq!(
+            authorization.check(&create_test_transfer(500), &policy),
+            Ok(())
+        );
+        // Only transfers are gated
+        let mut withdrawal = create_test_transfer(10_000);
+        withdrawal.operation_type = TreasuryProposalType::Withdrawal;
+        assert_eq!(authorization.check(&withdrawal, &policy), Ok(()));
+    }
+
+    #[test]
+    fn test_large_transfer_needs_withdraw() {
+        let policy = TreasuryPolicy {
+            exempt_transfer_max: 500,
+        };
+        let author = Member::new(1u8, vec![CapabilityType::Propose]);
+        assert_eq!(
+            TreasuryAuthorization::from_member(&author).check(&create_test_transfer(501), &policy),
+            Err(FsmError::CapabilityRequired {
+                capability: CapabilityType::Withdraw
+            })
+        );
+        assert_eq!(
+            TreasuryAuthorization::new(vec![CapabilityType::Withdraw])
+                .check(&create_test_transfer(501), &policy),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_sponsor_with_withdraw_authorizes() {
+        let policy = TreasuryPolicy::default();
+        let author = Member::new(1u8, vec![CapabilityType::Propose]);
+        let treasurer = Member::new(2u8, vec![CapabilityType::Withdraw]);
+        let authorization = TreasuryAuthorization::from_member(&author).with_sponsor(&treasurer);
+        assert_eq!(
+            authorization.check(&create_test_transfer(1), &policy),
+            Ok(())
+        );
+
+        let mut suspended = treasurer.clone();
+        suspended.status = crate::enums::MemberStatus::Suspended;
+        let authorization = TreasuryAuthorization::from_member(&author).with_sponsor(&suspended);
+        assert!(!authorization.holds(CapabilityType::Withdraw));
+        assert!(
+            authorization
+                .check(&create_test_transfer(1), &policy)
+                .is_err()
+        );
+    }
 }
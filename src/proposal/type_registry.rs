@@ -0,0 +1,386 @@
+//! Proposal type registry
+//!
+//! Maps each proposal type name to the `TypePolicy` proposals of that type
+//! follow. `Proposal::new_typed` only accepts registered types and copies the
+//! tally rule, overflow mode, tie-break policy and execution timelock onto the proposal;
+//! activation consults the rest of the policy through `ActivationContext::type_policy`.
+use super::builder::ProposalBuilder;
+use super::types::Proposal;
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+use crate::math::ArithmeticPolicy;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How yes/no votes decide a proposal
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TallyPolicy {
+    /// More yes than no votes
+    #[default]
+    SimpleMajority,
+    /// Yes votes are at least `percent` of yes + no (51-100)
+    Supermajority { percent: u8 },
+}
+impl TallyPolicy {
+    /// `Some(true)` to pass, `Some(false)` to reject, `None` for a tie
+    ///
+    /// A supermajority with no votes cast is a tie.
+    pub fn decide(&self, yes_votes: u64, no_votes: u64) -> Option<bool> {
+        match self {
+            TallyPolicy::SimpleMajority => match yes_votes.cmp(&no_votes) {
+                std::cmp::Ordering::Greater => Some(true),
+                std::cmp::Ordering::Less => Some(false),
+                std::cmp::Ordering::Equal => None,
+            },
+            TallyPolicy::Supermajority { percent } => {
+                let cast = yes_votes as u128 + no_votes as u128;
+                if cast == 0 {
+                    return None;
+                }
+                Some(yes_votes as u128 * 100 >= *percent as u128 * cast)
+            }
+        }
+    }
+    fn validate(&self) -> Result<(), FsmError> {
+        match self {
+            TallyPolicy::SimpleMajority => Ok(()),
+            TallyPolicy::Supermajority { percent } if (51..=100).contains(percent) => Ok(()),
+            TallyPolicy::Supermajority { .. } => Err(FsmError::InvalidInput),
+        }
+    }
+}
+/// How a proposal whose vote ended tied is settled
+///
+/// Applied by `auto_transition_detailed`; the policy that fired is recorded
+/// in `Proposal::tie_break_applied`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TieBreakPolicy {
+    /// Leave the proposal Tied for manual resolution
+    #[default]
+    Manual,
+    /// Reject: without a majority for change, the status quo stands
+    StatusQuo,
+    /// Reject: the author needed a majority and did not get one
+    AuthorLoses,
+    /// Pass on the Approved security board decision `decision_ref`
+    ///
+    /// Only `auto_transition_with_chair_decision` can supply the decision;
+    /// without it the proposal is left Tied.
+    ChairDecides { decision_ref: u64 },
+}
+/// What a vote does when it would push a tally past `u64::MAX`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TallyOverflow {
+    /// Fail with `FsmError::Overflow`; the vote is not recorded
+    #[default]
+    Reject,
+    /// Saturate at `u64::MAX`; the vote is recorded
+    Clamp,
+}
+impl TallyOverflow {
+    /// `tally + weight` under this mode
+    pub fn add(self, tally: u64, weight: u64) -> Result<u64, FsmError> {
+        Ok(ArithmeticPolicy::from(self).add(tally, weight)?.value)
+    }
+}
+/// Lifecycle rules for one proposal type
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TypePolicy {
+    /// Proposals must carry treasury operation data
+    pub requires_treasury_op: bool,
+    pub tally: TallyPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tally_overflow: TallyOverflow,
+    /// Minimum members required at activation, replacing the caller's `min_quorum` when larger
+    pub min_quorum_override: Option<u8>,
+    /// Delay between passing and execution
+    pub execution_timelock: DurationSecs,
+    /// Templates proposals may be created from; empty allows any
+    pub allowed_templates: Vec<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tie_break: TieBreakPolicy,
+}
+impl TypePolicy {
+    pub(crate) fn validate(&self) -> Result<(), FsmError> {
+        self.tally.validate()?;
+        if self.min_quorum_override == Some(0) || self.execution_timelock.is_negative() {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+    /// Whether a proposal created from `template_id` is allowed
+    pub fn allows_template(&self, template_id: Option<u64>) -> bool {
+        if self.allowed_templates.is_empty() {
+            return true;
+        }
+        template_id.is_some_and(|id| self.allowed_templates.contains(&id))
+    }
+}
+/// Registered proposal types, ordered by name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ProposalTypeRegistry {
+    types: BTreeMap<String, TypePolicy>,
+}
+impl ProposalTypeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Register a type
+    ///
+    /// `InvalidInput` if the name is not 1-50 chars, is already registered, or
+    /// the policy is invalid.
+    pub fn register(&mut self, name: &str, policy: TypePolicy) -> Result<(), FsmError> {
+        if name.trim().is_empty() || name.len() > 50 || self.types.contains_key(name) {
+            return Err(FsmError::InvalidInput);
+        }
+        policy.validate()?;
+        self.types.insert(name.to_string(), policy);
+        Ok(())
+    }
+    /// Replace the policy of a registered type
+    pub fn update(&mut self, name: &str, policy: TypePolicy) -> Result<(), FsmError> {
+        policy.validate()?;
+        let existing = self.types.get_mut(name).ok_or(FsmError::InvalidInput)?;
+        *existing = policy;
+        Ok(())
+    }
+    pub fn get(&self, name: &str) -> Option<&TypePolicy> {
+        self.types.get(name)
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+    /// Registered names in ascending order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+}
+impl<P> Proposal<P> {
+    /// Build a proposal of a registered type, applying its policy
+    ///
+    /// `InvalidInput` if the type is unknown, `template_id` is not allowed, or
+    /// the policy requires treasury data the builder lacks; otherwise the
+    /// builder's own checks apply.
+    pub fn new_typed(
+        registry: &ProposalTypeRegistry,
+        builder: ProposalBuilder<P>,
+        template_id: Option<u64>,
+        current_time: i64,
+    ) -> Result<Proposal<P>, FsmError> {
+        let policy = registry
+            .get(builder.proposal_type())
+            .ok_or(FsmError::InvalidInput)?;
+        if !policy.allows_template(template_id) {
+            return Err(FsmError::InvalidInput);
+        }
+        if policy.requires_treasury_op && !builder.has_treasury_operation() {
+            return Err(FsmError::InvalidInput);
+        }
+        let mut proposal = builder.build(current_time)?;
+        proposal.tally_policy = policy.tally;
+        proposal.tally_overflow = policy.tally_overflow;
+        proposal.tie_break_policy = policy.tie_break;
+        proposal.execution_timelock = policy.execution_timelock;
+        Ok(proposal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proposal::activation::ActivationContext;
+    use crate::proposal::treasury::{TreasuryOperationData, TreasuryProposalType};
+    use crate::proposal::types::ProposalStatus;
+    fn create_test_registry() -> ProposalTypeRegistry {
+        let mut registry = ProposalTypeRegistry::new();
+        registry
+            .register("governance", TypePolicy::default())
+            .unwrap();
+        registry
+            .register(
+                "treasury",
+                TypePolicy {
+                    requires_treasury_op: true,
+                    tally: TallyPolicy::Supermajority { percent: 67 },
+                    tally_overflow: TallyOverflow::Clamp,
+                    min_quorum_override: Some(5),
+                    execution_timelock: DurationSecs::from_secs(100),
+                    allowed_templates: vec![7],
+                    tie_break: TieBreakPolicy::StatusQuo,
+                },
+            )
+            .unwrap();
+        registry
+    }
+    fn create_test_builder(proposal_type: &str) -> ProposalBuilder<u8> {
+        Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            proposal_type.to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(10))
+    }
+    fn create_test_withdrawal() -> TreasuryOperationData<u8> {
+        TreasuryOperationData::new(
+            TreasuryProposalType::Withdrawal,
+            Some(1000),
+            None,
+            None,
+            None,
+            None,
+            "Withdraw for expenses".to_string(),
+        )
+    }
+    #[test]
+    fn test_register_validation() {
+        let mut registry = create_test_registry();
+        assert_eq!(
+            registry.register("governance", TypePolicy::default()),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            registry.register("", TypePolicy::default()),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            registry.register(&"x".repeat(51), TypePolicy::default()),
+            Err(FsmError::InvalidInput)
+        );
+        let bad_tally = TypePolicy {
+            tally: TallyPolicy::Supermajority { percent: 50 },
+            ..TypePolicy::default()
+        };
+        assert_eq!(
+            registry.register("grant", bad_tally),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            registry.names().collect::<Vec<_>>(),
+            vec!["governance", "treasury"]
+        );
+    }
+    #[test]
+    fn test_new_typed_rejects_unknown_type() {
+        let registry = create_test_registry();
+        assert_eq!(
+            Proposal::new_typed(&registry, create_test_builder("govrenance"), None, 0),
+            Err(FsmError::InvalidInput)
+        );
+    }
+    #[test]
+    fn test_new_typed_applies_policy() {
+        let registry = create_test_registry();
+        assert_eq!(
+            Proposal::new_typed(&registry, create_test_builder("treasury"), Some(7), 0),
+            Err(FsmError::InvalidInput)
+        );
+        let builder = create_test_builder("treasury").treasury_operation(create_test_withdrawal());
+        assert_eq!(
+            Proposal::new_typed(&registry, builder.clone(), Some(8), 0),
+            Err(FsmError::InvalidInput)
+        );
+        let proposal = Proposal::new_typed(&registry, builder, Some(7), 0).unwrap();
+        assert_eq!(
+            proposal.tally_policy,
+            TallyPolicy::Supermajority { percent: 67 }
+        );
+        assert_eq!(proposal.tally_overflow, TallyOverflow::Clamp);
+        assert_eq!(proposal.tie_break_policy, TieBreakPolicy::StatusQuo);
+        assert_eq!(proposal.execution_timelock.as_secs(), 100);
+    }
+    #[test]
+    fn test_activation_consults_policy() {
+        let registry = create_test_registry();
+        let policy = registry.get("treasury").unwrap();
+        let builder = create_test_builder("treasury").treasury_operation(create_test_withdrawal());
+        let mut proposal = Proposal::new_typed(&registry, builder, Some(7), 0).unwrap();
+        assert_eq!(
+            proposal.activate_in(&ActivationContext::new(1, 4, 0).type_policy(policy)),
+            Err(FsmError::InsufficientMembers)
+        );
+        proposal
+            .activate_in(&ActivationContext::new(1, 5, 0).type_policy(policy))
+            .unwrap();
+
+        // Treasury data removed after creation fails the policy gate
+        let mut stripped = Proposal::new_typed(
+            &registry,
+            create_test_builder("treasury").treasury_operation(create_test_withdrawal()),
+            Some(7),
+            0,
+        )
+        .unwrap();
+        stripped.treasury_operation = None;
+        assert_eq!(
+            stripped.activate_in(&ActivationContext::new(1, 5, 0).type_policy(policy)),
+            Err(FsmError::InvalidState)
+        );
+    }
+    #[test]
+    fn test_supermajority_and_timelock() {
+        let registry = create_test_registry();
+        let builder = create_test_builder("treasury").treasury_operation(create_test_withdrawal());
+        let mut proposal = Proposal::new_typed(&registry, builder, Some(7), 0).unwrap();
+        proposal.activate_with_time(1, 5, 0).unwrap();
+        proposal.yes_votes = 60;
+        proposal.no_votes = 40;
+        assert!(
+            proposal
+                .auto_transition_detailed(10)
+                .unwrap()
+                .transitioned()
+        );
+        assert_eq!(proposal.status, ProposalStatus::Rejected);
+
+        let builder = create_test_builder("treasury").treasury_operation(create_test_withdrawal());
+        let mut proposal = Proposal::new_typed(&registry, builder, Some(7), 0).unwrap();
+        proposal.activate_with_time(1, 5, 0).unwrap();
+        proposal.yes_votes = 67;
+        proposal.no_votes = 33;
+        assert!(
+            proposal
+                .auto_transition_detailed(10)
+                .unwrap()
+                .transitioned()
+        );
+        assert_eq!(proposal.status, ProposalStatus::Passed);
+        assert_eq!(proposal.passed_at, Some(10));
+        assert_eq!(proposal.execute_with_time(109), Err(FsmError::InvalidState));
+        assert_eq!(proposal.prepare_execute(109), Err(FsmError::InvalidState));
+        proposal.execute_with_time(110).unwrap();
+    }
+    #[test]
+    fn test_tally_policy_decide() {
+        assert_eq!(TallyPolicy::SimpleMajority.decide(3, 3), None);
+        assert_eq!(TallyPolicy::SimpleMajority.decide(4, 3), Some(true));
+        let two_thirds = TallyPolicy::Supermajority { percent: 67 };
+        assert_eq!(two_thirds.decide(0, 0), None);
+        assert_eq!(two_thirds.decide(u64::MAX, 0), Some(true));
+        assert_eq!(two_thirds.decide(66, 34), Some(false));
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_registry_serde_roundtrip() {
+        let registry = create_test_registry();
+        let json = serde_json::to_string(&registry).unwrap();
+        let decoded: ProposalTypeRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, registry);
+    }
+}
@@ -1,9 +1,13 @@
 //! Proposal account structures
-use std::marker::PhantomData;
 
+use crate::error::FsmError;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 /// Proposal status enum
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ProposalStatus {
     Draft,
     Active,
@@ -13,9 +17,34 @@ pub enum ProposalStatus {
     Cancelled,
     Archived,
     Tied,
+    /// Voting ended short of quorum, whatever the tally
+    QuorumFailed,
+    /// Execution prepared; waiting for commit or abort (two-phase execute)
+    ///
+    /// Declared last so the earlier variants keep their indices.
+    Executing,
+}
+/// Variant name, as serialized by serde
+impl fmt::Display for ProposalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+/// Parse a variant name; `InvalidInput` for anything else
+impl FromStr for ProposalStatus {
+    type Err = FsmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .iter()
+            .find(|status| status.to_string() == s)
+            .cloned()
+            .ok_or(FsmError::InvalidInput)
+    }
 }
 /// Proposal account structure
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Proposal<P> {
     pub id: u64,
     pub title: String,
@@ -27,14 +56,32 @@ pub struct Proposal<P> {
     pub submitted_at: Option<i64>,
     pub cancelled_at: Option<i64>,
     pub executed_at: Option<i64>,
+    /// Set when the proposal passes; starts the execution timelock
+    pub passed_at: Option<i64>,
     pub archived_at: Option<i64>,
-    pub voting_duration: i64,
+    pub voting_duration: crate::duration::DurationSecs,
     pub status: ProposalStatus,
     pub yes_votes: u64,
     pub no_votes: u64,
     pub total_votes: u64,
+    /// Votes recorded by `cast_vote`, in casting order
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub votes: Vec<crate::proposal::votes::VoteRecord<P>>,
+    /// Highest nonce accepted per voter (see `cast_vote_with_nonce`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vote_nonces: Vec<crate::proposal::votes::VoterNonce<P>>,
     pub last_tallied_at: Option<i64>,
     pub cancellation_reason: Option<String>,
+    /// Machine-readable cancellation reason; `Other` for free-text-only cancellations
+    pub cancellation_code: Option<crate::reason::ReasonCode>,
+    /// Successor set by `supersede`
+    pub superseded_by: Option<u64>,
+    /// Proposals that must be executed before this one (see `readiness`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub depends_on: Vec<u64>,
+    /// Co-sponsors in signing order; the author is implied (see `sponsors`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub sponsors: Vec<P>,
     /// Execution data (JSON-encoded data for proposal execution)
     /// For role changes: {"type": "role_change", "target": "...", "role_mask": 123}
     pub execution_data: Option<String>,
@@ -47,13 +94,78 @@ pub struct Proposal<P> {
     /// Optional: Treasury operation data for Treasury proposals
     /// None means this is not a Treasury proposal
     pub treasury_operation: Option<crate::proposal::treasury::TreasuryOperationData<P>>,
-    pub(crate) _phantom: PhantomData<P>,
+    /// Ticket of the in-flight two-phase execution (status Executing)
+    pub execution_ticket: Option<crate::proposal::execution::ExecutionTicket>,
+    /// Number of `prepare_execute` calls so far; used as the next ticket id
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub execution_attempts: u32,
+    /// Reason given by the last `abort_execute` (or stale sweep)
+    pub execution_abort_reason: Option<String>,
+    /// Results of the latest execution attempts, oldest first (see `execution`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub execution_history: Vec<crate::proposal::execution::ExecutionResult>,
+    /// Timestamp of the latest lifecycle event (creation included)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_event_at: i64,
+    /// Rule applied to `current_time` against `last_event_at`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub time_ordering: crate::time_guard::TimeOrdering,
+    /// Seconds a transition may lag `last_event_at` and be clamped (see `time_guard`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub clock_skew_tolerance_seconds: u32,
+    /// How votes decide the outcome (from the type policy)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tally_policy: crate::proposal::type_registry::TallyPolicy,
+    /// What votes do when a tally would overflow (from the type policy)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tally_overflow: crate::proposal::type_registry::TallyOverflow,
+    /// A tally was clamped under `TallyOverflow::Clamp` and understates the votes
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tally_degraded: bool,
+    /// Governance voting record linked by `initialize_governance_voting_for`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub voting_id: Option<u64>,
+    /// Heavy fields were moved out by `compact`; lifecycle steps fail
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compacted: bool,
+    /// `canonical_hash` of the `CompactedData` moved out by `compact`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compacted_hash: Option<[u8; 32]>,
+    /// Voting power fixed at activation (see `attach_snapshot`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub power_snapshot: Option<crate::proposal::snapshot::PowerSnapshot<P>>,
+    /// Votes with a lower raw weight are refused (see `set_min_vote_weight`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub min_vote_weight: u64,
+    /// Raw weights are divided by this before tallying (see `normalize_weights`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub weight_divisor: Option<u64>,
+    /// Which transitions must or must not carry a reason (see `reason_policy`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reason_policy: crate::reason_policy::ReasonPolicy<ProposalStatus>,
+    /// Delay between passing and execution (from the type policy)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub execution_timelock: crate::duration::DurationSecs,
+    /// Sorted, lowercased tags (see `tags`)
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, serialize_with = "crate::tags::serialize_sorted")
+    )]
+    pub tags: Vec<String>,
+    /// How a tied vote is settled (from the type policy)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tie_break_policy: crate::proposal::type_registry::TieBreakPolicy,
+    /// The policy that settled a tied vote, if one did
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tie_break_applied: Option<crate::proposal::type_registry::TieBreakPolicy>,
 }
 #[cfg(test)]
 mod tests {
     #![allow(clippy::useless_vec)]
     use super::*;
-    use std::marker::PhantomData;
+    use crate::duration::DurationSecs;
+    use crate::proposal::type_registry::{TallyOverflow, TallyPolicy, TieBreakPolicy};
+    use crate::time_guard::TimeOrdering;
     fn create_test_pubkey(seed: u8) -> u8 {
         seed
     }
@@ -69,19 +181,46 @@ mod tests {
             submitted_at: None,
             cancelled_at: None,
             executed_at: None,
+            passed_at: None,
             archived_at: None,
-            voting_duration: 168,
+            voting_duration: DurationSecs::from_hours(168).unwrap(),
             status: ProposalStatus::Draft,
             yes_votes: 0,
             no_votes: 0,
             total_votes: 0,
+            votes: Vec::new(),
+            vote_nonces: Vec::new(),
             last_tallied_at: None,
             cancellation_reason: None,
+            cancellation_code: None,
+            superseded_by: None,
+            depends_on: Vec::new(),
+            sponsors: Vec::new(),
             execution_data: None,
             expires_at: None,
             idea_id: None,
             treasury_operation: None,
-            _phantom: PhantomData,
+            execution_ticket: None,
+            execution_attempts: 0,
+            execution_abort_reason: None,
+            execution_history: Vec::new(),
+            last_event_at: 1000,
+            time_ordering: TimeOrdering::AllowEqual,
+            clock_skew_tolerance_seconds: 0,
+            tally_policy: TallyPolicy::SimpleMajority,
+            tally_overflow: TallyOverflow::Reject,
+            tally_degraded: false,
+            voting_id: None,
+            compacted: false,
+            compacted_hash: None,
+            power_snapshot: None,
+            min_vote_weight: 0,
+            weight_divisor: None,
+            reason_policy: crate::reason_policy::ReasonPolicy::default(),
+            execution_timelock: DurationSecs::ZERO,
+            tags: Vec::new(),
+            tie_break_policy: TieBreakPolicy::Manual,
+            tie_break_applied: None,
         }
     }
     #[test]
@@ -90,11 +229,13 @@ mod tests {
             ProposalStatus::Draft,
             ProposalStatus::Active,
             ProposalStatus::Passed,
+            ProposalStatus::Executing,
             ProposalStatus::Rejected,
             ProposalStatus::Executed,
             ProposalStatus::Cancelled,
             ProposalStatus::Archived,
             ProposalStatus::Tied,
+            ProposalStatus::QuorumFailed,
         ];
 
         // Check all variants are unique
@@ -119,11 +260,13 @@ mod tests {
             ProposalStatus::Draft,
             ProposalStatus::Active,
             ProposalStatus::Passed,
+            ProposalStatus::Executing,
             ProposalStatus::Rejected,
             ProposalStatus::Executed,
             ProposalStatus::Cancelled,
             ProposalStatus::Archived,
             ProposalStatus::Tied,
+            ProposalStatus::QuorumFailed,
         ];
 
         for status in &statuses {
@@ -209,11 +352,11 @@ mod tests {
     fn test_proposal_voting_duration() {
         let mut proposal = create_test_proposal();
 
-        proposal.voting_duration = 24; // 1 day
-        assert_eq!(proposal.voting_duration, 24);
+        proposal.voting_duration = DurationSecs::from_days(1).unwrap();
+        assert_eq!(proposal.voting_duration.as_secs(), 86_400);
 
-        proposal.voting_duration = 720; // 30 days
-        assert_eq!(proposal.voting_duration, 720);
+        proposal.voting_duration = DurationSecs::from_days(30).unwrap();
+        assert_eq!(proposal.voting_duration.as_secs(), 2_592_000);
     }
     #[test]
     fn test_proposal_id_boundary() {
@@ -238,11 +381,13 @@ mod tests {
             ProposalStatus::Draft,
             ProposalStatus::Active,
             ProposalStatus::Passed,
+            ProposalStatus::Executing,
             ProposalStatus::Rejected,
             ProposalStatus::Executed,
             ProposalStatus::Cancelled,
             ProposalStatus::Archived,
             ProposalStatus::Tied,
+            ProposalStatus::QuorumFailed,
         ];
 
         // Test equality
@@ -280,19 +425,46 @@ mod tests {
             submitted_at: Some(3000),
             cancelled_at: Some(4000),
             executed_at: Some(5000),
+            passed_at: Some(4500),
             archived_at: Some(6000),
-            voting_duration: 168,
+            voting_duration: DurationSecs::from_hours(168).unwrap(),
             status: ProposalStatus::Active,
             yes_votes: 100,
             no_votes: 50,
             total_votes: 150,
+            votes: Vec::new(),
+            vote_nonces: Vec::new(),
             last_tallied_at: Some(7000),
             cancellation_reason: Some("Reason".to_string()),
+            cancellation_code: None,
+            superseded_by: None,
+            depends_on: Vec::new(),
+            sponsors: Vec::new(),
             execution_data: Some("Data".to_string()),
             expires_at: None,
             idea_id: None,
             treasury_operation: None,
-            _phantom: PhantomData,
+            execution_ticket: None,
+            execution_attempts: 0,
+            execution_abort_reason: None,
+            execution_history: Vec::new(),
+            last_event_at: 1000,
+            time_ordering: TimeOrdering::AllowEqual,
+            clock_skew_tolerance_seconds: 0,
+            tally_policy: TallyPolicy::SimpleMajority,
+            tally_overflow: TallyOverflow::Reject,
+            tally_degraded: false,
+            voting_id: None,
+            compacted: false,
+            compacted_hash: None,
+            power_snapshot: None,
+            min_vote_weight: 0,
+            weight_divisor: None,
+            reason_policy: crate::reason_policy::ReasonPolicy::default(),
+            execution_timelock: DurationSecs::ZERO,
+            tags: Vec::new(),
+            tie_break_policy: TieBreakPolicy::Manual,
+            tie_break_applied: None,
         };
 
         assert_eq!(proposal.id, 123);
@@ -306,7 +478,10 @@ mod tests {
         assert_eq!(proposal.cancelled_at, Some(4000));
         assert_eq!(proposal.executed_at, Some(5000));
         assert_eq!(proposal.archived_at, Some(6000));
-        assert_eq!(proposal.voting_duration, 168);
+        assert_eq!(
+            proposal.voting_duration,
+            DurationSecs::from_days(7).unwrap()
+        );
         assert_eq!(proposal.status, ProposalStatus::Active);
         assert_eq!(proposal.yes_votes, 100);
         assert_eq!(proposal.no_votes, 50);
@@ -328,19 +503,46 @@ mod tests {
             submitted_at: None,
             cancelled_at: None,
             executed_at: None,
+            passed_at: None,
             archived_at: None,
-            voting_duration: 168,
+            voting_duration: DurationSecs::from_hours(168).unwrap(),
             status: ProposalStatus::Draft,
             yes_votes: 0,
             no_votes: 0,
             total_votes: 0,
+            votes: Vec::new(),
+            vote_nonces: Vec::new(),
             last_tallied_at: None,
             cancellation_reason: None,
+            cancellation_code: None,
+            superseded_by: None,
+            depends_on: Vec::new(),
+            sponsors: Vec::new(),
             execution_data: None,
             expires_at: None,
             idea_id: None,
             treasury_operation: None,
-            _phantom: PhantomData,
+            execution_ticket: None,
+            execution_attempts: 0,
+            execution_abort_reason: None,
+            execution_history: Vec::new(),
+            last_event_at: 1000,
+            time_ordering: TimeOrdering::AllowEqual,
+            clock_skew_tolerance_seconds: 0,
+            tally_policy: TallyPolicy::SimpleMajority,
+            tally_overflow: TallyOverflow::Reject,
+            tally_degraded: false,
+            voting_id: None,
+            compacted: false,
+            compacted_hash: None,
+            power_snapshot: None,
+            min_vote_weight: 0,
+            weight_divisor: None,
+            reason_policy: crate::reason_policy::ReasonPolicy::default(),
+            execution_timelock: DurationSecs::ZERO,
+            tags: Vec::new(),
+            tie_break_policy: TieBreakPolicy::Manual,
+            tie_break_applied: None,
         };
 
         assert_eq!(proposal.updated_at, None);
@@ -382,11 +584,13 @@ mod tests {
             ProposalStatus::Draft,
             ProposalStatus::Active,
             ProposalStatus::Passed,
+            ProposalStatus::Executing,
             ProposalStatus::Rejected,
             ProposalStatus::Executed,
             ProposalStatus::Cancelled,
             ProposalStatus::Archived,
             ProposalStatus::Tied,
+            ProposalStatus::QuorumFailed,
         ];
 
         for i in 0..statuses.len() {
@@ -409,19 +613,46 @@ mod tests {
             submitted_at: Some(7000),
             cancelled_at: None,
             executed_at: Some(8000),
+            passed_at: Some(7500),
             archived_at: Some(9000),
-            voting_duration: 720,
+            voting_duration: DurationSecs::from_hours(720).unwrap(),
             status: ProposalStatus::Executed,
             yes_votes: 200,
             no_votes: 100,
             total_votes: 300,
+            votes: Vec::new(),
+            vote_nonces: Vec::new(),
             last_tallied_at: Some(8500),
             cancellation_reason: None,
+            cancellation_code: None,
+            superseded_by: None,
+            depends_on: Vec::new(),
+            sponsors: Vec::new(),
             execution_data: Some(r#"{"type": "test"}"#.to_string()),
             expires_at: None,
             idea_id: None,
             treasury_operation: None,
-            _phantom: PhantomData,
+            execution_ticket: None,
+            execution_attempts: 0,
+            execution_abort_reason: None,
+            execution_history: Vec::new(),
+            last_event_at: 5000,
+            time_ordering: TimeOrdering::AllowEqual,
+            clock_skew_tolerance_seconds: 0,
+            tally_policy: TallyPolicy::SimpleMajority,
+            tally_overflow: TallyOverflow::Reject,
+            tally_degraded: false,
+            voting_id: None,
+            compacted: false,
+            compacted_hash: None,
+            power_snapshot: None,
+            min_vote_weight: 0,
+            weight_divisor: None,
+            reason_policy: crate::reason_policy::ReasonPolicy::default(),
+            execution_timelock: DurationSecs::ZERO,
+            tags: Vec::new(),
+            tie_break_policy: TieBreakPolicy::Manual,
+            tie_break_applied: None,
         };
 
         assert_eq!(proposal.id, 999);
@@ -434,7 +665,10 @@ mod tests {
         assert_eq!(proposal.submitted_at, Some(7000));
         assert_eq!(proposal.executed_at, Some(8000));
         assert_eq!(proposal.archived_at, Some(9000));
-        assert_eq!(proposal.voting_duration, 720);
+        assert_eq!(
+            proposal.voting_duration,
+            DurationSecs::from_days(30).unwrap()
+        );
         assert_eq!(proposal.status, ProposalStatus::Executed);
         assert_eq!(proposal.yes_votes, 200);
         assert_eq!(proposal.no_votes, 100);
@@ -445,4 +679,105 @@ mod tests {
             Some(r#"{"type": "test"}"#.to_string())
         );
     }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_durations_serialize_as_plain_seconds() {
+        let mut proposal = create_test_proposal();
+        proposal.execution_timelock = DurationSecs::from_secs(50);
+        let value = serde_json::to_value(&proposal).unwrap();
+        assert_eq!(value["voting_duration"], serde_json::json!(604_800));
+        assert_eq!(value["execution_timelock"], serde_json::json!(50));
+        let decoded: Proposal<u8> = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded, proposal);
+    }
+    #[test]
+    #[allow(deprecated)]
+    fn test_raw_duration_accessors() {
+        let mut proposal = create_test_proposal();
+        proposal.execution_timelock = DurationSecs::from_secs(50);
+        assert_eq!(proposal.voting_duration_secs(), 604_800);
+        assert_eq!(proposal.execution_timelock_secs(), 50);
+    }
+    #[test]
+    fn test_status_display_round_trips() {
+        for status in ProposalStatus::VARIANTS {
+            assert_eq!(
+                status.to_string().parse::<ProposalStatus>(),
+                Ok(status.clone())
+            );
+        }
+        assert_eq!(ProposalStatus::QuorumFailed.to_string(), "QuorumFailed");
+        assert_eq!(
+            "quorum_failed".parse::<ProposalStatus>(),
+            Err(FsmError::InvalidInput)
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_status_serde_names_are_stable() {
+        // Persisted names; existing records never contain QuorumFailed
+        for (status, name) in [
+            (ProposalStatus::Rejected, "\"Rejected\""),
+            (ProposalStatus::Tied, "\"Tied\""),
+            (ProposalStatus::QuorumFailed, "\"QuorumFailed\""),
+        ] {
+            assert_eq!(serde_json::to_string(&status).unwrap(), name);
+            assert_eq!(
+                serde_json::from_str::<ProposalStatus>(name).unwrap(),
+                status
+            );
+        }
+        let mut proposal = create_test_proposal();
+        proposal.status = ProposalStatus::Rejected;
+        let json = serde_json::to_string(&proposal).unwrap();
+        let restored: Proposal<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.status, ProposalStatus::Rejected);
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserializes_baseline_proposal() {
+        // Field set of proposals serialized before votes and lifecycle policies existed
+        let json = r#"{
+            "id": 1, "title": "T", "description": "D", "proposal_type": "governance",
+            "author": 1, "created_at": 1000, "updated_at": null, "submitted_at": null,
+            "cancelled_at": null, "executed_at": null, "archived_at": null,
+            "voting_duration": 604800, "status": "Draft", "yes_votes": 0, "no_votes": 0,
+            "total_votes": 0, "last_tallied_at": null, "cancellation_reason": null,
+            "execution_data": null, "expires_at": null, "idea_id": null,
+            "treasury_operation": null
+        }"#;
+        let proposal: Proposal<u8> = serde_json::from_str(json).unwrap();
+        assert_eq!(proposal.voting_duration, DurationSecs::from_secs(604800));
+        assert!(proposal.votes.is_empty() && proposal.depends_on.is_empty());
+        assert_eq!(proposal.execution_attempts, 0);
+        assert_eq!(proposal.last_event_at, 0);
+        assert_eq!(proposal.time_ordering, TimeOrdering::default());
+        assert_eq!(proposal.tally_policy, TallyPolicy::SimpleMajority);
+        assert_eq!(proposal.execution_timelock, DurationSecs::default());
+    }
+    #[test]
+    fn test_status_indices_predate_executing() {
+        use crate::reason_policy::ReasonTransition;
+        use ProposalStatus::*;
+        // Variant indices as encoded before Executing existed
+        let old = [
+            Draft,
+            Active,
+            Passed,
+            Rejected,
+            Executed,
+            Cancelled,
+            Archived,
+            Tied,
+            QuorumFailed,
+        ];
+        assert_eq!(&ProposalStatus::VARIANTS[..old.len()], &old);
+        assert_eq!(ProposalStatus::VARIANTS.last(), Some(&Executing));
+        let encoded = ReasonTransition {
+            entity: crate::enums::EntityKind::Proposal,
+            from: 1,
+            to: 5,
+        };
+        assert_eq!(encoded.to_string(), "Proposal Active -> Cancelled");
+    }
 }
@@ -0,0 +1,526 @@
+//! Recorded votes and participation
+//!
+//! `cast_vote` stores one `VoteRecord` per voter and keeps the tallies in
+//! sync. Participation helpers compare the recorded voters with an eligible
+//! member list; they need `P: Ord` to index voters, and results follow the
+//! order of the eligible list.
+//!
+//! The author gets no special treatment: if listed as eligible they are
+//! expected to vote like anyone else. Voters missing from the eligible list
+//! are reported by `vote_anomalies` and never counted as eligible turnout.
+//!
+//! Votes relayed as signed off-chain messages carry a per-voter nonce
+//! (`cast_vote_with_nonce`, `change_vote`). The highest accepted nonce of each
+//! voter is kept in `Proposal::vote_nonces` and serialized with the proposal,
+//! so a replayed message is refused with `ReplayDetected` even after a restore.
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+use crate::math::ArithmeticPolicy;
+use crate::metrics;
+use crate::projections::{RATE_SCALE, rate_bps};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One recorded vote
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VoteRecord<P> {
+    pub voter: P,
+    pub support: bool,
+    pub weight: u64,
+    pub cast_at: i64,
+}
+/// Highest nonce accepted from one voter
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VoterNonce<P> {
+    pub voter: P,
+    pub last_nonce: u64,
+}
+impl<P: Ord> Proposal<P> {
+    /// Record a vote on an Active proposal and add its weight to the tallies
+    ///
+    /// With a `PowerSnapshot` attached, `weight` must be the voter's snapshot
+    /// power (see `cast_weighted_vote`). `weight` is the raw weight; the
+    /// `min_vote_weight` floor and `weight_divisor` apply before it is tallied.
+    ///
+    /// Errors: `InvalidInput` if `weight` is zero, `InvalidState` if not
+    /// Active, `voter` already voted or voting has ended, `NotInSnapshot` or
+    /// `InvalidInput` for a vote the snapshot does not back, `BelowMinimumWeight`
+    /// for a dust vote (see `set_min_vote_weight`), `TimeRegression` if
+    /// `current_time` breaks `time_ordering`, `Overflow` if a tally would pass
+    /// `u64::MAX` under `TallyOverflow::Reject`.
+    pub fn cast_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let result = self.try_cast_vote(voter, support, weight, current_time);
+        metrics::record_vote("proposal", "cast", &result);
+        result
+    }
+    /// `cast_vote` without the metrics report, for methods that report themselves
+    pub(crate) fn try_cast_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        if weight == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.check_voting_open(current_time)?;
+        if self.has_voted(&voter) {
+            return Err(FsmError::InvalidState);
+        }
+        self.check_snapshot_weight(&voter, weight)?;
+        let weight = self.weigh(weight)?;
+        self.add_vote(voter, support, weight, current_time)
+    }
+    /// `cast_vote` for a signed message carrying the voter's nonce
+    ///
+    /// With `Some(nonce)`, `ReplayDetected` unless `nonce` is above the last
+    /// nonce accepted from `voter`; it is recorded once the vote is. `None`
+    /// behaves exactly like `cast_vote`.
+    pub fn cast_vote_with_nonce(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        nonce: Option<u64>,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: Clone,
+    {
+        let result = self.try_cast_vote_with_nonce(voter, support, weight, nonce, current_time);
+        metrics::record_vote("proposal", "cast", &result);
+        result
+    }
+    fn try_cast_vote_with_nonce(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        nonce: Option<u64>,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: Clone,
+    {
+        let Some(nonce) = nonce else {
+            return self.try_cast_vote(voter, support, weight, current_time);
+        };
+        if weight == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.check_voting_open(current_time)?;
+        self.check_nonce(&voter, nonce)?;
+        if self.has_voted(&voter) {
+            return Err(FsmError::InvalidState);
+        }
+        self.check_snapshot_weight(&voter, weight)?;
+        let weight = self.weigh(weight)?;
+        self.add_vote(voter.clone(), support, weight, current_time)?;
+        self.record_nonce(voter, nonce);
+        Ok(())
+    }
+    /// Move `voter`'s recorded vote to `support`, keeping its weight
+    ///
+    /// `nonce` must be above the last nonce accepted from `voter`
+    /// (`ReplayDetected` otherwise). `InvalidState` if `voter` has not voted,
+    /// plus the status, timing and overflow errors of `cast_vote`.
+    pub fn change_vote(
+        &mut self,
+        voter: &P,
+        support: bool,
+        nonce: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: Clone,
+    {
+        let result = self.try_change_vote(voter, support, nonce, current_time);
+        metrics::record_vote("proposal", "change", &result);
+        result
+    }
+    fn try_change_vote(
+        &mut self,
+        voter: &P,
+        support: bool,
+        nonce: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError>
+    where
+        P: Clone,
+    {
+        self.check_voting_open(current_time)?;
+        self.check_nonce(voter, nonce)?;
+        let index = self
+            .votes
+            .iter()
+            .position(|vote| &vote.voter == voter)
+            .ok_or(FsmError::InvalidState)?;
+        let (previous, weight) = (self.votes[index].support, self.votes[index].weight);
+        if previous != support {
+            let (from, to) = if support {
+                (self.no_votes, self.yes_votes)
+            } else {
+                (self.yes_votes, self.no_votes)
+            };
+            let from = from.checked_sub(weight).ok_or(FsmError::Overflow)?;
+            let to = ArithmeticPolicy::from(self.tally_overflow).add(to, weight)?;
+            if support {
+                (self.no_votes, self.yes_votes) = (from, to.value);
+            } else {
+                (self.yes_votes, self.no_votes) = (from, to.value);
+            }
+            self.tally_degraded |= to.degraded;
+        }
+        let vote = &mut self.votes[index];
+        vote.support = support;
+        vote.cast_at = current_time;
+        self.last_tallied_at = Some(current_time);
+        self.last_event_at = self.last_event_at.max(current_time);
+        self.record_nonce(voter.clone(), nonce);
+        Ok(())
+    }
+    /// Last nonce accepted from `voter`, if any
+    pub fn last_nonce(&self, voter: &P) -> Option<u64> {
+        self.vote_nonces
+            .iter()
+            .find(|entry| &entry.voter == voter)
+            .map(|entry| entry.last_nonce)
+    }
+    fn check_nonce(&self, voter: &P, nonce: u64) -> Result<(), FsmError> {
+        match self.last_nonce(voter) {
+            Some(last) if nonce <= last => Err(FsmError::ReplayDetected),
+            _ => Ok(()),
+        }
+    }
+    fn record_nonce(&mut self, voter: P, nonce: u64) {
+        match self
+            .vote_nonces
+            .iter_mut()
+            .find(|entry| entry.voter == voter)
+        {
+            Some(entry) => entry.last_nonce = nonce,
+            None => self.vote_nonces.push(VoterNonce {
+                voter,
+                last_nonce: nonce,
+            }),
+        }
+    }
+    /// `InvalidState` unless Active, then the time-ordering and voting-end checks
+    fn check_voting_open(&self, current_time: i64) -> Result<(), FsmError> {
+        if self.status != ProposalStatus::Active {
+            return Err(FsmError::InvalidState);
+        }
+        self.time_ordering.check(self.last_event_at, current_time)?;
+        if current_time >= self.voting_ends_at()? {
+            return Err(FsmError::InvalidState);
+        }
+        Ok(())
+    }
+    pub(super) fn add_vote(
+        &mut self,
+        voter: P,
+        support: bool,
+        weight: u64,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        let tally = if support {
+            self.yes_votes
+        } else {
+            self.no_votes
+        };
+        let policy = ArithmeticPolicy::from(self.tally_overflow);
+        let tally = policy.add(tally, weight)?;
+        let total_votes = policy.add(self.total_votes, weight)?;
+        if support {
+            self.yes_votes = tally.value;
+        } else {
+            self.no_votes = tally.value;
+        }
+        self.total_votes = total_votes.value;
+        self.tally_degraded |= tally.degraded || total_votes.degraded;
+        self.last_tallied_at = Some(current_time);
+        self.last_event_at = self.last_event_at.max(current_time);
+        self.votes.push(VoteRecord {
+            voter,
+            support,
+            weight,
+            cast_at: current_time,
+        });
+        Ok(())
+    }
+    /// Whether `voter` has a recorded vote
+    pub fn has_voted(&self, voter: &P) -> bool {
+        self.votes.iter().any(|vote| &vote.voter == voter)
+    }
+    fn voter_set(&self) -> BTreeSet<&P> {
+        self.votes.iter().map(|vote| &vote.voter).collect()
+    }
+    /// Eligible members without a recorded vote, in eligible-list order without duplicates
+    pub fn non_voters<'a>(&self, eligible: &'a [P]) -> Vec<&'a P> {
+        let voters = self.voter_set();
+        let mut seen = BTreeSet::new();
+        eligible
+            .iter()
+            .filter(|member| !voters.contains(member) && seen.insert(*member))
+            .collect()
+    }
+    /// Recorded voters that are not in `eligible`, in casting order
+    pub fn vote_anomalies(&self, eligible: &[P]) -> Vec<&P> {
+        let eligible: BTreeSet<&P> = eligible.iter().collect();
+        self.votes
+            .iter()
+            .map(|vote| &vote.voter)
+            .filter(|voter| !eligible.contains(voter))
+            .collect()
+    }
+    /// Eligible members who voted, in basis points of `eligible`; None if `eligible` is empty
+    ///
+    /// Anomalous voters are excluded.
+    pub fn eligible_participation_bps(&self, eligible: &[P]) -> Option<u64> {
+        let eligible: BTreeSet<&P> = eligible.iter().collect();
+        let voted = self
+            .voter_set()
+            .into_iter()
+            .filter(|voter| eligible.contains(voter))
+            .count();
+        rate_bps(voted as u64, eligible.len() as u64)
+    }
+}
+impl<P> Proposal<P> {
+    /// Recorded voters per `eligible_count`, in basis points capped at 100%
+    ///
+    /// Only the count is known here, so voters outside the eligible set are
+    /// counted; use `eligible_participation_bps` to exclude them.
+    pub fn participation_rate(&self, eligible_count: u64) -> Option<u64> {
+        rate_bps(self.votes.len() as u64, eligible_count).map(|bps| bps.min(RATE_SCALE))
+    }
+}
+/// Non-voters of every Active proposal, as `(proposal_id, non_voters)` in input order
+pub fn outstanding_votes<'a, P: Ord>(
+    proposals: &[Proposal<P>],
+    eligible: &'a [P],
+) -> Vec<(u64, Vec<&'a P>)> {
+    proposals
+        .iter()
+        .filter(|proposal| proposal.status == ProposalStatus::Active)
+        .map(|proposal| (proposal.id, proposal.non_voters(eligible)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::proposal::TallyOverflow;
+    fn create_active_proposal(id: u64, author: u8) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            author,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 0).unwrap();
+        proposal
+    }
+    #[test]
+    fn test_cast_vote_overflow_rejected() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal.yes_votes = u64::MAX - 1;
+        proposal.total_votes = u64::MAX - 1;
+        assert_eq!(proposal.cast_vote(2, true, 5, 10), Err(FsmError::Overflow));
+        assert_eq!(
+            (proposal.yes_votes, proposal.total_votes),
+            (u64::MAX - 1, u64::MAX - 1)
+        );
+        assert!(!proposal.has_voted(&2));
+        assert_eq!(proposal.last_tallied_at, None);
+        // The other side still has room, but the total does not
+        assert_eq!(proposal.cast_vote(2, false, 5, 10), Err(FsmError::Overflow));
+        assert_eq!(proposal.no_votes, 0);
+        assert!(!proposal.tally_degraded);
+    }
+    #[test]
+    fn test_cast_vote_overflow_clamped() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal.tally_overflow = TallyOverflow::Clamp;
+        proposal.yes_votes = u64::MAX - 2;
+        proposal.total_votes = u64::MAX - 2;
+        // Landing exactly on the bound is not degraded
+        proposal.cast_vote(4, true, 1, 10).unwrap();
+        assert!(!proposal.tally_degraded);
+        proposal.cast_vote(2, true, 5, 10).unwrap();
+        assert_eq!(
+            (proposal.yes_votes, proposal.total_votes),
+            (u64::MAX, u64::MAX)
+        );
+        assert!(proposal.tally_degraded);
+        assert!(proposal.has_voted(&2));
+        proposal.cast_vote(3, false, 5, 10).unwrap();
+        assert_eq!((proposal.no_votes, proposal.total_votes), (5, u64::MAX));
+    }
+    #[test]
+    fn test_cast_vote_updates_tallies() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal.cast_vote(2, true, 3, 10).unwrap();
+        proposal.cast_vote(3, false, 1, 20).unwrap();
+        assert_eq!(
+            (proposal.yes_votes, proposal.no_votes, proposal.total_votes),
+            (3, 1, 4)
+        );
+        assert_eq!(proposal.last_tallied_at, Some(20));
+        assert!(proposal.has_voted(&2));
+        assert_eq!(
+            proposal.cast_vote(2, false, 1, 30),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            proposal.cast_vote(4, true, 0, 30),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            proposal.cast_vote(4, true, 1, 100),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            proposal.cast_vote(4, true, 1, 15),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(proposal.votes.len(), 2);
+    }
+    #[test]
+    fn test_cast_vote_requires_active() {
+        let mut proposal = Proposal::<u8>::new(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(
+            proposal.cast_vote(2, true, 1, 0),
+            Err(FsmError::InvalidState)
+        );
+    }
+    #[test]
+    fn test_replayed_vote_rejected() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal
+            .cast_vote_with_nonce(2, true, 3, Some(7), 10)
+            .unwrap();
+        assert_eq!(
+            proposal.cast_vote_with_nonce(2, true, 3, Some(7), 11),
+            Err(FsmError::ReplayDetected)
+        );
+        assert_eq!((proposal.yes_votes, proposal.total_votes), (3, 3));
+        assert_eq!(proposal.votes.len(), 1);
+        assert_eq!(proposal.last_nonce(&2), Some(7));
+        // A fresh nonce still cannot vote twice
+        assert_eq!(
+            proposal.cast_vote_with_nonce(2, true, 3, Some(8), 11),
+            Err(FsmError::InvalidState)
+        );
+        // Without a nonce the call is plain `cast_vote`
+        proposal
+            .cast_vote_with_nonce(3, false, 1, None, 12)
+            .unwrap();
+        assert_eq!(proposal.last_nonce(&3), None);
+    }
+    #[test]
+    fn test_change_vote_requires_fresh_nonce() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal
+            .cast_vote_with_nonce(2, true, 3, Some(5), 10)
+            .unwrap();
+        for stale in [4, 5] {
+            assert_eq!(
+                proposal.change_vote(&2, false, stale, 20),
+                Err(FsmError::ReplayDetected)
+            );
+        }
+        assert_eq!((proposal.yes_votes, proposal.no_votes), (3, 0));
+        proposal.change_vote(&2, false, 6, 20).unwrap();
+        assert_eq!(
+            (proposal.yes_votes, proposal.no_votes, proposal.total_votes),
+            (0, 3, 3)
+        );
+        assert!(!proposal.votes[0].support);
+        assert_eq!(proposal.votes[0].cast_at, 20);
+        assert_eq!(proposal.last_nonce(&2), Some(6));
+        assert_eq!(
+            proposal.change_vote(&2, true, 6, 30),
+            Err(FsmError::ReplayDetected)
+        );
+        assert_eq!(
+            proposal.change_vote(&9, true, 1, 30),
+            Err(FsmError::InvalidState)
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_nonces_survive_snapshot_restore() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal
+            .cast_vote_with_nonce(2, true, 1, Some(3), 10)
+            .unwrap();
+        let snapshot = serde_json::to_string(&proposal).unwrap();
+        let mut restored: Proposal<u8> = serde_json::from_str(&snapshot).unwrap();
+        assert_eq!(restored, proposal);
+        assert_eq!(
+            restored.change_vote(&2, false, 3, 20),
+            Err(FsmError::ReplayDetected)
+        );
+        // Snapshots taken before nonces existed restore with none recorded
+        let mut legacy = serde_json::to_value(&proposal).unwrap();
+        legacy.as_object_mut().unwrap().remove("vote_nonces");
+        let legacy: Proposal<u8> = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.vote_nonces.is_empty());
+    }
+    #[test]
+    fn test_non_voters_and_anomalies() {
+        let mut proposal = create_active_proposal(1, 1);
+        proposal.cast_vote(2, true, 1, 10).unwrap();
+        proposal.cast_vote(9, true, 1, 10).unwrap();
+        // Author 1 is eligible and has not voted; 3 is listed twice
+        let eligible = [1, 2, 3, 3, 4];
+        assert_eq!(proposal.non_voters(&eligible), vec![&1, &3, &4]);
+        assert_eq!(proposal.vote_anomalies(&eligible), vec![&9]);
+        assert_eq!(proposal.eligible_participation_bps(&eligible), Some(2500));
+        assert_eq!(proposal.participation_rate(4), Some(5000));
+        assert_eq!(proposal.participation_rate(1), Some(RATE_SCALE));
+        assert_eq!(proposal.participation_rate(0), None);
+    }
+    #[test]
+    fn test_outstanding_votes_skips_inactive() {
+        let mut voted = create_active_proposal(1, 1);
+        voted.cast_vote(1, true, 1, 10).unwrap();
+        let untouched = create_active_proposal(2, 1);
+        let draft = Proposal::<u8>::new(
+            3,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .unwrap();
+        let eligible = [1, 2];
+        assert_eq!(
+            outstanding_votes(&[voted, draft, untouched], &eligible),
+            vec![(1, vec![&2]), (2, vec![&1, &2])]
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Vote weight policy: dust floor and normalization
+//!
+//! Token-weighted votes can arrive in thousands of tiny weights that never
+//! change an outcome. `set_min_vote_weight` refuses votes whose raw weight is
+//! below a floor with `BelowMinimumWeight`, and `normalize_weights` records a
+//! divisor applied to every raw weight before it is stored and tallied. Both
+//! are fixed once the first vote is recorded and serialized with the proposal,
+//! so `raw_weight` can reconstruct the cast weights (rounded down to a multiple
+//! of the divisor) for receipts and audits.
+use super::types::{Proposal, ProposalStatus};
+use crate::error::FsmError;
+
+impl<P> Proposal<P> {
+    /// Refuse votes whose raw weight is below `min`; 0 removes the floor
+    ///
+    /// `InvalidState` once a vote is recorded or if not Draft or Active.
+    pub fn set_min_vote_weight(&mut self, min: u64) -> Result<(), FsmError> {
+        self.check_weight_policy_open()?;
+        self.min_vote_weight = min;
+        Ok(())
+    }
+    /// Divide every raw vote weight by `divisor` before it is tallied
+    ///
+    /// `InvalidInput` for a zero divisor, `InvalidState` once a vote is recorded
+    /// or if not Draft or Active. Votes that divide to nothing are refused with
+    /// `BelowMinimumWeight`.
+    pub fn normalize_weights(&mut self, divisor: u64) -> Result<(), FsmError> {
+        if divisor == 0 {
+            return Err(FsmError::InvalidInput);
+        }
+        self.check_weight_policy_open()?;
+        self.weight_divisor = Some(divisor);
+        Ok(())
+    }
+    /// Raw weight behind a stored weight, rounded down to a multiple of the divisor
+    pub fn raw_weight(&self, stored: u64) -> u64 {
+        stored.saturating_mul(self.weight_divisor.unwrap_or(1))
+    }
+    /// Stored weight of a raw weight under the recorded divisor
+    pub(crate) fn normalized_weight(&self, raw: u64) -> u64 {
+        raw / self.weight_divisor.unwrap_or(1)
+    }
+    /// Apply the floor and the divisor to a raw vote weight
+    ///
+    /// `BelowMinimumWeight` below `min_vote_weight` or if it divides to zero.
+    pub(crate) fn weigh(&self, raw: u64) -> Result<u64, FsmError> {
+        let weight = self.normalized_weight(raw);
+        if raw < self.min_vote_weight || weight == 0 {
+            return Err(FsmError::BelowMinimumWeight);
+        }
+        Ok(weight)
+    }
+    fn check_weight_policy_open(&self) -> Result<(), FsmError> {
+        if !self.votes.is_empty()
+            || !matches!(self.status, ProposalStatus::Draft | ProposalStatus::Active)
+        {
+            return Err(FsmError::InvalidState);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    fn create_active_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            1,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 10).unwrap();
+        proposal
+    }
+    #[test]
+    fn test_dust_vote_rejected() {
+        let mut proposal = create_active_proposal();
+        proposal.set_min_vote_weight(3).unwrap();
+        assert_eq!(
+            proposal.cast_vote(2, true, 2, 20),
+            Err(FsmError::BelowMinimumWeight)
+        );
+        assert_eq!(
+            proposal.cast_vote_with_nonce(2, true, 1, Some(1), 20),
+            Err(FsmError::BelowMinimumWeight)
+        );
+        assert_eq!(proposal.last_nonce(&2), None);
+        proposal.cast_vote(2, true, 3, 20).unwrap();
+        assert_eq!((proposal.yes_votes, proposal.votes.len()), (3, 1));
+        assert_eq!(proposal.set_min_vote_weight(0), Err(FsmError::InvalidState));
+        assert_eq!(proposal.normalize_weights(10), Err(FsmError::InvalidState));
+        assert_eq!(proposal.min_vote_weight, 3);
+    }
+    #[test]
+    fn test_normalized_tally_matches_raw_within_rounding() {
+        let raw = [
+            (2u8, true, 1_234_567u64),
+            (3, false, 987_654),
+            (4, true, 5_000_001),
+        ];
+        let divisor = 1_000;
+        let mut undivided = create_active_proposal();
+        let mut normalized = create_active_proposal();
+        assert_eq!(normalized.normalize_weights(0), Err(FsmError::InvalidInput));
+        normalized.normalize_weights(divisor).unwrap();
+        for (voter, support, weight) in raw {
+            undivided.cast_vote(voter, support, weight, 20).unwrap();
+            normalized.cast_vote(voter, support, weight, 20).unwrap();
+        }
+        assert_eq!(normalized.yes_votes, 6_234);
+        let max_loss = raw.len() as u64 * divisor;
+        for (raw_tally, stored) in [
+            (undivided.yes_votes, normalized.yes_votes),
+            (undivided.no_votes, normalized.no_votes),
+            (undivided.total_votes, normalized.total_votes),
+        ] {
+            let reconstructed = normalized.raw_weight(stored);
+            assert!(reconstructed <= raw_tally && raw_tally - reconstructed < max_loss);
+        }
+        assert_eq!(normalized.raw_weight(normalized.votes[1].weight), 987_000);
+        assert_eq!(
+            normalized.cast_vote(5, true, 999, 20),
+            Err(FsmError::BelowMinimumWeight)
+        );
+    }
+    #[test]
+    fn test_normalized_snapshot_quorum() {
+        let mut proposal = create_active_proposal();
+        proposal.normalize_weights(10).unwrap();
+        proposal
+            .attach_snapshot(
+                crate::proposal::snapshot::PowerSnapshot::new(vec![(2, 35), (3, 65)], 10).unwrap(),
+            )
+            .unwrap();
+        proposal.cast_weighted_vote(2, true, 20).unwrap();
+        assert_eq!(proposal.yes_votes, 3);
+        assert_eq!(proposal.quorum_counts(), Some((30, 100)));
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_weight_policy_serde_roundtrip() {
+        let mut proposal = create_active_proposal();
+        proposal.set_min_vote_weight(5).unwrap();
+        proposal.normalize_weights(100).unwrap();
+        let json = serde_json::to_string(&proposal).unwrap();
+        let restored: Proposal<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, proposal);
+        let mut legacy = serde_json::to_value(create_active_proposal()).unwrap();
+        let object = legacy.as_object_mut().unwrap();
+        object.remove("min_vote_weight");
+        object.remove("weight_divisor");
+        let restored: Proposal<u8> = serde_json::from_value(legacy).unwrap();
+        assert_eq!(
+            (restored.min_vote_weight, restored.weight_divisor),
+            (0, None)
+        );
+    }
+}
@@ -0,0 +1,676 @@
+//! Proposal and grant queries.
+//!
+//! A query is built fluently, then applied to a slice. Every filter that is
+//! set must match (AND); unset filters match everything. Results keep input
+//! order unless a sort key is set, in which case ties are broken by id
+//! ascending, so `offset`/`limit` pages are stable for the same input.
+//!
+//! For large collections, `VersionedRegistry::page` and `GrantQuery::page`
+//! walk the matches by id with a cursor instead: each `Page` ends with the
+//! id to resume after, so entries inserted between pages are seen once if
+//! their id is past the cursor and never repeat what was already returned.
+//! Cursor pages use the filters of the query only, not its sort or
+//! `offset`/`limit`.
+
+use crate::grant::{Grant, GrantCategory, GrantStatus, GrantType};
+use crate::proposal::{Proposal, ProposalStatus};
+
+/// Field to sort query results by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    CreatedAt,
+    /// Proposals: `total_votes`. Grants: approve + reject + abstain weight.
+    TotalVotes,
+    /// Proposals: treasury operation amount, 0 without one. Grants: `total_amount`.
+    Amount,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Sort and page settings shared by both queries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Paging {
+    sort: Option<(SortKey, SortOrder)>,
+    offset: usize,
+    limit: Option<usize>,
+}
+
+impl Paging {
+    fn apply<'a, T>(
+        &self,
+        mut matched: Vec<&'a T>,
+        id: impl Fn(&T) -> u64,
+        key: impl Fn(&T, SortKey) -> i128,
+    ) -> Vec<&'a T> {
+        if let Some((sort_key, order)) = self.sort {
+            matched.sort_by(|a, b| {
+                let by_key = key(a, sort_key).cmp(&key(b, sort_key));
+                let by_key = match order {
+                    SortOrder::Ascending => by_key,
+                    SortOrder::Descending => by_key.reverse(),
+                };
+                by_key.then_with(|| id(a).cmp(&id(b)))
+            });
+        }
+        matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+/// One cursor page of matches, ascending by id
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Id of the last item, to pass as `after_id` for the next page; `None` once no match is left
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Up to `limit` items (at least one) of `matches`, which must ascend by `id`
+    pub(crate) fn from_ascending(
+        matches: impl Iterator<Item = T>,
+        limit: usize,
+        id: impl Fn(&T) -> u64,
+    ) -> Self {
+        let mut matches = matches.peekable();
+        let items: Vec<T> = matches.by_ref().take(limit.max(1)).collect();
+        let next_cursor = match matches.peek() {
+            Some(_) => items.last().map(id),
+            None => None,
+        };
+        Self { items, next_cursor }
+    }
+}
+
+fn created_in(created_at: i64, range: Option<(i64, i64)>) -> bool {
+    range.is_none_or(|(from, to)| from <= created_at && created_at <= to)
+}
+
+/// Tags to filter by, normalized like stored tags
+fn tag_filter(tags: Vec<String>) -> Vec<String> {
+    tags.iter().map(|tag| tag.trim().to_lowercase()).collect()
+}
+
+fn tagged(tags: &[String], any: Option<&Vec<String>>, all: Option<&Vec<String>>) -> bool {
+    any.is_none_or(|any| any.iter().any(|tag| tags.contains(tag)))
+        && all.is_none_or(|all| all.iter().all(|tag| tags.contains(tag)))
+}
+
+/// Filter over proposals
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProposalQuery<P> {
+    statuses: Option<Vec<ProposalStatus>>,
+    author: Option<P>,
+    sponsored_by: Option<P>,
+    created_between: Option<(i64, i64)>,
+    proposal_type: Option<String>,
+    idea_linked: Option<bool>,
+    has_treasury_operation: Option<bool>,
+    /// Lowercased
+    title_contains: Option<String>,
+    tagged_any: Option<Vec<String>>,
+    tagged_all: Option<Vec<String>>,
+    page: Paging,
+}
+
+impl<P> Default for ProposalQuery<P> {
+    fn default() -> Self {
+        Self {
+            statuses: None,
+            author: None,
+            sponsored_by: None,
+            created_between: None,
+            proposal_type: None,
+            idea_linked: None,
+            has_treasury_operation: None,
+            title_contains: None,
+            tagged_any: None,
+            tagged_all: None,
+            page: Paging::default(),
+        }
+    }
+}
+
+impl<P: PartialEq> ProposalQuery<P> {
+    /// A query matching every proposal
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status is one of `statuses`
+    pub fn status_in(mut self, statuses: Vec<ProposalStatus>) -> Self {
+        self.statuses = Some(statuses);
+        self
+    }
+
+    pub fn author(mut self, author: P) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// `member` is the author or a co-sponsor
+    pub fn sponsored_by(mut self, member: P) -> Self {
+        self.sponsored_by = Some(member);
+        self
+    }
+
+    /// `from <= created_at <= to`; matches nothing if `from > to`
+    pub fn created_between(mut self, from: i64, to: i64) -> Self {
+        self.created_between = Some((from, to));
+        self
+    }
+
+    pub fn proposal_type(mut self, proposal_type: String) -> Self {
+        self.proposal_type = Some(proposal_type);
+        self
+    }
+
+    /// Whether the proposal has an `idea_id`
+    pub fn idea_linked(mut self, linked: bool) -> Self {
+        self.idea_linked = Some(linked);
+        self
+    }
+
+    /// Whether the proposal carries treasury operation data
+    pub fn has_treasury_operation(mut self, has: bool) -> Self {
+        self.has_treasury_operation = Some(has);
+        self
+    }
+
+    /// Title contains `text`, ignoring case
+    pub fn text_contains(mut self, text: &str) -> Self {
+        self.title_contains = Some(text.to_lowercase());
+        self
+    }
+
+    /// Has at least one of `tags`; an empty list matches nothing
+    pub fn tagged_any(mut self, tags: Vec<String>) -> Self {
+        self.tagged_any = Some(tag_filter(tags));
+        self
+    }
+
+    /// Has every one of `tags`
+    pub fn tagged_all(mut self, tags: Vec<String>) -> Self {
+        self.tagged_all = Some(tag_filter(tags));
+        self
+    }
+
+    pub fn sort_by(mut self, key: SortKey, order: SortOrder) -> Self {
+        self.page.sort = Some((key, order));
+        self
+    }
+
+    /// Skip the first `offset` results
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.page.offset = offset;
+        self
+    }
+
+    /// Return at most `limit` results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.page.limit = Some(limit);
+        self
+    }
+
+    /// Whether `proposal` passes every filter
+    pub fn matches(&self, proposal: &Proposal<P>) -> bool {
+        self.statuses
+            .as_ref()
+            .is_none_or(|s| s.contains(&proposal.status))
+            && self.author.as_ref().is_none_or(|a| *a == proposal.author)
+            && self
+                .sponsored_by
+                .as_ref()
+                .is_none_or(|member| proposal.is_sponsor(member))
+            && created_in(proposal.created_at, self.created_between)
+            && self
+                .proposal_type
+                .as_ref()
+                .is_none_or(|t| *t == proposal.proposal_type)
+            && self
+                .idea_linked
+                .is_none_or(|linked| proposal.idea_id.is_some() == linked)
+            && self
+                .has_treasury_operation
+                .is_none_or(|has| proposal.treasury_operation.is_some() == has)
+            && self
+                .title_contains
+                .as_ref()
+                .is_none_or(|text| proposal.title.to_lowercase().contains(text.as_str()))
+            && tagged(
+                &proposal.tags,
+                self.tagged_any.as_ref(),
+                self.tagged_all.as_ref(),
+            )
+    }
+
+    /// Matching proposals, sorted and paged
+    pub fn apply<'a>(&self, proposals: &'a [Proposal<P>]) -> Vec<&'a Proposal<P>> {
+        let matched = proposals.iter().filter(|p| self.matches(p)).collect();
+        self.page.apply(
+            matched,
+            |p| p.id,
+            |p, key| match key {
+                SortKey::CreatedAt => i128::from(p.created_at),
+                SortKey::TotalVotes => i128::from(p.total_votes),
+                SortKey::Amount => i128::from(
+                    p.treasury_operation
+                        .as_ref()
+                        .and_then(|op| op.amount)
+                        .unwrap_or(0),
+                ),
+            },
+        )
+    }
+}
+
+/// Filter over grants
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GrantQuery {
+    statuses: Option<Vec<GrantStatus>>,
+    mesh_group_id: Option<[u8; 32]>,
+    created_between: Option<(i64, i64)>,
+    category: Option<GrantCategory>,
+    grant_type: Option<GrantType>,
+    idea_id: Option<u64>,
+    tagged_any: Option<Vec<String>>,
+    tagged_all: Option<Vec<String>>,
+    page: Paging,
+}
+
+impl GrantQuery {
+    /// A query matching every grant
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status is one of `statuses`
+    pub fn status_in(mut self, statuses: Vec<GrantStatus>) -> Self {
+        self.statuses = Some(statuses);
+        self
+    }
+
+    /// Recipient mesh group equals `mesh_group_id`
+    pub fn mesh_group(mut self, mesh_group_id: [u8; 32]) -> Self {
+        self.mesh_group_id = Some(mesh_group_id);
+        self
+    }
+
+    /// `from <= created_at <= to`; matches nothing if `from > to`
+    pub fn created_between(mut self, from: i64, to: i64) -> Self {
+        self.created_between = Some((from, to));
+        self
+    }
+
+    pub fn category(mut self, category: GrantCategory) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn grant_type(mut self, grant_type: GrantType) -> Self {
+        self.grant_type = Some(grant_type);
+        self
+    }
+
+    pub fn idea_id(mut self, idea_id: u64) -> Self {
+        self.idea_id = Some(idea_id);
+        self
+    }
+
+    /// Has at least one of `tags`; an empty list matches nothing
+    pub fn tagged_any(mut self, tags: Vec<String>) -> Self {
+        self.tagged_any = Some(tag_filter(tags));
+        self
+    }
+
+    /// Has every one of `tags`
+    pub fn tagged_all(mut self, tags: Vec<String>) -> Self {
+        self.tagged_all = Some(tag_filter(tags));
+        self
+    }
+
+    pub fn sort_by(mut self, key: SortKey, order: SortOrder) -> Self {
+        self.page.sort = Some((key, order));
+        self
+    }
+
+    /// Skip the first `offset` results
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.page.offset = offset;
+        self
+    }
+
+    /// Return at most `limit` results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.page.limit = Some(limit);
+        self
+    }
+
+    /// Whether `grant` passes every filter
+    pub fn matches(&self, grant: &Grant) -> bool {
+        self.statuses
+            .as_ref()
+            .is_none_or(|s| s.contains(&grant.status))
+            && self.mesh_group_id.is_none_or(|m| m == grant.mesh_group_id)
+            && created_in(grant.created_at, self.created_between)
+            && self.category.is_none_or(|c| c == grant.category)
+            && self.grant_type.is_none_or(|t| t == grant.grant_type)
+            && self.idea_id.is_none_or(|id| id == grant.idea_id)
+            && tagged(
+                &grant.tags,
+                self.tagged_any.as_ref(),
+                self.tagged_all.as_ref(),
+            )
+    }
+
+    /// Matching grants with an id above `after_id`, at most `limit` (at least one), by id
+    ///
+    /// `grants` need not be sorted; ids are assumed unique. Each call looks
+    /// at the whole slice, so for very large sets keep grants in an id-keyed
+    /// map and page over it instead.
+    pub fn page<'a>(
+        &self,
+        grants: &'a [Grant],
+        after_id: Option<u64>,
+        limit: usize,
+    ) -> Page<&'a Grant> {
+        let mut matched: Vec<&Grant> = grants
+            .iter()
+            .filter(|g| after_id.is_none_or(|after| g.id > after) && self.matches(g))
+            .collect();
+        matched.sort_by_key(|g| g.id);
+        Page::from_ascending(matched.into_iter(), limit, |g| g.id)
+    }
+
+    /// Matching grants, sorted and paged
+    pub fn apply<'a>(&self, grants: &'a [Grant]) -> Vec<&'a Grant> {
+        let matched = grants.iter().filter(|g| self.matches(g)).collect();
+        self.page.apply(
+            matched,
+            |g| g.id,
+            |g, key| match key {
+                SortKey::CreatedAt => i128::from(g.created_at),
+                // Three u64 weights always fit
+                SortKey::TotalVotes => i128::try_from(g.tally.participating()).unwrap_or(i128::MAX),
+                SortKey::Amount => i128::from(g.total_amount),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::GrantDisbursementType;
+    use crate::proposal::{TreasuryOperationData, TreasuryProposalType};
+
+    fn create_test_proposal(id: u64, author: u8, title: &str, created_at: i64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            title.to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            author,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(created_at)
+        .unwrap()
+    }
+
+    fn create_test_proposals() -> Vec<Proposal<u8>> {
+        let mut proposals = vec![
+            create_test_proposal(1, 1, "Fund the Bridge", 10),
+            create_test_proposal(2, 1, "bridge repairs", 20),
+            create_test_proposal(3, 2, "Bridge audit", 30),
+            create_test_proposal(4, 1, "Park", 40),
+            create_test_proposal(5, 1, "BRIDGE lighting", 50),
+            create_test_proposal(6, 1, "Bridge paint", 60),
+        ];
+        for (proposal, votes) in proposals.iter_mut().zip([5, 7, 7, 1, 7, 2]) {
+            proposal
+                .activate_with_time(1, 10, proposal.created_at)
+                .unwrap();
+            proposal.total_votes = votes;
+        }
+        proposals[5]
+            .cancel_with_time("Withdrawn".to_string(), 70)
+            .unwrap();
+        proposals
+    }
+
+    #[test]
+    fn test_three_filters_and() {
+        let proposals = create_test_proposals();
+        let query = ProposalQuery::new()
+            .author(1)
+            .status_in(vec![ProposalStatus::Active])
+            .text_contains("bRiDgE");
+        let ids: Vec<u64> = query.apply(&proposals).iter().map(|p| p.id).collect();
+        // 3 has another author, 4 no match in the title, 6 is cancelled
+        assert_eq!(ids, vec![1, 2, 5]);
+
+        let ids: Vec<u64> = query
+            .created_between(15, 50)
+            .apply(&proposals)
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![2, 5]);
+    }
+
+    #[test]
+    fn test_sponsored_by_includes_author() {
+        let mut proposals = vec![
+            create_test_proposal(1, 1, "Bridge", 10),
+            create_test_proposal(2, 2, "Park", 20),
+            create_test_proposal(3, 3, "Road", 30),
+        ];
+        proposals[1].add_sponsor(1, 20).unwrap();
+        let ids: Vec<u64> = ProposalQuery::new()
+            .sponsored_by(1)
+            .apply(&proposals)
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_other_filters() {
+        let mut proposals = create_test_proposals();
+        proposals[0].idea_id = Some(9);
+        proposals[1].proposal_type = "treasury".to_string();
+        proposals[1].treasury_operation = Some(TreasuryOperationData::new(
+            TreasuryProposalType::Withdrawal,
+            Some(500),
+            None,
+            None,
+            None,
+            None,
+            "Withdraw".to_string(),
+        ));
+        let ids = |query: ProposalQuery<u8>| -> Vec<u64> {
+            query.apply(&proposals).iter().map(|p| p.id).collect()
+        };
+        assert_eq!(ids(ProposalQuery::new().idea_linked(true)), vec![1]);
+        assert_eq!(ids(ProposalQuery::new().idea_linked(false)).len(), 5);
+        assert_eq!(
+            ids(ProposalQuery::new().proposal_type("treasury".to_string())),
+            vec![2]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new().has_treasury_operation(true)),
+            vec![2]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new().created_between(30, 10)),
+            Vec::<u64>::new()
+        );
+        assert_eq!(ids(ProposalQuery::new()).len(), 6);
+
+        proposals[3].fail_quorum_with_time(140).unwrap();
+        let ids = |query: ProposalQuery<u8>| -> Vec<u64> {
+            query.apply(&proposals).iter().map(|p| p.id).collect()
+        };
+        assert_eq!(
+            ids(ProposalQuery::new().status_in(vec![ProposalStatus::QuorumFailed])),
+            vec![4]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new().status_in(vec![ProposalStatus::Rejected])),
+            Vec::<u64>::new()
+        );
+    }
+
+    #[test]
+    fn test_sort_and_pagination_stable() {
+        let proposals = create_test_proposals();
+        let query = ProposalQuery::new().sort_by(SortKey::TotalVotes, SortOrder::Descending);
+        let ids: Vec<u64> = query.apply(&proposals).iter().map(|p| p.id).collect();
+        // Equal vote totals keep id order
+        assert_eq!(ids, vec![2, 3, 5, 1, 6, 4]);
+
+        let mut reversed = proposals.clone();
+        reversed.reverse();
+        let mut paged = Vec::new();
+        for page in 0..3 {
+            let query = query.clone().offset(page * 2).limit(2);
+            let page_ids: Vec<u64> = query.apply(&reversed).iter().map(|p| p.id).collect();
+            assert_eq!(page_ids.len(), 2);
+            paged.extend(page_ids);
+        }
+        assert_eq!(paged, ids);
+        assert!(query.offset(6).apply(&proposals).is_empty());
+
+        let ids: Vec<u64> = ProposalQuery::new()
+            .sort_by(SortKey::CreatedAt, SortOrder::Descending)
+            .limit(2)
+            .apply(&proposals)
+            .iter()
+            .map(|p| p.id)
+            .collect();
+        assert_eq!(ids, vec![6, 5]);
+    }
+
+    fn create_test_grant(id: u64, mesh_group: u8, amount: u64, created_at: i64) -> Grant {
+        Grant::new(
+            id,
+            id * 10,
+            [mesh_group; 32],
+            GrantCategory::Development,
+            GrantType::Initial,
+            GrantDisbursementType::Escrow,
+            amount,
+            0,
+            created_at,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_grant_query() {
+        let mut grants = vec![
+            create_test_grant(1, 1, 300, 10),
+            create_test_grant(2, 1, 100, 20),
+            create_test_grant(3, 2, 200, 30),
+            create_test_grant(4, 1, 200, 40),
+        ];
+        grants[3].category = GrantCategory::Research;
+        let ids =
+            |query: GrantQuery| -> Vec<u64> { query.apply(&grants).iter().map(|g| g.id).collect() };
+        assert_eq!(
+            ids(GrantQuery::new()
+                .mesh_group([1; 32])
+                .status_in(vec![GrantStatus::Pending])
+                .category(GrantCategory::Development)),
+            vec![1, 2]
+        );
+        assert_eq!(ids(GrantQuery::new().idea_id(30)), vec![3]);
+        assert_eq!(
+            ids(GrantQuery::new().status_in(vec![GrantStatus::Active])),
+            Vec::<u64>::new()
+        );
+        assert_eq!(
+            ids(GrantQuery::new().sort_by(SortKey::Amount, SortOrder::Ascending)),
+            vec![2, 3, 4, 1]
+        );
+        assert_eq!(
+            ids(GrantQuery::new()
+                .created_between(15, 40)
+                .sort_by(SortKey::Amount, SortOrder::Descending)
+                .offset(1)
+                .limit(5)),
+            vec![4, 2]
+        );
+    }
+
+    #[test]
+    fn test_tag_filters() {
+        let mut proposals = create_test_proposals();
+        proposals[0].add_tag("infrastructure", None).unwrap();
+        proposals[0].add_tag("marketing", None).unwrap();
+        proposals[1].add_tag("Marketing", None).unwrap();
+        proposals[2].add_tag("infrastructure", None).unwrap();
+        let ids = |query: ProposalQuery<u8>| -> Vec<u64> {
+            query.apply(&proposals).iter().map(|p| p.id).collect()
+        };
+        assert_eq!(
+            ids(ProposalQuery::new().tagged_any(vec!["MARKETING".to_string()])),
+            vec![1, 2]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new()
+                .tagged_any(vec!["marketing".to_string(), "infrastructure".to_string()])),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new()
+                .tagged_all(vec!["marketing".to_string(), "infrastructure".to_string()])),
+            vec![1]
+        );
+        assert_eq!(
+            ids(ProposalQuery::new().tagged_any(Vec::new())),
+            Vec::<u64>::new()
+        );
+
+        let mut grants = vec![
+            create_test_grant(1, 1, 100, 10),
+            create_test_grant(2, 1, 100, 20),
+        ];
+        grants[1].add_tag("research", None).unwrap();
+        let grant_ids: Vec<u64> = GrantQuery::new()
+            .tagged_all(vec!["research".to_string()])
+            .apply(&grants)
+            .iter()
+            .map(|g| g.id)
+            .collect();
+        assert_eq!(grant_ids, vec![2]);
+    }
+
+    #[test]
+    fn test_grant_cursor_pages() {
+        let grants: Vec<Grant> = [5, 3, 9, 1, 7, 2]
+            .into_iter()
+            .map(|id| create_test_grant(id, (id % 2) as u8, 100, 10))
+            .collect();
+        let query = GrantQuery::new().mesh_group([1; 32]).limit(1);
+        let first = query.page(&grants, None, 2);
+        let ids = |page: &Page<&Grant>| page.items.iter().map(|g| g.id).collect::<Vec<_>>();
+        assert_eq!(ids(&first), vec![1, 3]);
+        assert_eq!(first.next_cursor, Some(3));
+        let second = query.page(&grants, first.next_cursor, 2);
+        assert_eq!(ids(&second), vec![5, 7]);
+        let third = query.page(&grants, second.next_cursor, 2);
+        assert_eq!(ids(&third), vec![9]);
+        assert_eq!(third.next_cursor, None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Machine-readable reason codes.
+//!
+//! Codes are stored next to the free-text reason so a UI can translate the
+//! reason via `label_key` and still show the author's own wording.
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Why a proposal or grant was cancelled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReasonCode {
+    /// The author withdrew it
+    AuthorWithdrawn,
+    /// Replaced by another proposal
+    SupersededBy { id: u64 },
+    /// Vetoed by the security board
+    SecurityVeto,
+    /// Removed as spam
+    Spam,
+    /// Deadline passed
+    Expired,
+    /// Anything else; legacy free-text-only cancellations map here
+    Other,
+    /// Execution kept failing until the retry policy gave up
+    ExecutionFailed,
+}
+
+impl ReasonCode {
+    /// Stable i18n key for this reason
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Self::AuthorWithdrawn => "reason.cancellation.author_withdrawn",
+            Self::SupersededBy { .. } => "reason.cancellation.superseded_by",
+            Self::SecurityVeto => "reason.cancellation.security_veto",
+            Self::Spam => "reason.cancellation.spam",
+            Self::Expired => "reason.cancellation.expired",
+            Self::Other => "reason.cancellation.other",
+            Self::ExecutionFailed => "reason.cancellation.execution_failed",
+        }
+    }
+}
+
+/// Why a grant was suspended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SuspensionCode {
+    /// Grant is under compliance review
+    ComplianceReview,
+    /// A milestone was missed
+    MilestoneMissed,
+    /// Held by the security board
+    SecurityHold,
+    /// Anything else
+    Other,
+    /// A dispute raised after completion was upheld
+    DisputeUpheld,
+}
+
+impl SuspensionCode {
+    /// Stable i18n key for this reason
+    pub fn label_key(&self) -> &'static str {
+        match self {
+            Self::ComplianceReview => "reason.suspension.compliance_review",
+            Self::MilestoneMissed => "reason.suspension.milestone_missed",
+            Self::SecurityHold => "reason.suspension.security_hold",
+            Self::DisputeUpheld => "reason.suspension.dispute_upheld",
+            Self::Other => "reason.suspension.other",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason_label_keys() {
+        assert_eq!(
+            ReasonCode::SupersededBy { id: 7 }.label_key(),
+            "reason.cancellation.superseded_by"
+        );
+        assert_eq!(
+            SuspensionCode::MilestoneMissed.label_key(),
+            "reason.suspension.milestone_missed"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_reason_code_serde_roundtrip() {
+        let code = ReasonCode::SupersededBy { id: 7 };
+        let json = serde_json::to_string(&code).unwrap();
+        assert_eq!(json, r#"{"SupersededBy":{"id":7}}"#);
+        assert_eq!(serde_json::from_str::<ReasonCode>(&json).unwrap(), code);
+    }
+}
@@ -0,0 +1,256 @@
+//! Which transitions must, may or must not carry a reason.
+//!
+//! A `ReasonPolicy` maps `(from, to)` status pairs of one entity kind to a
+//! `ReasonRequirement`. Proposals and grants keep their policy next to their
+//! status (`Proposal::reason_policy`, `Grant::reason_policy`) and the lifecycle
+//! methods that take a reason consult it before changing status: proposal
+//! cancellation, grant cancellation and grant suspension (including a
+//! suspension by an upheld dispute). A blank reason counts as no reason.
+//!
+//! Pairs without a rule are `Optional`, so the empty default policy keeps the
+//! behaviour of earlier releases; `ReasonPolicy::strict` requires a reason on
+//! every transition listed in `ReasonStatus::REASON_TRANSITIONS`.
+
+use crate::enums::EntityKind;
+use crate::error::FsmError;
+use crate::grant::GrantStatus;
+use crate::proposal::ProposalStatus;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Whether a transition carries a reason
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReasonRequirement {
+    /// A non-blank reason must be given (`ReasonRequired` otherwise)
+    Required,
+    /// A reason may be given
+    #[default]
+    Optional,
+    /// No reason may be given (`ReasonNotAllowed` otherwise)
+    Forbidden,
+}
+
+/// Requirement for one `(from, to)` transition
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReasonRule<S> {
+    pub from: S,
+    pub to: S,
+    pub requirement: ReasonRequirement,
+}
+
+/// A status enum whose transitions a `ReasonPolicy` can govern
+pub trait ReasonStatus: Clone + PartialEq + fmt::Debug + 'static {
+    /// Entity kind named by `ReasonTransition`
+    const ENTITY: EntityKind;
+    /// Every variant, in declaration order
+    const ALL: &'static [Self];
+    /// Transitions whose lifecycle methods take a reason
+    const REASON_TRANSITIONS: &'static [(Self, Self)];
+}
+
+impl ReasonStatus for ProposalStatus {
+    const ENTITY: EntityKind = EntityKind::Proposal;
+    const ALL: &'static [Self] = Self::VARIANTS;
+    const REASON_TRANSITIONS: &'static [(Self, Self)] = &[
+        (ProposalStatus::Draft, ProposalStatus::Cancelled),
+        (ProposalStatus::Active, ProposalStatus::Cancelled),
+    ];
+}
+
+impl ReasonStatus for GrantStatus {
+    const ENTITY: EntityKind = EntityKind::Grant;
+    const ALL: &'static [Self] = Self::VARIANTS;
+    const REASON_TRANSITIONS: &'static [(Self, Self)] = &[
+        (GrantStatus::Active, GrantStatus::Cancelled),
+        (GrantStatus::Suspended, GrantStatus::Cancelled),
+        (GrantStatus::Active, GrantStatus::Suspended),
+        (GrantStatus::Completed, GrantStatus::Suspended),
+    ];
+}
+
+/// Transition named by `FsmError::ReasonRequired` and `ReasonNotAllowed`
+///
+/// `from` and `to` index the status enum's `VARIANTS`, which keeps the error
+/// `Copy`; `Display` prints the variant names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReasonTransition {
+    pub entity: EntityKind,
+    pub from: u8,
+    pub to: u8,
+}
+
+impl ReasonTransition {
+    fn of<S: ReasonStatus>(from: &S, to: &S) -> Self {
+        let index = |status: &S| {
+            S::ALL
+                .iter()
+                .position(|candidate| candidate == status)
+                .map_or(u8::MAX, |i| i as u8)
+        };
+        Self {
+            entity: S::ENTITY,
+            from: index(from),
+            to: index(to),
+        }
+    }
+
+    /// Variant name of a status index; `#index` if out of range
+    pub(crate) fn name(&self, index: u8) -> String {
+        let index = usize::from(index);
+        let name = match self.entity {
+            EntityKind::Proposal => ProposalStatus::ALL.get(index).map(|s| format!("{s:?}")),
+            EntityKind::Grant => GrantStatus::ALL.get(index).map(|s| format!("{s:?}")),
+            EntityKind::Idea => None,
+        };
+        name.unwrap_or_else(|| format!("#{index}"))
+    }
+}
+
+impl fmt::Display for ReasonTransition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} {} -> {}",
+            self.entity,
+            self.name(self.from),
+            self.name(self.to)
+        )
+    }
+}
+
+/// Reason requirements of one entity kind's transitions
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReasonPolicy<S> {
+    rules: Vec<ReasonRule<S>>,
+}
+
+impl<S> Default for ReasonPolicy<S> {
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<S: ReasonStatus> ReasonPolicy<S> {
+    /// Every transition `Optional`, as before reason policies existed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reason required on every `REASON_TRANSITIONS` entry
+    pub fn strict() -> Self {
+        S::REASON_TRANSITIONS
+            .iter()
+            .fold(Self::new(), |policy, (from, to)| {
+                policy.with_rule(from.clone(), to.clone(), ReasonRequirement::Required)
+            })
+    }
+
+    /// Set the requirement of `from -> to`, replacing any earlier rule for it
+    pub fn with_rule(mut self, from: S, to: S, requirement: ReasonRequirement) -> Self {
+        self.rules.retain(|rule| rule.from != from || rule.to != to);
+        if requirement != ReasonRequirement::Optional {
+            self.rules.push(ReasonRule {
+                from,
+                to,
+                requirement,
+            });
+        }
+        self
+    }
+
+    /// Requirement of `from -> to`; `Optional` without a rule
+    pub fn requirement(&self, from: &S, to: &S) -> ReasonRequirement {
+        self.rules
+            .iter()
+            .find(|rule| &rule.from == from && &rule.to == to)
+            .map_or(ReasonRequirement::Optional, |rule| rule.requirement)
+    }
+
+    /// Rules that differ from `Optional`, in insertion order
+    pub fn rules(&self) -> &[ReasonRule<S>] {
+        &self.rules
+    }
+
+    /// Check `reason` against the requirement of `from -> to`
+    ///
+    /// A blank reason counts as none. `ReasonRequired` or `ReasonNotAllowed`
+    /// name the transition.
+    pub fn check(&self, from: &S, to: &S, reason: Option<&str>) -> Result<(), FsmError> {
+        let given = reason.is_some_and(|reason| !reason.trim().is_empty());
+        match (self.requirement(from, to), given) {
+            (ReasonRequirement::Required, false) => {
+                Err(FsmError::ReasonRequired(ReasonTransition::of(from, to)))
+            }
+            (ReasonRequirement::Forbidden, true) => {
+                Err(FsmError::ReasonNotAllowed(ReasonTransition::of(from, to)))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_is_optional() {
+        let policy = ReasonPolicy::<ProposalStatus>::new();
+        for (from, to) in ProposalStatus::REASON_TRANSITIONS {
+            assert_eq!(policy.requirement(from, to), ReasonRequirement::Optional);
+            assert_eq!(policy.check(from, to, None), Ok(()));
+            assert_eq!(policy.check(from, to, Some("spam")), Ok(()));
+        }
+        assert!(policy.rules().is_empty());
+    }
+
+    #[test]
+    fn required_rejects_missing_and_blank_reasons() {
+        let policy = ReasonPolicy::<GrantStatus>::strict();
+        let err = FsmError::ReasonRequired(ReasonTransition {
+            entity: EntityKind::Grant,
+            from: 2,
+            to: 3,
+        });
+        let (from, to) = (GrantStatus::Active, GrantStatus::Suspended);
+        assert_eq!(policy.check(&from, &to, None), Err(err));
+        assert_eq!(policy.check(&from, &to, Some("  ")), Err(err));
+        assert_eq!(policy.check(&from, &to, Some("Audit")), Ok(()));
+        assert_eq!(
+            err.to_string(),
+            "A reason is required for the Grant Active -> Suspended transition"
+        );
+        assert_eq!(policy.rules().len(), GrantStatus::REASON_TRANSITIONS.len());
+    }
+
+    #[test]
+    fn forbidden_rejects_a_reason() {
+        let policy = ReasonPolicy::new().with_rule(
+            ProposalStatus::Draft,
+            ProposalStatus::Cancelled,
+            ReasonRequirement::Forbidden,
+        );
+        let (from, to) = (ProposalStatus::Draft, ProposalStatus::Cancelled);
+        assert_eq!(policy.check(&from, &to, Some("")), Ok(()));
+        assert_eq!(
+            policy.check(&from, &to, Some("Typo")),
+            Err(FsmError::ReasonNotAllowed(ReasonTransition {
+                entity: EntityKind::Proposal,
+                from: 0,
+                to: 5,
+            }))
+        );
+        let relaxed = policy.with_rule(from.clone(), to.clone(), ReasonRequirement::Optional);
+        assert_eq!(relaxed, ReasonPolicy::new());
+    }
+}
@@ -0,0 +1,350 @@
+//! Decision records for auditors.
+//!
+//! `decision_record` gathers everything known about one governance decision
+//! (the proposal, its tally, the quorum check, the security board decision and
+//! the proposal's slice of the audit trail) into one serializable
+//! `DecisionRecord`, with content hashes so the parts can be checked against
+//! their sources. Cross-references must agree: a board decision or quorum
+//! record that points at another proposal is refused rather than included.
+
+use crate::audit::{AuditEntry, AuditTrail, PurgeEntry};
+use crate::enums::EntityKind;
+use crate::error::FsmError;
+use crate::governance::{
+    QuorumCalculationMethod, QuorumMetadata, SecurityBoardDecisionMetadata,
+    SecurityBoardDecisionStatus,
+};
+use crate::proposal::{Proposal, ProposalStatus, TallyPolicy};
+use crate::retention::content_hash;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+
+/// Quorum check of the decision
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuorumSummary {
+    pub quorum_id: u64,
+    pub required_percentage: u8,
+    pub current_percentage: u8,
+    pub quorum_reached: bool,
+    pub calculation_method: QuorumCalculationMethod,
+}
+
+/// Security board decision on the proposal
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoardDecisionSummary {
+    pub decision_id: u64,
+    pub status: SecurityBoardDecisionStatus,
+    pub created_at: i64,
+    pub decided_at: Option<i64>,
+    pub decision_data_hash: [u8; 32],
+}
+
+/// Complete record of one governance decision
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecisionRecord<P> {
+    pub proposal_id: u64,
+    pub title: String,
+    pub proposal_type: String,
+    pub author: P,
+    pub status: ProposalStatus,
+    pub created_at: i64,
+    pub submitted_at: Option<i64>,
+    pub passed_at: Option<i64>,
+    pub executed_at: Option<i64>,
+    pub tally_policy: TallyPolicy,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub total_votes: u64,
+    pub quorum: Option<QuorumSummary>,
+    pub board_decision: Option<BoardDecisionSummary>,
+    /// Transition entries of the proposal, in recorded order
+    ///
+    /// The trail records grant transitions only, so this is empty until
+    /// proposal transitions are audited.
+    pub audit_entries: Vec<AuditEntry>,
+    /// `AuditEntry::content_hash` of each of `audit_entries`
+    pub audit_entry_hashes: Vec<[u8; 32]>,
+    /// Purge records of the proposal
+    pub purges: Vec<PurgeEntry>,
+    /// Head of the trail the slice was taken from
+    pub audit_chain_head: [u8; 32],
+    /// `retention::content_hash` of the proposal
+    pub proposal_hash: [u8; 32],
+}
+
+/// Assemble the decision record of `proposal`
+///
+/// Errors: `InvalidInput` if `decision` or `quorum` does not reference
+/// `proposal.id`, or if the proposal cannot be serialized for hashing.
+pub fn decision_record<P: Clone + Serialize>(
+    proposal: &Proposal<P>,
+    decision: Option<&SecurityBoardDecisionMetadata<P>>,
+    quorum: Option<&QuorumMetadata>,
+    trail: &AuditTrail,
+) -> Result<DecisionRecord<P>, FsmError> {
+    if decision.is_some_and(|d| d.proposal_id != Some(proposal.id))
+        || quorum.is_some_and(|q| q.proposal_id != Some(proposal.id))
+    {
+        return Err(FsmError::InvalidInput);
+    }
+    let audit_entries: Vec<AuditEntry> = trail
+        .entity_entries(EntityKind::Proposal, proposal.id)
+        .map(|(_, entry)| entry.clone())
+        .collect();
+    Ok(DecisionRecord {
+        proposal_id: proposal.id,
+        title: proposal.title.clone(),
+        proposal_type: proposal.proposal_type.clone(),
+        author: proposal.author.clone(),
+        status: proposal.status.clone(),
+        created_at: proposal.created_at,
+        submitted_at: proposal.submitted_at,
+        passed_at: proposal.passed_at,
+        executed_at: proposal.executed_at,
+        tally_policy: proposal.tally_policy,
+        yes_votes: proposal.yes_votes,
+        no_votes: proposal.no_votes,
+        total_votes: proposal.total_votes,
+        quorum: quorum.map(|q| QuorumSummary {
+            quorum_id: q.quorum_id,
+            required_percentage: q.required_percentage,
+            current_percentage: q.current_percentage,
+            quorum_reached: q.quorum_reached,
+            calculation_method: q.calculation_method,
+        }),
+        board_decision: decision.map(|d| BoardDecisionSummary {
+            decision_id: d.decision_id,
+            status: d.status,
+            created_at: d.created_at,
+            decided_at: d.decided_at,
+            decision_data_hash: d.decision_data_hash,
+        }),
+        audit_entry_hashes: audit_entries.iter().map(AuditEntry::content_hash).collect(),
+        audit_entries,
+        purges: trail
+            .purges()
+            .iter()
+            .filter(|p| p.entity_kind == EntityKind::Proposal && p.entity_id == proposal.id)
+            .cloned()
+            .collect(),
+        audit_chain_head: trail.chain_head(),
+        proposal_hash: content_hash(proposal)?,
+    })
+}
+
+fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn or_dash(value: Option<i64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+impl<P: fmt::Debug> DecisionRecord<P> {
+    /// Markdown summary for human review
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_markdown(&mut out);
+        out
+    }
+
+    fn write_markdown(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "# Decision record: proposal {} \u{2014} {}",
+            self.proposal_id, self.title
+        )?;
+        writeln!(out)?;
+        writeln!(out, "| Field | Value |")?;
+        writeln!(out, "|---|---|")?;
+        writeln!(out, "| Type | {} |", self.proposal_type)?;
+        writeln!(out, "| Author | {:?} |", self.author)?;
+        writeln!(out, "| Status | {:?} |", self.status)?;
+        writeln!(out, "| Created | {} |", self.created_at)?;
+        writeln!(out, "| Submitted | {} |", or_dash(self.submitted_at))?;
+        writeln!(out, "| Passed | {} |", or_dash(self.passed_at))?;
+        writeln!(out, "| Executed | {} |", or_dash(self.executed_at))?;
+        writeln!(out, "| Proposal hash | `{}` |", hex(&self.proposal_hash))?;
+        writeln!(out)?;
+        writeln!(out, "## Tally")?;
+        writeln!(out)?;
+        writeln!(
+            out,
+            "{:?}: {} yes, {} no, {} total",
+            self.tally_policy, self.yes_votes, self.no_votes, self.total_votes
+        )?;
+        writeln!(out)?;
+        writeln!(out, "## Quorum")?;
+        writeln!(out)?;
+        match &self.quorum {
+            Some(q) => writeln!(
+                out,
+                "Quorum {} ({:?}): {}% of {}% required, {}",
+                q.quorum_id,
+                q.calculation_method,
+                q.current_percentage,
+                q.required_percentage,
+                if q.quorum_reached {
+                    "reached"
+                } else {
+                    "not reached"
+                }
+            )?,
+            None => writeln!(out, "None")?,
+        }
+        writeln!(out)?;
+        writeln!(out, "## Security board")?;
+        writeln!(out)?;
+        match &self.board_decision {
+            Some(d) => writeln!(
+                out,
+                "Decision {}: {:?} (decided {}), data hash `{}`",
+                d.decision_id,
+                d.status,
+                or_dash(d.decided_at),
+                hex(&d.decision_data_hash)
+            )?,
+            None => writeln!(out, "None")?,
+        }
+        writeln!(out)?;
+        writeln!(out, "## Audit")?;
+        writeln!(out)?;
+        writeln!(out, "Chain head `{}`", hex(&self.audit_chain_head))?;
+        writeln!(out)?;
+        for (entry, hash) in self.audit_entries.iter().zip(&self.audit_entry_hashes) {
+            writeln!(
+                out,
+                "- {} {}: {:?} -> {:?} `{}`",
+                entry.timestamp,
+                entry.action,
+                entry.from_state,
+                entry.to_state,
+                hex(hash)
+            )?;
+        }
+        for purge in &self.purges {
+            writeln!(
+                out,
+                "- {} purged, content hash `{}`",
+                purge.purged_at,
+                hex(&purge.content_hash)
+            )?;
+        }
+        if self.audit_entries.is_empty() && self.purges.is_empty() {
+            writeln!(out, "No entries")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+
+    fn create_passed_proposal() -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            7,
+            "Upgrade".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap();
+        proposal.activate_with_time(1, 10, 5).unwrap();
+        proposal.yes_votes = 3;
+        proposal.no_votes = 1;
+        proposal.total_votes = 4;
+        proposal.pass_with_time(200).unwrap();
+        proposal
+    }
+
+    #[test]
+    fn record_collects_all_parts() {
+        let proposal = create_passed_proposal();
+        let mut decision =
+            SecurityBoardDecisionMetadata::<u8>::initialize(3, Some(7), [0xab; 32], 150).unwrap();
+        decision.approve(210).unwrap();
+        let mut quorum =
+            QuorumMetadata::initialize(2, Some(7), 40, QuorumCalculationMethod::default(), 150)
+                .unwrap();
+        quorum.current_percentage = 55;
+        quorum.quorum_reached = true;
+        let mut trail = AuditTrail::new();
+        trail
+            .record_purge(PurgeEntry {
+                entity_kind: EntityKind::Proposal,
+                entity_id: 7,
+                content_hash: [1; 32],
+                purged_at: 300,
+                archived_at: 100,
+                retention_seconds: 100,
+                sequence: 0,
+            })
+            .unwrap();
+        trail
+            .record_purge(PurgeEntry {
+                entity_kind: EntityKind::Proposal,
+                entity_id: 8,
+                content_hash: [2; 32],
+                purged_at: 300,
+                archived_at: 100,
+                retention_seconds: 100,
+                sequence: 0,
+            })
+            .unwrap();
+
+        let record = decision_record(&proposal, Some(&decision), Some(&quorum), &trail).unwrap();
+        assert_eq!(record.status, ProposalStatus::Passed);
+        assert_eq!((record.yes_votes, record.no_votes), (3, 1));
+        assert_eq!(
+            record.board_decision.as_ref().map(|d| d.status),
+            Some(SecurityBoardDecisionStatus::Approved)
+        );
+        assert_eq!(record.quorum.as_ref().map(|q| q.quorum_reached), Some(true));
+        assert_eq!(record.purges.len(), 1);
+        assert_eq!(record.proposal_hash, content_hash(&proposal).unwrap());
+        assert_eq!(record.audit_chain_head, trail.chain_head());
+
+        let markdown = record.render_markdown();
+        assert!(markdown.starts_with("# Decision record: proposal 7"));
+        assert!(markdown.contains("| Status | Passed |"));
+        assert!(markdown.contains("Decision 3: Approved (decided 210)"));
+        assert!(markdown.contains("55% of 40% required, reached"));
+        assert!(markdown.contains(&"ab".repeat(32)));
+
+        let json = serde_json::to_string(&record).unwrap();
+        let decoded: DecisionRecord<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, record);
+    }
+
+    #[test]
+    fn mismatched_references_are_refused() {
+        let proposal = create_passed_proposal();
+        let trail = AuditTrail::new();
+        let other =
+            SecurityBoardDecisionMetadata::<u8>::initialize(3, Some(8), [0; 32], 150).unwrap();
+        let unlinked =
+            SecurityBoardDecisionMetadata::<u8>::initialize(4, None, [0; 32], 150).unwrap();
+        let quorum =
+            QuorumMetadata::initialize(2, Some(8), 40, QuorumCalculationMethod::default(), 150)
+                .unwrap();
+        assert_eq!(
+            decision_record(&proposal, Some(&other), None, &trail),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            decision_record(&proposal, Some(&unlinked), None, &trail),
+            Err(FsmError::InvalidInput)
+        );
+        assert_eq!(
+            decision_record(&proposal, None, Some(&quorum), &trail),
+            Err(FsmError::InvalidInput)
+        );
+        let record = decision_record(&proposal, None, None, &trail).unwrap();
+        assert!(record.render_markdown().contains("No entries"));
+    }
+}
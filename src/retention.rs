@@ -0,0 +1,357 @@
+//! Retention of archived entities.
+//!
+//! Archived proposals and grants are kept for a retention period and then
+//! purged. A purge is recorded in the `AuditTrail` with a hash of the entity's
+//! content taken before removal, and leaves a `Tombstone` so references to the
+//! purged id still resolve. Purging needs the `hash` and `serde` features;
+//! `Tombstone` and eligibility checks are always available.
+
+#[cfg(all(feature = "hash", feature = "serde"))]
+use crate::audit::{AuditTrail, PurgeEntry};
+#[cfg(all(feature = "hash", feature = "serde"))]
+use crate::canonical::canonical_hash;
+#[cfg(all(feature = "hash", feature = "serde"))]
+use crate::enums::EntityKind;
+use crate::error::FsmError;
+use crate::grant::{Grant, GrantStatus};
+#[cfg(all(feature = "hash", feature = "serde"))]
+use crate::proposal::VersionedRegistry;
+use crate::proposal::{Proposal, ProposalStatus};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Seven 365.25-day years
+pub const DEFAULT_RETENTION_SECONDS: i64 = 7 * 31_557_600;
+
+/// Marker left in place of a purged entity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Tombstone {
+    pub id: u64,
+    pub content_hash: [u8; 32],
+    pub purged_at: i64,
+}
+
+/// Entity subject to retention
+pub trait Retained {
+    fn retained_id(&self) -> u64;
+    /// Archive timestamp, if the entity is archived
+    fn archived_at(&self) -> Option<i64>;
+}
+
+impl<P> Retained for Proposal<P> {
+    fn retained_id(&self) -> u64 {
+        self.id
+    }
+    fn archived_at(&self) -> Option<i64> {
+        match self.status {
+            ProposalStatus::Archived => self.archived_at,
+            _ => None,
+        }
+    }
+}
+
+impl Retained for Grant {
+    fn retained_id(&self) -> u64 {
+        self.id
+    }
+    fn archived_at(&self) -> Option<i64> {
+        match self.status {
+            GrantStatus::Archived => self.archived_at,
+            _ => None,
+        }
+    }
+}
+
+/// Ids of archived entities whose `archived_at` is older than `retention_seconds`
+///
+/// Entities archived without a timestamp are never eligible. Ids are ascending.
+pub fn purge_eligible<T: Retained>(
+    entities: &[T],
+    retention_seconds: i64,
+    current_time: i64,
+) -> Result<Vec<u64>, FsmError> {
+    if retention_seconds < 0 {
+        return Err(FsmError::InvalidInput);
+    }
+    let mut ids = Vec::new();
+    for entity in entities {
+        let Some(archived_at) = entity.archived_at() else {
+            continue;
+        };
+        if retention_expired(archived_at, retention_seconds, current_time)? {
+            ids.push(entity.retained_id());
+        }
+    }
+    ids.sort_unstable();
+    Ok(ids)
+}
+
+/// Whether `current_time` is past the retention window of an entity archived at `archived_at`
+///
+/// `InvalidInput` for a negative `retention_seconds`, `Overflow` if the window
+/// end overflows.
+pub(crate) fn retention_expired(
+    archived_at: i64,
+    retention_seconds: i64,
+    current_time: i64,
+) -> Result<bool, FsmError> {
+    if retention_seconds < 0 {
+        return Err(FsmError::InvalidInput);
+    }
+    let expires = archived_at
+        .checked_add(retention_seconds)
+        .ok_or(FsmError::Overflow)?;
+    Ok(current_time > expires)
+}
+
+/// `canonical_hash` of `entity`: stable across field order and releases
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub fn content_hash<T: Serialize>(entity: &T) -> Result<[u8; 32], FsmError> {
+    canonical_hash(entity)
+}
+
+/// Purge `ids` from `registry`, leaving tombstones and one audit record each
+///
+/// All ids are checked first: an unknown or repeated id or a negative
+/// `retention_seconds` is `InvalidInput`, a proposal that is not archived or
+/// still inside its retention window (see `purge_eligible`) is `InvalidState`,
+/// `Overflow` if `trail` cannot number every record, and in any of these cases
+/// nothing is purged.
+#[cfg(all(feature = "hash", feature = "serde"))]
+pub fn purge<P: Clone + Serialize>(
+    registry: &mut VersionedRegistry<P>,
+    ids: &[u64],
+    retention_seconds: i64,
+    trail: &mut AuditTrail,
+    current_time: i64,
+) -> Result<Vec<Tombstone>, FsmError> {
+    let mut records = Vec::with_capacity(ids.len());
+    for &id in ids {
+        let entry = registry.get(id).ok_or(FsmError::InvalidInput)?;
+        let archived_at = entry.proposal.archived_at().ok_or(FsmError::InvalidState)?;
+        if !retention_expired(archived_at, retention_seconds, current_time)? {
+            return Err(FsmError::InvalidState);
+        }
+        if records.iter().any(|r: &PurgeEntry| r.entity_id == id) {
+            return Err(FsmError::InvalidInput);
+        }
+        records.push(PurgeEntry {
+            entity_kind: EntityKind::Proposal,
+            entity_id: id,
+            content_hash: content_hash(&entry.proposal)?,
+            purged_at: current_time,
+            archived_at,
+            retention_seconds,
+            sequence: 0,
+        });
+    }
+    trail
+        .sequence_generator()
+        .last()
+        .checked_add(records.len() as u64)
+        .ok_or(FsmError::Overflow)?;
+    let mut tombstones = Vec::with_capacity(records.len());
+    for record in records {
+        let tombstone = Tombstone {
+            id: record.entity_id,
+            content_hash: record.content_hash,
+            purged_at: record.purged_at,
+        };
+        // Cannot fail: eligibility and generator room were checked above
+        trail.record_purge(record)?;
+        registry.bury(tombstone);
+        tombstones.push(tombstone);
+    }
+    Ok(tombstones)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    use crate::proposal::Resolved;
+
+    fn create_archived_proposal(id: u64, archived_at: i64) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap();
+        proposal
+            .cancel_with_time("withdrawn".to_string(), 0)
+            .unwrap();
+        proposal.archive_with_time(archived_at).unwrap();
+        proposal
+    }
+
+    fn create_draft_proposal(id: u64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_purge_eligible_proposals() {
+        let proposals = vec![
+            create_archived_proposal(3, 100),
+            create_archived_proposal(1, 100),
+            create_archived_proposal(2, 500),
+            create_draft_proposal(4),
+        ];
+        assert_eq!(purge_eligible(&proposals, 300, 401).unwrap(), vec![1, 3]);
+        assert_eq!(
+            purge_eligible(&proposals, 300, 400).unwrap(),
+            Vec::<u64>::new()
+        );
+        assert_eq!(
+            purge_eligible(&proposals, -1, 400),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn test_purge_eligible_grants() {
+        let mut grant = Grant::new(
+            1,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap();
+        grant.approve().unwrap();
+        grant.activate().unwrap();
+        grant.disburse(100).unwrap();
+        assert!(
+            purge_eligible(&[grant.clone()], 10, 1_000)
+                .unwrap()
+                .is_empty()
+        );
+
+        grant.archive_with_time(50).unwrap();
+        assert_eq!(purge_eligible(&[grant], 10, 61).unwrap(), vec![1]);
+    }
+
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    #[test]
+    fn test_purge_writes_audit_and_tombstone() {
+        let archived = create_archived_proposal(1, 100);
+        let expected_hash = content_hash(&archived).unwrap();
+        let mut registry = VersionedRegistry::new();
+        registry.insert(archived).unwrap();
+        registry.insert(create_draft_proposal(2)).unwrap();
+        let mut trail = AuditTrail::new();
+
+        let tombstones = purge(&mut registry, &[1], 300, &mut trail, 9_000).unwrap();
+        assert_eq!(
+            tombstones,
+            vec![Tombstone {
+                id: 1,
+                content_hash: expected_hash,
+                purged_at: 9_000,
+            }]
+        );
+        assert_eq!(
+            trail.purges(),
+            &[PurgeEntry {
+                entity_kind: EntityKind::Proposal,
+                entity_id: 1,
+                content_hash: expected_hash,
+                purged_at: 9_000,
+                archived_at: 100,
+                retention_seconds: 300,
+                sequence: 1,
+            }]
+        );
+        assert!(trail.verify().is_ok());
+        assert_eq!(registry.resolve(1), Some(Resolved::Purged(&tombstones[0])));
+        assert!(matches!(registry.resolve(2), Some(Resolved::Live(_))));
+        assert_eq!(registry.resolve(3), None);
+
+        // Purged ids are never reused
+        assert_eq!(
+            registry.insert(create_draft_proposal(1)),
+            Err(FsmError::InvalidInput)
+        );
+    }
+
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    #[test]
+    fn test_purge_rejects_non_archived() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_archived_proposal(1, 100)).unwrap();
+        registry.insert(create_draft_proposal(2)).unwrap();
+        let mut trail = AuditTrail::new();
+
+        assert_eq!(
+            purge(&mut registry, &[1, 2], 300, &mut trail, 9_000),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            purge(&mut registry, &[1, 7], 300, &mut trail, 9_000),
+            Err(FsmError::InvalidInput)
+        );
+        // Nothing was purged
+        assert!(matches!(registry.resolve(1), Some(Resolved::Live(_))));
+        assert!(trail.purges().is_empty());
+    }
+
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    #[test]
+    fn test_purge_rechecks_retention() {
+        let mut registry = VersionedRegistry::new();
+        registry.insert(create_archived_proposal(1, 100)).unwrap();
+        registry.insert(create_archived_proposal(2, 5_000)).unwrap();
+        let mut trail = AuditTrail::new();
+
+        // Proposal 2 is still inside its retention window
+        assert_eq!(
+            purge(&mut registry, &[1, 2], 5_000, &mut trail, 5_200),
+            Err(FsmError::InvalidState)
+        );
+        assert_eq!(
+            purge(&mut registry, &[1], -1, &mut trail, 5_200),
+            Err(FsmError::InvalidInput)
+        );
+        assert!(matches!(registry.resolve(1), Some(Resolved::Live(_))));
+        assert!(trail.purges().is_empty());
+        purge(&mut registry, &[1], 5_000, &mut trail, 5_200).unwrap();
+        assert_eq!(trail.purges().len(), 1);
+    }
+
+    #[cfg(all(feature = "hash", feature = "serde"))]
+    #[test]
+    fn test_purge_record_needs_expired_retention() {
+        let record = |purged_at| PurgeEntry {
+            entity_kind: EntityKind::Proposal,
+            entity_id: 1,
+            content_hash: [1; 32],
+            purged_at,
+            archived_at: 100,
+            retention_seconds: 300,
+            sequence: 0,
+        };
+        let mut trail = AuditTrail::new();
+        assert_eq!(trail.record_purge(record(400)), Err(FsmError::InvalidState));
+        let head = trail.chain_head();
+        trail.record_purge(record(401)).unwrap();
+        assert_ne!(trail.chain_head(), head);
+        assert!(trail.verify().is_ok());
+    }
+}
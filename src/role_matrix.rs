@@ -0,0 +1,193 @@
+//! Role-to-transition authorization matrix of an `FsmDefinition`.
+//!
+//! A transition whose `metadata.roles` is empty (or has no metadata) is open:
+//! any caller may fire it. The matrix lists it once with `role: None` rather
+//! than repeating it for every role. Restricted transitions get one row per
+//! listed role.
+//!
+//! `validate_strict` adds role-name checks to `validate`; plain `validate`
+//! keeps accepting any role names within `DefinitionLimits`.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::definition::FsmDefinition;
+use crate::error::FsmError;
+
+/// Longest role name `validate_strict` accepts, in characters
+pub const MAX_ROLE_NAME_LENGTH: usize = 50;
+
+/// One role allowed to fire one transition
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MatrixRow {
+    /// `None` for an open transition
+    pub role: Option<String>,
+    pub from: String,
+    pub to: String,
+    pub action: String,
+}
+
+impl FsmDefinition {
+    /// Every (role, transition) pair, sorted by role (open rows first), from, to, action
+    ///
+    /// Duplicate rows, such as a role listed twice on one transition, appear once.
+    pub fn authorization_matrix(&self) -> Vec<MatrixRow> {
+        let mut rows = BTreeSet::new();
+        for transition in &self.transitions {
+            let roles = transition
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.roles.as_slice())
+                .unwrap_or_default();
+            let row = |role: Option<&String>| MatrixRow {
+                role: role.cloned(),
+                from: transition.from.clone(),
+                to: transition.to.clone(),
+                action: transition.action.clone(),
+            };
+            if roles.is_empty() {
+                rows.insert(row(None));
+            }
+            for role in roles {
+                rows.insert(row(Some(role)));
+            }
+        }
+        rows.into_iter().collect()
+    }
+
+    /// Distinct role names across all transitions, sorted
+    pub fn roles(&self) -> Vec<&str> {
+        let roles: BTreeSet<&str> = self
+            .transitions
+            .iter()
+            .filter_map(|transition| transition.metadata.as_ref())
+            .flat_map(|metadata| metadata.roles.iter().map(String::as_str))
+            .collect();
+        roles.into_iter().collect()
+    }
+
+    /// Matrix rows `role` may fire: its own rows and the open ones, in matrix order
+    pub fn transitions_for_role(&self, role: &str) -> Vec<MatrixRow> {
+        self.authorization_matrix()
+            .into_iter()
+            .filter(|row| row.role.as_deref().is_none_or(|r| r == role))
+            .collect()
+    }
+
+    /// `InvalidInput` if a role name is blank or longer than `MAX_ROLE_NAME_LENGTH`
+    pub fn validate_roles(&self) -> Result<(), FsmError> {
+        let invalid = self
+            .transitions
+            .iter()
+            .filter_map(|transition| transition.metadata.as_ref())
+            .flat_map(|metadata| metadata.roles.iter())
+            .any(|role| role.trim().is_empty() || role.chars().count() > MAX_ROLE_NAME_LENGTH);
+        if invalid {
+            return Err(FsmError::InvalidInput);
+        }
+        Ok(())
+    }
+
+    /// `validate`, then `validate_roles`
+    pub fn validate_strict(&self) -> Result<(), FsmError> {
+        self.validate()?;
+        self.validate_roles()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::definition::{FsmTransition, FsmTransitionMetadata};
+
+    fn transition(from: &str, to: &str, action: &str, roles: &[&str]) -> FsmTransition {
+        FsmTransition {
+            from: from.into(),
+            to: to.into(),
+            action: action.into(),
+            guard: None,
+            metadata: (!roles.is_empty()).then(|| FsmTransitionMetadata {
+                description: None,
+                roles: roles.iter().map(|r| r.to_string()).collect(),
+            }),
+        }
+    }
+
+    fn mixed_definition() -> FsmDefinition {
+        FsmDefinition {
+            name: Some("grant".into()),
+            states: vec!["Draft".into(), "Review".into(), "Approved".into()],
+            transitions: vec![
+                transition("Review", "Approved", "approve", &["reviewer", "admin"]),
+                transition("Draft", "Review", "submit", &[]),
+                transition("Review", "Draft", "return", &["reviewer", "reviewer"]),
+            ],
+            defaults: None,
+            invariants: Vec::new(),
+        }
+    }
+
+    fn row(role: Option<&str>, from: &str, to: &str, action: &str) -> MatrixRow {
+        MatrixRow {
+            role: role.map(str::to_string),
+            from: from.into(),
+            to: to.into(),
+            action: action.into(),
+        }
+    }
+
+    #[test]
+    fn matrix_expands_roles_in_stable_order() {
+        let definition = mixed_definition();
+        assert_eq!(
+            definition.authorization_matrix(),
+            vec![
+                row(None, "Draft", "Review", "submit"),
+                row(Some("admin"), "Review", "Approved", "approve"),
+                row(Some("reviewer"), "Review", "Approved", "approve"),
+                row(Some("reviewer"), "Review", "Draft", "return"),
+            ]
+        );
+        assert_eq!(definition.roles(), vec!["admin", "reviewer"]);
+        let mut reordered = definition.clone();
+        reordered.transitions.reverse();
+        assert_eq!(
+            reordered.authorization_matrix(),
+            definition.authorization_matrix()
+        );
+    }
+
+    #[test]
+    fn transitions_for_role_includes_open_rows() {
+        let definition = mixed_definition();
+        assert_eq!(
+            definition.transitions_for_role("admin"),
+            vec![
+                row(None, "Draft", "Review", "submit"),
+                row(Some("admin"), "Review", "Approved", "approve"),
+            ]
+        );
+        assert_eq!(
+            definition.transitions_for_role("auditor"),
+            vec![row(None, "Draft", "Review", "submit")]
+        );
+        let json = serde_json::to_string(&definition.authorization_matrix()).unwrap();
+        assert!(json.starts_with(r#"[{"role":null,"from":"Draft""#));
+    }
+
+    #[test]
+    fn strict_validation_checks_role_names() {
+        let mut definition = mixed_definition();
+        assert_eq!(definition.validate_strict(), Ok(()));
+        definition.transitions[0].metadata.as_mut().unwrap().roles[1] =
+            "a".repeat(MAX_ROLE_NAME_LENGTH);
+        assert_eq!(definition.validate_strict(), Ok(()));
+        definition.transitions[0].metadata.as_mut().unwrap().roles[1] =
+            "a".repeat(MAX_ROLE_NAME_LENGTH + 1);
+        assert_eq!(definition.validate(), Ok(()));
+        assert_eq!(definition.validate_strict(), Err(FsmError::InvalidInput));
+        definition.transitions[0].metadata.as_mut().unwrap().roles[1] = " ".into();
+        assert_eq!(definition.validate_strict(), Err(FsmError::InvalidInput));
+    }
+}
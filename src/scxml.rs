@@ -0,0 +1,478 @@
+//! SCXML import and export for `FsmDefinition` (feature `scxml`).
+//!
+//! Mapping:
+//!
+//! - `<state id>` and `<final id>` children of `<scxml>` become states; final
+//!   states are also listed in a `terminal_states` invariant.
+//! - `<scxml name>` becomes the definition `name`.
+//! - `<scxml initial>` becomes `defaults.initialState`; without it the first
+//!   state in document order is initial, as in SCXML.
+//! - `<transition event target cond>` becomes a transition with `action` from
+//!   `event` and `guard` from `cond`.
+//!
+//! Executable content, data models, history states and other SCXML features
+//! with no counterpart are skipped and reported as warnings. Constructs that
+//! would change the meaning of the workflow if skipped (parallel or nested
+//! states, eventless or multi-target transitions) are errors.
+
+use std::fmt;
+
+use crate::definition::{
+    DefinitionLimit, DefinitionLimits, FsmDefaults, FsmDefinition, FsmInvariant, FsmTransition,
+};
+
+const SCXML_NAMESPACE: &str = "http://www.w3.org/2005/07/scxml";
+const TERMINAL_STATES: &str = "terminal_states";
+
+/// Why an SCXML document could not be imported
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ScxmlError {
+    /// The document is not well-formed XML
+    Xml(String),
+    /// The root element is not `<scxml>`
+    NotScxml(String),
+    /// A required attribute is missing
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    /// The construct has no `FsmDefinition` equivalent
+    Unsupported { construct: String, reason: String },
+    /// The document or the imported definition exceeds the default `DefinitionLimits`
+    TooLarge(DefinitionLimit),
+}
+
+impl std::error::Error for ScxmlError {}
+
+impl fmt::Display for ScxmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScxmlError::Xml(message) => write!(f, "Invalid XML: {}", message),
+            ScxmlError::NotScxml(root) => {
+                write!(f, "Root element is <{}>, expected <scxml>", root)
+            }
+            ScxmlError::MissingAttribute { element, attribute } => {
+                write!(f, "<{}> is missing the '{}' attribute", element, attribute)
+            }
+            ScxmlError::Unsupported { construct, reason } => {
+                write!(f, "Cannot represent {}: {}", construct, reason)
+            }
+            ScxmlError::TooLarge(limit) => write!(f, "Definition exceeds the {:?} limit", limit),
+        }
+    }
+}
+
+/// Imported definition with the features that were skipped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScxmlImport {
+    pub definition: FsmDefinition,
+    pub warnings: Vec<String>,
+}
+
+fn unsupported(construct: impl Into<String>, reason: &str) -> ScxmlError {
+    ScxmlError::Unsupported {
+        construct: construct.into(),
+        reason: reason.to_string(),
+    }
+}
+
+fn required<'a>(
+    node: roxmltree::Node<'a, '_>,
+    attribute: &'static str,
+) -> Result<&'a str, ScxmlError> {
+    node.attribute(attribute)
+        .ok_or_else(|| ScxmlError::MissingAttribute {
+            element: node.tag_name().name().to_string(),
+            attribute,
+        })
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl FsmDefinition {
+    /// Parse an SCXML document
+    ///
+    /// The document and the imported definition must stay within the default
+    /// `DefinitionLimits`.
+    pub fn from_scxml(xml: &str) -> Result<ScxmlImport, ScxmlError> {
+        let limits = DefinitionLimits::default();
+        if xml.len() > limits.max_input_bytes {
+            return Err(ScxmlError::TooLarge(DefinitionLimit::InputBytes));
+        }
+        let document =
+            roxmltree::Document::parse(xml).map_err(|err| ScxmlError::Xml(err.to_string()))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "scxml" {
+            return Err(ScxmlError::NotScxml(root.tag_name().name().to_string()));
+        }
+
+        let mut warnings = Vec::new();
+        let mut states = Vec::new();
+        let mut finals = Vec::new();
+        let mut transitions = Vec::new();
+
+        for child in root.children().filter(|node| node.is_element()) {
+            let name = child.tag_name().name();
+            match name {
+                "state" | "final" => {
+                    let id = required(child, "id")?;
+                    states.push(id.to_string());
+                    if name == "final" {
+                        finals.push(id.to_string());
+                    }
+                    Self::read_state(child, id, &mut transitions, &mut warnings)?;
+                }
+                "parallel" => {
+                    return Err(unsupported(
+                        format!("<parallel id=\"{}\">", child.attribute("id").unwrap_or("")),
+                        "parallel states have no equivalent in a single-state FSM",
+                    ));
+                }
+                other => warnings.push(format!("ignored <{}> in <scxml>", other)),
+            }
+        }
+
+        let initial = root
+            .attribute("initial")
+            .map(str::to_string)
+            .or_else(|| states.first().cloned());
+        if let Some(initial) = &initial
+            && initial.contains(char::is_whitespace)
+        {
+            return Err(unsupported(
+                format!("initial=\"{}\"", initial),
+                "multiple initial states",
+            ));
+        }
+
+        let invariants = if finals.is_empty() {
+            Vec::new()
+        } else {
+            vec![FsmInvariant {
+                kind: TERMINAL_STATES.to_string(),
+                states: finals,
+                transitions: Vec::new(),
+                description: None,
+            }]
+        };
+
+        let definition = FsmDefinition {
+            name: root.attribute("name").map(str::to_string),
+            states,
+            transitions,
+            defaults: initial.map(|initial_state| FsmDefaults {
+                initial_state: Some(initial_state),
+            }),
+            invariants,
+        };
+        if let Some(limit) = definition.exceeded_limit(&limits) {
+            return Err(ScxmlError::TooLarge(limit));
+        }
+        Ok(ScxmlImport {
+            definition,
+            warnings,
+        })
+    }
+
+    fn read_state(
+        state: roxmltree::Node,
+        id: &str,
+        transitions: &mut Vec<FsmTransition>,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), ScxmlError> {
+        for child in state.children().filter(|node| node.is_element()) {
+            match child.tag_name().name() {
+                "transition" => {
+                    let Some(target) = child.attribute("target") else {
+                        warnings.push(format!("ignored targetless <transition> in state '{}'", id));
+                        continue;
+                    };
+                    if target.split_whitespace().count() != 1 {
+                        return Err(unsupported(
+                            format!("<transition target=\"{}\"> in state '{}'", target, id),
+                            "transitions must have exactly one target",
+                        ));
+                    }
+                    let event = child.attribute("event").ok_or_else(|| {
+                        unsupported(
+                            format!("<transition> without event in state '{}'", id),
+                            "eventless transitions have no action",
+                        )
+                    })?;
+                    if child.has_children() {
+                        warnings.push(format!(
+                            "ignored executable content in transition '{}' of state '{}'",
+                            event, id
+                        ));
+                    }
+                    transitions.push(FsmTransition {
+                        from: id.to_string(),
+                        to: target.to_string(),
+                        action: event.to_string(),
+                        guard: child.attribute("cond").map(str::to_string),
+                        metadata: None,
+                    });
+                }
+                "state" | "parallel" | "final" => {
+                    return Err(unsupported(
+                        format!("nested <{}> in state '{}'", child.tag_name().name(), id),
+                        "hierarchical states are not supported",
+                    ));
+                }
+                other => warnings.push(format!("ignored <{}> in state '{}'", other, id)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Render as an SCXML document
+    ///
+    /// States listed in a `terminal_states` invariant are written as `<final>`.
+    /// Transition metadata and other invariants have no SCXML form and are omitted.
+    pub fn to_scxml(&self) -> String {
+        let finals: Vec<&str> = self
+            .invariants
+            .iter()
+            .filter(|invariant| invariant.kind == TERMINAL_STATES)
+            .flat_map(|invariant| invariant.states.iter().map(String::as_str))
+            .collect();
+
+        let mut xml = format!("<scxml xmlns=\"{}\" version=\"1.0\"", SCXML_NAMESPACE);
+        if let Some(name) = &self.name {
+            xml.push_str(&format!(" name=\"{}\"", escape(name)));
+        }
+        if let Some(initial) = self
+            .defaults
+            .as_ref()
+            .and_then(|defaults| defaults.initial_state.as_ref())
+        {
+            xml.push_str(&format!(" initial=\"{}\"", escape(initial)));
+        }
+        xml.push_str(">\n");
+
+        for state in &self.states {
+            let outgoing: Vec<&FsmTransition> = self
+                .transitions
+                .iter()
+                .filter(|transition| &transition.from == state)
+                .collect();
+            let element = if finals.contains(&state.as_str()) && outgoing.is_empty() {
+                "final"
+            } else {
+                "state"
+            };
+            if outgoing.is_empty() {
+                xml.push_str(&format!("  <{} id=\"{}\"/>\n", element, escape(state)));
+                continue;
+            }
+            xml.push_str(&format!("  <{} id=\"{}\">\n", element, escape(state)));
+            for transition in outgoing {
+                xml.push_str(&format!(
+                    "    <transition event=\"{}\" target=\"{}\"",
+                    escape(&transition.action),
+                    escape(&transition.to)
+                ));
+                if let Some(guard) = &transition.guard {
+                    xml.push_str(&format!(" cond=\"{}\"", escape(guard)));
+                }
+                xml.push_str("/>\n");
+            }
+            xml.push_str(&format!("  </{}>\n", element));
+        }
+        xml.push_str("</scxml>\n");
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    const APPROVAL: &str = r#"<?xml version="1.0"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" name="approval" initial="Draft">
+  <datamodel><data id="votes" expr="0"/></datamodel>
+  <state id="Draft">
+    <onentry><log expr="'draft'"/></onentry>
+    <transition event="submit" target="Review"/>
+  </state>
+  <state id="Review">
+    <transition event="approve" target="Approved" cond="votes &gt; 2"/>
+    <transition event="reject" target="Rejected"/>
+  </state>
+  <final id="Approved"/>
+  <final id="Rejected"/>
+</scxml>"#;
+
+    fn transition_set(
+        definition: &FsmDefinition,
+    ) -> BTreeSet<(String, String, String, Option<String>)> {
+        definition
+            .transitions
+            .iter()
+            .map(|t| {
+                (
+                    t.from.clone(),
+                    t.to.clone(),
+                    t.action.clone(),
+                    t.guard.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn import_approval_workflow() {
+        let import = FsmDefinition::from_scxml(APPROVAL).unwrap();
+        let definition = &import.definition;
+        assert_eq!(
+            definition.states,
+            vec!["Draft", "Review", "Approved", "Rejected"]
+        );
+        assert_eq!(
+            definition
+                .defaults
+                .as_ref()
+                .unwrap()
+                .initial_state
+                .as_deref(),
+            Some("Draft")
+        );
+        assert_eq!(definition.transitions.len(), 3);
+        assert_eq!(
+            definition.transitions[1].guard.as_deref(),
+            Some("votes > 2")
+        );
+        assert_eq!(
+            definition.invariants[0].states,
+            vec!["Approved", "Rejected"]
+        );
+        assert_eq!(
+            import.warnings,
+            vec![
+                "ignored <datamodel> in <scxml>".to_string(),
+                "ignored <onentry> in state 'Draft'".to_string(),
+            ]
+        );
+        assert!(definition.validate().is_ok());
+    }
+
+    #[test]
+    fn import_enforces_limits() {
+        let long_state = APPROVAL.replace("Review", &"R".repeat(129));
+        assert_eq!(
+            FsmDefinition::from_scxml(&long_state).map(|_| ()),
+            Err(ScxmlError::TooLarge(DefinitionLimit::NameLength))
+        );
+        let huge = format!("{APPROVAL}{}", " ".repeat(16 * 1024 * 1024));
+        assert_eq!(
+            FsmDefinition::from_scxml(&huge).map(|_| ()),
+            Err(ScxmlError::TooLarge(DefinitionLimit::InputBytes))
+        );
+    }
+
+    #[test]
+    fn round_trip_preserves_transitions() {
+        let original = FsmDefinition::from_scxml(APPROVAL).unwrap().definition;
+        let exported = original.to_scxml();
+        let reimported = FsmDefinition::from_scxml(&exported).unwrap();
+
+        assert!(reimported.warnings.is_empty());
+        assert_eq!(
+            transition_set(&reimported.definition),
+            transition_set(&original)
+        );
+        assert_eq!(reimported.definition, original);
+    }
+
+    #[test]
+    fn initial_defaults_to_first_state() {
+        let xml = r#"<scxml xmlns="http://www.w3.org/2005/07/scxml">
+  <state id="A"><transition event="go" target="B"/></state>
+  <state id="B"/>
+</scxml>"#;
+        let definition = FsmDefinition::from_scxml(xml).unwrap().definition;
+        assert_eq!(
+            definition.defaults.unwrap().initial_state.as_deref(),
+            Some("A")
+        );
+    }
+
+    #[test]
+    fn parallel_states_rejected() {
+        let xml = r#"<scxml><parallel id="P"><state id="A"/></parallel></scxml>"#;
+        let err = FsmDefinition::from_scxml(xml).unwrap_err();
+        assert!(matches!(err, ScxmlError::Unsupported { .. }));
+        assert!(err.to_string().contains("parallel"));
+    }
+
+    #[test]
+    fn unrepresentable_constructs_rejected() {
+        let nested = r#"<scxml><state id="A"><state id="A1"/></state></scxml>"#;
+        assert!(
+            FsmDefinition::from_scxml(nested)
+                .unwrap_err()
+                .to_string()
+                .contains("hierarchical")
+        );
+
+        let eventless =
+            r#"<scxml><state id="A"><transition target="B"/></state><state id="B"/></scxml>"#;
+        assert!(
+            FsmDefinition::from_scxml(eventless)
+                .unwrap_err()
+                .to_string()
+                .contains("eventless")
+        );
+
+        let multi = r#"<scxml><state id="A"><transition event="e" target="B C"/></state></scxml>"#;
+        assert!(
+            FsmDefinition::from_scxml(multi)
+                .unwrap_err()
+                .to_string()
+                .contains("exactly one target")
+        );
+    }
+
+    #[test]
+    fn invalid_documents_rejected() {
+        assert!(matches!(
+            FsmDefinition::from_scxml("<scxml><state id=\"A\"></scxml>"),
+            Err(ScxmlError::Xml(_))
+        ));
+        assert_eq!(
+            FsmDefinition::from_scxml("<workflow/>"),
+            Err(ScxmlError::NotScxml("workflow".to_string()))
+        );
+        assert_eq!(
+            FsmDefinition::from_scxml("<scxml><state/></scxml>"),
+            Err(ScxmlError::MissingAttribute {
+                element: "state".to_string(),
+                attribute: "id",
+            })
+        );
+    }
+
+    #[test]
+    fn export_escapes_attributes() {
+        let xml = FsmDefinition::from_scxml(APPROVAL)
+            .unwrap()
+            .definition
+            .to_scxml();
+        assert!(xml.contains("cond=\"votes &gt; 2\""));
+        assert!(xml.contains("<final id=\"Approved\"/>"));
+    }
+}
@@ -0,0 +1,177 @@
+//! Sequence numbers for appended records
+//!
+//! A `SequenceGenerator` hands out strictly increasing `u64`s starting at 1;
+//! 0 marks a record written before it was sequenced. `AuditTrail` stamps each
+//! entry and `EventBuffer` each event with the next number as it is appended,
+//! so an indexer can order a log's records and spot any it has already seen.
+//!
+//! A generator is a handle on one counter: its clones draw from the same
+//! counter. Give an `AuditTrail` and an `EventBuffer` clones of one generator
+//! (`with_sequence`) and their records share a single total order, with no
+//! number issued to both. A log built without one gets a counter of its own.
+//!
+//! After a restart, `resume_from(max_seen)` with the highest sequence found in
+//! the persisted records of every log sharing the counter continues above it,
+//! so no number is issued twice. A generator is persisted as its `last()`
+//! number; deserializing one starts a new counter, to be shared again through
+//! `with_sequence`. Numbers may be skipped: a generator persisted ahead of its
+//! records, or numbers drawn by a log that later discarded its records, leave
+//! gaps. Readers must rely on the order of sequences, never on their being
+//! contiguous.
+
+use crate::error::FsmError;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Monotonic source of sequence numbers (see the module docs)
+///
+/// Equality compares `last()`, not counter identity; see `shares_counter`.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "GeneratorState", into = "GeneratorState")
+)]
+pub struct SequenceGenerator {
+    /// Last number handed out; 0 before the first
+    last: Arc<AtomicU64>,
+}
+
+/// Persisted form of a generator
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct GeneratorState {
+    last: u64,
+}
+
+impl From<GeneratorState> for SequenceGenerator {
+    fn from(state: GeneratorState) -> Self {
+        Self::resume_from(state.last)
+    }
+}
+
+impl From<SequenceGenerator> for GeneratorState {
+    fn from(generator: SequenceGenerator) -> Self {
+        Self {
+            last: generator.last(),
+        }
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for SequenceGenerator {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&GeneratorState { last: self.last() }, writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for SequenceGenerator {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        GeneratorState::deserialize_reader(reader).map(Self::from)
+    }
+}
+
+impl PartialEq for SequenceGenerator {
+    fn eq(&self, other: &Self) -> bool {
+        self.last() == other.last()
+    }
+}
+
+impl Eq for SequenceGenerator {}
+
+impl SequenceGenerator {
+    /// Generator whose first number is 1
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generator continuing after `max_seen`, the highest sequence already persisted
+    pub fn resume_from(max_seen: u64) -> Self {
+        Self {
+            last: Arc::new(AtomicU64::new(max_seen)),
+        }
+    }
+
+    /// Last number handed out; 0 if none
+    pub fn last(&self) -> u64 {
+        self.last.load(Ordering::SeqCst)
+    }
+
+    /// Hand out the next number; `Overflow` once `u64::MAX` has been issued
+    pub fn issue(&self) -> Result<u64, FsmError> {
+        self.last
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+                last.checked_add(1)
+            })
+            .map(|previous| previous + 1)
+            .map_err(|_| FsmError::Overflow)
+    }
+
+    /// Whether `other` draws from the same counter
+    pub fn shares_counter(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.last, &other.last)
+    }
+}
+
+/// `InvalidState` unless `sequences` are strictly increasing and nonzero
+pub fn check_increasing(sequences: impl IntoIterator<Item = u64>) -> Result<(), FsmError> {
+    let mut previous = 0;
+    for sequence in sequences {
+        if sequence <= previous {
+            return Err(FsmError::InvalidState);
+        }
+        previous = sequence;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_continues_above_max_seen() {
+        let generator = SequenceGenerator::new();
+        assert_eq!((generator.issue(), generator.issue()), (Ok(1), Ok(2)));
+        let resumed = SequenceGenerator::resume_from(generator.last());
+        assert_eq!(resumed.issue(), Ok(3));
+        assert!(!resumed.shares_counter(&generator));
+        let exhausted = SequenceGenerator::resume_from(u64::MAX);
+        assert_eq!(exhausted.issue(), Err(FsmError::Overflow));
+        assert_eq!(exhausted.last(), u64::MAX);
+    }
+
+    #[test]
+    fn test_clones_share_the_counter() {
+        let generator = SequenceGenerator::new();
+        let clone = generator.clone();
+        assert_eq!((generator.issue(), clone.issue()), (Ok(1), Ok(2)));
+        assert_eq!(generator.last(), 2);
+        assert!(clone.shares_counter(&generator));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_persisted_as_last_number() {
+        let generator = SequenceGenerator::resume_from(7);
+        let json = serde_json::to_string(&generator).unwrap();
+        assert_eq!(json, r#"{"last":7}"#);
+        let restored: SequenceGenerator = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, generator);
+        assert!(!restored.shares_counter(&generator));
+    }
+
+    #[test]
+    fn test_check_increasing_allows_gaps_not_duplicates() {
+        assert_eq!(check_increasing([1, 2, 5, 9]), Ok(()));
+        assert_eq!(check_increasing([]), Ok(()));
+        assert_eq!(check_increasing([1, 3, 3]), Err(FsmError::InvalidState));
+        assert_eq!(check_increasing([2, 1]), Err(FsmError::InvalidState));
+        assert_eq!(check_increasing([0, 1]), Err(FsmError::InvalidState));
+    }
+}
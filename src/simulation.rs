@@ -0,0 +1,260 @@
+//! Shadow evaluation of candidate governance parameters.
+//!
+//! `shadow_evaluate` replays the recorded tallies of finalized proposals
+//! under a candidate `GovernanceParams` and reports which outcomes would
+//! differ, so a quorum change can be judged against past votes before it is
+//! applied. It only reads its inputs, and the same inputs give the same
+//! report.
+//!
+//! A proposal is finalized once its vote settled it: Passed, Executing,
+//! Executed, Rejected, Tied or QuorumFailed. Quorum is met when
+//! `total_votes * 100 >= quorum_percentage * eligible`, as in
+//! `Grant::finalize_voting_with_quorum`; a proposal meeting it is decided by
+//! its own tally policy, and a tie is settled by its tie-break policy as far
+//! as the record allows (see `shadow_outcome`). Only the quorum percentage
+//! changes outcomes: recorded tallies are already final, so the voting
+//! duration and early quorum of the candidate have nothing to act on.
+
+use crate::governance_params::GovernanceParams;
+use crate::proposal::{Proposal, ProposalStatus, TieBreakPolicy};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How the vote on a proposal ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VoteOutcome {
+    Passed,
+    Rejected,
+    Tied,
+    QuorumFailed,
+}
+
+impl VoteOutcome {
+    /// The recorded outcome of a finalized proposal; `None` if its vote has not settled it
+    pub fn recorded(status: &ProposalStatus) -> Option<Self> {
+        match status {
+            ProposalStatus::Passed | ProposalStatus::Executing | ProposalStatus::Executed => {
+                Some(VoteOutcome::Passed)
+            }
+            ProposalStatus::Rejected => Some(VoteOutcome::Rejected),
+            ProposalStatus::Tied => Some(VoteOutcome::Tied),
+            ProposalStatus::QuorumFailed => Some(VoteOutcome::QuorumFailed),
+            ProposalStatus::Draft
+            | ProposalStatus::Active
+            | ProposalStatus::Cancelled
+            | ProposalStatus::Archived => None,
+        }
+    }
+}
+
+/// A proposal whose outcome differs under the candidate parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OutcomeFlip {
+    pub proposal_id: u64,
+    pub recorded: VoteOutcome,
+    pub shadow: VoteOutcome,
+}
+
+/// Result of `shadow_evaluate`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ShadowReport {
+    /// Finalized proposals replayed under the candidate
+    pub evaluated: usize,
+    /// Finalized proposals without usable tally data: no eligible count, a
+    /// zero eligible count, or a clamped tally (`tally_degraded`)
+    pub skipped: usize,
+    /// Shadow outcomes of the evaluated proposals, in `VoteOutcome` order
+    pub outcomes: BTreeMap<VoteOutcome, usize>,
+    /// Evaluated proposals whose outcome would change, by ascending id
+    pub flips: Vec<OutcomeFlip>,
+}
+
+impl ShadowReport {
+    /// Evaluated proposals whose outcome would stay the same
+    pub fn unchanged(&self) -> usize {
+        self.evaluated - self.flips.len()
+    }
+}
+
+/// Outcome of `proposal`'s recorded tally under `quorum_percentage` of `eligible`
+///
+/// A tie falls to StatusQuo and AuthorLoses as a rejection, and to
+/// ChairDecides as a pass only if the recorded vote was settled by it; the
+/// board decision itself is not part of the record.
+pub fn shadow_outcome<P>(
+    proposal: &Proposal<P>,
+    quorum_percentage: u8,
+    eligible: u64,
+) -> VoteOutcome {
+    let participating = u128::from(proposal.total_votes) * 100;
+    if participating < u128::from(quorum_percentage) * u128::from(eligible) {
+        return VoteOutcome::QuorumFailed;
+    }
+    match proposal
+        .tally_policy
+        .decide(proposal.yes_votes, proposal.no_votes)
+    {
+        Some(true) => VoteOutcome::Passed,
+        Some(false) => VoteOutcome::Rejected,
+        None => match proposal.tie_break_policy {
+            TieBreakPolicy::Manual => VoteOutcome::Tied,
+            TieBreakPolicy::StatusQuo | TieBreakPolicy::AuthorLoses => VoteOutcome::Rejected,
+            TieBreakPolicy::ChairDecides { .. } if proposal.tie_break_applied.is_some() => {
+                VoteOutcome::Passed
+            }
+            TieBreakPolicy::ChairDecides { .. } => VoteOutcome::Tied,
+        },
+    }
+}
+
+/// Replay finalized `proposals` under `candidate` (see the module docs)
+///
+/// `historical_eligible` holds `(proposal id, eligible voting weight)` as it
+/// was when each vote closed; the first entry for an id is used. Proposals
+/// that are not finalized are ignored.
+pub fn shadow_evaluate<P>(
+    proposals: &[Proposal<P>],
+    historical_eligible: &[(u64, u64)],
+    candidate: &GovernanceParams,
+) -> ShadowReport {
+    let mut eligible_by_id = BTreeMap::new();
+    for (id, eligible) in historical_eligible {
+        eligible_by_id.entry(*id).or_insert(*eligible);
+    }
+    let mut report = ShadowReport::default();
+    for proposal in proposals {
+        let Some(recorded) = VoteOutcome::recorded(&proposal.status) else {
+            continue;
+        };
+        let eligible = eligible_by_id.get(&proposal.id).copied().unwrap_or(0);
+        if eligible == 0 || proposal.tally_degraded {
+            report.skipped += 1;
+            continue;
+        }
+        let shadow = shadow_outcome(proposal, candidate.quorum_percentage, eligible);
+        report.evaluated += 1;
+        *report.outcomes.entry(shadow).or_insert(0) += 1;
+        if shadow != recorded {
+            report.flips.push(OutcomeFlip {
+                proposal_id: proposal.id,
+                recorded,
+                shadow,
+            });
+        }
+    }
+    report.flips.sort_by_key(|flip| flip.proposal_id);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_finalized_proposal(
+        id: u64,
+        yes: u64,
+        no: u64,
+        status: ProposalStatus,
+    ) -> Proposal<u8> {
+        let mut proposal = Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .build(0)
+        .unwrap();
+        proposal.yes_votes = yes;
+        proposal.no_votes = no;
+        proposal.total_votes = yes + no;
+        proposal.status = status;
+        proposal
+    }
+
+    fn history() -> (Vec<Proposal<u8>>, Vec<(u64, u64)>) {
+        let proposals = vec![
+            create_finalized_proposal(4, 30, 10, ProposalStatus::Executed),
+            create_finalized_proposal(1, 60, 20, ProposalStatus::Passed),
+            create_finalized_proposal(2, 10, 50, ProposalStatus::Rejected),
+            create_finalized_proposal(3, 5, 5, ProposalStatus::QuorumFailed),
+            create_finalized_proposal(5, 0, 0, ProposalStatus::Active),
+            create_finalized_proposal(6, 40, 40, ProposalStatus::Tied),
+        ];
+        let eligible = vec![(1, 100), (2, 100), (3, 100), (4, 100), (5, 100), (6, 100)];
+        (proposals, eligible)
+    }
+
+    fn params(quorum_percentage: u8) -> GovernanceParams {
+        GovernanceParams::new(quorum_percentage, 72, 0, false, 0).unwrap()
+    }
+
+    #[test]
+    fn test_current_params_reproduce_history() {
+        let (proposals, eligible) = history();
+        let report = shadow_evaluate(&proposals, &eligible, &params(20));
+        assert_eq!(report.evaluated, 5);
+        assert_eq!(report.skipped, 0);
+        assert!(report.flips.is_empty());
+        assert_eq!(report.unchanged(), 5);
+    }
+
+    #[test]
+    fn test_raising_quorum_flips_one_proposal() {
+        let (proposals, eligible) = history();
+        let before = proposals.clone();
+        let report = shadow_evaluate(&proposals, &eligible, &params(45));
+        // Only proposal 4 (40 of 100 voted) falls below the new quorum
+        assert_eq!(
+            report.flips,
+            vec![OutcomeFlip {
+                proposal_id: 4,
+                recorded: VoteOutcome::Passed,
+                shadow: VoteOutcome::QuorumFailed,
+            }]
+        );
+        assert_eq!(
+            report.outcomes,
+            BTreeMap::from([
+                (VoteOutcome::Passed, 1),
+                (VoteOutcome::Rejected, 1),
+                (VoteOutcome::Tied, 1),
+                (VoteOutcome::QuorumFailed, 2),
+            ])
+        );
+        assert_eq!(proposals, before);
+        let mut reversed = proposals.clone();
+        reversed.reverse();
+        assert_eq!(shadow_evaluate(&reversed, &eligible, &params(45)), report);
+    }
+
+    #[test]
+    fn test_missing_tally_data_is_skipped() {
+        let (mut proposals, _) = history();
+        proposals[1].tally_degraded = true;
+        // No count for 2, zero for 3; the first entry for 4 wins
+        let eligible = vec![(1, 100), (3, 0), (4, 100), (4, 1), (6, 100)];
+        let report = shadow_evaluate(&proposals, &eligible, &params(20));
+        assert_eq!((report.evaluated, report.skipped), (2, 3));
+        assert!(report.flips.is_empty());
+    }
+
+    #[test]
+    fn test_ties_follow_the_tie_break_policy() {
+        let mut proposal = create_finalized_proposal(1, 40, 40, ProposalStatus::Rejected);
+        proposal.tie_break_policy = TieBreakPolicy::StatusQuo;
+        assert_eq!(shadow_outcome(&proposal, 50, 100), VoteOutcome::Rejected);
+        proposal.tie_break_policy = TieBreakPolicy::ChairDecides { decision_ref: 1 };
+        assert_eq!(shadow_outcome(&proposal, 50, 100), VoteOutcome::Tied);
+        proposal.tie_break_applied = Some(proposal.tie_break_policy);
+        assert_eq!(shadow_outcome(&proposal, 50, 100), VoteOutcome::Passed);
+        assert_eq!(
+            shadow_outcome(&proposal, 90, 100),
+            VoteOutcome::QuorumFailed
+        );
+    }
+}
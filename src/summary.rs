@@ -0,0 +1,271 @@
+//! Status badges for mixed entity lists.
+//!
+//! `entity_summaries` flattens proposals and grants into one list of
+//! `EntitySummary` rows for dashboards: a short title, the status, the next
+//! calendar deadline and the reasons the entity needs attention. Rows needing
+//! attention come first, then by nearest deadline.
+
+use crate::calendar::{self, Deadline, EntityKind};
+use crate::grant::{Grant, GrantStatus};
+use crate::proposal::{Proposal, ProposalStatus};
+use crate::reason::SuspensionCode;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Maximum title length in characters, ellipsis included
+pub const MAX_SUMMARY_TITLE_LEN: usize = 80;
+
+/// Why an entity is flagged on a dashboard
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AttentionReason {
+    /// Proposal vote ended in a tie
+    Tied,
+    /// Voting period of an Active proposal ended with no votes cast
+    QuorumFailing,
+    /// Grant has an open dispute
+    Disputed,
+    /// Grant is suspended for a missed milestone
+    MilestoneMissed,
+    /// A deadline of the entity has passed
+    Overdue,
+}
+
+/// One dashboard row
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntitySummary {
+    pub kind: EntityKind,
+    pub id: u64,
+    /// At most `MAX_SUMMARY_TITLE_LEN` characters; `None` for untitled entities
+    pub title: Option<String>,
+    /// Status variant name, e.g. `"Active"`
+    pub status: String,
+    /// Earliest deadline from `calendar::upcoming`, overdue ones included
+    pub next_deadline: Option<Deadline>,
+    /// Sorted and deduplicated; empty if nothing needs attention
+    pub attention: Vec<AttentionReason>,
+}
+
+impl EntitySummary {
+    pub fn needs_attention(&self) -> bool {
+        !self.attention.is_empty()
+    }
+}
+
+/// Summaries of `proposals` and `grants` at `current_time`
+///
+/// Rows needing attention come first; within each group rows are ordered by
+/// next deadline (rows without one last), then kind and id. Archived
+/// proposals are included but have no deadline.
+pub fn entity_summaries<P>(
+    proposals: &[Proposal<P>],
+    grants: &[Grant],
+    current_time: i64,
+) -> Vec<EntitySummary> {
+    let deadlines = calendar::upcoming(proposals, grants, i64::MAX, current_time);
+    let next_deadline = |kind: EntityKind, id: u64| {
+        deadlines
+            .iter()
+            .find(|d| d.entity_kind == kind && d.entity_id == id)
+            .copied()
+    };
+
+    let mut summaries = Vec::with_capacity(proposals.len() + grants.len());
+    for proposal in proposals {
+        let next_deadline = next_deadline(EntityKind::Proposal, proposal.id);
+        let mut attention = Vec::new();
+        if proposal.status == ProposalStatus::Tied {
+            attention.push(AttentionReason::Tied);
+        }
+        if proposal.status == ProposalStatus::Active
+            && proposal.total_votes == 0
+            && deadlines.iter().any(|d| {
+                d.entity_kind == EntityKind::Proposal
+                    && d.entity_id == proposal.id
+                    && d.deadline_type == calendar::DeadlineType::VotingEnd
+                    && d.overdue
+            })
+        {
+            attention.push(AttentionReason::QuorumFailing);
+        }
+        if next_deadline.is_some_and(|d| d.overdue) {
+            attention.push(AttentionReason::Overdue);
+        }
+        summaries.push(EntitySummary {
+            kind: EntityKind::Proposal,
+            id: proposal.id,
+            title: Some(truncate_title(&proposal.title)),
+            status: format!("{:?}", proposal.status),
+            next_deadline,
+            attention,
+        });
+    }
+    for grant in grants {
+        let next_deadline = next_deadline(EntityKind::Grant, grant.id);
+        let mut attention = Vec::new();
+        if grant.has_open_dispute() {
+            attention.push(AttentionReason::Disputed);
+        }
+        if grant.status == GrantStatus::Suspended
+            && grant.suspension_code == Some(SuspensionCode::MilestoneMissed)
+        {
+            attention.push(AttentionReason::MilestoneMissed);
+        }
+        if next_deadline.is_some_and(|d| d.overdue) {
+            attention.push(AttentionReason::Overdue);
+        }
+        summaries.push(EntitySummary {
+            kind: EntityKind::Grant,
+            id: grant.id,
+            title: None,
+            status: format!("{:?}", grant.status),
+            next_deadline,
+            attention,
+        });
+    }
+
+    summaries.sort_by_key(|s| {
+        (
+            !s.needs_attention(),
+            s.next_deadline.map_or((1, 0), |d| (0, d.at)),
+            s.kind,
+            s.id,
+        )
+    });
+    summaries
+}
+
+/// `title` cut to `MAX_SUMMARY_TITLE_LEN` characters, ending in `…` if cut
+fn truncate_title(title: &str) -> String {
+    if title.chars().count() <= MAX_SUMMARY_TITLE_LEN {
+        return title.to_string();
+    }
+    let mut truncated: String = title.chars().take(MAX_SUMMARY_TITLE_LEN - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::dispute::{Dispute, DisputeStatus};
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantType};
+
+    fn proposal(id: u64, title: &str) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            title.to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap()
+    }
+
+    fn grant(id: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            1_000,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    fn fixtures() -> (Vec<Proposal<u8>>, Vec<Grant>) {
+        let draft = proposal(1, "Draft");
+        let mut silent = proposal(2, "No votes");
+        silent.activate_with_time(1, 100, 0).unwrap();
+        let mut active = proposal(3, &"Long title ".repeat(10));
+        active.activate_with_time(1, 100, 250).unwrap();
+        let mut tied = proposal(4, "Tied");
+        tied.status = ProposalStatus::Tied;
+        tied.expires_at = Some(900);
+
+        let quiet = grant(10);
+        let mut disputed = grant(11);
+        disputed.disputes.push(Dispute {
+            raised_by: [1; 32],
+            reason: "Unpaid".to_string(),
+            raised_at: 200,
+            status: DisputeStatus::Open,
+            resolved_by: None,
+            resolved_at: None,
+        });
+        let mut missed = grant(12);
+        missed.status = GrantStatus::Suspended;
+        missed.suspension_code = Some(SuspensionCode::MilestoneMissed);
+        (
+            vec![draft, silent, active, tied],
+            vec![quiet, disputed, missed],
+        )
+    }
+
+    #[test]
+    fn test_attention_and_order() {
+        let (proposals, grants) = fixtures();
+        let summaries = entity_summaries(&proposals, &grants, 300);
+        let rows: Vec<_> = summaries
+            .iter()
+            .map(|s| (s.kind, s.id, s.attention.clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    EntityKind::Proposal,
+                    2,
+                    vec![AttentionReason::QuorumFailing, AttentionReason::Overdue]
+                ),
+                (EntityKind::Proposal, 4, vec![AttentionReason::Tied]),
+                (EntityKind::Grant, 11, vec![AttentionReason::Disputed]),
+                (
+                    EntityKind::Grant,
+                    12,
+                    vec![AttentionReason::MilestoneMissed]
+                ),
+                (EntityKind::Proposal, 3, vec![]),
+                (EntityKind::Proposal, 1, vec![]),
+                (EntityKind::Grant, 10, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_title_truncation() {
+        assert_eq!(truncate_title("Short"), "Short");
+        let exact = "é".repeat(MAX_SUMMARY_TITLE_LEN);
+        assert_eq!(truncate_title(&exact), exact);
+        let long = truncate_title(&"é".repeat(MAX_SUMMARY_TITLE_LEN + 1));
+        assert_eq!(long.chars().count(), MAX_SUMMARY_TITLE_LEN);
+        assert!(long.ends_with('…'));
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        assert!(entity_summaries::<u8>(&[], &[], 0).is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialized_snapshot() {
+        let (proposals, grants) = fixtures();
+        let summaries = entity_summaries(&proposals[2..], &grants[..2], 300);
+        let expected = concat!(
+            r#"[{"kind":"Proposal","id":4,"title":"Tied","status":"Tied","next_deadline":null,"attention":["Tied"]},"#,
+            r#"{"kind":"Grant","id":11,"title":null,"status":"Pending","next_deadline":null,"attention":["Disputed"]},"#,
+            r#"{"kind":"Proposal","id":3,"title":"Long title Long title Long title Long title Long title Long title Long title Lo…","status":"Active","next_deadline":{"entity_kind":"Proposal","entity_id":3,"deadline_type":"VotingEnd","at":350,"overdue":false},"attention":[]},"#,
+            r#"{"kind":"Grant","id":10,"title":null,"status":"Pending","next_deadline":null,"attention":[]}]"#,
+        );
+        assert_eq!(serde_json::to_string(&summaries).unwrap(), expected);
+    }
+}
@@ -0,0 +1,251 @@
+//! Tags on proposals and grants.
+//!
+//! Tags are free-form labels for analytics and filtering ("infrastructure",
+//! "marketing"). They are trimmed and lowercased on the way in, limited to
+//! `MAX_TAG_LEN` characters and `MAX_TAGS` per entity, and kept sorted without
+//! duplicates. A `TagVocabulary` restricts them to a fixed set.
+
+use crate::grant::Grant;
+use crate::proposal::Proposal;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Maximum characters in one tag
+pub const MAX_TAG_LEN: usize = 30;
+/// Maximum tags on one entity
+pub const MAX_TAGS: usize = 10;
+
+/// Why a tag was refused
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagError {
+    /// The tag is empty after trimming
+    Empty,
+    /// The tag is longer than `MAX_TAG_LEN` characters
+    TooLong(String),
+    /// The entity already has `MAX_TAGS` tags
+    TooMany,
+    /// The tag is not in the vocabulary
+    Unknown(String),
+}
+
+impl std::error::Error for TagError {}
+
+impl fmt::Display for TagError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TagError::Empty => write!(f, "Tag is empty"),
+            TagError::TooLong(tag) => {
+                write!(f, "Tag '{}' is longer than {} characters", tag, MAX_TAG_LEN)
+            }
+            TagError::TooMany => write!(f, "At most {} tags are allowed", MAX_TAGS),
+            TagError::Unknown(tag) => write!(f, "Tag '{}' is not in the vocabulary", tag),
+        }
+    }
+}
+
+/// Allowed tags
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TagVocabulary {
+    tags: BTreeSet<String>,
+}
+
+impl TagVocabulary {
+    /// Vocabulary of `tags`, each normalized like an added tag
+    pub fn new<I, S>(tags: I) -> Result<Self, TagError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let tags = tags
+            .into_iter()
+            .map(|tag| normalize_tag(tag.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { tags })
+    }
+
+    /// Whether `tag` (normalized) is allowed
+    pub fn contains(&self, tag: &str) -> bool {
+        normalize_tag(tag).is_ok_and(|tag| self.tags.contains(&tag))
+    }
+
+    /// Allowed tags in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+}
+
+/// Trim and lowercase `tag`, refusing empty or overlong ones
+pub fn normalize_tag(tag: &str) -> Result<String, TagError> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err(TagError::Empty);
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(TagError::TooLong(tag));
+    }
+    Ok(tag)
+}
+
+/// Insert `tag` into sorted `tags`; false if it was already there
+pub(crate) fn insert_tag(
+    tags: &mut Vec<String>,
+    tag: &str,
+    vocabulary: Option<&TagVocabulary>,
+) -> Result<bool, TagError> {
+    let tag = normalize_tag(tag)?;
+    if vocabulary.is_some_and(|v| !v.tags.contains(&tag)) {
+        return Err(TagError::Unknown(tag));
+    }
+    match tags.binary_search(&tag) {
+        Ok(_) => Ok(false),
+        Err(_) if tags.len() >= MAX_TAGS => Err(TagError::TooMany),
+        Err(index) => {
+            tags.insert(index, tag);
+            Ok(true)
+        }
+    }
+}
+
+/// Remove `tag` from `tags`; false if it was not there
+pub(crate) fn remove_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    let before = tags.len();
+    tags.retain(|t| *t != tag);
+    tags.len() != before
+}
+
+/// Whether `tags` holds `tag` (normalized)
+pub(crate) fn has_tag(tags: &[String], tag: &str) -> bool {
+    let tag = tag.trim().to_lowercase();
+    tags.contains(&tag)
+}
+
+impl<P> Proposal<P> {
+    /// Add `tag`, optionally restricted to `vocabulary`; false if already present
+    pub fn add_tag(
+        &mut self,
+        tag: &str,
+        vocabulary: Option<&TagVocabulary>,
+    ) -> Result<bool, TagError> {
+        insert_tag(&mut self.tags, tag, vocabulary)
+    }
+
+    /// Remove `tag`; false if it was not present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        remove_tag(&mut self.tags, tag)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
+}
+
+impl Grant {
+    /// Add `tag`, optionally restricted to `vocabulary`; false if already present
+    pub fn add_tag(
+        &mut self,
+        tag: &str,
+        vocabulary: Option<&TagVocabulary>,
+    ) -> Result<bool, TagError> {
+        insert_tag(&mut self.tags, tag, vocabulary)
+    }
+
+    /// Remove `tag`; false if it was not present
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        remove_tag(&mut self.tags, tag)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        has_tag(&self.tags, tag)
+    }
+}
+
+/// Serialize tags sorted and deduplicated, whatever order the field holds
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_sorted<S: serde::Serializer>(
+    tags: &[String],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(tags.iter().collect::<BTreeSet<_>>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_normalized_and_sorted() {
+        let mut tags = Vec::new();
+        assert_eq!(insert_tag(&mut tags, " Marketing ", None), Ok(true));
+        assert_eq!(insert_tag(&mut tags, "infrastructure", None), Ok(true));
+        assert_eq!(insert_tag(&mut tags, "MARKETING", None), Ok(false));
+        assert_eq!(tags, vec!["infrastructure", "marketing"]);
+        assert!(has_tag(&tags, "Infrastructure"));
+        assert!(remove_tag(&mut tags, "INFRASTRUCTURE"));
+        assert!(!remove_tag(&mut tags, "infrastructure"));
+        assert_eq!(tags, vec!["marketing"]);
+    }
+
+    #[test]
+    fn tag_limits() {
+        let mut tags = Vec::new();
+        assert_eq!(insert_tag(&mut tags, "  ", None), Err(TagError::Empty));
+        let long = "a".repeat(MAX_TAG_LEN + 1);
+        assert_eq!(
+            insert_tag(&mut tags, &long, None),
+            Err(TagError::TooLong(long))
+        );
+        assert_eq!(
+            insert_tag(&mut tags, &"é".repeat(MAX_TAG_LEN), None),
+            Ok(true)
+        );
+        for i in 1..MAX_TAGS {
+            insert_tag(&mut tags, &format!("tag{}", i), None).unwrap();
+        }
+        assert_eq!(
+            insert_tag(&mut tags, "one-more", None),
+            Err(TagError::TooMany)
+        );
+        // Re-adding an existing tag is not an error at the limit
+        assert_eq!(insert_tag(&mut tags, "tag1", None), Ok(false));
+    }
+
+    #[test]
+    fn vocabulary_names_unknown_tag() {
+        let vocabulary = TagVocabulary::new(["Infrastructure", "marketing"]).unwrap();
+        assert!(vocabulary.contains("INFRASTRUCTURE"));
+        assert_eq!(
+            vocabulary.iter().collect::<Vec<_>>(),
+            vec!["infrastructure", "marketing"]
+        );
+        let mut tags = Vec::new();
+        assert_eq!(
+            insert_tag(&mut tags, "Infrastructure", Some(&vocabulary)),
+            Ok(true)
+        );
+        let error = insert_tag(&mut tags, "Sports", Some(&vocabulary)).unwrap_err();
+        assert_eq!(error, TagError::Unknown("sports".to_string()));
+        assert_eq!(error.to_string(), "Tag 'sports' is not in the vocabulary");
+        assert_eq!(TagVocabulary::new([""]), Err(TagError::Empty));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tags_serialize_sorted() {
+        let mut grant = Grant::default();
+        grant.add_tag("marketing", None).unwrap();
+        // Pushed directly, bypassing `add_tag`
+        grant.tags.push("infrastructure".to_string());
+        grant.tags.push("marketing".to_string());
+        let json = serde_json::to_value(&grant).unwrap();
+        assert_eq!(
+            json["tags"],
+            serde_json::json!(["infrastructure", "marketing"])
+        );
+
+        let mut legacy = json.clone();
+        legacy.as_object_mut().unwrap().remove("tags");
+        let decoded: Grant = serde_json::from_value(legacy).unwrap();
+        assert!(decoded.tags.is_empty());
+    }
+}
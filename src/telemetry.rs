@@ -0,0 +1,182 @@
+//! Tracing spans for lifecycle operations.
+//!
+//! Enabled with the `tracing` feature; without it every call here compiles to
+//! nothing. Span and field names are part of the public contract:
+//!
+//! - `fsm.transition`: `entity`, `entity_id`, `action`, `from`, `to`, `error`
+//! - `fsm.definition.validate`: `states`, `transitions`, `error`
+//!
+//! `to` is recorded on success and `error` (the `FsmError` variant name) on
+//! failure. A `DEBUG` event is emitted for applied transitions and a `WARN`
+//! event for rejected ones, both inside the span.
+
+use crate::error::FsmError;
+use std::fmt::Debug;
+
+/// Span name for proposal/grant lifecycle steps
+pub const TRANSITION_SPAN: &str = "fsm.transition";
+/// Span name for `FsmDefinition::validate`
+pub const DEFINITION_VALIDATE_SPAN: &str = "fsm.definition.validate";
+
+/// Span guarding one operation; a no-op without the `tracing` feature
+pub(crate) struct OperationSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl OperationSpan {
+    /// Open a `fsm.transition` span
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn transition(entity: &str, entity_id: u64, action: &str, from: &dyn Debug) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "fsm.transition",
+                entity,
+                entity_id,
+                action,
+                from = ?from,
+                to = tracing::field::Empty,
+                error = tracing::field::Empty,
+            ),
+        }
+    }
+
+    /// Open a `fsm.definition.validate` span
+    #[cfg(feature = "definitions")]
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn definition_validate(states: usize, transitions: usize) -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::info_span!(
+                "fsm.definition.validate",
+                states,
+                transitions,
+                error = tracing::field::Empty,
+            ),
+        }
+    }
+
+    /// Record the outcome and close the span
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    pub(crate) fn finish<T>(self, to: Option<&dyn Debug>, result: &Result<T, FsmError>) {
+        #[cfg(feature = "tracing")]
+        {
+            let _entered = self.span.enter();
+            match result {
+                Ok(_) => {
+                    if let Some(to) = to {
+                        self.span.record("to", tracing::field::debug(to));
+                    }
+                    tracing::debug!("operation applied");
+                }
+                Err(err) => {
+                    self.span.record("error", tracing::field::debug(err));
+                    tracing::warn!(error = ?err, "operation rejected");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use crate::proposal::Proposal;
+    use std::collections::BTreeMap;
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    type Spans = Arc<Mutex<BTreeMap<u64, (String, BTreeMap<String, String>)>>>;
+
+    struct FieldVisitor<'a>(&'a mut BTreeMap<String, String>);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(
+                field.name().to_string(),
+                format!("{value:?}").replace('"', ""),
+            );
+        }
+    }
+
+    struct CaptureLayer(Spans);
+
+    impl<S: tracing::Subscriber> Layer<S> for CaptureLayer {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = BTreeMap::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            self.0
+                .lock()
+                .unwrap()
+                .insert(id.into_u64(), (attrs.metadata().name().to_string(), fields));
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            if let Some((_, fields)) = self.0.lock().unwrap().get_mut(&id.into_u64()) {
+                values.record(&mut FieldVisitor(fields));
+            }
+        }
+    }
+
+    fn capture(f: impl FnOnce()) -> Vec<(String, BTreeMap<String, String>)> {
+        let spans = Spans::default();
+        let subscriber = tracing_subscriber::registry().with(CaptureLayer(spans.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+        let spans = spans.lock().unwrap();
+        spans.values().cloned().collect()
+    }
+
+    fn field<'a>(fields: &'a BTreeMap<String, String>, name: &str) -> Option<&'a str> {
+        fields.get(name).map(String::as_str)
+    }
+
+    #[test]
+    fn test_failed_transition_span_fields() {
+        let spans = capture(|| {
+            let mut proposal = Proposal::<u8>::new_with_time(
+                7,
+                "Test".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                1,
+                0,
+            )
+            .unwrap();
+            assert!(proposal.execute_with_time(10).is_err());
+        });
+
+        assert_eq!(spans.len(), 1);
+        let (name, fields) = &spans[0];
+        assert_eq!(name, TRANSITION_SPAN);
+        assert_eq!(field(fields, "entity"), Some("proposal"));
+        assert_eq!(field(fields, "entity_id"), Some("7"));
+        assert_eq!(field(fields, "action"), Some("execute"));
+        assert_eq!(field(fields, "from"), Some("Draft"));
+        assert_eq!(field(fields, "error"), Some("InvalidStateTransition"));
+        assert_eq!(field(fields, "to"), None);
+    }
+
+    #[test]
+    fn test_successful_transition_records_to() {
+        let spans = capture(|| {
+            let mut proposal = Proposal::<u8>::new_with_time(
+                7,
+                "Test".to_string(),
+                "Description".to_string(),
+                "governance".to_string(),
+                1,
+                0,
+            )
+            .unwrap();
+            proposal.activate_with_time(1, 10, 0).unwrap();
+        });
+
+        let (_, fields) = &spans[0];
+        assert_eq!(field(fields, "to"), Some("Active"));
+        assert_eq!(field(fields, "error"), None);
+    }
+}
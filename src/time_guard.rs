@@ -0,0 +1,214 @@
+//! Monotonic time enforcement for lifecycle calls.
+//!
+//! Proposals and grants remember the timestamp of their latest event in
+//! `last_event_at`. Every timestamped lifecycle method checks the caller's
+//! `current_time` against it with the entity's `TimeOrdering`, so records such
+//! as `executed_at < submitted_at` cannot be produced. Construction rejects
+//! negative timestamps.
+//!
+//! Services with slightly skewed clocks can set a clock skew tolerance on an
+//! entity (`clock_skew_tolerance_seconds`, 0 by default). A lifecycle
+//! transition timestamped at most that far before the earliest acceptable time
+//! is then accepted and recorded at that earliest time instead (`resolve`);
+//! larger regressions still fail. Votes, sponsorship and other non-transition
+//! events keep the plain `check`.
+//!
+//! `SimClock` is a manually advanced clock for simulations and examples; it
+//! only moves forward, so the times it hands out always pass `AllowEqual`.
+
+use crate::duration::DurationSecs;
+use crate::error::FsmError;
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How `current_time` must relate to an entity's `last_event_at`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TimeOrdering {
+    /// `current_time >= last_event_at`; several operations may share a second
+    #[default]
+    AllowEqual,
+    /// `current_time > last_event_at`
+    Strict,
+    /// No check; for tests and simulations that replay events out of order
+    Unchecked,
+}
+
+impl TimeOrdering {
+    /// `TimeRegression` if `current_time` is not acceptable after `last_event_at`
+    pub fn check(self, last_event_at: i64, current_time: i64) -> Result<(), FsmError> {
+        let ok = match self {
+            TimeOrdering::AllowEqual => current_time >= last_event_at,
+            TimeOrdering::Strict => current_time > last_event_at,
+            TimeOrdering::Unchecked => true,
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(FsmError::TimeRegression)
+        }
+    }
+}
+
+/// Time an event is recorded at, as decided by `TimeOrdering::resolve`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EventTime {
+    pub time: i64,
+    /// `time` was moved forward from the caller's timestamp to absorb clock skew
+    pub clamped: bool,
+}
+
+impl TimeOrdering {
+    /// `check`, absorbing up to `tolerance_secs` of clock skew
+    ///
+    /// A `current_time` that `check` refuses but that is at most
+    /// `tolerance_secs` before the earliest acceptable time (`last_event_at`,
+    /// or one second later under `Strict`) is clamped to that time. With a
+    /// tolerance of 0 this is exactly `check`.
+    pub fn resolve(
+        self,
+        last_event_at: i64,
+        current_time: i64,
+        tolerance_secs: u32,
+    ) -> Result<EventTime, FsmError> {
+        let earliest = match self {
+            TimeOrdering::AllowEqual => last_event_at,
+            TimeOrdering::Strict => last_event_at.saturating_add(1),
+            TimeOrdering::Unchecked => current_time,
+        };
+        if current_time >= earliest {
+            return Ok(EventTime {
+                time: current_time,
+                clamped: false,
+            });
+        }
+        if i128::from(earliest) - i128::from(current_time) <= i128::from(tolerance_secs) {
+            return Ok(EventTime {
+                time: earliest,
+                clamped: true,
+            });
+        }
+        Err(FsmError::TimeRegression)
+    }
+}
+
+/// Clock that moves only when told to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SimClock {
+    now: i64,
+}
+
+impl SimClock {
+    /// `InvalidInput` for a negative `start`, as for construction timestamps
+    pub fn new(start: i64) -> Result<Self, FsmError> {
+        check_construction_time(start)?;
+        Ok(Self { now: start })
+    }
+
+    pub fn now(&self) -> i64 {
+        self.now
+    }
+
+    /// Move forward by `step` and return the new time
+    ///
+    /// `TimeRegression` for a negative step, `Overflow` past `i64::MAX`.
+    pub fn advance(&mut self, step: DurationSecs) -> Result<i64, FsmError> {
+        if step.is_negative() {
+            return Err(FsmError::TimeRegression);
+        }
+        self.now = step.after(self.now).ok_or(FsmError::Overflow)?;
+        Ok(self.now)
+    }
+
+    /// Move to `time`; `TimeRegression` if it is before `now`
+    pub fn advance_to(&mut self, time: i64) -> Result<i64, FsmError> {
+        TimeOrdering::AllowEqual.check(self.now, time)?;
+        self.now = time;
+        Ok(self.now)
+    }
+}
+
+/// Reject negative construction timestamps
+pub(crate) fn check_construction_time(created_at: i64) -> Result<(), FsmError> {
+    if created_at < 0 {
+        return Err(FsmError::InvalidInput);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_equal() {
+        assert_eq!(TimeOrdering::AllowEqual.check(100, 100), Ok(()));
+        assert_eq!(TimeOrdering::AllowEqual.check(100, 101), Ok(()));
+        assert_eq!(
+            TimeOrdering::AllowEqual.check(100, 99),
+            Err(FsmError::TimeRegression)
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        assert_eq!(TimeOrdering::Strict.check(100, 101), Ok(()));
+        assert_eq!(
+            TimeOrdering::Strict.check(100, 100),
+            Err(FsmError::TimeRegression)
+        );
+    }
+
+    #[test]
+    fn test_unchecked() {
+        assert_eq!(TimeOrdering::Unchecked.check(100, -5), Ok(()));
+    }
+
+    #[test]
+    fn test_resolve_at_tolerance_boundary() {
+        let at = |time, clamped| Ok(EventTime { time, clamped });
+        let ordering = TimeOrdering::AllowEqual;
+        assert_eq!(ordering.resolve(100, 101, 2), at(101, false));
+        assert_eq!(ordering.resolve(100, 100, 2), at(100, false));
+        assert_eq!(ordering.resolve(100, 98, 2), at(100, true));
+        assert_eq!(ordering.resolve(100, 97, 2), Err(FsmError::TimeRegression));
+        // No tolerance is plain `check`
+        assert_eq!(ordering.resolve(100, 99, 0), Err(FsmError::TimeRegression));
+
+        let strict = TimeOrdering::Strict;
+        assert_eq!(strict.resolve(100, 100, 0), Err(FsmError::TimeRegression));
+        assert_eq!(strict.resolve(100, 99, 2), at(101, true));
+        assert_eq!(strict.resolve(100, 98, 2), Err(FsmError::TimeRegression));
+        assert_eq!(TimeOrdering::Unchecked.resolve(100, 5, 0), at(5, false));
+    }
+
+    #[test]
+    fn test_construction_time() {
+        assert_eq!(check_construction_time(0), Ok(()));
+        assert_eq!(check_construction_time(-5), Err(FsmError::InvalidInput));
+    }
+
+    #[test]
+    fn test_sim_clock() {
+        assert_eq!(SimClock::new(-1), Err(FsmError::InvalidInput));
+        let mut clock = SimClock::new(100).unwrap();
+        assert_eq!(clock.advance(DurationSecs::from_secs(50)), Ok(150));
+        assert_eq!(clock.advance_to(150), Ok(150));
+        assert_eq!(clock.advance_to(149), Err(FsmError::TimeRegression));
+        assert_eq!(
+            clock.advance(DurationSecs::from_secs(-1)),
+            Err(FsmError::TimeRegression)
+        );
+        assert_eq!(clock.now(), 150);
+        let mut late = SimClock::new(i64::MAX).unwrap();
+        assert_eq!(
+            late.advance(DurationSecs::from_secs(1)),
+            Err(FsmError::Overflow)
+        );
+        assert_eq!(late.now(), i64::MAX);
+    }
+}
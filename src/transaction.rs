@@ -0,0 +1,477 @@
+//! All-or-nothing application of several governance mutations.
+//!
+//! `apply` runs a list of `GovernanceOp`s, such as the steps of executing a
+//! proposal that approves grants and updates parameters, against working
+//! copies and writes them back only if every op succeeds. On the first
+//! failure it returns the error with the index of the failing op and the
+//! `GovernanceState` is left exactly as it was.
+//!
+//! Only what the ops reference is copied: each proposal and grant the first
+//! time an op names it, the parameters if an op updates them. Audit entries
+//! are checked as `AuditTrail::record` would check them and held until the
+//! commit, so the trail itself is never copied.
+//!
+//! The commit checks that every proposal is still at the revision it was
+//! copied at and issues the sequences of all held audit entries before it
+//! writes anything back. If either fails, the error is returned and the state
+//! is left as it was, apart from the sequence numbers already issued (gaps
+//! are allowed, see `sequence`).
+
+use crate::audit::{AuditEntry, AuditTrail};
+use crate::error::FsmError;
+use crate::governance_params::{GovernanceParams, ParamChanges};
+use crate::grant::Grant;
+use crate::proposal::{Proposal, VersionedRegistry};
+use crate::reason::ReasonCode;
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+
+/// A proposal lifecycle step, applied with the `*_with_time` method of the same name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProposalTransition {
+    Activate { min_quorum: u64, total_members: u64 },
+    Pass,
+    Reject,
+    Execute,
+    Cancel { code: ReasonCode, reason: String },
+    Archive,
+}
+
+/// One mutation `apply` supports
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GovernanceOp {
+    TransitionProposal {
+        id: u64,
+        transition: ProposalTransition,
+    },
+    /// `Grant::approve_with_time`
+    ApproveGrant { id: u64 },
+    /// `GovernanceParams::update`, then `set_max_suspension_hours` if given
+    UpdateParams(ParamChanges),
+    /// `AuditTrail::record`
+    AppendAudit(AuditEntry),
+}
+
+/// State `apply` mutates
+#[derive(Debug)]
+pub struct GovernanceState<'a, P> {
+    pub proposals: &'a mut VersionedRegistry<P>,
+    pub grants: &'a mut [Grant],
+    pub params: &'a mut GovernanceParams,
+    pub trail: &'a mut AuditTrail,
+}
+
+/// What a successful `apply` committed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppliedReport {
+    /// Ops applied, all of them
+    pub applied: usize,
+    /// Proposals written back with their new revision, ids ascending
+    pub proposal_revisions: Vec<(u64, u64)>,
+    /// Grants written back, ids ascending
+    pub grants: Vec<u64>,
+    pub params_updated: bool,
+    pub audit_entries: usize,
+}
+
+/// Apply every op at `current_time`, or none of them (see the module docs)
+///
+/// Unknown proposal or grant ids are `InvalidInput`; an audit entry with a
+/// transition the grant FSM does not allow is `InvalidStateTransition`.
+/// Every op sees the effects of the ops before it. Errors found by the commit,
+/// such as `Overflow` once the trail's generator is exhausted, are reported
+/// against the last op.
+pub fn apply<P: Clone>(
+    ops: Vec<GovernanceOp>,
+    state: &mut GovernanceState<'_, P>,
+    current_time: i64,
+) -> Result<AppliedReport, (FsmError, usize)> {
+    let mut work = WorkingSet::default();
+    let applied = ops.len();
+    for (index, op) in ops.into_iter().enumerate() {
+        work.apply(op, state, current_time)
+            .map_err(|error| (error, index))?;
+    }
+    work.commit(state, applied)
+        .map_err(|error| (error, applied.saturating_sub(1)))
+}
+
+/// Copies of the entities the ops touched so far
+struct WorkingSet<P> {
+    /// Copy and the revision it was taken at
+    proposals: BTreeMap<u64, (Proposal<P>, u64)>,
+    /// Copy and its position in `GovernanceState::grants`
+    grants: BTreeMap<u64, (Grant, usize)>,
+    params: Option<GovernanceParams>,
+    audit: Vec<AuditEntry>,
+}
+
+impl<P> Default for WorkingSet<P> {
+    fn default() -> Self {
+        Self {
+            proposals: BTreeMap::new(),
+            grants: BTreeMap::new(),
+            params: None,
+            audit: Vec::new(),
+        }
+    }
+}
+
+impl<P: Clone> WorkingSet<P> {
+    fn apply(
+        &mut self,
+        op: GovernanceOp,
+        state: &GovernanceState<'_, P>,
+        current_time: i64,
+    ) -> Result<(), FsmError> {
+        match op {
+            GovernanceOp::TransitionProposal { id, transition } => {
+                let proposal = self.proposal(state, id)?;
+                match transition {
+                    ProposalTransition::Activate {
+                        min_quorum,
+                        total_members,
+                    } => proposal.activate_with_time(min_quorum, total_members, current_time),
+                    ProposalTransition::Pass => proposal.pass_with_time(current_time),
+                    ProposalTransition::Reject => proposal.reject_with_time(current_time),
+                    ProposalTransition::Execute => proposal.execute_with_time(current_time),
+                    ProposalTransition::Cancel { code, reason } => {
+                        proposal.cancel_with_code(code, reason, current_time)
+                    }
+                    ProposalTransition::Archive => proposal.archive_with_time(current_time),
+                }
+            }
+            GovernanceOp::ApproveGrant { id } => {
+                self.grant(state, id)?.approve_with_time(current_time)
+            }
+            GovernanceOp::UpdateParams(changes) => {
+                let params = self.params.get_or_insert_with(|| state.params.clone());
+                params.update(
+                    changes.quorum_percentage,
+                    changes.vote_duration_hours,
+                    changes.delegate_weight_percentage,
+                    changes.early_quorum_enabled,
+                    current_time,
+                )?;
+                match changes.max_suspension_hours {
+                    Some(hours) => params.set_max_suspension_hours(hours, current_time),
+                    None => Ok(()),
+                }
+            }
+            GovernanceOp::AppendAudit(entry) => {
+                entry
+                    .from_state
+                    .validate_transition(entry.to_state)
+                    .map_err(|_| FsmError::InvalidStateTransition)?;
+                self.audit.push(entry);
+                Ok(())
+            }
+        }
+    }
+
+    fn proposal(
+        &mut self,
+        state: &GovernanceState<'_, P>,
+        id: u64,
+    ) -> Result<&mut Proposal<P>, FsmError> {
+        let copy = match self.proposals.entry(id) {
+            Entry::Occupied(copy) => copy.into_mut(),
+            Entry::Vacant(slot) => {
+                let entry = state.proposals.get(id).ok_or(FsmError::InvalidInput)?;
+                // The commit bumps the revision, which must not fail there
+                entry.revision.checked_add(1).ok_or(FsmError::Overflow)?;
+                slot.insert((entry.proposal.clone(), entry.revision))
+            }
+        };
+        Ok(&mut copy.0)
+    }
+
+    fn grant(&mut self, state: &GovernanceState<'_, P>, id: u64) -> Result<&mut Grant, FsmError> {
+        let copy = match self.grants.entry(id) {
+            Entry::Occupied(copy) => copy.into_mut(),
+            Entry::Vacant(slot) => {
+                let index = state
+                    .grants
+                    .iter()
+                    .position(|grant| grant.id == id)
+                    .ok_or(FsmError::InvalidInput)?;
+                slot.insert((state.grants[index].clone(), index))
+            }
+        };
+        Ok(&mut copy.0)
+    }
+
+    /// Check the copies can be written back and issue the audit sequences, then write every copy back
+    fn commit(
+        self,
+        state: &mut GovernanceState<'_, P>,
+        applied: usize,
+    ) -> Result<AppliedReport, FsmError> {
+        for (&id, (proposal, revision)) in &self.proposals {
+            let entry = state.proposals.get(id).ok_or(FsmError::InvalidInput)?;
+            if entry.revision != *revision {
+                return Err(FsmError::ConflictDetected);
+            }
+            if proposal.id != id {
+                return Err(FsmError::InvalidInput);
+            }
+        }
+        let sequence = state.trail.sequence_generator();
+        let audit = self
+            .audit
+            .into_iter()
+            .map(|mut entry| {
+                entry.sequence = sequence.issue()?;
+                Ok(entry)
+            })
+            .collect::<Result<Vec<_>, FsmError>>()?;
+
+        let mut report = AppliedReport {
+            applied,
+            ..AppliedReport::default()
+        };
+        for (id, (proposal, revision)) in self.proposals {
+            let revision = state.proposals.modify(id, revision, |stored| {
+                *stored = proposal;
+                Ok(())
+            })?;
+            report.proposal_revisions.push((id, revision));
+        }
+        for (id, (grant, index)) in self.grants {
+            state.grants[index] = grant;
+            report.grants.push(id);
+        }
+        if let Some(params) = self.params {
+            *state.params = params;
+            report.params_updated = true;
+        }
+        // Transitions checked when the ops were applied
+        report.audit_entries = audit.len();
+        for entry in audit {
+            state.trail.append(entry);
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duration::DurationSecs;
+    use crate::grant::{GrantCategory, GrantDisbursementType, GrantStatus, GrantType};
+    use crate::proposal::ProposalStatus;
+    use crate::sequence::SequenceGenerator;
+
+    fn create_proposal(id: u64) -> Proposal<u8> {
+        Proposal::builder(
+            id,
+            "Test".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .voting_duration(DurationSecs::from_secs(100))
+        .build(0)
+        .unwrap()
+    }
+
+    fn create_grant(id: u64) -> Grant {
+        Grant::new(
+            id,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            0,
+        )
+        .unwrap()
+    }
+
+    struct Fixture {
+        proposals: VersionedRegistry<u8>,
+        grants: Vec<Grant>,
+        params: GovernanceParams,
+        trail: AuditTrail,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let mut proposals = VersionedRegistry::new();
+            for id in 1..=2 {
+                proposals.insert(create_proposal(id)).unwrap();
+            }
+            proposals
+                .modify(1, 0, |p| {
+                    p.activate_with_time(1, 10, 0)?;
+                    p.pass_with_time(100)
+                })
+                .unwrap();
+            Self {
+                proposals,
+                grants: (1..=4).map(create_grant).collect(),
+                params: GovernanceParams::new(50, 72, 30, false, 0).unwrap(),
+                trail: AuditTrail::new(),
+            }
+        }
+
+        fn apply(&mut self, ops: Vec<GovernanceOp>) -> Result<AppliedReport, (FsmError, usize)> {
+            let mut state = GovernanceState {
+                proposals: &mut self.proposals,
+                grants: &mut self.grants,
+                params: &mut self.params,
+                trail: &mut self.trail,
+            };
+            apply(ops, &mut state, 200)
+        }
+    }
+
+    fn execution_ops() -> Vec<GovernanceOp> {
+        vec![
+            GovernanceOp::TransitionProposal {
+                id: 1,
+                transition: ProposalTransition::Execute,
+            },
+            GovernanceOp::ApproveGrant { id: 1 },
+            GovernanceOp::ApproveGrant { id: 2 },
+            GovernanceOp::ApproveGrant { id: 3 },
+            GovernanceOp::UpdateParams(ParamChanges {
+                quorum_percentage: Some(55),
+                ..ParamChanges::default()
+            }),
+            GovernanceOp::AppendAudit(AuditEntry::new(
+                1,
+                [9u8; 32],
+                GrantStatus::Pending,
+                GrantStatus::Approved,
+                "approve",
+                200,
+                None,
+            )),
+        ]
+    }
+
+    #[test]
+    fn test_all_ops_commit_together() {
+        let mut fixture = Fixture::new();
+        let report = fixture.apply(execution_ops()).unwrap();
+        assert_eq!(
+            report,
+            AppliedReport {
+                applied: 6,
+                proposal_revisions: vec![(1, 2)],
+                grants: vec![1, 2, 3],
+                params_updated: true,
+                audit_entries: 1,
+            }
+        );
+        assert_eq!(
+            fixture.proposals.get(1).unwrap().proposal.status,
+            ProposalStatus::Executed
+        );
+        let statuses: Vec<GrantStatus> = fixture.grants.iter().map(|g| g.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                GrantStatus::Approved,
+                GrantStatus::Approved,
+                GrantStatus::Approved,
+                GrantStatus::Pending,
+            ]
+        );
+        assert_eq!(fixture.params.quorum_percentage, 55);
+        assert_eq!(fixture.trail.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_failure_at_the_last_op_changes_nothing() {
+        let mut fixture = Fixture::new();
+        let before = (
+            fixture.proposals.clone(),
+            fixture.grants.clone(),
+            fixture.params.clone(),
+            fixture.trail.chain_head(),
+        );
+        let mut ops = execution_ops();
+        // Approved -> Pending is not a grant transition
+        ops.push(GovernanceOp::AppendAudit(AuditEntry::new(
+            2,
+            [9u8; 32],
+            GrantStatus::Approved,
+            GrantStatus::Pending,
+            "revert",
+            200,
+            None,
+        )));
+        assert_eq!(
+            fixture.apply(ops),
+            Err((FsmError::InvalidStateTransition, 6))
+        );
+        assert_eq!(
+            (
+                fixture.proposals.clone(),
+                fixture.grants.clone(),
+                fixture.params.clone(),
+                fixture.trail.chain_head(),
+            ),
+            before
+        );
+        assert!(fixture.trail.entries().is_empty());
+    }
+
+    #[test]
+    fn test_exhausted_sequence_fails_the_commit() {
+        let mut fixture = Fixture::new();
+        fixture.trail = AuditTrail::new().with_sequence(SequenceGenerator::resume_from(u64::MAX));
+        let before = (
+            fixture.proposals.clone(),
+            fixture.grants.clone(),
+            fixture.params.clone(),
+        );
+        assert_eq!(fixture.apply(execution_ops()), Err((FsmError::Overflow, 5)));
+        assert_eq!(
+            (
+                fixture.proposals.clone(),
+                fixture.grants.clone(),
+                fixture.params.clone(),
+            ),
+            before
+        );
+        assert!(fixture.trail.entries().is_empty());
+    }
+
+    #[test]
+    fn test_later_ops_see_earlier_ones() {
+        let mut fixture = Fixture::new();
+        // The second approval of grant 4 fails on the working copy
+        let ops = vec![
+            GovernanceOp::ApproveGrant { id: 4 },
+            GovernanceOp::ApproveGrant { id: 4 },
+        ];
+        assert_eq!(
+            fixture.apply(ops),
+            Err((FsmError::InvalidStateTransition, 1))
+        );
+        assert_eq!(fixture.grants[3].status, GrantStatus::Pending);
+
+        let ops = vec![
+            GovernanceOp::UpdateParams(ParamChanges {
+                quorum_percentage: Some(60),
+                ..ParamChanges::default()
+            }),
+            // A second +10 step from the updated copy
+            GovernanceOp::UpdateParams(ParamChanges {
+                quorum_percentage: Some(70),
+                ..ParamChanges::default()
+            }),
+            GovernanceOp::TransitionProposal {
+                id: 3,
+                transition: ProposalTransition::Archive,
+            },
+        ];
+        assert_eq!(fixture.apply(ops), Err((FsmError::InvalidInput, 2)));
+        assert_eq!(fixture.params.quorum_percentage, 50);
+    }
+}
@@ -0,0 +1,172 @@
+//! Public API stability checks.
+//!
+//! `api_stability/*.rs` are small consumers pinning what integrations rely
+//! on: constructor and method signatures, exhaustive matches on the status
+//! enums, `FsmDefinition` field names and the crate-root re-exports. Each is
+//! compiled with trybuild; one that stops compiling is a breaking change.
+//!
+//! `public_api.txt` lists every `pub` declaration under `src/`, one
+//! normalized signature per line, by file. A failure means the public
+//! surface changed. If the change is intended, rerun with
+//! `UPDATE_PUBLIC_API=1` to rewrite the snapshot, commit it with the change,
+//! and note the change in the changelog.
+
+#![cfg(all(feature = "hash", feature = "definitions", feature = "governance-meta"))]
+
+use std::path::{Path, PathBuf};
+
+#[test]
+fn pinned_consumers_compile() {
+    let cases = trybuild::TestCases::new();
+    cases.pass("tests/api_stability/*.rs");
+}
+
+/// Source files under `dir`, sorted by path
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            source_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+/// `pub` declarations of one file up to their body, whitespace collapsed
+///
+/// Constants are listed without their value and enums with one
+/// `Enum::Variant` line per variant. Test modules, `pub(crate)` and
+/// `pub(super)` are skipped.
+fn public_items(source: &str) -> Vec<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut items = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        index += 1;
+        let next = lines.get(index).map_or("", |next| next.trim_start());
+        if trimmed.starts_with("#[cfg(test)]") && next.starts_with("mod ") {
+            // Skip to the brace closing the test module
+            let end = format!("{}}}", &line[..line.len() - trimmed.len()]);
+            while index < lines.len() && lines[index] != end {
+                index += 1;
+            }
+            continue;
+        }
+        if !trimmed.starts_with("pub ") {
+            continue;
+        }
+        let mut item = trimmed.trim_end().to_string();
+        let mut depth = nesting(trimmed);
+        while !(depth <= 0 && (item.contains('{') || item.ends_with(';') || item.ends_with(',')))
+            && index < lines.len()
+        {
+            item.push(' ');
+            item.push_str(lines[index].trim());
+            depth += nesting(lines[index]);
+            index += 1;
+        }
+        let mut signature = item.split(" {").next().unwrap_or(&item).to_string();
+        if signature.starts_with("pub const ") || signature.starts_with("pub static ") {
+            signature = signature
+                .split(" = ")
+                .next()
+                .unwrap_or(&signature)
+                .to_string();
+        }
+        let signature = signature.split(" where ").next().unwrap_or(&signature);
+        let signature = signature
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace("( ", "(")
+            .replace(", )", ")")
+            .replace(",>", ">");
+        if let Some(name) = signature.strip_prefix("pub enum ") {
+            let name: String = name
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect();
+            items.push(signature.clone());
+            let mut braces = 1;
+            while index < lines.len() && braces > 0 {
+                let variant = lines[index].trim();
+                if braces == 1 && variant.starts_with(|c: char| c.is_ascii_uppercase()) {
+                    let variant: String = variant
+                        .chars()
+                        .take_while(|c| c.is_alphanumeric() || *c == '_')
+                        .collect();
+                    items.push(format!("{name}::{variant}"));
+                }
+                braces += lines[index].matches('{').count() as i32;
+                braces -= lines[index].matches('}').count() as i32;
+                index += 1;
+            }
+            continue;
+        }
+        items.push(signature);
+    }
+    items
+}
+
+/// Open minus closed parentheses and brackets on `line`
+fn nesting(line: &str) -> i32 {
+    line.chars()
+        .map(|c| match c {
+            '(' | '[' => 1,
+            ')' | ']' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+fn public_api() -> String {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let mut files = Vec::new();
+    source_files(&root.join("src"), &mut files);
+    let mut snapshot = String::new();
+    for path in files {
+        let items = public_items(&std::fs::read_to_string(&path).unwrap());
+        if items.is_empty() {
+            continue;
+        }
+        let relative = path.strip_prefix(&root).unwrap().to_string_lossy();
+        snapshot.push_str(&format!("# {}\n", relative.replace('\\', "/")));
+        for item in items {
+            snapshot.push_str(&item);
+            snapshot.push('\n');
+        }
+    }
+    snapshot
+}
+
+#[test]
+fn public_api_matches_snapshot() {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/api_stability/public_api.txt");
+    let current = public_api();
+    if std::env::var_os("UPDATE_PUBLIC_API").is_some() {
+        std::fs::write(&path, &current).unwrap();
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_default();
+    if current != expected {
+        let added: Vec<&str> = current
+            .lines()
+            .filter(|line| !expected.lines().any(|e| e == *line))
+            .collect();
+        let removed: Vec<&str> = expected
+            .lines()
+            .filter(|line| !current.lines().any(|c| c == *line))
+            .collect();
+        panic!(
+            "public API changed; rerun with UPDATE_PUBLIC_API=1 if intended\nadded:\n  {}\nremoved:\n  {}",
+            added.join("\n  "),
+            removed.join("\n  ")
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! `FsmDefinition` and its parts destructured without `..`, so an added,
+//! removed or renamed field fails here.
+
+use fsm_governance_engine_lib::{
+    FsmDefaults, FsmDefinition, FsmInvariant, FsmTransition, FsmTransitionMetadata,
+};
+
+fn transition(transition: FsmTransition) {
+    let FsmTransition {
+        from,
+        to,
+        action,
+        guard,
+        metadata,
+    } = transition;
+    let _: (String, String, String) = (from, to, action);
+    let _ = guard;
+    if let Some(FsmTransitionMetadata { description, roles }) = metadata {
+        let _: (Option<String>, Vec<String>) = (description, roles);
+    }
+}
+
+fn main() {
+    let FsmDefinition {
+        name,
+        states,
+        transitions,
+        defaults,
+        invariants,
+    } = FsmDefinition {
+        name: None,
+        states: Vec::new(),
+        transitions: Vec::new(),
+        defaults: None,
+        invariants: Vec::new(),
+    };
+    let _: (Option<String>, Vec<String>) = (name, states);
+    let _: Option<FsmDefaults> = defaults;
+    let _: Vec<FsmInvariant> = invariants;
+    transitions.into_iter().for_each(transition);
+}
@@ -0,0 +1,85 @@
+//! Status enums matched exhaustively, as integrations do; a new or renamed
+//! variant fails here.
+
+use fsm_governance_engine_lib::{ErrorClass, FsmError, GrantStatus, IdeaStatus, ProposalStatus};
+
+fn proposal(status: ProposalStatus) -> u8 {
+    match status {
+        ProposalStatus::Draft => 0,
+        ProposalStatus::Active => 1,
+        ProposalStatus::Passed => 2,
+        ProposalStatus::Executing => 3,
+        ProposalStatus::Rejected => 4,
+        ProposalStatus::Executed => 5,
+        ProposalStatus::Cancelled => 6,
+        ProposalStatus::Archived => 7,
+        ProposalStatus::Tied => 8,
+        ProposalStatus::QuorumFailed => 9,
+    }
+}
+
+fn grant(status: GrantStatus) -> u8 {
+    match status {
+        GrantStatus::Pending => 0,
+        GrantStatus::Approved => 1,
+        GrantStatus::Active => 2,
+        GrantStatus::Suspended => 3,
+        GrantStatus::Completed => 4,
+        GrantStatus::Cancelled => 5,
+        GrantStatus::Rejected => 6,
+        GrantStatus::Expired => 7,
+        GrantStatus::Archived => 8,
+    }
+}
+
+fn idea(status: IdeaStatus) -> u8 {
+    match status {
+        IdeaStatus::Draft => 0,
+        IdeaStatus::UnderReview => 1,
+        IdeaStatus::Approved => 2,
+        IdeaStatus::Rejected => 3,
+        IdeaStatus::InProgress => 4,
+        IdeaStatus::Paused => 5,
+        IdeaStatus::Completed => 6,
+        IdeaStatus::Executed => 7,
+        IdeaStatus::Commercialization => 8,
+        IdeaStatus::Archived => 9,
+        IdeaStatus::Resubmitted => 10,
+        IdeaStatus::Voting => 11,
+        IdeaStatus::Expired => 12,
+    }
+}
+
+fn class(class: ErrorClass) -> u8 {
+    match class {
+        ErrorClass::Transition => 0,
+        ErrorClass::Input => 1,
+        ErrorClass::Precondition => 2,
+        ErrorClass::Conflict => 3,
+        ErrorClass::Arithmetic => 4,
+    }
+}
+
+/// `FsmError` gains variants between releases; the 0.1 ones and the
+/// accessors must stay
+fn error(error: FsmError) -> (&'static str, &'static str, ErrorClass) {
+    (error.code(), error.message_key(), error.class())
+}
+
+fn main() {
+    let _ = (
+        proposal(ProposalStatus::Draft),
+        grant(GrantStatus::Pending),
+        idea(IdeaStatus::Draft),
+        class(ErrorClass::Input),
+    );
+    for variant in [
+        FsmError::InvalidStateTransition,
+        FsmError::InvalidInput,
+        FsmError::InsufficientMembers,
+        FsmError::InvalidState,
+        FsmError::Overflow,
+    ] {
+        let _ = error(variant);
+    }
+}
@@ -0,0 +1,24 @@
+//! Crate-root re-exports with the default features; removing or renaming
+//! one fails here.
+
+#[allow(unused_imports)]
+use fsm_governance_engine_lib::{
+    AmendmentStatus, ArithmeticPolicy, AuditActor, AuditEntry, AuditSubscriber, AuditTrail,
+    ChannelSubscriber, ChronologyViolation, DefinitionFailure, DefinitionResult, DurationSecs,
+    ErrorClass, EventTime, FinalizedRound, FsmDefaults, FsmDefinition, FsmError, FsmInvariant,
+    FsmTransition, FsmTransitionMetadata, FsmTransitionRef, GovernanceParams, GovernanceRound,
+    Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType, GrantVote, Idea,
+    IdeaFsmProfile, IdeaStatus, InvariantKind, Lint, LintCode, LintSeverity, MAX_ROLE_NAME_LENGTH,
+    MatrixRow, Member, MetricsSink, Obligation, ObligationIndex, ParamChanges,
+    ParamRecommendation, Proposal, ProposalBuilder, ProposalStatus, QuorumMetadata, Reassignment,
+    ReasonCode, ReasonPolicy, ReasonRequirement, ReasonTransition, RecommendationError,
+    RecommendationSource, RedactionEntry, ReviewAssessment, ReviewDecision, ReviewOutcome,
+    RoundConfig, SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus,
+    SecurityBoardMemberMetadata, SimClock, SubscriberError, SuspensionCode, TickOutcome,
+    TimeOrdering, ValidationReport, VerificationStatus, VoteType, deny_warnings, validate_many,
+};
+
+#[allow(unused_imports)]
+use fsm_governance_engine_lib::prelude::*;
+
+fn main() {}
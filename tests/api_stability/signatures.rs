@@ -0,0 +1,47 @@
+//! Constructors and lifecycle methods, pinned as function pointers so a
+//! changed arity, argument type or return type fails to coerce.
+
+use fsm_governance_engine_lib::{
+    FsmDefinition, FsmError, Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType,
+    Idea, IdeaStatus, Proposal, ProposalBuilder, ProposalStatus,
+};
+
+type Outcome = Result<(), FsmError>;
+
+fn main() {
+    let _: fn(u64, String, String, String, u8, i64) -> Result<Proposal<u8>, FsmError> =
+        Proposal::new_with_time;
+    let _: fn(u64, String, String, String, u8) -> ProposalBuilder<u8> = Proposal::builder;
+    let _: fn(ProposalBuilder<u8>, i64) -> Result<Proposal<u8>, FsmError> = ProposalBuilder::build;
+    let _: fn(&mut Proposal<u8>, u64, u64, i64) -> Outcome = Proposal::activate_with_time;
+    let _: fn(&mut Proposal<u8>, u8, bool, u64, i64) -> Outcome = Proposal::cast_vote;
+    let _: fn(&mut Proposal<u8>, i64) -> Outcome = Proposal::pass_with_time;
+    let _: fn(&mut Proposal<u8>, i64) -> Outcome = Proposal::reject_with_time;
+    let _: fn(&mut Proposal<u8>, i64) -> Outcome = Proposal::execute_with_time;
+    let _: fn(&mut Proposal<u8>, String, i64) -> Outcome = Proposal::cancel_with_time;
+    let _: fn(&mut Proposal<u8>, i64) -> Outcome = Proposal::archive_with_time;
+
+    let _: fn(
+        u64,
+        u64,
+        [u8; 32],
+        GrantCategory,
+        GrantType,
+        GrantDisbursementType,
+        u64,
+        u64,
+        i64,
+    ) -> Result<Grant, FsmError> = Grant::new;
+    let _: fn(&mut Grant, i64) -> Outcome = Grant::approve_with_time;
+    let _: fn(&mut Grant, i64) -> Outcome = Grant::activate_with_time;
+    let _: fn(&mut Grant, u64) -> Outcome = Grant::disburse;
+
+    let _: fn(u64, i64) -> Result<Idea, FsmError> = Idea::new;
+
+    let _: fn(&ProposalStatus, &ProposalStatus) -> Outcome = ProposalStatus::validate_transition;
+    let _: fn(&GrantStatus, GrantStatus) -> Outcome = GrantStatus::validate_transition;
+    let _: fn(&IdeaStatus, IdeaStatus) -> Outcome = IdeaStatus::validate_transition;
+
+    let _: fn(&str) -> Result<FsmDefinition, FsmError> = FsmDefinition::from_json_str;
+    let _: fn(&FsmDefinition) -> Outcome = FsmDefinition::validate;
+}
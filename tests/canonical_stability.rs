@@ -0,0 +1,89 @@
+//! Golden fixtures for the canonical form used in signatures and content hashes.
+//!
+//! A failure here means the signed bytes of a governance object changed. If the
+//! change is intended (e.g. a new field), rerun with `UPDATE_CANONICAL_GOLDEN=1`
+//! to rewrite the fixtures and note the change in the changelog.
+
+#![cfg(all(feature = "hash", feature = "definitions"))]
+
+use fsm_governance_engine_lib::canonical::{canonical_hash, to_canonical_bytes};
+use fsm_governance_engine_lib::retention::content_hash;
+use fsm_governance_engine_lib::{AuditEntry, DurationSecs, FsmDefinition, GrantStatus, Proposal};
+use serde::Serialize;
+use std::path::PathBuf;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn check_golden<T: Serialize>(name: &str, value: &T) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/canonical_golden");
+    let bytes_path = dir.join(format!("{name}.json"));
+    let hash_path = dir.join(format!("{name}.sha256"));
+    let bytes = to_canonical_bytes(value).unwrap();
+    let hash = format!("{}\n", hex(&canonical_hash(value).unwrap()));
+    if std::env::var_os("UPDATE_CANONICAL_GOLDEN").is_some() {
+        std::fs::write(&bytes_path, &bytes).unwrap();
+        std::fs::write(&hash_path, &hash).unwrap();
+    }
+    assert_eq!(
+        String::from_utf8(bytes).unwrap(),
+        std::fs::read_to_string(&bytes_path).unwrap(),
+        "{name}"
+    );
+    assert_eq!(hash, std::fs::read_to_string(&hash_path).unwrap(), "{name}");
+}
+
+fn proposal() -> Proposal<u8> {
+    let mut proposal = Proposal::builder(
+        7,
+        "Fund the bridge".to_string(),
+        "Repairs, \"phase\" 1\n".to_string(),
+        "governance".to_string(),
+        3,
+    )
+    .voting_duration(DurationSecs::from_secs(100))
+    .build(1_000)
+    .unwrap();
+    proposal.add_tag("Infra", None).unwrap();
+    proposal.add_tag("bridge", None).unwrap();
+    proposal.activate_with_time(1, 10, 1_010).unwrap();
+    proposal.cast_vote(4, true, 2, 1_020).unwrap();
+    proposal.cast_vote(5, false, 1, 1_030).unwrap();
+    proposal
+}
+
+#[test]
+fn proposal_bytes_are_stable() {
+    check_golden("proposal", &proposal());
+}
+
+#[test]
+fn definition_bytes_are_stable() {
+    let definition =
+        FsmDefinition::from_json_str(include_str!("vectors/valid_metadata.json")).unwrap();
+    check_golden("definition", &definition);
+}
+
+#[test]
+fn audit_entry_bytes_are_stable() {
+    let entry = AuditEntry::new(
+        7,
+        [1; 32],
+        GrantStatus::Active,
+        GrantStatus::Suspended,
+        "suspend",
+        1_040,
+        None,
+    );
+    check_golden("audit_entry", &entry);
+}
+
+#[test]
+fn content_hash_is_the_canonical_hash() {
+    let proposal = proposal();
+    assert_eq!(
+        content_hash(&proposal).unwrap(),
+        canonical_hash(&proposal).unwrap()
+    );
+}
@@ -0,0 +1,97 @@
+//! A consumer written against the 0.1 API, built on the `compat` shims.
+//!
+//! If this file stops compiling, an existing integration would too.
+
+#![cfg(feature = "compat-v1")]
+#![allow(deprecated)]
+
+mod legacy_consumer {
+    use fsm_governance_engine_lib::compat::{self, FsmErrorV1, ProposalId, Seconds, Timestamp};
+    use fsm_governance_engine_lib::{
+        Grant, GrantCategory, GrantDisbursementType, GrantStatus, GrantType, Proposal,
+        ProposalStatus,
+    };
+
+    /// Exhaustive over the 0.1 variants, as integrations wrote it
+    pub fn describe(error: FsmErrorV1) -> &'static str {
+        match error {
+            FsmErrorV1::InvalidStateTransition => "transition",
+            FsmErrorV1::InvalidInput => "input",
+            FsmErrorV1::InsufficientMembers => "quorum",
+            FsmErrorV1::InvalidState => "state",
+            FsmErrorV1::Overflow => "overflow",
+        }
+    }
+
+    pub fn open_proposal(
+        id: ProposalId,
+        now: Timestamp,
+    ) -> Result<(Proposal<u8>, Seconds), FsmErrorV1> {
+        let mut proposal = compat::new_proposal(
+            id,
+            "Fund the bridge".to_string(),
+            "Repairs".to_string(),
+            "governance".to_string(),
+            1u8,
+            now,
+        )?;
+        compat::activate_proposal(&mut proposal, 1, 10, now)?;
+        compat::validate_proposal_transition(&proposal.status, &ProposalStatus::Passed)?;
+        Ok((proposal.clone(), compat::voting_duration_secs(&proposal)))
+    }
+
+    pub fn approve(created_at: Timestamp) -> Result<Grant, FsmErrorV1> {
+        let mut grant = Grant::new(
+            1,
+            1,
+            [0u8; 32],
+            GrantCategory::Research,
+            GrantType::Initial,
+            GrantDisbursementType::Standard,
+            100,
+            0,
+            created_at,
+        )
+        .map_err(FsmErrorV1::from)?;
+        compat::approve_grant(&mut grant)?;
+        compat::validate_grant_transition(grant.status, GrantStatus::Active)?;
+        Ok(grant)
+    }
+}
+
+use fsm_governance_engine_lib::compat::{self, FsmErrorV1};
+use fsm_governance_engine_lib::{FsmError, GrantStatus, IdeaStatus, ProposalStatus};
+
+#[test]
+fn legacy_consumer_runs_on_shims() {
+    let (proposal, duration) = legacy_consumer::open_proposal(7, 1_000).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Active);
+    assert_eq!(duration, proposal.voting_duration.as_secs());
+    assert_eq!(
+        legacy_consumer::approve(1_000).unwrap().status,
+        GrantStatus::Approved
+    );
+}
+
+#[test]
+fn legacy_errors_keep_their_shape() {
+    assert_eq!(
+        legacy_consumer::open_proposal(7, -1).unwrap_err(),
+        FsmErrorV1::InvalidInput
+    );
+    // Errors of the current API convert at the boundary
+    assert_eq!(
+        legacy_consumer::approve(-1).map(|_| ()).unwrap_err(),
+        FsmErrorV1::InvalidInput
+    );
+    assert_eq!(
+        legacy_consumer::describe(
+            compat::validate_idea_transition(IdeaStatus::Draft, IdeaStatus::Executed).unwrap_err()
+        ),
+        "transition"
+    );
+    assert_eq!(
+        FsmError::from(FsmErrorV1::InsufficientMembers),
+        FsmError::InsufficientMembers
+    );
+}
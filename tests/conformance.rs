@@ -0,0 +1,93 @@
+#![cfg(feature = "definitions")]
+
+use std::fs;
+
+use fsm_governance_engine_lib::conformance::{
+    ConformanceReport, EnumTable, Mismatch, WalkSamples, differential,
+};
+use fsm_governance_engine_lib::{
+    FsmDefinition, GrantStatus, IdeaFsmProfile, IdeaStatus, ProposalStatus,
+};
+
+fn load(name: &str) -> FsmDefinition {
+    let raw = fs::read_to_string(format!("tests/conformance/{name}.json"))
+        .unwrap_or_else(|err| panic!("read fixture {name}: {err}"));
+    let definition = FsmDefinition::from_json_str(&raw)
+        .unwrap_or_else(|err| panic!("parse fixture {name}: {err:?}"));
+    definition.validate().unwrap();
+    definition
+}
+
+fn assert_conforms(report: ConformanceReport, states: usize) {
+    assert!(report.is_ok(), "{:#?}", report.mismatches);
+    assert_eq!(report.pairs_checked, states * (states - 1));
+    assert_eq!(report.walks_checked, WalkSamples::default().walks);
+}
+
+#[test]
+fn idea_status_matches_definition() {
+    let table = EnumTable::new(IdeaStatus::VARIANTS, |a: &IdeaStatus, b| {
+        a.can_transition_to(*b)
+    });
+    let report = differential(&table, &load("idea_status"), WalkSamples::default());
+    assert_conforms(report, IdeaStatus::VARIANTS.len());
+}
+
+#[test]
+fn grant_status_matches_definition() {
+    let table = EnumTable::new(GrantStatus::VARIANTS, |a: &GrantStatus, b| {
+        a.can_transition_to(*b)
+    });
+    let report = differential(&table, &load("grant_status"), WalkSamples::default());
+    assert_conforms(report, GrantStatus::VARIANTS.len());
+}
+
+#[test]
+fn proposal_status_matches_definition() {
+    let table = EnumTable::new(ProposalStatus::VARIANTS, ProposalStatus::can_transition_to);
+    let report = differential(&table, &load("proposal_status"), WalkSamples::default());
+    assert_conforms(report, ProposalStatus::VARIANTS.len());
+}
+
+#[test]
+fn strict_idea_profile_diverges_from_standard_definition() {
+    let table = EnumTable::new(IdeaStatus::VARIANTS, |a: &IdeaStatus, b| {
+        a.can_transition_to_in(IdeaFsmProfile::StrictTerminal, *b)
+    });
+    let report = differential(&table, &load("idea_status"), WalkSamples::default());
+    let edges: Vec<(&str, &str)> = report
+        .mismatches
+        .iter()
+        .filter_map(|m| match m {
+            Mismatch::Edge {
+                from,
+                to,
+                enum_allows: false,
+                definition_allows: true,
+            } => Some((from.as_str(), to.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        [("Rejected", "Resubmitted"), ("Archived", "Resubmitted")]
+    );
+}
+
+#[test]
+fn walks_replay_from_seed() {
+    let table = EnumTable::new(GrantStatus::VARIANTS, |a: &GrantStatus, b| {
+        a.can_transition_to(*b)
+    });
+    let mut definition = load("grant_status");
+    definition
+        .transitions
+        .retain(|t| !(t.from == "Active" && t.to == "Expired"));
+    let samples = WalkSamples {
+        seed: 7,
+        ..WalkSamples::default()
+    };
+    let first = differential(&table, &definition, samples);
+    assert!(!first.is_ok());
+    assert_eq!(first, differential(&table, &definition, samples));
+}
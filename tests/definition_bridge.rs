@@ -0,0 +1,77 @@
+#![cfg(feature = "definitions")]
+
+use fsm_governance_engine_lib::instance::FsmInstance;
+use fsm_governance_engine_lib::proposal::definition_bridge::context_for;
+use fsm_governance_engine_lib::{DurationSecs, FsmDefinition, FsmError, Proposal, ProposalStatus};
+
+const WORKFLOW: &str = r#"{
+    "name": "custom_proposal",
+    "states": ["Active", "Passed", "Rejected"],
+    "transitions": [
+        {
+            "from": "Active",
+            "to": "Passed",
+            "action": "close",
+            "guard": "current_time >= voting_end and yes_votes > no_votes"
+        },
+        {
+            "from": "Active",
+            "to": "Rejected",
+            "action": "close",
+            "guard": "current_time >= voting_end and not (yes_votes > no_votes)"
+        }
+    ],
+    "defaults": { "initialState": "Active" }
+}"#;
+
+fn active_proposal() -> Proposal<u8> {
+    let mut proposal = Proposal::builder(
+        1,
+        "Fund the bridge".to_string(),
+        "Repairs".to_string(),
+        "governance".to_string(),
+        1,
+    )
+    .voting_duration(DurationSecs::from_secs(100))
+    .build(0)
+    .unwrap();
+    proposal.activate_with_time(1, 10, 10).unwrap();
+    proposal
+}
+
+#[test]
+fn custom_workflow_passes_after_window_with_majority() {
+    let definition: FsmDefinition = serde_json::from_str(WORKFLOW).unwrap();
+    let mut instance = FsmInstance::new(&definition).unwrap();
+    let mut proposal = active_proposal();
+    proposal.yes_votes = 4;
+    proposal.no_votes = 1;
+
+    // Voting window ends at 110
+    assert_eq!(
+        instance.fire("close", &context_for(&proposal, 109)),
+        Err(FsmError::InvalidState)
+    );
+    assert_eq!(instance.state(), "Active");
+
+    let state = instance
+        .fire("close", &context_for(&proposal, 110))
+        .unwrap();
+    assert_eq!(state, "Passed");
+    proposal.pass_with_time(110).unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Passed);
+}
+
+#[test]
+fn custom_workflow_rejects_without_majority() {
+    let definition: FsmDefinition = serde_json::from_str(WORKFLOW).unwrap();
+    let mut instance = FsmInstance::new(&definition).unwrap();
+    let mut proposal = active_proposal();
+    proposal.yes_votes = 2;
+    proposal.no_votes = 2;
+
+    assert_eq!(
+        instance.fire("close", &context_for(&proposal, 200)),
+        Ok("Rejected")
+    );
+}
@@ -1,9 +1,12 @@
+#![cfg(feature = "definitions")]
+
 use fsm_governance_engine_lib::{
     FsmDefaults, FsmDefinition, FsmError, FsmInvariant, FsmTransition, FsmTransitionRef,
 };
 
 fn base_definition() -> FsmDefinition {
     FsmDefinition {
+        name: None,
         states: vec!["A".into(), "B".into()],
         transitions: vec![FsmTransition {
             from: "A".into(),
@@ -1,9 +1,12 @@
+#![cfg(feature = "definitions")]
+
 use fsm_governance_engine_lib::{FsmDefinition, FsmError, FsmTransition};
 use proptest::prelude::*;
 use proptest::string::string_regex;
 
 fn base_definition(action: String, from: &str, to: &str) -> FsmDefinition {
     FsmDefinition {
+        name: None,
         states: vec!["A".into(), "B".into()],
         transitions: vec![FsmTransition {
             from: from.into(),
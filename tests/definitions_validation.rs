@@ -1,3 +1,5 @@
+#![cfg(feature = "definitions")]
+
 use std::fs;
 
 use fsm_governance_engine_lib::FsmDefinition;
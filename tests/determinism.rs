@@ -0,0 +1,101 @@
+//! Collection-returning functions must not depend on insertion order.
+//!
+//! Each test builds the same state twice in different orders and compares the
+//! serialized outputs byte for byte.
+
+#![cfg(feature = "serde")]
+
+use fsm_governance_engine_lib::grant::funding::pool_exposure;
+use fsm_governance_engine_lib::grant::{Grant, GrantCategory, GrantDisbursementType, GrantType};
+use fsm_governance_engine_lib::proposal::{Proposal, check_stale_executions};
+use fsm_governance_engine_lib::{DurationSecs, calendar};
+
+fn proposal(id: u64, created_at: i64) -> Proposal<u8> {
+    let mut proposal = Proposal::builder(
+        id,
+        "Title".to_string(),
+        "Description".to_string(),
+        "governance".to_string(),
+        1,
+    )
+    .voting_duration(DurationSecs::from_secs(100))
+    .build(created_at)
+    .unwrap();
+    proposal.activate_with_time(1, 1, created_at).unwrap();
+    proposal
+}
+
+fn grant(id: u64, pools: &[(u64, u64)], disbursed: u64) -> Grant {
+    let total = pools.iter().map(|(_, amount)| amount).sum();
+    let mut grant = Grant::new(
+        id,
+        1,
+        [0u8; 32],
+        GrantCategory::Research,
+        GrantType::Initial,
+        GrantDisbursementType::Standard,
+        total,
+        0,
+        0,
+    )
+    .unwrap();
+    for (pool_id, amount) in pools {
+        grant.allocate_funding(*pool_id, *amount).unwrap();
+    }
+    grant.approve().unwrap();
+    grant.activate().unwrap();
+    if disbursed > 0 {
+        grant.disburse(disbursed).unwrap();
+    }
+    grant
+}
+
+#[test]
+fn calendar_is_order_independent() {
+    // Ids 1 and 3 share a deadline, so the tie-break decides their order
+    let forward = vec![proposal(1, 0), proposal(2, 50), proposal(3, 0)];
+    let mut backward = forward.clone();
+    backward.reverse();
+
+    let a = serde_json::to_vec(&calendar::upcoming(&forward, &[], 1_000, 0)).unwrap();
+    let b = serde_json::to_vec(&calendar::upcoming(&backward, &[], 1_000, 0)).unwrap();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn stale_executions_are_reported_by_id() {
+    let build = |ids: &[u64]| -> Vec<Proposal<u8>> {
+        ids.iter()
+            .map(|id| {
+                let mut proposal = proposal(*id, 0);
+                proposal.pass_with_time(100).unwrap();
+                proposal.prepare_execute(100).unwrap();
+                proposal
+            })
+            .collect()
+    };
+    let mut forward = build(&[1, 2, 3]);
+    let mut shuffled = build(&[3, 1, 2]);
+
+    let a = check_stale_executions(&mut forward, 10, 200);
+    let b = check_stale_executions(&mut shuffled, 10, 200);
+    assert_eq!(a.reverted, vec![1, 2, 3]);
+    assert_eq!(
+        serde_json::to_vec(&a).unwrap(),
+        serde_json::to_vec(&b).unwrap()
+    );
+}
+
+#[test]
+fn pool_exposure_is_order_independent() {
+    let first = grant(1, &[(20, 400), (10, 600)], 500);
+    let second = grant(2, &[(30, 100), (10, 100)], 0);
+
+    let a = pool_exposure(&[first.clone(), second.clone()]);
+    let b = pool_exposure(&[second, first]);
+    assert_eq!(
+        serde_json::to_vec(&a).unwrap(),
+        serde_json::to_vec(&b).unwrap()
+    );
+    assert_eq!(a.keys().copied().collect::<Vec<_>>(), vec![10, 20, 30]);
+}
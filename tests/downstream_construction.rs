@@ -0,0 +1,191 @@
+//! Construction of generic public types from outside the crate.
+//!
+//! Every type here must be buildable by a downstream crate, either with a
+//! struct literal or with a public constructor covering every field.
+
+#[cfg(feature = "governance-meta")]
+use fsm_governance_engine_lib::governance::{
+    SecurityBoardDecisionMetadata, SecurityBoardDecisionStatus, SecurityBoardMemberMetadata,
+    SecurityBoardMemberRole,
+};
+use fsm_governance_engine_lib::proposal::{
+    AmendmentStatus, Proposal, ProposalAmendment, ProposalStatus, ProposalTemplate, TemplateField,
+    TemplateFieldType, TreasuryOperationData, TreasuryProposalType,
+};
+
+use fsm_governance_engine_lib::proposal::{TallyOverflow, TallyPolicy, TieBreakPolicy};
+use fsm_governance_engine_lib::{DurationSecs, ReasonPolicy, TimeOrdering};
+
+type Pubkey = [u8; 32];
+
+#[test]
+fn proposal_struct_literal() {
+    let treasury_operation = TreasuryOperationData::<Pubkey> {
+        operation_type: TreasuryProposalType::Transfer,
+        amount: Some(500),
+        target_treasury: Some([2u8; 32]),
+        capability_grantee: None,
+        capability_type: None,
+        expires_at: None,
+        description: "Move funds".to_string(),
+    };
+    let proposal = Proposal::<Pubkey> {
+        id: 1,
+        title: "Title".to_string(),
+        description: "Description".to_string(),
+        proposal_type: "treasury".to_string(),
+        author: [1u8; 32],
+        created_at: 1000,
+        updated_at: None,
+        submitted_at: None,
+        cancelled_at: None,
+        executed_at: None,
+        passed_at: None,
+        archived_at: None,
+        voting_duration: DurationSecs::from_secs(3600),
+        status: ProposalStatus::Draft,
+        yes_votes: 0,
+        no_votes: 0,
+        total_votes: 0,
+        votes: Vec::new(),
+        vote_nonces: Vec::new(),
+        last_tallied_at: None,
+        cancellation_reason: None,
+        cancellation_code: None,
+        superseded_by: None,
+        depends_on: Vec::new(),
+        sponsors: Vec::new(),
+        execution_data: None,
+        expires_at: None,
+        idea_id: None,
+        treasury_operation: Some(treasury_operation.clone()),
+        execution_ticket: None,
+        execution_attempts: 0,
+        execution_abort_reason: None,
+        execution_history: Vec::new(),
+        last_event_at: 1000,
+        time_ordering: TimeOrdering::AllowEqual,
+        clock_skew_tolerance_seconds: 0,
+        tally_policy: TallyPolicy::SimpleMajority,
+        tally_overflow: TallyOverflow::Reject,
+        tally_degraded: false,
+        voting_id: None,
+        compacted: false,
+        compacted_hash: None,
+        power_snapshot: None,
+        min_vote_weight: 0,
+        weight_divisor: None,
+        reason_policy: ReasonPolicy::default(),
+        execution_timelock: DurationSecs::ZERO,
+        tags: Vec::new(),
+        tie_break_policy: TieBreakPolicy::Manual,
+        tie_break_applied: None,
+    };
+
+    let built = Proposal::builder(
+        1,
+        "Title".to_string(),
+        "Description".to_string(),
+        "treasury".to_string(),
+        [1u8; 32],
+    )
+    .voting_duration(DurationSecs::from_secs(3600))
+    .treasury_operation(treasury_operation)
+    .build(1000)
+    .unwrap();
+    assert_eq!(proposal, built);
+}
+
+#[test]
+fn proposal_amendment_struct_literal() {
+    let amendment = ProposalAmendment::<Pubkey> {
+        amendment_id: 1,
+        proposal_id: 7,
+        author: [3u8; 32],
+        content: "Amended text".to_string(),
+        created_at: 1000,
+        status: AmendmentStatus::Proposed,
+        allow_active_parent: false,
+        voting_ends_at: None,
+        yes_votes: 0,
+        no_votes: 0,
+        votes: Vec::new(),
+        resolved_at: None,
+    };
+    let created =
+        ProposalAmendment::new_with_time(1, 7, [3u8; 32], "Amended text".to_string(), 1000)
+            .unwrap();
+    assert_eq!(amendment, created);
+}
+
+#[test]
+fn proposal_template_with_fields() {
+    let field = TemplateField::<Pubkey>::new(
+        "budget".to_string(),
+        "Requested budget".to_string(),
+        TemplateFieldType::Number,
+        true,
+    );
+    let template = ProposalTemplate::<Pubkey> {
+        template_id: 1,
+        name: "Budget".to_string(),
+        description: "Budget request".to_string(),
+        proposal_type: "treasury".to_string(),
+        fields: vec![field],
+        created_by: [4u8; 32],
+        created_at: 1000,
+        updated_at: None,
+        is_active: true,
+    };
+    assert_eq!(template.fields[0].name, "budget");
+    assert!(template.fields[0].required);
+}
+
+#[cfg(feature = "governance-meta")]
+#[test]
+fn security_board_member_struct_literal() {
+    let member = SecurityBoardMemberMetadata::<Pubkey> {
+        member_id: 1,
+        member_pubkey: [5u8; 32],
+        role: SecurityBoardMemberRole::Chairperson,
+        joined_at: 1000,
+        last_active_at: 1000,
+        decisions_participated: 0,
+    };
+    let initialized = SecurityBoardMemberMetadata::initialize(
+        1,
+        [5u8; 32],
+        SecurityBoardMemberRole::Chairperson,
+        1000,
+    )
+    .unwrap();
+    assert_eq!(member, initialized);
+}
+
+#[cfg(feature = "governance-meta")]
+#[test]
+fn security_board_decision_constructor() {
+    let decision = SecurityBoardDecisionMetadata::<Pubkey>::new(
+        1,
+        Some(7),
+        SecurityBoardDecisionStatus::Approved,
+        1000,
+        Some(2000),
+        [6u8; 32],
+    );
+    assert_eq!(decision.status, SecurityBoardDecisionStatus::Approved);
+    assert_eq!(decision.decided_at, Some(2000));
+
+    let pending = SecurityBoardDecisionMetadata::<Pubkey>::new(
+        1,
+        Some(7),
+        SecurityBoardDecisionStatus::Pending,
+        1000,
+        None,
+        [6u8; 32],
+    );
+    assert_eq!(
+        pending,
+        SecurityBoardDecisionMetadata::initialize(1, Some(7), [6u8; 32], 1000).unwrap()
+    );
+}
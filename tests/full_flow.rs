@@ -0,0 +1,150 @@
+#![cfg(feature = "governance-meta")]
+
+use fsm_governance_engine_lib::events::GovernanceEvent;
+use fsm_governance_engine_lib::governance::{GovernanceVotingStatus, GovernanceVotingType};
+use fsm_governance_engine_lib::{
+    FsmError, GovernanceParams, GovernanceRound, Proposal, ProposalStatus, RoundConfig, TickOutcome,
+};
+
+const OPENED_AT: i64 = 1_000;
+
+fn round(total_members: u64) -> GovernanceRound<u32> {
+    let params = GovernanceParams::new(50, 48, 0, false, 0).unwrap();
+    let proposal = Proposal::builder(
+        42,
+        "Raise the treasury cap".to_string(),
+        "Full-cycle integration fixture".to_string(),
+        "governance".to_string(),
+        1,
+    )
+    .build(0)
+    .unwrap();
+    let config = RoundConfig {
+        quorum_id: 7,
+        voting_id: 8,
+        voting_type: GovernanceVotingType::SimpleMajority,
+        voting_data_hash: [9; 32],
+        total_members,
+        min_quorum: 2,
+    };
+    GovernanceRound::open(proposal, &params, config, OPENED_AT).unwrap()
+}
+
+fn statuses(events: &[GovernanceEvent]) -> Vec<(ProposalStatus, ProposalStatus)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            GovernanceEvent::ProposalStatusChanged { from, to, .. } => {
+                Some((from.clone(), to.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn passes_with_quorum_and_majority() {
+    let mut round = round(5);
+    let ends_at = round.voting_ends_at().unwrap();
+    assert_eq!(ends_at, OPENED_AT + 48 * 3600);
+
+    round.vote(1, true, 1, Some(1), OPENED_AT + 10).unwrap();
+    round.vote(2, true, 1, None, OPENED_AT + 20).unwrap();
+    round.vote(3, false, 1, None, OPENED_AT + 30).unwrap();
+    assert!(round.quorum().quorum_reached);
+    assert_eq!(round.quorum().current_percentage, 60);
+    assert!(round.is_consistent());
+
+    assert_eq!(
+        round.tick(OPENED_AT + 40),
+        Ok(TickOutcome::Voting { ends_at })
+    );
+    assert_eq!(
+        round.tick(ends_at),
+        Ok(TickOutcome::Closed {
+            status: ProposalStatus::Passed,
+            quorum_reached: true,
+        })
+    );
+    assert!(round.is_consistent());
+
+    let finalized = round.finalize().unwrap();
+    assert_eq!(finalized.proposal.status, ProposalStatus::Passed);
+    assert_eq!(finalized.proposal.yes_votes, 2);
+    assert_eq!(finalized.proposal.no_votes, 1);
+    assert_eq!(finalized.voting.status, GovernanceVotingStatus::Closed);
+    assert_eq!(finalized.quorum.proposal_id, Some(42));
+    assert_eq!(
+        statuses(&finalized.events),
+        [
+            (ProposalStatus::Draft, ProposalStatus::Active),
+            (ProposalStatus::Active, ProposalStatus::Passed),
+        ]
+    );
+}
+
+#[test]
+fn quorum_failure_has_its_own_status() {
+    let mut round = round(10);
+    round.vote(1, true, 1, None, OPENED_AT + 10).unwrap();
+    round.vote(2, true, 1, None, OPENED_AT + 20).unwrap();
+    assert!(!round.quorum().quorum_reached);
+
+    let ends_at = round.voting_ends_at().unwrap();
+    assert_eq!(
+        round.tick(ends_at),
+        Ok(TickOutcome::Closed {
+            status: ProposalStatus::QuorumFailed,
+            quorum_reached: false,
+        })
+    );
+    assert_eq!(
+        round.vote(3, true, 1, None, ends_at + 1),
+        Err(FsmError::InvalidState)
+    );
+    let finalized = round.finalize().unwrap();
+    assert_eq!(
+        statuses(&finalized.events),
+        [
+            (ProposalStatus::Draft, ProposalStatus::Active),
+            (ProposalStatus::Active, ProposalStatus::QuorumFailed),
+        ]
+    );
+}
+
+#[test]
+fn ties_under_quorum_are_reported() {
+    let mut round = round(4);
+    round.vote(1, true, 1, None, OPENED_AT + 10).unwrap();
+    round.vote(2, false, 1, None, OPENED_AT + 20).unwrap();
+
+    let ends_at = round.voting_ends_at().unwrap();
+    assert_eq!(
+        round.tick(ends_at),
+        Ok(TickOutcome::Closed {
+            status: ProposalStatus::Tied,
+            quorum_reached: true,
+        })
+    );
+    assert!(round.is_consistent());
+    assert_eq!(
+        round.finalize().unwrap().proposal.status,
+        ProposalStatus::Tied
+    );
+}
+
+#[test]
+fn replayed_vote_leaves_records_unchanged() {
+    let mut round = round(5);
+    round.vote(1, true, 1, Some(3), OPENED_AT + 10).unwrap();
+    let before = round.clone();
+    assert_eq!(
+        round.vote(1, false, 1, Some(3), OPENED_AT + 20),
+        Err(FsmError::ReplayDetected)
+    );
+    assert_eq!(round, before);
+    assert_eq!(
+        round.clone().finalize().map(|_| ()),
+        Err(FsmError::InvalidState)
+    );
+}
@@ -0,0 +1,27 @@
+#![cfg(all(feature = "hash", feature = "serde"))]
+
+use fsm_governance_engine_lib::hashing::{canonical_json, hash_canonical, verify};
+use serde_json::Value;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn canonical_encoding_and_digests_match_vectors() {
+    let vectors: Vec<Value> =
+        serde_json::from_str(include_str!("hash_vectors.json")).expect("valid vector file");
+    assert!(!vectors.is_empty());
+    for vector in &vectors {
+        let name = vector["name"].as_str().unwrap();
+        let input = &vector["input"];
+        assert_eq!(
+            canonical_json(input).unwrap(),
+            vector["canonical"].as_str().unwrap(),
+            "{name}"
+        );
+        let hash = hash_canonical(input).unwrap();
+        assert_eq!(hex(&hash), vector["sha256"].as_str().unwrap(), "{name}");
+        assert!(verify(&hash, input), "{name}");
+    }
+}
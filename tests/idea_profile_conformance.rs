@@ -0,0 +1,77 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use fsm_governance_engine_lib::{IdeaFsmProfile, IdeaStatus};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct ProfileFixture {
+    profile: IdeaFsmProfile,
+    transitions: BTreeMap<String, Vec<IdeaStatus>>,
+}
+
+fn load(name: &str) -> ProfileFixture {
+    let raw = fs::read_to_string(format!("tests/idea_profiles/{name}.json"))
+        .unwrap_or_else(|err| panic!("read fixture {name}: {err}"));
+    serde_json::from_str(&raw).unwrap_or_else(|err| panic!("parse fixture {name}: {err}"))
+}
+
+fn status(name: &str) -> IdeaStatus {
+    serde_json::from_value(serde_json::Value::String(name.to_string()))
+        .unwrap_or_else(|err| panic!("unknown status {name}: {err}"))
+}
+
+fn assert_conforms(name: &str, expected_profile: IdeaFsmProfile) {
+    let fixture = load(name);
+    assert_eq!(fixture.profile, expected_profile);
+    assert_eq!(fixture.transitions.len(), 13, "every status is listed");
+    for (from, targets) in &fixture.transitions {
+        let from = status(from);
+        let mut actual = from.next_states_for(fixture.profile).to_vec();
+        let mut expected = targets.clone();
+        actual.sort_by_key(|s| format!("{s:?}"));
+        expected.sort_by_key(|s| format!("{s:?}"));
+        assert_eq!(actual, expected, "{name}: next states of {from:?}");
+        for to in fixture.transitions.keys().map(|k| status(k)) {
+            assert_eq!(
+                from.can_transition_to_in(fixture.profile, to),
+                from == to || targets.contains(&to),
+                "{name}: {from:?} -> {to:?}"
+            );
+        }
+    }
+}
+
+fn edges_into_resubmitted(name: &str) -> usize {
+    load(name)
+        .transitions
+        .iter()
+        .filter(|(from, targets)| {
+            from.as_str() != "Resubmitted" && targets.contains(&IdeaStatus::Resubmitted)
+        })
+        .count()
+}
+
+#[test]
+fn standard_profile_conforms() {
+    assert_conforms("standard", IdeaFsmProfile::Standard);
+}
+
+#[test]
+fn strict_terminal_profile_conforms() {
+    assert_conforms("strict_terminal", IdeaFsmProfile::StrictTerminal);
+}
+
+#[test]
+fn resubmitted_edge_only_in_standard() {
+    assert_eq!(edges_into_resubmitted("standard"), 2);
+    assert_eq!(edges_into_resubmitted("strict_terminal"), 0);
+    assert!(IdeaStatus::Archived.can_transition_to(IdeaStatus::Resubmitted));
+    assert!(
+        !IdeaStatus::Archived
+            .can_transition_to_in(IdeaFsmProfile::StrictTerminal, IdeaStatus::Resubmitted)
+    );
+    assert_eq!(IdeaFsmProfile::default(), IdeaFsmProfile::Standard);
+}
@@ -0,0 +1,200 @@
+#![cfg(feature = "serde")]
+
+use std::fs;
+
+use fsm_governance_engine_lib::grant::{
+    GrantCategory, GrantDisbursementType, GrantType, GrantVote, GrantVotingOutcome, VoteType,
+    VoterType,
+};
+use fsm_governance_engine_lib::policy_bundle::{
+    POLICY_BUNDLE_VERSION, PolicyBundle, PolicyBundleError,
+};
+use fsm_governance_engine_lib::{
+    FsmError, Grant, GrantStatus, Idea, IdeaStatus, Proposal, ProposalStatus, ReviewAssessment,
+};
+
+fn load_example() -> String {
+    fs::read_to_string("tests/policy_bundles/example.json").expect("read example bundle")
+}
+
+#[test]
+fn example_bundle_validates_and_round_trips() {
+    let json = load_example();
+    let bundle = PolicyBundle::from_json(&json).unwrap();
+    assert_eq!(bundle.version, POLICY_BUNDLE_VERSION);
+    assert_eq!(bundle.type_registry().len(), 3);
+    assert_eq!(bundle.execution_retry().max_attempts, 5);
+    assert_eq!(bundle.retention_seconds(), 220_903_200);
+    let reparsed = PolicyBundle::from_json(&bundle.to_json()).unwrap();
+    assert_eq!(reparsed, bundle);
+    #[cfg(feature = "definitions")]
+    {
+        assert_eq!(bundle.definition_limits().max_states, 500);
+        let original: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_value(&bundle).unwrap(), original);
+    }
+}
+
+#[test]
+fn example_bundle_drives_proposal_activation() {
+    let bundle = PolicyBundle::from_json(&load_example()).unwrap();
+    let mut proposal = Proposal::<u8>::builder(
+        1,
+        "Rotate keys".to_string(),
+        "Description".to_string(),
+        "governance".to_string(),
+        1,
+    )
+    .voting_duration(bundle.governance().vote_duration())
+    .reason_policy(bundle.proposal_reason_policy().clone())
+    .build(0)
+    .unwrap();
+    proposal
+        .activate_in(&bundle.activation_context("governance", 3, 10, 10))
+        .unwrap();
+    assert_eq!(proposal.status, ProposalStatus::Active);
+    assert!(matches!(
+        proposal.cancel_with_time(String::new(), 20),
+        Err(FsmError::ReasonRequired(_))
+    ));
+
+    let mut emergency = Proposal::<u8>::builder(
+        2,
+        "Halt".to_string(),
+        "Description".to_string(),
+        "emergency".to_string(),
+        1,
+    )
+    .voting_duration(bundle.governance().vote_duration())
+    .build(0)
+    .unwrap();
+    assert_eq!(
+        emergency.activate_in(&bundle.activation_context("emergency", 3, 30, 10)),
+        Err(FsmError::InvalidState)
+    );
+}
+
+#[test]
+fn example_bundle_sets_clock_skew_tolerance() {
+    let bundle = PolicyBundle::from_json(&load_example()).unwrap();
+    assert_eq!(bundle.clock_skew_tolerance_seconds, 5);
+    let activated = || {
+        let mut proposal = Proposal::<u8>::builder(
+            1,
+            "Rotate keys".to_string(),
+            "Description".to_string(),
+            "governance".to_string(),
+            1,
+        )
+        .clock_skew_tolerance(bundle.clock_skew_tolerance_seconds)
+        .build(0)
+        .unwrap();
+        proposal
+            .activate_in(&bundle.activation_context("governance", 3, 10, 100))
+            .unwrap();
+        proposal
+    };
+    let mut proposal = activated();
+    proposal
+        .cancel_with_time("Superseded".to_string(), 95)
+        .unwrap();
+    assert_eq!(proposal.cancelled_at, Some(100));
+    assert_eq!(
+        activated().cancel_with_time("Superseded".to_string(), 94),
+        Err(FsmError::TimeRegression)
+    );
+}
+
+#[test]
+fn example_bundle_drives_grant_voting() {
+    let bundle = PolicyBundle::from_json(&load_example()).unwrap();
+    let mut grant = Grant::new(
+        1,
+        1,
+        [0u8; 32],
+        GrantCategory::Research,
+        GrantType::Initial,
+        GrantDisbursementType::Standard,
+        1_000,
+        0,
+        0,
+    )
+    .unwrap()
+    .with_reason_policy(bundle.grant_reason_policy().clone());
+    for (voter, vote_type, weight) in [(1, VoteType::Approve, 13), (2, VoteType::Reject, 7)] {
+        grant
+            .cast_vote(GrantVote {
+                grant_id: 1,
+                voter_id: [voter; 32],
+                vote_type,
+                voter_type: VoterType::DaoMember,
+                weight,
+                cast_at: 10,
+            })
+            .unwrap();
+    }
+    assert_eq!(
+        grant.finalize_voting_under(bundle.grant_voting(), 80, 20),
+        Ok(GrantVotingOutcome::Approved)
+    );
+    assert_eq!(grant.status, GrantStatus::Approved);
+}
+
+#[test]
+fn example_bundle_limits_fast_grants() {
+    let bundle = PolicyBundle::from_json(&load_example()).unwrap();
+    let mut idea = Idea::new(1, 0).unwrap().with_author([7; 32]);
+    idea.submit_for_review(10).unwrap();
+    idea.add_assessment(ReviewAssessment {
+        reviewer: [1; 32],
+        score: 80,
+        category_scores: Vec::new(),
+        summary_hash: [0; 32],
+        assessed_at: 20,
+    })
+    .unwrap();
+    idea.conclude_review(60, 30).unwrap();
+    assert_eq!(
+        idea.spawn_grant(10, 5000, [7; 32], bundle.fast_grant(), None, 40),
+        Err(FsmError::InvalidInput)
+    );
+    let grant = idea
+        .spawn_grant(10, 4999, [7; 32], bundle.fast_grant(), None, 40)
+        .unwrap();
+    assert_eq!(grant.category, GrantCategory::Research);
+    assert_eq!(grant.disbursement_type, GrantDisbursementType::Urgent);
+    assert!(grant.fast_track);
+    assert_eq!(idea.status, IdeaStatus::InProgress);
+    // Disabled unless the bundle sets a limit
+    assert_eq!(
+        PolicyBundle::new(bundle.governance().clone())
+            .fast_grant()
+            .max_fast_grant,
+        0
+    );
+}
+
+#[test]
+fn newer_bundle_is_refused() {
+    let mut json: serde_json::Value = serde_json::from_str(&load_example()).unwrap();
+    json["version"] = serde_json::Value::from(POLICY_BUNDLE_VERSION + 1);
+    assert_eq!(
+        PolicyBundle::from_json(&json.to_string()),
+        Err(PolicyBundleError::UnsupportedVersion {
+            found: POLICY_BUNDLE_VERSION + 1,
+            supported: POLICY_BUNDLE_VERSION,
+        })
+    );
+}
+
+#[test]
+fn inconsistent_bundle_names_the_field() {
+    let mut json: serde_json::Value = serde_json::from_str(&load_example()).unwrap();
+    json["proposal_types"]["types"]["treasury"]["execution_timelock"] =
+        serde_json::Value::from(31 * 24 * 3600);
+    let err = PolicyBundle::from_json(&json.to_string()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "proposal_types.treasury.execution_timelock: Invalid input provided"
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "definitions")]
+
 use std::fs;
 use std::path::Path;
 